@@ -0,0 +1,235 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::http::{self, http_client};
+use regex::Regex;
+use std::sync::LazyLock;
+
+const ANILIST_URL: &str = "https://graphql.anilist.co";
+const MAX_CONTEXT_CHARACTERS: usize = 12;
+
+const SYNOPSIS_QUERY: &str = r#"
+query ($search: String) {
+  Media(search: $search, type: ANIME) {
+    id
+    title { romaji english }
+    description(asHtml: false)
+    characters(sort: [ROLE, RELEVANCE], perPage: 12) {
+      nodes { name { full } }
+    }
+  }
+}"#;
+
+const METADATA_QUERY: &str = r#"
+query ($search: String) {
+  Media(search: $search, type: ANIME) {
+    id
+    idMal
+    title { romaji english native }
+    episodes
+    seasonYear
+    coverImage { large }
+    streamingEpisodes { title }
+  }
+}"#;
+
+static STREAMING_EPISODE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^\s*episode\s+(\d+)\s*[-:–]\s*(.+?)\s*$").unwrap());
+static HTML_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<br\s*/?>|<[^>]+>").unwrap());
+static SOURCE_NOTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)\s*\((?:source|written by)[^)]*\)\s*$").unwrap());
+
+/// Posts a GraphQL query to AniList and returns its `data` object.
+pub(crate) async fn query_anilist(
+    query: &str,
+    variables: serde_json::Value,
+) -> Result<serde_json::Value, AnimeSubsError> {
+    let response = http_client()?
+        .post(ANILIST_URL)
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
+        .send()
+        .await
+        .map_err(|e| AnimeSubsError::Provider(format!("Failed to query AniList: {}", e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(http::status_error(
+            status,
+            None,
+            format!("AniList API error ({}): {}", status, error_text),
+        ));
+    }
+
+    let mut body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse AniList response: {}", e)))?;
+    Ok(body["data"].take())
+}
+
+fn clean_description(description: &str) -> String {
+    let text = HTML_TAG_RE.replace_all(description, "\n");
+    let text = SOURCE_NOTE_RE.replace(&text, "");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn series_context_from_media(media: &serde_json::Value) -> Option<SeriesContext> {
+    let id = media["id"].as_u64()?;
+    let title = media["title"]["english"]
+        .as_str()
+        .or_else(|| media["title"]["romaji"].as_str())
+        .map(String::from);
+
+    let mut context = clean_description(media["description"].as_str().unwrap_or_default());
+    let characters: Vec<&str> = media["characters"]["nodes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| node["name"]["full"].as_str())
+        .take(MAX_CONTEXT_CHARACTERS)
+        .collect();
+    if !characters.is_empty() {
+        context.push_str(&format!("\n\nMain characters: {}", characters.join(", ")));
+    }
+
+    Some(SeriesContext {
+        title,
+        anilist_id: Some(id),
+        context: context.trim().to_string(),
+    })
+}
+
+/// Finds the title of `episode` among AniList's streaming episode entries
+/// ("Episode 5 - The Priest's Lie").
+fn episode_title(media: &serde_json::Value, episode: u32) -> Option<String> {
+    media["streamingEpisodes"]
+        .as_array()?
+        .iter()
+        .filter_map(|entry| entry["title"].as_str())
+        .filter_map(|title| STREAMING_EPISODE_RE.captures(title))
+        .find(|c| c[1].parse::<u32>().ok() == Some(episode))
+        .map(|c| c[2].to_string())
+}
+
+fn metadata_from_media(media: &serde_json::Value, episode: Option<u32>) -> Option<AnimeMetadata> {
+    let text = |value: &serde_json::Value| value.as_str().map(String::from);
+    let number = |value: &serde_json::Value| value.as_u64().map(|n| n as u32);
+
+    Some(AnimeMetadata {
+        anilist_id: media["id"].as_u64()?,
+        mal_id: media["idMal"].as_u64(),
+        title_romaji: text(&media["title"]["romaji"])?,
+        title_english: text(&media["title"]["english"]),
+        title_native: text(&media["title"]["native"]),
+        episodes: number(&media["episodes"]),
+        season_year: number(&media["seasonYear"]),
+        cover_image: text(&media["coverImage"]["large"]),
+        episode,
+        episode_title: episode.and_then(|episode| episode_title(media, episode)),
+        release: None,
+    })
+}
+
+async fn fetch_metadata(
+    title: &str,
+    episode: Option<u32>,
+) -> Result<AnimeMetadata, AnimeSubsError> {
+    let data = query_anilist(METADATA_QUERY, serde_json::json!({ "search": title })).await?;
+    metadata_from_media(&data["Media"], episode)
+        .ok_or_else(|| format!("No AniList entry found for \"{}\"", title).into())
+}
+
+/// Searches AniList by series title; `episode` also resolves that episode's title.
+#[tauri::command]
+pub async fn lookup_anime(
+    title: String,
+    episode: Option<u32>,
+) -> Result<AnimeMetadata, AnimeSubsError> {
+    fetch_metadata(title.trim(), episode).await
+}
+
+/// Parses a release filename and identifies the series and episode on AniList.
+#[tauri::command]
+pub async fn identify_episode(file_path: String) -> Result<AnimeMetadata, AnimeSubsError> {
+    let release = super::release::parse_release_name(&file_path);
+    if release.title.is_empty() {
+        return Err(format!("Could not find a series title in {}", file_path).into());
+    }
+
+    let mut metadata = fetch_metadata(&release.title, release.episode).await?;
+    metadata.release = Some(release);
+    Ok(metadata)
+}
+
+/// Looks the series up on AniList and builds a starting context from its synopsis
+/// and main cast, ready to be reviewed and saved with `save_series_context`.
+#[tauri::command]
+pub async fn fetch_anilist_synopsis(title: String) -> Result<SeriesContext, AnimeSubsError> {
+    let data = query_anilist(
+        SYNOPSIS_QUERY,
+        serde_json::json!({ "search": title.trim() }),
+    )
+    .await?;
+    series_context_from_media(&data["Media"])
+        .ok_or_else(|| format!("No AniList entry found for \"{}\"", title.trim()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_context_from_anilist_media() {
+        let media = serde_json::json!({
+            "id": 9253,
+            "title": { "romaji": "Steins;Gate", "english": "Steins;Gate" },
+            "description": "Eccentric scientist Rintarou Okabe...<br><br>\nA microwave sends texts to the past.<br>\n(Source: Funimation)",
+            "characters": { "nodes": [
+                { "name": { "full": "Rintarou Okabe" } },
+                { "name": { "full": "Kurisu Makise" } }
+            ]}
+        });
+
+        let context = series_context_from_media(&media).unwrap();
+        assert_eq!(context.anilist_id, Some(9253));
+        assert_eq!(
+            context.context,
+            "Eccentric scientist Rintarou Okabe...\nA microwave sends texts to the past.\n\n\
+             Main characters: Rintarou Okabe, Kurisu Makise"
+        );
+        assert!(series_context_from_media(&serde_json::Value::Null).is_none());
+    }
+
+    #[test]
+    fn reads_metadata_and_episode_title() {
+        let media = serde_json::json!({
+            "id": 154587,
+            "idMal": 52991,
+            "title": { "romaji": "Sousou no Frieren", "english": "Frieren: Beyond Journey's End", "native": "葬送のフリーレン" },
+            "episodes": 28,
+            "seasonYear": 2023,
+            "coverImage": { "large": "https://img.anili.st/frieren.jpg" },
+            "streamingEpisodes": [
+                { "title": "Episode 4 - The Land Where Souls Rest" },
+                { "title": "Episode 5 - Phantoms of the Dead" }
+            ]
+        });
+
+        let metadata = metadata_from_media(&media, Some(5)).unwrap();
+        assert_eq!(metadata.mal_id, Some(52991));
+        assert_eq!(metadata.episodes, Some(28));
+        assert_eq!(
+            metadata.episode_title.as_deref(),
+            Some("Phantoms of the Dead")
+        );
+        assert!(metadata_from_media(&media, Some(9))
+            .unwrap()
+            .episode_title
+            .is_none());
+    }
+}
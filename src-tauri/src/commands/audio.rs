@@ -0,0 +1,217 @@
+use super::operations::run_ffmpeg_with_progress;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+fn ffmpeg_default_audio_args(audio_count: usize, default_index: u32) -> Vec<String> {
+    let mut args = Vec::new();
+    for i in 0..audio_count {
+        args.push(format!("-disposition:a:{}", i));
+        args.push(if i == default_index as usize {
+            "default".to_string()
+        } else {
+            "0".to_string()
+        });
+    }
+    args
+}
+
+fn mkvpropedit_default_audio_args(audio_count: usize, default_index: u32) -> Vec<String> {
+    let mut args = Vec::new();
+    for i in 0..audio_count {
+        args.push("--edit".to_string());
+        args.push(format!("track:a{}", i + 1));
+        args.push("--set".to_string());
+        args.push(format!(
+            "flag-default={}",
+            u8::from(i == default_index as usize)
+        ));
+    }
+    args
+}
+
+async fn remux_audio(
+    app: &AppHandle,
+    video_path: &str,
+    ffmpeg_path: Option<String>,
+    stream_args: Vec<String>,
+    operation: &str,
+    output_policy: Option<OutputPolicy>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
+    let duration = super::video::get_video_info(video_path.to_string(), ffmpeg_path)
+        .await
+        .ok()
+        .and_then(|info| info.duration);
+    let temp_output = build_remux_temp_path(video_path, "audio");
+
+    let mut args = vec!["-i".to_string(), ffmpeg_file(video_path)];
+    args.extend(stream_args);
+    args.extend([
+        "-c".to_string(),
+        "copy".to_string(),
+        "-y".to_string(),
+        ffmpeg_file(&temp_output.to_string_lossy()),
+    ]);
+
+    let result = run_ffmpeg_with_progress(
+        app,
+        &ffmpeg,
+        &args,
+        operation,
+        video_path,
+        duration,
+        vec![temp_output.clone()],
+    )
+    .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
+
+    if result.status.success() {
+        let final_path =
+            finalize_remux_output(&temp_output, video_path, &output_policy.unwrap_or_default())?;
+
+        Ok(OperationResult {
+            success: true,
+            message: "Audio tracks updated successfully".to_string(),
+            data: Some(final_path),
+            verification: None,
+        })
+    } else {
+        let _ = fs::remove_file(&temp_output);
+
+        Ok(OperationResult {
+            success: false,
+            message: String::from_utf8_lossy(&result.stderr).to_string(),
+            data: None,
+            verification: None,
+        })
+    }
+}
+
+#[tauri::command]
+pub async fn set_default_audio_track(
+    app: AppHandle,
+    video_path: String,
+    track_index: u32,
+    ffmpeg_path: Option<String>,
+    output_policy: Option<OutputPolicy>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
+    let audio_count = video_info.audio_tracks.len();
+
+    if track_index as usize >= audio_count {
+        return Err(AnimeSubsError::TrackNotFound(
+            "Invalid audio track index".to_string(),
+        ));
+    }
+
+    let ext = Path::new(&video_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let in_place = output_policy
+        .as_ref()
+        .is_none_or(|p| *p == OutputPolicy::Overwrite);
+
+    // Matroska flags can be edited in place without rewriting the whole file.
+    if in_place && is_mkv_container(&ext) {
+        if let Some(mkvpropedit) = resolve_mkvpropedit_path() {
+            let result = create_command(&mkvpropedit)
+                .arg(mkvtoolnix_file_arg(&video_path))
+                .args(mkvpropedit_default_audio_args(audio_count, track_index))
+                .output()
+                .map_err(|e| {
+                    AnimeSubsError::from_tool(&e, format!("Failed to run mkvpropedit: {}", e))
+                })?;
+
+            return Ok(OperationResult {
+                success: result.status.success(),
+                message: if result.status.success() {
+                    "Default audio track updated".to_string()
+                } else {
+                    String::from_utf8_lossy(&result.stdout).to_string()
+                },
+                data: Some(video_path),
+                verification: None,
+            });
+        }
+    }
+
+    let mut stream_args = vec!["-map".to_string(), "0".to_string()];
+    stream_args.extend(ffmpeg_default_audio_args(audio_count, track_index));
+
+    remux_audio(
+        &app,
+        &video_path,
+        ffmpeg_path,
+        stream_args,
+        "set_default_audio",
+        output_policy,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_audio_track(
+    app: AppHandle,
+    video_path: String,
+    track_index: u32,
+    ffmpeg_path: Option<String>,
+    output_policy: Option<OutputPolicy>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
+
+    if track_index as usize >= video_info.audio_tracks.len() {
+        return Err(AnimeSubsError::TrackNotFound(
+            "Invalid audio track index".to_string(),
+        ));
+    }
+    if video_info.audio_tracks.len() == 1 {
+        return Err("Refusing to remove the only audio track".into());
+    }
+
+    let stream_args = vec![
+        "-map".to_string(),
+        "0".to_string(),
+        "-map".to_string(),
+        format!("-0:a:{}", track_index),
+    ];
+
+    remux_audio(
+        &app,
+        &video_path,
+        ffmpeg_path,
+        stream_args,
+        "remove_audio",
+        output_policy,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_default_audio_flags_for_every_track() {
+        assert_eq!(
+            ffmpeg_default_audio_args(2, 1),
+            vec!["-disposition:a:0", "0", "-disposition:a:1", "default"]
+        );
+        assert_eq!(
+            mkvpropedit_default_audio_args(2, 0),
+            vec![
+                "--edit",
+                "track:a1",
+                "--set",
+                "flag-default=1",
+                "--edit",
+                "track:a2",
+                "--set",
+                "flag-default=0"
+            ]
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use super::presets::read_presets;
+use super::secrets::read_api_key;
+use super::translation::start_translation_job;
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::write_file_atomic;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tracing::{info, warn};
+
+const AUTOMATION_FILE: &str = "automation.json";
+/// Sonarr and Radarr event types sent once files are imported.
+const IMPORT_EVENTS: [&str; 2] = ["Download", "ImportComplete"];
+
+/// Files queued for automatic translation. One worker drains the queue, one job
+/// at a time, while `worker` is set.
+#[derive(Default)]
+pub struct AutomationState {
+    queue: Mutex<QueueState>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    queued: Vec<QueuedFile>,
+    running: Vec<String>,
+    worker: bool,
+}
+
+struct QueuedFile {
+    path: String,
+    /// Saved preset the file is translated with.
+    preset: String,
+}
+
+fn read_automation_settings(app: &AppHandle) -> Result<AutomationSettings, AnimeSubsError> {
+    let path = app_config_path(app, AUTOMATION_FILE)?;
+    if !path.exists() {
+        return Ok(AutomationSettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read automation settings: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse automation settings: {}", e)))
+}
+
+/// Applies the mapping with the longest matching `from`; paths no mapping
+/// covers are returned unchanged.
+fn map_path(path: &str, mappings: &[PathMapping]) -> String {
+    mappings
+        .iter()
+        .filter(|mapping| !mapping.from.trim().is_empty())
+        .filter_map(|mapping| {
+            let from = mapping.from.trim().trim_end_matches(['/', '\\']);
+            let rest = path.strip_prefix(from)?;
+            (rest.is_empty() || rest.starts_with(['/', '\\'])).then_some((
+                from.len(),
+                mapping,
+                rest,
+            ))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, mapping, rest)| {
+            format!(
+                "{}{}",
+                mapping.to.trim().trim_end_matches(['/', '\\']),
+                rest
+            )
+        })
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Full path of a Sonarr `episodeFile` or Radarr `movieFile`; older versions
+/// only send it relative to the series or movie folder.
+fn import_file_path(file: &Value, folder: &Value) -> Option<String> {
+    if let Some(path) = file["path"].as_str().filter(|path| !path.is_empty()) {
+        return Some(path.to_string());
+    }
+    let relative = file["relativePath"].as_str()?;
+    let folder = folder.as_str()?;
+    Some(
+        Path::new(folder)
+            .join(relative)
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+/// Files a Sonarr or Radarr webhook reports as imported; empty for other
+/// events, including the "Test" one sent when the connection is saved.
+fn arr_import_paths(payload: &Value) -> Vec<String> {
+    let event = payload["eventType"].as_str().unwrap_or_default();
+    if !IMPORT_EVENTS.contains(&event) {
+        return Vec::new();
+    }
+
+    let sources = [
+        ("episodeFile", "episodeFiles", &payload["series"]["path"]),
+        ("movieFile", "movieFiles", &payload["movie"]["folderPath"]),
+    ];
+    let mut paths: Vec<String> = Vec::new();
+    for (single, many, folder) in sources {
+        let files =
+            std::iter::once(&payload[single]).chain(payload[many].as_array().into_iter().flatten());
+        for path in files.filter_map(|file| import_file_path(file, folder)) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    paths
+}
+
+fn job_request(
+    app: &AppHandle,
+    settings: &AutomationSettings,
+    preset: &str,
+    video_paths: Vec<String>,
+) -> Result<TranslationJobRequest, AnimeSubsError> {
+    let preset = read_presets(app)?
+        .into_iter()
+        .find(|saved| saved.name == preset)
+        .ok_or_else(|| format!("Preset not found: {}", preset))?;
+    let mut config = preset.config;
+    // Presets never store keys; fallbacks read theirs when the job starts.
+    config.api_key = read_api_key(app, &config.provider)?;
+
+    Ok(TranslationJobRequest {
+        video_paths,
+        config,
+        source_lang: preset.source_lang,
+        target_lang: preset.target_lang,
+        output_format: settings.output_format.clone(),
+        rate_limit: preset.rate_limit,
+        max_parallel_files: preset.max_parallel_files,
+        strip_sdh: preset.strip_sdh,
+        honorific_second_pass: preset.honorific_second_pass,
+        skip_target_language: true,
+        skip_translated: true,
+        embed_subtitles: settings.embed_subtitles,
+        use_mkvmerge: true,
+        auto_backup: true,
+        keep_original_track: true,
+        ..Default::default()
+    })
+}
+
+/// Translates queued files until the queue is empty. Each round takes every
+/// queued file sharing the preset of the oldest one into a single job.
+async fn drain_queue(app: AppHandle) {
+    let state = app.state::<AutomationState>();
+    loop {
+        let (preset, video_paths) = {
+            let mut queue = state.queue.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(preset) = queue.queued.first().map(|file| file.preset.clone()) else {
+                queue.running.clear();
+                queue.worker = false;
+                return;
+            };
+            let (batch, rest) = std::mem::take(&mut queue.queued)
+                .into_iter()
+                .partition::<Vec<_>, _>(|file| file.preset == preset);
+            queue.queued = rest;
+            queue.running = batch.into_iter().map(|file| file.path).collect();
+            (preset, queue.running.clone())
+        };
+
+        let total = video_paths.len();
+        info!(
+            "Automatic translation of {} files started with preset '{}'",
+            total, preset
+        );
+        let result = match read_automation_settings(&app)
+            .and_then(|settings| job_request(&app, &settings, &preset, video_paths))
+        {
+            Ok(request) => start_translation_job(app.clone(), request).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(result) => info!(
+                "Automatic translation finished: {}/{} files",
+                result.completed_files, result.total_files
+            ),
+            Err(e) => warn!("Automatic translation of {} files failed: {}", total, e),
+        }
+    }
+}
+
+/// Queues `paths` (after the path mappings) with the automation preset.
+/// Returns the files actually added; missing and already queued ones are skipped.
+pub(crate) fn enqueue(app: &AppHandle, paths: &[String]) -> Result<Vec<String>, AnimeSubsError> {
+    let settings = read_automation_settings(app)?;
+    if !settings.enabled {
+        return Err("Automatic translation is disabled".into());
+    }
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|path| map_path(path, &settings.path_mappings))
+        .collect();
+    Ok(enqueue_with_preset(app, &paths, &settings.preset))
+}
+
+/// Queues local `paths` to be translated with `preset` and starts the worker if
+/// it is idle. Returns the files actually added.
+pub(crate) fn enqueue_with_preset(app: &AppHandle, paths: &[String], preset: &str) -> Vec<String> {
+    let state = app.state::<AutomationState>();
+    let mut queue = state.queue.lock().unwrap_or_else(|e| e.into_inner());
+    let mut added = Vec::new();
+    for path in paths {
+        if !Path::new(path).is_file() {
+            warn!(
+                "Skipping {}: file not found (check the path mappings)",
+                path
+            );
+            continue;
+        }
+        if queue.queued.iter().any(|file| &file.path == path) || queue.running.contains(path) {
+            continue;
+        }
+        queue.queued.push(QueuedFile {
+            path: path.clone(),
+            preset: preset.to_string(),
+        });
+        added.push(path.clone());
+    }
+
+    if !added.is_empty() && !queue.worker {
+        queue.worker = true;
+        tauri::async_runtime::spawn(drain_queue(app.clone()));
+    }
+    added
+}
+
+/// Handles a Sonarr or Radarr "On Import" webhook; see `arr_import_paths`.
+pub(crate) fn ingest_arr_webhook(
+    app: &AppHandle,
+    payload: &Value,
+) -> Result<Vec<String>, AnimeSubsError> {
+    let paths = arr_import_paths(payload);
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let added = enqueue(app, &paths)?;
+    info!(
+        "Webhook queued {} of {} imported files",
+        added.len(),
+        paths.len()
+    );
+    Ok(added)
+}
+
+#[tauri::command]
+pub async fn get_automation_settings(app: AppHandle) -> Result<AutomationSettings, AnimeSubsError> {
+    read_automation_settings(&app)
+}
+
+#[tauri::command]
+pub async fn save_automation_settings(
+    app: AppHandle,
+    settings: AutomationSettings,
+) -> Result<OperationResult, AnimeSubsError> {
+    if settings.enabled
+        && !read_presets(&app)?
+            .iter()
+            .any(|p| p.name == settings.preset)
+    {
+        return Err(format!("Preset not found: {}", settings.preset).into());
+    }
+    let data = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize automation settings: {}", e))?;
+    write_file_atomic(&app_config_path(&app, AUTOMATION_FILE)?, data.as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "Automation settings saved".to_string(),
+        data: None,
+        verification: None,
+    })
+}
+
+#[tauri::command]
+pub async fn get_automation_queue(app: AppHandle) -> Result<AutomationQueue, AnimeSubsError> {
+    let state = app.state::<AutomationState>();
+    let queue = state.queue.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(AutomationQueue {
+        queued: queue.queued.iter().map(|file| file.path.clone()).collect(),
+        running: queue.running.clone(),
+    })
+}
+
+/// Queues videos for automatic translation with the automation preset.
+#[tauri::command]
+pub async fn enqueue_automation(
+    app: AppHandle,
+    paths: Vec<String>,
+) -> Result<Vec<String>, AnimeSubsError> {
+    enqueue(&app, &paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_sonarr_and_radarr_imports() {
+        let sonarr = json!({
+            "eventType": "Download",
+            "series": { "path": "/tv/Frieren" },
+            "episodeFile": { "relativePath": "Season 1/Frieren - S01E01.mkv" }
+        });
+        assert_eq!(
+            arr_import_paths(&sonarr),
+            [Path::new("/tv/Frieren")
+                .join("Season 1/Frieren - S01E01.mkv")
+                .to_string_lossy()
+                .to_string()]
+        );
+
+        let season_pack = json!({
+            "eventType": "ImportComplete",
+            "series": { "path": "/tv/Frieren" },
+            "episodeFiles": [
+                { "path": "/tv/Frieren/Season 1/E02.mkv" },
+                { "path": "/tv/Frieren/Season 1/E03.mkv" }
+            ]
+        });
+        assert_eq!(
+            arr_import_paths(&season_pack),
+            [
+                "/tv/Frieren/Season 1/E02.mkv",
+                "/tv/Frieren/Season 1/E03.mkv"
+            ]
+        );
+
+        let radarr = json!({
+            "eventType": "Download",
+            "movie": { "folderPath": "/movies/Perfect Blue (1997)" },
+            "movieFile": { "path": "/movies/Perfect Blue (1997)/Perfect Blue.mkv" }
+        });
+        assert_eq!(
+            arr_import_paths(&radarr),
+            ["/movies/Perfect Blue (1997)/Perfect Blue.mkv"]
+        );
+
+        assert!(arr_import_paths(&json!({ "eventType": "Test" })).is_empty());
+        assert!(arr_import_paths(&json!({ "eventType": "Grab" })).is_empty());
+    }
+
+    #[test]
+    fn maps_paths_by_the_longest_prefix() {
+        let mappings = [
+            PathMapping {
+                from: "/tv".into(),
+                to: "/mnt/media/tv".into(),
+            },
+            PathMapping {
+                from: "/tv/anime/".into(),
+                to: "D:\\Anime".into(),
+            },
+        ];
+
+        assert_eq!(
+            map_path("/tv/Show/E01.mkv", &mappings),
+            "/mnt/media/tv/Show/E01.mkv"
+        );
+        assert_eq!(
+            map_path("/tv/anime/E01.mkv", &mappings),
+            "D:\\Anime/E01.mkv"
+        );
+        assert_eq!(map_path("/tvshows/E01.mkv", &mappings), "/tvshows/E01.mkv");
+        assert_eq!(map_path("/movies/M.mkv", &[]), "/movies/M.mkv");
+    }
+}
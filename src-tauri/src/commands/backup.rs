@@ -1,24 +1,130 @@
+use super::operations::{run_ffmpeg_with_progress, run_tracked_command};
+use super::temp_files::TempFileRegistry;
+use super::verify::finalize_verified_remux;
+use crate::error::AnimeSubsError;
 use crate::models::*;
 use crate::utils::*;
-use chrono;
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+const BACKUP_META_FILE: &str = "backups.json";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+fn read_backup_meta(backup_dir: &Path) -> Vec<BackupInfo> {
+    fs::read_to_string(backup_dir.join(BACKUP_META_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_backup_meta(backup_dir: &Path, backups: &[BackupInfo]) -> Result<(), AnimeSubsError> {
+    fs::write(
+        backup_dir.join(BACKUP_META_FILE),
+        serde_json::to_string_pretty(backups).unwrap(),
+    )
+    .map_err(|e| AnimeSubsError::Io(format!("Failed to save backup metadata: {}", e)))
+}
+
+/// Backups moved out of the library live here, with the same metadata file.
+fn central_backup_dir(app: &AppHandle) -> Result<PathBuf, AnimeSubsError> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to resolve app data directory: {}", e)))?;
+    Ok(data_dir.join("backups"))
+}
+
+fn same_video(a: &str, b: &str) -> bool {
+    let normalize = |path: &str| {
+        Path::new(path)
+            .canonicalize()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string())
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Every `.animesubs_backup` directory below `root`.
+fn find_backup_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_dir() {
+                continue;
+            }
+            match path.file_name().map(|n| n.to_string_lossy().to_string()) {
+                Some(name) if name == ".animesubs_backup" => found.push(path),
+                Some(name) if name.starts_with('.') => {}
+                _ => pending.push(path),
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+/// Backups that fall outside `retention`: beyond the newest `keep_last` of each
+/// video/track pair, or older than `max_age_days` relative to `now`.
+fn expired_backups(
+    backups: &[BackupInfo],
+    retention: &BackupRetention,
+    now: NaiveDateTime,
+) -> Vec<BackupInfo> {
+    let mut groups: HashMap<(String, u32), Vec<&BackupInfo>> = HashMap::new();
+    for backup in backups {
+        groups
+            .entry((backup.original_path.clone(), backup.track_index))
+            .or_default()
+            .push(backup);
+    }
+
+    let mut expired = Vec::new();
+    for group in groups.values_mut() {
+        // The timestamp format sorts lexically in chronological order.
+        group.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        for (position, backup) in group.iter().enumerate() {
+            let over_count = retention.keep_last.is_some_and(|keep| position >= keep);
+            let too_old = retention.max_age_days.is_some_and(|days| {
+                NaiveDateTime::parse_from_str(&backup.created_at, BACKUP_TIMESTAMP_FORMAT)
+                    .is_ok_and(|created| (now - created).num_days() >= i64::from(days))
+            });
+            if over_count || too_old {
+                expired.push((*backup).clone());
+            }
+        }
+    }
+    expired.sort_by(|a, b| a.backup_path.cmp(&b.backup_path));
+    expired
+}
 
 #[tauri::command]
 pub async fn backup_subtitle(
+    app: AppHandle,
     video_path: String,
     track_index: u32,
     ffmpeg_path: Option<String>,
-) -> Result<BackupInfo, String> {
-    let backup_dir = get_backup_dir(&video_path);
+    central: Option<bool>,
+) -> Result<BackupInfo, AnimeSubsError> {
+    let backup_dir = if central.unwrap_or(false) {
+        central_backup_dir(&app)?
+    } else {
+        get_backup_dir(&video_path)
+    };
     fs::create_dir_all(&backup_dir)
-        .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create backup directory: {}", e)))?;
 
     let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
     let track = video_info
         .subtitle_tracks
         .get(track_index as usize)
-        .ok_or("Subtitle track not found")?;
+        .ok_or_else(|| AnimeSubsError::TrackNotFound("Subtitle track not found".to_string()))?;
 
     let format = match track.codec.as_str() {
         "ass" | "ssa" => "ass",
@@ -26,7 +132,9 @@ pub async fn backup_subtitle(
         _ => "srt",
     };
 
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let timestamp = chrono::Local::now()
+        .format(BACKUP_TIMESTAMP_FORMAT)
+        .to_string();
     let stem = Path::new(&video_path)
         .file_stem()
         .map(|s| s.to_string_lossy().to_string())
@@ -40,12 +148,14 @@ pub async fn backup_subtitle(
     let backup_path = backup_dir.join(&backup_filename);
 
     let result = super::subtitle::extract_subtitle(
+        app,
         video_path.clone(),
         track_index,
         Some(backup_path.to_string_lossy().to_string()),
         Some(format.to_string()),
         Some(false),
         ffmpeg_path,
+        None,
     )
     .await?;
 
@@ -56,71 +166,320 @@ pub async fn backup_subtitle(
             track_index,
             format: format.to_string(),
             created_at: timestamp,
+            track: Some(BackupTrack {
+                stream_index: track.stream_index,
+                codec: track.codec.clone(),
+                language: track.language.clone(),
+                title: track.title.clone(),
+            }),
         };
 
-        let meta_path = backup_dir.join("backups.json");
-        let mut backups: Vec<BackupInfo> = if meta_path.exists() {
-            let content = fs::read_to_string(&meta_path).unwrap_or_else(|_| "[]".to_string());
-            serde_json::from_str(&content).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-
+        let mut backups = read_backup_meta(&backup_dir);
         backups.push(backup_info.clone());
-        fs::write(&meta_path, serde_json::to_string_pretty(&backups).unwrap())
-            .map_err(|e| format!("Failed to save backup metadata: {}", e))?;
+        write_backup_meta(&backup_dir, &backups)?;
 
         Ok(backup_info)
     } else {
-        Err(result.error.unwrap_or_else(|| "Unknown error".to_string()))
+        Err(result
+            .error
+            .unwrap_or_else(|| "Unknown error".to_string())
+            .into())
     }
 }
 
+/// Backups of `video_path`, from its folder and the central backup directory.
 #[tauri::command]
-pub async fn list_backups(video_path: String) -> Result<Vec<BackupInfo>, String> {
+pub async fn list_backups(
+    app: AppHandle,
+    video_path: String,
+) -> Result<Vec<BackupInfo>, AnimeSubsError> {
     let backup_dir = get_backup_dir(&video_path);
-    let meta_path = backup_dir.join("backups.json");
+    let meta_path = backup_dir.join(BACKUP_META_FILE);
+
+    let mut all_backups: Vec<BackupInfo> = if meta_path.exists() {
+        let content = fs::read_to_string(&meta_path)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to read backup metadata: {}", e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| AnimeSubsError::parse(format!("Failed to parse backup metadata: {}", e)))?
+    } else {
+        Vec::new()
+    };
+    all_backups.extend(read_backup_meta(&central_backup_dir(&app)?));
 
-    if !meta_path.exists() {
-        return Ok(Vec::new());
+    Ok(all_backups
+        .into_iter()
+        .filter(|b| same_video(&b.original_path, &video_path))
+        .collect())
+}
+
+/// Aggregates backup metadata of every folder below `root` plus the central directory,
+/// newest first.
+#[tauri::command]
+pub async fn list_all_backups(
+    app: AppHandle,
+    root: String,
+) -> Result<Vec<BackupInfo>, AnimeSubsError> {
+    let mut backups: Vec<BackupInfo> = find_backup_dirs(Path::new(&root))
+        .iter()
+        .flat_map(|dir| read_backup_meta(dir))
+        .collect();
+    let root_path = Path::new(&root);
+    backups.extend(
+        read_backup_meta(&central_backup_dir(&app)?)
+            .into_iter()
+            .filter(|b| Path::new(&b.original_path).starts_with(root_path)),
+    );
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(backups)
+}
+
+/// Deletes the backups below `root` (and central ones of its videos) that fall outside
+/// `retention`.
+#[tauri::command]
+pub async fn prune_backups(
+    app: AppHandle,
+    root: String,
+    retention: BackupRetention,
+) -> Result<OperationResult, AnimeSubsError> {
+    if retention.keep_last.is_none() && retention.max_age_days.is_none() {
+        return Err("Retention needs keep_last or max_age_days".into());
     }
+    let now = chrono::Local::now().naive_local();
+    let root_path = Path::new(&root);
+    let mut dirs = find_backup_dirs(root_path);
+    dirs.push(central_backup_dir(&app)?);
 
-    let content = fs::read_to_string(&meta_path)
-        .map_err(|e| format!("Failed to read backup metadata: {}", e))?;
+    let mut removed = 0usize;
+    for dir in dirs {
+        let backups = read_backup_meta(&dir);
+        let candidates: Vec<BackupInfo> = backups
+            .iter()
+            .filter(|b| Path::new(&b.original_path).starts_with(root_path))
+            .cloned()
+            .collect();
+        let expired = expired_backups(&candidates, &retention, now);
+        if expired.is_empty() {
+            continue;
+        }
 
-    let all_backups: Vec<BackupInfo> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse backup metadata: {}", e))?;
+        for backup in &expired {
+            if Path::new(&backup.backup_path).exists() {
+                fs::remove_file(&backup.backup_path).map_err(|e| {
+                    AnimeSubsError::Io(format!("Failed to delete backup file: {}", e))
+                })?;
+            }
+        }
+        let remaining: Vec<BackupInfo> = backups
+            .into_iter()
+            .filter(|b| !expired.iter().any(|e| e.backup_path == b.backup_path))
+            .collect();
+        write_backup_meta(&dir, &remaining)?;
+        removed += expired.len();
+    }
 
-    let video_path_normalized = Path::new(&video_path)
-        .canonicalize()
-        .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or(video_path.clone());
+    Ok(OperationResult {
+        success: true,
+        message: format!("Pruned {} backups", removed),
+        data: Some(removed.to_string()),
+        verification: None,
+    })
+}
 
-    let backups: Vec<BackupInfo> = all_backups
-        .into_iter()
-        .filter(|b| {
-            Path::new(&b.original_path)
-                .canonicalize()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or(b.original_path.clone())
-                == video_path_normalized
+/// Moves every backup below `root` into the central app-data directory.
+#[tauri::command]
+pub async fn relocate_backups(
+    app: AppHandle,
+    root: String,
+) -> Result<OperationResult, AnimeSubsError> {
+    let central_dir = central_backup_dir(&app)?;
+    fs::create_dir_all(&central_dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create backup directory: {}", e)))?;
+    let mut central = read_backup_meta(&central_dir);
+
+    let mut moved = 0usize;
+    for dir in find_backup_dirs(Path::new(&root)) {
+        for mut backup in read_backup_meta(&dir) {
+            let source = PathBuf::from(&backup.backup_path);
+            let Some(file_name) = source.file_name() else {
+                continue;
+            };
+            let target = central_dir.join(file_name);
+            if source.exists() && fs::rename(&source, &target).is_err() {
+                // Renames fail across filesystems.
+                fs::copy(&source, &target)
+                    .map_err(|e| AnimeSubsError::Io(format!("Failed to move backup: {}", e)))?;
+                let _ = fs::remove_file(&source);
+            }
+            backup.backup_path = target.to_string_lossy().to_string();
+            central.push(backup);
+            moved += 1;
+        }
+        write_backup_meta(&central_dir, &central)?;
+        let _ = fs::remove_file(dir.join(BACKUP_META_FILE));
+        let _ = fs::remove_dir(&dir);
+    }
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Moved {} backups", moved),
+        data: Some(central_dir.to_string_lossy().to_string()),
+        verification: None,
+    })
+}
+
+fn disposition_value(track: &SubtitleTrack) -> &'static str {
+    match (track.default, track.forced) {
+        (true, true) => "default+forced",
+        (true, false) => "default",
+        (false, true) => "forced",
+        (false, false) => "0",
+    }
+}
+
+/// ffmpeg arguments that copy every stream of the video in order, putting the backup
+/// where `replaced` was (or after the last stream when that track no longer exists).
+fn restore_ffmpeg_args(
+    video_path: &str,
+    backup_path: &str,
+    stream_count: u32,
+    replaced: Option<&SubtitleTrack>,
+    subtitle_position: usize,
+    subtitle_codec: &str,
+    output: &str,
+) -> Vec<String> {
+    let mut args = vec![
+        "-i".to_string(),
+        ffmpeg_file(video_path),
+        "-i".to_string(),
+        ffmpeg_file(backup_path),
+    ];
+    for stream in 0..stream_count {
+        args.push("-map".to_string());
+        if replaced.is_some_and(|track| track.stream_index == stream) {
+            args.push("1:0".to_string());
+        } else {
+            args.push(format!("0:{}", stream));
+        }
+    }
+    if replaced.is_none() {
+        args.extend(["-map".to_string(), "1:0".to_string()]);
+    }
+
+    args.extend(["-c".to_string(), "copy".to_string()]);
+    args.push(format!("-c:s:{}", subtitle_position));
+    args.push(subtitle_codec.to_string());
+
+    if let Some(track) = replaced {
+        if let Some(language) = &track.language {
+            args.push(format!("-metadata:s:s:{}", subtitle_position));
+            args.push(format!("language={}", tool_language(language)));
+        }
+        if let Some(title) = &track.title {
+            args.push(format!("-metadata:s:s:{}", subtitle_position));
+            args.push(format!("title={}", tool_metadata(title)));
+        }
+        args.push(format!("-disposition:s:{}", subtitle_position));
+        args.push(disposition_value(track).to_string());
+    }
+
+    args.extend(["-y".to_string(), ffmpeg_file(output)]);
+    args
+}
+
+/// mkvmerge equivalent of [`restore_ffmpeg_args`]. `track_ids` are the container's
+/// track ids in order; in MKV they match ffprobe's stream indices because
+/// attachments are listed after all tracks.
+fn restore_mkvmerge_args(
+    video_path: &str,
+    backup_path: &str,
+    track_ids: &[u32],
+    replaced: Option<&SubtitleTrack>,
+    output: &str,
+) -> Vec<String> {
+    let mut args = vec!["-o".to_string(), mkvtoolnix_file(output)];
+    let Some(track) = replaced else {
+        args.extend([mkvtoolnix_file(video_path), mkvtoolnix_file(backup_path)]);
+        return args;
+    };
+
+    args.extend([
+        "--subtitle-tracks".to_string(),
+        format!("!{}", track.stream_index),
+        mkvtoolnix_file(video_path),
+    ]);
+    if let Some(language) = &track.language {
+        args.extend([
+            "--language".to_string(),
+            format!("0:{}", tool_language(language)),
+        ]);
+    }
+    if let Some(title) = &track.title {
+        args.extend([
+            "--track-name".to_string(),
+            format!("0:{}", tool_metadata(title)),
+        ]);
+    }
+    args.extend([
+        "--default-track".to_string(),
+        format!("0:{}", u8::from(track.default)),
+        "--forced-track".to_string(),
+        format!("0:{}", u8::from(track.forced)),
+        mkvtoolnix_file(backup_path),
+    ]);
+
+    let order: Vec<String> = track_ids
+        .iter()
+        .map(|id| {
+            if *id == track.stream_index {
+                "1:0".to_string()
+            } else {
+                format!("0:{}", id)
+            }
         })
         .collect();
+    args.extend(["--track-order".to_string(), order.join(",")]);
+    args
+}
 
-    Ok(backups)
+/// Fails when `current` is not the track `backup` was taken from, e.g. because the
+/// video was remuxed since and its tracks moved. The track may carry a translation
+/// now; only its place in the container has to match.
+fn check_restore_target(
+    backup: &BackupTrack,
+    current: &SubtitleTrack,
+) -> Result<(), AnimeSubsError> {
+    if backup.stream_index == current.stream_index {
+        return Ok(());
+    }
+    let described = match (&backup.language, &backup.title) {
+        (Some(language), Some(title)) => format!("{}, {}", language, title),
+        (Some(language), None) => language.clone(),
+        (None, Some(title)) => title.clone(),
+        (None, None) => backup.codec.clone(),
+    };
+    Err(AnimeSubsError::TrackNotFound(format!(
+        "Track {} is not the track this backup came from ({}, stream {})",
+        current.index, described, backup.stream_index
+    )))
 }
 
+/// Puts a backed-up subtitle back in place of track `track_index`, keeping every other
+/// stream, attachment and chapter of the video. Uses mkvmerge for MKV when available.
 #[tauri::command]
 pub async fn restore_subtitle(
+    app: AppHandle,
     video_path: String,
     backup_path: String,
-    _track_index: u32,
+    track_index: u32,
     ffmpeg_path: Option<String>,
-) -> Result<OperationResult, String> {
-    let ffmpeg = get_ffmpeg_path(ffmpeg_path);
+    output_policy: Option<OutputPolicy>,
+    verify_decode: Option<bool>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let output_policy = output_policy.unwrap_or_default();
+    let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
 
     if !Path::new(&backup_path).exists() {
-        return Err("Backup file not found".to_string());
+        return Err("Backup file not found".into());
     }
 
     let video_pathbuf = Path::new(&video_path);
@@ -135,47 +494,101 @@ pub async fn restore_subtitle(
         .unwrap_or_else(|| "mkv".to_string());
 
     let temp_output = parent.join(format!("{}_restored.{}", stem, ext));
+    ensure_remux_space(&video_path, &temp_output, &output_policy)?;
+    let mut temp_files = TempFileRegistry::new(&app);
+    temp_files.track(temp_output.clone());
+
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
+    let replaced = video_info.subtitle_tracks.get(track_index as usize);
+    let recorded = Path::new(&backup_path).parent().and_then(|dir| {
+        read_backup_meta(dir)
+            .into_iter()
+            .find(|backup| backup.backup_path == backup_path)
+            .and_then(|backup| backup.track)
+    });
+    if let (Some(recorded), Some(replaced)) = (&recorded, replaced) {
+        check_restore_target(recorded, replaced)?;
+    }
+    let subtitle_delta = if replaced.is_some() { 0 } else { 1 };
+    let output = temp_output.to_string_lossy().to_string();
+
+    let mkvmerge_path = resolve_mkvmerge_path().filter(|_| is_mkv_container(&ext));
+    let result = if let Some(mkvmerge) = mkvmerge_path {
+        let mut track_ids: Vec<u32> = video_info
+            .video_streams
+            .iter()
+            .map(|s| s.stream_index)
+            .chain(video_info.audio_tracks.iter().map(|t| t.stream_index))
+            .chain(video_info.subtitle_tracks.iter().map(|t| t.stream_index))
+            .collect();
+        track_ids.sort_unstable();
 
-    let result = create_command(&ffmpeg)
-        .args([
-            "-i",
+        let mut command = create_command(&mkvmerge);
+        command.args(restore_mkvmerge_args(
             &video_path,
-            "-i",
             &backup_path,
-            "-map",
-            "0:v",
-            "-map",
-            "0:a",
-            "-map",
-            "1:0",
-            "-c:v",
-            "copy",
-            "-c:a",
-            "copy",
-            "-c:s",
-            "copy",
-            "-y",
-            temp_output.to_str().unwrap(),
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+            &track_ids,
+            replaced,
+            &output,
+        ));
+        run_tracked_command(
+            &app,
+            command,
+            "restore",
+            &video_path,
+            temp_files.paths(),
+            |_, _| {},
+        )
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run mkvmerge: {}", e)))?
+    } else {
+        let backup_ext = Path::new(&backup_path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let subtitle_codec = resolve_ffmpeg_subtitle_codec(&ext, &backup_ext)?;
+        let args = restore_ffmpeg_args(
+            &video_path,
+            &backup_path,
+            video_info.stream_count,
+            replaced,
+            replaced.map_or(video_info.subtitle_tracks.len(), |t| t.index as usize),
+            subtitle_codec,
+            &output,
+        );
+        run_ffmpeg_with_progress(
+            &app,
+            &ffmpeg,
+            &args,
+            "restore",
+            &video_path,
+            video_info.duration,
+            temp_files.paths(),
+        )
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?
+    };
 
     if result.status.success() {
-        fs::rename(&temp_output, &video_path)
-            .map_err(|e| format!("Failed to replace original file: {}", e))?;
-
-        Ok(OperationResult {
-            success: true,
-            message: "Subtitle restored successfully".to_string(),
-            data: None,
-        })
+        let outcome = finalize_verified_remux(
+            &app,
+            &video_path,
+            &temp_output,
+            &output_policy,
+            subtitle_delta,
+            ffmpeg_path.clone(),
+            verify_decode.unwrap_or(false),
+            "Subtitle restored successfully",
+        )
+        .await?;
+        if outcome.success {
+            temp_files.keep(&temp_output);
+        }
+        Ok(outcome)
     } else {
-        let _ = fs::remove_file(&temp_output);
-
         Ok(OperationResult {
             success: false,
             message: String::from_utf8_lossy(&result.stderr).to_string(),
             data: None,
+            verification: None,
         })
     }
 }
@@ -184,28 +597,147 @@ pub async fn restore_subtitle(
 pub async fn delete_backup(
     backup_path: String,
     video_path: String,
-) -> Result<OperationResult, String> {
+) -> Result<OperationResult, AnimeSubsError> {
     if Path::new(&backup_path).exists() {
         fs::remove_file(&backup_path)
-            .map_err(|e| format!("Failed to delete backup file: {}", e))?;
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to delete backup file: {}", e)))?;
     }
 
-    let backup_dir = get_backup_dir(&video_path);
-    let meta_path = backup_dir.join("backups.json");
+    // Metadata sits next to the backup, in the video's folder or the central directory.
+    let backup_dir = Path::new(&backup_path)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| get_backup_dir(&video_path));
+    let meta_path = backup_dir.join(BACKUP_META_FILE);
 
     if meta_path.exists() {
-        let content = fs::read_to_string(&meta_path).unwrap_or_else(|_| "[]".to_string());
-        let mut backups: Vec<BackupInfo> = serde_json::from_str(&content).unwrap_or_default();
-
+        let mut backups = read_backup_meta(&backup_dir);
         backups.retain(|b| b.backup_path != backup_path);
-
-        fs::write(&meta_path, serde_json::to_string_pretty(&backups).unwrap())
-            .map_err(|e| format!("Failed to update backup metadata: {}", e))?;
+        write_backup_meta(&backup_dir, &backups)?;
     }
 
     Ok(OperationResult {
         success: true,
         message: "Backup deleted successfully".to_string(),
         data: None,
+        verification: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup(track_index: u32, created_at: &str) -> BackupInfo {
+        BackupInfo {
+            original_path: "/anime/Show - 01.mkv".to_string(),
+            backup_path: format!(
+                "/anime/.animesubs_backup/{}_{}.ass",
+                track_index, created_at
+            ),
+            track_index,
+            format: "ass".to_string(),
+            created_at: created_at.to_string(),
+            track: None,
+        }
+    }
+
+    fn track(index: u32, stream_index: u32) -> SubtitleTrack {
+        SubtitleTrack {
+            index,
+            stream_index,
+            codec: "ass".to_string(),
+            language: Some("eng".to_string()),
+            title: Some("Full".to_string()),
+            default: true,
+            forced: false,
+            detected_language: None,
+        }
+    }
+
+    #[test]
+    fn restores_only_over_the_backed_up_track() {
+        let recorded = BackupTrack {
+            stream_index: 3,
+            codec: "ass".to_string(),
+            language: Some("eng".to_string()),
+            title: Some("Full".to_string()),
+        };
+        // Replaced by a translation in place: same stream, different metadata.
+        let mut translated = track(1, 3);
+        translated.title = Some("Translated (pt-br)".to_string());
+        assert!(check_restore_target(&recorded, &translated).is_ok());
+
+        let moved = track(1, 4);
+        assert!(matches!(
+            check_restore_target(&recorded, &moved),
+            Err(AnimeSubsError::TrackNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn restore_keeps_streams_and_replaces_track_in_place() {
+        let replaced = track(0, 2);
+        let args = restore_ffmpeg_args("v.mkv", "b.ass", 5, Some(&replaced), 0, "ass", "o.mkv");
+        let maps: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "-map")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(maps, ["0:0", "0:1", "1:0", "0:3", "0:4"]);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-disposition:s:0", "default"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata:s:s:0", "language=eng"]));
+
+        let args = restore_mkvmerge_args("v.mkv", "b.ass", &[0, 1, 2, 3], Some(&replaced), "o.mkv");
+        assert!(args.windows(2).any(|w| w == ["--subtitle-tracks", "!2"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["--track-order", "0:0,0:1,1:0,0:3"]));
+
+        let appended = restore_ffmpeg_args("v.mkv", "b.ass", 2, None, 0, "ass", "o.mkv");
+        assert!(appended.windows(2).any(|w| w == ["-map", "1:0"]));
+        assert!(!appended.iter().any(|a| a.starts_with("-disposition")));
+    }
+
+    #[test]
+    fn selects_backups_outside_retention() {
+        let backups = vec![
+            backup(2, "20240101_120000"),
+            backup(2, "20240301_120000"),
+            backup(2, "20240201_120000"),
+            backup(3, "20240101_120000"),
+        ];
+        let now =
+            NaiveDateTime::parse_from_str("20240310_120000", BACKUP_TIMESTAMP_FORMAT).unwrap();
+
+        let keep_last = BackupRetention {
+            keep_last: Some(2),
+            max_age_days: None,
+        };
+        let expired = expired_backups(&backups, &keep_last, now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].created_at, "20240101_120000");
+        assert_eq!(expired[0].track_index, 2);
+
+        let max_age = BackupRetention {
+            keep_last: None,
+            max_age_days: Some(30),
+        };
+        let expired: Vec<(u32, String)> = expired_backups(&backups, &max_age, now)
+            .into_iter()
+            .map(|b| (b.track_index, b.created_at))
+            .collect();
+        assert_eq!(
+            expired,
+            vec![
+                (2, "20240101_120000".to_string()),
+                (2, "20240201_120000".to_string()),
+                (3, "20240101_120000".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,605 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::http::{self, http_client};
+use crate::utils::*;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::info;
+
+const FFMPEG_DIR: &str = "ffmpeg";
+const MKVTOOLNIX_DIR: &str = "mkvtoolnix";
+const DOWNLOAD_DIR: &str = ".download";
+pub(crate) const DOWNLOAD_PROGRESS_EVENT: &str = "tool-download-progress";
+/// Bytes between two progress events while downloading.
+const PROGRESS_STEP: u64 = 512 * 1024;
+
+/// App data directory the downloaded tools live under, once resolved at startup.
+static TOOLS_DIR: LazyLock<RwLock<Option<PathBuf>>> = LazyLock::new(|| RwLock::new(None));
+
+/// One archive to fetch and the checksum file published next to it.
+struct Download {
+    /// Tool reported in progress events (`ffmpeg`, `mkvtoolnix`).
+    tool: &'static str,
+    file_name: String,
+    url: String,
+    checksum_url: String,
+}
+
+/// One archive of a static ffmpeg build and its checksum file.
+struct BuildAsset {
+    base_url: &'static str,
+    file_name: &'static str,
+    checksum_file: &'static str,
+}
+
+impl BuildAsset {
+    fn download(&self) -> Download {
+        Download {
+            tool: "ffmpeg",
+            file_name: self.file_name.to_string(),
+            url: format!("{}/{}", self.base_url, self.file_name),
+            checksum_url: format!("{}/{}", self.base_url, self.checksum_file),
+        }
+    }
+}
+const BTBN_RELEASE: &str = "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest";
+const RIEDL_MACOS_AMD64: &str =
+    "https://ffmpeg.martin-riedl.de/redirect/latest/macos/amd64/release";
+const RIEDL_MACOS_ARM64: &str =
+    "https://ffmpeg.martin-riedl.de/redirect/latest/macos/arm64/release";
+
+const fn btbn(file_name: &'static str) -> BuildAsset {
+    BuildAsset {
+        base_url: BTBN_RELEASE,
+        file_name,
+        checksum_file: "checksums.sha256",
+    }
+}
+
+/// Static GPL builds for the running OS/arch: BtbN for Windows and Linux (one
+/// archive with both tools), martin-riedl.de for macOS (one archive per tool).
+fn build_assets() -> Option<&'static [BuildAsset]> {
+    const ASSETS: &[(&str, &str, &[BuildAsset])] = &[
+        (
+            "windows",
+            "x86_64",
+            &[btbn("ffmpeg-master-latest-win64-gpl.zip")],
+        ),
+        (
+            "windows",
+            "aarch64",
+            &[btbn("ffmpeg-master-latest-winarm64-gpl.zip")],
+        ),
+        (
+            "linux",
+            "x86_64",
+            &[btbn("ffmpeg-master-latest-linux64-gpl.tar.xz")],
+        ),
+        (
+            "linux",
+            "aarch64",
+            &[btbn("ffmpeg-master-latest-linuxarm64-gpl.tar.xz")],
+        ),
+        (
+            "macos",
+            "x86_64",
+            &[
+                BuildAsset {
+                    base_url: RIEDL_MACOS_AMD64,
+                    file_name: "ffmpeg.zip",
+                    checksum_file: "ffmpeg.zip.sha256",
+                },
+                BuildAsset {
+                    base_url: RIEDL_MACOS_AMD64,
+                    file_name: "ffprobe.zip",
+                    checksum_file: "ffprobe.zip.sha256",
+                },
+            ],
+        ),
+        (
+            "macos",
+            "aarch64",
+            &[
+                BuildAsset {
+                    base_url: RIEDL_MACOS_ARM64,
+                    file_name: "ffmpeg.zip",
+                    checksum_file: "ffmpeg.zip.sha256",
+                },
+                BuildAsset {
+                    base_url: RIEDL_MACOS_ARM64,
+                    file_name: "ffprobe.zip",
+                    checksum_file: "ffprobe.zip.sha256",
+                },
+            ],
+        ),
+    ];
+    ASSETS
+        .iter()
+        .find(|(os, arch, _)| *os == std::env::consts::OS && *arch == std::env::consts::ARCH)
+        .map(|(_, _, assets)| *assets)
+}
+
+/// File name of `tool`'s executable on this platform.
+pub(crate) fn exe_name(tool: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", tool)
+    } else {
+        tool.to_string()
+    }
+}
+
+const MKVTOOLNIX_LATEST: &str = "https://mkvtoolnix.download/latest-release.xml";
+const MKVTOOLNIX_RELEASES: &str = "https://mkvtoolnix.download/windows/releases";
+
+/// Version number of the newest release in MKVToolNix's `latest-release.xml`.
+fn parse_latest_version(feed: &str) -> Option<String> {
+    let start = feed.find("<version>")? + "<version>".len();
+    let end = start + feed[start..].find("</version>")?;
+    let version = feed[start..end].trim();
+    let valid = !version.is_empty() && version.chars().all(|c| c.is_ascii_digit() || c == '.');
+    valid.then(|| version.to_string())
+}
+
+/// The portable (no installer) build of `version`. MKVToolNix only publishes one for
+/// 64-bit Windows; elsewhere it comes from the system package manager.
+fn mkvtoolnix_download(os: &str, arch: &str, version: &str) -> Option<Download> {
+    if (os, arch) != ("windows", "x86_64") {
+        return None;
+    }
+    let base_url = format!("{}/{}", MKVTOOLNIX_RELEASES, version);
+    let file_name = format!("mkvtoolnix-64-bit-{}.7z", version);
+    Some(Download {
+        tool: "mkvtoolnix",
+        url: format!("{}/{}", base_url, file_name),
+        checksum_url: format!("{}/sha256sums.txt", base_url),
+        file_name,
+    })
+}
+
+/// Path of a downloaded tool (`ffmpeg`, `ffprobe`, `mkvmerge`, `mkvpropedit`), if
+/// one was installed.
+pub(crate) fn bundled_tool_path(tool: &str) -> Option<String> {
+    let subdir = if tool.starts_with("mkv") {
+        MKVTOOLNIX_DIR
+    } else {
+        FFMPEG_DIR
+    };
+    let root = TOOLS_DIR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()?;
+    let path = root.join(subdir).join(exe_name(tool));
+    path.exists().then(|| path.to_string_lossy().to_string())
+}
+
+fn tools_dir(app: &AppHandle) -> Result<PathBuf, AnimeSubsError> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to resolve app data directory: {}", e)))
+}
+
+fn bundled_dir(app: &AppHandle, subdir: &str) -> Result<PathBuf, AnimeSubsError> {
+    let dir = tools_dir(app)?.join(subdir);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create {} directory: {}", subdir, e)))?;
+    Ok(dir)
+}
+
+/// Makes previously downloaded tools visible to `get_ffmpeg_path` and
+/// `resolve_mkvmerge_path`; called once at startup.
+pub(crate) fn load_bundled_tools(app: &AppHandle) -> Result<(), AnimeSubsError> {
+    *TOOLS_DIR.write().unwrap_or_else(|e| e.into_inner()) = Some(tools_dir(app)?);
+    Ok(())
+}
+
+/// SHA-256 for `file_name` in a checksum file, which is either a bare hash or
+/// `sha256sum` output listing several files.
+fn parse_checksum(content: &str, file_name: &str) -> Option<String> {
+    let is_hash = |s: &str| s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit());
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next().filter(|hash| is_hash(hash))?;
+        match parts.next() {
+            None => Some(hash.to_ascii_lowercase()),
+            Some(name) if name.trim_start_matches('*') == file_name => {
+                Some(hash.to_ascii_lowercase())
+            }
+            Some(_) => None,
+        }
+    })
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    tool: &str,
+    file_name: &str,
+    stage: ToolDownloadStage,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+) {
+    let progress = ToolDownloadProgress {
+        tool: tool.to_string(),
+        file_name: file_name.to_string(),
+        stage,
+        downloaded_bytes,
+        total_bytes,
+    };
+    let _ = app.emit(DOWNLOAD_PROGRESS_EVENT, &progress);
+}
+
+async fn fetch_text(
+    client: &reqwest::Client,
+    url: &str,
+    what: &str,
+) -> Result<String, AnimeSubsError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| AnimeSubsError::Provider(format!("Failed to download {}: {}", what, e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(http::status_error(
+            status,
+            None,
+            format!("Download of {} failed ({})", what, status),
+        ));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| AnimeSubsError::Provider(format!("Failed to read {}: {}", what, e)))
+}
+
+/// Streams `download` into `target`, hashing as it goes, and fails on a checksum mismatch.
+async fn download_asset(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    download: &Download,
+    target: &Path,
+) -> Result<(), AnimeSubsError> {
+    let file_name = &download.file_name;
+    let checksums = fetch_text(client, &download.checksum_url, "checksum").await?;
+    let expected = parse_checksum(&checksums, file_name)
+        .ok_or_else(|| AnimeSubsError::parse(format!("No checksum published for {}", file_name)))?;
+
+    let mut response = client.get(&download.url).send().await.map_err(|e| {
+        AnimeSubsError::Provider(format!("Failed to download {}: {}", file_name, e))
+    })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(http::status_error(
+            status,
+            None,
+            format!("Download of {} failed ({})", file_name, status),
+        ));
+    }
+
+    let total_bytes = response.content_length();
+    let mut file = fs::File::create(target)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create {}: {}", file_name, e)))?;
+    let mut hasher = Sha256::new();
+    let mut downloaded_bytes = 0u64;
+    let mut reported_bytes = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AnimeSubsError::Provider(format!("Failed to download {}: {}", file_name, e)))?
+    {
+        hasher.update(&chunk);
+        file.write_all(&chunk)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to write {}: {}", file_name, e)))?;
+        downloaded_bytes += chunk.len() as u64;
+        if downloaded_bytes - reported_bytes >= PROGRESS_STEP {
+            reported_bytes = downloaded_bytes;
+            emit_progress(
+                app,
+                download.tool,
+                file_name,
+                ToolDownloadStage::Downloading,
+                downloaded_bytes,
+                total_bytes,
+            );
+        }
+    }
+    file.sync_all()
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to write {}: {}", file_name, e)))?;
+
+    emit_progress(
+        app,
+        download.tool,
+        file_name,
+        ToolDownloadStage::Verifying,
+        downloaded_bytes,
+        total_bytes,
+    );
+    let actual = format!("{:x}", hasher.finalize());
+    if actual != expected {
+        let _ = fs::remove_file(target);
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            file_name, expected, actual
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Downloads and unpacks `download` into `work_dir`.
+async fn fetch_and_extract(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    download: &Download,
+    work_dir: &Path,
+) -> Result<(), AnimeSubsError> {
+    let archive = work_dir.join(&download.file_name);
+    download_asset(app, client, download, &archive).await?;
+    emit_progress(
+        app,
+        download.tool,
+        &download.file_name,
+        ToolDownloadStage::Extracting,
+        0,
+        None,
+    );
+    extract_archive(&archive, work_dir)
+}
+
+/// Empty scratch directory for one tool's download, next to the install dirs.
+fn work_dir(app: &AppHandle, tool: &str) -> Result<PathBuf, AnimeSubsError> {
+    let dir = bundled_dir(app, DOWNLOAD_DIR)?.join(tool);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create download directory: {}", e)))?;
+    Ok(dir)
+}
+
+/// First line of `<program> <version_arg>`, failing when the installed binary
+/// does not run here.
+fn installed_version(program: &Path, version_arg: &str) -> Result<String, AnimeSubsError> {
+    let name = program
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let output = create_command(program)
+        .arg(version_arg)
+        .output()
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run {}: {}", name, e)))?;
+    if !output.status.success() {
+        return Err(AnimeSubsError::FfmpegNotFound(format!(
+            "The downloaded {} does not run on this system",
+            name
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .unwrap_or_else(|| format!("{} installed", name)))
+}
+
+/// Unpacks `archive` with the system `tar` (bsdtar on Windows and macOS reads zip
+/// and 7z too).
+fn extract_archive(archive: &Path, destination: &Path) -> Result<(), AnimeSubsError> {
+    let output = create_command("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(destination)
+        .output()
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run tar: {}", e)))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Finds `file_name` anywhere below `dir`; archives nest the binaries under `bin/`.
+fn find_file(dir: &Path, file_name: &str) -> Option<PathBuf> {
+    let entries = fs::read_dir(dir).ok()?;
+    let mut subdirs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            subdirs.push(path);
+        } else if path.file_name().is_some_and(|name| name == file_name) {
+            return Some(path);
+        }
+    }
+    subdirs
+        .iter()
+        .find_map(|subdir| find_file(subdir, file_name))
+}
+
+fn install_tool(
+    extracted: &Path,
+    install_dir: &Path,
+    tool: &str,
+) -> Result<PathBuf, AnimeSubsError> {
+    let name = exe_name(tool);
+    let source = find_file(extracted, &name)
+        .ok_or_else(|| format!("{} is missing from the downloaded build", name))?;
+    let target = install_dir.join(&name);
+    fs::copy(&source, &target)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to install {}: {}", name, e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o755)).map_err(|e| {
+            AnimeSubsError::Io(format!("Failed to make {} executable: {}", name, e))
+        })?;
+    }
+    Ok(target)
+}
+
+/// Replaces `install_dir` with the extracted folder that holds `tool`; MKVToolNix
+/// needs its DLLs and data files next to the executables.
+fn install_folder(
+    extracted: &Path,
+    install_dir: &Path,
+    tool: &str,
+) -> Result<PathBuf, AnimeSubsError> {
+    let name = exe_name(tool);
+    let folder = find_file(extracted, &name)
+        .and_then(|source| source.parent().map(Path::to_path_buf))
+        .ok_or_else(|| format!("{} is missing from the downloaded build", name))?;
+    let _ = fs::remove_dir_all(install_dir);
+    fs::rename(&folder, install_dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to install {}: {}", tool, e)))?;
+    Ok(install_dir.join(name))
+}
+
+/// Downloads a static ffmpeg/ffprobe build for this OS/arch into the app data dir,
+/// verifying each archive against its published SHA-256. Progress is reported on
+/// `tool-download-progress`; afterwards `get_ffmpeg_path` prefers this build.
+#[tauri::command]
+pub async fn download_ffmpeg(app: AppHandle) -> Result<OperationResult, AnimeSubsError> {
+    let assets = build_assets().ok_or_else(|| {
+        format!(
+            "No ffmpeg build available for {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })?;
+    let install_dir = bundled_dir(&app, FFMPEG_DIR)?;
+    let work_dir = work_dir(&app, FFMPEG_DIR)?;
+
+    let client = http_client()?;
+    let result = async {
+        for asset in assets {
+            fetch_and_extract(&app, &client, &asset.download(), &work_dir).await?;
+        }
+        let ffmpeg = install_tool(&work_dir, &install_dir, "ffmpeg")?;
+        install_tool(&work_dir, &install_dir, "ffprobe")?;
+        Ok::<_, AnimeSubsError>(ffmpeg)
+    }
+    .await;
+    let _ = fs::remove_dir_all(&work_dir);
+    let ffmpeg = result?;
+
+    let version = installed_version(&ffmpeg, "-version")?;
+    load_bundled_tools(&app)?;
+    emit_progress(&app, "ffmpeg", "", ToolDownloadStage::Done, 0, None);
+    info!("Installed {} to {}", version, ffmpeg.display());
+    Ok(OperationResult {
+        success: true,
+        message: version,
+        data: Some(ffmpeg.to_string_lossy().to_string()),
+        verification: None,
+    })
+}
+
+/// Downloads the latest portable MKVToolNix into the app data dir (64-bit Windows
+/// only), verified against the release's `sha256sums.txt`. Progress is reported on
+/// `tool-download-progress`; afterwards `resolve_mkvmerge_path` prefers this copy.
+#[tauri::command]
+pub async fn download_mkvtoolnix(app: AppHandle) -> Result<OperationResult, AnimeSubsError> {
+    let (os, arch) = (std::env::consts::OS, std::env::consts::ARCH);
+    // Checked before touching the network so unsupported platforms fail fast.
+    if mkvtoolnix_download(os, arch, "0").is_none() {
+        return Err(format!(
+            "No portable MKVToolNix build for {} {}; install it with the system package \
+             manager (e.g. `brew install mkvtoolnix` or `apt install mkvtoolnix`)",
+            os, arch
+        )
+        .into());
+    }
+
+    let client = http_client()?;
+    let feed = fetch_text(&client, MKVTOOLNIX_LATEST, "MKVToolNix release list").await?;
+    let version = parse_latest_version(&feed)
+        .ok_or_else(|| AnimeSubsError::parse("No version in the MKVToolNix release list"))?;
+    let download = mkvtoolnix_download(os, arch, &version)
+        .ok_or_else(|| format!("No portable MKVToolNix build for {} {}", os, arch))?;
+
+    let install_dir = tools_dir(&app)?.join(MKVTOOLNIX_DIR);
+    let work_dir = work_dir(&app, MKVTOOLNIX_DIR)?;
+    let result = async {
+        fetch_and_extract(&app, &client, &download, &work_dir).await?;
+        install_folder(&work_dir, &install_dir, "mkvmerge")
+    }
+    .await;
+    let _ = fs::remove_dir_all(&work_dir);
+    let mkvmerge = result?;
+
+    let version = installed_version(&mkvmerge, "--version")?;
+    load_bundled_tools(&app)?;
+    emit_progress(&app, "mkvtoolnix", "", ToolDownloadStage::Done, 0, None);
+    info!("Installed {} to {}", version, install_dir.display());
+    Ok(OperationResult {
+        success: true,
+        message: version,
+        data: Some(mkvmerge.to_string_lossy().to_string()),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn reads_bare_and_listed_checksums() {
+        assert_eq!(
+            parse_checksum(&format!("{}\n", HASH), "ffmpeg.zip"),
+            Some(HASH.to_string())
+        );
+
+        let listing = format!(
+            "{}  ffmpeg-master-latest-linux64-lgpl.tar.xz\n\
+             {}  ffmpeg-master-latest-linux64-gpl.tar.xz\n",
+            "0".repeat(64),
+            HASH.to_ascii_uppercase()
+        );
+        assert_eq!(
+            parse_checksum(&listing, "ffmpeg-master-latest-linux64-gpl.tar.xz"),
+            Some(HASH.to_string())
+        );
+        assert_eq!(parse_checksum(&listing, "ffmpeg-win64.zip"), None);
+        assert_eq!(parse_checksum("not a checksum", "ffmpeg.zip"), None);
+    }
+
+    #[test]
+    fn finds_binaries_nested_in_archives() {
+        let dir = std::env::temp_dir().join(format!("animesubs-bootstrap-{}", std::process::id()));
+        let bin = dir.join("ffmpeg-master-latest-linux64-gpl").join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("ffprobe"), b"").unwrap();
+
+        assert_eq!(find_file(&dir, "ffprobe"), Some(bin.join("ffprobe")));
+        assert_eq!(find_file(&dir, "ffmpeg"), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn builds_portable_mkvtoolnix_urls() {
+        let feed = "<?xml version=\"1.0\"?>\n<mkvtoolnix-releases>\n  <latest-source>\n    \
+                    <version>88.0</version>\n  </latest-source>\n</mkvtoolnix-releases>";
+        assert_eq!(parse_latest_version(feed).as_deref(), Some("88.0"));
+        assert_eq!(parse_latest_version("<version>88.0; rm</version>"), None);
+
+        let download = mkvtoolnix_download("windows", "x86_64", "88.0").unwrap();
+        assert_eq!(
+            download.url,
+            "https://mkvtoolnix.download/windows/releases/88.0/mkvtoolnix-64-bit-88.0.7z"
+        );
+        assert_eq!(
+            download.checksum_url,
+            "https://mkvtoolnix.download/windows/releases/88.0/sha256sums.txt"
+        );
+        assert!(mkvtoolnix_download("linux", "x86_64", "88.0").is_none());
+        assert!(mkvtoolnix_download("windows", "aarch64", "88.0").is_none());
+    }
+}
@@ -0,0 +1,263 @@
+use super::operations::run_ffmpeg_with_progress;
+use super::temp_files::TempFileRegistry;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::path::Path;
+use tauri::AppHandle;
+
+const VAAPI_DEVICE: &str = "/dev/dri/renderD128";
+
+/// Escapes a path for use as a filter option value inside an ffmpeg filtergraph.
+/// Both the option level (`\ : '`) and the graph level (`\ ' [ ] , ;`) need escaping.
+fn escape_filter_path(path: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    let mut option_escaped = String::with_capacity(normalized.len());
+    for c in normalized.chars() {
+        if matches!(c, '\\' | ':' | '\'') {
+            option_escaped.push('\\');
+        }
+        option_escaped.push(c);
+    }
+
+    let mut escaped = String::with_capacity(option_escaped.len());
+    for c in option_escaped.chars() {
+        if matches!(c, '\\' | '\'' | '[' | ']' | ',' | ';') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+pub(crate) fn build_subtitle_filter(
+    video_path: &str,
+    subtitle_path: Option<&str>,
+    track_index: Option<u32>,
+) -> Result<String, AnimeSubsError> {
+    match (subtitle_path, track_index) {
+        (Some(path), _) => {
+            let ext = Path::new(path)
+                .extension()
+                .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            let filter = if matches!(ext.as_str(), "ass" | "ssa") {
+                "ass"
+            } else {
+                "subtitles"
+            };
+            Ok(format!("{}={}", filter, escape_filter_path(path)))
+        }
+        // Rendering from the video itself lets libass pick up fonts attached to the MKV.
+        (None, Some(index)) => Ok(format!(
+            "subtitles={}:si={}",
+            escape_filter_path(video_path),
+            index
+        )),
+        (None, None) => Err("Either a subtitle file or a track index is required".into()),
+    }
+}
+
+/// ffmpeg encoder name behind each `VideoEncoder` choice.
+pub(crate) fn encoder_codec(encoder: VideoEncoder) -> &'static str {
+    match encoder {
+        VideoEncoder::X264 => "libx264",
+        VideoEncoder::X265 => "libx265",
+        VideoEncoder::Nvenc => "h264_nvenc",
+        VideoEncoder::Qsv => "h264_qsv",
+        VideoEncoder::Vaapi => "h264_vaapi",
+    }
+}
+
+fn build_encoder_args(options: &BurnOptions) -> Vec<String> {
+    let (quality_flag, default_quality, default_preset) = match options.encoder {
+        VideoEncoder::X264 => ("-crf", 20, Some("medium")),
+        VideoEncoder::X265 => ("-crf", 22, Some("medium")),
+        VideoEncoder::Nvenc => ("-cq", 21, Some("p5")),
+        VideoEncoder::Qsv => ("-global_quality", 21, Some("medium")),
+        VideoEncoder::Vaapi => ("-qp", 21, None),
+    };
+
+    let mut args = vec![
+        "-c:v".to_string(),
+        encoder_codec(options.encoder).to_string(),
+    ];
+    if let Some(preset) = options.preset.as_deref().or(default_preset) {
+        args.extend(["-preset".to_string(), preset.to_string()]);
+    }
+    match options.bitrate.as_deref().filter(|b| !b.trim().is_empty()) {
+        Some(bitrate) => args.extend(["-b:v".to_string(), bitrate.trim().to_string()]),
+        None => {
+            if options.encoder == VideoEncoder::Nvenc {
+                args.extend(["-rc".to_string(), "vbr".to_string()]);
+            }
+            args.extend([
+                quality_flag.to_string(),
+                options.crf.unwrap_or(default_quality).to_string(),
+            ]);
+        }
+    }
+    args
+}
+
+fn build_burn_args(
+    video_path: &str,
+    subtitle_filter: &str,
+    options: &BurnOptions,
+    output_path: &str,
+) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut filter = subtitle_filter.to_string();
+    if options.encoder == VideoEncoder::Vaapi {
+        // Subtitles are rendered in software, then the frames are uploaded to the GPU.
+        args.extend(["-vaapi_device".to_string(), VAAPI_DEVICE.to_string()]);
+        filter.push_str(",format=nv12,hwupload");
+    }
+
+    args.extend([
+        "-i".to_string(),
+        ffmpeg_file(video_path),
+        "-map".to_string(),
+        "0:v:0".to_string(),
+        "-map".to_string(),
+        "0:a?".to_string(),
+        "-vf".to_string(),
+        filter,
+    ]);
+    args.extend(build_encoder_args(options));
+    args.extend([
+        "-c:a".to_string(),
+        "copy".to_string(),
+        "-y".to_string(),
+        ffmpeg_file(output_path),
+    ]);
+    args
+}
+
+/// Renders a subtitle file or embedded track into the video picture.
+/// Unlike the remux commands this re-encodes, so the default output is a `_hardsub` copy.
+#[tauri::command]
+pub async fn burn_subtitle(
+    app: AppHandle,
+    video_path: String,
+    subtitle_path: Option<String>,
+    track_index: Option<u32>,
+    options: Option<BurnOptions>,
+    ffmpeg_path: Option<String>,
+    output_policy: Option<OutputPolicy>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let options = options.unwrap_or_default();
+    let output_policy = output_policy.unwrap_or(OutputPolicy::Alongside {
+        suffix: "_hardsub".to_string(),
+        replace_existing: false,
+    });
+
+    if let Some(path) = subtitle_path.as_deref() {
+        if !Path::new(path).exists() {
+            return Err(format!("Subtitle file not found: {}", path).into());
+        }
+    }
+    let subtitle_filter =
+        build_subtitle_filter(&video_path, subtitle_path.as_deref(), track_index)?;
+
+    let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
+    let duration = super::video::get_video_info(video_path.clone(), ffmpeg_path)
+        .await
+        .ok()
+        .and_then(|info| info.duration);
+    let temp_output = build_remux_temp_path(&video_path, "hardsub");
+    ensure_remux_space(&video_path, &temp_output, &output_policy)?;
+    let mut temp_files = TempFileRegistry::new(&app);
+    temp_files.track(temp_output.clone());
+
+    let args = build_burn_args(
+        &video_path,
+        &subtitle_filter,
+        &options,
+        &temp_output.to_string_lossy(),
+    );
+
+    let result = run_ffmpeg_with_progress(
+        &app,
+        &ffmpeg,
+        &args,
+        "burn_subtitle",
+        &video_path,
+        duration,
+        temp_files.paths(),
+    )
+    .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
+
+    if result.status.success() {
+        let final_path = finalize_remux_output(&temp_output, &video_path, &output_policy)?;
+        temp_files.keep(&temp_output);
+
+        Ok(OperationResult {
+            success: true,
+            message: "Subtitles burned in successfully".to_string(),
+            data: Some(final_path),
+            verification: None,
+        })
+    } else {
+        Ok(OperationResult {
+            success: false,
+            message: String::from_utf8_lossy(&result.stderr).to_string(),
+            data: None,
+            verification: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_filter_paths_for_both_levels() {
+        assert_eq!(
+            escape_filter_path(r"C:\Anime\Show [01].ass"),
+            r"C\\:/Anime/Show \[01\].ass"
+        );
+        assert_eq!(escape_filter_path("it's.ass"), r"it\\\'s.ass");
+    }
+
+    #[test]
+    fn picks_filter_and_encoder_settings() {
+        assert_eq!(
+            build_subtitle_filter("/v/ep.mkv", None, Some(1)).unwrap(),
+            "subtitles=/v/ep.mkv:si=1"
+        );
+        assert!(build_subtitle_filter("/v/ep.mkv", Some("/v/ep.ass"), None)
+            .unwrap()
+            .starts_with("ass="));
+        assert!(build_subtitle_filter("/v/ep.mkv", None, None).is_err());
+
+        let nvenc = BurnOptions {
+            encoder: VideoEncoder::Nvenc,
+            crf: Some(19),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_encoder_args(&nvenc),
+            vec![
+                "-c:v",
+                "h264_nvenc",
+                "-preset",
+                "p5",
+                "-rc",
+                "vbr",
+                "-cq",
+                "19"
+            ]
+        );
+
+        let bitrate = BurnOptions {
+            bitrate: Some("4M".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            build_encoder_args(&bitrate),
+            vec!["-c:v", "libx264", "-preset", "medium", "-b:v", "4M"]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use super::burn::encoder_codec;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+const BURN_ENCODERS: [VideoEncoder; 5] = [
+    VideoEncoder::X264,
+    VideoEncoder::X265,
+    VideoEncoder::Nvenc,
+    VideoEncoder::Qsv,
+    VideoEncoder::Vaapi,
+];
+const HARDWARE_ENCODER_SUFFIXES: [&str; 6] = [
+    "_nvenc",
+    "_qsv",
+    "_vaapi",
+    "_amf",
+    "_videotoolbox",
+    "_v4l2m2m",
+];
+
+const MKVTOOLNIX_MISSING: &str =
+    "MKVToolNix not found; MKV output falls back to ffmpeg, which handles ASS subtitles less well";
+
+/// Probe results per ffmpeg binary; probing spawns several processes so it only runs once.
+static CAPABILITY_CACHE: LazyLock<Mutex<HashMap<String, CapabilityReport>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn run_tool(program: &str, args: &[&str]) -> Option<String> {
+    let output = create_command(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Names listed after the `------` separator of `ffmpeg -encoders` / `-filters`,
+/// keeping only entries whose flag column starts with `kind` (`V`, `A`, `S`, ...).
+fn parse_listing_names(output: &str, kind: Option<char>) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let flags = parts.next()?;
+            let name = parts.next()?;
+            match kind {
+                Some(kind) if !flags.starts_with(kind) => None,
+                _ => Some(name.to_string()),
+            }
+        })
+        .collect()
+}
+
+fn parse_hwaccels(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("Hardware acceleration methods"))
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn mkvmerge_version(mkvmerge: &str) -> Option<String> {
+    run_tool(mkvmerge, &["--version"])
+        .and_then(|out| out.lines().next().map(|line| line.trim().to_string()))
+}
+
+fn probe(ffmpeg: &str) -> CapabilityReport {
+    let ffmpeg_version = run_tool(ffmpeg, &["-version"])
+        .and_then(|out| out.lines().next().map(|line| line.trim().to_string()));
+    if ffmpeg_version.is_none() {
+        return CapabilityReport {
+            ffmpeg_path: ffmpeg.to_string(),
+            mkvmerge_path: resolve_mkvmerge_path(),
+            mkvpropedit_available: resolve_mkvpropedit_path().is_some(),
+            ..Default::default()
+        };
+    }
+
+    let encoders = run_tool(ffmpeg, &["-hide_banner", "-encoders"])
+        .map(|out| parse_listing_names(&out, Some('V')))
+        .unwrap_or_default();
+    let filters = run_tool(ffmpeg, &["-hide_banner", "-filters"])
+        .map(|out| parse_listing_names(&out, None))
+        .unwrap_or_default();
+    let hwaccels = run_tool(ffmpeg, &["-hide_banner", "-hwaccels"])
+        .map(|out| parse_hwaccels(&out))
+        .unwrap_or_default();
+
+    let ffprobe = get_ffprobe_path(Some(ffmpeg.to_string()));
+    let mkvmerge_path = resolve_mkvmerge_path();
+    let mkvmerge_version = mkvmerge_path.as_deref().and_then(mkvmerge_version);
+
+    CapabilityReport {
+        ffmpeg_path: ffmpeg.to_string(),
+        ffmpeg_version,
+        ffprobe_available: run_tool(&ffprobe, &["-version"]).is_some(),
+        burn_encoders: BURN_ENCODERS
+            .into_iter()
+            .filter(|encoder| encoders.iter().any(|name| name == encoder_codec(*encoder)))
+            .collect(),
+        hardware_encoders: encoders
+            .iter()
+            .filter(|name| HARDWARE_ENCODER_SUFFIXES.iter().any(|s| name.ends_with(s)))
+            .cloned()
+            .collect(),
+        hwaccels,
+        libass: filters
+            .iter()
+            .any(|name| name == "ass" || name == "subtitles"),
+        mkvmerge_path,
+        mkvmerge_version,
+        mkvpropedit_available: resolve_mkvpropedit_path().is_some(),
+    }
+}
+
+/// Reports which encoders, filters and external tools are usable so the GUI can
+/// disable features up front. Results are cached unless `refresh` is set.
+#[tauri::command]
+pub async fn probe_capabilities(
+    ffmpeg_path: Option<String>,
+    refresh: Option<bool>,
+) -> Result<CapabilityReport, AnimeSubsError> {
+    let ffmpeg = get_ffmpeg_path(ffmpeg_path);
+
+    if !refresh.unwrap_or(false) {
+        if let Some(report) = CAPABILITY_CACHE
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&ffmpeg).cloned())
+        {
+            return Ok(report);
+        }
+    }
+
+    let report = probe(&ffmpeg);
+    if let Ok(mut cache) = CAPABILITY_CACHE.lock() {
+        cache.insert(ffmpeg, report.clone());
+    }
+    Ok(report)
+}
+
+/// Reports which mkvmerge/mkvpropedit will be used and their version, so the GUI
+/// can warn before MKV output silently falls back to ffmpeg.
+#[tauri::command]
+pub async fn check_mkvtoolnix() -> Result<MkvToolNixStatus, AnimeSubsError> {
+    let configured_path = super::settings::current_settings().mkvtoolnix_path;
+    let mkvmerge_path = resolve_mkvmerge_path();
+    let mkvmerge_version = mkvmerge_path.as_deref().and_then(mkvmerge_version);
+    let available = mkvmerge_version.is_some();
+
+    let mut message = match (&mkvmerge_path, &mkvmerge_version) {
+        (_, Some(version)) => version.clone(),
+        (Some(path), None) => format!("{} was found but does not run", path),
+        (None, None) => MKVTOOLNIX_MISSING.to_string(),
+    };
+    if let Some(configured) = &configured_path {
+        if mkvtoolnix_tool_in(configured, "mkvmerge").is_none() {
+            message = format!("mkvmerge not found in {}; {}", configured, message);
+        }
+    }
+
+    Ok(MkvToolNixStatus {
+        available,
+        mkvmerge_path,
+        mkvmerge_version,
+        mkvpropedit_path: resolve_mkvpropedit_path(),
+        configured_path,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_encoder_and_hwaccel_listings() {
+        let encoders = "Encoders:\n V..... = Video\n ------\n\
+            V....D libx264              libx264 H.264\n\
+            V....D h264_nvenc           NVIDIA NVENC\n\
+            A....D aac                  AAC\n";
+        assert_eq!(
+            parse_listing_names(encoders, Some('V')),
+            vec!["libx264", "h264_nvenc"]
+        );
+
+        let filters = "Filters:\n  T.. = Timeline support\n  ------\n\
+            ... ass               V->V       Render ASS subtitles\n";
+        assert_eq!(parse_listing_names(filters, None), vec!["ass"]);
+
+        assert_eq!(
+            parse_hwaccels("Hardware acceleration methods:\ncuda\nvaapi\n\n"),
+            vec!["cuda", "vaapi"]
+        );
+    }
+}
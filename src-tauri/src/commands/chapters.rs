@@ -0,0 +1,271 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::call_llm_api;
+use crate::utils::*;
+use std::fs;
+use tauri::AppHandle;
+
+fn parse_ffprobe_chapters(json: &serde_json::Value) -> Vec<ChapterInfo> {
+    let Some(chapters) = json["chapters"].as_array() else {
+        return Vec::new();
+    };
+
+    chapters
+        .iter()
+        .enumerate()
+        .map(|(index, chapter)| ChapterInfo {
+            index: index as u32,
+            start: chapter["start_time"]
+                .as_str()
+                .and_then(|t| t.parse::<f64>().ok())
+                .unwrap_or(0.0),
+            end: chapter["end_time"]
+                .as_str()
+                .and_then(|t| t.parse::<f64>().ok())
+                .unwrap_or(0.0),
+            title: chapter["tags"]["title"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+        })
+        .collect()
+}
+
+fn format_chapter_time(seconds: f64) -> String {
+    let total_nanos = (seconds.max(0.0) * 1_000_000_000.0).round() as u64;
+    let nanos = total_nanos % 1_000_000_000;
+    let total_secs = total_nanos / 1_000_000_000;
+    format!(
+        "{:02}:{:02}:{:02}.{:09}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60,
+        nanos
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn build_chapters_xml(chapters: &[ChapterInfo], language: &str) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE Chapters SYSTEM \"matroskachapters.dtd\">\n\
+         <Chapters>\n  <EditionEntry>\n",
+    );
+
+    for chapter in chapters {
+        xml.push_str("    <ChapterAtom>\n");
+        xml.push_str(&format!(
+            "      <ChapterTimeStart>{}</ChapterTimeStart>\n",
+            format_chapter_time(chapter.start)
+        ));
+        if chapter.end > chapter.start {
+            xml.push_str(&format!(
+                "      <ChapterTimeEnd>{}</ChapterTimeEnd>\n",
+                format_chapter_time(chapter.end)
+            ));
+        }
+        xml.push_str("      <ChapterDisplay>\n");
+        xml.push_str(&format!(
+            "        <ChapterString>{}</ChapterString>\n",
+            escape_xml(&chapter.title)
+        ));
+        xml.push_str(&format!(
+            "        <ChapterLanguage>{}</ChapterLanguage>\n",
+            language
+        ));
+        xml.push_str("      </ChapterDisplay>\n");
+        xml.push_str("    </ChapterAtom>\n");
+    }
+
+    xml.push_str("  </EditionEntry>\n</Chapters>\n");
+    xml
+}
+
+#[tauri::command]
+pub async fn list_chapters(
+    video_path: String,
+    ffmpeg_path: Option<String>,
+) -> Result<Vec<ChapterInfo>, AnimeSubsError> {
+    let ffprobe = get_ffprobe_path(ffmpeg_path);
+
+    let output = create_command(&ffprobe)
+        .args(["-v", "quiet", "-print_format", "json", "-show_chapters"])
+        .arg(ffmpeg_file_arg(&video_path))
+        .output()
+        .map_err(|e| {
+            AnimeSubsError::from_tool(
+                &e,
+                format!("Failed to run ffprobe: {}. Is FFmpeg installed?", e),
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse ffprobe output: {}", e)))?;
+
+    Ok(parse_ffprobe_chapters(&json))
+}
+
+#[tauri::command]
+pub async fn translate_chapters(
+    app: AppHandle,
+    chapters: Vec<ChapterInfo>,
+    mut config: LLMConfig,
+    source_lang: String,
+    target_lang: String,
+) -> Result<Vec<ChapterInfo>, AnimeSubsError> {
+    super::settings::apply_saved_llm_defaults(&mut config);
+    super::prompts::resolve_prompt_template(&app, &mut config)?;
+    let lines: Vec<TranslationLine> = chapters
+        .iter()
+        .filter(|c| !c.title.trim().is_empty())
+        .map(|c| TranslationLine {
+            id: c.index as usize,
+            text: c.title.clone(),
+            reference: None,
+            draft: None,
+            rejected: None,
+        })
+        .collect();
+
+    if lines.is_empty() {
+        return Err("No chapter titles to translate".into());
+    }
+
+    let translations = call_llm_api(&config, &lines, &source_lang, &target_lang).await?;
+
+    let mut translated = chapters;
+    for chapter in &mut translated {
+        if let Some(t) = translations.iter().find(|t| t.id == chapter.index as usize) {
+            chapter.title = t.text.trim().to_string();
+        }
+    }
+
+    Ok(translated)
+}
+
+#[tauri::command]
+pub async fn write_chapters(
+    video_path: String,
+    chapters: Vec<ChapterInfo>,
+    language: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    if chapters.is_empty() {
+        return Err("No chapters to write".into());
+    }
+
+    let mkvpropedit = resolve_mkvpropedit_path()
+        .ok_or("mkvpropedit not found. Please install MKVToolNix to edit chapters.")?;
+
+    let lang_code = super::translation::to_ffmpeg_lang_code(language.as_deref());
+    let xml_path = build_temp_subtitle_path(&video_path, "chapters", "xml")?;
+    write_utf8_file(
+        &xml_path.to_string_lossy(),
+        &build_chapters_xml(&chapters, &lang_code),
+        false,
+    )?;
+
+    let result = create_command(&mkvpropedit)
+        .arg(mkvtoolnix_file_arg(&video_path))
+        .arg("--chapters")
+        .arg(mkvtoolnix_file_arg(&xml_path))
+        .output();
+
+    let _ = fs::remove_file(&xml_path);
+
+    let result = result
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run mkvpropedit: {}", e)))?;
+
+    if result.status.success() {
+        Ok(OperationResult {
+            success: true,
+            message: format!("Wrote {} chapters", chapters.len()),
+            data: None,
+            verification: None,
+        })
+    } else {
+        Ok(OperationResult {
+            success: false,
+            message: String::from_utf8_lossy(&result.stdout).to_string(),
+            data: None,
+            verification: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(index: u32, start: f64, end: f64, title: &str) -> ChapterInfo {
+        ChapterInfo {
+            index,
+            start,
+            end,
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_ffprobe_chapter_titles_and_times() {
+        let json = serde_json::json!({
+            "chapters": [
+                {"start_time": "0.000000", "end_time": "90.500000", "tags": {"title": "Avant"}},
+                {"start_time": "90.500000", "end_time": "600.000000", "tags": {}}
+            ]
+        });
+
+        let chapters = parse_ffprobe_chapters(&json);
+
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "Avant");
+        assert_eq!(chapters[0].end, 90.5);
+        assert_eq!(chapters[1].index, 1);
+        assert_eq!(chapters[1].title, "");
+        assert!(parse_ffprobe_chapters(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn formats_matroska_chapter_timestamps() {
+        assert_eq!(format_chapter_time(0.0), "00:00:00.000000000");
+        assert_eq!(format_chapter_time(3725.25), "01:02:05.250000000");
+    }
+
+    #[test]
+    fn builds_escaped_chapter_xml() {
+        let xml = build_chapters_xml(
+            &[
+                chapter(0, 0.0, 90.0, "Part A"),
+                chapter(1, 90.0, 0.0, "Tom & Jerry <3"),
+            ],
+            "por",
+        );
+
+        assert!(xml.contains("<ChapterString>Part A</ChapterString>"));
+        assert!(xml.contains("<ChapterTimeEnd>00:01:30.000000000</ChapterTimeEnd>"));
+        assert!(xml.contains("<ChapterString>Tom &amp; Jerry &lt;3</ChapterString>"));
+        assert!(xml.contains("<ChapterLanguage>por</ChapterLanguage>"));
+        assert_eq!(xml.matches("<ChapterTimeEnd>").count(), 1);
+    }
+}
@@ -0,0 +1,304 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use regex::Regex;
+use std::sync::LazyLock;
+
+const DEFAULT_MERGE_TOLERANCE_MS: u64 = 100;
+const DEFAULT_MIN_DURATION_MS: u64 = 1000;
+const DEFAULT_MIN_GAP_MS: u64 = 84;
+/// Half a millisecond, so gaps created by earlier passes aren't "fixed" again.
+const EPSILON: f64 = 0.0005;
+
+static SDH_BRACKETS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[[^\]]*\]").unwrap());
+static SDH_PARENTHESES_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\([^)]*\)").unwrap());
+static SDH_SPEAKER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\s*[-–—]?\s*)\p{Lu}[\p{Lu}\d .'#-]{0,30}:\s*").unwrap());
+
+fn strip_sdh_text(text: &str, rules: &SdhOptions) -> String {
+    let mut text = text.to_string();
+    if rules.brackets {
+        text = SDH_BRACKETS_RE.replace_all(&text, "").to_string();
+    }
+    if rules.parentheticals {
+        text = SDH_PARENTHESES_RE.replace_all(&text, "").to_string();
+    }
+
+    let rows: Vec<String> = text
+        .lines()
+        .map(|row| {
+            if rules.speaker_labels {
+                SDH_SPEAKER_RE.replace(row, "$1").to_string()
+            } else {
+                row.to_string()
+            }
+        })
+        .map(|row| row.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|row| !row.trim_start_matches(['-', '–', '—']).trim().is_empty())
+        .collect();
+
+    // A lone "- line" left over from a two-speaker cue no longer needs its dash.
+    match rows.as_slice() {
+        [single] => single
+            .trim_start_matches(['-', '–', '—'])
+            .trim()
+            .to_string(),
+        _ => rows.join("\n"),
+    }
+}
+
+/// Removes SDH artifacts and drops lines left empty; returns how many lines changed.
+/// Skipped lines (signs, songs) are kept as they are.
+pub(crate) fn strip_sdh_lines(
+    lines: Vec<DialogLine>,
+    rules: &SdhOptions,
+) -> (Vec<DialogLine>, usize) {
+    let mut changed = 0;
+    let lines = lines
+        .into_iter()
+        .filter_map(|mut line| {
+            if line.skip {
+                return Some(line);
+            }
+            let stripped = strip_sdh_text(&line.text, rules);
+            if stripped != line.text {
+                changed += 1;
+                line.text = stripped;
+            }
+            (!line.text.is_empty()).then_some(line)
+        })
+        .enumerate()
+        .map(|(index, mut line)| {
+            line.index = index;
+            line
+        })
+        .collect();
+    (lines, changed)
+}
+
+struct TimedLine {
+    line: DialogLine,
+    start: f64,
+    end: f64,
+}
+
+fn merge_identical(cues: Vec<TimedLine>, tolerance: f64) -> (Vec<TimedLine>, usize) {
+    let mut merged: Vec<TimedLine> = Vec::with_capacity(cues.len());
+    let mut count = 0;
+    for cue in cues {
+        if let Some(previous) = merged.last_mut() {
+            if previous.line.text.trim() == cue.line.text.trim()
+                && previous.line.style == cue.line.style
+                && cue.start - previous.end <= tolerance
+            {
+                previous.end = previous.end.max(cue.end);
+                count += 1;
+                continue;
+            }
+        }
+        merged.push(cue);
+    }
+    (merged, count)
+}
+
+fn extend_short(cues: &mut [TimedLine], min_duration: f64, min_gap: f64) -> usize {
+    let mut count = 0;
+    for i in 0..cues.len() {
+        if cues[i].end - cues[i].start >= min_duration {
+            continue;
+        }
+        let limit = cues
+            .get(i + 1)
+            .map_or(f64::MAX, |next| next.start - min_gap);
+        let new_end = (cues[i].start + min_duration).min(limit);
+        if new_end > cues[i].end {
+            cues[i].end = new_end;
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Pulls cue ends back so consecutive cues are at least `min_gap` apart. Overlaps
+/// longer than the gap are left alone since those are usually simultaneous speakers.
+fn enforce_gaps(cues: &mut [TimedLine], min_gap: f64) -> usize {
+    let mut count = 0;
+    for i in 1..cues.len() {
+        let next_start = cues[i].start;
+        let current = &mut cues[i - 1];
+        let gap = next_start - current.end;
+        if gap < min_gap - EPSILON && gap > -min_gap && next_start - min_gap > current.start {
+            current.end = next_start - min_gap;
+            count += 1;
+        }
+    }
+    count
+}
+
+pub(crate) fn clean_lines(
+    lines: Vec<DialogLine>,
+    options: &CleanupOptions,
+) -> Result<(Vec<DialogLine>, usize, usize, usize), AnimeSubsError> {
+    let ms = |value: Option<u64>, default: u64| value.unwrap_or(default) as f64 / 1000.0;
+    let min_gap = if options.enforce_gaps {
+        ms(options.min_gap_ms, DEFAULT_MIN_GAP_MS)
+    } else {
+        0.0
+    };
+
+    let mut cues = lines
+        .into_iter()
+        .map(|line| {
+            let start = parse_subtitle_timestamp(&line.start);
+            let end = parse_subtitle_timestamp(&line.end);
+            match (start, end) {
+                (Some(start), Some(end)) => Ok(TimedLine { line, start, end }),
+                _ => Err(AnimeSubsError::parse_at(
+                    line.index,
+                    format!("Invalid timestamps {} --> {}", line.start, line.end),
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>, AnimeSubsError>>()?;
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let mut merged = 0;
+    if options.merge_identical {
+        let tolerance = ms(options.merge_tolerance_ms, DEFAULT_MERGE_TOLERANCE_MS);
+        (cues, merged) = merge_identical(cues, tolerance);
+    }
+    let extended = if options.extend_short {
+        extend_short(
+            &mut cues,
+            ms(options.min_duration_ms, DEFAULT_MIN_DURATION_MS),
+            min_gap,
+        )
+    } else {
+        0
+    };
+    let gaps_fixed = if options.enforce_gaps {
+        enforce_gaps(&mut cues, min_gap)
+    } else {
+        0
+    };
+
+    let lines = cues
+        .into_iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            let mut line = cue.line;
+            line.index = index;
+            line.start = format_subtitle_timestamp_like(&line.start, cue.start);
+            line.end = format_subtitle_timestamp_like(&line.end, cue.end);
+            line
+        })
+        .collect();
+    Ok((lines, merged, extended, gaps_fixed))
+}
+
+/// Optionally strips SDH artifacts, then merges repeated cues, lengthens flashes
+/// that are too short to read and keeps a minimum gap between cues, depending on
+/// which fixes `options` enables.
+#[tauri::command]
+pub async fn clean_subtitle(
+    mut subtitle_data: SubtitleData,
+    options: CleanupOptions,
+) -> Result<CleanupResult, AnimeSubsError> {
+    let (lines, sdh_cleaned) = match &options.strip_sdh {
+        Some(rules) => strip_sdh_lines(subtitle_data.lines, rules),
+        None => (subtitle_data.lines, 0),
+    };
+    let (lines, merged, extended, gaps_fixed) = clean_lines(lines, &options)?;
+    subtitle_data.line_count = lines.len();
+    subtitle_data.class_counts = LineClassCounts::from_lines(&lines);
+    subtitle_data.lines = lines;
+
+    Ok(CleanupResult {
+        subtitle_data,
+        merged,
+        extended,
+        gaps_fixed,
+        sdh_cleaned,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(index: usize, start: &str, end: &str, text: &str) -> DialogLine {
+        DialogLine {
+            index,
+            text: text.to_string(),
+            original_with_formatting: text.to_string(),
+            start: start.to_string(),
+            end: end.to_string(),
+            style: None,
+            name: None,
+            reference: None,
+            formatting: None,
+            event: AssEventKind::Dialogue,
+            classification: LineClass::Dialogue,
+            skip: false,
+        }
+    }
+
+    #[test]
+    fn merges_extends_and_separates_cues() {
+        let lines = vec![
+            line(0, "00:00:01,000", "00:00:02,000", "Wait!"),
+            line(1, "00:00:02,050", "00:00:03,000", "Wait!"),
+            line(2, "00:00:03,050", "00:00:03,300", "Huh?"),
+            line(3, "00:00:04,000", "00:00:06,000", "Over here."),
+        ];
+        let options = CleanupOptions {
+            merge_identical: true,
+            extend_short: true,
+            enforce_gaps: true,
+            ..Default::default()
+        };
+
+        let (cleaned, merged, extended, gaps_fixed) = clean_lines(lines, &options).unwrap();
+        let times: Vec<(&str, &str)> = cleaned
+            .iter()
+            .map(|l| (l.start.as_str(), l.end.as_str()))
+            .collect();
+
+        assert_eq!((merged, extended, gaps_fixed), (1, 1, 1));
+        assert_eq!(
+            times,
+            vec![
+                ("00:00:01,000", "00:00:02,966"),
+                ("00:00:03,050", "00:00:03,916"),
+                ("00:00:04,000", "00:00:06,000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_sdh_rules_independently() {
+        let all = SdhOptions {
+            brackets: true,
+            parentheticals: true,
+            speaker_labels: true,
+        };
+        assert_eq!(
+            strip_sdh_text("[door slams]\nJOHN: Who's there?", &all),
+            "Who's there?"
+        );
+        assert_eq!(
+            strip_sdh_text("- MARY: Run! (gasps)\n- DR. KATO: Wait.", &all),
+            "- Run!\n- Wait."
+        );
+        assert_eq!(strip_sdh_text("(laughs)", &all), "");
+
+        let brackets_only = SdhOptions {
+            brackets: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            strip_sdh_text("JOHN: [sighs] Fine (really).", &brackets_only),
+            "JOHN: Fine (really)."
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::http::http_client;
+use crate::providers::{parse_translation_response_content, request_llm_content};
+use reqwest::StatusCode;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const PROBE_SOURCE_LANG: &str = "en";
+const PROBE_LINES: [&str; 2] = ["Good morning, everyone.", "Thank you for waiting."];
+
+fn probe_lines() -> Vec<TranslationLine> {
+    PROBE_LINES
+        .iter()
+        .enumerate()
+        .map(|(id, text)| TranslationLine {
+            id,
+            text: text.to_string(),
+            reference: None,
+            draft: None,
+            rejected: None,
+        })
+        .collect()
+}
+
+/// Sorts a failed probe by what the user has to fix. Providers word missing
+/// models and unreachable hosts differently, so those are read from the message;
+/// a 404 that names no model means the endpoint path is wrong.
+fn error_kind(error: &AnimeSubsError) -> ConnectionErrorKind {
+    match error {
+        AnimeSubsError::ProviderAuth(_) => ConnectionErrorKind::BadKey,
+        AnimeSubsError::RateLimited { .. } => ConnectionErrorKind::RateLimited,
+        AnimeSubsError::Timeout(_) => ConnectionErrorKind::Unreachable,
+        AnimeSubsError::ParseError { .. } => ConnectionErrorKind::BadResponse,
+        AnimeSubsError::Provider(message) => {
+            let message = message.to_ascii_lowercase();
+            let not_found = message.contains("(404")
+                || ["not found", "does not exist", "no such"]
+                    .iter()
+                    .any(|phrase| message.contains(phrase));
+            if not_found && message.contains("model") {
+                ConnectionErrorKind::ModelMissing
+            } else if not_found
+                || ["error sending request", "connection refused", "dns error"]
+                    .iter()
+                    .any(|phrase| message.contains(phrase))
+            {
+                ConnectionErrorKind::Unreachable
+            } else {
+                ConnectionErrorKind::Other
+            }
+        }
+        _ => ConnectionErrorKind::Other,
+    }
+}
+
+fn build_report(
+    model: &str,
+    latency_ms: u64,
+    content: Result<String, AnimeSubsError>,
+) -> LlmConnectionReport {
+    let failed = |error: AnimeSubsError| LlmConnectionReport {
+        success: false,
+        latency_ms,
+        json_mode: false,
+        auth_error: matches!(error, AnimeSubsError::ProviderAuth(_)),
+        message: error.to_string(),
+        translations: Vec::new(),
+        model: model.to_string(),
+        error_kind: Some(error_kind(&error)),
+    };
+
+    let content = match content {
+        Ok(content) => content,
+        Err(e) => return failed(e),
+    };
+    let json_mode = serde_json::from_str::<TranslationResponse>(content.trim()).is_ok();
+    let translations = match parse_translation_response_content(&content) {
+        Ok(translations) => translations,
+        Err(e) => return failed(e),
+    };
+
+    let missing: Vec<usize> = (0..PROBE_LINES.len())
+        .filter(|id| {
+            !translations
+                .iter()
+                .any(|t| t.id == *id && !t.text.trim().is_empty())
+        })
+        .collect();
+    let message = if missing.is_empty() {
+        format!("Connection OK ({} ms)", latency_ms)
+    } else {
+        format!("Response is missing line ids {:?}", missing)
+    };
+
+    LlmConnectionReport {
+        success: missing.is_empty(),
+        latency_ms,
+        json_mode,
+        auth_error: false,
+        message,
+        translations,
+        model: model.to_string(),
+        error_kind: (!missing.is_empty()).then_some(ConnectionErrorKind::BadResponse),
+    }
+}
+
+/// Sends a tiny two-line batch through the configured provider so settings can be
+/// checked before a long job.
+#[tauri::command]
+pub async fn test_llm_connection(
+    app: AppHandle,
+    mut config: LLMConfig,
+    target_lang: String,
+) -> Result<LlmConnectionReport, AnimeSubsError> {
+    super::settings::apply_saved_llm_defaults(&mut config);
+    super::prompts::resolve_prompt_template(&app, &mut config)?;
+    let started = Instant::now();
+    let content =
+        request_llm_content(&config, &probe_lines(), PROBE_SOURCE_LANG, &target_lang).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(build_report(&config.model, latency_ms, content))
+}
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Providers served from the user's machine, checked for a running server before a job.
+pub(crate) fn is_local_provider(provider: &str) -> bool {
+    matches!(provider, "ollama" | "lmstudio" | "llamacpp" | "vllm")
+}
+
+/// Server health endpoint, which llama.cpp and vLLM expose next to `/v1` rather than in it.
+fn health_url(provider: &str, endpoint: &str) -> Option<String> {
+    if !matches!(provider, "llamacpp" | "vllm") {
+        return None;
+    }
+    let base = endpoint
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches("/chat/completions")
+        .trim_end_matches("/v1");
+    Some(format!("{}/health", base))
+}
+
+fn model_available(provider: &str, model: &str, models: &[String]) -> Option<bool> {
+    let model = model.trim();
+    // llama.cpp answers with whatever model it was started with, whatever the request names.
+    if provider == "llamacpp" || model.is_empty() {
+        return None;
+    }
+    Some(
+        models
+            .iter()
+            .any(|m| m == model || (provider == "ollama" && *m == format!("{}:latest", model))),
+    )
+}
+
+fn health_report(
+    provider: &str,
+    model: &str,
+    latency_ms: u64,
+    listing: Result<Vec<ProviderModel>, AnimeSubsError>,
+) -> ProviderHealth {
+    let models: Vec<String> = match listing {
+        Ok(models) => models.into_iter().map(|m| m.id).collect(),
+        Err(e) => {
+            return ProviderHealth {
+                reachable: false,
+                latency_ms,
+                models: Vec::new(),
+                model_available: None,
+                message: e.to_string(),
+            }
+        }
+    };
+    let model_available = model_available(provider, model, &models);
+    let message = if model_available == Some(false) {
+        format!(
+            "Model '{}' is not served by {}; available: {}",
+            model.trim(),
+            provider,
+            models.join(", ")
+        )
+    } else {
+        format!("Server OK ({} models, {} ms)", models.len(), latency_ms)
+    };
+
+    ProviderHealth {
+        reachable: true,
+        latency_ms,
+        models,
+        model_available,
+        message,
+    }
+}
+
+/// Probes the provider's health endpoint and `/v1/models` listing without spending tokens.
+#[tauri::command]
+pub async fn check_provider_health(
+    provider: String,
+    endpoint: String,
+    api_key: Option<String>,
+    model: Option<String>,
+) -> Result<ProviderHealth, AnimeSubsError> {
+    let provider = provider.trim().to_ascii_lowercase();
+    let started = Instant::now();
+
+    if let Some(url) = health_url(&provider, &endpoint) {
+        let response = http_client()?
+            .get(&url)
+            .timeout(HEALTH_TIMEOUT)
+            .send()
+            .await;
+        let failure = match response {
+            // llama.cpp answers 503 until the model has finished loading.
+            Ok(r) if r.status() == StatusCode::SERVICE_UNAVAILABLE => {
+                Some("Server is still loading the model".to_string())
+            }
+            Err(e) => Some(format!("Server not reachable at {}: {}", url, e)),
+            // Builds or proxies without /health fall through to the model listing.
+            Ok(_) => None,
+        };
+        if let Some(message) = failure {
+            return Ok(ProviderHealth {
+                reachable: false,
+                latency_ms: started.elapsed().as_millis() as u64,
+                models: Vec::new(),
+                model_available: None,
+                message,
+            });
+        }
+    }
+
+    let listing = super::utils::list_models(provider.clone(), endpoint, api_key).await;
+    Ok(health_report(
+        &provider,
+        model.as_deref().unwrap_or_default(),
+        started.elapsed().as_millis() as u64,
+        listing,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_json_mode_missing_lines_and_auth_errors() {
+        let strict = r#"{"translations":[{"id":0,"text":"Bom dia."},{"id":1,"text":"Obrigado."}]}"#;
+        let report = build_report("gpt-4o-mini", 120, Ok(strict.to_string()));
+        assert!(report.success && report.json_mode);
+        assert_eq!(report.model, "gpt-4o-mini");
+        assert_eq!(report.error_kind, None);
+
+        let fenced = "```json\n{\"translations\":[{\"id\":0,\"text\":\"Bom dia.\"}]}\n```";
+        let report = build_report("gpt-4o-mini", 120, Ok(fenced.to_string()));
+        assert!(!report.success && !report.json_mode);
+        assert_eq!(report.message, "Response is missing line ids [1]");
+        assert_eq!(report.error_kind, Some(ConnectionErrorKind::BadResponse));
+
+        let report = build_report(
+            "gpt-4o-mini",
+            40,
+            Err(AnimeSubsError::ProviderAuth(
+                "LLM API error (401 Unauthorized): invalid key".to_string(),
+            )),
+        );
+        assert!(report.auth_error && !report.success);
+        assert_eq!(report.error_kind, Some(ConnectionErrorKind::BadKey));
+    }
+
+    #[test]
+    fn sorts_probe_failures_by_what_to_fix() {
+        let kind = |message: &str| error_kind(&AnimeSubsError::Provider(message.to_string()));
+        assert_eq!(
+            kind("LLM API error (404 Not Found): {\"error\":\"model 'qwen3' not found\"}"),
+            ConnectionErrorKind::ModelMissing
+        );
+        assert_eq!(
+            kind("LLM API error (400 Bad Request): The model `gpt-9` does not exist"),
+            ConnectionErrorKind::ModelMissing
+        );
+        assert_eq!(
+            kind("Failed to send request: error sending request for url (http://localhost:11434/api/chat)"),
+            ConnectionErrorKind::Unreachable
+        );
+        assert_eq!(
+            kind("LLM API error (500 Internal Server Error): overloaded"),
+            ConnectionErrorKind::Other
+        );
+        assert_eq!(
+            error_kind(&AnimeSubsError::Timeout("Request timed out".to_string())),
+            ConnectionErrorKind::Unreachable
+        );
+    }
+
+    #[test]
+    fn probes_health_outside_v1_and_checks_served_models() {
+        assert_eq!(
+            health_url("llamacpp", "http://localhost:8080/v1/").as_deref(),
+            Some("http://localhost:8080/health")
+        );
+        assert_eq!(
+            health_url("vllm", "http://gpu-box:8000/v1/chat/completions").as_deref(),
+            Some("http://gpu-box:8000/health")
+        );
+        assert_eq!(health_url("openai", "https://api.openai.com/v1"), None);
+
+        let listing = |ids: &[&str]| -> Result<Vec<ProviderModel>, AnimeSubsError> {
+            Ok(ids
+                .iter()
+                .map(|id| ProviderModel {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    context_length: None,
+                })
+                .collect())
+        };
+        let report = health_report("vllm", "Qwen/Qwen2.5-7B", 12, listing(&["Qwen/Qwen2.5-7B"]));
+        assert!(report.reachable && report.model_available == Some(true));
+
+        let report = health_report("vllm", "llama3", 12, listing(&["Qwen/Qwen2.5-7B"]));
+        assert_eq!(report.model_available, Some(false));
+        assert!(report.message.contains("available: Qwen/Qwen2.5-7B"));
+
+        assert_eq!(
+            health_report("ollama", "llama3", 5, listing(&["llama3:latest"])).model_available,
+            Some(true)
+        );
+        assert_eq!(
+            health_report("llamacpp", "anything", 5, listing(&["model.gguf"])).model_available,
+            None
+        );
+        assert!(!health_report("lmstudio", "m", 5, Err("refused".into())).reachable);
+    }
+}
@@ -0,0 +1,506 @@
+use super::styles::parse_ass_styles;
+use super::subtitle::{parse_subtitle_content, FONT_COLOR_RE};
+use super::translation::{apply_srt_formatting, reconstruct_subtitle_content};
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+const ASS_TIME_TEMPLATE: &str = "0:00:00.00";
+const SRT_TIME_TEMPLATE: &str = "00:00:00,000";
+const VTT_TIME_TEMPLATE: &str = "00:00:00.000";
+
+const DEFAULT_ASS_HEADER: &str = concat!(
+    "[Script Info]\n",
+    "ScriptType: v4.00+\n",
+    "PlayResX: 1920\n",
+    "PlayResY: 1080\n",
+    "WrapStyle: 0\n",
+    "ScaledBorderAndShadow: yes\n",
+    "\n",
+    "[V4+ Styles]\n",
+    "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, ",
+    "BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, ",
+    "BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n",
+    "Style: Default,Arial,72,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,",
+    "0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1\n",
+    "\n",
+    "[Events]\n",
+    "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n",
+);
+
+static ASS_OVERRIDE_BLOCK_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{[^}]*\}").unwrap());
+static MARKUP_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(/?)([A-Za-z]*)([^>]*)>").unwrap());
+
+/// One cue in format-neutral form: seconds and SRT-style markup with `\n` breaks.
+struct Cue {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+fn normalize_format(format: &str) -> Result<&'static str, AnimeSubsError> {
+    match format.trim().to_ascii_lowercase().as_str() {
+        "ass" | "ssa" => Ok("ass"),
+        "srt" | "subrip" => Ok("srt"),
+        "vtt" | "webvtt" => Ok("vtt"),
+        other => Err(format!("Unsupported subtitle format: {}", other).into()),
+    }
+}
+
+/// `&HAABBGGRR&` / `&HBBGGRR&` to `#rrggbb`.
+fn ass_color_to_html(value: &str) -> Option<String> {
+    let hex = value
+        .trim()
+        .trim_start_matches(['&', 'H', 'h'])
+        .trim_end_matches('&');
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let (blue, green, red) = ((value >> 16) & 0xFF, (value >> 8) & 0xFF, value & 0xFF);
+    Some(format!("#{:02x}{:02x}{:02x}", red, green, blue))
+}
+
+/// `#rrggbb` to `&HBBGGRR&`; named HTML colors are not mapped.
+fn html_color_to_ass(value: &str) -> Option<String> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!(
+        "&H{}{}{}&",
+        &hex[4..6].to_ascii_uppercase(),
+        &hex[2..4].to_ascii_uppercase(),
+        &hex[0..2].to_ascii_uppercase()
+    ))
+}
+
+/// Bold/italic/underline/primary color of every style of the script.
+fn style_formatting(content: &str) -> HashMap<String, LineFormatting> {
+    parse_ass_styles(content)
+        .into_iter()
+        .map(|style| {
+            let formatting = LineFormatting {
+                italic: style.italic,
+                bold: style.bold,
+                underline: style.underline,
+                // White is the renderer default and not worth a <font> tag.
+                color: ass_color_to_html(&style.primary_colour).filter(|c| c != "#ffffff"),
+            };
+            (style.name, formatting)
+        })
+        .collect()
+}
+
+/// Applies one override tag (without its backslash) to the running state.
+fn apply_ass_override(
+    tag: &str,
+    base: &LineFormatting,
+    state: &mut LineFormatting,
+    drawing: &mut bool,
+) {
+    let tag = tag.trim().trim_end_matches(')');
+    let numeric = |prefix: char| {
+        tag.strip_prefix(prefix)
+            .and_then(|rest| rest.parse::<u32>().ok())
+    };
+
+    if let Some(value) = numeric('i') {
+        state.italic = value != 0;
+    } else if let Some(value) = numeric('b') {
+        state.bold = value != 0;
+    } else if let Some(value) = numeric('u') {
+        state.underline = value != 0;
+    } else if let Some(value) = numeric('p') {
+        *drawing = value != 0;
+    } else if let Some(color) = tag
+        .strip_prefix("1c")
+        .or_else(|| tag.strip_prefix('c'))
+        .filter(|color| color.is_empty() || color.starts_with('&'))
+    {
+        state.color = if color.is_empty() {
+            base.color.clone()
+        } else {
+            ass_color_to_html(color)
+        };
+    } else if tag == "r" || (tag.starts_with('r') && !tag.starts_with("rnd")) {
+        *state = base.clone();
+    }
+}
+
+/// ASS event text to SRT markup. Drawings (`\p1`) are dropped since they would show
+/// up as vector commands.
+fn ass_text_to_markup(text: &str, base: &LineFormatting, styling: AssDowngrade) -> String {
+    let mut runs: Vec<(LineFormatting, String)> = Vec::new();
+    let mut state = base.clone();
+    let mut drawing = false;
+    let mut cursor = 0;
+
+    let blocks = ASS_OVERRIDE_BLOCK_RE
+        .find_iter(text)
+        .map(Some)
+        .chain([None]);
+    for block in blocks {
+        let segment_end = block.map_or(text.len(), |b| b.start());
+        let segment = &text[cursor..segment_end];
+        if !drawing && !segment.is_empty() {
+            match runs.last_mut() {
+                Some((formatting, run)) if *formatting == state => run.push_str(segment),
+                _ => runs.push((state.clone(), segment.to_string())),
+            }
+        }
+
+        let Some(block) = block else { break };
+        cursor = block.end();
+        let inner = &block.as_str()[1..block.as_str().len() - 1];
+        for tag in inner.split('\\').skip(1) {
+            apply_ass_override(tag, base, &mut state, &mut drawing);
+        }
+    }
+
+    runs.into_iter()
+        .map(|(formatting, run)| {
+            let run = run
+                .replace("\\N", "\n")
+                .replace("\\n", "\n")
+                .replace("\\h", " ");
+            match styling {
+                AssDowngrade::Tags => apply_srt_formatting(&run, Some(&formatting)),
+                AssDowngrade::Strip => run,
+            }
+        })
+        .collect()
+}
+
+/// Cues of `content`, read with the subtitle parsers: ASS dialogue events with their
+/// override tags turned into SRT markup, and SRT/VTT cues with their markup kept.
+fn read_cues(
+    content: &str,
+    format: &str,
+    options: &FormatConversionOptions,
+) -> Result<Vec<Cue>, AnimeSubsError> {
+    let data = parse_subtitle_content(content, format, true)?;
+    let styles = match format {
+        "ass" => style_formatting(content),
+        _ => HashMap::new(),
+    };
+    let plain = LineFormatting::default();
+    let mut cues = Vec::new();
+
+    for line in data.lines {
+        let (Some(start), Some(end)) = (
+            parse_subtitle_timestamp(&line.start),
+            parse_subtitle_timestamp(&line.end),
+        ) else {
+            continue;
+        };
+        let text = if format == "ass" {
+            let style = line.style.as_deref().unwrap_or_default();
+            if line.event != AssEventKind::Dialogue
+                || (options.skip_signs && is_sign_or_song_style(style))
+            {
+                continue;
+            }
+            let base = styles.get(style).unwrap_or(&plain);
+            ass_text_to_markup(&line.original_with_formatting, base, options.ass_styling)
+        } else {
+            line.text
+        };
+        if !text.trim().is_empty() {
+            cues.push(Cue {
+                start,
+                end,
+                text: text.trim().to_string(),
+            });
+        }
+    }
+
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(cues)
+}
+
+/// Drops every markup tag not named in `keep` (e.g. VTT `<c>`/`<v>` or SRT `<font>`).
+fn filter_markup(text: &str, keep: &[&str]) -> String {
+    MARKUP_TAG_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            if keep.contains(&caps[2].to_ascii_lowercase().as_str()) {
+                caps[0].to_string()
+            } else {
+                String::new()
+            }
+        })
+        .to_string()
+}
+
+/// SRT markup to ASS override tags; `</font>` resets to the style color.
+fn markup_to_ass(text: &str) -> String {
+    let mut result = String::new();
+    let mut cursor = 0;
+
+    for caps in MARKUP_TAG_RE.captures_iter(text) {
+        let tag = caps.get(0).unwrap();
+        result.push_str(&text[cursor..tag.start()]);
+        cursor = tag.end();
+
+        let closing = !caps[1].is_empty();
+        match caps[2].to_ascii_lowercase().as_str() {
+            name @ ("i" | "b" | "u") => {
+                result.push_str(&format!("{{\\{}{}}}", name, if closing { 0 } else { 1 }));
+            }
+            "font" if closing => result.push_str("{\\c}"),
+            "font" => {
+                let color = FONT_COLOR_RE
+                    .captures(&caps[3])
+                    .and_then(|c| html_color_to_ass(&c[1]));
+                if let Some(color) = color {
+                    result.push_str(&format!("{{\\c{}}}", color));
+                }
+            }
+            _ => {}
+        }
+    }
+    result.push_str(&text[cursor..]);
+
+    result.replace("}{", "").replace('\n', "\\N")
+}
+
+/// Writes `cues` as `format` through `reconstruct_subtitle_content`, keeping only
+/// the markup the format supports.
+fn write_cues(cues: &[Cue], format: &str) -> Result<String, AnimeSubsError> {
+    let time_template = match format {
+        "ass" => ASS_TIME_TEMPLATE,
+        "srt" => SRT_TIME_TEMPLATE,
+        _ => VTT_TIME_TEMPLATE,
+    };
+    let lines: Vec<DialogLine> = cues
+        .iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            let text = match format {
+                "ass" => markup_to_ass(&cue.text),
+                "srt" => filter_markup(&cue.text, &["i", "b", "u", "font"]),
+                _ => filter_markup(&cue.text, &["i", "b", "u"]),
+            };
+            DialogLine {
+                index,
+                original_with_formatting: text.clone(),
+                text,
+                start: format_subtitle_timestamp_like(time_template, cue.start),
+                end: format_subtitle_timestamp_like(time_template, cue.end),
+                style: None,
+                name: None,
+                reference: None,
+                formatting: None,
+                event: AssEventKind::Dialogue,
+                classification: LineClass::Dialogue,
+                skip: false,
+            }
+        })
+        .collect();
+    let data = SubtitleData {
+        format: format.to_string(),
+        line_count: lines.len(),
+        class_counts: LineClassCounts::from_lines(&lines),
+        deduplicated_lines: 0,
+        lines,
+        source_path: String::new(),
+        ass_header: Some(DEFAULT_ASS_HEADER.trim_end().to_string()),
+        failed_line_ids: Vec::new(),
+        batch_providers: Vec::new(),
+    };
+    reconstruct_subtitle_content(&data, None)
+}
+
+/// Converts subtitle text between formats. Unlike the translation parsers, no line is
+/// filtered out as music or too short; only ASS drawings and, when asked, sign styles.
+pub(crate) fn convert_subtitle_content(
+    content: &str,
+    source_format: &str,
+    target_format: &str,
+    options: &FormatConversionOptions,
+) -> Result<String, AnimeSubsError> {
+    let source = normalize_format(source_format)?;
+    let target = normalize_format(target_format)?;
+    if source == target {
+        return Ok(content.to_string());
+    }
+
+    let cues = read_cues(content, source, options)?;
+    write_cues(&cues, target)
+}
+
+/// `<stem>.<format>` next to the source, or `<stem>_converted.<format>` (then
+/// `_converted_2`, ...) when that is the source itself or already exists.
+fn converted_output_path(subtitle_path: &str, format: &str) -> String {
+    let path = Path::new(subtitle_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "subtitle".to_string());
+    let candidates = std::iter::once(path.with_extension(format)).chain((1..).map(|n| {
+        let suffix = if n == 1 {
+            String::new()
+        } else {
+            format!("_{}", n)
+        };
+        path.with_file_name(format!("{}_converted{}.{}", stem, suffix, format))
+    }));
+    candidates
+        .find(|candidate| candidate != path && !candidate.exists())
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Converts an ASS/SRT/VTT file to `target_format` without translating it and writes
+/// the result to `output_path` (default `<stem>.<format>` next to the source, under
+/// a new name when that file exists).
+#[tauri::command]
+pub async fn convert_subtitle_format(
+    subtitle_path: String,
+    target_format: String,
+    output_path: Option<String>,
+    options: Option<FormatConversionOptions>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let options = options.unwrap_or_default();
+    let content = read_file_as_utf8(&subtitle_path)?;
+    let source_format = Path::new(&subtitle_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let converted = convert_subtitle_content(&content, &source_format, &target_format, &options)?;
+    let output = output_path.filter(|p| !p.is_empty()).unwrap_or_else(|| {
+        let format = normalize_format(&target_format).unwrap_or("srt");
+        converted_output_path(&subtitle_path, format)
+    });
+    write_subtitle_file(
+        &output,
+        &converted,
+        &options.output_encoding.unwrap_or_default(),
+        false,
+    )?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Subtitle converted to {}", target_format.to_uppercase()),
+        data: Some(output),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASS: &str = r#"[Script Info]
+Title: Example
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, Bold, Italic, Encoding
+Style: Default,Arial,20,&H00FFFFFF,0,0,1
+Style: Thoughts,Arial,20,&H00FFFFFF,0,-1,1
+Style: Signs,Arial,20,&H00FFFFFF,0,0,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,{\b1}Run{\b0}, {\c&H0000FF&}now{\c}!
+Dialogue: 0,0:00:01.00,0:00:02.50,Thoughts,,0,0,0,,Where am I...\Nagain?
+Dialogue: 0,0:00:05.00,0:00:06.00,Signs,,0,0,0,,{\pos(10,10)}Bakery
+Dialogue: 0,0:00:05.00,0:00:06.00,Signs,,0,0,0,,{\p1}m 0 0 l 10 0 10 10{\p0}
+"#;
+
+    #[test]
+    fn converts_ass_to_srt_with_style_and_override_tags() {
+        let options = FormatConversionOptions {
+            skip_signs: true,
+            ..FormatConversionOptions::default()
+        };
+
+        let output = convert_subtitle_content(ASS, "ass", "srt", &options).unwrap();
+
+        assert!(
+            output.starts_with("1\n00:00:01,000 --> 00:00:02,500\n<i>Where am I...\nagain?</i>")
+        );
+        assert!(output.contains(
+            "2\n00:00:03,000 --> 00:00:04,000\n<b>Run</b>, <font color=\"#ff0000\">now</font>!"
+        ));
+        assert!(!output.contains("Bakery"));
+    }
+
+    #[test]
+    fn strips_ass_styling_and_drops_drawings() {
+        let options = FormatConversionOptions {
+            ass_styling: AssDowngrade::Strip,
+            ..FormatConversionOptions::default()
+        };
+
+        let output = convert_subtitle_content(ASS, "ass", "vtt", &options).unwrap();
+
+        assert!(
+            output.starts_with("WEBVTT\n\n00:00:01.000 --> 00:00:02.500\nWhere am I...\nagain?")
+        );
+        assert!(output.contains("00:00:03.000 --> 00:00:04.000\nRun, now!"));
+        assert!(output.contains("Bakery"));
+        assert!(!output.contains("m 0 0"));
+    }
+
+    #[test]
+    fn converts_srt_tags_to_ass_overrides() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:02,000\r\n<i>Hello</i>\r\n\
+                   <font color=\"#00ff00\">there</font>\r\n";
+
+        let output =
+            convert_subtitle_content(srt, "srt", "ass", &FormatConversionOptions::default())
+                .unwrap();
+
+        assert!(output.contains("[V4+ Styles]"));
+        assert!(output.contains(
+            "Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,\
+             {\\i1}Hello{\\i0}\\N{\\c&H00FF00&}there{\\c}"
+        ));
+    }
+
+    #[test]
+    fn converts_vtt_cues_to_srt_and_drops_vtt_only_tags() {
+        let vtt = "WEBVTT\n\nNOTE intro\n\n00:01.500 --> 00:03.000 align:start\n\
+                   <v Alice><c.yellow>Hi</c> <i>you</i>\n";
+
+        let output =
+            convert_subtitle_content(vtt, "webvtt", "srt", &FormatConversionOptions::default())
+                .unwrap();
+
+        assert_eq!(output, "1\n00:00:01,500 --> 00:00:03,000\nHi <i>you</i>\n");
+    }
+
+    #[test]
+    fn picks_an_unused_name_for_converted_files() {
+        let dir = std::env::temp_dir().join(format!("animesubs-convert-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("episode.ass").to_string_lossy().to_string();
+        let expected = |name: &str| dir.join(name).to_string_lossy().to_string();
+
+        assert_eq!(
+            converted_output_path(&source, "srt"),
+            expected("episode.srt")
+        );
+        assert_eq!(
+            converted_output_path(&source, "ass"),
+            expected("episode_converted.ass")
+        );
+        std::fs::write(dir.join("episode.srt"), "").unwrap();
+        assert_eq!(
+            converted_output_path(&source, "srt"),
+            expected("episode_converted.srt")
+        );
+        std::fs::write(dir.join("episode_converted.srt"), "").unwrap();
+        assert_eq!(
+            converted_output_path(&source, "srt"),
+            expected("episode_converted_2.srt")
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
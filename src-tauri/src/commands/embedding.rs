@@ -1,19 +1,260 @@
+use super::operations::{run_ffmpeg_with_progress, run_tracked_command};
+use super::temp_files::TempFileRegistry;
+use super::verify::finalize_verified_remux;
+use crate::error::AnimeSubsError;
 use crate::models::*;
 use crate::utils::*;
-use std::fs;
 use std::path::Path;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+fn embed_title(subtitle: &SubtitleEmbed) -> String {
+    subtitle
+        .title
+        .clone()
+        .unwrap_or_else(|| "Translated".to_string())
+}
 
-#[tauri::command]
-pub async fn embed_subtitle(
+fn subtitle_format(path: &str) -> String {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// How each subtitle fits `container_ext`, and the container to use when one does not.
+fn embed_compatibility(container_ext: &str, subtitle_paths: &[String]) -> EmbedCompatibilityReport {
+    let subtitles: Vec<SubtitleCompatibility> = subtitle_paths
+        .iter()
+        .map(|path| {
+            let format = subtitle_format(path);
+            let codec = subtitle_container_codec(container_ext, &format);
+            SubtitleCompatibility {
+                path: path.clone(),
+                codec: codec.map(|(codec, _)| codec.to_string()),
+                lossy: codec.is_some_and(|(_, lossy)| lossy),
+                format,
+            }
+        })
+        .collect();
+    let compatible = subtitles.iter().all(|s| s.codec.is_some() && !s.lossy);
+    EmbedCompatibilityReport {
+        container: container_ext.to_ascii_lowercase(),
+        subtitles,
+        compatible,
+        suggested_container: (!compatible).then(|| "mkv".to_string()),
+    }
+}
+
+fn incompatibility_message(report: &EmbedCompatibilityReport) -> String {
+    let problems: Vec<String> = report
+        .subtitles
+        .iter()
+        .filter(|s| s.codec.is_none() || s.lossy)
+        .map(|s| match &s.codec {
+            Some(codec) => format!("{} ({} -> {}, styling lost)", s.path, s.format, codec),
+            None => format!("{} ({} not supported)", s.path, s.format),
+        })
+        .collect();
+    format!(
+        "Subtitles not compatible with .{}: {}",
+        report.container,
+        problems.join("; ")
+    )
+}
+
+/// A subtitle track of the output, in final order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SubtitleSlot {
+    /// Index into the video's existing subtitle tracks.
+    Existing(usize),
+    /// Index into the subtitles being embedded.
+    Added(usize),
+}
+
+/// Output subtitle order: existing tracks keep their relative order, new tracks with a
+/// position are inserted there (clamped to the end) and the rest are appended.
+fn subtitle_layout(existing: usize, positions: &[Option<u32>]) -> Vec<SubtitleSlot> {
+    let mut layout: Vec<SubtitleSlot> = (0..existing).map(SubtitleSlot::Existing).collect();
+    let mut positioned: Vec<(u32, usize)> = positions
+        .iter()
+        .enumerate()
+        .filter_map(|(added, position)| position.map(|p| (p, added)))
+        .collect();
+    // Inserting in ascending order keeps earlier insertions at their requested index.
+    positioned.sort();
+    for (position, added) in positioned {
+        let at = (position as usize).min(layout.len());
+        layout.insert(at, SubtitleSlot::Added(added));
+    }
+    for (added, position) in positions.iter().enumerate() {
+        if position.is_none() {
+            layout.push(SubtitleSlot::Added(added));
+        }
+    }
+    layout
+}
+
+/// mkvmerge arguments adding every file in `subtitles` (UTF-8 path, options) in one pass.
+/// `other_track_ids` are the video/audio track ids, needed to spell out `--track-order`.
+fn mkvmerge_embed_args(
+    video_path: &str,
+    output: &str,
+    existing: &[SubtitleTrack],
+    other_track_ids: &[u32],
+    subtitles: &[(String, &SubtitleEmbed)],
+    clear_existing_defaults: bool,
+) -> Vec<String> {
+    let mut args = vec!["-o".to_string(), mkvtoolnix_file(output)];
+    if clear_existing_defaults {
+        for track in existing.iter().filter(|t| t.default) {
+            args.push("--default-track".to_string());
+            args.push(format!("{}:0", track.stream_index));
+        }
+    }
+    args.push(mkvtoolnix_file(video_path));
+
+    for (path, subtitle) in subtitles {
+        let language = subtitle.language.as_deref().unwrap_or("und");
+        args.extend([
+            "--language".to_string(),
+            format!("0:{}", tool_language(language)),
+            "--track-name".to_string(),
+            format!("0:{}", tool_metadata(&embed_title(subtitle))),
+            "--default-track".to_string(),
+            format!("0:{}", u8::from(subtitle.default)),
+        ]);
+        if subtitle.forced {
+            args.extend(["--forced-track".to_string(), "0:1".to_string()]);
+        }
+        args.push(mkvtoolnix_file(path));
+    }
+
+    let positions: Vec<Option<u32>> = subtitles.iter().map(|(_, s)| s.position).collect();
+    if positions.iter().any(Option::is_some) {
+        let order: Vec<String> =
+            other_track_ids
+                .iter()
+                .map(|id| format!("0:{}", id))
+                .chain(subtitle_layout(existing.len(), &positions).into_iter().map(
+                    |slot| match slot {
+                        SubtitleSlot::Existing(j) => format!("0:{}", existing[j].stream_index),
+                        SubtitleSlot::Added(k) => format!("{}:0", k + 1),
+                    },
+                ))
+                .collect();
+        args.extend(["--track-order".to_string(), order.join(",")]);
+    }
+    args
+}
+
+/// ffmpeg arguments adding `subtitles` (UTF-8 path, options, codec) next to the
+/// `existing` subtitle tracks of the video.
+fn ffmpeg_embed_args(
+    video_path: &str,
+    output: &str,
+    existing: &[SubtitleTrack],
+    subtitles: &[(String, &SubtitleEmbed, &str)],
+    clear_existing_defaults: bool,
+) -> Vec<String> {
+    let mut args = vec!["-i".to_string(), ffmpeg_file(video_path)];
+    for (path, _, _) in subtitles {
+        args.extend(["-i".to_string(), ffmpeg_file(path)]);
+    }
+
+    let positions: Vec<Option<u32>> = subtitles.iter().map(|(_, s, _)| s.position).collect();
+    let layout = subtitle_layout(existing.len(), &positions);
+    if positions.iter().any(Option::is_some) {
+        // Every non-subtitle stream first, then the subtitles in their new order.
+        args.extend(["-map", "0", "-map", "-0:s"].map(String::from));
+        for slot in &layout {
+            args.push("-map".to_string());
+            args.push(match slot {
+                SubtitleSlot::Existing(j) => format!("0:s:{}", j),
+                SubtitleSlot::Added(k) => format!("{}:0", k + 1),
+            });
+        }
+    } else {
+        args.extend(["-map".to_string(), "0".to_string()]);
+        for input in 1..=subtitles.len() {
+            args.extend(["-map".to_string(), format!("{}:0", input)]);
+        }
+    }
+    args.extend(["-c".to_string(), "copy".to_string()]);
+
+    let output_is_mkv = Path::new(output)
+        .extension()
+        .is_some_and(|e| is_mkv_container(&e.to_string_lossy()));
+    for (track, slot) in layout.into_iter().enumerate() {
+        let (subtitle, codec) = match slot {
+            SubtitleSlot::Added(k) => (subtitles[k].1, subtitles[k].2),
+            SubtitleSlot::Existing(j) => {
+                // MP4 timed text cannot be copied into Matroska.
+                if output_is_mkv && existing[j].codec == "mov_text" {
+                    args.push(format!("-c:s:{}", track));
+                    args.push("srt".to_string());
+                }
+                if clear_existing_defaults && existing[j].default {
+                    // Replaces the whole disposition, so keep a forced flag explicitly.
+                    let disposition = if existing[j].forced { "forced" } else { "0" };
+                    args.push(format!("-disposition:s:{}", track));
+                    args.push(disposition.to_string());
+                }
+                continue;
+            }
+        };
+        args.push(format!("-c:s:{}", track));
+        args.push(codec.to_string());
+
+        if let Some(lang) = &subtitle.language {
+            args.push(format!("-metadata:s:s:{}", track));
+            args.push(format!("language={}", tool_language(lang)));
+        }
+        args.push(format!("-metadata:s:s:{}", track));
+        args.push(format!("title={}", tool_metadata(&embed_title(subtitle))));
+
+        let disposition = match (subtitle.default, subtitle.forced) {
+            (true, true) => Some("default+forced"),
+            (true, false) => Some("default"),
+            (false, true) => Some("forced"),
+            (false, false) => None,
+        };
+        if let Some(disposition) = disposition {
+            args.push(format!("-disposition:s:{}", track));
+            args.push(disposition.to_string());
+        }
+    }
+
+    args.push("-y".to_string());
+    args.push(ffmpeg_file(output));
+    args
+}
+
+/// Muxes all `subtitles` into the video with a single mkvmerge/ffmpeg run.
+#[allow(clippy::too_many_arguments)]
+async fn embed_subtitle_files(
+    app: AppHandle,
     video_path: String,
-    subtitle_path: String,
-    language: Option<String>,
-    title: Option<String>,
-    set_default: bool,
+    subtitles: Vec<SubtitleEmbed>,
     ffmpeg_path: Option<String>,
     use_mkvmerge: Option<bool>,
-) -> Result<OperationResult, String> {
-    let ffmpeg = get_ffmpeg_path(ffmpeg_path);
+    output_policy: Option<OutputPolicy>,
+    verify_decode: Option<bool>,
+    clear_existing_defaults: bool,
+    on_incompatible: IncompatibleSubtitleAction,
+) -> Result<OperationResult, AnimeSubsError> {
+    if subtitles.is_empty() {
+        return Err("No subtitle files to embed".into());
+    }
+    if let Some(language) = subtitles
+        .iter()
+        .filter_map(|s| s.language.as_deref())
+        .find(|language| !is_valid_language_code(language))
+    {
+        return Err(format!("Invalid language code: {}", language).into());
+    }
+    let output_policy = output_policy.unwrap_or_default();
+    let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
     let mut use_mkvmerge = use_mkvmerge.unwrap_or(true);
     let mkvmerge_path = resolve_mkvmerge_path();
 
@@ -28,12 +269,41 @@ pub async fn embed_subtitle(
         .map(|s| s.to_string_lossy().to_ascii_lowercase())
         .unwrap_or_else(|| "mkv".to_string());
 
-    let temp_output = parent.join(format!("{}_with_subs.{}", stem, ext));
-
-    let (utf8_subtitle_path, temp_utf8_path) = convert_subtitle_to_utf8(&subtitle_path)?;
+    let subtitle_paths: Vec<String> = subtitles.iter().map(|s| s.path.clone()).collect();
+    let compatibility = embed_compatibility(&ext, &subtitle_paths);
+    let mut output_ext = ext.clone();
+    if !compatibility.compatible {
+        match on_incompatible {
+            // Unsupported containers still fail when the codec is resolved below.
+            IncompatibleSubtitleAction::Convert => {
+                warn!("{}; converting", incompatibility_message(&compatibility))
+            }
+            IncompatibleSubtitleAction::Mkv => output_ext = "mkv".to_string(),
+            IncompatibleSubtitleAction::Fail => {
+                return Err(incompatibility_message(&compatibility).into());
+            }
+        }
+    }
+    let converts_to_mkv = output_ext != ext;
+
+    let temp_output = parent.join(format!("{}_with_subs.{}", stem, output_ext));
+    ensure_remux_space(&video_path, &temp_output, &output_policy)?;
+
+    let mut temp_files = TempFileRegistry::new(&app);
+    temp_files.track(temp_output.clone());
+    let mut utf8_paths = Vec::with_capacity(subtitles.len());
+    for subtitle in &subtitles {
+        let (utf8_subtitle_path, temp_utf8_path) = convert_subtitle_to_utf8(&subtitle.path)?;
+        if let Some(temp_utf8_path) = temp_utf8_path {
+            temp_files.track(temp_utf8_path);
+        }
+        utf8_paths.push(utf8_subtitle_path);
+    }
+    let output = temp_output.to_string_lossy().to_string();
+    let added = subtitles.len();
 
     if use_mkvmerge && !is_mkv_container(&ext) {
-        eprintln!(
+        info!(
             "mkvmerge only supports MKV output here, falling back to ffmpeg for embedding into {}",
             ext
         );
@@ -41,141 +311,205 @@ pub async fn embed_subtitle(
     }
 
     if use_mkvmerge && mkvmerge_path.is_none() {
-        eprintln!("mkvmerge not available, falling back to ffmpeg for embedding");
+        warn!(
+            "mkvmerge not found, falling back to ffmpeg for embedding; install MKVToolNix \
+             or set its path in the settings for better ASS output"
+        );
         use_mkvmerge = false;
     }
 
-    if use_mkvmerge {
-        let lang_opt = language.unwrap_or_else(|| "und".to_string());
-        let title_val = title.unwrap_or_else(|| "Translated".to_string());
-        let default_flag = if set_default { "0:1" } else { "0:0" };
-
-        let args = vec![
-            "-o".to_string(),
-            temp_output.to_string_lossy().to_string(),
-            video_path.clone(),
-            "--language".to_string(),
-            format!("0:{}", lang_opt),
-            "--track-name".to_string(),
-            format!("0:{}", title_val),
-            "--default-track".to_string(),
-            default_flag.to_string(),
-            utf8_subtitle_path.clone(),
-        ];
+    let video_info = super::video::get_video_info(video_path.clone(), Some(ffmpeg.clone())).await?;
 
+    let (result, success_message) = if use_mkvmerge {
+        let mut other_track_ids: Vec<u32> = video_info
+            .video_streams
+            .iter()
+            .map(|s| s.stream_index)
+            .chain(video_info.audio_tracks.iter().map(|t| t.stream_index))
+            .collect();
+        other_track_ids.sort_unstable();
+        let entries: Vec<(String, &SubtitleEmbed)> =
+            utf8_paths.iter().cloned().zip(subtitles.iter()).collect();
         let mkvmerge_bin = mkvmerge_path.unwrap_or_else(|| "mkvmerge".to_string());
 
-        let result = create_command(&mkvmerge_bin)
-            .args(&args)
-            .output()
-            .map_err(|e| format!("Failed to run mkvmerge: {}", e))?;
-
-        if let Some(temp_path) = &temp_utf8_path {
-            let _ = fs::remove_file(temp_path);
+        let mut command = create_command(&mkvmerge_bin);
+        command.args(mkvmerge_embed_args(
+            &video_path,
+            &output,
+            &video_info.subtitle_tracks,
+            &other_track_ids,
+            &entries,
+            clear_existing_defaults,
+        ));
+        let result = run_tracked_command(
+            &app,
+            command,
+            "embed",
+            &video_path,
+            temp_files.paths(),
+            |_, _| {},
+        )
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run mkvmerge: {}", e)))?;
+        (result, "Subtitle embedded successfully (mkvmerge)")
+    } else {
+        let mut entries: Vec<(String, &SubtitleEmbed, &str)> = Vec::new();
+        for (path, subtitle) in utf8_paths.iter().zip(subtitles.iter()) {
+            let sub_codec = resolve_ffmpeg_subtitle_codec(&output_ext, &subtitle_format(path))?;
+            entries.push((path.clone(), subtitle, sub_codec));
         }
 
-        if result.status.success() {
-            fs::rename(&temp_output, &video_path)
-                .map_err(|e| format!("Failed to replace original file: {}", e))?;
+        let args = ffmpeg_embed_args(
+            &video_path,
+            &output,
+            &video_info.subtitle_tracks,
+            &entries,
+            clear_existing_defaults,
+        );
 
-            return Ok(OperationResult {
-                success: true,
-                message: "Subtitle embedded successfully (mkvmerge)".to_string(),
-                data: None,
-            });
+        let result = run_ffmpeg_with_progress(
+            &app,
+            &ffmpeg,
+            &args,
+            "embed",
+            &video_path,
+            video_info.duration,
+            temp_files.paths(),
+        )
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
+        if converts_to_mkv {
+            (result, "Subtitle embedded successfully (converted to MKV)")
         } else {
-            let _ = fs::remove_file(&temp_output);
-            return Ok(OperationResult {
-                success: false,
-                message: String::from_utf8_lossy(&result.stderr).to_string(),
-                data: None,
-            });
+            (result, "Subtitle embedded successfully")
         }
-    }
+    };
 
-    let sub_ext = Path::new(&utf8_subtitle_path)
-        .extension()
-        .map(|e| e.to_string_lossy().to_ascii_lowercase())
-        .unwrap_or_default();
-    let sub_codec = resolve_ffmpeg_subtitle_codec(&ext, &sub_ext)?;
-
-    let mut args = vec![
-        "-i".to_string(),
-        video_path.clone(),
-        "-i".to_string(),
-        utf8_subtitle_path.clone(),
-        "-map".to_string(),
-        "0".to_string(),
-        "-map".to_string(),
-        "1:0".to_string(),
-        "-c".to_string(),
-        "copy".to_string(),
-    ];
-
-    let video_info = super::video::get_video_info(video_path.clone(), Some(ffmpeg.clone())).await?;
-    let new_track_idx = video_info.subtitle_tracks.len();
-
-    args.push(format!("-c:s:{}", new_track_idx));
-    args.push(sub_codec.to_string());
-
-    if let Some(lang) = language {
-        args.push(format!("-metadata:s:s:{}", new_track_idx));
-        args.push(format!("language={}", lang));
-    }
-
-    let title_val = title.unwrap_or_else(|| "Translated".to_string());
-    args.push(format!("-metadata:s:s:{}", new_track_idx));
-    args.push(format!("title={}", title_val));
-
-    if set_default {
-        args.push(format!("-disposition:s:{}", new_track_idx));
-        args.push("default".to_string());
+    if !result.status.success() {
+        return Ok(OperationResult {
+            success: false,
+            message: String::from_utf8_lossy(&result.stderr).to_string(),
+            data: None,
+            verification: None,
+        });
     }
 
-    args.push("-y".to_string());
-    args.push(temp_output.to_string_lossy().to_string());
-
-    let result = create_command(&ffmpeg)
-        .args(&args)
-        .output()
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
-
-    if let Some(temp_path) = &temp_utf8_path {
-        let _ = fs::remove_file(temp_path);
+    let batch_message = format!("Embedded {} subtitle tracks successfully", added);
+    let outcome = finalize_verified_remux(
+        &app,
+        &video_path,
+        &temp_output,
+        &output_policy,
+        added as i64,
+        ffmpeg_path,
+        verify_decode.unwrap_or(false),
+        if added == 1 {
+            success_message
+        } else {
+            &batch_message
+        },
+    )
+    .await?;
+    if outcome.success {
+        temp_files.keep(&temp_output);
     }
+    Ok(outcome)
+}
 
-    if result.status.success() {
-        fs::rename(&temp_output, &video_path)
-            .map_err(|e| format!("Failed to replace original file: {}", e))?;
+#[tauri::command]
+pub async fn embed_subtitle(
+    app: AppHandle,
+    video_path: String,
+    subtitle_path: String,
+    language: Option<String>,
+    title: Option<String>,
+    set_default: bool,
+    ffmpeg_path: Option<String>,
+    use_mkvmerge: Option<bool>,
+    output_policy: Option<OutputPolicy>,
+    verify_decode: Option<bool>,
+    track_options: Option<EmbedTrackOptions>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let track_options = track_options.unwrap_or_default();
+    let subtitle = SubtitleEmbed {
+        path: subtitle_path,
+        language,
+        title,
+        default: set_default,
+        forced: track_options.forced,
+        position: track_options.position,
+    };
+    embed_subtitle_files(
+        app,
+        video_path,
+        vec![subtitle],
+        ffmpeg_path,
+        use_mkvmerge,
+        output_policy,
+        verify_decode,
+        track_options.clear_existing_defaults,
+        track_options.on_incompatible,
+    )
+    .await
+}
 
-        Ok(OperationResult {
-            success: true,
-            message: "Subtitle embedded successfully".to_string(),
-            data: None,
-        })
-    } else {
-        let _ = fs::remove_file(&temp_output);
+/// Embeds several subtitle files (e.g. one per language) with a single remux.
+#[tauri::command]
+pub async fn embed_subtitles_batch(
+    app: AppHandle,
+    video_path: String,
+    subtitles: Vec<SubtitleEmbed>,
+    ffmpeg_path: Option<String>,
+    use_mkvmerge: Option<bool>,
+    output_policy: Option<OutputPolicy>,
+    verify_decode: Option<bool>,
+    clear_existing_defaults: Option<bool>,
+    on_incompatible: Option<IncompatibleSubtitleAction>,
+) -> Result<OperationResult, AnimeSubsError> {
+    embed_subtitle_files(
+        app,
+        video_path,
+        subtitles,
+        ffmpeg_path,
+        use_mkvmerge,
+        output_policy,
+        verify_decode,
+        clear_existing_defaults.unwrap_or(false),
+        on_incompatible.unwrap_or_default(),
+    )
+    .await
+}
 
-        Ok(OperationResult {
-            success: false,
-            message: String::from_utf8_lossy(&result.stderr).to_string(),
-            data: None,
-        })
-    }
+/// Reports whether `subtitle_paths` fit the video's container as-is, which ones would be
+/// converted by embedding, and the container to remux into instead.
+#[tauri::command]
+pub async fn check_embed_compatibility(
+    video_path: String,
+    subtitle_paths: Vec<String>,
+) -> Result<EmbedCompatibilityReport, AnimeSubsError> {
+    let ext = Path::new(&video_path)
+        .extension()
+        .map(|s| s.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_else(|| "mkv".to_string());
+    Ok(embed_compatibility(&ext, &subtitle_paths))
 }
 
 #[tauri::command]
 pub async fn remove_subtitle_track(
+    app: AppHandle,
     video_path: String,
     track_index: u32,
     ffmpeg_path: Option<String>,
-) -> Result<OperationResult, String> {
+    output_policy: Option<OutputPolicy>,
+    verify_decode: Option<bool>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let output_policy = output_policy.unwrap_or_default();
     let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
 
-    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path).await?;
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
 
     if track_index as usize >= video_info.subtitle_tracks.len() {
-        return Err("Invalid track index".to_string());
+        return Err(AnimeSubsError::TrackNotFound(
+            "Invalid track index".to_string(),
+        ));
     }
 
     let video_pathbuf = Path::new(&video_path);
@@ -190,10 +524,13 @@ pub async fn remove_subtitle_track(
         .unwrap_or_else(|| "mkv".to_string());
 
     let temp_output = parent.join(format!("{}_modified.{}", stem, ext));
+    ensure_remux_space(&video_path, &temp_output, &output_policy)?;
+    let mut temp_files = TempFileRegistry::new(&app);
+    temp_files.track(temp_output.clone());
 
     let mut args = vec![
         "-i".to_string(),
-        video_path.clone(),
+        ffmpeg_file(&video_path),
         "-map".to_string(),
         "0:v".to_string(),
         "-map".to_string(),
@@ -211,30 +548,189 @@ pub async fn remove_subtitle_track(
         "-c".to_string(),
         "copy".to_string(),
         "-y".to_string(),
-        temp_output.to_string_lossy().to_string(),
+        ffmpeg_file(&temp_output.to_string_lossy()),
     ]);
 
-    let result = create_command(&ffmpeg)
-        .args(&args)
-        .output()
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    let result = run_ffmpeg_with_progress(
+        &app,
+        &ffmpeg,
+        &args,
+        "remove_track",
+        &video_path,
+        video_info.duration,
+        temp_files.paths(),
+    )
+    .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
 
     if result.status.success() {
-        fs::rename(&temp_output, &video_path)
-            .map_err(|e| format!("Failed to replace original file: {}", e))?;
-
-        Ok(OperationResult {
-            success: true,
-            message: "Subtitle track removed successfully".to_string(),
-            data: None,
-        })
+        let outcome = finalize_verified_remux(
+            &app,
+            &video_path,
+            &temp_output,
+            &output_policy,
+            -1,
+            ffmpeg_path.clone(),
+            verify_decode.unwrap_or(false),
+            "Subtitle track removed successfully",
+        )
+        .await?;
+        if outcome.success {
+            temp_files.keep(&temp_output);
+        }
+        Ok(outcome)
     } else {
-        let _ = fs::remove_file(&temp_output);
-
         Ok(OperationResult {
             success: false,
             message: String::from_utf8_lossy(&result.stderr).to_string(),
             data: None,
+            verification: None,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn existing_track(index: u32, stream_index: u32, default: bool) -> SubtitleTrack {
+        SubtitleTrack {
+            index,
+            stream_index,
+            codec: "ass".to_string(),
+            language: Some("jpn".to_string()),
+            title: None,
+            default,
+            forced: false,
+            detected_language: None,
+        }
+    }
+
+    #[test]
+    fn builds_one_remux_for_several_subtitles() {
+        let existing = [existing_track(0, 2, false), existing_track(1, 3, false)];
+        let english = SubtitleEmbed {
+            path: "en.ass".to_string(),
+            language: Some("eng".to_string()),
+            title: Some("English".to_string()),
+            default: true,
+            ..Default::default()
+        };
+        let signs = SubtitleEmbed {
+            path: "signs.ass".to_string(),
+            language: Some("por".to_string()),
+            forced: true,
+            ..Default::default()
+        };
+
+        let args = ffmpeg_embed_args(
+            "v.mkv",
+            "o.mkv",
+            &existing,
+            &[
+                ("en.ass".to_string(), &english, "ass"),
+                ("signs.ass".to_string(), &signs, "ass"),
+            ],
+            false,
+        );
+        let maps: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "-map")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(maps, ["0", "1:0", "2:0"]);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-disposition:s:2", "default"]));
+        assert!(args.windows(2).any(|w| w == ["-disposition:s:3", "forced"]));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-metadata:s:s:3", "title=Translated"]));
+
+        let args = mkvmerge_embed_args(
+            "v.mkv",
+            "o.mkv",
+            &existing,
+            &[0, 1],
+            &[
+                ("en.ass".to_string(), &english),
+                ("signs.ass".to_string(), &signs),
+            ],
+            false,
+        );
+        assert_eq!(args.iter().filter(|a| *a == "--language").count(), 2);
+        assert!(args.windows(2).any(|w| w == ["--forced-track", "0:1"]));
+        assert!(!args.contains(&"--track-order".to_string()));
+    }
+
+    #[test]
+    fn reports_subtitles_the_container_cannot_hold() {
+        let paths = ["a.ass".to_string(), "b.srt".to_string()];
+
+        let report = embed_compatibility("mp4", &paths);
+        assert!(!report.compatible);
+        assert_eq!(report.suggested_container.as_deref(), Some("mkv"));
+        assert!(report.subtitles[0].lossy);
+        assert!(!report.subtitles[1].lossy);
+        assert_eq!(
+            incompatibility_message(&report),
+            "Subtitles not compatible with .mp4: a.ass (ass -> mov_text, styling lost)"
+        );
+
+        assert!(embed_compatibility("mkv", &paths).compatible);
+        assert_eq!(embed_compatibility("avi", &paths).subtitles[1].codec, None);
+    }
+
+    #[test]
+    fn places_new_track_first_and_clears_old_defaults() {
+        assert_eq!(
+            subtitle_layout(2, &[None, Some(0), Some(9)]),
+            [
+                SubtitleSlot::Added(1),
+                SubtitleSlot::Existing(0),
+                SubtitleSlot::Existing(1),
+                SubtitleSlot::Added(2),
+                SubtitleSlot::Added(0),
+            ]
+        );
+
+        let existing = [existing_track(0, 2, true), existing_track(1, 3, false)];
+        let translated = SubtitleEmbed {
+            path: "pt.ass".to_string(),
+            default: true,
+            position: Some(0),
+            ..Default::default()
+        };
+
+        let args = ffmpeg_embed_args(
+            "v.mkv",
+            "o.mkv",
+            &existing,
+            &[("pt.ass".to_string(), &translated, "ass")],
+            true,
+        );
+        let maps: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "-map")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(maps, ["0", "-0:s", "1:0", "0:s:0", "0:s:1"]);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-disposition:s:0", "default"]));
+        assert!(args.windows(2).any(|w| w == ["-disposition:s:1", "0"]));
+        assert!(!args.iter().any(|a| a == "-disposition:s:2"));
+
+        let args = mkvmerge_embed_args(
+            "v.mkv",
+            "o.mkv",
+            &existing,
+            &[0, 1],
+            &[("pt.ass".to_string(), &translated)],
+            true,
+        );
+        assert_eq!(&args[2..5], ["--default-track", "2:0", "v.mkv"]);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["--track-order", "0:0,0:1,1:0,0:2,0:3"]));
+    }
+}
@@ -0,0 +1,262 @@
+use super::translation::{
+    batch_token_usage, chain_max_input_tokens, estimate_tokens, job_track_index,
+    plan_translation_batches, prepare_job_subtitle, provider_chain, repeated_lines,
+};
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+use tauri::AppHandle;
+
+/// Parsed subtitles per source (`video#track` or subtitle path) with the source's
+/// modification time, so a changed model or batch size re-estimates without
+/// reading the tracks again.
+static PARSED_SOURCES: LazyLock<Mutex<HashMap<String, (Option<SystemTime>, SubtitleData)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Subtitles the job would translate for `video_path`, without extracting to disk.
+pub(crate) async fn job_source_subtitle(
+    request: &TranslationJobRequest,
+    video_path: &str,
+) -> Result<SubtitleData, AnimeSubsError> {
+    let (key, source_path, track) = match request.file_subtitles.get(video_path) {
+        Some(subtitle_path) => (subtitle_path.clone(), subtitle_path.clone(), None),
+        None => {
+            let explicit = request
+                .file_tracks
+                .get(video_path)
+                .copied()
+                .or(request.subtitle_track);
+            let track = match explicit {
+                Some(index) => index,
+                None => {
+                    let video_info = super::video::get_video_info(
+                        video_path.to_string(),
+                        request.ffmpeg_path.clone(),
+                    )
+                    .await?;
+                    job_track_index(request, video_path, &video_info)?
+                }
+            };
+            (
+                format!("{}#{}", video_path, track),
+                video_path.to_string(),
+                Some(track),
+            )
+        }
+    };
+
+    let source_modified = modified(&source_path);
+    if let Some((cached_modified, data)) = PARSED_SOURCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+    {
+        if *cached_modified == source_modified {
+            return Ok(data.clone());
+        }
+    }
+
+    let data = match track {
+        Some(track) => {
+            super::subtitle::parse_embedded_subtitle(
+                video_path.to_string(),
+                track,
+                request.ffmpeg_path.clone(),
+            )
+            .await?
+        }
+        None => super::subtitle::parse_subtitle_file(source_path).await?,
+    };
+    PARSED_SOURCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(key, (source_modified, data.clone()));
+    Ok(data)
+}
+
+/// Lines, batches and tokens of one file, planned the way `translate_subtitles`
+/// plans them and counted like the job reports. Output is assumed to be about as
+/// long as the source text.
+fn estimate_subtitle(
+    data: &SubtitleData,
+    config: &LLMConfig,
+    max_input_tokens: usize,
+    source_lang: &str,
+    target_lang: &str,
+    refine: bool,
+) -> FileEstimate {
+    let repeats = repeated_lines(config, &data.lines);
+    let source: Vec<DialogLine> = data
+        .lines
+        .iter()
+        .filter(|l| !l.skip && !repeats.contains_key(&l.index))
+        .cloned()
+        .collect();
+    let text_tokens: usize = source.iter().map(|l| estimate_tokens(&l.text)).sum();
+    let batches = plan_translation_batches(&source, config.batching, max_input_tokens);
+    let (input_tokens, output_tokens) = batch_token_usage(
+        config,
+        &batches,
+        source_lang,
+        target_lang,
+        text_tokens,
+        refine,
+    );
+    let passes = if refine { 2 } else { 1 };
+
+    FileEstimate {
+        lines: source.len(),
+        batches: batches.len() * passes,
+        input_tokens,
+        output_tokens,
+        ..Default::default()
+    }
+}
+
+/// Estimates lines, requests, tokens and cost of a translation job before it runs.
+#[tauri::command]
+pub async fn estimate_translation_job(
+    app: AppHandle,
+    mut request: TranslationJobRequest,
+) -> Result<JobEstimate, AnimeSubsError> {
+    super::settings::apply_saved_job_defaults(&mut request);
+    let mut config = request.config.clone();
+    super::settings::apply_saved_llm_defaults(&mut config);
+    super::prompts::resolve_prompt_template(&app, &mut config)?;
+    let max_input_tokens = chain_max_input_tokens(&provider_chain(&config));
+
+    let mut estimate = JobEstimate {
+        model: config.model.clone(),
+        ..Default::default()
+    };
+    for video_path in &request.video_paths {
+        let mut file = match job_source_subtitle(&request, video_path).await {
+            Ok(data) => estimate_subtitle(
+                &prepare_job_subtitle(&request, data),
+                &config,
+                max_input_tokens,
+                &request.source_lang,
+                &request.target_lang,
+                request.refine,
+            ),
+            Err(e) => FileEstimate {
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        };
+        file.video_path = video_path.clone();
+        estimate.lines += file.lines;
+        estimate.batches += file.batches;
+        estimate.input_tokens += file.input_tokens;
+        estimate.output_tokens += file.output_tokens;
+        estimate.files.push(file);
+    }
+    estimate.estimated_cost = super::reports::estimate_cost(
+        &super::settings::current_settings().model_pricing,
+        &config.model,
+        &[],
+        estimate.input_tokens,
+        estimate.output_tokens,
+    );
+    Ok(estimate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subtitle(texts: &[&str]) -> SubtitleData {
+        let lines: Vec<DialogLine> = texts
+            .iter()
+            .enumerate()
+            .map(|(index, text)| DialogLine {
+                index,
+                text: text.to_string(),
+                original_with_formatting: text.to_string(),
+                start: format!("0:00:{:02}.00", index * 2),
+                end: format!("0:00:{:02}.50", index * 2 + 1),
+                style: None,
+                name: None,
+                reference: None,
+                formatting: None,
+                event: AssEventKind::Dialogue,
+                classification: LineClass::Dialogue,
+                skip: text.is_empty(),
+            })
+            .collect();
+        SubtitleData {
+            format: "ass".to_string(),
+            line_count: lines.len(),
+            lines,
+            source_path: "/anime/ep01.ass".to_string(),
+            ass_header: None,
+            failed_line_ids: Vec::new(),
+            batch_providers: Vec::new(),
+            class_counts: LineClassCounts::default(),
+            deduplicated_lines: 0,
+        }
+    }
+
+    fn estimate(data: &SubtitleData, max_input_tokens: usize, refine: bool) -> FileEstimate {
+        estimate_subtitle(
+            data,
+            &LLMConfig::default(),
+            max_input_tokens,
+            "ja",
+            "en",
+            refine,
+        )
+    }
+
+    #[test]
+    fn counts_sent_lines_batches_prompts_and_refine_passes() {
+        let data = subtitle(&["Good morning, everyone.", "", "Let's go!"]);
+        let single = estimate(&data, 10_000, false);
+        assert_eq!(single.lines, 2);
+        assert_eq!(single.batches, 1);
+        assert_eq!(
+            single.output_tokens,
+            estimate_tokens("Good morning, everyone.") + estimate_tokens("Let's go!")
+        );
+        // The system prompt goes out with every request.
+        assert!(single.input_tokens > single.output_tokens + 100);
+
+        let chunked = estimate(&data, 8, false);
+        assert!(chunked.batches > 1);
+        assert!(chunked.input_tokens > single.input_tokens);
+
+        let refined = estimate(&data, 10_000, true);
+        assert_eq!(refined.batches, 2);
+        assert_eq!(
+            refined.input_tokens,
+            single.input_tokens * 2 + single.output_tokens
+        );
+        assert_eq!(refined.output_tokens, single.output_tokens * 2);
+    }
+
+    #[test]
+    fn repeated_lines_are_sent_once() {
+        let data = subtitle(&[
+            "Let's get out of here!",
+            "Good morning, everyone.",
+            "Let's get out of here!",
+            "Let's get out of here!",
+        ]);
+        let repeats = repeated_lines(&LLMConfig::default(), &data.lines);
+        assert_eq!(repeats, HashMap::from([(2, 0), (3, 0)]));
+
+        let file = estimate(&data, 10_000, false);
+        assert_eq!(file.lines, 2);
+        assert_eq!(
+            file.output_tokens,
+            estimate_tokens("Let's get out of here!") + estimate_tokens("Good morning, everyone.")
+        );
+    }
+}
@@ -0,0 +1,498 @@
+use super::styles::{parse_ass_styles, replace_ass_styles};
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+const FONT_EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+/// Family, full name and typographic family in the OpenType `name` table.
+const FONT_NAME_IDS: [u16; 3] = [1, 4, 16];
+const MAX_FONT_DIR_DEPTH: usize = 6;
+
+static INLINE_FONT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\fn([^\\}]*)").unwrap());
+
+/// ASS font names compare case-insensitively; `@` only asks for vertical layout.
+fn font_key(name: &str) -> String {
+    name.trim().trim_start_matches('@').to_lowercase()
+}
+
+fn new_usage(name: &str) -> FontUsage {
+    FontUsage {
+        name: name.trim().trim_start_matches('@').to_string(),
+        styles: Vec::new(),
+        inline_uses: 0,
+        attached: false,
+        installed: false,
+    }
+}
+
+/// Fonts used by styles and by inline `\fn` tags, keyed by `font_key`.
+fn referenced_fonts(content: &str) -> BTreeMap<String, FontUsage> {
+    let mut fonts: BTreeMap<String, FontUsage> = BTreeMap::new();
+
+    for style in parse_ass_styles(content) {
+        if style.fontname.trim().is_empty() {
+            continue;
+        }
+        fonts
+            .entry(font_key(&style.fontname))
+            .or_insert_with(|| new_usage(&style.fontname))
+            .styles
+            .push(style.name);
+    }
+
+    let mut in_events = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_events = trimmed.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events || !trimmed.starts_with("Dialogue:") {
+            continue;
+        }
+        let mut seen = HashSet::new();
+        for caps in INLINE_FONT_RE.captures_iter(trimmed) {
+            let name = caps[1].trim();
+            if name.is_empty() || !seen.insert(font_key(name)) {
+                continue;
+            }
+            fonts
+                .entry(font_key(name))
+                .or_insert_with(|| new_usage(name))
+                .inline_uses += 1;
+        }
+    }
+    fonts
+}
+
+fn read_at(file: &mut File, offset: u64, len: usize) -> Option<Vec<u8>> {
+    let mut buf = vec![0; len];
+    file.seek(SeekFrom::Start(offset)).ok()?;
+    file.read_exact(&mut buf).ok()?;
+    Some(buf)
+}
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// Names from the `name` table of the font whose table directory starts at `offset`.
+fn sfnt_font_names(file: &mut File, offset: u64) -> Option<Vec<String>> {
+    let header = read_at(file, offset, 12)?;
+    let table_count = be_u16(&header, 4)? as usize;
+    let directory = read_at(file, offset + 12, table_count * 16)?;
+    let (name_offset, name_length) = (0..table_count).find_map(|i| {
+        let entry = &directory[i * 16..i * 16 + 16];
+        (&entry[..4] == b"name").then(|| (be_u32(entry, 8), be_u32(entry, 12)))
+    })?;
+    let table = read_at(file, name_offset? as u64, name_length? as usize)?;
+
+    let record_count = be_u16(&table, 2)? as usize;
+    let strings_offset = be_u16(&table, 4)? as usize;
+    let mut names = Vec::new();
+    for i in 0..record_count {
+        let record = 6 + i * 12;
+        let platform = be_u16(&table, record)?;
+        let name_id = be_u16(&table, record + 6)?;
+        if !FONT_NAME_IDS.contains(&name_id) {
+            continue;
+        }
+        let length = be_u16(&table, record + 8)? as usize;
+        let start = strings_offset + be_u16(&table, record + 10)? as usize;
+        let Some(bytes) = table.get(start..start + length) else {
+            continue;
+        };
+        let name = match platform {
+            // Unicode and Windows platforms store UTF-16BE.
+            0 | 3 => String::from_utf16_lossy(
+                &bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect::<Vec<u16>>(),
+            ),
+            _ => bytes.iter().map(|&b| b as char).collect(),
+        };
+        if !name.trim().is_empty() {
+            names.push(name);
+        }
+    }
+    Some(names)
+}
+
+/// Family and full names of every face in a TTF/OTF file or TTC collection.
+fn font_file_names(path: &Path) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    let Some(header) = read_at(&mut file, 0, 12) else {
+        return Vec::new();
+    };
+
+    let offsets: Vec<u64> = if &header[..4] == b"ttcf" {
+        let count = be_u32(&header, 8).unwrap_or(0) as usize;
+        read_at(&mut file, 12, count * 4)
+            .map(|data| {
+                (0..count)
+                    .filter_map(|i| be_u32(&data, i * 4).map(u64::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else {
+        vec![0]
+    };
+
+    offsets
+        .into_iter()
+        .filter_map(|offset| sfnt_font_names(&mut file, offset))
+        .flatten()
+        .collect()
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|e| FONT_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// Adds the names of all fonts under `dir` to `names`, as `font_key`s. File stems count
+/// too, since attachments are often named after the font.
+fn collect_font_names(dir: &Path, names: &mut HashSet<String>) {
+    let mut pending = vec![(dir.to_path_buf(), 0)];
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && depth < MAX_FONT_DIR_DEPTH {
+                pending.push((path, depth + 1));
+            } else if is_font_file(&path) {
+                if let Some(stem) = path.file_stem() {
+                    names.insert(font_key(&stem.to_string_lossy()));
+                }
+                names.extend(font_file_names(&path).into_iter().map(|n| font_key(&n)));
+            }
+        }
+    }
+}
+
+fn system_font_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    if cfg!(windows) {
+        let windir = env::var_os("WINDIR").map_or(PathBuf::from(r"C:\Windows"), PathBuf::from);
+        let mut dirs = vec![windir.join("Fonts")];
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join(r"Microsoft\Windows\Fonts"));
+        }
+        dirs
+    } else if cfg!(target_os = "macos") {
+        let mut dirs = vec![
+            PathBuf::from("/System/Library/Fonts"),
+            PathBuf::from("/Library/Fonts"),
+        ];
+        dirs.extend(home.map(|h| h.join("Library/Fonts")));
+        dirs
+    } else {
+        let mut dirs = vec![
+            PathBuf::from("/usr/share/fonts"),
+            PathBuf::from("/usr/local/share/fonts"),
+        ];
+        if let Some(home) = home {
+            dirs.push(home.join(".local/share/fonts"));
+            dirs.push(home.join(".fonts"));
+        }
+        dirs
+    }
+}
+
+fn installed_font_names() -> HashSet<String> {
+    let mut names = HashSet::new();
+    for dir in system_font_dirs() {
+        collect_font_names(&dir, &mut names);
+    }
+    names
+}
+
+/// Font attached to a video. The file name stored in the container is only a name
+/// hint; it is never used as a path, since a crafted file could point it anywhere.
+#[derive(Debug, PartialEq)]
+struct FontAttachment {
+    stream_index: u64,
+    filename: String,
+}
+
+impl FontAttachment {
+    /// Name the attachment is dumped to inside the temp directory.
+    fn dump_name(&self) -> String {
+        let extension = Path::new(&self.filename)
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .filter(|e| FONT_EXTENSIONS.contains(&e.as_str()))
+            .unwrap_or_else(|| "ttf".to_string());
+        format!("attachment_{}.{}", self.stream_index, extension)
+    }
+}
+
+/// Font attachments in ffprobe's `-show_streams` output; other attachments
+/// (cover art, chapters) are left out.
+fn parse_font_attachments(json: &serde_json::Value) -> Vec<FontAttachment> {
+    let Some(streams) = json["streams"].as_array() else {
+        return Vec::new();
+    };
+    streams
+        .iter()
+        .filter(|stream| stream["codec_type"].as_str() == Some("attachment"))
+        .filter_map(|stream| {
+            let filename = stream["tags"]["filename"].as_str().unwrap_or_default();
+            let mimetype = stream["tags"]["mimetype"].as_str().unwrap_or_default();
+            let is_font = is_font_file(Path::new(filename))
+                || mimetype.contains("font")
+                || mimetype.contains("truetype")
+                || mimetype.contains("opentype");
+            is_font.then(|| FontAttachment {
+                stream_index: stream["index"].as_u64()?,
+                filename: filename.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn font_attachments(video_path: &str, ffmpeg_path: Option<String>) -> Vec<FontAttachment> {
+    let Ok(output) = create_command(get_ffprobe_path(ffmpeg_path))
+        .args(["-v", "quiet", "-print_format", "json", "-show_streams"])
+        .arg(ffmpeg_file_arg(video_path))
+        .output()
+    else {
+        return Vec::new();
+    };
+    serde_json::from_slice(&output.stdout)
+        .map(|json| parse_font_attachments(&json))
+        .unwrap_or_default()
+}
+
+/// Dumps the video's font attachments into a temp directory, each by stream index
+/// under a generated name, and reads their font names.
+fn attached_font_names(video_path: &str, ffmpeg_path: Option<String>) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let attachments = font_attachments(video_path, ffmpeg_path.clone());
+    if attachments.is_empty() {
+        return names;
+    }
+    let Ok(dir) = build_temp_subtitle_path(video_path, "fonts", "d") else {
+        return names;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return names;
+    }
+
+    let mut command = create_command(get_ffmpeg_path(ffmpeg_path));
+    command.args(["-y", "-v", "quiet"]);
+    for attachment in &attachments {
+        command
+            .arg(format!("-dump_attachment:{}", attachment.stream_index))
+            .arg(dir.join(attachment.dump_name()));
+    }
+    // ffmpeg exits with an error after dumping since no output is given; the files
+    // written to the directory are what matters.
+    let _ = command.arg("-i").arg(ffmpeg_file_arg(video_path)).output();
+
+    for attachment in &attachments {
+        // Attachments are often named after the font.
+        if let Some(stem) = Path::new(&attachment.filename).file_stem() {
+            names.insert(font_key(&stem.to_string_lossy()));
+        }
+        let dumped = dir.join(attachment.dump_name());
+        names.extend(font_file_names(&dumped).into_iter().map(|n| font_key(&n)));
+    }
+    let _ = fs::remove_dir_all(&dir);
+    names
+}
+
+/// Points styles and `\fn` tags that use a font in `missing` (as `font_key`s) at
+/// `fallback`.
+fn remap_fonts(
+    content: &str,
+    missing: &HashSet<String>,
+    fallback: &str,
+) -> Result<String, AnimeSubsError> {
+    let mut styles = parse_ass_styles(content);
+    let mut content = content.to_string();
+    if styles
+        .iter()
+        .any(|s| missing.contains(&font_key(&s.fontname)))
+    {
+        for style in &mut styles {
+            if missing.contains(&font_key(&style.fontname)) {
+                style.fontname = fallback.to_string();
+            }
+        }
+        content = replace_ass_styles(&content, &styles)?;
+    }
+
+    Ok(INLINE_FONT_RE
+        .replace_all(&content, |caps: &regex::Captures| {
+            if missing.contains(&font_key(&caps[1])) {
+                format!("\\fn{}", fallback)
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string())
+}
+
+fn remapped_output_path(subtitle_path: &str) -> String {
+    let path = Path::new(subtitle_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "subtitle".to_string());
+    path.with_file_name(format!("{}_fonts.ass", stem))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Lists the fonts an ASS file needs and whether each is attached to `video_path` or
+/// installed locally. With `fallback_font`, also writes a copy that uses the fallback
+/// in place of every missing font.
+#[tauri::command]
+pub async fn analyze_subtitle_fonts(
+    subtitle_path: String,
+    video_path: Option<String>,
+    ffmpeg_path: Option<String>,
+    fallback_font: Option<String>,
+    output_path: Option<String>,
+) -> Result<FontReport, AnimeSubsError> {
+    let content = read_file_as_utf8(&subtitle_path)?;
+    let mut fonts = referenced_fonts(&content);
+
+    let attached = match video_path.as_deref().filter(|p| !p.is_empty()) {
+        Some(video) => attached_font_names(video, ffmpeg_path),
+        None => HashSet::new(),
+    };
+    let installed = installed_font_names();
+    for (key, usage) in fonts.iter_mut() {
+        usage.attached = attached.contains(key);
+        usage.installed = installed.contains(key);
+    }
+
+    let missing_keys: HashSet<String> = fonts
+        .iter()
+        .filter(|(_, usage)| !usage.attached && !usage.installed)
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    let fallback = fallback_font
+        .as_deref()
+        .map(str::trim)
+        .filter(|f| !f.is_empty());
+    let remapped_path = match fallback {
+        Some(fallback) if !missing_keys.is_empty() => {
+            if fallback.contains(',') {
+                return Err(format!("Invalid fallback font: {}", fallback).into());
+            }
+            let output = output_path
+                .filter(|p| !p.is_empty())
+                .unwrap_or_else(|| remapped_output_path(&subtitle_path));
+            write_utf8_file(
+                &output,
+                &remap_fonts(&content, &missing_keys, fallback)?,
+                true,
+            )?;
+            Some(output)
+        }
+        _ => None,
+    };
+
+    let fonts: Vec<FontUsage> = fonts.into_values().collect();
+    Ok(FontReport {
+        missing: fonts
+            .iter()
+            .filter(|usage| !usage.attached && !usage.installed)
+            .map(|usage| usage.name.clone())
+            .collect(),
+        fonts,
+        remapped_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASS: &str = r#"[Script Info]
+Title: Example
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, Encoding
+Style: Default,Open Sans Semibold,20,1
+Style: Sign,@Komika Axis,20,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello
+Dialogue: 0,0:00:03.00,0:00:04.00,Sign,,0,0,0,,{\fnFOT-Rodin Pro DB\fs30}Shop {\fnfot-rodin pro db}sign
+"#;
+
+    #[test]
+    fn collects_fonts_from_styles_and_inline_tags() {
+        let fonts = referenced_fonts(ASS);
+
+        assert_eq!(fonts.len(), 3);
+        assert_eq!(fonts["open sans semibold"].styles, vec!["Default"]);
+        assert_eq!(fonts["komika axis"].name, "Komika Axis");
+        assert_eq!(fonts["fot-rodin pro db"].inline_uses, 1);
+        assert!(fonts["fot-rodin pro db"].styles.is_empty());
+    }
+
+    #[test]
+    fn remaps_missing_fonts_in_styles_and_overrides() {
+        let missing: HashSet<String> = ["komika axis", "fot-rodin pro db"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let output = remap_fonts(ASS, &missing, "Arial").unwrap();
+
+        assert!(output.contains("Style: Default,Open Sans Semibold,20,"));
+        assert!(output.contains("Style: Sign,Arial,20,"));
+        assert!(output.contains("{\\fnArial\\fs30}Shop {\\fnArial}sign"));
+    }
+
+    #[test]
+    fn dumps_font_attachments_under_generated_names() {
+        let json = serde_json::json!({
+            "streams": [
+                { "index": 0, "codec_type": "video" },
+                { "index": 3, "codec_type": "attachment",
+                  "tags": { "filename": "../../.bashrc.TTF", "mimetype": "font/ttf" } },
+                { "index": 4, "codec_type": "attachment",
+                  "tags": { "filename": "Komika", "mimetype": "application/x-truetype-font" } },
+                { "index": 5, "codec_type": "attachment",
+                  "tags": { "filename": "cover.jpg", "mimetype": "image/jpeg" } }
+            ]
+        });
+
+        let attachments = parse_font_attachments(&json);
+        assert_eq!(
+            attachments
+                .iter()
+                .map(|a| (a.stream_index, a.dump_name()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "attachment_3.ttf".to_string()),
+                (4, "attachment_4.ttf".to_string()),
+            ]
+        );
+    }
+}
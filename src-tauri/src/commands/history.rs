@@ -0,0 +1,232 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::debug_log::DebugLog;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+const HISTORY_FILE: &str = "history.jsonl";
+const DEBUG_LOG_DIR: &str = "debug-logs";
+
+fn history_path(app: &AppHandle) -> Result<PathBuf, AnimeSubsError> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to resolve app data directory: {}", e)))?;
+    fs::create_dir_all(&data_dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create app data directory: {}", e)))?;
+    Ok(data_dir.join(HISTORY_FILE))
+}
+
+fn debug_log_dir(app: &AppHandle) -> Result<PathBuf, AnimeSubsError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to resolve app data directory: {}", e)))?
+        .join(DEBUG_LOG_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create debug log directory: {}", e)))?;
+    Ok(dir)
+}
+
+/// Opens the request/response log for a translation job started now.
+pub(crate) fn create_job_debug_log(app: &AppHandle) -> Result<DebugLog, AnimeSubsError> {
+    let name = format!("job-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    DebugLog::create(&debug_log_dir(app)?.join(name))
+}
+
+/// Program and arguments of `command`, as recorded in the history.
+pub(crate) fn command_parameters(command: &Command) -> serde_json::Value {
+    let program = command.get_program().to_string_lossy().to_string();
+    let args: Vec<String> = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect();
+    serde_json::json!({ "program": program, "args": args })
+}
+
+/// Outcome of a finished process plus the last stderr line when it failed.
+pub(crate) fn command_outcome(
+    result: &std::io::Result<Output>,
+) -> (HistoryOutcome, Option<String>) {
+    match result {
+        Ok(output) if output.status.success() => (HistoryOutcome::Success, None),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let last_line = stderr
+                .lines()
+                .rev()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(String::from);
+            (HistoryOutcome::Failed, last_line)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+            (HistoryOutcome::Cancelled, Some(e.to_string()))
+        }
+        Err(e) => (HistoryOutcome::Failed, Some(e.to_string())),
+    }
+}
+
+/// Appends `entry` to the history. Failures are only logged so that a read-only
+/// data directory never breaks the operation itself.
+pub(crate) fn record_operation(app: &AppHandle, entry: &HistoryEntry) {
+    let result = history_path(app).and_then(|path| {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to open history: {}", e)))?;
+        writeln!(file, "{}", line)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to write history: {}", e)))
+    });
+    if let Err(e) = result {
+        warn!("{}", e);
+    }
+}
+
+fn read_history(app: &AppHandle) -> Result<Vec<HistoryEntry>, AnimeSubsError> {
+    let path = history_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read history: {}", e)))?;
+    // A line cut short by a crash is skipped instead of hiding the whole history.
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn history_to_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv =
+        String::from("id,operation,videoPath,startedAt,durationMs,outcome,message,parameters\n");
+    for entry in entries {
+        let outcome = serde_json::to_value(entry.outcome)
+            .ok()
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_default();
+        let fields = [
+            entry.id.clone(),
+            entry.operation.clone(),
+            entry.video_path.clone(),
+            entry.started_at.clone(),
+            entry.duration_ms.to_string(),
+            outcome,
+            entry.message.clone().unwrap_or_default(),
+            entry.parameters.to_string(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// History entries, newest first, optionally limited to one video.
+#[tauri::command]
+pub async fn get_history(
+    app: AppHandle,
+    video_path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, AnimeSubsError> {
+    let mut entries = read_history(&app)?;
+    entries.reverse();
+    if let Some(video_path) = video_path {
+        entries.retain(|entry| entry.video_path == video_path);
+    }
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
+
+/// Writes the full history to `output_path` as JSON lines or, for `format = "csv"`, CSV.
+#[tauri::command]
+pub async fn export_history(
+    app: AppHandle,
+    output_path: String,
+    format: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let entries = read_history(&app)?;
+    let content = match format.as_deref().unwrap_or("jsonl") {
+        "csv" => history_to_csv(&entries),
+        "jsonl" => entries
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap_or_default() + "\n")
+            .collect(),
+        other => return Err(format!("Unsupported history format: {}", other).into()),
+    };
+    fs::write(&output_path, content)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to export history: {}", e)))?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Exported {} history entries", entries.len()),
+        data: Some(output_path),
+        verification: None,
+    })
+}
+
+/// Path and content of the newest job debug log, if any job ran with `debugLog`.
+#[tauri::command]
+pub async fn get_last_job_debug_log(app: AppHandle) -> Result<Option<JobDebugLog>, AnimeSubsError> {
+    let entries = fs::read_dir(debug_log_dir(&app)?)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read debug log directory: {}", e)))?;
+    // Names embed the start time, so the newest log sorts last.
+    let Some(path) = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .max()
+    else {
+        return Ok(None);
+    };
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read debug log: {}", e)))?;
+    Ok(Some(JobDebugLog {
+        path: path.to_string_lossy().to_string(),
+        content,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_history_as_csv() {
+        let entry = HistoryEntry {
+            id: "op-1".to_string(),
+            operation: "embed".to_string(),
+            video_path: "/anime/Show, Part 2.mkv".to_string(),
+            started_at: "2024-05-01T10:00:00+00:00".to_string(),
+            duration_ms: 1500,
+            outcome: HistoryOutcome::Failed,
+            message: Some("No space left on device".to_string()),
+            parameters: serde_json::json!({ "program": "mkvmerge" }),
+        };
+
+        let csv = history_to_csv(&[entry]);
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            "op-1,embed,\"/anime/Show, Part 2.mkv\",2024-05-01T10:00:00+00:00,1500,failed,\
+             No space left on device,\"{\"\"program\"\":\"\"mkvmerge\"\"}\""
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use super::translation::{batch_token_usage, estimate_tokens};
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::call_llm_api_with_context;
+use crate::utils::*;
+use regex::Regex;
+use std::sync::LazyLock;
+use tauri::AppHandle;
+use tracing::info;
+
+/// Romanized honorifics and the Japanese spellings that imply them.
+const HONORIFICS: [(&str, &[&str]); 7] = [
+    ("san", &["さん"]),
+    ("kun", &["くん"]),
+    ("chan", &["ちゃん"]),
+    ("sama", &["さま", "様"]),
+    ("senpai", &["先輩", "せんぱい"]),
+    ("sensei", &["先生"]),
+    ("dono", &["殿"]),
+];
+
+const CORRECTION: &str = "A previous translation of these lines dropped Japanese honorifics. \
+                          Keep every honorific from the source attached to the name it \
+                          follows (for example \"Okabe-san\", \"Mayuri-chan\", \
+                          \"Kurisu-senpai\").";
+
+static ROMANIZED_SOURCE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b\p{L}+-(san|kun|chan|sama|senpai|sensei|dono)\b").unwrap());
+static TRANSLATED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(san|kun|chan|sama|senpai|sensei|dono)\b").unwrap());
+
+fn count_source_honorific(source: &str, honorific: &str, japanese: &[&str]) -> usize {
+    let romanized = ROMANIZED_SOURCE_RE
+        .captures_iter(source)
+        .filter(|c| c[1].eq_ignore_ascii_case(honorific))
+        .count();
+    let native: usize = japanese.iter().map(|s| source.matches(s).count()).sum();
+    romanized + native
+}
+
+/// Honorifics that occur more often in `source` than in `translated`.
+fn missing_honorifics(source: &str, translated: &str) -> Vec<String> {
+    HONORIFICS
+        .iter()
+        .filter(|(honorific, japanese)| {
+            let expected = count_source_honorific(source, honorific, japanese);
+            let kept = TRANSLATED_RE
+                .captures_iter(translated)
+                .filter(|c| c[1].eq_ignore_ascii_case(honorific))
+                .count();
+            expected > kept
+        })
+        .map(|(honorific, _)| honorific.to_string())
+        .collect()
+}
+
+pub(crate) fn find_honorific_issues(subtitle_data: &SubtitleData) -> Vec<HonorificIssue> {
+    subtitle_data
+        .lines
+        .iter()
+        .filter(|line| !line.skip)
+        .filter_map(|line| {
+            let source = strip_ass_tags(&line.original_with_formatting);
+            let missing = missing_honorifics(&source, &line.text);
+            (!missing.is_empty()).then(|| HonorificIssue {
+                line_index: line.index,
+                missing,
+                source_text: source,
+                translated_text: line.text.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Flagged lines as sent back to the model, with their translation as the draft.
+fn correction_lines(issues: &[HonorificIssue]) -> Vec<TranslationLine> {
+    issues
+        .iter()
+        .map(|issue| TranslationLine {
+            id: issue.line_index,
+            text: issue.source_text.clone(),
+            reference: None,
+            draft: Some(issue.translated_text.clone()),
+            rejected: None,
+        })
+        .collect()
+}
+
+/// Input and output tokens `fix_honorifics` will spend on `subtitle_data`, assuming
+/// retranslations about as long as the current text.
+pub(crate) fn honorific_pass_tokens(
+    config: &LLMConfig,
+    subtitle_data: &SubtitleData,
+    source_lang: &str,
+    target_lang: &str,
+) -> (usize, usize) {
+    let issues = find_honorific_issues(subtitle_data);
+    if issues.is_empty() {
+        return (0, 0);
+    }
+    let output_tokens = issues
+        .iter()
+        .map(|issue| estimate_tokens(&issue.translated_text))
+        .sum();
+    let (input_tokens, output_tokens) = batch_token_usage(
+        config,
+        &[correction_lines(&issues)],
+        source_lang,
+        target_lang,
+        output_tokens,
+        false,
+    );
+    (input_tokens + estimate_tokens(CORRECTION), output_tokens)
+}
+
+/// Flags translated lines that dropped honorifics present in the source line.
+#[tauri::command]
+pub async fn check_honorifics(
+    subtitle_data: SubtitleData,
+) -> Result<Vec<HonorificIssue>, AnimeSubsError> {
+    Ok(find_honorific_issues(&subtitle_data))
+}
+
+/// Sends only the flagged lines back to the model with a corrective note and keeps
+/// a retranslation when it drops fewer honorifics than the first attempt.
+#[tauri::command]
+pub async fn fix_honorifics(
+    app: AppHandle,
+    mut subtitle_data: SubtitleData,
+    mut config: LLMConfig,
+    source_lang: String,
+    target_lang: String,
+) -> Result<SubtitleData, AnimeSubsError> {
+    super::settings::apply_saved_llm_defaults(&mut config);
+    super::prompts::resolve_prompt_template(&app, &mut config)?;
+    let issues = find_honorific_issues(&subtitle_data);
+    if issues.is_empty() {
+        return Ok(subtitle_data);
+    }
+
+    let lines = correction_lines(&issues);
+    let retranslated = call_llm_api_with_context(
+        &config,
+        &lines,
+        &source_lang,
+        &target_lang,
+        Some(CORRECTION),
+    )
+    .await?;
+
+    let mut fixed = 0usize;
+    for translated in retranslated {
+        let Some(issue) = issues.iter().find(|i| i.line_index == translated.id) else {
+            continue;
+        };
+        let still_missing = missing_honorifics(&issue.source_text, &translated.text).len();
+        if still_missing < issue.missing.len() {
+            if let Some(line) = subtitle_data
+                .lines
+                .iter_mut()
+                .find(|l| l.index == translated.id)
+            {
+                line.text = translated.text;
+                fixed += 1;
+            }
+        }
+    }
+
+    info!(
+        "Honorific second pass fixed {}/{} lines",
+        fixed,
+        issues.len()
+    );
+    Ok(subtitle_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_dropped_romanized_and_japanese_honorifics() {
+        assert_eq!(
+            missing_honorifics(
+                "Okabe-san, wait for Mayuri-chan!",
+                "Okabe, wait for Mayuri-chan!"
+            ),
+            vec!["san"]
+        );
+        assert_eq!(
+            missing_honorifics("岡部さん、先輩が来た", "Okabe, senpai is here"),
+            vec!["san"]
+        );
+        assert!(missing_honorifics("Sensei!", "Teacher!").is_empty());
+        assert!(missing_honorifics("まゆりちゃん", "Mayuri-chan").is_empty());
+    }
+}
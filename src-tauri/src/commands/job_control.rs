@@ -0,0 +1,158 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::{Mutex, MutexGuard, Notify};
+
+/// Pause and stop requests for the running translation job. Work checks in
+/// between batches and file stages; a stop also interrupts the LLM call in flight,
+/// while remuxes run to the end so no video is left half written.
+#[derive(Default)]
+pub struct JobControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    changed: Notify,
+    running: Mutex<()>,
+}
+
+impl JobControl {
+    /// Held for a whole job, so one started by the remote server or the
+    /// automation queue waits for the running job instead of taking over its
+    /// pause and stop state.
+    pub(crate) async fn exclusive(&self) -> MutexGuard<'_, ()> {
+        self.running.lock().await
+    }
+
+    /// Whether a job currently holds `exclusive`.
+    pub(crate) fn is_running(&self) -> bool {
+        self.running.try_lock().is_err()
+    }
+
+    /// Clears requests left over from the previous job.
+    pub(crate) fn reset(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+        self.changed.notify_waiters();
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.changed.notify_waiters();
+    }
+
+    /// Waits while the job is paused; `Cancelled` once a stop was requested.
+    pub(crate) async fn checkpoint(&self) -> Result<(), AnimeSubsError> {
+        loop {
+            // Created before the checks so a change in between still wakes us.
+            let changed = self.changed.notified();
+            if self.is_cancelled() {
+                return Err(AnimeSubsError::Cancelled);
+            }
+            if !self.is_paused() {
+                return Ok(());
+            }
+            changed.await;
+        }
+    }
+
+    /// Resolves once a stop is requested.
+    pub(crate) async fn cancelled(&self) {
+        loop {
+            let changed = self.changed.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            changed.await;
+        }
+    }
+}
+
+fn job_state_result(message: &str) -> OperationResult {
+    OperationResult {
+        success: true,
+        message: message.to_string(),
+        data: None,
+        verification: None,
+    }
+}
+
+/// Holds the job before its next batch; the batch in flight still finishes.
+#[tauri::command]
+pub async fn pause_translation_job(
+    app: AppHandle,
+    control: State<'_, JobControl>,
+) -> Result<OperationResult, AnimeSubsError> {
+    control.set_paused(true);
+    let _ = app.emit("translation-job-paused", true);
+    Ok(job_state_result("Translation paused"))
+}
+
+#[tauri::command]
+pub async fn resume_translation_job(
+    app: AppHandle,
+    control: State<'_, JobControl>,
+) -> Result<OperationResult, AnimeSubsError> {
+    control.set_paused(false);
+    let _ = app.emit("translation-job-paused", false);
+    Ok(job_state_result("Translation resumed"))
+}
+
+/// Stops the job; finished files keep their output and the rest stay pending.
+#[tauri::command]
+pub async fn cancel_translation_job(
+    app: AppHandle,
+    control: State<'_, JobControl>,
+) -> Result<OperationResult, AnimeSubsError> {
+    control.cancel();
+    let _ = app.emit("translation-job-paused", false);
+    Ok(job_state_result("Stopping translation..."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn paused_checkpoints_wait_for_resume_or_stop() {
+        let control = Arc::new(JobControl::default());
+        assert!(control.checkpoint().await.is_ok());
+
+        control.set_paused(true);
+        let waiting = tokio::spawn({
+            let control = control.clone();
+            async move { control.checkpoint().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+        control.set_paused(false);
+        assert!(waiting.await.unwrap().is_ok());
+
+        control.set_paused(true);
+        let waiting = tokio::spawn({
+            let control = control.clone();
+            async move { control.checkpoint().await }
+        });
+        control.cancel();
+        assert!(matches!(
+            waiting.await.unwrap(),
+            Err(AnimeSubsError::Cancelled)
+        ));
+
+        control.reset();
+        assert!(control.checkpoint().await.is_ok());
+    }
+}
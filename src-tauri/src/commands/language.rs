@@ -0,0 +1,209 @@
+use super::track_selection::IMAGE_CODECS;
+use super::translation::to_ffmpeg_lang_code;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::strip_ass_tags;
+use tauri::AppHandle;
+use tracing::warn;
+use whatlang::Lang;
+
+/// Dialog lines read from each track to detect its language.
+const SAMPLE_LINES: usize = 200;
+/// Enough text for a confident guess; longer samples only cost time.
+const SAMPLE_CHARS: usize = 4000;
+
+/// ISO 639-1 codes of the languages whatlang detects.
+const ISO_639_1: [(&str, Lang); 70] = [
+    ("af", Lang::Afr),
+    ("ak", Lang::Aka),
+    ("am", Lang::Amh),
+    ("ar", Lang::Ara),
+    ("az", Lang::Aze),
+    ("be", Lang::Bel),
+    ("bg", Lang::Bul),
+    ("bn", Lang::Ben),
+    ("ca", Lang::Cat),
+    ("cs", Lang::Ces),
+    ("da", Lang::Dan),
+    ("de", Lang::Deu),
+    ("el", Lang::Ell),
+    ("en", Lang::Eng),
+    ("eo", Lang::Epo),
+    ("es", Lang::Spa),
+    ("et", Lang::Est),
+    ("fa", Lang::Pes),
+    ("fi", Lang::Fin),
+    ("fr", Lang::Fra),
+    ("gu", Lang::Guj),
+    ("he", Lang::Heb),
+    ("hi", Lang::Hin),
+    ("hr", Lang::Hrv),
+    ("hu", Lang::Hun),
+    ("hy", Lang::Hye),
+    ("id", Lang::Ind),
+    ("it", Lang::Ita),
+    ("ja", Lang::Jpn),
+    ("jv", Lang::Jav),
+    ("ka", Lang::Kat),
+    ("km", Lang::Khm),
+    ("kn", Lang::Kan),
+    ("ko", Lang::Kor),
+    ("la", Lang::Lat),
+    ("lt", Lang::Lit),
+    ("lv", Lang::Lav),
+    ("mk", Lang::Mkd),
+    ("ml", Lang::Mal),
+    ("mr", Lang::Mar),
+    ("my", Lang::Mya),
+    ("nb", Lang::Nob),
+    ("ne", Lang::Nep),
+    ("nl", Lang::Nld),
+    ("no", Lang::Nob),
+    ("or", Lang::Ori),
+    ("pa", Lang::Pan),
+    ("pl", Lang::Pol),
+    ("pt", Lang::Por),
+    ("ro", Lang::Ron),
+    ("ru", Lang::Rus),
+    ("si", Lang::Sin),
+    ("sk", Lang::Slk),
+    ("sl", Lang::Slv),
+    ("sn", Lang::Sna),
+    ("sr", Lang::Srp),
+    ("sv", Lang::Swe),
+    ("ta", Lang::Tam),
+    ("te", Lang::Tel),
+    ("th", Lang::Tha),
+    ("tk", Lang::Tuk),
+    ("tl", Lang::Tgl),
+    ("tr", Lang::Tur),
+    ("uk", Lang::Ukr),
+    ("ur", Lang::Urd),
+    ("uz", Lang::Uzb),
+    ("vi", Lang::Vie),
+    ("yi", Lang::Yid),
+    ("zh", Lang::Cmn),
+    ("zu", Lang::Zul),
+];
+
+/// Code in the vocabulary of `to_ffmpeg_lang_code`, so detected languages
+/// compare equal to track tags and the job's languages.
+fn language_code(lang: Lang) -> String {
+    match lang {
+        Lang::Cmn => "zho".to_string(),
+        Lang::Pes => "per".to_string(),
+        other => other.code().to_string(),
+    }
+}
+
+/// Language most of `texts` is written in, as an ISO 639 code like `eng`;
+/// `None` when the sample is too short or too mixed to tell reliably.
+pub(crate) fn detect_language<'a>(texts: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let mut sample = String::new();
+    for text in texts {
+        if sample.len() >= SAMPLE_CHARS {
+            break;
+        }
+        // Override tags and line breaks are not language and skew short samples.
+        sample.push_str(&strip_ass_tags(text).replace("\\h", " "));
+        sample.push('\n');
+    }
+    let info = whatlang::detect(&sample)?;
+    info.is_reliable().then(|| language_code(info.lang()))
+}
+
+/// Whether a detected code names the same language as `lang` (`pt-BR`, `por`, ...).
+pub(crate) fn is_language(detected: &str, lang: &str) -> bool {
+    let base = lang
+        .trim()
+        .to_ascii_lowercase()
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let lang = match ISO_639_1.iter().find(|(code, _)| *code == base) {
+        Some((_, lang)) => language_code(*lang),
+        None => to_ffmpeg_lang_code(Some(lang)),
+    };
+    lang != "und" && lang == detected
+}
+
+/// `get_video_info` with `detected_language` filled in for every text track,
+/// read from each track's dialog.
+#[tauri::command]
+pub async fn detect_subtitle_languages(
+    app: AppHandle,
+    video_path: String,
+    ffmpeg_path: Option<String>,
+) -> Result<VideoInfo, AnimeSubsError> {
+    let mut video_info =
+        super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
+    for track in &mut video_info.subtitle_tracks {
+        if IMAGE_CODECS.contains(&track.codec.as_str()) {
+            continue;
+        }
+        match super::subtitle::preview_subtitle_track(
+            app.clone(),
+            video_path.clone(),
+            track.index,
+            Some(SAMPLE_LINES),
+            ffmpeg_path.clone(),
+        )
+        .await
+        {
+            Ok(preview) => {
+                track.detected_language =
+                    detect_language(preview.lines.iter().map(|line| line.text.as_str()));
+            }
+            Err(e) => warn!(
+                "Language detection skipped track {} of {}: {}",
+                track.index, video_path, e
+            ),
+        }
+    }
+    Ok(video_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_dialog_languages() {
+        let english = [
+            "I told you we should have left the village before nightfall.",
+            "The demon king has been dead for eighty years, you know.",
+            "Then why does everyone still look so afraid of the forest?",
+        ];
+        let portuguese = [
+            "Eu te disse que devíamos ter saído da vila antes do anoitecer.",
+            "O rei demônio está morto há oitenta anos, sabia?",
+            "Então por que todo mundo ainda parece ter tanto medo da floresta?",
+        ];
+
+        assert_eq!(detect_language(english).as_deref(), Some("eng"));
+        assert_eq!(detect_language(portuguese).as_deref(), Some("por"));
+        assert_eq!(detect_language(["Ok"]), None);
+
+        let tagged = english.map(|line| format!("{{\\an8\\fs40}}{}\\N{{\\i1}}", line));
+        assert_eq!(
+            detect_language(tagged.iter().map(String::as_str)).as_deref(),
+            Some("eng")
+        );
+    }
+
+    #[test]
+    fn compares_detected_codes_with_job_languages() {
+        assert!(is_language("por", "pt-BR"));
+        assert!(is_language("eng", "en"));
+        assert!(is_language("zho", "zh"));
+        assert!(is_language("per", "fa"));
+        assert!(is_language("tur", "tr"));
+        assert!(is_language("vie", "vi"));
+        assert!(is_language("ind", "id"));
+        assert!(is_language("pol", "pl"));
+        assert!(is_language("tur", "tur"));
+        assert!(!is_language("eng", "pt"));
+        assert!(!is_language("und", ""));
+    }
+}
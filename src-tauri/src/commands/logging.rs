@@ -0,0 +1,290 @@
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::write_file_atomic;
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::field::{Field, Visit};
+use tracing::{info, warn, Event, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+const LOG_SETTINGS_FILE: &str = "logging.json";
+const LOG_DIR: &str = "logs";
+const LOG_FILE_PREFIX: &str = "animesubs";
+/// Daily log files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+const DEFAULT_LOG_LEVEL: &str = "warn,animesubs_lib=info";
+/// Entries kept in memory for `tail_logs`.
+const RECENT_CAPACITY: usize = 1000;
+/// Event carrying each new `LogEntry` to the GUI log pane.
+pub(crate) const LOG_EVENT: &str = "backend-log";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+/// Flushes the file writer on drop, so it lives as long as the process.
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+static RECENT: LazyLock<Mutex<VecDeque<LogEntry>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(RECENT_CAPACITY)));
+
+fn log_dir(app: &AppHandle) -> Result<PathBuf, AnimeSubsError> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to resolve app data directory: {}", e)))?
+        .join(LOG_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create log directory: {}", e)))?;
+    Ok(dir)
+}
+
+fn parse_log_filter(level: &str) -> Result<EnvFilter, AnimeSubsError> {
+    let level = level.trim();
+    if level.is_empty() {
+        return Err(AnimeSubsError::parse("Log level is empty"));
+    }
+    EnvFilter::try_new(level)
+        .map_err(|e| AnimeSubsError::parse(format!("Invalid log level '{}': {}", level, e)))
+}
+
+fn read_log_level(app: &AppHandle) -> Result<String, AnimeSubsError> {
+    let path = app_config_path(app, LOG_SETTINGS_FILE)?;
+    if !path.exists() {
+        return Ok(DEFAULT_LOG_LEVEL.to_string());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read log settings: {}", e)))?;
+    let settings: LogSettings = serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse log settings: {}", e)))?;
+    Ok(settings.level)
+}
+
+/// Collects an event's message and fields into one line.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.message, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+fn push_recent(entry: LogEntry) {
+    let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    if recent.len() == RECENT_CAPACITY {
+        recent.pop_front();
+    }
+    recent.push_back(entry);
+}
+
+fn recent_entries(limit: usize) -> Vec<LogEntry> {
+    let recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+    recent
+        .iter()
+        .skip(recent.len().saturating_sub(limit))
+        .cloned()
+        .collect()
+}
+
+/// One `timestamp LEVEL target: message` line per entry, as in the log files.
+fn format_log_lines(entries: &[LogEntry]) -> String {
+    let mut text = String::new();
+    for entry in entries {
+        let _ = writeln!(
+            text,
+            "{} {:>5} {}: {}",
+            entry.timestamp, entry.level, entry.target, entry.message
+        );
+    }
+    text
+}
+
+/// Keeps the latest events for `tail_logs` and streams each one to the GUI.
+struct LogPaneLayer {
+    app: AppHandle,
+}
+
+impl<S: Subscriber> Layer<S> for LogPaneLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let entry = LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+        };
+        push_recent(entry.clone());
+        let _ = self.app.emit(LOG_EVENT, &entry);
+    }
+}
+
+/// Installs the global subscriber: stderr, a daily rolling file in the app data
+/// dir and the GUI log pane, all behind the saved (reloadable) level filter.
+/// Called once at startup, before anything logs.
+pub(crate) fn init_logging(app: &AppHandle) -> Result<(), AnimeSubsError> {
+    let saved_level = read_log_level(app);
+    let filter = saved_level
+        .as_ref()
+        .ok()
+        .and_then(|level| parse_log_filter(level).ok())
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_LOG_LEVEL));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir(app)?)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to open log file: {}", e)))?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    // Only this crate's events reach the pane; emitting them must not log again.
+    let own_events = filter_fn(|metadata| metadata.target().starts_with(env!("CARGO_CRATE_NAME")));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(file_writer)
+                .with_ansi(false),
+        )
+        .with(LogPaneLayer { app: app.clone() }.with_filter(own_events))
+        .try_init()
+        .map_err(|e| format!("Failed to install logger: {}", e))?;
+
+    let _ = FILTER_HANDLE.set(handle);
+    let _ = FILE_GUARD.set(guard);
+    if let Err(e) = saved_level {
+        warn!("Using default log level: {}", e);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_log_settings(app: AppHandle) -> Result<LogSettings, AnimeSubsError> {
+    Ok(LogSettings {
+        level: read_log_level(&app)?,
+        log_dir: Some(log_dir(&app)?.to_string_lossy().to_string()),
+    })
+}
+
+/// Changes the level filter of every log sink without a restart and saves it.
+/// Accepts a plain level (`debug`) or per-module directives
+/// (`info,animesubs_lib::providers=trace`).
+#[tauri::command]
+pub async fn set_log_level(app: AppHandle, level: String) -> Result<LogSettings, AnimeSubsError> {
+    let level = level.trim().to_string();
+    let filter = parse_log_filter(&level)?;
+    if let Some(handle) = FILTER_HANDLE.get() {
+        handle
+            .reload(filter)
+            .map_err(|e| format!("Failed to change log level: {}", e))?;
+    }
+
+    let settings = LogSettings {
+        level,
+        log_dir: None,
+    };
+    let data = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize log settings: {}", e))?;
+    write_file_atomic(&app_config_path(&app, LOG_SETTINGS_FILE)?, data.as_bytes())?;
+    info!("Log level set to {}", settings.level);
+
+    Ok(LogSettings {
+        log_dir: Some(log_dir(&app)?.to_string_lossy().to_string()),
+        ..settings
+    })
+}
+
+/// Latest backend log entries, oldest first; new ones arrive on `backend-log`.
+#[tauri::command]
+pub async fn tail_logs(limit: Option<usize>) -> Result<Vec<LogEntry>, AnimeSubsError> {
+    Ok(recent_entries(limit.unwrap_or(200).min(RECENT_CAPACITY)))
+}
+
+/// Writes the entries shown in the log pane (already filtered by the GUI) as a
+/// plain text file.
+#[tauri::command]
+pub async fn export_logs(
+    path: String,
+    entries: Vec<LogEntry>,
+) -> Result<OperationResult, AnimeSubsError> {
+    write_file_atomic(Path::new(&path), format_log_lines(&entries).as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Exported {} log entries", entries.len()),
+        data: Some(path),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: String::new(),
+            level: "INFO".to_string(),
+            target: "animesubs_lib".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn keeps_the_latest_entries() {
+        for i in 0..RECENT_CAPACITY + 5 {
+            push_recent(entry(&i.to_string()));
+        }
+
+        let tail = recent_entries(3);
+        let messages: Vec<_> = tail.iter().map(|e| e.message.as_str()).collect();
+        let last = RECENT_CAPACITY + 4;
+        assert_eq!(
+            messages,
+            [
+                (last - 2).to_string(),
+                (last - 1).to_string(),
+                last.to_string()
+            ]
+        );
+        assert_eq!(recent_entries(usize::MAX).len(), RECENT_CAPACITY);
+    }
+
+    #[test]
+    fn accepts_levels_and_module_directives() {
+        assert!(parse_log_filter("debug").is_ok());
+        assert!(parse_log_filter("warn,animesubs_lib::providers=trace").is_ok());
+        assert_eq!(parse_log_filter("  ").unwrap_err().code(), "PARSE_ERROR");
+        assert!(parse_log_filter("animesubs_lib=loud").is_err());
+    }
+
+    #[test]
+    fn formats_one_line_per_entry() {
+        let mut warning = entry("Rate limited");
+        warning.level = "WARN".to_string();
+        let text = format_log_lines(&[entry("Started"), warning]);
+
+        assert_eq!(
+            text,
+            " INFO animesubs_lib: Started\n WARN animesubs_lib: Rate limited\n"
+        );
+    }
+}
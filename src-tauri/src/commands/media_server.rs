@@ -0,0 +1,245 @@
+use super::secrets::{read_api_key, write_api_key};
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::http::{self, http_client};
+use crate::utils::write_file_atomic;
+use reqwest::RequestBuilder;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+use tracing::{info, warn};
+
+const MEDIA_SERVER_FILE: &str = "media_server.json";
+/// Keyring entry holding the Jellyfin API key or Plex token.
+const MEDIA_SERVER_SECRET: &str = "media-server";
+
+fn read_media_server_settings(app: &AppHandle) -> Result<MediaServerSettings, AnimeSubsError> {
+    let path = app_config_path(app, MEDIA_SERVER_FILE)?;
+    if !path.exists() {
+        return Ok(MediaServerSettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read media server settings: {}", e)))?;
+    let mut settings: MediaServerSettings = serde_json::from_str(&content).map_err(|e| {
+        AnimeSubsError::parse(format!("Failed to parse media server settings: {}", e))
+    })?;
+    settings.api_key = Some(read_api_key(app, MEDIA_SERVER_SECRET)?).filter(|key| !key.is_empty());
+    Ok(settings)
+}
+
+fn endpoint(settings: &MediaServerSettings, path: &str) -> String {
+    format!("{}{}", settings.url.trim().trim_end_matches('/'), path)
+}
+
+fn authorize(request: RequestBuilder, settings: &MediaServerSettings) -> RequestBuilder {
+    let key = settings.api_key.as_deref().unwrap_or_default();
+    match settings.kind {
+        MediaServerKind::Jellyfin => request.header("X-Emby-Token", key),
+        MediaServerKind::Plex => request
+            .header("X-Plex-Token", key)
+            .header("Accept", "application/json"),
+        MediaServerKind::None => request,
+    }
+}
+
+async fn send(
+    request: RequestBuilder,
+    settings: &MediaServerSettings,
+    failure: &str,
+) -> Result<reqwest::Response, AnimeSubsError> {
+    let response = authorize(request, settings)
+        .send()
+        .await
+        .map_err(|e| AnimeSubsError::Provider(format!("{}: {}", failure, e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(http::status_error(
+            status,
+            None,
+            format!("{} ({}): {}", failure, status, body.trim()),
+        ));
+    }
+    Ok(response)
+}
+
+/// Body of Jellyfin's `/Library/Media/Updated`, which rescans the item at `path`.
+fn jellyfin_update(path: &str) -> Value {
+    json!({ "Updates": [{ "Path": path, "UpdateType": "Modified" }] })
+}
+
+/// Key of the Plex library whose folder holds `path`; the deepest folder wins
+/// when libraries are nested.
+fn plex_section_for(sections: &Value, path: &Path) -> Option<String> {
+    sections["MediaContainer"]["Directory"]
+        .as_array()?
+        .iter()
+        .flat_map(|section| {
+            section["Location"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(move |location| Some((section, location["path"].as_str()?)))
+        })
+        .filter(|(_, location)| path.starts_with(location))
+        .max_by_key(|(_, location)| location.len())
+        .and_then(|(section, _)| match &section["key"] {
+            Value::String(key) => Some(key.clone()),
+            Value::Number(key) => Some(key.to_string()),
+            _ => None,
+        })
+}
+
+async fn refresh(settings: &MediaServerSettings, path: &str) -> Result<(), AnimeSubsError> {
+    let client = http_client()?;
+    match settings.kind {
+        MediaServerKind::None => {}
+        MediaServerKind::Jellyfin => {
+            let request = client
+                .post(endpoint(settings, "/Library/Media/Updated"))
+                .json(&jellyfin_update(path));
+            send(request, settings, "Jellyfin refresh failed").await?;
+        }
+        MediaServerKind::Plex => {
+            let folder = Path::new(path).parent().unwrap_or(Path::new(path));
+            let sections: Value = send(
+                client.get(endpoint(settings, "/library/sections")),
+                settings,
+                "Plex library lookup failed",
+            )
+            .await?
+            .json()
+            .await
+            .map_err(|e| AnimeSubsError::parse(format!("Failed to parse Plex libraries: {}", e)))?;
+            let section = plex_section_for(&sections, folder).ok_or_else(|| {
+                AnimeSubsError::Other(format!("No Plex library contains {}", folder.display()))
+            })?;
+            let request = client
+                .get(endpoint(
+                    settings,
+                    &format!("/library/sections/{}/refresh", section),
+                ))
+                .query(&[("path", folder.to_string_lossy())]);
+            send(request, settings, "Plex refresh failed").await?;
+        }
+    }
+    Ok(())
+}
+
+/// Asks the configured media server to rescan `path` so a new subtitle shows up
+/// right away. Best effort: failures are logged and never fail the job.
+pub(crate) async fn refresh_library(app: &AppHandle, path: &str) {
+    let settings = match read_media_server_settings(app) {
+        Ok(settings) if settings.kind != MediaServerKind::None => settings,
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Failed to read media server settings: {}", e);
+            return;
+        }
+    };
+    match refresh(&settings, path).await {
+        Ok(()) => info!("Asked {:?} to rescan {}", settings.kind, path),
+        Err(e) => warn!("Media server refresh failed for {}: {}", path, e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_media_server_settings(
+    app: AppHandle,
+) -> Result<MediaServerSettings, AnimeSubsError> {
+    read_media_server_settings(&app)
+}
+
+#[tauri::command]
+pub async fn save_media_server_settings(
+    app: AppHandle,
+    settings: MediaServerSettings,
+) -> Result<OperationResult, AnimeSubsError> {
+    if settings.kind != MediaServerKind::None {
+        reqwest::Url::parse(settings.url.trim()).map_err(|e| {
+            AnimeSubsError::parse(format!(
+                "Invalid media server URL '{}': {}",
+                settings.url, e
+            ))
+        })?;
+    }
+
+    write_api_key(
+        &app,
+        MEDIA_SERVER_SECRET,
+        settings.api_key.as_deref().unwrap_or_default(),
+    )?;
+    let stored = MediaServerSettings {
+        api_key: None,
+        ..settings
+    };
+    let data = serde_json::to_string_pretty(&stored)
+        .map_err(|e| format!("Failed to serialize media server settings: {}", e))?;
+    write_file_atomic(&app_config_path(&app, MEDIA_SERVER_FILE)?, data.as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "Media server settings saved".to_string(),
+        data: None,
+        verification: None,
+    })
+}
+
+/// Checks the URL and key with a request that needs both.
+#[tauri::command]
+pub async fn test_media_server(
+    settings: MediaServerSettings,
+) -> Result<OperationResult, AnimeSubsError> {
+    let path = match settings.kind {
+        MediaServerKind::None => return Err("No media server selected".into()),
+        MediaServerKind::Jellyfin => "/System/Info",
+        MediaServerKind::Plex => "/library/sections",
+    };
+    send(
+        http_client()?.get(endpoint(&settings, path)),
+        &settings,
+        "Media server connection failed",
+    )
+    .await?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "Media server connection OK".to_string(),
+        data: None,
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_deepest_plex_library_holding_the_folder() {
+        let sections = json!({
+            "MediaContainer": {
+                "Directory": [
+                    { "key": "1", "Location": [{ "path": "/media" }] },
+                    { "key": "4", "Location": [{ "path": "/srv/tv" }, { "path": "/media/anime" }] },
+                    { "key": 7, "Location": [{ "path": "/media/anime-movies" }] }
+                ]
+            }
+        });
+
+        let section = |path: &str| plex_section_for(&sections, Path::new(path));
+        assert_eq!(section("/media/anime/Show/Season 1").as_deref(), Some("4"));
+        assert_eq!(section("/media/anime-movies/Film").as_deref(), Some("7"));
+        assert_eq!(section("/media/music").as_deref(), Some("1"));
+        assert_eq!(section("/elsewhere"), None);
+    }
+
+    #[test]
+    fn builds_the_jellyfin_update() {
+        assert_eq!(
+            jellyfin_update("/media/anime/ep01.mkv")["Updates"][0],
+            json!({ "Path": "/media/anime/ep01.mkv", "UpdateType": "Modified" })
+        );
+    }
+}
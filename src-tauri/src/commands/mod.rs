@@ -1,6 +1,50 @@
+pub mod anilist;
+pub mod audio;
+pub mod automation;
 pub mod backup;
+pub mod bootstrap;
+pub mod burn;
+pub mod capabilities;
+pub mod chapters;
+pub mod cleanup;
+pub mod connection;
+pub mod convert;
 pub mod embedding;
+pub mod estimate;
+pub mod fonts;
+pub mod history;
+pub mod honorifics;
+pub mod job_control;
+pub mod language;
+pub mod logging;
+pub mod media_server;
+pub mod names;
+pub mod naming;
+pub mod operations;
+pub mod parser_suite;
+pub mod presets;
+pub mod preview;
+pub mod project;
+pub mod prompts;
+pub mod proxy;
+pub mod release;
+pub mod reports;
+pub mod review;
+pub mod secrets;
+pub mod series;
+pub mod server;
+pub mod settings;
+pub mod sidecar;
+pub mod styles;
 pub mod subtitle;
+pub mod temp_files;
+pub mod timing;
+pub mod track_selection;
+pub mod transcription;
 pub mod translation;
 pub mod utils;
+pub mod validation;
+pub mod verify;
 pub mod video;
+pub mod watch;
+pub mod wrapping;
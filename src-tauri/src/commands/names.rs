@@ -0,0 +1,372 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tracing::warn;
+
+const NAME_TABLE_FILE: &str = ".animesubs-names.json";
+const MIN_OCCURRENCES: usize = 3;
+const GLOSSARY_HEADERS: [&str; 2] = ["term", "translation"];
+const STOPWORDS: [&str; 10] = [
+    "I", "OK", "Okay", "Oh", "Hey", "Mr", "Mrs", "Ms", "Dr", "God",
+];
+
+/// Capitalized words, optionally followed by a romanized honorific ("Okabe-san").
+static NAME_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(\p{Lu}\p{Ll}+)(-(?:san|kun|chan|sama|senpai|sensei|dono|tan))?\b").unwrap()
+});
+
+fn name_table_path(series_folder: &str) -> PathBuf {
+    Path::new(series_folder).join(NAME_TABLE_FILE)
+}
+
+fn is_sentence_start(text: &str, offset: usize) -> bool {
+    let before = text[..offset].trim_end_matches([' ', '"', '\'', '-', '–', '—', '¿', '¡']);
+    before.is_empty() || before.ends_with(['.', '!', '?', '…', '\n', ':'])
+}
+
+/// Counts likely character names: ASS Name/Actor fields, capitalized words inside
+/// sentences and anything carrying an honorific. Words that only ever start a
+/// sentence are ignored since they are usually ordinary words.
+fn count_names(lines: &[DialogLine]) -> HashMap<String, (usize, bool)> {
+    let mut counts: HashMap<String, (usize, bool)> = HashMap::new();
+
+    for line in lines {
+        if let Some(actor) = line
+            .name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            let entry = counts.entry(actor.to_string()).or_default();
+            entry.0 += 1;
+            entry.1 = true;
+        }
+
+        for captures in NAME_RE.captures_iter(&line.text) {
+            let word = &captures[1];
+            if STOPWORDS.contains(&word) {
+                continue;
+            }
+            let has_honorific = captures.get(2).is_some();
+            let offset = captures.get(0).map_or(0, |m| m.start());
+            let mid_sentence = !is_sentence_start(&line.text, offset);
+
+            let entry = counts.entry(word.to_string()).or_default();
+            entry.0 += 1;
+            entry.1 |= has_honorific || mid_sentence;
+        }
+    }
+
+    counts
+}
+
+/// Merges freshly counted names into `existing`: pinned spellings survive, counts
+/// are refreshed and unpinned names that no longer appear are dropped.
+fn merge_name_table(
+    existing: Vec<CharacterName>,
+    counts: HashMap<String, (usize, bool)>,
+) -> Vec<CharacterName> {
+    let mut table: Vec<CharacterName> = existing
+        .into_iter()
+        .filter_map(|mut name| {
+            let occurrences = counts.get(&name.source).map_or(0, |(count, _)| *count);
+            name.occurrences = occurrences;
+            (name.pinned || occurrences > 0).then_some(name)
+        })
+        .collect();
+
+    for (source, (occurrences, qualifies)) in counts {
+        if !qualifies || occurrences < MIN_OCCURRENCES {
+            continue;
+        }
+        if table.iter().any(|name| name.source == source) {
+            continue;
+        }
+        table.push(CharacterName {
+            canonical: source.clone(),
+            source,
+            occurrences,
+            pinned: false,
+        });
+    }
+
+    table.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then(b.occurrences.cmp(&a.occurrences))
+            .then(a.source.cmp(&b.source))
+    });
+    table
+}
+
+fn read_name_table(series_folder: &str) -> Result<Vec<CharacterName>, AnimeSubsError> {
+    let path = name_table_path(series_folder);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read name table: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse name table: {}", e)))
+}
+
+/// Builds a series-wide name table from several subtitle files, merged with the
+/// table already saved in `series_folder` when one is given.
+#[tauri::command]
+pub async fn analyze_character_names(
+    subtitle_paths: Vec<String>,
+    series_folder: Option<String>,
+) -> Result<Vec<CharacterName>, AnimeSubsError> {
+    let mut lines = Vec::new();
+    for path in subtitle_paths {
+        let data = super::subtitle::parse_subtitle_file(path).await?;
+        lines.extend(data.lines.into_iter().filter(|line| !line.skip));
+    }
+
+    let existing = match series_folder.as_deref() {
+        Some(folder) => read_name_table(folder)?,
+        None => Vec::new(),
+    };
+    Ok(merge_name_table(existing, count_names(&lines)))
+}
+
+/// Name-table suggestions for the videos of a job, read from the tracks the job
+/// would translate. Videos whose subtitles cannot be read are left out.
+#[tauri::command]
+pub async fn suggest_glossary_terms(
+    request: TranslationJobRequest,
+    existing: Vec<CharacterName>,
+) -> Result<Vec<CharacterName>, AnimeSubsError> {
+    let mut lines = Vec::new();
+    let mut first_error = None;
+    for video_path in &request.video_paths {
+        match super::estimate::job_source_subtitle(&request, video_path).await {
+            Ok(data) => lines.extend(data.lines.into_iter().filter(|line| !line.skip)),
+            Err(e) => {
+                warn!("Skipping {} for glossary suggestions: {}", video_path, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    if lines.is_empty() {
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+    }
+    Ok(merge_name_table(existing, count_names(&lines)))
+}
+
+/// Glossary entries from `term,translation` rows; a header row and rows without
+/// a term are skipped, and a repeated term keeps its last translation. Imported
+/// entries are pinned so re-analysis keeps them.
+fn glossary_from_rows(rows: Vec<Vec<String>>) -> Vec<CharacterName> {
+    let mut names: Vec<CharacterName> = Vec::new();
+    for (index, row) in rows.into_iter().enumerate() {
+        let mut cells = row.into_iter().map(|cell| cell.trim().to_string());
+        let source = cells.next().unwrap_or_default();
+        let canonical = cells.next().unwrap_or_default();
+        if source.is_empty() || (index == 0 && source.eq_ignore_ascii_case(GLOSSARY_HEADERS[0])) {
+            continue;
+        }
+        let canonical = if canonical.is_empty() {
+            source.clone()
+        } else {
+            canonical
+        };
+        names.retain(|name| name.source != source);
+        names.push(CharacterName {
+            source,
+            canonical,
+            occurrences: 0,
+            pinned: true,
+        });
+    }
+    names
+}
+
+fn glossary_to_csv(names: &[CharacterName]) -> Result<Vec<u8>, AnimeSubsError> {
+    let mut writer = csv::Writer::from_writer("\u{FEFF}".as_bytes().to_vec());
+    writer
+        .write_record(GLOSSARY_HEADERS)
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    for name in names {
+        writer
+            .write_record([&name.source, &name.canonical])
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e).into())
+}
+
+fn is_json(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Reads a glossary from a CSV (`term,translation`) or a JSON name table.
+#[tauri::command]
+pub async fn import_glossary(path: String) -> Result<Vec<CharacterName>, AnimeSubsError> {
+    let content = read_file_as_utf8(&path)?;
+    if is_json(&path) {
+        return serde_json::from_str(&content)
+            .map_err(|e| AnimeSubsError::parse(format!("Failed to parse glossary: {}", e)));
+    }
+    let names = glossary_from_rows(super::review::read_csv_table(&content)?);
+    if names.is_empty() {
+        return Err(AnimeSubsError::parse("Glossary file has no terms"));
+    }
+    Ok(names)
+}
+
+/// Writes the glossary as CSV, or as a JSON name table when `path` ends in `.json`.
+#[tauri::command]
+pub async fn export_glossary(
+    path: String,
+    names: Vec<CharacterName>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let data = if is_json(&path) {
+        serde_json::to_string_pretty(&names)
+            .map_err(|e| format!("Failed to serialize glossary: {}", e))?
+            .into_bytes()
+    } else {
+        glossary_to_csv(&names)?
+    };
+    write_file_atomic(Path::new(&path), &data)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Exported {} terms", names.len()),
+        data: Some(path),
+        verification: None,
+    })
+}
+
+#[tauri::command]
+pub async fn load_name_table(series_folder: String) -> Result<Vec<CharacterName>, AnimeSubsError> {
+    read_name_table(&series_folder)
+}
+
+#[tauri::command]
+pub async fn save_name_table(
+    series_folder: String,
+    names: Vec<CharacterName>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let path = name_table_path(&series_folder);
+    let data = serde_json::to_string_pretty(&names)
+        .map_err(|e| format!("Failed to serialize name table: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Saved {} names", names.len()),
+        data: Some(path.to_string_lossy().to_string()),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(text: &str, name: Option<&str>) -> DialogLine {
+        DialogLine {
+            index: 0,
+            text: text.to_string(),
+            original_with_formatting: text.to_string(),
+            start: "0:00:00.00".to_string(),
+            end: "0:00:01.00".to_string(),
+            style: None,
+            name: name.map(String::from),
+            reference: None,
+            formatting: None,
+            event: AssEventKind::Dialogue,
+            classification: LineClass::Dialogue,
+            skip: false,
+        }
+    }
+
+    #[test]
+    fn finds_recurring_names_and_keeps_pinned_spellings() {
+        let lines = vec![
+            line("Where is Kurisu?", Some("Okabe")),
+            line("Ask Kurisu, not me.", Some("Okabe")),
+            line("Thanks, Kurisu-san. Really.", Some("Okabe")),
+            line("Really? Really.", None),
+        ];
+        let existing = vec![
+            CharacterName {
+                source: "Kurisu".to_string(),
+                canonical: "Kurisu Makise".to_string(),
+                occurrences: 0,
+                pinned: true,
+            },
+            CharacterName {
+                source: "Daru".to_string(),
+                canonical: "Daru".to_string(),
+                occurrences: 9,
+                pinned: false,
+            },
+        ];
+
+        let table = merge_name_table(existing, count_names(&lines));
+        let summary: Vec<(&str, &str, usize)> = table
+            .iter()
+            .map(|n| (n.source.as_str(), n.canonical.as_str(), n.occurrences))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![("Kurisu", "Kurisu Makise", 3), ("Okabe", "Okabe", 3)]
+        );
+    }
+
+    #[test]
+    fn glossary_csv_round_trips_and_skips_header_and_blank_terms() {
+        let names = vec![
+            CharacterName {
+                source: "Himmel".to_string(),
+                canonical: "Himmel, the Hero".to_string(),
+                occurrences: 12,
+                pinned: false,
+            },
+            CharacterName {
+                source: "Mimic".to_string(),
+                canonical: "Mímico".to_string(),
+                occurrences: 0,
+                pinned: true,
+            },
+        ];
+        let csv = String::from_utf8(glossary_to_csv(&names).unwrap()).unwrap();
+        let imported =
+            glossary_from_rows(super::super::review::read_csv_table(&strip_utf8_bom(csv)).unwrap());
+        let pairs: Vec<(&str, &str, bool)> = imported
+            .iter()
+            .map(|n| (n.source.as_str(), n.canonical.as_str(), n.pinned))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("Himmel", "Himmel, the Hero", true),
+                ("Mimic", "Mímico", true)
+            ]
+        );
+
+        let rows = vec![
+            vec![" ".to_string(), "ignored".to_string()],
+            vec!["Fern".to_string()],
+            vec!["Stark".to_string(), "Stark".to_string()],
+            vec!["Fern".to_string(), "Fern-sama".to_string()],
+        ];
+        let table = glossary_from_rows(rows);
+        assert_eq!(table.len(), 2);
+        assert_eq!(table[1].source, "Fern");
+        assert_eq!(table[1].canonical, "Fern-sama");
+    }
+}
@@ -0,0 +1,216 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+const VARIABLES: [&str; 11] = [
+    "stem",
+    "title",
+    "season",
+    "episode",
+    "episode_title",
+    "lang",
+    "ext",
+    "track",
+    "group",
+    "resolution",
+    "date",
+];
+
+/// `{name}` or `{name:02}` (zero-padded to two digits).
+static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{([a-z_]+)(?::0(\d))?\}").unwrap());
+
+/// Values available to an output naming template.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct NamingVars {
+    pub stem: String,
+    pub release: ReleaseInfo,
+    pub episode_title: Option<String>,
+    pub lang: String,
+    pub ext: String,
+    pub track: Option<u32>,
+}
+
+impl NamingVars {
+    /// Naming values for `source_path`, with title/season/episode parsed from its name.
+    pub(crate) fn for_file(source_path: &str, lang: &str, ext: &str, track: Option<u32>) -> Self {
+        let stem = Path::new(source_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "subtitle".to_string());
+        Self {
+            stem,
+            release: super::release::parse_release_name(source_path),
+            episode_title: None,
+            lang: lang.to_string(),
+            ext: ext.to_string(),
+            track,
+        }
+    }
+
+    fn text(&self, name: &str) -> Option<String> {
+        match name {
+            "stem" => Some(self.stem.clone()),
+            "title" => Some(self.release.title.clone()).filter(|t| !t.is_empty()),
+            "episode_title" => self.episode_title.clone(),
+            "lang" => Some(self.lang.clone()),
+            "ext" => Some(self.ext.clone()),
+            "group" => self.release.release_group.clone(),
+            "resolution" => self.release.resolution.clone(),
+            "date" => Some(chrono::Local::now().format("%Y-%m-%d").to_string()),
+            _ => None,
+        }
+    }
+
+    fn number(&self, name: &str) -> Option<u32> {
+        match name {
+            // Files without a season marker are treated as the first season.
+            "season" => Some(self.release.season.unwrap_or(1)),
+            "episode" => self.release.episode,
+            "track" => self.track,
+            _ => None,
+        }
+    }
+}
+
+/// Checks placeholder names and braces without rendering.
+pub(crate) fn validate_template(template: &str) -> Result<(), AnimeSubsError> {
+    if template.trim().is_empty() {
+        return Err("Naming template is empty".into());
+    }
+    if template.contains(['/', '\\']) {
+        return Err("Naming template must not contain path separators".into());
+    }
+    for captures in PLACEHOLDER_RE.captures_iter(template) {
+        if !VARIABLES.contains(&&captures[1]) {
+            return Err(format!("Unknown naming variable: {{{}}}", &captures[1]).into());
+        }
+    }
+    let leftover = PLACEHOLDER_RE.replace_all(template, "");
+    if leftover.contains(['{', '}']) {
+        return Err(format!("Malformed placeholder in naming template: {}", template).into());
+    }
+    if !template.contains("{ext}") {
+        return Err("Naming template must include {ext}".into());
+    }
+    Ok(())
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    cleaned.trim().trim_end_matches(['.', ' ']).to_string()
+}
+
+/// Renders a file name such as "{title} - S{season:02}E{episode:02}.{lang}.{ext}".
+/// Fails when the template uses a value the file does not provide (e.g. no episode).
+pub(crate) fn render_output_name(
+    template: &str,
+    vars: &NamingVars,
+) -> Result<String, AnimeSubsError> {
+    validate_template(template)?;
+
+    let mut missing = None;
+    let rendered = PLACEHOLDER_RE.replace_all(template, |c: &regex::Captures| {
+        let name = &c[1];
+        let width = c.get(2).and_then(|w| w.as_str().parse::<usize>().ok());
+        let value = match vars.number(name) {
+            Some(number) => Some(format!("{:0width$}", number, width = width.unwrap_or(0))),
+            None => vars.text(name),
+        };
+        value.unwrap_or_else(|| {
+            missing.get_or_insert_with(|| name.to_string());
+            String::new()
+        })
+    });
+    if let Some(name) = missing {
+        return Err(format!("No value for {{{}}} in {}", name, vars.stem).into());
+    }
+
+    let name = sanitize_file_name(&rendered);
+    if name.is_empty() {
+        return Err("Naming template produced an empty file name".into());
+    }
+    Ok(name)
+}
+
+#[tauri::command]
+pub async fn validate_naming_template(template: String) -> Result<OperationResult, AnimeSubsError> {
+    validate_template(&template)?;
+    Ok(OperationResult {
+        success: true,
+        message: "Naming template is valid".to_string(),
+        data: None,
+        verification: None,
+    })
+}
+
+/// Shows the file name `template` would produce for `source_path`.
+#[tauri::command]
+pub async fn preview_output_name(
+    template: String,
+    source_path: String,
+    lang: Option<String>,
+    ext: Option<String>,
+    track_index: Option<u32>,
+) -> Result<String, AnimeSubsError> {
+    let vars = NamingVars::for_file(
+        &source_path,
+        lang.as_deref().unwrap_or("und"),
+        ext.as_deref().unwrap_or("ass"),
+        track_index,
+    );
+    render_output_name(&template, &vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_episode_templates() {
+        let vars = NamingVars::for_file(
+            "/anime/[SubsPlease] Sousou no Frieren - 05 (1080p).mkv",
+            "pt-BR",
+            "ass",
+            Some(2),
+        );
+
+        assert_eq!(
+            render_output_name("{title} - S{season:02}E{episode:02}.{lang}.{ext}", &vars).unwrap(),
+            "Sousou no Frieren - S01E05.pt-BR.ass"
+        );
+        assert_eq!(
+            render_output_name("{stem}.translated.{ext}", &vars).unwrap(),
+            "[SubsPlease] Sousou no Frieren - 05 (1080p).translated.ass"
+        );
+        assert_eq!(
+            render_output_name("{title}: {episode_title}.{ext}", &vars)
+                .unwrap_err()
+                .to_string(),
+            "No value for {episode_title} in [SubsPlease] Sousou no Frieren - 05 (1080p)"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        assert!(validate_template("{stem}.{ext}").is_ok());
+        assert!(validate_template("{stem}.{language}.{ext}")
+            .unwrap_err()
+            .to_string()
+            .contains("{language}"));
+        assert!(validate_template("{stem.{ext}").is_err());
+        assert!(validate_template("subs/{stem}.{ext}").is_err());
+        assert!(validate_template("{stem}.srt").is_err());
+    }
+}
@@ -0,0 +1,257 @@
+use super::history::{command_outcome, command_parameters, record_operation};
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::runtime::RuntimeFlavor;
+
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+
+struct RunningOperation {
+    child: Child,
+    temp_paths: Vec<PathBuf>,
+}
+
+/// Child processes of running ffmpeg/mkvmerge operations, keyed by operation id.
+#[derive(Default)]
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<String, RunningOperation>>,
+}
+
+impl OperationRegistry {
+    fn insert(&self, operation_id: &str, operation: RunningOperation) {
+        if let Ok(mut operations) = self.operations.lock() {
+            operations.insert(operation_id.to_string(), operation);
+        }
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        self.operations
+            .lock()
+            .map(|operations| operations.is_empty())
+            .unwrap_or(false)
+    }
+
+    fn take(&self, operation_id: &str) -> Option<RunningOperation> {
+        self.operations
+            .lock()
+            .ok()
+            .and_then(|mut operations| operations.remove(operation_id))
+    }
+}
+
+fn next_operation_id() -> String {
+    format!(
+        "op-{}-{}",
+        chrono::Local::now().timestamp_millis(),
+        NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Runs `f`, which blocks on a child process, without stalling the async tasks that
+/// share the current runtime thread.
+fn block_on_process<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// Spawns `command`, registers it as a cancellable operation and feeds each stdout
+/// line to `on_stdout_line`. `temp_paths` are removed if the operation is cancelled.
+/// Every run is written to the operation history.
+pub fn run_tracked_command(
+    app: &AppHandle,
+    command: Command,
+    operation: &str,
+    video_path: &str,
+    temp_paths: Vec<PathBuf>,
+    on_stdout_line: impl FnMut(&str, &str),
+) -> std::io::Result<Output> {
+    let operation_id = next_operation_id();
+    let parameters = command_parameters(&command);
+    let started_at = chrono::Local::now();
+    let started = Instant::now();
+
+    let result = block_on_process(|| {
+        run_registered_command(
+            app,
+            command,
+            &operation_id,
+            operation,
+            video_path,
+            temp_paths,
+            on_stdout_line,
+        )
+    });
+
+    let (outcome, message) = command_outcome(&result);
+    record_operation(
+        app,
+        &HistoryEntry {
+            id: operation_id,
+            operation: operation.to_string(),
+            video_path: video_path.to_string(),
+            started_at: started_at.to_rfc3339(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            outcome,
+            message,
+            parameters,
+        },
+    );
+    result
+}
+
+fn run_registered_command(
+    app: &AppHandle,
+    mut command: Command,
+    operation_id: &str,
+    operation: &str,
+    video_path: &str,
+    temp_paths: Vec<PathBuf>,
+    mut on_stdout_line: impl FnMut(&str, &str),
+) -> std::io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child.stdout.take();
+    let stderr_reader = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stderr.read_to_end(&mut buffer);
+            buffer
+        })
+    });
+
+    let registry = app.state::<OperationRegistry>();
+    registry.insert(operation_id, RunningOperation { child, temp_paths });
+    let _ = app.emit(
+        "operation-started",
+        OperationStarted {
+            operation_id: operation_id.to_string(),
+            operation: operation.to_string(),
+            video_path: video_path.to_string(),
+        },
+    );
+
+    let mut stdout_buffer = Vec::new();
+    if let Some(stdout) = stdout {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            on_stdout_line(operation_id, &line);
+            stdout_buffer.extend_from_slice(line.as_bytes());
+            stdout_buffer.push(b'\n');
+        }
+    }
+
+    let Some(mut running) = registry.take(operation_id) else {
+        return Err(std::io::Error::new(
+            ErrorKind::Interrupted,
+            "Operation cancelled",
+        ));
+    };
+
+    let status = running.child.wait()?;
+    let stderr = stderr_reader
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    Ok(Output {
+        status,
+        stdout: stdout_buffer,
+        stderr,
+    })
+}
+
+/// Runs ffmpeg with `-progress pipe:1` and emits `ffmpeg-progress` events while it works.
+pub fn run_ffmpeg_with_progress(
+    app: &AppHandle,
+    ffmpeg: &str,
+    args: &[String],
+    operation: &str,
+    video_path: &str,
+    duration: Option<f64>,
+    temp_paths: Vec<PathBuf>,
+) -> std::io::Result<Output> {
+    let mut command = create_command(ffmpeg);
+    command.args(["-progress", "pipe:1", "-nostats"]).args(args);
+
+    let mut processed_seconds = 0.0;
+    run_tracked_command(
+        app,
+        command,
+        operation,
+        video_path,
+        temp_paths,
+        |operation_id, line| {
+            if let Some(seconds) = parse_ffmpeg_progress_time(line) {
+                processed_seconds = seconds;
+            } else if line.starts_with("progress=") {
+                let progress = duration
+                    .filter(|d| *d > 0.0)
+                    .map(|d| (processed_seconds / d * 100.0).clamp(0.0, 100.0));
+                let _ = app.emit(
+                    "ffmpeg-progress",
+                    FfmpegProgress {
+                        operation_id: operation_id.to_string(),
+                        operation: operation.to_string(),
+                        video_path: video_path.to_string(),
+                        processed_seconds,
+                        duration,
+                        progress,
+                        done: line.trim() == "progress=end",
+                    },
+                );
+            }
+        },
+    )
+}
+
+#[tauri::command]
+pub async fn cancel_operation(
+    registry: State<'_, OperationRegistry>,
+    operation_id: String,
+) -> Result<OperationResult, AnimeSubsError> {
+    let Some(mut running) = registry.take(&operation_id) else {
+        return Ok(OperationResult {
+            success: false,
+            message: "Operation is not running".to_string(),
+            data: Some(operation_id),
+            verification: None,
+        });
+    };
+
+    running
+        .child
+        .kill()
+        .map_err(|e| format!("Failed to cancel operation: {}", e))?;
+    let _ = running.child.wait();
+
+    for path in &running.temp_paths {
+        if path.exists() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    Ok(OperationResult {
+        success: true,
+        message: "Operation cancelled".to_string(),
+        data: Some(operation_id),
+        verification: None,
+    })
+}
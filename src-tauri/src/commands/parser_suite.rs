@@ -0,0 +1,125 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const SUBTITLE_EXTENSIONS: [&str; 5] = ["ass", "ssa", "srt", "vtt", "webvtt"];
+const MAX_DIFF_SAMPLES: usize = 20;
+
+/// Lines that are present in `left` but not in `right`, counting duplicates.
+/// Blank lines and trailing whitespace are ignored since writers re-space blocks.
+fn missing_lines(left: &str, right: &str) -> Vec<String> {
+    let mut remaining: HashMap<&str, usize> = HashMap::new();
+    for line in right.lines().map(str::trim_end).filter(|l| !l.is_empty()) {
+        *remaining.entry(line).or_insert(0) += 1;
+    }
+
+    left.lines()
+        .map(str::trim_end)
+        .filter(|l| !l.is_empty())
+        .filter(|line| match remaining.get_mut(line) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .map(String::from)
+        .collect()
+}
+
+async fn check_round_trip(path: &Path) -> ParserSuiteFileReport {
+    let path_str = path.to_string_lossy().to_string();
+    let format = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let result: Result<(usize, String, String), AnimeSubsError> = async {
+        let original = read_file_as_utf8(&path_str)?;
+        let data = super::subtitle::parse_subtitle_file(path_str.clone()).await?;
+        let rebuilt =
+            super::translation::reconstruct_subtitle_content(&data, Some(original.as_str()))?;
+        Ok((data.line_count, original, rebuilt))
+    }
+    .await;
+
+    match result {
+        Ok((parsed_lines, original, rebuilt)) => {
+            let lost = missing_lines(&original, &rebuilt);
+            let added = missing_lines(&rebuilt, &original);
+            ParserSuiteFileReport {
+                path: path_str,
+                format,
+                parsed_lines,
+                lost_count: lost.len(),
+                added_count: added.len(),
+                round_trip_ok: lost.is_empty() && added.is_empty(),
+                lost_samples: lost.into_iter().take(MAX_DIFF_SAMPLES).collect(),
+                added_samples: added.into_iter().take(MAX_DIFF_SAMPLES).collect(),
+                error: None,
+            }
+        }
+        Err(error) => ParserSuiteFileReport {
+            path: path_str,
+            format,
+            parsed_lines: 0,
+            lost_count: 0,
+            added_count: 0,
+            lost_samples: Vec::new(),
+            added_samples: Vec::new(),
+            error: Some(error.to_string()),
+            round_trip_ok: false,
+        },
+    }
+}
+
+/// Parses and rebuilds every subtitle file in `folder` without translating,
+/// reporting lines the pipeline would drop or rewrite.
+#[tauri::command]
+pub async fn run_parser_suite(folder: String) -> Result<ParserSuiteReport, AnimeSubsError> {
+    let entries = fs::read_dir(&folder)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read directory: {}", e)))?;
+
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                    .is_some_and(|e| SUBTITLE_EXTENSIONS.contains(&e.as_str()))
+        })
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::with_capacity(paths.len());
+    for path in &paths {
+        files.push(check_round_trip(path).await);
+    }
+
+    let passed = files.iter().filter(|f| f.round_trip_ok).count();
+    Ok(ParserSuiteReport {
+        folder,
+        passed,
+        failed: files.len() - passed,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_missing_lines_with_duplicates_and_ignores_spacing() {
+        let original = "1\nHello  \n\n2\nHello\n\n3\nBye\n";
+        let rebuilt = "1\nHello\n2\nBye\n";
+
+        assert_eq!(missing_lines(original, rebuilt), vec!["Hello", "3"]);
+        assert!(missing_lines(rebuilt, original).is_empty());
+    }
+}
@@ -0,0 +1,270 @@
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::fs;
+use tauri::AppHandle;
+
+const PRESETS_FILE: &str = "presets.json";
+const PRESET_BUNDLE_VERSION: u32 = 1;
+
+pub(crate) fn read_presets(app: &AppHandle) -> Result<Vec<Preset>, AnimeSubsError> {
+    let path = app_config_path(app, PRESETS_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read presets: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse presets: {}", e)))
+}
+
+fn write_presets(app: &AppHandle, presets: &[Preset]) -> Result<(), AnimeSubsError> {
+    let path = app_config_path(app, PRESETS_FILE)?;
+    let data = serde_json::to_string_pretty(presets)
+        .map_err(|e| format!("Failed to serialize presets: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())
+}
+
+/// Trims the name and drops API keys from the config and its fallbacks; keys stay
+/// in the OS keyring and must not end up in a shared file.
+fn prepare_preset(mut preset: Preset) -> Result<Preset, AnimeSubsError> {
+    preset.name = preset.name.trim().to_string();
+    if preset.name.is_empty() {
+        return Err("Preset name is required".into());
+    }
+    let mut configs = vec![&mut preset.config];
+    while let Some(config) = configs.pop() {
+        config.api_key.clear();
+        configs.extend(config.fallbacks.iter_mut());
+    }
+    Ok(preset)
+}
+
+/// Adds `incoming` to `presets`, replacing same-named ones only with `overwrite`.
+/// Returns the names imported and the names skipped.
+fn merge_presets(
+    presets: &mut Vec<Preset>,
+    incoming: Vec<Preset>,
+    overwrite: bool,
+) -> (Vec<String>, Vec<String>) {
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for preset in incoming {
+        match presets
+            .iter_mut()
+            .find(|existing| existing.name == preset.name)
+        {
+            Some(_) if !overwrite => skipped.push(preset.name),
+            Some(existing) => {
+                imported.push(preset.name.clone());
+                *existing = preset;
+            }
+            None => {
+                imported.push(preset.name.clone());
+                presets.push(preset);
+            }
+        }
+    }
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    (imported, skipped)
+}
+
+/// Accepts a file written by `export_presets` or a single preset.
+fn parse_preset_file(content: &str) -> Result<Vec<Preset>, AnimeSubsError> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse preset file: {}", e)))?;
+    let presets = if value.get("presets").is_some() {
+        let bundle: PresetBundle = serde_json::from_value(value)
+            .map_err(|e| AnimeSubsError::parse(format!("Invalid preset file: {}", e)))?;
+        if bundle.version > PRESET_BUNDLE_VERSION {
+            return Err(format!(
+                "Presets were exported by a newer version (format {})",
+                bundle.version
+            )
+            .into());
+        }
+        bundle.presets
+    } else {
+        vec![serde_json::from_value(value)
+            .map_err(|e| AnimeSubsError::parse(format!("Invalid preset: {}", e)))?]
+    };
+    presets.into_iter().map(prepare_preset).collect()
+}
+
+#[tauri::command]
+pub async fn list_presets(app: AppHandle) -> Result<Vec<Preset>, AnimeSubsError> {
+    read_presets(&app)
+}
+
+/// Creates the preset or replaces the one with the same name.
+#[tauri::command]
+pub async fn save_preset(
+    app: AppHandle,
+    preset: Preset,
+) -> Result<OperationResult, AnimeSubsError> {
+    let preset = prepare_preset(preset)?;
+    let name = preset.name.clone();
+    let mut presets = read_presets(&app)?;
+    merge_presets(&mut presets, vec![preset], true);
+    write_presets(&app, &presets)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Saved preset {}", name),
+        data: Some(name),
+        verification: None,
+    })
+}
+
+#[tauri::command]
+pub async fn delete_preset(
+    app: AppHandle,
+    name: String,
+) -> Result<OperationResult, AnimeSubsError> {
+    let mut presets = read_presets(&app)?;
+    let count = presets.len();
+    presets.retain(|preset| preset.name != name);
+    if presets.len() == count {
+        return Err(format!("Preset not found: {}", name).into());
+    }
+    write_presets(&app, &presets)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Deleted preset {}", name),
+        data: None,
+        verification: None,
+    })
+}
+
+/// Writes the named presets (all of them when `names` is empty) to `output_path`.
+#[tauri::command]
+pub async fn export_presets(
+    app: AppHandle,
+    output_path: String,
+    names: Option<Vec<String>>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let names = names.unwrap_or_default();
+    let presets: Vec<Preset> = read_presets(&app)?
+        .into_iter()
+        .filter(|preset| names.is_empty() || names.contains(&preset.name))
+        .collect();
+    if presets.is_empty() {
+        return Err("No presets to export".into());
+    }
+
+    let count = presets.len();
+    let bundle = PresetBundle {
+        version: PRESET_BUNDLE_VERSION,
+        presets,
+    };
+    let data = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize presets: {}", e))?;
+    write_file_atomic(std::path::Path::new(&output_path), data.as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Exported {} presets", count),
+        data: Some(output_path),
+        verification: None,
+    })
+}
+
+/// Adds the presets in `path`; ones named like an existing preset are skipped
+/// unless `overwrite` is set.
+#[tauri::command]
+pub async fn import_presets(
+    app: AppHandle,
+    path: String,
+    overwrite: bool,
+) -> Result<OperationResult, AnimeSubsError> {
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read preset file: {}", e)))?;
+    let incoming = parse_preset_file(&content)?;
+
+    let mut presets = read_presets(&app)?;
+    let (imported, skipped) = merge_presets(&mut presets, incoming, overwrite);
+    if !imported.is_empty() {
+        write_presets(&app, &presets)?;
+    }
+
+    let mut message = format!("Imported {} presets", imported.len());
+    if !skipped.is_empty() {
+        message.push_str(&format!(" (skipped existing: {})", skipped.join(", ")));
+    }
+    Ok(OperationResult {
+        success: !imported.is_empty(),
+        message,
+        data: Some(imported.join(",")),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, model: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            config: LLMConfig {
+                provider: "gemini".to_string(),
+                api_key: "AIza-secret".to_string(),
+                model: model.to_string(),
+                fallbacks: vec![LLMConfig {
+                    provider: "ollama".to_string(),
+                    api_key: "fallback-secret".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            source_lang: "en".to_string(),
+            target_lang: "pt-BR".to_string(),
+            style: Some("natural".to_string()),
+            rate_limit: None,
+            max_parallel_files: None,
+            strip_sdh: None,
+            honorific_second_pass: false,
+        }
+    }
+
+    #[test]
+    fn strips_api_keys_from_shared_presets() {
+        let bundle = PresetBundle {
+            version: PRESET_BUNDLE_VERSION,
+            presets: vec![preset(" Gemini fast draft ", "gemini-2.0-flash")],
+        };
+        let content = serde_json::to_string(&bundle).unwrap();
+
+        let presets = parse_preset_file(&content).unwrap();
+        assert_eq!(presets[0].name, "Gemini fast draft");
+        assert!(presets[0].config.api_key.is_empty());
+        assert!(presets[0].config.fallbacks[0].api_key.is_empty());
+
+        let single = serde_json::to_string(&preset("Local Qwen", "qwen2.5")).unwrap();
+        assert_eq!(parse_preset_file(&single).unwrap()[0].name, "Local Qwen");
+
+        let newer = serde_json::json!({ "version": 99, "presets": [] }).to_string();
+        assert!(parse_preset_file(&newer).is_err());
+    }
+
+    #[test]
+    fn imports_skip_or_replace_existing_names() {
+        let mut presets = vec![preset("Draft", "old")];
+
+        let (imported, skipped) = merge_presets(
+            &mut presets,
+            vec![preset("Draft", "new"), preset("Archive", "m")],
+            false,
+        );
+        assert_eq!(imported, ["Archive"]);
+        assert_eq!(skipped, ["Draft"]);
+        assert_eq!(presets[1].config.model, "old");
+
+        let (imported, _) = merge_presets(&mut presets, vec![preset("Draft", "new")], true);
+        assert_eq!(imported, ["Draft"]);
+        assert_eq!(presets.len(), 2);
+        assert_eq!(presets[1].config.model, "new");
+    }
+}
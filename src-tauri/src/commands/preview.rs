@@ -0,0 +1,108 @@
+use super::burn::build_subtitle_filter;
+use super::temp_files::TempFileRegistry;
+use super::translation::reconstruct_subtitle_content;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::env;
+use tauri::ipc::Response;
+use tauri::AppHandle;
+
+/// Width the preview frame is scaled down to; subtitles are rendered before scaling.
+const PREVIEW_WIDTH: u32 = 1280;
+
+fn build_preview_args(video_path: &str, time_seconds: f64, filter: Option<&str>) -> Vec<String> {
+    let mut video_filter = filter.map(|f| format!("{},", f)).unwrap_or_default();
+    video_filter.push_str(&format!("scale='min({},iw)':-2", PREVIEW_WIDTH));
+
+    vec![
+        "-v".to_string(),
+        "error".to_string(),
+        "-ss".to_string(),
+        format!("{:.3}", time_seconds.max(0.0)),
+        // Keeps the seeked frame's original timestamp so the filter shows the cue due at that time.
+        "-copyts".to_string(),
+        "-i".to_string(),
+        ffmpeg_file(video_path),
+        "-map".to_string(),
+        "0:v:0".to_string(),
+        "-vf".to_string(),
+        video_filter,
+        "-frames:v".to_string(),
+        "1".to_string(),
+        "-f".to_string(),
+        "image2pipe".to_string(),
+        "-c:v".to_string(),
+        "png".to_string(),
+        "pipe:1".to_string(),
+    ]
+}
+
+/// Renders one PNG frame of the video at `time_seconds` with `subtitle_data` drawn on top,
+/// the way libass will show it once the subtitles are embedded.
+#[tauri::command]
+pub async fn render_preview_frame(
+    app: AppHandle,
+    video_path: String,
+    time_seconds: f64,
+    subtitle_data: Option<SubtitleData>,
+    ffmpeg_path: Option<String>,
+) -> Result<Response, AnimeSubsError> {
+    let mut temp_files = TempFileRegistry::new(&app);
+    let filter = match &subtitle_data {
+        Some(data) => {
+            let content = reconstruct_subtitle_content(data, None)?;
+            let subtitle_path = temp_files.track(env::temp_dir().join(format!(
+                "animesubs-preview-{}.{}",
+                std::process::id(),
+                data.format
+            )));
+            write_utf8_file(&subtitle_path.to_string_lossy(), &content, false)?;
+            Some(build_subtitle_filter(
+                &video_path,
+                Some(&subtitle_path.to_string_lossy()),
+                None,
+            )?)
+        }
+        None => None,
+    };
+
+    let output = create_command(get_ffmpeg_path(ffmpeg_path))
+        .args(build_preview_args(
+            &video_path,
+            time_seconds,
+            filter.as_deref(),
+        ))
+        .output()
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(format!(
+            "Failed to render preview frame: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(Response::new(output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeks_with_copied_timestamps_and_renders_before_scaling() {
+        let args = build_preview_args("/anime/ep01.mkv", 83.25, Some("ass=/tmp/p.ass"));
+
+        let seek = args.iter().position(|a| a == "-ss").unwrap();
+        assert_eq!(args[seek + 1], "83.250");
+        assert!(seek < args.iter().position(|a| a == "-i").unwrap());
+        assert!(args.contains(&"-copyts".to_string()));
+        let filter = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        assert_eq!(filter, "ass=/tmp/p.ass,scale='min(1280,iw)':-2");
+
+        let args = build_preview_args("/anime/ep01.mkv", -1.0, None);
+        assert!(args.contains(&"0.000".to_string()));
+        assert!(args.contains(&"scale='min(1280,iw)':-2".to_string()));
+    }
+}
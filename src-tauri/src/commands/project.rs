@@ -0,0 +1,308 @@
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const PROJECT_EXTENSION: &str = "animesubs";
+const PROJECT_VERSION: u32 = 1;
+const RECENT_FILE: &str = "recent_projects.json";
+const MAX_RECENT: usize = 10;
+
+fn project_path(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    if path.extension().is_some_and(|e| e == PROJECT_EXTENSION) {
+        path
+    } else {
+        let mut with_extension = path.into_os_string();
+        with_extension.push(format!(".{}", PROJECT_EXTENSION));
+        PathBuf::from(with_extension)
+    }
+}
+
+/// Strips what must not be written to disk and stamps the current format version.
+fn prepare_for_save(mut project: TranslationProject) -> TranslationProject {
+    project.version = PROJECT_VERSION;
+    project.config.api_key.clear();
+    project.updated_at = Some(chrono::Local::now().to_rfc3339());
+    project
+}
+
+/// Moves `entry` to the front of the list, dropping duplicates and the oldest overflow.
+fn push_recent(mut recent: Vec<RecentProject>, entry: RecentProject) -> Vec<RecentProject> {
+    recent.retain(|existing| existing.path != entry.path);
+    recent.insert(0, entry);
+    recent.truncate(MAX_RECENT);
+    recent
+}
+
+fn read_recent(app: &AppHandle) -> Result<Vec<RecentProject>, AnimeSubsError> {
+    let path = app_config_path(app, RECENT_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read recent projects: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse recent projects: {}", e)))
+}
+
+fn remember_project(
+    app: &AppHandle,
+    path: &Path,
+    project: &TranslationProject,
+) -> Result<(), AnimeSubsError> {
+    let entry = RecentProject {
+        path: path.to_string_lossy().to_string(),
+        name: project.name.clone(),
+        updated_at: project.updated_at.clone(),
+        folder: project.folder.clone(),
+        total_files: project.files.len(),
+        done_files: project
+            .files
+            .iter()
+            .filter(|file| file.status == ProjectFileStatus::Done)
+            .count(),
+    };
+    let recent = push_recent(read_recent(app)?, entry);
+    let data = serde_json::to_string_pretty(&recent)
+        .map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+    write_file_atomic(&app_config_path(app, RECENT_FILE)?, data.as_bytes())
+}
+
+/// Copies each video's outcome from a job report into the project's file list,
+/// adding videos the project did not list yet. Cancelled videos stay pending
+/// and keep their previous output.
+fn apply_job_results(project: &mut TranslationProject, files: &[JobFileReport]) {
+    for report in files {
+        let index = match project
+            .files
+            .iter()
+            .position(|file| file.path == report.video_path)
+        {
+            Some(index) => index,
+            None => {
+                project.files.push(ProjectFile {
+                    path: report.video_path.clone(),
+                    subtitle_track: report.subtitle_track,
+                    reference_track: None,
+                    status: ProjectFileStatus::Pending,
+                    output_path: None,
+                    error: None,
+                });
+                project.files.len() - 1
+            }
+        };
+        let file = &mut project.files[index];
+        if report.status == ProjectFileStatus::Pending {
+            continue;
+        }
+        file.status = report.status.clone();
+        file.error = report.error.clone();
+        if report.output_path.is_some() {
+            file.output_path = report.output_path.clone();
+        }
+        if report.subtitle_track.is_some() {
+            file.subtitle_track = report.subtitle_track;
+        }
+    }
+}
+
+fn read_project(path: &Path) -> Result<TranslationProject, AnimeSubsError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read project: {}", e)))?;
+    let project: TranslationProject = serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse project: {}", e)))?;
+    if project.version > PROJECT_VERSION {
+        return Err(format!(
+            "Project was saved by a newer version (format {})",
+            project.version
+        )
+        .into());
+    }
+    Ok(project)
+}
+
+/// Records a finished job's per-video outcomes in the project at `project_path`,
+/// so its recent-project entry shows how far the series got.
+pub(crate) fn record_job_results(
+    app: &AppHandle,
+    project_path: &str,
+    files: &[JobFileReport],
+) -> Result<(), AnimeSubsError> {
+    let path = PathBuf::from(project_path);
+    let mut project = read_project(&path)?;
+    apply_job_results(&mut project, files);
+    let project = prepare_for_save(project);
+    let data = serde_json::to_string_pretty(&project)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())?;
+    remember_project(app, &path, &project)
+}
+
+/// Writes the project (without the API key) and returns what was saved.
+#[tauri::command]
+pub async fn save_project(
+    app: AppHandle,
+    path: String,
+    project: TranslationProject,
+) -> Result<TranslationProject, AnimeSubsError> {
+    if project.name.trim().is_empty() {
+        return Err("Project name is required".into());
+    }
+    let path = project_path(&path);
+    let project = prepare_for_save(project);
+    let data = serde_json::to_string_pretty(&project)
+        .map_err(|e| format!("Failed to serialize project: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())?;
+    remember_project(&app, &path, &project)?;
+    Ok(project)
+}
+
+#[tauri::command]
+pub async fn open_project(
+    app: AppHandle,
+    path: String,
+) -> Result<TranslationProject, AnimeSubsError> {
+    let path = PathBuf::from(path);
+    let project = read_project(&path)?;
+    remember_project(&app, &path, &project)?;
+    Ok(project)
+}
+
+/// Recently opened or saved projects, newest first; files that no longer exist are skipped.
+#[tauri::command]
+pub async fn list_recent_projects(app: AppHandle) -> Result<Vec<RecentProject>, AnimeSubsError> {
+    Ok(read_recent(&app)?
+        .into_iter()
+        .filter(|project| Path::new(&project.path).exists())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent(path: &str) -> RecentProject {
+        RecentProject {
+            path: path.to_string(),
+            name: path.to_string(),
+            updated_at: None,
+            folder: String::new(),
+            total_files: 0,
+            done_files: 0,
+        }
+    }
+
+    fn project_file(path: &str, status: ProjectFileStatus) -> ProjectFile {
+        ProjectFile {
+            path: path.to_string(),
+            subtitle_track: None,
+            reference_track: None,
+            status,
+            output_path: Some(format!("{}.old.ass", path)),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn saves_without_secrets_and_tracks_recent_projects() {
+        let project = TranslationProject {
+            version: 0,
+            name: "Frieren S1".to_string(),
+            folder: "/anime/Frieren".to_string(),
+            files: Vec::new(),
+            source_lang: "ja".to_string(),
+            target_lang: "pt-BR".to_string(),
+            config: LLMConfig {
+                provider: "openai".to_string(),
+                api_key: "sk-secret".to_string(),
+                endpoint: String::new(),
+                model: "gpt-4o-mini".to_string(),
+                system_prompt: "default".to_string(),
+                name_table: Vec::new(),
+                ..Default::default()
+            },
+            updated_at: None,
+        };
+        let saved = prepare_for_save(project);
+        assert!(saved.config.api_key.is_empty());
+        assert_eq!(saved.version, PROJECT_VERSION);
+        assert_eq!(
+            project_path("/p/frieren"),
+            PathBuf::from("/p/frieren.animesubs")
+        );
+
+        let list = (0..MAX_RECENT)
+            .map(|i| recent(&format!("/p/{}", i)))
+            .collect();
+        let list = push_recent(list, recent("/p/5"));
+        assert_eq!(list.len(), MAX_RECENT);
+        assert_eq!(list[0].path, "/p/5");
+        let list = push_recent(list, recent("/p/new"));
+        assert_eq!(list[0].path, "/p/new");
+        assert!(!list.iter().any(|p| p.path == "/p/9"));
+    }
+
+    #[test]
+    fn job_results_update_statuses_and_keep_cancelled_files_pending() {
+        let mut project = TranslationProject {
+            version: PROJECT_VERSION,
+            name: "Frieren S1".to_string(),
+            folder: "/anime/Frieren".to_string(),
+            files: vec![
+                project_file("/anime/Frieren/01.mkv", ProjectFileStatus::Done),
+                project_file("/anime/Frieren/02.mkv", ProjectFileStatus::Failed),
+                project_file("/anime/Frieren/03.mkv", ProjectFileStatus::Pending),
+            ],
+            source_lang: "ja".to_string(),
+            target_lang: "pt-BR".to_string(),
+            config: LLMConfig::default(),
+            updated_at: None,
+        };
+        let report = |path: &str, status: ProjectFileStatus, output: Option<&str>| JobFileReport {
+            video_path: path.to_string(),
+            status,
+            subtitle_track: Some(2),
+            output_path: output.map(str::to_string),
+            ..Default::default()
+        };
+
+        apply_job_results(
+            &mut project,
+            &[
+                report(
+                    "/anime/Frieren/02.mkv",
+                    ProjectFileStatus::Done,
+                    Some("/out/02.ass"),
+                ),
+                report("/anime/Frieren/03.mkv", ProjectFileStatus::Pending, None),
+                report(
+                    "/anime/Frieren/04.mkv",
+                    ProjectFileStatus::Done,
+                    Some("/out/04.ass"),
+                ),
+            ],
+        );
+
+        let statuses: Vec<_> = project.files.iter().map(|f| f.status.clone()).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ProjectFileStatus::Done,
+                ProjectFileStatus::Done,
+                ProjectFileStatus::Pending,
+                ProjectFileStatus::Done,
+            ]
+        );
+        assert_eq!(project.files[1].output_path.as_deref(), Some("/out/02.ass"));
+        assert_eq!(project.files[1].subtitle_track, Some(2));
+        assert_eq!(
+            project.files[2].output_path.as_deref(),
+            Some("/anime/Frieren/03.mkv.old.ass")
+        );
+        assert_eq!(project.files[3].path, "/anime/Frieren/04.mkv");
+    }
+}
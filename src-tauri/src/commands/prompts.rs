@@ -0,0 +1,111 @@
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::fs;
+use tauri::AppHandle;
+
+const TEMPLATES_FILE: &str = "prompt_templates.json";
+
+fn read_templates(app: &AppHandle) -> Result<Vec<PromptTemplate>, AnimeSubsError> {
+    let path = app_config_path(app, TEMPLATES_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read prompt templates: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse prompt templates: {}", e)))
+}
+
+fn write_templates(app: &AppHandle, templates: &[PromptTemplate]) -> Result<(), AnimeSubsError> {
+    let path = app_config_path(app, TEMPLATES_FILE)?;
+    let data = serde_json::to_string_pretty(templates)
+        .map_err(|e| format!("Failed to serialize prompt templates: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())
+}
+
+/// Loads the body of `config.prompt_template` so prompt building stays free of I/O.
+pub(crate) fn resolve_prompt_template(
+    app: &AppHandle,
+    config: &mut LLMConfig,
+) -> Result<(), AnimeSubsError> {
+    let Some(name) = config
+        .prompt_template
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    else {
+        return Ok(());
+    };
+    if config.template_content.is_some() {
+        return Ok(());
+    }
+
+    let template = read_templates(app)?
+        .into_iter()
+        .find(|template| template.name == name)
+        .ok_or_else(|| format!("Prompt template not found: {}", name))?;
+    config.template_content = Some(template.content);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_prompt_templates(app: AppHandle) -> Result<Vec<PromptTemplate>, AnimeSubsError> {
+    read_templates(&app)
+}
+
+/// Creates the template or replaces the one with the same name.
+#[tauri::command]
+pub async fn save_prompt_template(
+    app: AppHandle,
+    template: PromptTemplate,
+) -> Result<OperationResult, AnimeSubsError> {
+    let name = template.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Template name is required".into());
+    }
+    if template.content.trim().is_empty() {
+        return Err("Template content is required".into());
+    }
+
+    let mut templates = read_templates(&app)?;
+    let template = PromptTemplate {
+        name: name.clone(),
+        content: template.content,
+    };
+    match templates.iter_mut().find(|existing| existing.name == name) {
+        Some(existing) => *existing = template,
+        None => templates.push(template),
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    write_templates(&app, &templates)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Saved prompt template {}", name),
+        data: Some(name),
+        verification: None,
+    })
+}
+
+#[tauri::command]
+pub async fn delete_prompt_template(
+    app: AppHandle,
+    name: String,
+) -> Result<OperationResult, AnimeSubsError> {
+    let mut templates = read_templates(&app)?;
+    let count = templates.len();
+    templates.retain(|template| template.name != name);
+    if templates.len() == count {
+        return Err(format!("Prompt template not found: {}", name).into());
+    }
+    write_templates(&app, &templates)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Deleted prompt template {}", name),
+        data: None,
+        verification: None,
+    })
+}
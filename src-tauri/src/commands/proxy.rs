@@ -0,0 +1,65 @@
+use super::secrets::{read_api_key, write_api_key};
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::http::{set_proxy_settings, validate_proxy_settings};
+use crate::utils::write_file_atomic;
+use std::fs;
+use tauri::AppHandle;
+
+const PROXY_FILE: &str = "proxy.json";
+/// Keyring entry holding the proxy password.
+const PROXY_SECRET: &str = "proxy";
+
+fn read_proxy_settings(app: &AppHandle) -> Result<ProxySettings, AnimeSubsError> {
+    let path = app_config_path(app, PROXY_FILE)?;
+    if !path.exists() {
+        return Ok(ProxySettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read proxy settings: {}", e)))?;
+    let mut settings: ProxySettings = serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse proxy settings: {}", e)))?;
+    settings.password = Some(read_api_key(app, PROXY_SECRET)?).filter(|p| !p.is_empty());
+    Ok(settings)
+}
+
+/// Applies the saved proxy to every HTTP client; called once at startup.
+pub(crate) fn load_proxy_settings(app: &AppHandle) -> Result<(), AnimeSubsError> {
+    set_proxy_settings(read_proxy_settings(app)?);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_proxy_settings(app: AppHandle) -> Result<ProxySettings, AnimeSubsError> {
+    read_proxy_settings(&app)
+}
+
+#[tauri::command]
+pub async fn save_proxy_settings(
+    app: AppHandle,
+    settings: ProxySettings,
+) -> Result<OperationResult, AnimeSubsError> {
+    validate_proxy_settings(&settings)?;
+
+    write_api_key(
+        &app,
+        PROXY_SECRET,
+        settings.password.as_deref().unwrap_or_default(),
+    )?;
+    let stored = ProxySettings {
+        password: None,
+        ..settings.clone()
+    };
+    let data = serde_json::to_string_pretty(&stored)
+        .map_err(|e| format!("Failed to serialize proxy settings: {}", e))?;
+    write_file_atomic(&app_config_path(&app, PROXY_FILE)?, data.as_bytes())?;
+    set_proxy_settings(settings);
+
+    Ok(OperationResult {
+        success: true,
+        message: "Proxy settings saved".to_string(),
+        data: None,
+        verification: None,
+    })
+}
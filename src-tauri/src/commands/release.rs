@@ -0,0 +1,378 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+const EXTENSIONS: [&str; 15] = [
+    "mkv", "mp4", "webm", "avi", "mov", "wmv", "flv", "m4v", "ts", "m2ts", "ass", "ssa", "srt",
+    "vtt", "sub",
+];
+const SOURCES: [&str; 14] = [
+    "bd", "bdrip", "bluray", "blu-ray", "bdremux", "web", "web-dl", "webdl", "webrip", "hdtv",
+    "tv", "tvrip", "dvd", "dvdrip",
+];
+/// Codec, audio and packaging words that never belong to a title.
+const NOISE: [&str; 24] = [
+    "x264",
+    "x265",
+    "h264",
+    "h265",
+    "h.264",
+    "h.265",
+    "hevc",
+    "avc",
+    "av1",
+    "aac",
+    "flac",
+    "opus",
+    "ac3",
+    "eac3",
+    "dts",
+    "10bit",
+    "10-bit",
+    "8bit",
+    "8-bit",
+    "hi10p",
+    "hdr",
+    "dual-audio",
+    "multi-sub",
+    "batch",
+];
+
+static RESOLUTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(\d{3,4}p|\d{3,4}x\d{3,4})$").unwrap());
+static SEASON_EPISODE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^S(\d{1,2})E(\d{1,4})(?:v(\d))?(?:-E?\d{1,4})?$").unwrap());
+static SEASON_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^S(\d{1,2})$").unwrap());
+static PREFIXED_EPISODE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:E|EP)(\d{1,4})(?:v(\d))?$").unwrap());
+static NUMBER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^#?(\d{1,4})(?:v(\d))?$").unwrap());
+static VERSION_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^v(\d)$").unwrap());
+static ORDINAL_SEASON_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(\d{1,2})(?:st|nd|rd|th)$").unwrap());
+static CHECKSUM_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9A-Fa-f]{8}$").unwrap());
+
+#[derive(Debug)]
+struct Token {
+    text: String,
+    enclosed: bool,
+}
+
+fn closing_bracket(c: char) -> Option<char> {
+    match c {
+        '[' => Some(']'),
+        '(' => Some(')'),
+        '{' => Some('}'),
+        '【' => Some('】'),
+        _ => None,
+    }
+}
+
+fn file_stem(file_name: &str) -> String {
+    let path = Path::new(file_name);
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.to_string());
+    match path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+    {
+        Some(ext) if EXTENSIONS.contains(&ext.as_str()) => path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or(name),
+        _ => name,
+    }
+}
+
+/// Splits a filename into bracketed and free-text runs.
+fn tokenize(stem: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = stem;
+
+    while let Some(first) = rest.chars().next() {
+        if let Some(close) = closing_bracket(first) {
+            let inner = &rest[first.len_utf8()..];
+            match inner.find(close) {
+                Some(end) => {
+                    tokens.push(Token {
+                        text: inner[..end].trim().to_string(),
+                        enclosed: true,
+                    });
+                    rest = &inner[end + close.len_utf8()..];
+                }
+                None => rest = inner,
+            }
+            continue;
+        }
+
+        let end = rest
+            .find(|c| closing_bracket(c).is_some())
+            .unwrap_or(rest.len());
+        tokens.push(Token {
+            text: rest[..end].to_string(),
+            enclosed: false,
+        });
+        rest = &rest[end..];
+    }
+
+    tokens.retain(|token| !token.text.trim().is_empty());
+    tokens
+}
+
+/// Words of the free text. Scene names use dots or underscores instead of spaces.
+fn split_words(tokens: &[Token]) -> Vec<String> {
+    let free: Vec<&str> = tokens
+        .iter()
+        .filter(|t| !t.enclosed)
+        .map(|t| t.text.as_str())
+        .collect();
+    let joined = free.join(" ");
+    let delimiter = if joined.trim().contains(' ') {
+        ' '
+    } else if joined.matches('_').count() >= joined.matches('.').count() {
+        '_'
+    } else {
+        '.'
+    };
+
+    free.iter()
+        .flat_map(|text| text.split([delimiter, ' ']))
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn is_keyword(word: &str) -> bool {
+    let lower = word.to_ascii_lowercase();
+    RESOLUTION_RE.is_match(word)
+        || SOURCES.contains(&lower.as_str())
+        || NOISE.contains(&lower.as_str())
+        || VERSION_RE.is_match(word)
+}
+
+/// Records resolution, source and version found in any word; returns whether the
+/// word was one of those keywords.
+fn read_keyword(word: &str, info: &mut ReleaseInfo) -> bool {
+    let lower = word.to_ascii_lowercase();
+    if RESOLUTION_RE.is_match(word) {
+        info.resolution.get_or_insert_with(|| lower.clone());
+    } else if SOURCES.contains(&lower.as_str()) {
+        info.source.get_or_insert_with(|| word.to_string());
+    } else if let Some(c) = VERSION_RE.captures(word) {
+        info.version = info.version.or(c[1].parse().ok());
+    }
+    is_keyword(word)
+}
+
+/// Index where the episode marker starts (which also ends the title), the episode
+/// number, and any season or version written with it.
+fn find_episode(words: &[String]) -> Option<(usize, u32, Option<u32>, Option<u32>)> {
+    let parse = |value: &str| value.parse::<u32>().ok();
+
+    for (i, word) in words.iter().enumerate() {
+        if let Some(c) = SEASON_EPISODE_RE.captures(word) {
+            let version = c.get(3).and_then(|v| parse(v.as_str()));
+            return Some((i, parse(&c[2])?, parse(&c[1]), version));
+        }
+    }
+    for (i, word) in words.iter().enumerate() {
+        if let Some(c) = PREFIXED_EPISODE_RE.captures(word) {
+            let version = c.get(2).and_then(|v| parse(v.as_str()));
+            return Some((i, parse(&c[1])?, None, version));
+        }
+        let next = words.get(i + 1).and_then(|w| NUMBER_RE.captures(w));
+        if matches!(word.to_ascii_lowercase().as_str(), "ep" | "episode" | "-") {
+            if let Some(c) = next.filter(|_| i > 0) {
+                let version = c.get(2).and_then(|v| parse(v.as_str()));
+                return Some((i, parse(&c[1])?, None, version));
+            }
+        }
+    }
+
+    // A bare number counts only when nothing but keywords follows it ("Show 05 1080p").
+    let last_number = words.iter().enumerate().skip(1).rev().find(|(i, word)| {
+        NUMBER_RE.is_match(word) && words[i + 1..].iter().all(|w| is_keyword(w))
+    })?;
+    let c = NUMBER_RE.captures(last_number.1)?;
+    let version = c.get(2).and_then(|v| parse(v.as_str()));
+    Some((last_number.0, parse(&c[1])?, None, version))
+}
+
+/// anitomy-style parse of a release filename into group, title, season, episode,
+/// resolution, source and version.
+pub(crate) fn parse_release_name(file_name: &str) -> ReleaseInfo {
+    let stem = file_stem(file_name);
+    let tokens = tokenize(&stem);
+    let mut info = ReleaseInfo::default();
+
+    if let Some(first) = tokens
+        .first()
+        .filter(|t| t.enclosed && !is_keyword(&t.text))
+    {
+        info.release_group = Some(first.text.clone());
+    }
+    for token in tokens.iter().filter(|t| t.enclosed) {
+        for word in token.text.split([' ', ',', '_']) {
+            read_keyword(word, &mut info);
+        }
+    }
+
+    let mut words = split_words(&tokens);
+    // Scene releases end with "-GROUP" glued to the last keyword: "x264-GROUP".
+    if info.release_group.is_none() {
+        if let Some(last) = words.last().cloned() {
+            if let Some((head, group)) = last.rsplit_once('-') {
+                if !group.is_empty() && !is_keyword(&last) && is_keyword(head) {
+                    info.release_group = Some(group.to_string());
+                    *words.last_mut().unwrap() = head.to_string();
+                }
+            }
+        }
+    }
+
+    let episode = find_episode(&words);
+    if let Some((_, number, season, version)) = episode {
+        info.episode = Some(number);
+        info.season = season;
+        info.version = version.or(info.version);
+    }
+
+    let mut title_end = episode.map_or(words.len(), |(i, ..)| i);
+    for (i, word) in words.iter().enumerate() {
+        let keyword = read_keyword(word, &mut info);
+        if i >= title_end {
+            continue;
+        }
+        if keyword || (i > 0 && CHECKSUM_RE.is_match(word)) {
+            title_end = i;
+        } else if let Some(c) = SEASON_RE.captures(word) {
+            info.season = info.season.or(c[1].parse().ok());
+            title_end = i;
+        }
+    }
+
+    // "Season 2" and "2nd Season" stay in the title but also set the season.
+    for pair in words[..title_end].windows(2) {
+        let season = if pair[0].eq_ignore_ascii_case("season") {
+            pair[1].parse().ok()
+        } else if pair[1].eq_ignore_ascii_case("season") {
+            ORDINAL_SEASON_RE
+                .captures(&pair[0])
+                .and_then(|c| c[1].parse().ok())
+        } else {
+            None
+        };
+        info.season = info.season.or(season);
+    }
+
+    info.title = words[..title_end]
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches([' ', '-'])
+        .to_string();
+    info
+}
+
+/// Groups files by parsed title (case-insensitive) and orders each series by
+/// season, then episode, then filename.
+fn group_releases(paths: Vec<String>) -> Vec<ReleaseSeries> {
+    let mut series: BTreeMap<String, ReleaseSeries> = BTreeMap::new();
+    for path in paths {
+        let release = parse_release_name(&path);
+        let entry = series
+            .entry(release.title.to_lowercase())
+            .or_insert_with(|| ReleaseSeries {
+                title: release.title.clone(),
+                files: Vec::new(),
+            });
+        entry.files.push(ReleaseFile { path, release });
+    }
+
+    series
+        .into_values()
+        .map(|mut series| {
+            series.files.sort_by(|a, b| {
+                (a.release.season.unwrap_or(1), a.release.episode, &a.path).cmp(&(
+                    b.release.season.unwrap_or(1),
+                    b.release.episode,
+                    &b.path,
+                ))
+            });
+            series
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn parse_release_filename(file_name: String) -> Result<ReleaseInfo, AnimeSubsError> {
+    Ok(parse_release_name(&file_name))
+}
+
+#[tauri::command]
+pub async fn group_release_files(paths: Vec<String>) -> Result<Vec<ReleaseSeries>, AnimeSubsError> {
+    Ok(group_releases(paths))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_release_names() {
+        assert_eq!(
+            parse_release_name(
+                "/anime/[SubsPlease] Sousou no Frieren - 05v2 (1080p) [A1B2C3D4].mkv"
+            ),
+            ReleaseInfo {
+                title: "Sousou no Frieren".to_string(),
+                release_group: Some("SubsPlease".to_string()),
+                episode: Some(5),
+                season: None,
+                resolution: Some("1080p".to_string()),
+                source: None,
+                version: Some(2),
+            }
+        );
+
+        let scene = parse_release_name("Steins.Gate.S01E12.720p.WEB-DL.x264-GROUP.mkv");
+        assert_eq!(scene.title, "Steins Gate");
+        assert_eq!((scene.season, scene.episode), (Some(1), Some(12)));
+        assert_eq!(scene.source.as_deref(), Some("WEB-DL"));
+        assert_eq!(scene.release_group.as_deref(), Some("GROUP"));
+
+        let numbered = parse_release_name("[Group] Mob Psycho 100 II - 03 [BD 1080p FLAC].mkv");
+        assert_eq!(numbered.title, "Mob Psycho 100 II");
+        assert_eq!(numbered.episode, Some(3));
+        assert_eq!(numbered.source.as_deref(), Some("BD"));
+
+        let seasoned = parse_release_name("Spy x Family Season 2 Ep 7.mkv");
+        assert_eq!(seasoned.title, "Spy x Family Season 2");
+        assert_eq!((seasoned.season, seasoned.episode), (Some(2), Some(7)));
+
+        let movie = parse_release_name("Kimi no Na wa.mkv");
+        assert_eq!(movie.title, "Kimi no Na wa");
+        assert_eq!(movie.episode, None);
+    }
+
+    #[test]
+    fn groups_and_orders_files_by_series() {
+        let series = group_releases(vec![
+            "[G] Show - 10 [720p].mkv".to_string(),
+            "Other.S01E01.mkv".to_string(),
+            "[G] show - 02 [720p].mkv".to_string(),
+        ]);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].title, "Other");
+        let episodes: Vec<Option<u32>> =
+            series[1].files.iter().map(|f| f.release.episode).collect();
+        assert_eq!(episodes, vec![Some(2), Some(10)]);
+    }
+}
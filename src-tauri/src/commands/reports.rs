@@ -0,0 +1,322 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Folder next to a `.animesubs` project that holds its job reports.
+const PROJECT_REPORT_DIR: &str = ".animesubs_reports";
+/// App data folder for reports of jobs run outside a project.
+const REPORT_DIR: &str = "reports";
+
+fn reports_dir(app: &AppHandle, project_path: Option<&str>) -> Result<PathBuf, AnimeSubsError> {
+    let dir = match project_path {
+        Some(project) => Path::new(project)
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join(PROJECT_REPORT_DIR),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|e| {
+                AnimeSubsError::Io(format!("Failed to resolve app data directory: {}", e))
+            })?
+            .join(REPORT_DIR),
+    };
+    fs::create_dir_all(&dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create report directory: {}", e)))?;
+    Ok(dir)
+}
+
+/// Cost of `input_tokens`/`output_tokens`, split across models by the lines each
+/// batch provider translated (all on `model` when none is recorded). `None` when
+/// any of those models has no price.
+pub(crate) fn estimate_cost(
+    pricing: &BTreeMap<String, ModelPricing>,
+    model: &str,
+    batch_providers: &[BatchProvider],
+    input_tokens: usize,
+    output_tokens: usize,
+) -> Option<f64> {
+    let mut shares: Vec<(&str, f64)> = Vec::new();
+    let total_lines: usize = batch_providers.iter().map(|b| b.line_count).sum();
+    if total_lines == 0 {
+        shares.push((model, 1.0));
+    } else {
+        for batch in batch_providers {
+            shares.push((&batch.model, batch.line_count as f64 / total_lines as f64));
+        }
+    }
+
+    shares.into_iter().try_fold(0.0, |cost, (model, share)| {
+        let price = pricing.get(model)?;
+        Some(
+            cost + share
+                * (input_tokens as f64 * price.input_per_million
+                    + output_tokens as f64 * price.output_per_million)
+                / 1_000_000.0,
+        )
+    })
+}
+
+/// Totals the per-file reports of a finished job.
+pub(crate) fn build_job_report(
+    request: &TranslationJobRequest,
+    started_at: chrono::DateTime<chrono::Local>,
+    files: Vec<JobFileReport>,
+) -> JobReport {
+    let finished_at = chrono::Local::now();
+    // Files that failed before translating cost nothing, priced or not.
+    let estimated_cost = files
+        .iter()
+        .filter(|file| file.input_tokens + file.output_tokens > 0)
+        .map(|file| file.estimated_cost)
+        .sum::<Option<f64>>();
+    JobReport {
+        // Milliseconds keep jobs started in the same second from sharing a report.
+        id: format!("job-{}", started_at.format("%Y%m%d-%H%M%S-%3f")),
+        started_at: started_at.to_rfc3339(),
+        finished_at: finished_at.to_rfc3339(),
+        provider: request.config.provider.clone(),
+        model: request.config.model.clone(),
+        source_lang: request.source_lang.clone(),
+        target_lang: request.target_lang.clone(),
+        duration_ms: (finished_at - started_at).num_milliseconds().max(0) as u64,
+        input_tokens: files.iter().map(|file| file.input_tokens).sum(),
+        output_tokens: files.iter().map(|file| file.output_tokens).sum(),
+        estimated_cost,
+        files,
+        path: None,
+    }
+}
+
+/// Writes `report` as `<id>.json` next to the job's project, or in the app data
+/// directory, and records where.
+pub(crate) fn save_job_report(
+    app: &AppHandle,
+    project_path: Option<&str>,
+    report: &mut JobReport,
+) -> Result<(), AnimeSubsError> {
+    let path = reports_dir(app, project_path)?.join(format!("{}.json", report.id));
+    report.path = Some(path.to_string_lossy().to_string());
+    let data = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize job report: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map_or_else(|| "–".to_string(), |cost| format!("${:.4}", cost))
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+/// Standalone HTML page with the job totals and one row per file.
+fn report_to_html(report: &JobReport) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>AnimeSubs report {id}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em; }}\n\
+         table {{ border-collapse: collapse; width: 100%; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}\n\
+         .failed {{ background: #fdd; }}\n</style>\n</head>\n<body>\n\
+         <h1>Translation report</h1>\n\
+         <p>{started} &ndash; {finished} ({duration})<br>\n\
+         {provider} / {model}, {source} &rarr; {target}<br>\n\
+         ~{input} input / ~{output} output tokens, estimated cost {cost}</p>\n\
+         <table>\n<tr><th>File</th><th>Track</th><th>Translated</th><th>Skipped</th>\
+         <th>Failed</th><th>Repeats reused</th><th>Tokens (in/out)</th><th>Cost</th><th>Duration</th>\
+         <th>Warnings</th><th>Result</th></tr>\n",
+        id = escape_html(&report.id),
+        started = escape_html(&report.started_at),
+        finished = escape_html(&report.finished_at),
+        duration = format_duration(report.duration_ms),
+        provider = escape_html(&report.provider),
+        model = escape_html(&report.model),
+        source = escape_html(&report.source_lang),
+        target = escape_html(&report.target_lang),
+        input = report.input_tokens,
+        output = report.output_tokens,
+        cost = format_cost(report.estimated_cost),
+    );
+
+    for file in &report.files {
+        let failed = file.status == ProjectFileStatus::Failed;
+        let track = match (file.subtitle_track, &file.track_language) {
+            (Some(index), Some(language)) => format!("#{} ({})", index, language),
+            (Some(index), None) => format!("#{}", index),
+            (None, _) => "–".to_string(),
+        };
+        let warnings: Vec<String> = file
+            .warnings
+            .iter()
+            .map(|issue| escape_html(&format!("#{}: {}", issue.line_index, issue.message)))
+            .collect();
+        let result = if failed {
+            file.error.clone().unwrap_or_default()
+        } else if file.status == ProjectFileStatus::Skipped {
+            file.skip_reason.clone().unwrap_or_default()
+        } else {
+            file.output_path.clone().unwrap_or_default()
+        };
+        html.push_str(&format!(
+            "<tr{}><td>{}</td><td>{}</td><td>{}/{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}/{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            if failed { " class=\"failed\"" } else { "" },
+            escape_html(&file.video_path),
+            escape_html(&track),
+            file.lines_translated,
+            file.lines_total,
+            file.lines_skipped,
+            file.lines_failed,
+            file.lines_deduplicated,
+            file.input_tokens,
+            file.output_tokens,
+            format_cost(file.estimated_cost),
+            format_duration(file.duration_ms),
+            warnings.join("<br>"),
+            escape_html(&result),
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Saved job reports, newest first: those next to `project_path`, or those of
+/// jobs run outside a project.
+#[tauri::command]
+pub async fn list_job_reports(
+    app: AppHandle,
+    project_path: Option<String>,
+) -> Result<Vec<JobReport>, AnimeSubsError> {
+    let entries = fs::read_dir(reports_dir(&app, project_path.as_deref())?)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read report directory: {}", e)))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // Ids embed the start time, so names sort chronologically.
+    paths.sort();
+    paths.reverse();
+
+    Ok(paths
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect())
+}
+
+/// Writes `report` to `output_path` as JSON or, for `format = "html"` (or an
+/// `.html` path), as a page a person can read.
+#[tauri::command]
+pub async fn export_job_report(
+    report: JobReport,
+    output_path: String,
+    format: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let format = format.unwrap_or_else(|| {
+        Path::new(&output_path)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .filter(|ext| ext == "html" || ext == "htm")
+            .map_or_else(|| "json".to_string(), |_| "html".to_string())
+    });
+    let content = match format.as_str() {
+        "html" => report_to_html(&report),
+        "json" => serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize job report: {}", e))?,
+        other => return Err(format!("Unsupported report format: {}", other).into()),
+    };
+    write_file_atomic(Path::new(&output_path), content.as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Exported report {}", report.id),
+        data: Some(output_path),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(model: &str, line_count: usize) -> BatchProvider {
+        BatchProvider {
+            batch: 0,
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            line_count,
+        }
+    }
+
+    #[test]
+    fn estimates_cost_per_model_share() {
+        let pricing = BTreeMap::from([
+            (
+                "gpt-4o-mini".to_string(),
+                ModelPricing {
+                    input_per_million: 0.15,
+                    output_per_million: 0.60,
+                },
+            ),
+            (
+                "gpt-4o".to_string(),
+                ModelPricing {
+                    input_per_million: 2.5,
+                    output_per_million: 10.0,
+                },
+            ),
+        ]);
+
+        let cost = estimate_cost(&pricing, "gpt-4o-mini", &[], 1_000_000, 500_000).unwrap();
+        assert!((cost - 0.45).abs() < 1e-9);
+
+        let batches = [batch("gpt-4o-mini", 30), batch("gpt-4o", 10)];
+        let cost = estimate_cost(&pricing, "gpt-4o-mini", &batches, 1_000_000, 0).unwrap();
+        assert!((cost - (0.75 * 0.15 + 0.25 * 2.5)).abs() < 1e-9);
+
+        assert_eq!(
+            estimate_cost(&pricing, "gpt-4o-mini", &[batch("local", 5)], 10, 10),
+            None
+        );
+    }
+
+    #[test]
+    fn renders_escaped_html_rows() {
+        let report = JobReport {
+            id: "job-20261015-120000".to_string(),
+            files: vec![
+                JobFileReport {
+                    video_path: "/anime/<Show> & Co/01.mkv".to_string(),
+                    status: ProjectFileStatus::Done,
+                    subtitle_track: Some(2),
+                    track_language: Some("jpn".to_string()),
+                    lines_total: 300,
+                    lines_translated: 290,
+                    lines_skipped: 10,
+                    estimated_cost: Some(0.0123),
+                    ..Default::default()
+                },
+                JobFileReport {
+                    video_path: "/anime/02.mkv".to_string(),
+                    status: ProjectFileStatus::Failed,
+                    error: Some("No subtitle tracks found".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let html = report_to_html(&report);
+        assert!(html.contains("/anime/&lt;Show&gt; &amp; Co/01.mkv"));
+        assert!(html.contains("<td>#2 (jpn)</td><td>290/300</td>"));
+        assert!(html.contains("$0.0123"));
+        assert!(html.contains("<tr class=\"failed\"><td>/anime/02.mkv</td>"));
+        assert!(html.contains("No subtitle tracks found"));
+    }
+}
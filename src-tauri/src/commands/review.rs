@@ -0,0 +1,479 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use calamine::{open_workbook, Reader, Xlsx};
+use rust_xlsxwriter::{Format, FormatAlign, Workbook, XlsxError};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// Columns of an exported review table; imports find `id` and `translation` by name.
+const TABLE_HEADERS: [&str; 5] = ["id", "time", "source", "translation", "flags"];
+
+fn validation_flag(kind: &ValidationIssueKind) -> &'static str {
+    match kind {
+        ValidationIssueKind::ReadingSpeed => "readingSpeed",
+        ValidationIssueKind::LineLength => "lineLength",
+        ValidationIssueKind::ShortDuration => "shortDuration",
+        ValidationIssueKind::Overlap => "overlap",
+        ValidationIssueKind::InvalidTiming => "invalidTiming",
+    }
+}
+
+fn build_review_rows(subtitle_data: &SubtitleData) -> Vec<ReviewRow> {
+    let mut flags: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut add_flag = |id: usize, flag: &str| {
+        let row_flags = flags.entry(id).or_default();
+        if !row_flags.iter().any(|f| f == flag) {
+            row_flags.push(flag.to_string());
+        }
+    };
+
+    for line in subtitle_data.lines.iter().filter(|line| !line.skip) {
+        let source = strip_ass_tags(&line.original_with_formatting);
+        if line.text.trim().is_empty() {
+            add_flag(line.index, "empty");
+        } else if line.text.trim() == source.trim() {
+            add_flag(line.index, "untranslated");
+        }
+    }
+    for issue in super::validation::check_lines(&subtitle_data.lines, &Default::default()) {
+        add_flag(issue.line_index, validation_flag(&issue.kind));
+    }
+    for issue in super::honorifics::find_honorific_issues(subtitle_data) {
+        add_flag(issue.line_index, "honorifics");
+    }
+
+    subtitle_data
+        .lines
+        .iter()
+        .filter(|line| !line.skip)
+        .map(|line| ReviewRow {
+            id: line.index,
+            start: line.start.clone(),
+            end: line.end.clone(),
+            source: strip_ass_tags(&line.original_with_formatting),
+            translation: line.text.clone(),
+            flags: flags.remove(&line.index).unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Source/translation pairs with review flags, for an edit table before saving.
+#[tauri::command]
+pub async fn get_translation_diff(
+    subtitle_data: SubtitleData,
+) -> Result<Vec<ReviewRow>, AnimeSubsError> {
+    Ok(build_review_rows(&subtitle_data))
+}
+
+fn merge_line_edits(
+    mut subtitle_data: SubtitleData,
+    edits: Vec<LineEdit>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    for edit in edits {
+        let line = subtitle_data
+            .lines
+            .iter_mut()
+            .find(|line| line.index == edit.id)
+            .ok_or_else(|| format!("Line {} not found", edit.id))?;
+        line.text = edit.text.replace("\r\n", "\n");
+    }
+    Ok(subtitle_data)
+}
+
+/// Merges user-corrected lines into `subtitle_data`; pass the result to
+/// `save_translated_subtitles`.
+#[tauri::command]
+pub async fn apply_line_edits(
+    subtitle_data: SubtitleData,
+    edits: Vec<LineEdit>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    merge_line_edits(subtitle_data, edits)
+}
+
+/// `format` if given, else the extension of `path`.
+fn table_format(path: &str, format: Option<String>) -> String {
+    format
+        .map(|format| format.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| {
+            Path::new(path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default()
+        })
+}
+
+/// Leading characters spreadsheet apps read as the start of a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Quotes CSV text a spreadsheet would otherwise evaluate, so a line like
+/// `=HYPERLINK(…)` or `-Wait!` stays text; `unescape_cell` reverses it on import.
+fn escape_cell(text: &str) -> String {
+    if text.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn unescape_cell(text: &str) -> &str {
+    match text.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(FORMULA_PREFIXES) => rest,
+        _ => text,
+    }
+}
+
+fn table_cells(row: &ReviewRow) -> [String; 5] {
+    [
+        row.id.to_string(),
+        format!("{} - {}", row.start, row.end),
+        row.source.clone(),
+        row.translation.clone(),
+        row.flags.join(", "),
+    ]
+}
+
+/// `table_cells` for CSV, which spreadsheet apps evaluate as they open it.
+fn csv_cells(row: &ReviewRow) -> [String; 5] {
+    table_cells(row).map(|text| escape_cell(&text))
+}
+
+/// UTF-8 with a BOM, so spreadsheet apps don't guess a legacy encoding.
+fn rows_to_csv(rows: &[ReviewRow]) -> Result<Vec<u8>, AnimeSubsError> {
+    let mut writer = csv::Writer::from_writer("\u{FEFF}".as_bytes().to_vec());
+    writer
+        .write_record(TABLE_HEADERS)
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    for row in rows {
+        writer
+            .write_record(csv_cells(row))
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e).into())
+}
+
+fn rows_to_xlsx(rows: &[ReviewRow]) -> Result<Vec<u8>, AnimeSubsError> {
+    let xlsx_error = |e: XlsxError| AnimeSubsError::from(format!("Failed to write XLSX: {}", e));
+    let header = Format::new().set_bold();
+    // Quote-prefixed like typed `'` text, so editing a `-Wait!` cell keeps it a string.
+    let cell = Format::new()
+        .set_text_wrap()
+        .set_align(FormatAlign::Top)
+        .set_quote_prefix();
+
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    for (col, name) in TABLE_HEADERS.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, col as u16, *name, &header)
+            .map_err(xlsx_error)?;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let sheet_row = i as u32 + 1;
+        sheet
+            .write_number(sheet_row, 0, row.id as f64)
+            .map_err(xlsx_error)?;
+        for (col, text) in table_cells(row).iter().enumerate().skip(1) {
+            sheet
+                .write_string_with_format(sheet_row, col as u16, text, &cell)
+                .map_err(xlsx_error)?;
+        }
+    }
+    for (col, width) in [(1, 26), (2, 50), (3, 50), (4, 20)] {
+        sheet.set_column_width(col, width).map_err(xlsx_error)?;
+    }
+    sheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+    workbook.save_to_buffer().map_err(xlsx_error)
+}
+
+/// Read-only page for reviewers without a spreadsheet app; flagged rows stand out.
+fn rows_to_html(rows: &[ReviewRow], title: &str) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 2em; }}\n\
+         table {{ border-collapse: collapse; width: 100%; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}\n\
+         .flagged {{ background: #fff3cd; }}\n</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<table>\n<tr>{headers}</tr>\n",
+        title = escape_html(title),
+        headers = TABLE_HEADERS
+            .iter()
+            .map(|name| format!("<th>{}</th>", name))
+            .collect::<String>(),
+    );
+    for row in rows {
+        let cells: String = table_cells(row)
+            .iter()
+            .map(|text| format!("<td>{}</td>", escape_html(text).replace('\n', "<br>")))
+            .collect();
+        let class = if row.flags.is_empty() {
+            ""
+        } else {
+            " class=\"flagged\""
+        };
+        html.push_str(&format!("<tr{}>{}</tr>\n", class, cells));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Writes the review rows of `subtitle_data` to `output_path` as CSV, XLSX or
+/// HTML (`format`, or the extension), for proofreading outside the app.
+#[tauri::command]
+pub async fn export_translation_table(
+    subtitle_data: SubtitleData,
+    output_path: String,
+    format: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let rows = build_review_rows(&subtitle_data);
+    let content = match table_format(&output_path, format).as_str() {
+        "csv" => rows_to_csv(&rows)?,
+        "xlsx" => rows_to_xlsx(&rows)?,
+        "html" | "htm" => {
+            let title = Path::new(&subtitle_data.source_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "Translation review".to_string());
+            rows_to_html(&rows, &title).into_bytes()
+        }
+        other => return Err(format!("Unsupported table format: {}", other).into()),
+    };
+    write_file_atomic(Path::new(&output_path), &content)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Exported {} lines for review", rows.len()),
+        data: Some(output_path),
+        verification: None,
+    })
+}
+
+/// Rows of a CSV in any encoding; `;` is accepted as the separator when the
+/// header uses it, as spreadsheet apps in some locales save that way.
+pub(crate) fn read_csv_table(content: &str) -> Result<Vec<Vec<String>>, AnimeSubsError> {
+    let header = content.lines().next().unwrap_or_default();
+    let delimiter = if !header.contains(',') && header.contains(';') {
+        b';'
+    } else {
+        b','
+    };
+    csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes())
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|e| AnimeSubsError::parse(format!("Failed to parse CSV: {}", e)))
+        })
+        .collect()
+}
+
+/// Rows of the first sheet of an XLSX workbook.
+fn read_xlsx_table(path: &str) -> Result<Vec<Vec<String>>, AnimeSubsError> {
+    let mut workbook: Xlsx<_> = open_workbook(path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read reviewed table: {}", e)))?;
+    let sheet = workbook
+        .worksheet_range_at(0)
+        .ok_or_else(|| AnimeSubsError::parse("Reviewed table has no sheets"))?
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse XLSX: {}", e)))?;
+    Ok(sheet
+        .rows()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect())
+}
+
+/// Spreadsheets may turn ids into numbers, so `12.0` reads as line 12.
+fn parse_line_id(id: &str) -> Option<usize> {
+    let id = id.trim();
+    id.parse().ok().or_else(|| {
+        id.parse::<f64>()
+            .ok()
+            .filter(|id| *id >= 0.0 && id.fract() == 0.0)
+            .map(|id| id as usize)
+    })
+}
+
+/// Edits from a reviewed table's `id` and `translation` columns, wherever they
+/// were moved; rows without an id are ignored.
+fn table_edits(rows: Vec<Vec<String>>) -> Result<Vec<LineEdit>, AnimeSubsError> {
+    let mut rows = rows.into_iter();
+    let header = rows
+        .next()
+        .ok_or_else(|| AnimeSubsError::parse("Reviewed table is empty"))?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|cell| cell.trim().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                AnimeSubsError::parse(format!("Reviewed table has no '{}' column", name))
+            })
+    };
+    let (id_column, text_column) = (column("id")?, column("translation")?);
+
+    let mut edits = Vec::new();
+    for row in rows {
+        let Some(id) = row.get(id_column).filter(|id| !id.trim().is_empty()) else {
+            continue;
+        };
+        let id = parse_line_id(id)
+            .ok_or_else(|| AnimeSubsError::parse(format!("Invalid line id: {}", id)))?;
+        if let Some(text) = row.get(text_column) {
+            edits.push(LineEdit {
+                id,
+                text: unescape_cell(text).to_string(),
+            });
+        }
+    }
+    Ok(edits)
+}
+
+/// Merges the translations of a table written by `export_translation_table` (CSV
+/// or XLSX) and corrected by a reviewer into `subtitle_data`; pass the result to
+/// `save_translated_subtitles`.
+#[tauri::command]
+pub async fn import_reviewed_table(
+    subtitle_data: SubtitleData,
+    path: String,
+) -> Result<SubtitleData, AnimeSubsError> {
+    let rows = match table_format(&path, None).as_str() {
+        "csv" => read_csv_table(&read_file_as_utf8(&path)?)?,
+        "xlsx" => read_xlsx_table(&path)?,
+        "html" | "htm" => {
+            return Err("HTML review tables are read-only; export CSV or XLSX to edit".into())
+        }
+        other => return Err(format!("Unsupported table format: {}", other).into()),
+    };
+    let edits: Vec<LineEdit> = table_edits(rows)?
+        .into_iter()
+        .filter(|edit| {
+            subtitle_data
+                .lines
+                .iter()
+                .find(|line| line.index == edit.id)
+                .is_none_or(|line| line.text != edit.text.replace("\r\n", "\n"))
+        })
+        .collect();
+    info!("Merging {} reviewed lines from {}", edits.len(), path);
+    merge_line_edits(subtitle_data, edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(index: usize, source: &str, text: &str) -> DialogLine {
+        DialogLine {
+            index,
+            text: text.to_string(),
+            original_with_formatting: source.to_string(),
+            start: format!("0:00:0{}.00", index * 2),
+            end: format!("0:00:0{}.50", index * 2 + 1),
+            style: None,
+            name: None,
+            reference: None,
+            formatting: None,
+            event: AssEventKind::Dialogue,
+            classification: LineClass::Dialogue,
+            skip: false,
+        }
+    }
+
+    #[test]
+    fn flags_rows_and_applies_edits() {
+        let data = SubtitleData {
+            format: "ass".to_string(),
+            lines: vec![
+                line(0, "{\\i1}Good morning.{\\i0}", "Bom dia."),
+                line(1, "Okay.", "Okay."),
+                line(2, "Thanks, Okabe-san.", "Obrigado, Okabe."),
+            ],
+            line_count: 3,
+            source_path: "ep01.ass".to_string(),
+            ass_header: None,
+            failed_line_ids: Vec::new(),
+            batch_providers: Vec::new(),
+            class_counts: LineClassCounts::default(),
+            deduplicated_lines: 0,
+        };
+
+        let rows = build_review_rows(&data);
+        assert_eq!(rows[0].source, "Good morning.");
+        assert!(rows[0].flags.is_empty());
+        assert_eq!(rows[1].flags, vec!["untranslated"]);
+        assert!(rows[2].flags.contains(&"honorifics".to_string()));
+
+        let edited = merge_line_edits(
+            data,
+            vec![LineEdit {
+                id: 2,
+                text: "Obrigado, Okabe-san.".to_string(),
+            }],
+        )
+        .unwrap();
+        assert_eq!(edited.lines[2].text, "Obrigado, Okabe-san.");
+    }
+
+    #[test]
+    fn round_trips_reviewed_csv_tables() {
+        let rows = vec![
+            ReviewRow {
+                id: 0,
+                start: "0:00:01.00".to_string(),
+                end: "0:00:02.50".to_string(),
+                source: "Hello, \"Okabe\".".to_string(),
+                translation: "Olá, \"Okabe\".\nTudo bem?".to_string(),
+                flags: Vec::new(),
+            },
+            ReviewRow {
+                id: 1,
+                start: "0:00:03.00".to_string(),
+                end: "0:00:04.00".to_string(),
+                source: "Okay.".to_string(),
+                translation: "Okay.".to_string(),
+                flags: vec!["untranslated".to_string()],
+            },
+            ReviewRow {
+                id: 2,
+                start: "0:00:05.00".to_string(),
+                end: "0:00:06.00".to_string(),
+                source: "=HYPERLINK(\"http://example.com\")".to_string(),
+                translation: "-Espera!".to_string(),
+                flags: Vec::new(),
+            },
+        ];
+
+        let csv = String::from_utf8(rows_to_csv(&rows).unwrap()).unwrap();
+        assert!(csv.contains("\"'=HYPERLINK(\"\"http://example.com\"\")\""));
+        assert!(csv.contains(",'-Espera!,"));
+        let edits = table_edits(read_csv_table(strip_utf8_bom(csv).as_str()).unwrap()).unwrap();
+        assert_eq!(edits.len(), 3);
+        assert_eq!(edits[0].text, "Olá, \"Okabe\".\nTudo bem?");
+        assert_eq!(edits[2].text, "-Espera!");
+        assert_eq!(unescape_cell("'Tis"), "'Tis");
+        assert_eq!(escape_cell("\tSUM(A1)"), "'\tSUM(A1)");
+
+        let path =
+            std::env::temp_dir().join(format!("animesubs-review-{}.xlsx", std::process::id()));
+        std::fs::write(&path, rows_to_xlsx(&rows).unwrap()).unwrap();
+        let edits = table_edits(read_xlsx_table(&path.to_string_lossy()).unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(edits[2].text, "-Espera!");
+
+        let reordered = "flags;translation;ID\nuntranslated;Tudo bem.;1.0\n;;\n";
+        let edits = table_edits(read_csv_table(reordered).unwrap()).unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!((edits[0].id, edits[0].text.as_str()), (1, "Tudo bem."));
+
+        assert!(table_edits(read_csv_table("id,text\n1,x\n").unwrap()).is_err());
+        let html = rows_to_html(&rows, "ep01.ass");
+        assert!(html.contains("<tr class=\"flagged\">"));
+        assert!(html.contains("<td>-Espera!</td>"));
+    }
+}
@@ -0,0 +1,192 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::fs;
+use tauri::AppHandle;
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+const KEYRING_SERVICE: &str = "animesubs";
+
+fn secrets_path(app: &AppHandle) -> Result<std::path::PathBuf, AnimeSubsError> {
+    super::utils::app_config_path(app, "secrets.json")
+}
+
+fn read_secrets(
+    app: &AppHandle,
+) -> Result<serde_json::Map<String, serde_json::Value>, AnimeSubsError> {
+    let path = secrets_path(app)?;
+    if !path.exists() {
+        return Ok(serde_json::Map::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read secrets file: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse secrets file: {}", e)))
+}
+
+fn write_secrets(
+    app: &AppHandle,
+    secrets: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), AnimeSubsError> {
+    let path = secrets_path(app)?;
+    if secrets.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| AnimeSubsError::Io(format!("Failed to remove secrets file: {}", e)))?;
+        }
+        return Ok(());
+    }
+
+    let data = serde_json::to_string_pretty(secrets)
+        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())?;
+
+    #[cfg(unix)]
+    {
+        let mut permissions = fs::metadata(&path)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to read secrets permissions: {}", e)))?
+            .permissions();
+        permissions.set_mode(0o600);
+        fs::set_permissions(&path, permissions).map_err(|e| {
+            AnimeSubsError::Io(format!("Failed to restrict secrets permissions: {}", e))
+        })?;
+    }
+
+    Ok(())
+}
+
+fn keyring_entry(provider: &str) -> Result<keyring::Entry, keyring::Error> {
+    keyring::Entry::new(KEYRING_SERVICE, provider)
+}
+
+/// Keyring backends can be missing entirely (e.g. Linux without a Secret Service
+/// daemon); those keys stay in the restricted `secrets.json` file instead.
+fn keyring_unavailable(error: &keyring::Error) -> bool {
+    matches!(
+        error,
+        keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_)
+    )
+}
+
+fn keyring_get(provider: &str) -> Result<Option<String>, keyring::Error> {
+    match keyring_entry(provider)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn keyring_set(provider: &str, api_key: &str) -> Result<(), keyring::Error> {
+    let entry = keyring_entry(provider)?;
+    if api_key.is_empty() {
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e),
+        }
+    } else {
+        entry.set_password(api_key)
+    }
+}
+
+/// Moves plaintext keys from `secrets.json` into the OS keyring. Keys the keyring
+/// refuses are left in the file; the file is removed once it is empty.
+pub(crate) fn migrate_plaintext_secrets(app: &AppHandle) -> Result<usize, AnimeSubsError> {
+    let mut secrets = read_secrets(app)?;
+    if secrets.is_empty() {
+        return Ok(0);
+    }
+
+    let mut migrated = 0;
+    secrets.retain(|provider, value| {
+        let Some(api_key) = value.as_str() else {
+            return false;
+        };
+        let stored = keyring_set(provider, api_key).is_ok();
+        if stored {
+            migrated += 1;
+        }
+        !stored
+    });
+    write_secrets(app, &secrets)?;
+    Ok(migrated)
+}
+
+pub(crate) fn read_api_key(app: &AppHandle, provider: &str) -> Result<String, AnimeSubsError> {
+    match keyring_get(provider) {
+        Ok(Some(api_key)) => return Ok(api_key),
+        Ok(None) => {}
+        Err(e) if keyring_unavailable(&e) => {}
+        Err(e) => return Err(format!("Failed to read API key from keyring: {}", e).into()),
+    }
+
+    let api_key = read_secrets(app)?
+        .get(provider)
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_string();
+    if !api_key.is_empty() {
+        // Keys saved before the keyring was available are moved over on first use.
+        let _ = migrate_plaintext_secrets(app);
+    }
+    Ok(api_key)
+}
+
+pub(crate) fn write_api_key(
+    app: &AppHandle,
+    provider: &str,
+    api_key: &str,
+) -> Result<(), AnimeSubsError> {
+    let mut secrets = read_secrets(app)?;
+    match keyring_set(provider, api_key) {
+        Ok(()) => {
+            secrets.remove(provider);
+        }
+        Err(e) if keyring_unavailable(&e) => {
+            if api_key.is_empty() {
+                secrets.remove(provider);
+            } else {
+                secrets.insert(
+                    provider.to_string(),
+                    serde_json::Value::String(api_key.to_string()),
+                );
+            }
+        }
+        Err(e) => return Err(format!("Failed to store API key in keyring: {}", e).into()),
+    }
+    write_secrets(app, &secrets)
+}
+
+#[tauri::command]
+pub async fn get_api_key(
+    app: AppHandle,
+    provider: String,
+) -> Result<OperationResult, AnimeSubsError> {
+    let api_key = read_api_key(&app, &provider)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "API key loaded".to_string(),
+        data: Some(api_key),
+        verification: None,
+    })
+}
+
+/// Saves the key for `provider` in the OS keyring; an empty key deletes it.
+#[tauri::command]
+pub async fn store_api_key(
+    app: AppHandle,
+    provider: String,
+    api_key: String,
+) -> Result<OperationResult, AnimeSubsError> {
+    write_api_key(&app, &provider, &api_key)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "API key saved".to_string(),
+        data: None,
+        verification: None,
+    })
+}
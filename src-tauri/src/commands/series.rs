@@ -0,0 +1,50 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SERIES_CONTEXT_FILE: &str = ".animesubs-context.json";
+
+fn series_context_path(series_folder: &str) -> PathBuf {
+    Path::new(series_folder).join(SERIES_CONTEXT_FILE)
+}
+
+pub(crate) fn read_series_context(
+    series_folder: &str,
+) -> Result<Option<SeriesContext>, AnimeSubsError> {
+    let path = series_context_path(series_folder);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read series context: {}", e)))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse series context: {}", e)))
+}
+
+#[tauri::command]
+pub async fn load_series_context(
+    series_folder: String,
+) -> Result<Option<SeriesContext>, AnimeSubsError> {
+    read_series_context(&series_folder)
+}
+
+#[tauri::command]
+pub async fn save_series_context(
+    series_folder: String,
+    context: SeriesContext,
+) -> Result<OperationResult, AnimeSubsError> {
+    let path = series_context_path(&series_folder);
+    let data = serde_json::to_string_pretty(&context)
+        .map_err(|e| format!("Failed to serialize series context: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "Series context saved".to_string(),
+        data: Some(path.to_string_lossy().to_string()),
+        verification: None,
+    })
+}
@@ -0,0 +1,554 @@
+use super::logging::LOG_EVENT;
+use super::secrets::{read_api_key, write_api_key};
+use super::utils::app_config_path;
+use super::{automation, estimate, job_control, logging, project, settings, translation, video};
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::write_file_atomic;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State as RouteState};
+use axum::http::header::AUTHORIZATION;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, EventId, Listener, Manager};
+use tokio::sync::{broadcast, watch};
+use tracing::{info, warn};
+
+const SERVER_FILE: &str = "server.json";
+/// Keyring entry holding the bearer token.
+const TOKEN_SECRET: &str = "remote-server-token";
+const DEFAULT_BIND: &str = "127.0.0.1:7318";
+/// Events a WebSocket client receives, in the same shape the GUI gets them.
+const FORWARDED_EVENTS: [&str; 4] = [
+    "translation-job-progress",
+    "translation-progress",
+    "translation-job-paused",
+    LOG_EVENT,
+];
+/// Events buffered per client; a client that falls further behind skips ahead.
+const EVENT_BUFFER: usize = 256;
+/// How long a stop waits for requests in flight before dropping them.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+const RPC_METHOD_NOT_FOUND: i32 = -32601;
+const RPC_INVALID_PARAMS: i32 = -32602;
+/// Application error: the command ran and failed; `data` holds the `AnimeSubsError`.
+const RPC_COMMAND_FAILED: i32 = -32000;
+
+/// The running remote control server, if any.
+#[derive(Default)]
+pub struct RemoteServer {
+    running: Mutex<Option<RunningServer>>,
+}
+
+struct RunningServer {
+    address: String,
+    shutdown: watch::Sender<bool>,
+    listeners: Vec<EventId>,
+    /// The serve task; it owns the listening socket until it ends.
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+#[derive(Clone)]
+struct RouteContext {
+    app: AppHandle,
+    token: String,
+    events: broadcast::Sender<String>,
+    shutdown: watch::Receiver<bool>,
+}
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+enum RpcError {
+    MethodNotFound(String),
+    InvalidParams(String),
+    Command(AnimeSubsError),
+}
+
+#[derive(Deserialize)]
+struct JobParams {
+    request: TranslationJobRequest,
+}
+
+impl JobParams {
+    /// The request with the client's `ffmpegPath` dropped; see `VideoParams`.
+    fn into_request(self) -> TranslationJobRequest {
+        TranslationJobRequest {
+            ffmpeg_path: None,
+            ..self.request
+        }
+    }
+}
+
+/// Tool paths such as `ffmpegPath` are not accepted: a client could point them at
+/// any program. Remote calls use the tools configured in the settings.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VideoParams {
+    video_path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FolderParams {
+    folder_path: String,
+}
+
+#[derive(Deserialize)]
+struct PathParams {
+    path: String,
+}
+
+#[derive(Deserialize)]
+struct PathsParams {
+    paths: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TailParams {
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+fn bind_address(settings: &ServerSettings) -> &str {
+    match settings.bind.trim() {
+        "" => DEFAULT_BIND,
+        bind => bind,
+    }
+}
+
+fn read_server_settings(app: &AppHandle) -> Result<ServerSettings, AnimeSubsError> {
+    let path = app_config_path(app, SERVER_FILE)?;
+    if !path.exists() {
+        return Ok(ServerSettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read server settings: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse server settings: {}", e)))
+}
+
+/// 32 bytes from the OS random source, as 64 hex characters.
+fn new_token() -> Result<String, AnimeSubsError> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate a server token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The saved token, created on first use.
+fn server_token(app: &AppHandle) -> Result<String, AnimeSubsError> {
+    let token = read_api_key(app, TOKEN_SECRET)?;
+    if !token.is_empty() {
+        return Ok(token);
+    }
+    let token = new_token()?;
+    write_api_key(app, TOKEN_SECRET, &token)?;
+    Ok(token)
+}
+
+/// Compares in constant time so response timing does not leak the token.
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Accepts `Authorization: Bearer <token>`, or `?token=` for WebSocket clients
+/// that cannot set headers.
+fn authorized(headers: &HeaderMap, query: Option<&str>, token: &str) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(query)
+        .is_some_and(|given| token_matches(given.trim(), token))
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::InvalidParams(e.to_string()))
+}
+
+fn reply<T: serde::Serialize>(result: Result<T, AnimeSubsError>) -> Result<Value, RpcError> {
+    let value = result.map_err(RpcError::Command)?;
+    serde_json::to_value(value).map_err(|e| RpcError::Command(e.to_string().into()))
+}
+
+/// Runs one command by its Tauri name; params use the same camelCase names as
+/// `invoke` in the GUI.
+async fn dispatch(app: &AppHandle, method: &str, args: Value) -> Result<Value, RpcError> {
+    match method {
+        "start_translation_job" => {
+            let p: JobParams = params(args)?;
+            reply(translation::start_translation_job(app.clone(), p.into_request()).await)
+        }
+        "estimate_translation_job" => {
+            let p: JobParams = params(args)?;
+            reply(estimate::estimate_translation_job(app.clone(), p.into_request()).await)
+        }
+        "pause_translation_job" => {
+            reply(job_control::pause_translation_job(app.clone(), app.state()).await)
+        }
+        "resume_translation_job" => {
+            reply(job_control::resume_translation_job(app.clone(), app.state()).await)
+        }
+        "cancel_translation_job" => {
+            reply(job_control::cancel_translation_job(app.clone(), app.state()).await)
+        }
+        "get_video_info" => {
+            let p: VideoParams = params(args)?;
+            reply(video::get_video_info(p.video_path, None).await)
+        }
+        "scan_folder_for_videos" => {
+            let p: FolderParams = params(args)?;
+            reply(video::scan_folder_for_videos(p.folder_path).await)
+        }
+        "open_project" => {
+            let p: PathParams = params(args)?;
+            reply(project::open_project(app.clone(), p.path).await)
+        }
+        "list_recent_projects" => reply(project::list_recent_projects(app.clone()).await),
+        "get_settings" => reply(settings::get_settings().await),
+        "enqueue_automation" => {
+            let p: PathsParams = params(args)?;
+            reply(automation::enqueue_automation(app.clone(), p.paths).await)
+        }
+        "tail_logs" => {
+            let p: TailParams = params(args)?;
+            reply(logging::tail_logs(p.limit).await)
+        }
+        _ => Err(RpcError::MethodNotFound(method.to_string())),
+    }
+}
+
+fn rpc_response(id: Value, result: Result<Value, RpcError>) -> Value {
+    let error = match result {
+        Ok(result) => return json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(RpcError::MethodNotFound(method)) => json!({
+            "code": RPC_METHOD_NOT_FOUND,
+            "message": format!("Unknown method: {}", method),
+        }),
+        Err(RpcError::InvalidParams(message)) => json!({
+            "code": RPC_INVALID_PARAMS,
+            "message": format!("Invalid params: {}", message),
+        }),
+        Err(RpcError::Command(error)) => json!({
+            "code": RPC_COMMAND_FAILED,
+            "message": error.to_string(),
+            "data": error,
+        }),
+    };
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+async fn health(RouteState(context): RouteState<RouteContext>) -> Json<Value> {
+    Json(json!({
+        "name": "animesubs",
+        "version": context.app.package_info().version.to_string(),
+    }))
+}
+
+/// JSON-RPC 2.0 endpoint. A `start_translation_job` call answers when the job
+/// ends; follow its progress on `/events`.
+async fn rpc(
+    RouteState(context): RouteState<RouteContext>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Json(request): Json<RpcRequest>,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let result = dispatch(&context.app, &request.method, request.params).await;
+    Json(rpc_response(request.id, result)).into_response()
+}
+
+/// Sonarr/Radarr "On Import" webhook. Their webhook connection cannot set an
+/// `Authorization` header, so the token usually comes as `?token=`.
+async fn arr_webhook(
+    RouteState(context): RouteState<RouteContext>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    Json(payload): Json<Value>,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    match automation::ingest_arr_webhook(&context.app, &payload) {
+        Ok(queued) => Json(json!({ "queued": queued })).into_response(),
+        Err(e) => (StatusCode::UNPROCESSABLE_ENTITY, Json(json!(e))).into_response(),
+    }
+}
+
+/// WebSocket streaming `{ event, payload }` messages for `FORWARDED_EVENTS`.
+async fn events_socket(
+    RouteState(context): RouteState<RouteContext>,
+    headers: HeaderMap,
+    Query(query): Query<TokenQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if !authorized(&headers, query.token.as_deref(), &context.token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    upgrade.on_upgrade(move |socket| forward_events(socket, context))
+}
+
+async fn forward_events(mut socket: WebSocket, mut context: RouteContext) {
+    let mut events = context.events.subscribe();
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(message) => {
+                    if socket.send(Message::Text(message.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Remote client fell behind; skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = context.shutdown.changed() => break,
+        }
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+fn event_message(event: &str, payload: &str) -> String {
+    let payload = serde_json::from_str::<Value>(payload).unwrap_or(Value::Null);
+    json!({ "event": event, "payload": payload }).to_string()
+}
+
+/// Stops the server and waits until its socket is closed, so the address can be
+/// bound again right away.
+async fn stop_server(app: &AppHandle) {
+    let running = app
+        .state::<RemoteServer>()
+        .running
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    if let Some(mut running) = running {
+        for id in running.listeners {
+            app.unlisten(id);
+        }
+        let _ = running.shutdown.send(true);
+        // A `start_translation_job` call can hold its connection for the whole job.
+        if tokio::time::timeout(SHUTDOWN_GRACE, &mut running.task)
+            .await
+            .is_err()
+        {
+            running.task.abort();
+            let _ = running.task.await;
+        }
+        info!("Remote server on {} stopped", running.address);
+    }
+}
+
+async fn start_server(app: &AppHandle, settings: &ServerSettings) -> Result<(), AnimeSubsError> {
+    stop_server(app).await;
+    let token = server_token(app)?;
+    let bind = bind_address(settings);
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to listen on {}: {}", bind, e)))?;
+    let address = listener
+        .local_addr()
+        .map(|address| address.to_string())
+        .unwrap_or_else(|_| bind.to_string());
+
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    let listeners = FORWARDED_EVENTS
+        .iter()
+        .map(|&event| {
+            let events = events.clone();
+            app.listen_any(event, move |emitted| {
+                let _ = events.send(event_message(event, emitted.payload()));
+            })
+        })
+        .collect();
+
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let context = RouteContext {
+        app: app.clone(),
+        token,
+        events,
+        shutdown: shutdown_rx.clone(),
+    };
+    let router = Router::new()
+        .route("/health", get(health))
+        .route("/rpc", post(rpc))
+        .route("/events", get(events_socket))
+        .route("/webhooks/arr", post(arr_webhook))
+        .with_state(context);
+
+    let mut stopped = shutdown_rx;
+    let task = tauri::async_runtime::spawn(async move {
+        let served = axum::serve(listener, router)
+            .with_graceful_shutdown(async move {
+                let _ = stopped.changed().await;
+            })
+            .await;
+        if let Err(e) = served {
+            warn!("Remote server failed: {}", e);
+        }
+    });
+
+    info!("Remote server listening on {}", address);
+    *app.state::<RemoteServer>()
+        .running
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(RunningServer {
+        address,
+        shutdown,
+        listeners,
+        task,
+    });
+    Ok(())
+}
+
+fn server_status(app: &AppHandle) -> Result<ServerStatus, AnimeSubsError> {
+    let address = app
+        .state::<RemoteServer>()
+        .running
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|running| running.address.clone());
+    Ok(ServerStatus {
+        settings: read_server_settings(app)?,
+        address,
+        token: server_token(app)?,
+    })
+}
+
+/// Starts the server when it is enabled; called once at startup.
+pub(crate) async fn start_saved_server(app: &AppHandle) -> Result<(), AnimeSubsError> {
+    let settings = read_server_settings(app)?;
+    if settings.enabled {
+        start_server(app, &settings).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_server_status(app: AppHandle) -> Result<ServerStatus, AnimeSubsError> {
+    server_status(&app)
+}
+
+/// Saves the settings and starts, restarts or stops the server to match.
+#[tauri::command]
+pub async fn save_server_settings(
+    app: AppHandle,
+    settings: ServerSettings,
+) -> Result<ServerStatus, AnimeSubsError> {
+    let data = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize server settings: {}", e))?;
+    write_file_atomic(&app_config_path(&app, SERVER_FILE)?, data.as_bytes())?;
+
+    if settings.enabled {
+        start_server(&app, &settings).await?;
+    } else {
+        stop_server(&app).await;
+    }
+    server_status(&app)
+}
+
+/// Replaces the token; clients holding the old one are refused from now on.
+#[tauri::command]
+pub async fn regenerate_server_token(app: AppHandle) -> Result<ServerStatus, AnimeSubsError> {
+    write_api_key(&app, TOKEN_SECRET, &new_token()?)?;
+    let settings = read_server_settings(&app)?;
+    if app
+        .state::<RemoteServer>()
+        .running
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .is_some()
+    {
+        start_server(&app, &settings).await?;
+    }
+    server_status(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn accepts_the_token_from_header_or_query() {
+        let token = new_token().unwrap();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, new_token().unwrap());
+
+        let mut headers = HeaderMap::new();
+        assert!(!authorized(&headers, None, &token));
+        assert!(authorized(&headers, Some(&token), &token));
+        assert!(!authorized(&headers, Some("wrong"), &token));
+
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        assert!(authorized(&headers, None, &token));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer nope"));
+        assert!(!authorized(&headers, None, &token));
+    }
+
+    #[test]
+    fn shapes_json_rpc_replies() {
+        let ok = rpc_response(json!(1), Ok(json!({ "done": true })));
+        assert_eq!(ok["result"]["done"], true);
+        assert_eq!(ok["jsonrpc"], "2.0");
+
+        let missing = rpc_response(json!(2), Err(RpcError::MethodNotFound("nope".into())));
+        assert_eq!(missing["error"]["code"], RPC_METHOD_NOT_FOUND);
+
+        let failed = rpc_response(json!(3), Err(RpcError::Command(AnimeSubsError::Cancelled)));
+        assert_eq!(failed["error"]["code"], RPC_COMMAND_FAILED);
+        assert_eq!(failed["error"]["data"]["code"], "CANCELLED");
+
+        let bad: Result<JobParams, _> = params(Value::Null);
+        assert!(matches!(bad, Err(RpcError::InvalidParams(_))));
+        let tail: TailParams = params(Value::Null).ok().unwrap();
+        assert_eq!(tail.limit, None);
+    }
+
+    #[test]
+    fn wraps_event_payloads() {
+        let message: Value =
+            serde_json::from_str(&event_message("translation-job-paused", "true")).unwrap();
+        assert_eq!(
+            message,
+            json!({ "event": "translation-job-paused", "payload": true })
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use super::naming::validate_template;
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::{mkvtoolnix_tool_in, write_file_atomic};
+use std::fs;
+use std::sync::{LazyLock, RwLock};
+use tauri::AppHandle;
+use tracing::info;
+
+const SETTINGS_FILE: &str = "settings.json";
+/// GUI state file whose saved settings seed `settings.json` on first run.
+const APP_STATE_FILE: &str = "app_state.json";
+const SETTINGS_VERSION: u32 = 1;
+
+static SETTINGS: LazyLock<RwLock<AppSettings>> =
+    LazyLock::new(|| RwLock::new(AppSettings::default()));
+
+/// Saved settings as loaded at startup or last updated.
+pub(crate) fn current_settings() -> AppSettings {
+    SETTINGS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn set_current_settings(settings: AppSettings) {
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Brings settings written by any earlier version up to `SETTINGS_VERSION`. Fields
+/// added since then already took their serde defaults; blank strings become unset.
+fn migrate_settings(mut settings: AppSettings) -> AppSettings {
+    settings.ffmpeg_path = non_empty(settings.ffmpeg_path);
+    settings.mkvtoolnix_path = non_empty(settings.mkvtoolnix_path);
+    settings.naming_template = non_empty(settings.naming_template);
+    settings
+        .provider_endpoints
+        .retain(|provider, endpoint| !provider.trim().is_empty() && !endpoint.trim().is_empty());
+    settings.version = SETTINGS_VERSION;
+    settings
+}
+
+/// Settings the GUI already persisted in `app_state.json`, for installs that
+/// predate `settings.json`.
+fn settings_from_app_state(state: &serde_json::Value) -> AppSettings {
+    let saved = &state["settings"];
+    let text = |key: &str| non_empty(saved[key].as_str().map(str::to_string));
+
+    let mut settings = AppSettings {
+        ffmpeg_path: text("ffmpegPath"),
+        ..AppSettings::default()
+    };
+    if let (Some(provider), Some(endpoint)) = (text("provider"), text("apiEndpoint")) {
+        settings.provider_endpoints.insert(provider, endpoint);
+    }
+    settings
+}
+
+/// Reads `settings.json`, or seeds it from the GUI state on first run. The flag
+/// tells whether the file needs to be (re)written in the current format.
+fn read_settings(app: &AppHandle) -> Result<(AppSettings, bool), AnimeSubsError> {
+    let path = app_config_path(app, SETTINGS_FILE)?;
+    if !path.exists() {
+        let state_path = app_config_path(app, APP_STATE_FILE)?;
+        let seeded = fs::read_to_string(&state_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .map(|state| settings_from_app_state(&state))
+            .unwrap_or_default();
+        return Ok((migrate_settings(seeded), true));
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read settings: {}", e)))?;
+    let settings: AppSettings = serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse settings: {}", e)))?;
+    let outdated = settings.version < SETTINGS_VERSION;
+    Ok((migrate_settings(settings), outdated))
+}
+
+fn write_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), AnimeSubsError> {
+    let data = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    write_file_atomic(&app_config_path(app, SETTINGS_FILE)?, data.as_bytes())
+}
+
+fn validate_settings(settings: &AppSettings) -> Result<(), AnimeSubsError> {
+    if let Some(template) = &settings.naming_template {
+        validate_template(template)?;
+    }
+    if let Some(path) = &settings.mkvtoolnix_path {
+        if mkvtoolnix_tool_in(path, "mkvmerge").is_none() {
+            return Err(format!("mkvmerge not found in {}", path).into());
+        }
+    }
+    if settings.max_parallel_files == Some(0) {
+        return Err("max_parallel_files must be at least 1".into());
+    }
+    if settings.batch_token_budget == Some(0) {
+        return Err("batch_token_budget must be at least 1".into());
+    }
+    Ok(())
+}
+
+/// Loads the saved settings for every command; called once at startup.
+pub(crate) fn load_settings(app: &AppHandle) -> Result<(), AnimeSubsError> {
+    let (settings, needs_write) = read_settings(app)?;
+    if needs_write {
+        write_settings(app, &settings)?;
+        info!("Migrated settings to format {}", SETTINGS_VERSION);
+    }
+    set_current_settings(settings);
+    Ok(())
+}
+
+/// Fills in what an LLM call left unset from the saved settings, for `config` and
+/// each of its fallbacks.
+pub(crate) fn apply_saved_llm_defaults(config: &mut LLMConfig) {
+    let settings = current_settings();
+    let mut configs = vec![config];
+    while let Some(config) = configs.pop() {
+        if config.endpoint.trim().is_empty() {
+            if let Some(endpoint) = settings.provider_endpoints.get(config.provider.trim()) {
+                config.endpoint = endpoint.clone();
+            }
+        }
+        if config.batch_token_budget.is_none() {
+            config.batch_token_budget = settings.batch_token_budget;
+        }
+        configs.extend(config.fallbacks.iter_mut());
+    }
+}
+
+/// Fills in the job options the request left unset from the saved settings.
+pub(crate) fn apply_saved_job_defaults(request: &mut TranslationJobRequest) {
+    let settings = current_settings();
+    if non_empty(request.ffmpeg_path.clone()).is_none() {
+        request.ffmpeg_path = settings.ffmpeg_path;
+    }
+    if request.max_parallel_files.is_none() {
+        request.max_parallel_files = settings.max_parallel_files;
+    }
+    if request.rate_limit.is_none() {
+        request.rate_limit = settings.rate_limit;
+    }
+    if request.naming_template.is_none() {
+        request.naming_template = settings.naming_template;
+    }
+    if request.strip_sdh.is_none() {
+        request.strip_sdh = settings.strip_sdh;
+    }
+    apply_saved_llm_defaults(&mut request.config);
+}
+
+#[tauri::command]
+pub async fn get_settings() -> Result<AppSettings, AnimeSubsError> {
+    Ok(current_settings())
+}
+
+/// Merges `patch` (any subset of the settings fields; `null` clears one) into the
+/// saved settings and returns the result.
+#[tauri::command]
+pub async fn update_settings(
+    app: AppHandle,
+    patch: serde_json::Value,
+) -> Result<AppSettings, AnimeSubsError> {
+    let serde_json::Value::Object(patch) = patch else {
+        return Err(AnimeSubsError::parse("Settings update must be an object"));
+    };
+    let mut merged = serde_json::to_value(current_settings())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if let serde_json::Value::Object(fields) = &mut merged {
+        fields.extend(patch);
+    }
+    let settings: AppSettings = serde_json::from_value(merged)
+        .map_err(|e| AnimeSubsError::parse(format!("Invalid settings: {}", e)))?;
+    let settings = migrate_settings(settings);
+    validate_settings(&settings)?;
+
+    write_settings(&app, &settings)?;
+    set_current_settings(settings.clone());
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_from_saved_gui_state() {
+        let state = serde_json::json!({
+            "version": 1,
+            "settings": {
+                "provider": "ollama",
+                "apiEndpoint": "http://localhost:11434/v1",
+                "ffmpegPath": "  ",
+            },
+        });
+
+        let settings = migrate_settings(settings_from_app_state(&state));
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.ffmpeg_path, None);
+        assert_eq!(
+            settings
+                .provider_endpoints
+                .get("ollama")
+                .map(String::as_str),
+            Some("http://localhost:11434/v1")
+        );
+        assert!(
+            migrate_settings(settings_from_app_state(&serde_json::Value::Null))
+                .provider_endpoints
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn migrates_older_files_with_defaults() {
+        let settings: AppSettings = serde_json::from_str(
+            r#"{ "ffmpegPath": "/opt/ffmpeg", "namingTemplate": "", "mkvtoolnixPath": " " }"#,
+        )
+        .unwrap();
+        assert_eq!(settings.version, 0);
+
+        let settings = migrate_settings(settings);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.ffmpeg_path.as_deref(), Some("/opt/ffmpeg"));
+        assert_eq!(settings.naming_template, None);
+        assert_eq!(settings.mkvtoolnix_path, None);
+        assert!(settings.rate_limit.is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        let settings = AppSettings {
+            max_parallel_files: Some(0),
+            ..AppSettings::default()
+        };
+        assert!(validate_settings(&settings).is_err());
+
+        let settings = AppSettings {
+            naming_template: Some("{title}".to_string()),
+            ..AppSettings::default()
+        };
+        assert!(validate_settings(&settings).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::models::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub(crate) const SIDECAR_EXTENSIONS: [&str; 4] = ["ass", "ssa", "srt", "vtt"];
+const FLAG_TOKENS: [&str; 4] = ["forced", "sdh", "cc", "default"];
+
+/// "en", "eng", "pt-BR", "zh_Hans" and the like.
+fn is_language_token(token: &str) -> bool {
+    let mut parts = token.split(['-', '_']);
+    let base = parts.next().unwrap_or_default();
+    let base_ok = (2..=3).contains(&base.len()) && base.chars().all(|c| c.is_ascii_alphabetic());
+    base_ok
+        && parts.all(|part| {
+            (2..=4).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// Reads the part of a sidecar name after the video stem (".en.forced", ".[Group].pt-BR").
+fn parse_suffix(path: &Path, suffix: &str, format: &str) -> SidecarSubtitle {
+    let mut language = None;
+    let mut forced = false;
+    let mut labels = Vec::new();
+    for token in suffix.split('.').map(str::trim).filter(|t| !t.is_empty()) {
+        let lower = token.to_ascii_lowercase();
+        if FLAG_TOKENS.contains(&lower.as_str()) {
+            forced |= lower == "forced";
+        } else if language.is_none() && is_language_token(token) {
+            language = Some(token.replace('_', "-"));
+        } else {
+            labels.push(token.trim_matches(['[', ']', '(', ')']).to_string());
+        }
+    }
+
+    SidecarSubtitle {
+        path: path.to_string_lossy().to_string(),
+        format: format.to_string(),
+        language,
+        label: Some(labels.join(" ")).filter(|l| !l.is_empty()),
+        forced,
+    }
+}
+
+/// Pairs subtitle files with the video whose stem they start with. A sidecar
+/// belongs to the longest matching stem, so "Ep 1.ass" never lands on "Ep 10.mkv".
+pub(crate) fn pair_sidecars(
+    videos: &[PathBuf],
+    subtitles: &[PathBuf],
+) -> HashMap<PathBuf, Vec<SidecarSubtitle>> {
+    let mut paired: HashMap<PathBuf, Vec<SidecarSubtitle>> = HashMap::new();
+
+    for subtitle in subtitles {
+        let Some(format) = subtitle
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+        else {
+            continue;
+        };
+        let Some(subtitle_stem) = subtitle
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+
+        let best = videos
+            .iter()
+            .filter(|video| video.parent() == subtitle.parent())
+            .filter_map(|video| {
+                let stem = video.file_stem()?.to_string_lossy().to_string();
+                let suffix = subtitle_stem.strip_prefix(stem.as_str())?;
+                (suffix.is_empty() || suffix.starts_with('.')).then_some((
+                    video,
+                    stem.len(),
+                    suffix,
+                ))
+            })
+            .max_by_key(|(_, stem_len, _)| *stem_len);
+
+        if let Some((video, _, suffix)) = best {
+            paired
+                .entry(video.clone())
+                .or_default()
+                .push(parse_suffix(subtitle, suffix, &format));
+        }
+    }
+
+    for sidecars in paired.values_mut() {
+        sidecars.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    paired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_sidecars_by_stem_and_language_suffix() {
+        let videos = vec![
+            PathBuf::from("/anime/Show - 1.mkv"),
+            PathBuf::from("/anime/Show - 10.mkv"),
+        ];
+        let subtitles = vec![
+            PathBuf::from("/anime/Show - 1.ass"),
+            PathBuf::from("/anime/Show - 10.en.forced.srt"),
+            PathBuf::from("/anime/Show - 10.[Fansub].pt_BR.ass"),
+            PathBuf::from("/anime/Other.srt"),
+        ];
+
+        let paired = pair_sidecars(&videos, &subtitles);
+        assert_eq!(paired.len(), 2);
+
+        let first = &paired[&videos[0]];
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].language, None);
+        assert_eq!(first[0].format, "ass");
+
+        let tenth = &paired[&videos[1]];
+        assert_eq!(tenth.len(), 2);
+        assert_eq!(tenth[0].language.as_deref(), Some("pt-BR"));
+        assert_eq!(tenth[0].label.as_deref(), Some("Fansub"));
+        assert_eq!(tenth[1].language.as_deref(), Some("en"));
+        assert!(tenth[1].forced);
+    }
+}
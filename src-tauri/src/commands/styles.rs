@@ -0,0 +1,530 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+
+const STYLE_FORMAT: &str = "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, \
+    OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, \
+    Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding";
+/// `[V4 Styles]` layout of SSA v4.00 scripts, used when saving styles back into one.
+const LEGACY_STYLE_FORMAT: &str = "Format: Name, Fontname, Fontsize, PrimaryColour, \
+    SecondaryColour, TertiaryColour, BackColour, Bold, Italic, BorderStyle, Outline, Shadow, \
+    Alignment, MarginL, MarginR, MarginV, AlphaLevel, Encoding";
+
+/// Values Aegisub gives a new style; used for fields a `Format:` line leaves out.
+fn default_style(name: &str) -> AssStyle {
+    AssStyle {
+        name: name.to_string(),
+        fontname: "Arial".to_string(),
+        fontsize: 20.0,
+        primary_colour: "&H00FFFFFF".to_string(),
+        secondary_colour: "&H000000FF".to_string(),
+        outline_colour: "&H00000000".to_string(),
+        back_colour: "&H00000000".to_string(),
+        bold: false,
+        italic: false,
+        underline: false,
+        strike_out: false,
+        scale_x: 100.0,
+        scale_y: 100.0,
+        spacing: 0.0,
+        angle: 0.0,
+        border_style: 1,
+        outline: 2.0,
+        shadow: 2.0,
+        alignment: 2,
+        margin_l: 10,
+        margin_r: 10,
+        margin_v: 10,
+        encoding: 1,
+    }
+}
+
+fn is_styles_header(line: &str) -> bool {
+    let line = line.trim();
+    line.eq_ignore_ascii_case("[v4+ styles]") || is_legacy_styles_header(line)
+}
+
+fn is_legacy_styles_header(line: &str) -> bool {
+    line.trim().eq_ignore_ascii_case("[v4 styles]")
+}
+
+/// SSA alignment (1-3 bottom, +4 top, +8 middle) to the numpad layout ASS uses.
+fn alignment_from_legacy(alignment: u32) -> u32 {
+    match alignment {
+        5..=7 => alignment + 2,
+        9..=11 => alignment - 5,
+        _ => alignment,
+    }
+}
+
+fn alignment_to_legacy(alignment: u32) -> u32 {
+    match alignment {
+        7..=9 => alignment - 2,
+        4..=6 => alignment + 5,
+        _ => alignment,
+    }
+}
+
+/// SSA scripts often store colours as decimal BGR integers; ASS expects `&HAABBGGRR`.
+fn normalize_colour(value: &str) -> String {
+    match value.parse::<u32>() {
+        Ok(colour) => format!("&H{:08X}", colour),
+        Err(_) => value.to_string(),
+    }
+}
+
+fn parse_style_line(fields: &[String], line: &str, legacy: bool) -> Option<AssStyle> {
+    let values: Vec<&str> = line.split(',').map(str::trim).collect();
+    let field = |name: &str| {
+        fields
+            .iter()
+            .position(|f| f == name)
+            .and_then(|idx| values.get(idx).copied())
+    };
+    let mut style = default_style(field("name")?);
+
+    macro_rules! read {
+        ($field:ident, $name:literal) => {
+            if let Some(value) = field($name).and_then(|v| v.parse().ok()) {
+                style.$field = value;
+            }
+        };
+    }
+    macro_rules! read_text {
+        ($field:ident, $name:literal) => {
+            if let Some(value) = field($name) {
+                style.$field = value.to_string();
+            }
+        };
+    }
+    macro_rules! read_flag {
+        ($field:ident, $name:literal) => {
+            if let Some(value) = field($name) {
+                style.$field = value != "0";
+            }
+        };
+    }
+
+    read_text!(fontname, "fontname");
+    read!(fontsize, "fontsize");
+    read_text!(primary_colour, "primarycolour");
+    read_text!(secondary_colour, "secondarycolour");
+    read_text!(outline_colour, "outlinecolour");
+    read_text!(outline_colour, "tertiarycolour");
+    read_text!(back_colour, "backcolour");
+    read_flag!(bold, "bold");
+    read_flag!(italic, "italic");
+    read_flag!(underline, "underline");
+    read_flag!(strike_out, "strikeout");
+    read!(scale_x, "scalex");
+    read!(scale_y, "scaley");
+    read!(spacing, "spacing");
+    read!(angle, "angle");
+    read!(border_style, "borderstyle");
+    read!(outline, "outline");
+    read!(shadow, "shadow");
+    read!(alignment, "alignment");
+    read!(margin_l, "marginl");
+    read!(margin_r, "marginr");
+    read!(margin_v, "marginv");
+    read!(encoding, "encoding");
+
+    for colour in [
+        &mut style.primary_colour,
+        &mut style.secondary_colour,
+        &mut style.outline_colour,
+        &mut style.back_colour,
+    ] {
+        *colour = normalize_colour(colour);
+    }
+    if legacy {
+        style.alignment = alignment_from_legacy(style.alignment);
+    }
+    Some(style)
+}
+
+/// Reads the `[V4+ Styles]` section (or `[V4 Styles]` of SSA scripts), honoring the
+/// field order of its `Format:` line.
+pub(crate) fn parse_ass_styles(content: &str) -> Vec<AssStyle> {
+    let mut styles = Vec::new();
+    let mut fields: Vec<String> = Vec::new();
+    let mut in_styles = false;
+    let mut legacy = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_styles = is_styles_header(trimmed);
+            legacy = is_legacy_styles_header(trimmed);
+            continue;
+        }
+        if !in_styles {
+            continue;
+        }
+        if let Some(format) = trimmed.strip_prefix("Format:") {
+            fields = format
+                .split(',')
+                .map(|f| f.trim().to_ascii_lowercase())
+                .collect();
+        } else if let Some(style) = trimmed.strip_prefix("Style:") {
+            styles.extend(parse_style_line(&fields, style, legacy));
+        }
+    }
+    styles
+}
+
+fn ass_flag(value: bool) -> &'static str {
+    if value {
+        "-1"
+    } else {
+        "0"
+    }
+}
+
+fn format_style_line(style: &AssStyle) -> String {
+    format!(
+        "Style: {},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        style.name,
+        style.fontname,
+        style.fontsize,
+        style.primary_colour,
+        style.secondary_colour,
+        style.outline_colour,
+        style.back_colour,
+        ass_flag(style.bold),
+        ass_flag(style.italic),
+        ass_flag(style.underline),
+        ass_flag(style.strike_out),
+        style.scale_x,
+        style.scale_y,
+        style.spacing,
+        style.angle,
+        style.border_style,
+        style.outline,
+        style.shadow,
+        style.alignment,
+        style.margin_l,
+        style.margin_r,
+        style.margin_v,
+        style.encoding
+    )
+}
+
+fn format_legacy_style_line(style: &AssStyle) -> String {
+    format!(
+        "Style: {},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},0,{}",
+        style.name,
+        style.fontname,
+        style.fontsize,
+        style.primary_colour,
+        style.secondary_colour,
+        style.outline_colour,
+        style.back_colour,
+        ass_flag(style.bold),
+        ass_flag(style.italic),
+        style.border_style,
+        style.outline,
+        style.shadow,
+        alignment_to_legacy(style.alignment),
+        style.margin_l,
+        style.margin_r,
+        style.margin_v,
+        style.encoding
+    )
+}
+
+fn validate_style(style: &AssStyle) -> Result<(), AnimeSubsError> {
+    if style.name.trim().is_empty() || style.name.contains(',') {
+        return Err(format!("Invalid style name: {:?}", style.name).into());
+    }
+    if style.fontname.contains(',') {
+        return Err(format!("Font names cannot contain commas: {}", style.fontname).into());
+    }
+    if style.fontsize <= 0.0 {
+        return Err(format!("Style {} needs a positive font size", style.name).into());
+    }
+    if !(1..=9).contains(&style.alignment) {
+        return Err(format!("Style {} has an invalid alignment", style.name).into());
+    }
+    for colour in [
+        &style.primary_colour,
+        &style.secondary_colour,
+        &style.outline_colour,
+        &style.back_colour,
+    ] {
+        let hex = colour.trim_start_matches("&H").trim_end_matches('&');
+        if hex.is_empty() || hex.len() > 8 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid ASS colour in style {}: {}", style.name, colour).into());
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the `[V4+ Styles]` section with `styles` in the standard field order.
+/// SSA scripts keep a `[V4 Styles]` section in the legacy layout, dropping what SSA
+/// cannot express. A file without one gets the section inserted before `[Events]`.
+pub(crate) fn replace_ass_styles(
+    content: &str,
+    styles: &[AssStyle],
+) -> Result<String, AnimeSubsError> {
+    for style in styles {
+        validate_style(style)?;
+    }
+
+    let legacy = content.lines().any(is_legacy_styles_header);
+    let mut section = if legacy {
+        let mut section = vec!["[V4 Styles]".to_string(), LEGACY_STYLE_FORMAT.to_string()];
+        section.extend(styles.iter().map(format_legacy_style_line));
+        section
+    } else {
+        let mut section = vec!["[V4+ Styles]".to_string(), STYLE_FORMAT.to_string()];
+        section.extend(styles.iter().map(format_style_line));
+        section
+    };
+    section.push(String::new());
+
+    let mut result: Vec<String> = Vec::new();
+    let mut in_styles = false;
+    let mut written = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_styles = is_styles_header(trimmed);
+            if in_styles {
+                result.append(&mut section.clone());
+                written = true;
+                continue;
+            }
+            if !written && trimmed.eq_ignore_ascii_case("[events]") {
+                result.append(&mut section.clone());
+                written = true;
+            }
+        }
+        if !in_styles {
+            result.push(line.to_string());
+        }
+    }
+
+    if !written {
+        return Err(AnimeSubsError::parse(
+            "Not an ASS file: no [Events] section",
+        ));
+    }
+    Ok(result.join("\n") + "\n")
+}
+
+pub(crate) fn apply_style_patch(style: &mut AssStyle, patch: &AssStylePatch) {
+    macro_rules! set {
+        ($($field:ident),*) => {
+            $(if let Some(value) = patch.$field.as_ref().cloned() {
+                style.$field = value;
+            })*
+        };
+    }
+    set!(
+        fontname,
+        fontsize,
+        primary_colour,
+        secondary_colour,
+        outline_colour,
+        back_colour,
+        bold,
+        italic,
+        outline,
+        shadow,
+        alignment,
+        margin_l,
+        margin_r,
+        margin_v
+    );
+}
+
+/// Applies `patch` to the styles named in `style_names`, or to every dialogue style
+/// (not signs, songs or credits) when no names are given.
+pub(crate) fn apply_style_preset(
+    content: &str,
+    patch: &AssStylePatch,
+    style_names: Option<&[String]>,
+) -> Result<String, AnimeSubsError> {
+    let mut styles = parse_ass_styles(content);
+    if styles.is_empty() {
+        return Err("No [V4+ Styles] found in subtitle".into());
+    }
+
+    for style in &mut styles {
+        let selected = match style_names {
+            Some(names) => names.iter().any(|n| n == &style.name),
+            None => !is_sign_or_song_style(&style.name),
+        };
+        if selected {
+            apply_style_patch(style, patch);
+        }
+    }
+    replace_ass_styles(content, &styles)
+}
+
+fn ensure_ass_path(subtitle_path: &str) -> Result<(), AnimeSubsError> {
+    let is_ass = std::path::Path::new(subtitle_path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("ass") || e.eq_ignore_ascii_case("ssa"));
+    if is_ass {
+        Ok(())
+    } else {
+        Err("Styles can only be edited in .ass and .ssa files".into())
+    }
+}
+
+#[tauri::command]
+pub async fn list_ass_styles(subtitle_path: String) -> Result<Vec<AssStyle>, AnimeSubsError> {
+    ensure_ass_path(&subtitle_path)?;
+    Ok(parse_ass_styles(&read_file_as_utf8(&subtitle_path)?))
+}
+
+/// Writes `styles` as the file's style block, to `output_path` or in place.
+#[tauri::command]
+pub async fn save_ass_styles(
+    subtitle_path: String,
+    styles: Vec<AssStyle>,
+    output_path: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    ensure_ass_path(&subtitle_path)?;
+    let content = replace_ass_styles(&read_file_as_utf8(&subtitle_path)?, &styles)?;
+    let output = output_path
+        .filter(|p| !p.is_empty())
+        .unwrap_or(subtitle_path);
+    write_utf8_file(&output, &content, true)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: format!("Saved {} styles", styles.len()),
+        data: Some(output),
+        verification: None,
+    })
+}
+
+/// Restyles dialogue (or the named styles) with `preset`, e.g. a larger font for
+/// translated output, to `output_path` or in place.
+#[tauri::command]
+pub async fn apply_ass_style_preset(
+    subtitle_path: String,
+    preset: AssStylePatch,
+    style_names: Option<Vec<String>>,
+    output_path: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    ensure_ass_path(&subtitle_path)?;
+    let content = apply_style_preset(
+        &read_file_as_utf8(&subtitle_path)?,
+        &preset,
+        style_names.as_deref(),
+    )?;
+    let output = output_path
+        .filter(|p| !p.is_empty())
+        .unwrap_or(subtitle_path);
+    write_utf8_file(&output, &content, true)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "Style preset applied".to_string(),
+        data: Some(output),
+        verification: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASS: &str = r#"[Script Info]
+Title: Example
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, Bold, Italic, Alignment, MarginV, Encoding
+Style: Default,Trebuchet MS,24.5,&H00FFFFFF,-1,0,2,20,1
+Style: Signs,Arial,18,&H0000FFFF,0,0,8,10,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello
+"#;
+
+    #[test]
+    fn parses_styles_using_the_format_line_order() {
+        let styles = parse_ass_styles(ASS);
+
+        assert_eq!(styles.len(), 2);
+        assert_eq!(styles[0].name, "Default");
+        assert_eq!(styles[0].fontname, "Trebuchet MS");
+        assert_eq!(styles[0].fontsize, 24.5);
+        assert!(styles[0].bold && !styles[0].italic);
+        assert_eq!(styles[0].margin_v, 20);
+        assert_eq!(styles[1].alignment, 8);
+        // Missing fields fall back to the defaults.
+        assert_eq!(styles[1].outline_colour, "&H00000000");
+        assert_eq!(styles[1].scale_x, 100.0);
+    }
+
+    #[test]
+    fn preset_restyles_dialogue_and_keeps_signs_and_events() {
+        let preset = AssStylePatch {
+            fontname: Some("Open Sans".to_string()),
+            fontsize: Some(32.0),
+            outline: Some(3.0),
+            ..AssStylePatch::default()
+        };
+
+        let output = apply_style_preset(ASS, &preset, None).unwrap();
+
+        assert!(output.contains(STYLE_FORMAT));
+        assert!(output.contains(
+            "Style: Default,Open Sans,32,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,\
+             -1,0,0,0,100,100,0,0,1,3,2,2,10,10,20,1"
+        ));
+        assert!(output.contains("Style: Signs,Arial,18,&H0000FFFF,"));
+        assert!(output.contains("Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello"));
+        assert_eq!(output.matches("[V4+ Styles]").count(), 1);
+        assert_eq!(parse_ass_styles(&output).len(), 2);
+    }
+
+    #[test]
+    fn inserts_missing_style_section_and_rejects_invalid_styles() {
+        let content = "[Script Info]\nTitle: x\n\n[Events]\nFormat: Layer, Text\n";
+        let output = replace_ass_styles(content, &[default_style("Default")]).unwrap();
+        assert!(output.contains("Title: x\n\n[V4+ Styles]\n"));
+        assert!(output.contains("\n\n[Events]\n"));
+
+        let mut broken = default_style("Default");
+        broken.primary_colour = "white".to_string();
+        assert!(replace_ass_styles(content, &[broken]).is_err());
+    }
+
+    #[test]
+    fn reads_and_writes_legacy_ssa_styles() {
+        let content = r#"[Script Info]
+ScriptType: v4.00
+
+[V4 Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, TertiaryColour, BackColour, Bold, Italic, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, AlphaLevel, Encoding
+Style: Default,Tahoma,24,16777215,65535,0,0,-1,0,1,2,1,7,30,30,12,0,0
+
+[Events]
+Format: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: Marked=0,0:00:01.00,0:00:02.00,Default,,0000,0000,0000,,Hello
+"#;
+
+        let styles = parse_ass_styles(content);
+        assert_eq!(styles.len(), 1);
+        assert_eq!(styles[0].primary_colour, "&H00FFFFFF");
+        assert_eq!(styles[0].secondary_colour, "&H0000FFFF");
+        assert_eq!(styles[0].alignment, 9);
+        assert_eq!(styles[0].margin_v, 12);
+
+        let output = replace_ass_styles(content, &styles).unwrap();
+        assert!(output.contains("[V4 Styles]\n"));
+        assert!(!output.contains("[V4+ Styles]"));
+        assert!(output.contains(
+            "Style: Default,Tahoma,24,&H00FFFFFF,&H0000FFFF,&H00000000,&H00000000,\
+             -1,0,1,2,1,7,30,30,12,0,0"
+        ));
+        assert!(output.contains("Dialogue: Marked=0,0:00:01.00,0:00:02.00,Default,,"));
+    }
+}
@@ -1,18 +1,26 @@
+use super::naming::{render_output_name, NamingVars};
+use super::operations::run_ffmpeg_with_progress;
+use crate::error::AnimeSubsError;
 use crate::models::*;
 use crate::utils::*;
 use regex::Regex;
+use std::fs;
 use std::path::Path;
 use std::sync::LazyLock;
+use tauri::AppHandle;
+use tracing::warn;
 
 #[tauri::command]
 pub async fn extract_subtitle(
+    app: AppHandle,
     video_path: String,
     track_index: u32,
     output_path: Option<String>,
     format: Option<String>,
     temporary: Option<bool>,
     ffmpeg_path: Option<String>,
-) -> Result<ExtractResult, String> {
+    naming_template: Option<String>,
+) -> Result<ExtractResult, AnimeSubsError> {
     let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
 
     let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path).await?;
@@ -20,7 +28,7 @@ pub async fn extract_subtitle(
     let track = video_info
         .subtitle_tracks
         .get(track_index as usize)
-        .ok_or("Subtitle track not found")?;
+        .ok_or_else(|| AnimeSubsError::TrackNotFound("Subtitle track not found".to_string()))?;
 
     let fmt = resolve_extraction_format(format.as_deref(), &track.codec);
 
@@ -30,34 +38,50 @@ pub async fn extract_subtitle(
         build_temp_subtitle_path(&video_path, &format!("extract_track{}", track_index), &fmt)?
     } else {
         let video_pathbuf = Path::new(&video_path);
-        let stem = video_pathbuf
-            .file_stem()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| "subtitle".to_string());
         let lang = track.language.as_deref().unwrap_or("und");
         let parent = video_pathbuf.parent().unwrap_or(Path::new("."));
-        parent.join(format!("{}.{}.{}", stem, lang, fmt))
+        let file_name = match naming_template.as_deref() {
+            Some(template) => {
+                let vars = NamingVars::for_file(&video_path, lang, &fmt, Some(track_index));
+                render_output_name(template, &vars)?
+            }
+            None => {
+                let stem = video_pathbuf
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "subtitle".to_string());
+                format!("{}.{}.{}", stem, lang, fmt)
+            }
+        };
+        parent.join(file_name)
     };
 
-    let result = create_command(&ffmpeg)
-        .args([
-            "-i",
-            &video_path,
-            "-map",
-            &format!("0:s:{}", track_index),
-            "-c:s",
-            if fmt == "srt" {
-                "srt"
-            } else if fmt == "ass" {
-                "ass"
-            } else {
-                "webvtt"
-            },
-            "-y",
-            output.to_str().unwrap(),
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    let codec = match fmt.as_str() {
+        "srt" => "srt",
+        "ass" => "ass",
+        _ => "webvtt",
+    };
+    let args = vec![
+        "-i".to_string(),
+        ffmpeg_file(&video_path),
+        "-map".to_string(),
+        format!("0:s:{}", track_index),
+        "-c:s".to_string(),
+        codec.to_string(),
+        "-y".to_string(),
+        ffmpeg_file(&output.to_string_lossy()),
+    ];
+
+    let result = run_ffmpeg_with_progress(
+        &app,
+        &ffmpeg,
+        &args,
+        "extract",
+        &video_path,
+        video_info.duration,
+        vec![output.clone()],
+    )
+    .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
 
     if result.status.success() {
         Ok(ExtractResult {
@@ -74,6 +98,102 @@ pub async fn extract_subtitle(
     }
 }
 
+/// Keeps a track name apart from the other tracks of the same language.
+const DEFAULT_BATCH_TEMPLATE: &str = "{stem}.{lang}.{track}.{ext}";
+
+fn matches_language(track: &SubtitleTrack, languages: &[String]) -> bool {
+    languages.is_empty()
+        || track
+            .language
+            .as_deref()
+            .is_some_and(|lang| languages.iter().any(|l| l.eq_ignore_ascii_case(lang)))
+}
+
+/// Extracts every text subtitle track (or those in `languages`) of every video in
+/// `folder_path`, named by `naming_template`. Failures are recorded per track in the
+/// manifest instead of stopping the batch.
+#[tauri::command]
+pub async fn extract_all_subtitles(
+    app: AppHandle,
+    folder_path: String,
+    languages: Option<Vec<String>>,
+    format: Option<String>,
+    naming_template: Option<String>,
+    ffmpeg_path: Option<String>,
+) -> Result<ExtractionManifest, AnimeSubsError> {
+    let template = naming_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_BATCH_TEMPLATE.to_string());
+    super::naming::validate_template(&template)?;
+    let languages = languages.unwrap_or_default();
+
+    let videos = super::video::scan_folder_for_videos(folder_path.clone()).await?;
+    let mut tracks = Vec::new();
+    for video in &videos {
+        let info = match super::video::get_video_info(video.path.clone(), ffmpeg_path.clone()).await
+        {
+            Ok(info) => info,
+            Err(error) => {
+                warn!("Skipping {}: {}", video.path, error);
+                continue;
+            }
+        };
+
+        for track in info
+            .subtitle_tracks
+            .iter()
+            .filter(|t| matches_language(t, &languages))
+        {
+            let mut entry = ExtractedTrack {
+                video_path: video.path.clone(),
+                track_index: track.index,
+                codec: track.codec.clone(),
+                language: track.language.clone(),
+                title: track.title.clone(),
+                output_path: None,
+                error: None,
+            };
+            if super::track_selection::IMAGE_CODECS.contains(&track.codec.as_str()) {
+                entry.error = Some(format!(
+                    "Image-based subtitles ({}) are not text",
+                    track.codec
+                ));
+                tracks.push(entry);
+                continue;
+            }
+
+            match extract_subtitle(
+                app.clone(),
+                video.path.clone(),
+                track.index,
+                None,
+                format.clone(),
+                Some(false),
+                ffmpeg_path.clone(),
+                Some(template.clone()),
+            )
+            .await
+            {
+                Ok(result) => {
+                    entry.output_path = result.output_path;
+                    entry.error = result.error;
+                }
+                Err(error) => entry.error = Some(error.to_string()),
+            }
+            tracks.push(entry);
+        }
+    }
+
+    let extracted = tracks.iter().filter(|t| t.output_path.is_some()).count();
+    Ok(ExtractionManifest {
+        folder: folder_path,
+        video_count: videos.len(),
+        extracted,
+        failed: tracks.len() - extracted,
+        tracks,
+    })
+}
+
 fn resolve_extraction_format(format: Option<&str>, codec: &str) -> String {
     match format.map(|value| value.trim().to_ascii_lowercase()) {
         Some(value) if !value.is_empty() && value != "auto" => value,
@@ -90,15 +210,11 @@ fn resolve_extraction_format(format: Option<&str>, codec: &str) -> String {
     }
 }
 
-fn parse_ass_file(content: &str) -> Result<SubtitleData, String> {
+fn parse_ass_file(content: &str) -> Result<SubtitleData, AnimeSubsError> {
     let mut lines: Vec<DialogLine> = Vec::new();
     let mut in_events = false;
     let mut header_end = 0;
-
-    let skip_styles: Vec<&str> = vec![
-        "op", "ed", "opening", "ending", "karaoke", "romaji", "japanese", "sign", "signs", "title",
-        "song", "lyrics", "insert", "credit", "credits",
-    ];
+    let mut format = AssEventFormat::default();
 
     for (line_num, line) in content.lines().enumerate() {
         let trimmed = line.trim();
@@ -113,48 +229,52 @@ fn parse_ass_file(content: &str) -> Result<SubtitleData, String> {
             break;
         }
 
-        if in_events && trimmed.starts_with("Dialogue:") {
-            let parts: Vec<&str> = trimmed.splitn(10, ',').collect();
-            if parts.len() >= 10 {
-                let start = parts[1].trim().to_string();
-                let end = parts[2].trim().to_string();
-                let style = Some(parts[3].trim().to_string());
-                let name = {
-                    let n = parts[4].trim();
-                    if n.is_empty() {
-                        None
-                    } else {
-                        Some(n.to_string())
-                    }
-                };
-                let original_text = parts[9..].join(",");
-                let clean_text = strip_ass_tags(&original_text);
-                let is_music_line = is_music_or_karaoke_line(&original_text, &clean_text);
-
-                let style_lower = style.as_ref().map(|s| s.to_lowercase()).unwrap_or_default();
-                let should_skip_style = skip_styles.iter().any(|&skip| {
-                    style_lower.contains(skip)
-                        || style_lower.split_whitespace().any(|word| word == skip)
-                });
+        if in_events && trimmed.starts_with("Format:") {
+            format = AssEventFormat::parse(&trimmed["Format:".len()..]);
+            continue;
+        }
 
-                let is_too_short = clean_text.trim().chars().count() < 3;
-
-                if !clean_text.trim().is_empty()
-                    && !should_skip_style
-                    && !is_too_short
-                    && !is_music_line
-                {
-                    lines.push(DialogLine {
-                        index: lines.len(),
-                        text: clean_text,
-                        original_with_formatting: original_text,
-                        start,
-                        end,
-                        style,
-                        name,
-                    });
+        let event = trimmed
+            .split_once(':')
+            .and_then(|(label, values)| Some((AssEventKind::from_label(label)?, values)));
+        if let Some((event, values)) = event.filter(|_| in_events) {
+            let Some(parts) = format.split(values) else {
+                continue;
+            };
+            let field = |name: &str| format.get(&parts, name);
+            let (Some(start), Some(end), Some(original_text)) =
+                (field("start"), field("end"), field("text"))
+            else {
+                continue;
+            };
+            let style = field("style").map(|s| s.trim().to_string());
+            let name = field("name")
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+                .map(str::to_string);
+            let clean_text = strip_ass_tags(original_text);
+            let classification = match event {
+                AssEventKind::Dialogue => {
+                    classify_line(style.as_deref(), original_text, &clean_text)
                 }
-            }
+                AssEventKind::Comment => LineClass::Comment,
+                _ => LineClass::Other,
+            };
+
+            lines.push(DialogLine {
+                index: lines.len(),
+                text: clean_text,
+                original_with_formatting: original_text.to_string(),
+                start: start.trim().to_string(),
+                end: end.trim().to_string(),
+                style,
+                name,
+                reference: None,
+                formatting: None,
+                event,
+                classification,
+                skip: classification != LineClass::Dialogue,
+            });
         }
     }
 
@@ -167,20 +287,149 @@ fn parse_ass_file(content: &str) -> Result<SubtitleData, String> {
     Ok(SubtitleData {
         format: "ass".to_string(),
         line_count: lines.len(),
+        class_counts: LineClassCounts::from_lines(&lines),
+        deduplicated_lines: 0,
         lines,
         source_path: String::new(),
         ass_header: Some(header),
+        failed_line_ids: Vec::new(),
+        batch_providers: Vec::new(),
     })
 }
 
+/// Sorts an event into a [`LineClass`]. `style` is `None` for SRT/VTT cues, which are
+/// never too short: only ASS scripts carry one- and two-character typesetting.
+fn classify_line(style: Option<&str>, original_text: &str, clean_text: &str) -> LineClass {
+    let original_lower = original_text.to_ascii_lowercase();
+    if original_lower.contains("\\k") {
+        LineClass::Karaoke
+    } else if style.is_some_and(is_sign_or_song_style)
+        || original_lower.contains("\\p1")
+        || clean_text.trim().is_empty()
+    {
+        LineClass::Sign
+    } else if is_music_or_karaoke_line(original_text, clean_text) {
+        LineClass::Music
+    } else if style.is_some() && clean_text.trim().chars().count() < 3 {
+        LineClass::Short
+    } else {
+        LineClass::Dialogue
+    }
+}
+
+/// Builds an SRT/VTT cue line, classified so music cues are kept but skipped.
+fn cue_line(
+    index: usize,
+    text: String,
+    start: String,
+    end: String,
+    formatting: Option<LineFormatting>,
+) -> DialogLine {
+    let classification = classify_line(None, &text, &text);
+    DialogLine {
+        index,
+        original_with_formatting: text.clone(),
+        text,
+        start,
+        end,
+        style: None,
+        name: None,
+        reference: None,
+        formatting,
+        event: AssEventKind::Dialogue,
+        classification,
+        skip: classification != LineClass::Dialogue,
+    }
+}
+
 static HTML_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
 
-fn parse_srt_file(content: &str) -> Result<SubtitleData, String> {
+static SRT_FORMAT_TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<(/?)(i|b|u|font)\b([^>]*)>").unwrap());
+pub(crate) static FONT_COLOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)color\s*=\s*["']?([^"'\s>]+)"#).unwrap());
+
+fn cue_text(line: &str, markup: bool) -> String {
+    if markup {
+        line.to_string()
+    } else {
+        HTML_TAG_RE.replace_all(line, "").to_string()
+    }
+}
+
+/// Formatting active over every visible character of a raw SRT cue. Tags covering
+/// only part of the cue are dropped, since the translation cannot be mapped back
+/// onto individual words.
+fn srt_formatting_mask(raw: &str) -> Option<LineFormatting> {
+    // Open depth of <i>, <b> and <u>, plus the color of each open <font>.
+    let mut depths = [0usize; 3];
+    let mut fonts: Vec<Option<String>> = Vec::new();
+    let mut mask: Option<LineFormatting> = None;
+    let mut cursor = 0;
+
+    let tags = SRT_FORMAT_TAG_RE.captures_iter(raw).map(Some).chain([None]);
+    for caps in tags {
+        let segment_end = caps
+            .as_ref()
+            .map_or(raw.len(), |c| c.get(0).unwrap().start());
+        if !HTML_TAG_RE
+            .replace_all(&raw[cursor..segment_end], "")
+            .trim()
+            .is_empty()
+        {
+            let current = LineFormatting {
+                italic: depths[0] > 0,
+                bold: depths[1] > 0,
+                underline: depths[2] > 0,
+                color: fonts.iter().rev().find_map(|color| color.clone()),
+            };
+            mask = Some(match mask {
+                None => current,
+                Some(mask) => LineFormatting {
+                    italic: mask.italic && current.italic,
+                    bold: mask.bold && current.bold,
+                    underline: mask.underline && current.underline,
+                    color: mask
+                        .color
+                        .filter(|color| current.color.as_ref() == Some(color)),
+                },
+            });
+        }
+
+        let Some(caps) = caps else { break };
+        cursor = caps.get(0).unwrap().end();
+        let closing = !caps[1].is_empty();
+        let slot = match caps[2].to_ascii_lowercase().as_str() {
+            "i" => 0,
+            "b" => 1,
+            "u" => 2,
+            _ => {
+                if closing {
+                    fonts.pop();
+                } else {
+                    fonts.push(FONT_COLOR_RE.captures(&caps[3]).map(|c| c[1].to_string()));
+                }
+                continue;
+            }
+        };
+        depths[slot] = if closing {
+            depths[slot].saturating_sub(1)
+        } else {
+            depths[slot] + 1
+        };
+    }
+
+    mask.filter(|mask| !mask.is_plain())
+}
+
+/// `markup` keeps the cue's tags in `text`, for conversions between formats.
+fn parse_srt_file(content: &str, markup: bool) -> Result<SubtitleData, AnimeSubsError> {
     let mut lines: Vec<DialogLine> = Vec::new();
     let mut current_index: Option<usize> = None;
     let mut current_start = String::new();
     let mut current_end = String::new();
     let mut current_text = Vec::new();
+    let mut current_raw = Vec::new();
 
     for line in content.lines() {
         let trimmed = line.trim();
@@ -188,20 +437,19 @@ fn parse_srt_file(content: &str) -> Result<SubtitleData, String> {
         if let Ok(idx) = trimmed.parse::<usize>() {
             if current_index.is_some() && !current_text.is_empty() {
                 let text = current_text.join("\n");
-                if !text.trim().is_empty() && !is_music_or_karaoke_line(&text, &text) {
-                    lines.push(DialogLine {
-                        index: lines.len(),
-                        text: text.clone(),
-                        original_with_formatting: text,
-                        start: current_start.clone(),
-                        end: current_end.clone(),
-                        style: None,
-                        name: None,
-                    });
+                if !text.trim().is_empty() {
+                    lines.push(cue_line(
+                        lines.len(),
+                        text,
+                        current_start.clone(),
+                        current_end.clone(),
+                        srt_formatting_mask(&current_raw.join("\n")),
+                    ));
                 }
             }
             current_index = Some(idx);
             current_text.clear();
+            current_raw.clear();
             continue;
         }
 
@@ -215,36 +463,38 @@ fn parse_srt_file(content: &str) -> Result<SubtitleData, String> {
         }
 
         if current_index.is_some() && !trimmed.is_empty() {
-            let clean = HTML_TAG_RE.replace_all(trimmed, "").to_string();
-            current_text.push(clean);
+            current_text.push(cue_text(trimmed, markup));
+            current_raw.push(trimmed);
         }
     }
 
     if current_index.is_some() && !current_text.is_empty() {
         let text = current_text.join("\n");
-        if !text.trim().is_empty() && !is_music_or_karaoke_line(&text, &text) {
-            lines.push(DialogLine {
-                index: lines.len(),
-                text: text.clone(),
-                original_with_formatting: text,
-                start: current_start,
-                end: current_end,
-                style: None,
-                name: None,
-            });
+        if !text.trim().is_empty() {
+            lines.push(cue_line(
+                lines.len(),
+                text,
+                current_start,
+                current_end,
+                srt_formatting_mask(&current_raw.join("\n")),
+            ));
         }
     }
 
     Ok(SubtitleData {
         format: "srt".to_string(),
         line_count: lines.len(),
+        class_counts: LineClassCounts::from_lines(&lines),
+        deduplicated_lines: 0,
         lines,
         source_path: String::new(),
         ass_header: None,
+        failed_line_ids: Vec::new(),
+        batch_providers: Vec::new(),
     })
 }
 
-fn parse_vtt_file(content: &str) -> Result<SubtitleData, String> {
+fn parse_vtt_file(content: &str, markup: bool) -> Result<SubtitleData, AnimeSubsError> {
     let mut lines: Vec<DialogLine> = Vec::new();
     let mut current_start = String::new();
     let mut current_end = String::new();
@@ -261,16 +511,14 @@ fn parse_vtt_file(content: &str) -> Result<SubtitleData, String> {
         if trimmed.contains("-->") {
             if in_cue && !current_text.is_empty() {
                 let text = current_text.join("\n");
-                if !text.trim().is_empty() && !is_music_or_karaoke_line(&text, &text) {
-                    lines.push(DialogLine {
-                        index: lines.len(),
-                        text: text.clone(),
-                        original_with_formatting: text,
-                        start: current_start.clone(),
-                        end: current_end.clone(),
-                        style: None,
-                        name: None,
-                    });
+                if !text.trim().is_empty() {
+                    lines.push(cue_line(
+                        lines.len(),
+                        text,
+                        current_start.clone(),
+                        current_end.clone(),
+                        None,
+                    ));
                 }
                 current_text.clear();
             }
@@ -287,16 +535,14 @@ fn parse_vtt_file(content: &str) -> Result<SubtitleData, String> {
         if trimmed.is_empty() && in_cue {
             if !current_text.is_empty() {
                 let text = current_text.join("\n");
-                if !text.trim().is_empty() && !is_music_or_karaoke_line(&text, &text) {
-                    lines.push(DialogLine {
-                        index: lines.len(),
-                        text: text.clone(),
-                        original_with_formatting: text,
-                        start: current_start.clone(),
-                        end: current_end.clone(),
-                        style: None,
-                        name: None,
-                    });
+                if !text.trim().is_empty() {
+                    lines.push(cue_line(
+                        lines.len(),
+                        text,
+                        current_start.clone(),
+                        current_end.clone(),
+                        None,
+                    ));
                 }
                 current_text.clear();
             }
@@ -305,37 +551,38 @@ fn parse_vtt_file(content: &str) -> Result<SubtitleData, String> {
         }
 
         if in_cue && !trimmed.is_empty() {
-            let clean = HTML_TAG_RE.replace_all(trimmed, "").to_string();
-            current_text.push(clean);
+            current_text.push(cue_text(trimmed, markup));
         }
     }
 
     if !current_text.is_empty() {
         let text = current_text.join("\n");
-        if !text.trim().is_empty() && !is_music_or_karaoke_line(&text, &text) {
-            lines.push(DialogLine {
-                index: lines.len(),
-                text: text.clone(),
-                original_with_formatting: text,
-                start: current_start,
-                end: current_end,
-                style: None,
-                name: None,
-            });
+        if !text.trim().is_empty() {
+            lines.push(cue_line(
+                lines.len(),
+                text,
+                current_start,
+                current_end,
+                None,
+            ));
         }
     }
 
     Ok(SubtitleData {
         format: "vtt".to_string(),
         line_count: lines.len(),
+        class_counts: LineClassCounts::from_lines(&lines),
+        deduplicated_lines: 0,
         lines,
         source_path: String::new(),
         ass_header: None,
+        failed_line_ids: Vec::new(),
+        batch_providers: Vec::new(),
     })
 }
 
 #[tauri::command]
-pub async fn parse_subtitle_file(file_path: String) -> Result<SubtitleData, String> {
+pub async fn parse_subtitle_file(file_path: String) -> Result<SubtitleData, AnimeSubsError> {
     let content = read_file_as_utf8(&file_path)?;
 
     let ext = Path::new(&file_path)
@@ -343,23 +590,286 @@ pub async fn parse_subtitle_file(file_path: String) -> Result<SubtitleData, Stri
         .map(|e| e.to_string_lossy().to_lowercase())
         .unwrap_or_default();
 
-    let mut data = match ext.as_str() {
-        "ass" | "ssa" => parse_ass_file(&content)?,
-        "srt" => parse_srt_file(&content)?,
-        "vtt" | "webvtt" => parse_vtt_file(&content)?,
-        _ => return Err(format!("Unsupported subtitle format: {}", ext)),
+    let mut data = parse_subtitle_content(&content, &ext, false)?;
+    data.source_path = file_path;
+    Ok(data)
+}
+
+/// Parses subtitle text by format. `markup` keeps SRT/VTT tags in each cue's `text`;
+/// ASS events always keep theirs in `original_with_formatting`.
+pub(crate) fn parse_subtitle_content(
+    content: &str,
+    format: &str,
+    markup: bool,
+) -> Result<SubtitleData, AnimeSubsError> {
+    match format {
+        "ass" | "ssa" => parse_ass_file(content),
+        "srt" => parse_srt_file(content, markup),
+        "vtt" | "webvtt" => parse_vtt_file(content, markup),
+        _ => Err(format!("Unsupported subtitle format: {}", format).into()),
+    }
+}
+
+/// Parses a text subtitle track straight from the video: ffmpeg writes the track to
+/// stdout and the parser reads it from memory, so no temporary file is created.
+#[tauri::command]
+pub async fn parse_embedded_subtitle(
+    video_path: String,
+    track_index: u32,
+    ffmpeg_path: Option<String>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    let ffmpeg = get_ffmpeg_path(ffmpeg_path.clone());
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path).await?;
+    let track = video_info
+        .subtitle_tracks
+        .get(track_index as usize)
+        .ok_or_else(|| AnimeSubsError::TrackNotFound("Subtitle track not found".to_string()))?;
+
+    let fmt = resolve_extraction_format(None, &track.codec);
+    let (codec, muxer) = match fmt.as_str() {
+        "ass" => ("ass", "ass"),
+        "vtt" => ("webvtt", "webvtt"),
+        _ => ("srt", "srt"),
     };
+    let mut command = create_command(&ffmpeg);
+    command
+        .args(["-v", "error", "-i"])
+        .arg(ffmpeg_file_arg(&video_path))
+        .args([
+            "-map",
+            &format!("0:s:{}", track_index),
+            "-c:s",
+            codec,
+            "-f",
+            muxer,
+            "-",
+        ]);
+    let output = tokio::process::Command::from(command)
+        .output()
+        .await
+        .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
 
-    data.source_path = file_path;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to read subtitle track {}: {}",
+            track_index,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    // ffmpeg always writes text subtitles as UTF-8, whatever the source encoding was.
+    let content = String::from_utf8_lossy(&output.stdout);
+    let mut data = parse_subtitle_content(content.trim_start_matches('\u{feff}'), &fmt, false)?;
+    data.source_path = video_path;
     Ok(data)
 }
 
+/// Whether an ASS event belongs with typesetting rather than dialogue: sign, song or
+/// credit styles, karaoke/music lines and drawings.
+fn is_sign_or_song_event(style: &str, text: &str) -> bool {
+    let clean_text = strip_ass_tags(text);
+    is_sign_or_song_style(style)
+        || is_music_or_karaoke_line(text, &clean_text)
+        || text.to_ascii_lowercase().contains("\\p1")
+}
+
+/// Splits ASS `content` into a dialogue-only and a signs/songs-only script. Both keep
+/// the full header and styles; comments stay with the dialogue.
+fn split_signs_and_songs(content: &str) -> (String, String, usize, usize) {
+    let mut dialogue = Vec::new();
+    let mut signs = Vec::new();
+    let (mut dialogue_events, mut sign_events) = (0, 0);
+    let mut in_events = false;
+    let mut format = AssEventFormat::default();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_events = trimmed.eq_ignore_ascii_case("[events]");
+        }
+        if in_events && trimmed.starts_with("Format:") {
+            format = AssEventFormat::parse(&trimmed["Format:".len()..]);
+        }
+
+        if in_events && trimmed.starts_with("Dialogue:") {
+            let is_sign = format
+                .split(&trimmed["Dialogue:".len()..])
+                .is_some_and(|parts| {
+                    let style = format.get(&parts, "style").unwrap_or_default();
+                    let text = format.get(&parts, "text").unwrap_or_default();
+                    is_sign_or_song_event(style.trim(), text)
+                });
+            if is_sign {
+                signs.push(line);
+                sign_events += 1;
+            } else {
+                dialogue.push(line);
+                dialogue_events += 1;
+            }
+            continue;
+        }
+        if in_events && trimmed.starts_with("Comment:") {
+            dialogue.push(line);
+            continue;
+        }
+
+        dialogue.push(line);
+        signs.push(line);
+    }
+
+    (
+        dialogue.join("\n") + "\n",
+        signs.join("\n") + "\n",
+        dialogue_events,
+        sign_events,
+    )
+}
+
+fn split_output_path(subtitle_path: &str, label: &str) -> String {
+    let path = Path::new(subtitle_path);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "subtitle".to_string());
+    path.with_file_name(format!("{}.{}.ass", stem, label))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Writes the dialogue and the signs/songs of an ASS file to two scripts, so dialogue
+/// can be translated while the typeset track is embedded unchanged next to it.
+#[tauri::command]
+pub async fn split_signs_track(
+    subtitle_path: String,
+    dialogue_output: Option<String>,
+    signs_output: Option<String>,
+) -> Result<SignsSplitResult, AnimeSubsError> {
+    let is_ass = Path::new(&subtitle_path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("ass") || e.eq_ignore_ascii_case("ssa"));
+    if !is_ass {
+        return Err("Only ASS/SSA subtitles carry signs and songs to split".into());
+    }
+
+    let content = read_file_as_utf8(&subtitle_path)?;
+    let (dialogue, signs, dialogue_events, sign_events) = split_signs_and_songs(&content);
+    let dialogue_path = dialogue_output
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| split_output_path(&subtitle_path, "dialogue"));
+    let signs_path = signs_output
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| split_output_path(&subtitle_path, "signs"));
+    write_utf8_file(&dialogue_path, &dialogue, true)?;
+    write_utf8_file(&signs_path, &signs, true)?;
+
+    Ok(SignsSplitResult {
+        dialogue_path,
+        signs_path,
+        dialogue_events,
+        sign_events,
+    })
+}
+
+const DEFAULT_PREVIEW_LINES: usize = 50;
+
+/// Extracts a track to a temporary file and returns its first dialog lines, so tracks
+/// sharing a language (full dialog vs. signs & songs) can be told apart.
+#[tauri::command]
+pub async fn preview_subtitle_track(
+    app: AppHandle,
+    video_path: String,
+    track_index: u32,
+    max_lines: Option<usize>,
+    ffmpeg_path: Option<String>,
+) -> Result<SubtitleTrackPreview, AnimeSubsError> {
+    let extract_result = extract_subtitle(
+        app,
+        video_path,
+        track_index,
+        None,
+        None,
+        Some(true),
+        ffmpeg_path,
+        None,
+    )
+    .await?;
+    let extracted = extract_result.output_path.ok_or_else(|| {
+        extract_result
+            .error
+            .unwrap_or_else(|| "Failed to extract subtitle track".to_string())
+    })?;
+
+    let parsed = parse_subtitle_file(extracted.clone()).await;
+    let _ = fs::remove_file(&extracted);
+    let data = parsed?;
+
+    Ok(SubtitleTrackPreview {
+        track_index,
+        format: data.format,
+        total_lines: data.line_count - data.class_counts.skipped,
+        lines: data
+            .lines
+            .into_iter()
+            .filter(|line| !line.skip)
+            .take(max_lines.unwrap_or(DEFAULT_PREVIEW_LINES))
+            .collect(),
+    })
+}
+
+/// Queues `Comment:` lines with visible text, typically TL notes, for translation.
+pub(crate) fn include_comment_lines(subtitle_data: &mut SubtitleData) {
+    for line in &mut subtitle_data.lines {
+        if line.classification == LineClass::Comment && !line.text.trim().is_empty() {
+            line.skip = false;
+        }
+    }
+    subtitle_data.class_counts = LineClassCounts::from_lines(&subtitle_data.lines);
+}
+
+/// Applies user corrections to the skip flags chosen at parse time and refreshes the
+/// per-class counts. Unknown line indexes are rejected.
+#[tauri::command]
+pub async fn override_line_skips(
+    mut subtitle_data: SubtitleData,
+    overrides: Vec<LineSkipOverride>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    for entry in overrides {
+        let line = subtitle_data
+            .lines
+            .iter_mut()
+            .find(|line| line.index == entry.index)
+            .ok_or_else(|| format!("No subtitle line with index {}", entry.index))?;
+        line.skip = entry.skip;
+    }
+    subtitle_data.class_counts = LineClassCounts::from_lines(&subtitle_data.lines);
+    Ok(subtitle_data)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn parse_srt_strips_tags_and_skips_music_lines() {
+    fn filters_tracks_by_language_case_insensitively() {
+        let track = SubtitleTrack {
+            index: 0,
+            stream_index: 2,
+            codec: "ass".to_string(),
+            language: Some("eng".to_string()),
+            title: None,
+            default: false,
+            forced: false,
+            detected_language: None,
+        };
+
+        assert!(matches_language(&track, &[]));
+        assert!(matches_language(&track, &["ENG".to_string()]));
+        assert!(!matches_language(&track, &["jpn".to_string()]));
+    }
+
+    #[test]
+    fn parse_srt_strips_tags_and_marks_music_lines_skipped() {
         let content = r#"1
 00:00:01,000 --> 00:00:02,000
 <i>Hello there</i>
@@ -373,15 +883,49 @@ mod tests {
 General Kenobi
 "#;
 
-        let data = parse_srt_file(content).unwrap();
+        let data = parse_srt_file(content, false).unwrap();
 
         assert_eq!(data.format, "srt");
-        assert_eq!(data.line_count, 2);
+        assert_eq!(data.line_count, 3);
         assert_eq!(data.lines[0].index, 0);
         assert_eq!(data.lines[0].text, "Hello there");
         assert_eq!(data.lines[0].start, "00:00:01,000");
         assert_eq!(data.lines[0].end, "00:00:02,000");
-        assert_eq!(data.lines[1].text, "General Kenobi");
+        assert!(!data.lines[0].skip);
+        assert_eq!(data.lines[1].classification, LineClass::Music);
+        assert!(data.lines[1].skip);
+        assert_eq!(data.lines[2].text, "General Kenobi");
+        assert_eq!(data.class_counts.skipped, 1);
+    }
+
+    #[test]
+    fn parse_srt_records_formatting_that_covers_the_whole_cue() {
+        let content = r##"1
+00:00:01,000 --> 00:00:02,000
+<i>What is this place...</i>
+<i>I have to get out.</i>
+
+2
+00:00:03,000 --> 00:00:04,000
+<font color="#00ffff"><b>Run!</b></font>
+
+3
+00:00:05,000 --> 00:00:06,000
+That was <i>close</i>.
+"##;
+
+        let data = parse_srt_file(content, false).unwrap();
+
+        let first = data.lines[0].formatting.as_ref().unwrap();
+        assert!(first.italic && !first.bold);
+        assert_eq!(
+            data.lines[0].text,
+            "What is this place...\nI have to get out."
+        );
+        let second = data.lines[1].formatting.as_ref().unwrap();
+        assert!(second.bold && !second.italic);
+        assert_eq!(second.color.as_deref(), Some("#00ffff"));
+        assert_eq!(data.lines[2].formatting, None);
     }
 
     #[test]
@@ -395,7 +939,7 @@ General Kenobi
 World
 "#;
 
-        let data = parse_vtt_file(content).unwrap();
+        let data = parse_vtt_file(content, false).unwrap();
 
         assert_eq!(data.format, "vtt");
         assert_eq!(data.line_count, 2);
@@ -405,7 +949,7 @@ World
     }
 
     #[test]
-    fn parse_ass_preserves_dialogue_metadata_and_skips_sign_styles() {
+    fn parse_ass_preserves_dialogue_metadata_and_classifies_skipped_events() {
         let content = r#"[Script Info]
 Title: Example
 
@@ -418,12 +962,28 @@ Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
 Dialogue: 0,0:00:01.00,0:00:02.00,Default,Alice,0,0,0,,{\i1}Hello\Nthere
 Dialogue: 0,0:00:03.00,0:00:04.00,Signs,,0,0,0,,Shop sign
 Dialogue: 0,0:00:05.00,0:00:06.00,Default,,0,0,0,,♪ la la ♪
+Dialogue: 0,0:00:07.00,0:00:08.00,OP,,0,0,0,,{\k20}Ka{\k30}ze
+Dialogue: 0,0:00:09.00,0:00:10.00,Default,,0,0,0,,Oh
 "#;
 
         let data = parse_ass_file(content).unwrap();
 
         assert_eq!(data.format, "ass");
-        assert_eq!(data.line_count, 1);
+        assert_eq!(data.line_count, 5);
+        assert_eq!(
+            data.class_counts,
+            LineClassCounts {
+                dialogue: 1,
+                sign: 1,
+                karaoke: 1,
+                music: 1,
+                short: 1,
+                skipped: 4,
+            }
+        );
+        assert!(!data.lines[0].skip);
+        assert!(data.lines[1..].iter().all(|line| line.skip));
+        assert_eq!(data.lines[4].classification, LineClass::Short);
         assert_eq!(data.lines[0].text, "Hello\nthere");
         assert_eq!(
             data.lines[0].original_with_formatting,
@@ -434,6 +994,50 @@ Dialogue: 0,0:00:05.00,0:00:06.00,Default,,0,0,0,,♪ la la ♪
         assert!(data.ass_header.unwrap().contains("[Events]"));
     }
 
+    #[test]
+    fn parse_ass_follows_the_events_format_line() {
+        let content = r#"[Script Info]
+ScriptType: v4.00
+
+[Events]
+Format: Marked, Style, Start, End, Name, Text
+Dialogue: Marked=0,Default,0:00:01.00,0:00:02.00,Bob,Well, hello there
+"#;
+
+        let data = parse_ass_file(content).unwrap();
+
+        assert_eq!(data.line_count, 1);
+        assert_eq!(data.lines[0].start, "0:00:01.00");
+        assert_eq!(data.lines[0].end, "0:00:02.00");
+        assert_eq!(data.lines[0].style.as_deref(), Some("Default"));
+        assert_eq!(data.lines[0].name.as_deref(), Some("Bob"));
+        assert_eq!(data.lines[0].text, "Well, hello there");
+    }
+
+    #[test]
+    fn parse_ass_keeps_comments_and_other_events_skipped() {
+        let content = r#"[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,TL note: "senpai" means upperclassman
+Sound: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,chime.wav
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Good morning, senpai!
+"#;
+
+        let mut data = parse_ass_file(content).unwrap();
+
+        assert_eq!(data.line_count, 3);
+        assert_eq!(data.lines[0].event, AssEventKind::Comment);
+        assert_eq!(data.lines[1].classification, LineClass::Other);
+        assert_eq!(
+            (data.class_counts.comment, data.class_counts.skipped),
+            (1, 2)
+        );
+
+        include_comment_lines(&mut data);
+        assert!(!data.lines[0].skip && data.lines[1].skip);
+        assert_eq!(data.class_counts.skipped, 1);
+    }
+
     #[test]
     fn parse_ass_reads_dialogue_with_complex_override_tags() {
         let first_dialogue = concat!(
@@ -474,6 +1078,38 @@ Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
         assert_eq!(data.lines[1].text, "\"Moving and Girlfriend\"");
     }
 
+    #[test]
+    fn splits_signs_and_songs_from_dialogue() {
+        let content = r#"[Script Info]
+Title: Example
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, Encoding
+Style: Default,Arial,20,1
+Style: Signs,Arial,20,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:00.00,Default,,0,0,0,,Timing notes
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hello there
+Dialogue: 0,0:00:03.00,0:00:04.00,Signs,,0,0,0,,{\pos(10,10)}Bakery
+Dialogue: 0,0:00:05.00,0:00:06.00,Default,,0,0,0,,{\k20}la {\k20}la
+Dialogue: 0,0:00:07.00,0:00:08.00,Default,,0,0,0,,{\p1}m 0 0 l 10 10{\p0}
+"#;
+
+        let (dialogue, signs, dialogue_events, sign_events) = split_signs_and_songs(content);
+
+        assert_eq!((dialogue_events, sign_events), (1, 3));
+        assert!(dialogue.contains("Hello there") && dialogue.contains("Timing notes"));
+        assert!(!dialogue.contains("Bakery") && !dialogue.contains("\\k20"));
+        assert!(signs.contains("Bakery") && signs.contains("\\k20") && signs.contains("m 0 0"));
+        assert!(!signs.contains("Hello there"));
+        for script in [&dialogue, &signs] {
+            assert!(script.contains("Style: Signs,Arial,20,1"));
+            assert!(script.contains("Format: Layer, Start, End"));
+        }
+    }
+
     #[test]
     fn auto_extraction_format_keeps_ass_tracks_as_ass() {
         assert_eq!(resolve_extraction_format(None, "ass"), "ass");
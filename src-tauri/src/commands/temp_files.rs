@@ -0,0 +1,179 @@
+use super::job_control::JobControl;
+use super::operations::OperationRegistry;
+use super::utils::app_config_path;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::collections::HashSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+const TRACKED_FILES_FILE: &str = "temp_files.json";
+
+/// Paths held by registries of this run. They are in use, so a sweep leaves them;
+/// the lock also serializes updates of the tracked-files list.
+static LIVE_PATHS: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Temporary files of one operation. Whatever still exists when the registry is
+/// dropped is deleted, so early returns and failures no longer leave them behind;
+/// outputs that are renamed into place are simply gone by then.
+pub struct TempFileRegistry {
+    app: AppHandle,
+    paths: Vec<PathBuf>,
+}
+
+impl TempFileRegistry {
+    pub fn new(app: &AppHandle) -> Self {
+        Self {
+            app: app.clone(),
+            paths: Vec::new(),
+        }
+    }
+
+    /// Tracks `path` and records it on disk so a crash leftover is found on next start.
+    pub fn track(&mut self, path: PathBuf) -> PathBuf {
+        let mut live = LIVE_PATHS.lock().unwrap_or_else(|e| e.into_inner());
+        live.insert(path.clone());
+        let recorded = update_tracked_files(&self.app, |files| {
+            if !files.contains(&path) {
+                files.push(path.clone());
+            }
+        });
+        if let Err(e) = recorded {
+            warn!("{}", e);
+        }
+        self.paths.push(path.clone());
+        path
+    }
+
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.paths.clone()
+    }
+
+    /// Stops tracking `path`, e.g. once it became the final output.
+    pub fn keep(&mut self, path: &Path) {
+        self.paths.retain(|tracked| tracked != path);
+        forget(&self.app, &[path.to_path_buf()]);
+    }
+}
+
+impl Drop for TempFileRegistry {
+    fn drop(&mut self) {
+        for path in &self.paths {
+            if path.exists() {
+                let _ = fs::remove_file(path);
+            }
+        }
+        // Files that could not be deleted stay recorded for the next sweep.
+        let gone: Vec<PathBuf> = self.paths.iter().filter(|p| !p.exists()).cloned().collect();
+        forget(&self.app, &gone);
+    }
+}
+
+fn read_tracked_files(app: &AppHandle) -> Result<Vec<PathBuf>, AnimeSubsError> {
+    let path = app_config_path(app, TRACKED_FILES_FILE)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read tracked temp files: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse tracked temp files: {}", e)))
+}
+
+/// Applies `change` to the recorded temp files. Callers hold `LIVE_PATHS`.
+fn update_tracked_files(
+    app: &AppHandle,
+    change: impl FnOnce(&mut Vec<PathBuf>),
+) -> Result<(), AnimeSubsError> {
+    let mut files = read_tracked_files(app)?;
+    change(&mut files);
+    let data = serde_json::to_string_pretty(&files)
+        .map_err(|e| format!("Failed to serialize tracked temp files: {}", e))?;
+    write_file_atomic(&app_config_path(app, TRACKED_FILES_FILE)?, data.as_bytes())
+}
+
+/// Drops `paths` from the live set and from the record on disk.
+fn forget(app: &AppHandle, paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+    let mut live = LIVE_PATHS.lock().unwrap_or_else(|e| e.into_inner());
+    for path in paths {
+        live.remove(path);
+    }
+    if let Err(e) = update_tracked_files(app, |files| files.retain(|f| !paths.contains(f))) {
+        warn!("{}", e);
+    }
+}
+
+/// Recorded files no registry of this run holds: leftovers of a crash or kill.
+fn find_orphans(recorded: &[PathBuf], live: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    recorded
+        .iter()
+        .filter(|path| !live.contains(*path))
+        .cloned()
+        .collect()
+}
+
+/// Deletes the temp files earlier runs recorded but never removed. Files are
+/// only ever deleted by their recorded path, never by name pattern.
+pub(crate) fn cleanup_orphans(app: &AppHandle) -> Result<OrphanCleanupResult, AnimeSubsError> {
+    let mut result = OrphanCleanupResult::default();
+    let live = LIVE_PATHS.lock().unwrap_or_else(|e| e.into_inner());
+    let orphans = find_orphans(&read_tracked_files(app)?, &live);
+    for path in &orphans {
+        let display = path.to_string_lossy().to_string();
+        match fs::remove_file(path) {
+            Ok(()) => result.removed.push(display),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(_) => result.failed.push(display),
+        }
+    }
+    update_tracked_files(app, |files| {
+        files.retain(|path| !orphans.contains(path) || path.exists())
+    })?;
+    Ok(result)
+}
+
+/// Sweeps leftover temp files; refused while a translation job or any ffmpeg or
+/// mkvmerge operation is running.
+#[tauri::command]
+pub async fn cleanup_orphaned_temp_files(
+    app: AppHandle,
+) -> Result<OrphanCleanupResult, AnimeSubsError> {
+    if !app.state::<OperationRegistry>().is_idle() || app.state::<JobControl>().is_running() {
+        return Err("Cannot clean up temp files while an operation is running".into());
+    }
+    cleanup_orphans(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_recorded_files_outside_live_registries_are_orphans() {
+        let recorded: Vec<PathBuf> = [
+            "/anime/Show - 01_with_subs.mkv",
+            "/tmp/animesubs/preview_track2.ass",
+            "/anime/Show - 02_modified.mkv",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        let live = HashSet::from([PathBuf::from("/tmp/animesubs/preview_track2.ass")]);
+
+        assert_eq!(
+            find_orphans(&recorded, &live),
+            vec![
+                PathBuf::from("/anime/Show - 01_with_subs.mkv"),
+                PathBuf::from("/anime/Show - 02_modified.mkv"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::path::Path;
+
+fn map_seconds(adjustment: &TimingAdjustment, seconds: f64) -> Result<f64, AnimeSubsError> {
+    let mapped = match adjustment {
+        TimingAdjustment::Shift { offset_ms } => seconds + *offset_ms as f64 / 1000.0,
+        TimingAdjustment::Scale {
+            source_a_ms,
+            target_a_ms,
+            source_b_ms,
+            target_b_ms,
+        } => {
+            if source_a_ms == source_b_ms {
+                return Err("Scale anchors must use two different source times".into());
+            }
+            let ratio = (target_b_ms - target_a_ms) as f64 / (source_b_ms - source_a_ms) as f64;
+            *target_a_ms as f64 / 1000.0 + (seconds - *source_a_ms as f64 / 1000.0) * ratio
+        }
+        TimingAdjustment::FrameRate {
+            source_fps,
+            target_fps,
+        } => {
+            if *source_fps <= 0.0 || *target_fps <= 0.0 {
+                return Err("Frame rates must be positive".into());
+            }
+            seconds * source_fps / target_fps
+        }
+    };
+    Ok(mapped.max(0.0))
+}
+
+fn adjust_timestamp(value: &str, adjustment: &TimingAdjustment) -> Result<String, AnimeSubsError> {
+    let seconds = parse_subtitle_timestamp(value)
+        .ok_or_else(|| format!("Invalid subtitle timestamp: {}", value.trim()))?;
+    Ok(format_subtitle_timestamp_like(
+        value,
+        map_seconds(adjustment, seconds)?,
+    ))
+}
+
+/// Rewrites only the timestamps of `content`, so styles, comments, cue settings and
+/// lines the parser would skip come through untouched.
+fn adjust_content_timing(
+    content: &str,
+    format: &str,
+    adjustment: &TimingAdjustment,
+) -> Result<String, AnimeSubsError> {
+    let is_ass = matches!(format, "ass" | "ssa");
+    let mut result = Vec::new();
+    let mut in_events = false;
+    let mut event_format = AssEventFormat::default();
+
+    for raw_line in content.split('\n') {
+        let (line, line_ending) = match raw_line.strip_suffix('\r') {
+            Some(line) => (line, "\r"),
+            None => (raw_line, ""),
+        };
+
+        if is_ass && line.trim_start().starts_with('[') {
+            in_events = line.trim().eq_ignore_ascii_case("[events]");
+        }
+        if let Some(format) = line.trim().strip_prefix("Format:").filter(|_| in_events) {
+            event_format = AssEventFormat::parse(format);
+        }
+
+        let adjusted = if is_ass {
+            match line.split_once(':') {
+                Some((kind, rest)) if kind == "Dialogue" || kind == "Comment" => {
+                    let timed = event_format.split(rest).zip(
+                        event_format
+                            .position("start")
+                            .zip(event_format.position("end")),
+                    );
+                    if let Some((mut parts, (start, end))) = timed {
+                        let new_start = adjust_timestamp(parts[start], adjustment)?;
+                        let new_end = adjust_timestamp(parts[end], adjustment)?;
+                        parts[start] = &new_start;
+                        parts[end] = &new_end;
+                        format!("{}:{}", kind, parts.join(","))
+                    } else {
+                        line.to_string()
+                    }
+                }
+                _ => line.to_string(),
+            }
+        } else if let Some((start, rest)) = line.split_once("-->") {
+            let rest = rest.trim_start();
+            let (end, settings) =
+                rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()));
+            format!(
+                "{} --> {}{}",
+                adjust_timestamp(start, adjustment)?,
+                adjust_timestamp(end, adjustment)?,
+                settings
+            )
+        } else {
+            line.to_string()
+        };
+
+        result.push(format!("{}{}", adjusted, line_ending));
+    }
+
+    Ok(result.join("\n"))
+}
+
+/// Parses `"25"`, `"23.976"` or `"24000/1001"`; the usual rounded NTSC rates are
+/// mapped to their exact 1001-based ratios so long files don't drift.
+fn parse_frame_rate(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let fps = match value {
+        "23.976" | "23.98" => 24000.0 / 1001.0,
+        "29.97" => 30000.0 / 1001.0,
+        "47.952" => 48000.0 / 1001.0,
+        "59.94" => 60000.0 / 1001.0,
+        _ => match value.split_once('/') {
+            Some((num, den)) => num.trim().parse::<f64>().ok()? / den.trim().parse::<f64>().ok()?,
+            None => value.parse().ok()?,
+        },
+    };
+    (fps.is_finite() && fps > 0.0).then_some(fps)
+}
+
+fn retimed_output_path(subtitle_path: &str) -> String {
+    let path = Path::new(subtitle_path);
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "subtitle".to_string());
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "srt".to_string());
+    parent
+        .join(format!("{}_retimed.{}", stem, ext))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Shifts or stretches every cue of an ASS/SRT/VTT file and writes the result to
+/// `output_path` (default `<stem>_retimed.<ext>` next to the source).
+#[tauri::command]
+pub async fn shift_subtitle_timing(
+    subtitle_path: String,
+    adjustment: TimingAdjustment,
+    output_path: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let content = read_file_as_utf8(&subtitle_path)?;
+    let format = Path::new(&subtitle_path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if !matches!(format.as_str(), "ass" | "ssa" | "srt" | "vtt" | "webvtt") {
+        return Err(format!("Unsupported subtitle format: {}", format).into());
+    }
+
+    let adjusted = adjust_content_timing(&content, &format, &adjustment)?;
+    let output = output_path
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| retimed_output_path(&subtitle_path));
+    write_utf8_file(&output, &adjusted, false)?;
+
+    Ok(OperationResult {
+        success: true,
+        message: "Subtitle timing adjusted".to_string(),
+        data: Some(output),
+        verification: None,
+    })
+}
+
+/// Converts a subtitle between frame rates (`"23.976"`, `"25"`, `"29.97"` or a
+/// custom `"num/den"` ratio) by rescaling every timestamp.
+#[tauri::command]
+pub async fn convert_subtitle_framerate(
+    subtitle_path: String,
+    source_fps: String,
+    target_fps: String,
+    output_path: Option<String>,
+) -> Result<OperationResult, AnimeSubsError> {
+    let source_fps = parse_frame_rate(&source_fps)
+        .ok_or_else(|| format!("Invalid source frame rate: {}", source_fps))?;
+    let target_fps = parse_frame_rate(&target_fps)
+        .ok_or_else(|| format!("Invalid target frame rate: {}", target_fps))?;
+
+    shift_subtitle_timing(
+        subtitle_path,
+        TimingAdjustment::FrameRate {
+            source_fps,
+            target_fps,
+        },
+        output_path,
+    )
+    .await
+}
+
+/// Same adjustment applied to already parsed lines, e.g. before saving a translation.
+#[tauri::command]
+pub async fn shift_subtitle_data_timing(
+    mut subtitle_data: SubtitleData,
+    adjustment: TimingAdjustment,
+) -> Result<SubtitleData, AnimeSubsError> {
+    for line in &mut subtitle_data.lines {
+        line.start = adjust_timestamp(&line.start, &adjustment)?;
+        line.end = adjust_timestamp(&line.end, &adjustment)?;
+    }
+    Ok(subtitle_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_ass_events_and_keeps_other_lines() {
+        let content = "[Events]\r\nFormat: Layer, Start, End, Style, Text\r\n\
+                       Dialogue: 0,0:00:01.00,0:00:02.50,Default,,0,0,0,,Hi, there\r\n\
+                       Comment: 0,0:00:00.50,0:00:01.00,Default,,0,0,0,,note\r\n";
+        let adjusted =
+            adjust_content_timing(content, "ass", &TimingAdjustment::Shift { offset_ms: -750 })
+                .unwrap();
+
+        assert_eq!(
+            adjusted,
+            "[Events]\r\nFormat: Layer, Start, End, Style, Text\r\n\
+             Dialogue: 0,0:00:00.25,0:00:01.75,Default,,0,0,0,,Hi, there\r\n\
+             Comment: 0,0:00:00.00,0:00:00.25,Default,,0,0,0,,note\r\n"
+        );
+    }
+
+    #[test]
+    fn scales_srt_and_vtt_cues_between_anchors() {
+        let scale = TimingAdjustment::Scale {
+            source_a_ms: 0,
+            target_a_ms: 0,
+            source_b_ms: 24_000,
+            target_b_ms: 25_000,
+        };
+
+        assert_eq!(
+            adjust_content_timing("1\n00:00:24,000 --> 00:00:48,000\nHi\n", "srt", &scale).unwrap(),
+            "1\n00:00:25,000 --> 00:00:50,000\nHi\n"
+        );
+        assert_eq!(
+            adjust_content_timing("00:12.000 --> 00:24.000 align:start\n", "vtt", &scale).unwrap(),
+            "00:12.500 --> 00:25.000 align:start\n"
+        );
+    }
+
+    #[test]
+    fn converts_pal_timing_to_film_rate_with_format_precision() {
+        let pal_to_film = TimingAdjustment::FrameRate {
+            source_fps: parse_frame_rate("25").unwrap(),
+            target_fps: parse_frame_rate("23.976").unwrap(),
+        };
+
+        assert_eq!(parse_frame_rate("30000/1001"), parse_frame_rate("29.97"));
+        assert_eq!(parse_frame_rate("0"), None);
+        assert_eq!(
+            adjust_timestamp("0:20:00.00", &pal_to_film).unwrap(),
+            "0:20:51.25"
+        );
+        assert_eq!(
+            adjust_timestamp("00:20:00,000", &pal_to_film).unwrap(),
+            "00:20:51,250"
+        );
+    }
+}
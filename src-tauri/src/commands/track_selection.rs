@@ -0,0 +1,192 @@
+use super::translation::to_ffmpeg_lang_code;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+const FULL_KEYWORDS: [&str; 4] = ["full", "dialog", "dialogue", "main"];
+const PARTIAL_KEYWORDS: [&str; 5] = ["sign", "signs", "song", "songs", "forced"];
+pub(crate) const IMAGE_CODECS: [&str; 4] =
+    ["hdmv_pgs_subtitle", "dvd_subtitle", "dvb_subtitle", "xsub"];
+
+fn title_words(title: &str) -> Vec<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn score_track(
+    track: &SubtitleTrack,
+    preferred_languages: &[String],
+    dialog_lines: Option<usize>,
+    max_dialog_lines: usize,
+) -> TrackScore {
+    let mut score = 0;
+    let mut reasons = Vec::new();
+
+    if IMAGE_CODECS.contains(&track.codec.as_str()) {
+        score -= 200;
+        reasons.push(format!("image-based codec {}", track.codec));
+    }
+
+    let language = to_ffmpeg_lang_code(track.language.as_deref());
+    match preferred_languages
+        .iter()
+        .position(|pref| to_ffmpeg_lang_code(Some(pref)) == language)
+    {
+        Some(position) => {
+            score += 100 - 10 * position.min(9) as i32;
+            reasons.push(format!("preferred language {}", language));
+        }
+        None if language == "und" || preferred_languages.is_empty() => {}
+        None => {
+            score -= 50;
+            reasons.push(format!("other language {}", language));
+        }
+    }
+
+    let words = track.title.as_deref().map(title_words).unwrap_or_default();
+    if words.iter().any(|w| FULL_KEYWORDS.contains(&w.as_str())) {
+        score += 30;
+        reasons.push("title suggests full dialog".to_string());
+    }
+    if words.iter().any(|w| PARTIAL_KEYWORDS.contains(&w.as_str())) {
+        score -= 60;
+        reasons.push("title suggests signs & songs".to_string());
+    }
+    if words.iter().any(|w| w == "commentary") {
+        score -= 40;
+        reasons.push("commentary track".to_string());
+    }
+
+    if track.forced {
+        score -= 40;
+        reasons.push("forced flag".to_string());
+    }
+    if track.default {
+        score += 10;
+        reasons.push("default flag".to_string());
+    }
+
+    if let Some(lines) = dialog_lines.filter(|_| max_dialog_lines > 0) {
+        score += (40 * lines / max_dialog_lines) as i32;
+        if lines * 5 < max_dialog_lines {
+            score -= 40;
+            reasons.push(format!("only {} dialog lines", lines));
+        }
+    }
+
+    TrackScore {
+        track_index: track.index,
+        score,
+        dialog_lines,
+        reasons,
+    }
+}
+
+/// Ranks subtitle tracks best-first by language preference, title keywords,
+/// default/forced flags and, when known, how many dialog lines each track has.
+pub(crate) fn rank_subtitle_tracks(
+    tracks: &[SubtitleTrack],
+    preferred_languages: &[String],
+    dialog_lines: &HashMap<u32, usize>,
+) -> Vec<TrackScore> {
+    let max_dialog_lines = dialog_lines.values().copied().max().unwrap_or(0);
+    let mut scores: Vec<TrackScore> = tracks
+        .iter()
+        .map(|track| {
+            score_track(
+                track,
+                preferred_languages,
+                dialog_lines.get(&track.index).copied(),
+                max_dialog_lines,
+            )
+        })
+        .collect();
+    scores.sort_by(|a, b| b.score.cmp(&a.score));
+    scores
+}
+
+/// Returns the video's subtitle tracks ranked best-first. With `count_lines` every
+/// text track is extracted once to count dialog lines, which is slower but tells
+/// full subtitles apart from signs & songs tracks that share a title.
+#[tauri::command]
+pub async fn choose_best_subtitle_track(
+    app: AppHandle,
+    video_path: String,
+    preferred_languages: Vec<String>,
+    count_lines: Option<bool>,
+    ffmpeg_path: Option<String>,
+) -> Result<Vec<TrackScore>, AnimeSubsError> {
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
+
+    let mut dialog_lines = HashMap::new();
+    if count_lines.unwrap_or(false) {
+        for track in &video_info.subtitle_tracks {
+            if IMAGE_CODECS.contains(&track.codec.as_str()) {
+                continue;
+            }
+            if let Ok(preview) = super::subtitle::preview_subtitle_track(
+                app.clone(),
+                video_path.clone(),
+                track.index,
+                Some(0),
+                ffmpeg_path.clone(),
+            )
+            .await
+            {
+                dialog_lines.insert(track.index, preview.total_lines);
+            }
+        }
+    }
+
+    Ok(rank_subtitle_tracks(
+        &video_info.subtitle_tracks,
+        &preferred_languages,
+        &dialog_lines,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(index: u32, language: &str, title: Option<&str>, default: bool) -> SubtitleTrack {
+        SubtitleTrack {
+            index,
+            stream_index: index + 2,
+            codec: "ass".to_string(),
+            language: Some(language.to_string()),
+            title: title.map(String::from),
+            default,
+            forced: false,
+            detected_language: None,
+        }
+    }
+
+    #[test]
+    fn prefers_full_dialog_in_preferred_language() {
+        let tracks = vec![
+            track(0, "eng", Some("Signs & Songs"), true),
+            track(1, "eng", Some("Full Subtitles"), false),
+            track(2, "spa", None, false),
+        ];
+        let ranked = rank_subtitle_tracks(&tracks, &["en".to_string()], &HashMap::new());
+        assert_eq!(
+            ranked.iter().map(|s| s.track_index).collect::<Vec<_>>(),
+            vec![1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn line_counts_break_ties_between_untitled_tracks() {
+        let tracks = vec![track(0, "eng", None, false), track(1, "eng", None, false)];
+        let counts = HashMap::from([(0, 40), (1, 350)]);
+        let ranked = rank_subtitle_tracks(&tracks, &["eng".to_string()], &counts);
+        assert_eq!(ranked[0].track_index, 1);
+        assert!(ranked[1].score < ranked[0].score - 40);
+    }
+}
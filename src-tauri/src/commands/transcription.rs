@@ -0,0 +1,379 @@
+use super::operations::run_ffmpeg_with_progress;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::providers::http::{self, http_client};
+use crate::utils::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const DEFAULT_LANGUAGE: &str = "ja";
+const OPENAI_ENDPOINT: &str = "https://api.openai.com/v1";
+const DEEPGRAM_ENDPOINT: &str = "https://api.deepgram.com/v1";
+const WHISPER_BINARIES: [&str; 3] = ["whisper-cli", "whisper-cpp", "whisper"];
+const SRT_TEMPLATE: &str = "00:00:00,000";
+
+fn is_language(track_language: Option<&str>, language: &str) -> bool {
+    let Some(track_language) = track_language.map(str::to_ascii_lowercase) else {
+        return false;
+    };
+    match language {
+        "ja" | "jpn" | "jp" => matches!(track_language.as_str(), "ja" | "jpn" | "jp"),
+        _ => track_language.starts_with(language),
+    }
+}
+
+fn pick_audio_track(tracks: &[AudioTrack], language: &str) -> Option<u32> {
+    tracks
+        .iter()
+        .find(|track| is_language(track.language.as_deref(), language))
+        .or_else(|| tracks.first())
+        .map(|track| track.index)
+}
+
+/// ffmpeg arguments for a mono 16 kHz stream: WAV for whisper.cpp, small Opus for uploads.
+fn build_audio_args(
+    video_path: &str,
+    audio_index: u32,
+    engine: TranscriptionEngine,
+    output: &str,
+) -> Vec<String> {
+    let mut args = vec![
+        "-i".to_string(),
+        ffmpeg_file(video_path),
+        "-map".to_string(),
+        format!("0:a:{}", audio_index),
+        "-vn".to_string(),
+        "-ac".to_string(),
+        "1".to_string(),
+        "-ar".to_string(),
+        "16000".to_string(),
+    ];
+    match engine {
+        TranscriptionEngine::WhisperCpp => {
+            args.extend(["-c:a".to_string(), "pcm_s16le".to_string()]);
+        }
+        TranscriptionEngine::OpenAi | TranscriptionEngine::Deepgram => {
+            args.extend([
+                "-c:a".to_string(),
+                "libopus".to_string(),
+                "-b:a".to_string(),
+                "32k".to_string(),
+            ]);
+        }
+    }
+    args.extend(["-y".to_string(), ffmpeg_file(output)]);
+    args
+}
+
+/// Turns (start, end, text) segments into SRT content.
+fn segments_to_srt(segments: &[(f64, f64, String)]) -> String {
+    segments
+        .iter()
+        .filter(|(_, _, text)| !text.trim().is_empty())
+        .enumerate()
+        .map(|(i, (start, end, text))| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                format_subtitle_timestamp_like(SRT_TEMPLATE, *start),
+                format_subtitle_timestamp_like(SRT_TEMPLATE, *end),
+                text.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn deepgram_segments(
+    response: &serde_json::Value,
+) -> Result<Vec<(f64, f64, String)>, AnimeSubsError> {
+    let utterances = response["results"]["utterances"]
+        .as_array()
+        .ok_or_else(|| "Missing utterances in Deepgram response".to_string())?;
+    Ok(utterances
+        .iter()
+        .filter_map(|u| {
+            Some((
+                u["start"].as_f64()?,
+                u["end"].as_f64()?,
+                u["transcript"].as_str()?.to_string(),
+            ))
+        })
+        .collect())
+}
+
+fn resolve_api_key(
+    app: &AppHandle,
+    options: &TranscriptionOptions,
+    provider: &str,
+) -> Result<String, AnimeSubsError> {
+    let api_key = match options.api_key.as_deref().filter(|key| !key.is_empty()) {
+        Some(key) => key.to_string(),
+        None => super::secrets::read_api_key(app, provider)?,
+    };
+    if api_key.is_empty() {
+        return Err(AnimeSubsError::ProviderAuth(format!(
+            "An API key is required for {} transcription",
+            provider
+        )));
+    }
+    Ok(api_key)
+}
+
+fn run_whisper_cpp(
+    options: &TranscriptionOptions,
+    audio: &Path,
+    language: &str,
+) -> Result<String, AnimeSubsError> {
+    let binary = match options.whisper_path.as_deref().filter(|p| !p.is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => find_executable_in_path(&WHISPER_BINARIES)
+            .ok_or_else(|| "whisper.cpp not found. Install it or set its path.".to_string())?,
+    };
+    let model = options
+        .whisper_model
+        .as_deref()
+        .filter(|m| !m.is_empty())
+        .ok_or_else(|| "A whisper.cpp model file is required".to_string())?;
+
+    let output_base = audio.with_extension("");
+    let output = create_command(&binary)
+        .arg("-m")
+        .arg(model)
+        .arg("-f")
+        .arg(audio)
+        .args(["-l", language, "-osrt", "-of"])
+        .arg(&output_base)
+        .output()
+        .map_err(|e| format!("Failed to run whisper.cpp: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "whisper.cpp failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+
+    let srt_path = output_base.with_extension("srt");
+    let content = read_file_as_utf8(&srt_path.to_string_lossy());
+    let _ = fs::remove_file(&srt_path);
+    content
+}
+
+async fn run_openai(
+    app: &AppHandle,
+    options: &TranscriptionOptions,
+    audio: &Path,
+    language: &str,
+) -> Result<String, AnimeSubsError> {
+    let api_key = resolve_api_key(app, options, "openai")?;
+    let endpoint = options
+        .endpoint
+        .as_deref()
+        .unwrap_or(OPENAI_ENDPOINT)
+        .trim_end_matches('/');
+    let bytes = fs::read(audio)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read extracted audio: {}", e)))?;
+    let file = reqwest::multipart::Part::bytes(bytes)
+        .file_name("audio.ogg")
+        .mime_str("audio/ogg")
+        .map_err(|e| format!("Failed to build transcription upload: {}", e))?;
+    let form = reqwest::multipart::Form::new()
+        .part("file", file)
+        .text(
+            "model",
+            options
+                .model
+                .clone()
+                .unwrap_or_else(|| "whisper-1".to_string()),
+        )
+        .text("response_format", "srt")
+        .text("language", language.to_string());
+
+    let response = http_client()?
+        .post(format!("{}/audio/transcriptions", endpoint))
+        .bearer_auth(api_key)
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|e| {
+            AnimeSubsError::Provider(format!("Failed to call transcription API: {}", e))
+        })?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(http::status_error(
+            status,
+            None,
+            format!("Transcription API error ({}): {}", status, error_text),
+        ));
+    }
+    response.text().await.map_err(|e| {
+        AnimeSubsError::Provider(format!("Failed to read transcription response: {}", e))
+    })
+}
+
+async fn run_deepgram(
+    app: &AppHandle,
+    options: &TranscriptionOptions,
+    audio: &Path,
+    language: &str,
+) -> Result<String, AnimeSubsError> {
+    let api_key = resolve_api_key(app, options, "deepgram")?;
+    let endpoint = options
+        .endpoint
+        .as_deref()
+        .unwrap_or(DEEPGRAM_ENDPOINT)
+        .trim_end_matches('/');
+    let bytes = fs::read(audio)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read extracted audio: {}", e)))?;
+
+    let response = http_client()?
+        .post(format!("{}/listen", endpoint))
+        .query(&[
+            ("model", options.model.as_deref().unwrap_or("nova-2")),
+            ("language", language),
+            ("utterances", "true"),
+            ("smart_format", "true"),
+        ])
+        .header("Authorization", format!("Token {}", api_key))
+        .header("Content-Type", "audio/ogg")
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| {
+            AnimeSubsError::Provider(format!("Failed to call transcription API: {}", e))
+        })?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(http::status_error(
+            status,
+            None,
+            format!("Transcription API error ({}): {}", status, error_text),
+        ));
+    }
+
+    let json: serde_json::Value = response.json().await.map_err(|e| {
+        AnimeSubsError::parse(format!("Failed to parse transcription response: {}", e))
+    })?;
+    Ok(segments_to_srt(&deepgram_segments(&json)?))
+}
+
+/// Transcribes a video's audio (for raw releases without subtitles) into SubtitleData
+/// that can go straight to `translate_subtitles`.
+#[tauri::command]
+pub async fn transcribe_audio(
+    app: AppHandle,
+    video_path: String,
+    options: Option<TranscriptionOptions>,
+    ffmpeg_path: Option<String>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    let options = options.unwrap_or_default();
+    let language = options
+        .language
+        .clone()
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+    let video_info = super::video::get_video_info(video_path.clone(), ffmpeg_path.clone()).await?;
+    let audio_index = match options.audio_track {
+        Some(index) => index,
+        None => pick_audio_track(&video_info.audio_tracks, &language)
+            .ok_or_else(|| "No audio tracks found".to_string())?,
+    };
+
+    let extension = match options.engine {
+        TranscriptionEngine::WhisperCpp => "wav",
+        TranscriptionEngine::OpenAi | TranscriptionEngine::Deepgram => "ogg",
+    };
+    let audio_path = build_temp_subtitle_path(&video_path, "transcribe", extension)?;
+    let args = build_audio_args(
+        &video_path,
+        audio_index,
+        options.engine,
+        &audio_path.to_string_lossy(),
+    );
+    let result = run_ffmpeg_with_progress(
+        &app,
+        &get_ffmpeg_path(ffmpeg_path),
+        &args,
+        "transcribe",
+        &video_path,
+        video_info.duration,
+        vec![audio_path.clone()],
+    )
+    .map_err(|e| AnimeSubsError::from_tool(&e, format!("Failed to run ffmpeg: {}", e)))?;
+    if !result.status.success() {
+        let _ = fs::remove_file(&audio_path);
+        return Err(format!(
+            "Failed to extract audio: {}",
+            String::from_utf8_lossy(&result.stderr)
+        )
+        .into());
+    }
+
+    let transcript = match options.engine {
+        TranscriptionEngine::WhisperCpp => run_whisper_cpp(&options, &audio_path, &language),
+        TranscriptionEngine::OpenAi => run_openai(&app, &options, &audio_path, &language).await,
+        TranscriptionEngine::Deepgram => run_deepgram(&app, &options, &audio_path, &language).await,
+    };
+    let _ = fs::remove_file(&audio_path);
+
+    let srt_path = build_temp_subtitle_path(&video_path, "transcript", "srt")?;
+    write_utf8_file(&srt_path.to_string_lossy(), &transcript?, false)?;
+    let data = super::subtitle::parse_subtitle_file(srt_path.to_string_lossy().to_string()).await?;
+    if data.lines.is_empty() {
+        return Err("Transcription produced no dialog lines".into());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio_track(index: u32, language: Option<&str>) -> AudioTrack {
+        AudioTrack {
+            index,
+            stream_index: index + 1,
+            codec: "aac".to_string(),
+            language: language.map(String::from),
+            title: None,
+            channels: Some(2),
+            default: index == 0,
+        }
+    }
+
+    #[test]
+    fn picks_japanese_audio_and_builds_srt() {
+        let tracks = vec![audio_track(0, Some("eng")), audio_track(1, Some("jpn"))];
+        assert_eq!(pick_audio_track(&tracks, "ja"), Some(1));
+        assert_eq!(pick_audio_track(&tracks[..1], "ja"), Some(0));
+
+        let response = serde_json::json!({ "results": { "utterances": [
+            { "start": 1.5, "end": 3.25, "transcript": "おはよう" },
+            { "start": 62.0, "end": 63.0, "transcript": " " }
+        ]}});
+        assert_eq!(
+            segments_to_srt(&deepgram_segments(&response).unwrap()),
+            "1\n00:00:01,500 --> 00:00:03,250\nおはよう\n"
+        );
+    }
+
+    #[test]
+    fn builds_audio_args_per_engine() {
+        let wav = build_audio_args(
+            "/v/ep.mkv",
+            1,
+            TranscriptionEngine::WhisperCpp,
+            "/tmp/a.wav",
+        );
+        assert!(wav.windows(2).any(|w| w == ["-map", "0:a:1"]));
+        assert!(wav.windows(2).any(|w| w == ["-c:a", "pcm_s16le"]));
+
+        let ogg = build_audio_args("/v/ep.mkv", 0, TranscriptionEngine::Deepgram, "/tmp/a.ogg");
+        assert!(ogg.windows(2).any(|w| w == ["-c:a", "libopus"]));
+    }
+}
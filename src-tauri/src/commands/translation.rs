@@ -1,17 +1,29 @@
+use super::job_control::JobControl;
+use super::naming::{render_output_name, NamingVars};
+use super::sidecar::SIDECAR_EXTENSIONS;
+use super::styles::apply_style_preset;
+use crate::error::AnimeSubsError;
 use crate::models::*;
-use crate::providers::{call_llm_api_with_context, generate_compaction_summary};
+use crate::providers::http::is_timeout_error;
+use crate::providers::rate_limit::RateLimiter;
+use crate::providers::{
+    build_system_prompt, call_llm_api_with_context, generate_compaction_summary,
+};
 use crate::utils::*;
+use futures::StreamExt;
 use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Emitter};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use std::sync::{Arc, LazyLock};
+use std::time::Instant;
+use tokio::sync::{Mutex, Semaphore};
+use tracing::{error, info, warn};
 
 /// Estimates the number of tokens for a given text.
 /// Heuristic: CJK chars ~1.5 tokens each, non-CJK ~0.25 tokens each.
-fn estimate_tokens(text: &str) -> usize {
+pub(crate) fn estimate_tokens(text: &str) -> usize {
     let cjk_count = text.chars().filter(|c| is_cjk(*c)).count();
     let total_chars = text.chars().count();
     let non_cjk = total_chars.saturating_sub(cjk_count);
@@ -32,24 +44,99 @@ fn is_cjk(ch: char) -> bool {
 
 /// Default context window in tokens for modern LLMs.
 const DEFAULT_CONTEXT_WINDOW: usize = 128_000;
-/// Fraction of context window usable for input (leaves room for prompt + response).
-const INPUT_CONTEXT_RATIO: f64 = 0.65;
+/// Expected translation length relative to the source text; CJK sources expand most.
+const OUTPUT_EXPANSION: f64 = 1.5;
+/// JSON around each line in a request or a response (`{"id": 12, "text": ""}`).
+const LINE_JSON_TOKENS: usize = 8;
 /// Maximum tokens for a compaction summary.
 const MAX_COMPACTION_TOKENS: usize = 2_000;
+/// Context assumed for local servers (Ollama, LM Studio, llama.cpp, vLLM) whose model is unknown.
+const LOCAL_CONTEXT_WINDOW: usize = 8_192;
+
+/// Approximate context length of `model`, used when the config does not set one.
+fn model_context_window(provider: &str, model: &str) -> usize {
+    let model = model.to_ascii_lowercase();
+    let known = [
+        ("gemini", 1_000_000),
+        ("claude", 200_000),
+        ("gpt-4.1", 1_000_000),
+        ("gpt-4o", 128_000),
+        ("gpt-4-turbo", 128_000),
+        ("gpt-3.5", 16_385),
+        ("gpt-4", 8_192),
+        ("deepseek", 64_000),
+        ("qwen", 32_768),
+        ("mistral", 32_768),
+        ("llama-3.1", 128_000),
+        ("llama3.1", 128_000),
+    ];
+    if let Some((_, window)) = known.iter().find(|(name, _)| model.contains(name)) {
+        return *window;
+    }
+    match provider.trim().to_ascii_lowercase().as_str() {
+        "ollama" | "lmstudio" | "llamacpp" | "vllm" => LOCAL_CONTEXT_WINDOW,
+        _ => DEFAULT_CONTEXT_WINDOW,
+    }
+}
+
+/// Tokens per request for lines and their expected translations (see
+/// `batch_line_tokens`): the explicit `batch_token_budget`, or what the model's
+/// context window leaves after the system prompt.
+pub(crate) fn max_input_tokens(config: &LLMConfig) -> usize {
+    if let Some(budget) = config.batch_token_budget.filter(|b| *b > 0) {
+        return budget;
+    }
+    let context_window = config
+        .context_window
+        .filter(|w| *w > 0)
+        .unwrap_or_else(|| model_context_window(&config.provider, &config.model));
+    context_window.saturating_sub(system_prompt_tokens(config))
+}
+
+/// System prompt of a request without reference or draft lines.
+fn system_prompt_tokens(config: &LLMConfig) -> usize {
+    estimate_tokens(&build_system_prompt(config, &[], "", ""))
+}
+
+/// Batch budget for a provider chain: batches must fit the smallest context in it,
+/// since any batch may fall back to any provider.
+pub(crate) fn chain_max_input_tokens(chain: &[LLMConfig]) -> usize {
+    chain.iter().map(max_input_tokens).min().unwrap_or_default()
+}
+
+/// Room kept for the compaction summary in every chunk after the first; small
+/// budgets give up at most a quarter of their space.
+fn compaction_reserve(max_input_tokens: usize) -> usize {
+    MAX_COMPACTION_TOKENS.min(max_input_tokens / 4)
+}
+
+/// Tokens sent for one line, including its reference and draft translations.
+pub(crate) fn estimate_line_tokens(line: &TranslationLine) -> usize {
+    estimate_tokens(&line.text)
+        + line.reference.as_deref().map_or(0, estimate_tokens)
+        + line.draft.as_deref().map_or(0, estimate_tokens)
+}
+
+/// Room a line takes in a request's context: the line as sent plus the translation
+/// expected back, both wrapped in JSON.
+fn batch_line_tokens(line: &TranslationLine) -> usize {
+    let output = (estimate_tokens(&line.text) as f64 * OUTPUT_EXPANSION).ceil() as usize;
+    estimate_line_tokens(line) + output + LINE_JSON_TOKENS * 2
+}
 
 /// Splits subtitle lines into chunks that fit within the context window.
 fn plan_chunks(
-    lines: &[DialogLine],
+    lines: Vec<TranslationLine>,
     max_input_tokens: usize,
 ) -> Vec<Vec<TranslationLine>> {
-    let effective_budget = max_input_tokens.saturating_sub(MAX_COMPACTION_TOKENS);
+    let effective_budget = max_input_tokens - compaction_reserve(max_input_tokens);
 
     let mut chunks: Vec<Vec<TranslationLine>> = Vec::new();
     let mut current_chunk: Vec<TranslationLine> = Vec::new();
     let mut current_tokens = 0usize;
 
     for line in lines {
-        let line_tokens = estimate_tokens(&line.text);
+        let line_tokens = batch_line_tokens(&line);
         let budget = if chunks.is_empty() {
             max_input_tokens
         } else {
@@ -62,10 +149,7 @@ fn plan_chunks(
         }
 
         current_tokens += line_tokens;
-        current_chunk.push(TranslationLine {
-            id: line.index,
-            text: line.text.clone(),
-        });
+        current_chunk.push(line);
     }
 
     if !current_chunk.is_empty() {
@@ -79,6 +163,613 @@ fn plan_chunks(
     chunks
 }
 
+/// A scene cut is only taken once the chunk holds at least this share of its budget.
+const MIN_SCENE_CHUNK_FILL: f64 = 0.5;
+
+/// Seconds of silence before each line (0 for the first line or unparsable timings).
+fn scene_gaps(lines: &[DialogLine]) -> Vec<f64> {
+    let mut previous_end: Option<f64> = None;
+    lines
+        .iter()
+        .map(|line| {
+            let start = parse_subtitle_timestamp(&line.start);
+            let gap = match (previous_end, start) {
+                (Some(end), Some(start)) => (start - end).max(0.0),
+                _ => 0.0,
+            };
+            if let Some(end) = parse_subtitle_timestamp(&line.end) {
+                previous_end = Some(previous_end.map_or(end, |p: f64| p.max(end)));
+            }
+            gap
+        })
+        .collect()
+}
+
+/// Like `plan_chunks`, but when a chunk overflows it is cut at the largest gap in its
+/// second half instead of right before the overflowing line, so back-and-forth dialogue
+/// is not split across requests. `gaps[i]` is the silence before line `i`.
+fn plan_scene_chunks(
+    lines: Vec<TranslationLine>,
+    gaps: &[f64],
+    max_input_tokens: usize,
+) -> Vec<Vec<TranslationLine>> {
+    let effective_budget = max_input_tokens - compaction_reserve(max_input_tokens);
+    let tokens: Vec<usize> = lines.iter().map(batch_line_tokens).collect();
+    let gap = |i: usize| gaps.get(i).copied().unwrap_or(0.0);
+
+    let mut cuts: Vec<usize> = Vec::new();
+    let mut start = 0usize;
+    let mut current_tokens = 0usize;
+    for i in 0..lines.len() {
+        let budget = if cuts.is_empty() {
+            max_input_tokens
+        } else {
+            effective_budget
+        };
+
+        if i > start && current_tokens + tokens[i] > budget {
+            let min_fill = (budget as f64 * MIN_SCENE_CHUNK_FILL) as usize;
+            let mut filled = 0usize;
+            let mut cut = i;
+            let mut best_gap = f64::MIN;
+            for candidate in start + 1..=i {
+                filled += tokens[candidate - 1];
+                // Ties go to the later cut so chunks stay as full as possible.
+                if filled >= min_fill && gap(candidate) >= best_gap {
+                    cut = candidate;
+                    best_gap = gap(candidate);
+                }
+            }
+            cuts.push(cut);
+            current_tokens = tokens[cut..i].iter().sum();
+            start = cut;
+        }
+        current_tokens += tokens[i];
+    }
+
+    let mut chunks: Vec<Vec<TranslationLine>> = Vec::with_capacity(cuts.len() + 1);
+    let mut remaining = lines;
+    for cut in cuts.into_iter().rev() {
+        chunks.push(remaining.split_off(cut));
+    }
+    chunks.push(remaining);
+    chunks.reverse();
+    chunks
+}
+
+/// Tokens a chunk counts against a tokens-per-minute limit: the request plus a
+/// response of about the same length.
+fn chunk_rate_tokens(lines: &[TranslationLine]) -> usize {
+    lines.iter().map(estimate_line_tokens).sum::<usize>() * 2
+}
+
+/// Re-requests of lines a response left out, when the config does not set a count.
+const DEFAULT_MISSING_LINE_RETRIES: u32 = 2;
+
+/// A response checked against the ids that were requested.
+#[derive(Debug, Default)]
+struct ChunkReconciliation {
+    accepted: Vec<TranslatedLine>,
+    missing: Vec<usize>,
+    duplicates: Vec<usize>,
+    unknown: Vec<usize>,
+}
+
+/// Keeps the first translation for each requested id and sorts out the rest.
+fn reconcile_translations(
+    requested: &[TranslationLine],
+    translations: Vec<TranslatedLine>,
+) -> ChunkReconciliation {
+    let mut result = ChunkReconciliation::default();
+    let mut seen = std::collections::HashSet::new();
+    for translated in translations {
+        if !requested.iter().any(|line| line.id == translated.id) {
+            result.unknown.push(translated.id);
+        } else if !seen.insert(translated.id) {
+            result.duplicates.push(translated.id);
+        } else {
+            result.accepted.push(translated);
+        }
+    }
+    result.missing = requested
+        .iter()
+        .map(|line| line.id)
+        .filter(|id| !seen.contains(id))
+        .collect();
+    result
+}
+
+/// Default for `max_length_ratio`; generous because CJK sources expand a lot in
+/// Latin-script languages.
+const DEFAULT_MAX_LENGTH_RATIO: f64 = 5.0;
+/// Source lines shorter than this are measured as if they had this many characters.
+const MIN_RATIO_SOURCE_CHARS: usize = 8;
+const REFUSAL_PHRASES: [&str; 8] = [
+    "i cannot translate",
+    "i can't translate",
+    "i can not translate",
+    "i'm unable to",
+    "i am unable to",
+    "i'm sorry, but",
+    "as an ai",
+    "cannot assist with",
+];
+
+static JSON_SCAFFOLDING_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""(?:id|text|translations)"\s*:"#).unwrap());
+
+/// Why a translated line failed the output checks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SanityIssue {
+    Empty,
+    Untranslated,
+    JsonScaffolding,
+    TooLong,
+    Refusal,
+}
+
+impl SanityIssue {
+    fn reason(self) -> &'static str {
+        match self {
+            SanityIssue::Empty => "the translation was empty",
+            SanityIssue::Untranslated => "the translation was identical to the source",
+            SanityIssue::JsonScaffolding => "the translation contained JSON fields",
+            SanityIssue::TooLong => "the translation was far longer than the source",
+            SanityIssue::Refusal => "the translation was a refusal instead of a translation",
+        }
+    }
+
+    /// Identical or long lines can be legitimate (names, interjections), and refusal
+    /// phrases are ordinary dialogue in English, so those are kept once the retries run
+    /// out; the rest never are.
+    fn is_fatal(self) -> bool {
+        matches!(self, SanityIssue::Empty | SanityIssue::JsonScaffolding)
+    }
+}
+
+fn languages_differ(source_lang: &str, target_lang: &str) -> bool {
+    let source = to_ffmpeg_lang_code(Some(source_lang));
+    let target = to_ffmpeg_lang_code(Some(target_lang));
+    if source == "und" || target == "und" {
+        normalize_language_key(source_lang) != normalize_language_key(target_lang)
+    } else {
+        source != target
+    }
+}
+
+/// Checks one translated line against its source.
+fn sanity_issue(
+    source: &str,
+    translated: &str,
+    languages_differ: bool,
+    max_length_ratio: f64,
+) -> Option<SanityIssue> {
+    let translated = translated.trim();
+    let source = source.trim();
+    if translated.is_empty() {
+        return (!source.is_empty()).then_some(SanityIssue::Empty);
+    }
+
+    let lowered = translated.to_lowercase();
+    if REFUSAL_PHRASES
+        .iter()
+        .any(|phrase| lowered.contains(phrase))
+    {
+        return Some(SanityIssue::Refusal);
+    }
+    if JSON_SCAFFOLDING_RE.is_match(translated) {
+        return Some(SanityIssue::JsonScaffolding);
+    }
+    // Single words are often names or interjections that stay the same.
+    if languages_differ
+        && translated == source
+        && (source.chars().any(is_cjk) || source.split_whitespace().count() > 1)
+    {
+        return Some(SanityIssue::Untranslated);
+    }
+    let source_chars = source.chars().count().max(MIN_RATIO_SOURCE_CHARS);
+    if translated.chars().count() as f64 > source_chars as f64 * max_length_ratio {
+        return Some(SanityIssue::TooLong);
+    }
+    None
+}
+
+/// Sends one chunk (through `limiter` when set) and re-requests only the lines the
+/// model skipped or whose translation failed `sanity_issue`, telling the model why.
+/// Timed-out requests use up the same retries.
+/// Lines still missing after the retries are left out of the result.
+async fn translate_chunk(
+    config: &LLMConfig,
+    limiter: Option<&RateLimiter>,
+    lines: &[TranslationLine],
+    source_lang: &str,
+    target_lang: &str,
+    context: Option<&str>,
+) -> Result<Vec<TranslatedLine>, AnimeSubsError> {
+    let retries = config
+        .missing_line_retries
+        .unwrap_or(DEFAULT_MISSING_LINE_RETRIES);
+    let languages_differ = languages_differ(source_lang, target_lang);
+    let max_length_ratio = config
+        .max_length_ratio
+        .filter(|ratio| *ratio > 0.0)
+        .unwrap_or(DEFAULT_MAX_LENGTH_RATIO);
+    let mut accepted = Vec::with_capacity(lines.len());
+    let mut pending: Vec<TranslationLine> = lines.to_vec();
+
+    for attempt in 0..=retries {
+        let result = match limiter {
+            Some(limiter) => {
+                limiter
+                    .call_with_context(
+                        config,
+                        &pending,
+                        chunk_rate_tokens(&pending),
+                        source_lang,
+                        target_lang,
+                        context,
+                    )
+                    .await
+            }
+            None => {
+                call_llm_api_with_context(config, &pending, source_lang, target_lang, context).await
+            }
+        };
+        let translations = match result {
+            Ok(translations) => translations,
+            // A hung or stalled request is sent again like a response with missing lines.
+            Err(e) if is_timeout_error(&e) && attempt < retries => {
+                warn!("{} (attempt {}/{})", e, attempt + 1, retries + 1);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let result = reconcile_translations(&pending, translations);
+        if !result.duplicates.is_empty() || !result.unknown.is_empty() {
+            warn!(
+                "Ignored duplicate ids {:?} and unknown ids {:?} in LLM response",
+                result.duplicates, result.unknown
+            );
+        }
+
+        let mut retry: Vec<TranslationLine> = pending
+            .iter()
+            .filter(|line| result.missing.contains(&line.id))
+            .cloned()
+            .collect();
+        let missing = retry.len();
+        for translated in result.accepted {
+            let Some(line) = pending.iter().find(|line| line.id == translated.id) else {
+                continue;
+            };
+            match sanity_issue(
+                &line.text,
+                &translated.text,
+                languages_differ,
+                max_length_ratio,
+            ) {
+                Some(issue) if attempt < retries || issue.is_fatal() => {
+                    retry.push(TranslationLine {
+                        rejected: Some(issue.reason().to_string()),
+                        ..line.clone()
+                    });
+                }
+                _ => accepted.push(translated),
+            }
+        }
+        if retry.is_empty() {
+            break;
+        }
+        warn!(
+            "LLM response missed {} and failed checks on {} of {} lines (attempt {}/{})",
+            missing,
+            retry.len() - missing,
+            pending.len(),
+            attempt + 1,
+            retries + 1
+        );
+        pending = retry;
+    }
+    Ok(accepted)
+}
+
+/// `config` followed by its fallbacks. Fallbacks that leave the prompt settings empty
+/// take the primary's, so every provider in the chain gets the same instructions.
+pub(crate) fn provider_chain(config: &LLMConfig) -> Vec<LLMConfig> {
+    let mut primary = config.clone();
+    let fallbacks = std::mem::take(&mut primary.fallbacks);
+    let mut chain = Vec::with_capacity(fallbacks.len() + 1);
+
+    for mut fallback in fallbacks {
+        fallback.fallbacks.clear();
+        if fallback.system_prompt.trim().is_empty() && fallback.prompt_template.is_none() {
+            fallback.system_prompt = primary.system_prompt.clone();
+            fallback.prompt_template = primary.prompt_template.clone();
+            fallback.template_content = primary.template_content.clone();
+        }
+        if fallback.series_context.is_none() {
+            fallback.series_context = primary.series_context.clone();
+        }
+        if fallback.name_table.is_empty() {
+            fallback.name_table = primary.name_table.clone();
+        }
+        fallback.debug_log = primary.debug_log.clone();
+        chain.push(fallback);
+    }
+    chain.insert(0, primary);
+    chain
+}
+
+/// Runs `translate_chunk` on each provider of `chain` in turn, handing the lines one
+/// provider failed or never returned to the next. Returns the translations grouped by
+/// the index of the provider that produced them; fails only when no provider did.
+async fn translate_chunk_with_fallback(
+    chain: &[LLMConfig],
+    limiters: &[Option<RateLimiter>],
+    lines: &[TranslationLine],
+    source_lang: &str,
+    target_lang: &str,
+    context: Option<&str>,
+) -> Result<Vec<(usize, Vec<TranslatedLine>)>, AnimeSubsError> {
+    let mut results = Vec::new();
+    let mut pending = lines.to_vec();
+    let mut last_error = None;
+
+    for (provider_idx, config) in chain.iter().enumerate() {
+        if pending.is_empty() {
+            break;
+        }
+        if provider_idx > 0 {
+            info!(
+                "Falling back to {} ({}) for {} lines",
+                config.provider,
+                config.model,
+                pending.len()
+            );
+        }
+        let limiter = limiters.get(provider_idx).and_then(Option::as_ref);
+        match translate_chunk(config, limiter, &pending, source_lang, target_lang, context).await {
+            Ok(translations) => {
+                pending.retain(|line| !translations.iter().any(|t| t.id == line.id));
+                if !translations.is_empty() {
+                    results.push((provider_idx, translations));
+                }
+            }
+            Err(e) => {
+                warn!("Provider {} failed a batch: {}", config.provider, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match last_error {
+        Some(e) if results.is_empty() => Err(e),
+        _ => Ok(results),
+    }
+}
+
+fn batch_provider_entries(
+    chain: &[LLMConfig],
+    batch: usize,
+    results: &[(usize, Vec<TranslatedLine>)],
+) -> Vec<BatchProvider> {
+    results
+        .iter()
+        .map(|(provider_idx, translations)| BatchProvider {
+            batch,
+            provider: chain[*provider_idx].provider.clone(),
+            model: chain[*provider_idx].model.clone(),
+            line_count: translations.len(),
+        })
+        .collect()
+}
+
+/// Splits `lines` (built from `source`, in the same order) with the configured strategy.
+pub(crate) fn plan_batches(
+    strategy: BatchingStrategy,
+    lines: Vec<TranslationLine>,
+    source: &[DialogLine],
+    max_input_tokens: usize,
+) -> Vec<Vec<TranslationLine>> {
+    match strategy {
+        BatchingStrategy::Sequential => plan_chunks(lines, max_input_tokens),
+        BatchingStrategy::Scene => plan_scene_chunks(lines, &scene_gaps(source), max_input_tokens),
+    }
+}
+
+/// Requests `translate_subtitles` makes for `lines` (not skipped, repeats removed):
+/// one with every line when they fit `max_input_tokens`, else planned batches.
+pub(crate) fn plan_translation_batches(
+    lines: &[DialogLine],
+    strategy: BatchingStrategy,
+    max_input_tokens: usize,
+) -> Vec<Vec<TranslationLine>> {
+    let translation_lines: Vec<TranslationLine> = lines
+        .iter()
+        .map(|line| TranslationLine {
+            id: line.index,
+            text: line.text.clone(),
+            reference: line.reference.clone(),
+            draft: None,
+            rejected: None,
+        })
+        .collect();
+    let batch_tokens: usize = translation_lines.iter().map(batch_line_tokens).sum();
+    if translation_lines.is_empty() {
+        Vec::new()
+    } else if batch_tokens <= max_input_tokens {
+        vec![translation_lines]
+    } else {
+        plan_batches(strategy, translation_lines, lines, max_input_tokens)
+    }
+}
+
+/// Input and output tokens of sending `batches` and receiving `output_tokens` of
+/// translations, system prompt of every request included. Proofreading sends source
+/// and draft back in as many requests and receives the text again.
+pub(crate) fn batch_token_usage(
+    config: &LLMConfig,
+    batches: &[Vec<TranslationLine>],
+    source_lang: &str,
+    target_lang: &str,
+    output_tokens: usize,
+    refine: bool,
+) -> (usize, usize) {
+    let request_tokens: usize = batches
+        .iter()
+        .map(|batch| {
+            let prompt = build_system_prompt(config, batch, source_lang, target_lang);
+            estimate_tokens(&prompt) + batch.iter().map(estimate_line_tokens).sum::<usize>()
+        })
+        .sum();
+    let passes = if refine { 2 } else { 1 };
+    (
+        request_tokens * passes + output_tokens * (passes - 1),
+        output_tokens * passes,
+    )
+}
+
+/// Source lines shorter than this ("Yes.", "What?") depend on their context and are
+/// always sent, even when they repeat.
+const MIN_REPEAT_CHARS: usize = 10;
+
+/// Maps every line that repeats an earlier one (same source text, current text,
+/// style, class, speaker and reference) to the index of its first occurrence. Only
+/// first occurrences are sent; repeats get their translation afterwards. Skipped
+/// and short lines are ignored, and nothing is reused when `config` turns
+/// `reuse_repeats` off.
+pub(crate) fn repeated_lines(config: &LLMConfig, lines: &[DialogLine]) -> HashMap<usize, usize> {
+    let mut first_occurrence = HashMap::new();
+    let mut repeats = HashMap::new();
+    if config.reuse_repeats == Some(false) {
+        return repeats;
+    }
+    for line in lines.iter().filter(|line| !line.skip) {
+        let source = strip_ass_tags(&line.original_with_formatting)
+            .trim()
+            .to_string();
+        if source.chars().count() < MIN_REPEAT_CHARS {
+            continue;
+        }
+        // The source text keeps drafts of different lines apart when proofreading.
+        let key = (
+            source,
+            line.text.trim(),
+            line.style.as_deref(),
+            line.classification,
+            line.name.as_deref(),
+            line.reference.as_deref(),
+        );
+        let first = *first_occurrence.entry(key).or_insert(line.index);
+        if first != line.index {
+            repeats.insert(line.index, first);
+        }
+    }
+    repeats
+}
+
+/// Minimum share of the shorter cue that must overlap for two cues to be aligned.
+const MIN_REFERENCE_OVERLAP: f64 = 0.5;
+
+/// Attaches time-aligned text from `reference` to each line as its `reference`.
+fn align_reference_lines(lines: &mut [DialogLine], reference: &[DialogLine]) -> usize {
+    let reference_times: Vec<(f64, f64, &str)> = reference
+        .iter()
+        .filter_map(|r| {
+            let start = parse_subtitle_timestamp(&r.start)?;
+            let end = parse_subtitle_timestamp(&r.end)?;
+            Some((start, end, r.text.as_str()))
+        })
+        .collect();
+
+    let mut aligned = 0usize;
+    for line in lines.iter_mut() {
+        let (Some(start), Some(end)) = (
+            parse_subtitle_timestamp(&line.start),
+            parse_subtitle_timestamp(&line.end),
+        ) else {
+            continue;
+        };
+
+        let matches: Vec<&str> = reference_times
+            .iter()
+            .filter(|(ref_start, ref_end, _)| {
+                let overlap = end.min(*ref_end) - start.max(*ref_start);
+                let shorter = (end - start).min(ref_end - ref_start).max(0.001);
+                overlap > 0.0 && overlap / shorter >= MIN_REFERENCE_OVERLAP
+            })
+            .map(|(_, _, text)| *text)
+            .collect();
+
+        line.reference = if matches.is_empty() {
+            None
+        } else {
+            aligned += 1;
+            Some(matches.join("\n"))
+        };
+    }
+
+    aligned
+}
+
+/// Merges a second track into `subtitle_data` so each line is translated with
+/// the existing translation as reference (compare-and-merge mode).
+#[tauri::command]
+pub async fn attach_reference_track(
+    subtitle_data: SubtitleData,
+    reference_data: SubtitleData,
+) -> Result<SubtitleData, AnimeSubsError> {
+    let mut merged = subtitle_data;
+    let aligned = align_reference_lines(&mut merged.lines, &reference_data.lines);
+
+    if aligned == 0 {
+        return Err("No reference lines could be aligned with the source track".into());
+    }
+
+    info!(
+        "Aligned {}/{} lines with reference track",
+        aligned,
+        merged.lines.len()
+    );
+    Ok(merged)
+}
+
+/// Translates one chunk. Under a job's `control` it waits while the job is paused
+/// and gives up as soon as it is stopped.
+async fn translate_chunk_cancellable(
+    control: Option<&JobControl>,
+    chain: &[LLMConfig],
+    limiters: &[Option<RateLimiter>],
+    lines: &[TranslationLine],
+    source_lang: &str,
+    target_lang: &str,
+    context: Option<&str>,
+) -> Result<Vec<(usize, Vec<TranslatedLine>)>, AnimeSubsError> {
+    let translate =
+        translate_chunk_with_fallback(chain, limiters, lines, source_lang, target_lang, context);
+    let Some(control) = control else {
+        return translate.await;
+    };
+    control.checkpoint().await?;
+    tokio::select! {
+        results = translate => results,
+        _ = control.cancelled() => Err(AnimeSubsError::Cancelled),
+    }
+}
+
+/// One rate limiter per provider of a chain. A job shares one set across its files,
+/// so files translated in parallel stay within the configured limits together.
+type ChainLimiters = Arc<Vec<Option<RateLimiter>>>;
+
+fn chain_limiters(chain_len: usize, rate_limit: Option<&RateLimitOptions>) -> ChainLimiters {
+    Arc::new(
+        (0..chain_len)
+            .map(|_| rate_limit.map(RateLimiter::new))
+            .collect(),
+    )
+}
+
 #[tauri::command]
 pub async fn translate_subtitles(
     app: AppHandle,
@@ -86,27 +777,83 @@ pub async fn translate_subtitles(
     config: LLMConfig,
     source_lang: String,
     target_lang: String,
-) -> Result<SubtitleData, String> {
-    let total_lines = subtitle_data.lines.len();
+    rate_limit: Option<RateLimitOptions>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    translate_subtitles_with(
+        app,
+        subtitle_data,
+        config,
+        source_lang,
+        target_lang,
+        rate_limit,
+        None,
+        None,
+    )
+    .await
+    .map(|(data, _)| data)
+}
 
-    if total_lines == 0 {
-        return Err("No dialog lines to translate".to_string());
+/// `translate_subtitles` under `limiters` when given, else under limiters of its own.
+/// A job passes its `control`; the flag returned is whether the job was stopped
+/// partway, in which case the data holds the batches that finished and the other
+/// lines keep their source text.
+async fn translate_subtitles_with(
+    app: AppHandle,
+    subtitle_data: SubtitleData,
+    mut config: LLMConfig,
+    source_lang: String,
+    target_lang: String,
+    rate_limit: Option<RateLimitOptions>,
+    limiters: Option<ChainLimiters>,
+    control: Option<&JobControl>,
+) -> Result<(SubtitleData, bool), AnimeSubsError> {
+    let source_lines: Vec<DialogLine> = subtitle_data
+        .lines
+        .iter()
+        .filter(|line| !line.skip)
+        .cloned()
+        .collect();
+    if source_lines.is_empty() {
+        return Err("No dialog lines to translate".into());
     }
 
-    // Estimate total tokens and decide strategy
-    let total_text_tokens: usize = subtitle_data
-        .lines
+    let repeats = repeated_lines(&config, &source_lines);
+    let unique_lines: Vec<DialogLine> = source_lines
         .iter()
-        .map(|l| estimate_tokens(&l.text))
-        .sum();
-    let max_input_tokens =
-        (DEFAULT_CONTEXT_WINDOW as f64 * INPUT_CONTEXT_RATIO) as usize;
+        .filter(|line| !repeats.contains_key(&line.index))
+        .cloned()
+        .collect();
+    let total_lines = unique_lines.len();
+    if !repeats.is_empty() {
+        info!(
+            "Translating {} unique lines; {} repeats reuse their translation",
+            total_lines,
+            repeats.len()
+        );
+    }
+
+    let rate_limit = rate_limit.or_else(|| super::settings::current_settings().rate_limit);
+    super::settings::apply_saved_llm_defaults(&mut config);
+    super::prompts::resolve_prompt_template(&app, &mut config)?;
+    let mut chain = provider_chain(&config);
+    for fallback in chain.iter_mut().skip(1) {
+        if fallback.api_key.is_empty() {
+            fallback.api_key = super::secrets::read_api_key(&app, &fallback.provider)?;
+        }
+        if let Some(log) = &fallback.debug_log {
+            log.add_secret(&fallback.api_key);
+        }
+        super::prompts::resolve_prompt_template(&app, fallback)?;
+    }
 
-    let is_single_call = total_text_tokens <= max_input_tokens;
+    // Estimate total tokens and decide strategy
+    let total_text_tokens: usize = unique_lines.iter().map(|l| estimate_tokens(&l.text)).sum();
+    let max_input_tokens = chain_max_input_tokens(&chain);
+    let chunks = plan_translation_batches(&unique_lines, config.batching, max_input_tokens);
 
-    eprintln!(
+    info!(
         "Translation strategy: {} (est. {} tokens, max input {})",
-        if is_single_call {
+        if chunks.len() == 1 {
             "single call"
         } else {
             "chunked with compaction"
@@ -115,26 +862,19 @@ pub async fn translate_subtitles(
         max_input_tokens
     );
 
-    let chunks = if is_single_call {
-        let all_lines: Vec<TranslationLine> = subtitle_data
-            .lines
-            .iter()
-            .map(|line| TranslationLine {
-                id: line.index,
-                text: line.text.clone(),
-            })
-            .collect();
-        vec![all_lines]
-    } else {
-        plan_chunks(&subtitle_data.lines, max_input_tokens)
-    };
-
     let total_chunks = chunks.len();
     let translation_map: Arc<Mutex<HashMap<usize, String>>> =
         Arc::new(Mutex::new(HashMap::new()));
     let mut compacted_context: Option<String> = None;
-
-    for (chunk_idx, chunk_lines) in chunks.into_iter().enumerate() {
+    let mut batch_providers: Vec<BatchProvider> = Vec::new();
+    let limiters =
+        limiters.unwrap_or_else(|| chain_limiters(chain.len(), rate_limit.as_ref()));
+    let limiter = limiters.first().and_then(Option::as_ref);
+    let parallel = limiter.is_some_and(|l| l.max_concurrency() > 1);
+    let mut chunks = chunks.into_iter().enumerate();
+    let mut stopped = false;
+
+    for (chunk_idx, chunk_lines) in chunks.by_ref() {
         if chunk_lines.is_empty() {
             continue;
         }
@@ -160,14 +900,34 @@ pub async fn translate_subtitles(
         };
         let _ = app.emit("translation-progress", &progress);
 
-        let translations = call_llm_api_with_context(
-            &config,
+        let results = match translate_chunk_cancellable(
+            control,
+            &chain,
+            &limiters,
             &chunk_lines,
             &source_lang,
             &target_lang,
             compacted_context.as_deref(),
         )
-        .await?;
+        .await
+        {
+            Ok(results) => results,
+            Err(AnimeSubsError::Cancelled) => {
+                stopped = true;
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        batch_providers.extend(batch_provider_entries(&chain, chunk_idx, &results));
+        // The provider that finished the batch is the one most likely still answering.
+        let summary_config = results
+            .last()
+            .map(|(provider_idx, _)| &chain[*provider_idx])
+            .unwrap_or(&config);
+        let translations: Vec<TranslatedLine> = results
+            .into_iter()
+            .flat_map(|(_, translations)| translations)
+            .collect();
 
         {
             let mut map = translation_map.lock().await;
@@ -192,7 +952,7 @@ pub async fn translate_subtitles(
                 .collect();
 
             let summary = generate_compaction_summary(
-                &config,
+                summary_config,
                 &translated_sample,
                 &source_lang,
                 &target_lang,
@@ -202,17 +962,76 @@ pub async fn translate_subtitles(
             match summary {
                 Ok(s) => compacted_context = Some(s),
                 Err(e) => {
-                    eprintln!("Compaction summary failed (non-fatal): {}", e);
+                    warn!("Compaction summary failed (non-fatal): {}", e);
+                }
+            }
+        }
+
+        // In parallel mode the remaining chunks all share the first chunk's summary.
+        if parallel {
+            break;
+        }
+    }
+
+    if let Some(limiter) = limiter.filter(|_| parallel && !stopped) {
+        let (app, chain, limiters, map) = (&app, &chain, &limiters, &translation_map);
+        let (source_lang, target_lang) = (&source_lang, &target_lang);
+        let context = compacted_context.as_deref();
+        let mut pending = futures::stream::iter(chunks.filter(|(_, lines)| !lines.is_empty()))
+            .map(|(chunk_idx, chunk_lines)| async move {
+                let progress = TranslationProgress {
+                    current_chunk: chunk_idx,
+                    total_chunks,
+                    lines_translated: map.lock().await.len(),
+                    total_lines,
+                    status: format!(
+                        "Translating chunk {}/{} ({} lines)...",
+                        chunk_idx + 1,
+                        total_chunks,
+                        chunk_lines.len()
+                    ),
+                };
+                let _ = app.emit("translation-progress", &progress);
+                let results = translate_chunk_cancellable(
+                    control,
+                    chain,
+                    limiters,
+                    &chunk_lines,
+                    source_lang,
+                    target_lang,
+                    context,
+                )
+                .await;
+                (chunk_idx, results)
+            })
+            .buffer_unordered(limiter.max_concurrency());
+
+        while let Some((chunk_idx, results)) = pending.next().await {
+            let results = match results {
+                Ok(results) => results,
+                Err(AnimeSubsError::Cancelled) => {
+                    stopped = true;
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            batch_providers.extend(batch_provider_entries(chain, chunk_idx, &results));
+            let mut map = map.lock().await;
+            for (_, translations) in results {
+                for translated in translations {
+                    map.insert(translated.id, translated.text);
                 }
             }
         }
+        batch_providers.sort_by_key(|entry| entry.batch);
     }
 
     let map = translation_map.lock().await;
     let mut translated_lines = subtitle_data.lines.clone();
     let mut changed_lines = 0usize;
     for line in &mut translated_lines {
-        if let Some(translated_text) = map.get(&line.index) {
+        let index = repeats.get(&line.index).unwrap_or(&line.index);
+        if let Some(translated_text) = map.get(index) {
             if translated_text.trim() != line.text.trim() {
                 changed_lines += 1;
             }
@@ -220,11 +1039,27 @@ pub async fn translate_subtitles(
         }
     }
 
-    if changed_lines == 0 {
+    if stopped && map.is_empty() {
+        return Err(AnimeSubsError::Cancelled);
+    }
+    if changed_lines == 0 && !stopped {
         return Err(
             "Translation produced no subtitle changes. Check the \
              provider, model, prompt, and selected languages."
-                .to_string(),
+                .into(),
+        );
+    }
+
+    let failed_line_ids: Vec<usize> = source_lines
+        .iter()
+        .map(|line| line.index)
+        .filter(|index| !map.contains_key(repeats.get(index).unwrap_or(index)))
+        .collect();
+    if !failed_line_ids.is_empty() {
+        warn!(
+            "{} lines kept their source text after retries: {:?}",
+            failed_line_ids.len(),
+            failed_line_ids
         );
     }
 
@@ -233,39 +1068,170 @@ pub async fn translate_subtitles(
         total_chunks,
         lines_translated: map.len(),
         total_lines,
-        status: "done".to_string(),
+        status: if stopped { "stopped" } else { "done" }.to_string(),
     };
     let _ = app.emit("translation-progress", &final_progress);
 
-    Ok(SubtitleData {
+    let data = SubtitleData {
         format: subtitle_data.format,
         line_count: translated_lines.len(),
         lines: translated_lines,
         source_path: subtitle_data.source_path,
         ass_header: subtitle_data.ass_header,
-    })
+        failed_line_ids,
+        batch_providers,
+        class_counts: subtitle_data.class_counts,
+        deduplicated_lines: repeats.len(),
+    };
+    Ok((data, stopped))
 }
 
-fn reconstruct_ass(original_content: &str, translations: &[DialogLine]) -> String {
-    let mut result = Vec::new();
-    let mut in_events = false;
-    let mut in_styles = false;
-    let mut style_encoding_index: Option<usize> = None;
-
-    let translation_map: std::collections::HashMap<String, &str> = translations
-        .iter()
-        .map(|t| {
-            let key = strip_ass_tags(&t.original_with_formatting)
-                .trim()
-                .to_lowercase();
-            (key, t.text.as_str())
-        })
-        .collect();
-
-    let skip_styles: Vec<&str> = vec![
-        "op", "ed", "opening", "ending", "karaoke", "romaji", "japanese", "sign", "signs", "title",
-        "song", "lyrics", "insert", "credit", "credits",
-    ];
+/// Second pass over a finished translation: (source, draft) pairs are sent back in
+/// context-sized batches for proofreading and the improved text replaces each draft.
+#[tauri::command]
+pub async fn refine_translations(
+    app: AppHandle,
+    mut subtitle_data: SubtitleData,
+    mut config: LLMConfig,
+    source_lang: String,
+    target_lang: String,
+) -> Result<SubtitleData, AnimeSubsError> {
+    let source_lines: Vec<DialogLine> = subtitle_data
+        .lines
+        .iter()
+        .filter(|line| !line.skip)
+        .cloned()
+        .collect();
+    if source_lines.is_empty() {
+        return Err("No dialog lines to refine".into());
+    }
+    super::settings::apply_saved_llm_defaults(&mut config);
+    super::prompts::resolve_prompt_template(&app, &mut config)?;
+
+    // Repeats got the same draft as their first occurrence, so they share its proofreading.
+    let repeats = repeated_lines(&config, &source_lines);
+    let source_lines: Vec<DialogLine> = source_lines
+        .into_iter()
+        .filter(|line| !repeats.contains_key(&line.index))
+        .collect();
+    let total_lines = source_lines.len();
+
+    let draft_lines: Vec<TranslationLine> = source_lines
+        .iter()
+        .map(|line| TranslationLine {
+            id: line.index,
+            text: strip_ass_tags(&line.original_with_formatting),
+            reference: line.reference.clone(),
+            draft: Some(line.text.clone()),
+            rejected: None,
+        })
+        .collect();
+    let max_input_tokens = max_input_tokens(&config);
+    let chunks = plan_batches(
+        config.batching,
+        draft_lines,
+        &source_lines,
+        max_input_tokens,
+    );
+    let total_chunks = chunks.len();
+
+    let mut refined = HashMap::new();
+    for (chunk_idx, chunk_lines) in chunks.into_iter().enumerate() {
+        if chunk_lines.is_empty() {
+            continue;
+        }
+
+        let _ = app.emit(
+            "translation-progress",
+            &TranslationProgress {
+                current_chunk: chunk_idx,
+                total_chunks,
+                lines_translated: refined.len(),
+                total_lines,
+                status: format!(
+                    "Proofreading chunk {}/{} ({} lines)...",
+                    chunk_idx + 1,
+                    total_chunks,
+                    chunk_lines.len()
+                ),
+            },
+        );
+
+        let translations = translate_chunk(
+            &config,
+            None,
+            &chunk_lines,
+            &source_lang,
+            &target_lang,
+            None,
+        )
+        .await?;
+        for translated in translations {
+            if !translated.text.trim().is_empty() {
+                refined.insert(translated.id, translated.text);
+            }
+        }
+    }
+
+    let mut changed_lines = 0usize;
+    for line in &mut subtitle_data.lines {
+        let index = repeats.get(&line.index).unwrap_or(&line.index);
+        if let Some(text) = refined.get(index).cloned() {
+            if text.trim() != line.text.trim() {
+                changed_lines += 1;
+            }
+            line.text = text;
+        }
+    }
+    info!(
+        "Proofreading changed {}/{} lines",
+        changed_lines, total_lines
+    );
+
+    let _ = app.emit(
+        "translation-progress",
+        &TranslationProgress {
+            current_chunk: total_chunks,
+            total_chunks,
+            lines_translated: total_lines,
+            total_lines,
+            status: "done".to_string(),
+        },
+    );
+    Ok(subtitle_data)
+}
+
+/// Event text as compared between a `DialogLine` and the event it came from.
+fn ass_match_text(text: &str) -> String {
+    strip_ass_tags(text).trim().to_lowercase()
+}
+
+/// Writes each translation into the event it was parsed from: the nth event that
+/// `parse_ass_file` reads is line `n`. Lines whose index no longer points at their
+/// event (after cleanup merged or reordered cues) fall back to matching on event
+/// kind, style, speaker and text, so identical lines only share a translation when
+/// nothing tells them apart.
+fn reconstruct_ass(original_content: &str, translations: &[DialogLine]) -> String {
+    let mut result = Vec::new();
+    let mut in_events = false;
+    let mut in_styles = false;
+    let mut style_encoding_index: Option<usize> = None;
+    let mut event_format = AssEventFormat::default();
+    let mut event_index = 0;
+
+    // Skipped lines were never translated; mapping them would strip inline tags.
+    let translated: Vec<&DialogLine> = translations.iter().filter(|t| !t.skip).collect();
+    let by_index: HashMap<usize, &DialogLine> = translated.iter().map(|t| (t.index, *t)).collect();
+    let mut by_text: HashMap<(AssEventKind, String, String, String), &str> = HashMap::new();
+    for t in &translated {
+        let key = (
+            t.event,
+            t.style.as_deref().unwrap_or_default().to_string(),
+            t.name.as_deref().unwrap_or_default().to_string(),
+            ass_match_text(&t.original_with_formatting),
+        );
+        by_text.entry(key).or_insert(t.text.as_str());
+    }
 
     for line in original_content.lines() {
         let trimmed = line.trim();
@@ -273,7 +1239,7 @@ fn reconstruct_ass(original_content: &str, translations: &[DialogLine]) -> Strin
         if trimmed.starts_with("[") {
             let section = trimmed.trim_matches(&['[', ']'][..]).to_ascii_lowercase();
             match section.as_str() {
-                "v4+ styles" => {
+                "v4+ styles" | "v4 styles" => {
                     in_styles = true;
                     in_events = false;
                     style_encoding_index = None;
@@ -319,32 +1285,47 @@ fn reconstruct_ass(original_content: &str, translations: &[DialogLine]) -> Strin
             }
         }
 
-        if in_events && trimmed.starts_with("Dialogue:") {
-            let parts: Vec<&str> = trimmed.splitn(10, ',').collect();
-            if parts.len() >= 10 {
-                let original_text = parts[9..].join(",");
-                let clean_original = strip_ass_tags(&original_text);
-                let style = parts[3].trim().to_lowercase();
-                let is_music_line = is_music_or_karaoke_line(&original_text, &clean_original);
-
-                let should_skip = skip_styles.iter().any(|&skip| {
-                    style.contains(skip) || style.split_whitespace().any(|word| word == skip)
-                });
-
-                let is_too_short = clean_original.trim().chars().count() < 3;
-
-                if !should_skip
-                    && !is_too_short
-                    && !clean_original.trim().is_empty()
-                    && !is_music_line
-                {
-                    let lookup_key = clean_original.trim().to_lowercase();
-                    if let Some(translated_text) = translation_map.get(&lookup_key) {
-                        let new_text = apply_ass_formatting(&original_text, translated_text);
-                        let new_line = format!("{},{}", parts[..9].join(","), new_text);
-                        result.push(new_line);
-                        continue;
-                    }
+        if in_events && trimmed.starts_with("Format:") {
+            event_format = AssEventFormat::parse(&trimmed["Format:".len()..]);
+        }
+
+        let event = trimmed
+            .split_once(':')
+            .and_then(|(label, values)| Some((AssEventKind::from_label(label)?, values)));
+        if let Some((event, values)) = event.filter(|_| in_events) {
+            let text_index = event_format.position("text");
+            let timed = |parts: &[&str]| {
+                event_format.get(parts, "start").is_some()
+                    && event_format.get(parts, "end").is_some()
+            };
+            if let (Some(mut parts), Some(text_index)) = (
+                event_format.split(values).filter(|parts| timed(parts)),
+                text_index,
+            ) {
+                let index = event_index;
+                event_index += 1;
+                let original_text = parts[text_index];
+                let match_text = ass_match_text(original_text);
+                let field = |name: &str| {
+                    event_format
+                        .get(&parts, name)
+                        .map(str::trim)
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                let translation = by_index
+                    .get(&index)
+                    .filter(|t| ass_match_text(&t.original_with_formatting) == match_text)
+                    .map(|t| t.text.as_str())
+                    .or_else(|| {
+                        let key = (event, field("style"), field("name"), match_text.clone());
+                        by_text.get(&key).copied()
+                    });
+                if let Some(translated_text) = translation.filter(|_| !match_text.is_empty()) {
+                    let new_text = apply_ass_formatting(original_text, translated_text);
+                    parts[text_index] = &new_text;
+                    result.push(format!("{}:{}", event.label(), parts.join(",")));
+                    continue;
                 }
             }
         }
@@ -368,13 +1349,35 @@ fn apply_ass_formatting(original: &str, translated: &str) -> String {
     }
 }
 
+/// Wraps `text` in the SRT tags recorded for its source cue.
+pub(crate) fn apply_srt_formatting(text: &str, formatting: Option<&LineFormatting>) -> String {
+    let Some(formatting) = formatting else {
+        return text.to_string();
+    };
+
+    let mut result = text.to_string();
+    for (enabled, tag) in [
+        (formatting.underline, "u"),
+        (formatting.bold, "b"),
+        (formatting.italic, "i"),
+    ] {
+        if enabled {
+            result = format!("<{tag}>{result}</{tag}>");
+        }
+    }
+    if let Some(color) = &formatting.color {
+        result = format!("<font color=\"{}\">{}</font>", color, result);
+    }
+    result
+}
+
 fn reconstruct_srt(translations: &[DialogLine]) -> String {
     let mut result = Vec::new();
 
     for (idx, line) in translations.iter().enumerate() {
         result.push(format!("{}", idx + 1));
         result.push(format!("{} --> {}", line.start, line.end));
-        result.push(line.text.clone());
+        result.push(apply_srt_formatting(&line.text, line.formatting.as_ref()));
         result.push(String::new());
     }
 
@@ -393,13 +1396,158 @@ fn reconstruct_vtt(translations: &[DialogLine]) -> String {
     result.join("\n")
 }
 
+/// Writes `line` as an event (`Dialogue:`, `Comment:`, ...) in the field order of
+/// `format`; fields the parser doesn't keep get their neutral value.
+fn format_ass_event(format: &AssEventFormat, line: &DialogLine) -> String {
+    let values: Vec<String> = format
+        .fields()
+        .iter()
+        .map(|field| match field.as_str() {
+            "start" => line.start.clone(),
+            "end" => line.end.clone(),
+            "style" => line.style.clone().unwrap_or_else(|| "Default".to_string()),
+            "name" => line.name.clone().unwrap_or_default(),
+            "text" if line.skip => line.original_with_formatting.clone(),
+            "text" => line.text.replace("\n", "\\N"),
+            "marked" => "Marked=0".to_string(),
+            "effect" => String::new(),
+            _ => "0".to_string(),
+        })
+        .collect();
+    format!("{}: {}", line.event.label(), values.join(","))
+}
+
+/// Serializes `data` back to its subtitle format. ASS output is rebuilt on top of
+/// `original_content` when available so styles and untouched events survive.
+pub(crate) fn reconstruct_subtitle_content(
+    data: &SubtitleData,
+    original_content: Option<&str>,
+) -> Result<String, AnimeSubsError> {
+    let content = match data.format.as_str() {
+        "ass" | "ssa" => {
+            if let Some(original_content) = original_content {
+                reconstruct_ass(original_content, &data.lines)
+            } else if let Some(header) = &data.ass_header {
+                let event_format = header
+                    .lines()
+                    .skip_while(|l| !l.trim().eq_ignore_ascii_case("[events]"))
+                    .find_map(|l| l.trim().strip_prefix("Format:"))
+                    .map(AssEventFormat::parse)
+                    .unwrap_or_default();
+                let mut result = header.clone();
+                result.push('\n');
+                for line in &data.lines {
+                    result.push_str(&format_ass_event(&event_format, line));
+                    result.push('\n');
+                }
+                result
+            } else {
+                return Err("Cannot reconstruct ASS without original file or header".into());
+            }
+        }
+        "srt" => reconstruct_srt(&data.lines),
+        "vtt" | "webvtt" => reconstruct_vtt(&data.lines),
+        _ => return Err(format!("Unsupported format: {}", data.format).into()),
+    };
+
+    Ok(content)
+}
+
+/// Upper bound on `max_parallel_files`; more workers only multiply rate limits.
+const MAX_PARALLEL_FILES: usize = 8;
+
+const CREDIT_COMMENT: &str = "; Translated by AnimeSubs";
+/// Start of the title of embedded translations, e.g. "Translated (pt-br)".
+const TRANSLATED_TITLE_PREFIX: &str = "Translated (";
+
+/// Inserts `lines` after the last non-blank line of `result`, so they stay inside the
+/// section that just ended.
+fn append_to_section(result: &mut Vec<String>, lines: Vec<String>) {
+    let at = result
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(result.len(), |idx| idx + 1);
+    result.splice(at..at, lines);
+}
+
+/// Rewrites `[Script Info]` keys from `metadata`; keys the source lacks are added at
+/// the end of the section.
+fn update_script_info(
+    content: &str,
+    metadata: &AssMetadataOptions,
+    target_lang: Option<&str>,
+) -> String {
+    let mut updates: Vec<(&str, String)> = Vec::new();
+    if let Some(title) = metadata.title.as_deref().filter(|t| !t.trim().is_empty()) {
+        updates.push(("Title", title.trim().to_string()));
+    }
+    let language = metadata.language.as_deref().or(target_lang);
+    if let Some(language) = language.filter(|l| !l.trim().is_empty()) {
+        updates.push(("Language", language.trim().to_string()));
+    }
+    if let Some(scaled) = metadata.scaled_border_and_shadow {
+        let value = if scaled { "yes" } else { "no" };
+        updates.push(("ScaledBorderAndShadow", value.to_string()));
+    }
+
+    let mut result: Vec<String> = Vec::new();
+    let mut pending: Vec<(&str, String)> = Vec::new();
+    let mut in_info = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            if in_info {
+                let missing = pending.drain(..).map(|(k, v)| format!("{}: {}", k, v));
+                append_to_section(&mut result, missing.collect());
+            }
+            in_info = trimmed.eq_ignore_ascii_case("[script info]");
+            result.push(line.to_string());
+            if in_info {
+                pending = updates.clone();
+                if metadata.credit {
+                    result.push(CREDIT_COMMENT.to_string());
+                }
+            }
+            continue;
+        }
+
+        if in_info {
+            if metadata.credit && trimmed == CREDIT_COMMENT {
+                continue;
+            }
+            let key = trimmed.split_once(':').map(|(key, _)| key.trim());
+            if let Some(idx) = key.and_then(|key| pending.iter().position(|(k, _)| *k == key)) {
+                let (key, value) = pending.remove(idx);
+                result.push(format!("{}: {}", key, value));
+                continue;
+            }
+        }
+        result.push(line.to_string());
+    }
+    if in_info {
+        let missing = pending.drain(..).map(|(k, v)| format!("{}: {}", k, v));
+        append_to_section(&mut result, missing.collect());
+    }
+
+    let mut updated = result.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated
+}
+
 #[tauri::command]
 pub async fn save_translated_subtitles(
     translated_data: SubtitleData,
     output_path: Option<String>,
     original_file_path: Option<String>,
     temporary: Option<bool>,
-) -> Result<OperationResult, String> {
+    naming_template: Option<String>,
+    target_lang: Option<String>,
+    output_encoding: Option<SubtitleEncodingOptions>,
+    dialogue_style: Option<AssStylePatch>,
+    ass_metadata: Option<AssMetadataOptions>,
+) -> Result<OperationResult, AnimeSubsError> {
     let has_translated_changes = translated_data
         .lines
         .iter()
@@ -409,58 +1557,58 @@ pub async fn save_translated_subtitles(
         return Err(
             "Refusing to save translated subtitles because no \
              translated lines differ from the source."
-                .to_string(),
+                .into(),
         );
     }
 
-    let content = match translated_data.format.as_str() {
-        "ass" | "ssa" => {
-            if let Some(ref original_path) = original_file_path {
-                let original_content = read_file_as_utf8(original_path)?;
-                reconstruct_ass(&original_content, &translated_data.lines)
-            } else if let Some(header) = &translated_data.ass_header {
-                let mut result = header.clone();
-                result.push('\n');
-                for line in &translated_data.lines {
-                    result.push_str(&format!(
-                        "Dialogue: 0,{},{},{},{},0,0,0,,{}\n",
-                        line.start,
-                        line.end,
-                        line.style.as_deref().unwrap_or("Default"),
-                        line.name.as_deref().unwrap_or(""),
-                        line.text.replace("\n", "\\N")
-                    ));
-                }
-                result
-            } else {
-                return Err("Cannot reconstruct ASS without original file or header".to_string());
-            }
-        }
-        "srt" => reconstruct_srt(&translated_data.lines),
-        "vtt" | "webvtt" => reconstruct_vtt(&translated_data.lines),
-        _ => return Err(format!("Unsupported format: {}", translated_data.format)),
+    let original_content = match (translated_data.format.as_str(), original_file_path.as_deref()) {
+        ("ass" | "ssa", Some(path)) => Some(read_file_as_utf8(path)?),
+        _ => None,
     };
+    let mut content = reconstruct_subtitle_content(&translated_data, original_content.as_deref())?;
+    if let (Some(patch), "ass" | "ssa") = (&dialogue_style, translated_data.format.as_str()) {
+        content = apply_style_preset(&content, patch, None)?;
+    }
+    if let (Some(metadata), "ass" | "ssa") = (&ass_metadata, translated_data.format.as_str()) {
+        content = update_script_info(&content, metadata, target_lang.as_deref());
+    }
 
+    let source_path = original_file_path
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .unwrap_or(&translated_data.source_path);
     let resolved_output_path = if let Some(path) = output_path {
         path
     } else if temporary.unwrap_or(false) {
-        let source_path = original_file_path
-            .as_deref()
-            .filter(|p| !p.is_empty())
-            .unwrap_or(&translated_data.source_path);
         build_temp_subtitle_path(source_path, "translated", &translated_data.format)?
             .to_string_lossy()
             .to_string()
+    } else if let Some(template) = naming_template.as_deref() {
+        let lang = target_lang.as_deref().unwrap_or("und");
+        let vars = NamingVars::for_file(source_path, lang, &translated_data.format, None);
+        let parent = Path::new(source_path).parent().unwrap_or(Path::new("."));
+        parent
+            .join(render_output_name(template, &vars)?)
+            .to_string_lossy()
+            .to_string()
     } else {
-        return Err("output_path is required when temporary save is disabled".to_string());
+        return Err(
+            "output_path or naming_template is required when temporary save is disabled".into(),
+        );
     };
 
-    write_utf8_file(&resolved_output_path, &content, true)?;
+    write_subtitle_file(
+        &resolved_output_path,
+        &content,
+        &output_encoding.unwrap_or_default(),
+        true,
+    )?;
 
     Ok(OperationResult {
         success: true,
         message: format!("Saved translated subtitles to {}", resolved_output_path),
         data: Some(resolved_output_path),
+        verification: None,
     })
 }
 
@@ -478,6 +1626,34 @@ fn emit_job_progress(
             total_files,
             progress: progress.clamp(0.0, 100.0),
             status: status.into(),
+            video_path: None,
+            stage: None,
+            file_progress: None,
+        },
+    );
+}
+
+/// Progress of the `current_file`-th video, `step` (0–1) of the way through it.
+fn emit_file_progress(
+    app: &AppHandle,
+    video_path: &str,
+    current_file: usize,
+    total_files: usize,
+    step: f64,
+    stage: JobFileStage,
+    status: impl Into<String>,
+) {
+    let file_span = 100.0 / total_files as f64;
+    let _ = app.emit(
+        "translation-job-progress",
+        TranslationJobProgress {
+            current_file,
+            total_files,
+            progress: ((current_file - 1) as f64 * file_span + step * file_span).clamp(0.0, 100.0),
+            status: status.into(),
+            video_path: Some(video_path.to_string()),
+            stage: Some(stage),
+            file_progress: Some((step * 100.0).clamp(0.0, 100.0)),
         },
     );
 }
@@ -486,7 +1662,7 @@ fn normalize_language_key(value: &str) -> String {
     value.to_lowercase().replace('_', "-").trim().to_string()
 }
 
-fn to_ffmpeg_lang_code(value: Option<&str>) -> String {
+pub(crate) fn to_ffmpeg_lang_code(value: Option<&str>) -> String {
     let Some(value) = value else {
         return "und".to_string();
     };
@@ -563,32 +1739,139 @@ fn select_subtitle_format(output_format: &str, codec: &str) -> String {
     }
 }
 
+/// `path` with `.partial` before its extension, for the batches of a stopped file.
+fn partial_output_path(path: &str) -> String {
+    let path = Path::new(path);
+    let extension = path
+        .extension()
+        .map(|ext| format!("partial.{}", ext.to_string_lossy()))
+        .unwrap_or_else(|| "partial".to_string());
+    path.with_extension(extension).to_string_lossy().to_string()
+}
+
 fn persistent_output_path(
     video_path: &str,
     output_directory: Option<&str>,
+    naming_template: Option<&str>,
     lang_code: &str,
-    track_index: u32,
+    track_index: Option<u32>,
     format: &str,
-) -> String {
+) -> Result<String, AnimeSubsError> {
     let video_pathbuf = Path::new(video_path);
-    let stem = video_pathbuf
-        .file_stem()
-        .map(|s| s.to_string_lossy().to_string())
-        .unwrap_or_else(|| "subtitle".to_string());
-    let timestamp = chrono::Local::now().format("%Y%m%dT%H%M%S");
-    let filename = format!(
-        "{}_{}_{}_track{}.{}",
-        stem, lang_code, timestamp, track_index, format
-    );
+    let filename = match naming_template.filter(|t| !t.trim().is_empty()) {
+        Some(template) => {
+            let vars = NamingVars::for_file(video_path, lang_code, format, track_index);
+            render_output_name(template, &vars)?
+        }
+        None => {
+            let stem = video_pathbuf
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "subtitle".to_string());
+            let timestamp = chrono::Local::now().format("%Y%m%dT%H%M%S");
+            let source =
+                track_index.map_or_else(|| "external".to_string(), |i| format!("track{}", i));
+            format!("{}_{}_{}_{}.{}", stem, lang_code, timestamp, source, format)
+        }
+    };
 
-    if let Some(dir) = output_directory.filter(|d| !d.is_empty()) {
-        PathBuf::from(dir)
-            .join(filename)
-            .to_string_lossy()
-            .to_string()
+    let path = if let Some(dir) = output_directory.filter(|d| !d.is_empty()) {
+        PathBuf::from(dir).join(filename)
     } else {
         let parent = video_pathbuf.parent().unwrap_or(Path::new("."));
-        parent.join(filename).to_string_lossy().to_string()
+        parent.join(filename)
+    };
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Subtitle file an earlier job saved for this video and language, where
+/// `persistent_output_path` would write it.
+fn translated_sidecar(
+    video_path: &str,
+    naming_template: Option<&str>,
+    lang_code: &str,
+    track_index: Option<u32>,
+) -> Option<PathBuf> {
+    let video = Path::new(video_path);
+    if naming_template.is_some_and(|t| !t.trim().is_empty()) {
+        return SIDECAR_EXTENSIONS.iter().find_map(|format| {
+            let path = persistent_output_path(
+                video_path,
+                None,
+                naming_template,
+                lang_code,
+                track_index,
+                format,
+            )
+            .ok()?;
+            Path::new(&path).is_file().then(|| PathBuf::from(path))
+        });
+    }
+
+    // Default names carry a timestamp: "<stem>_<lang>_<timestamp>_<source>.<ext>".
+    let prefix = format!("{}_{}_", video.file_stem()?.to_string_lossy(), lang_code);
+    fs::read_dir(video.parent()?)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let extension = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            name.starts_with(&prefix) && SIDECAR_EXTENSIONS.contains(&extension.as_str())
+        })
+}
+
+/// Why `video_path` needs no new translation: it carries a track an earlier job
+/// embedded in the target language, or the subtitle file an earlier job saved.
+fn existing_translation(
+    request: &TranslationJobRequest,
+    video_path: &str,
+    video_info: &VideoInfo,
+    track_index: Option<u32>,
+) -> Option<String> {
+    if request.target_lang.is_empty() {
+        return None;
+    }
+    let ffmpeg_lang_code = to_ffmpeg_lang_code(Some(&request.target_lang));
+    if let Some(track) = video_info.subtitle_tracks.iter().find(|track| {
+        track
+            .title
+            .as_deref()
+            .is_some_and(|title| title.starts_with(TRANSLATED_TITLE_PREFIX))
+            && to_ffmpeg_lang_code(track.language.as_deref()) == ffmpeg_lang_code
+    }) {
+        return Some(format!(
+            "Already has a translated {} track (#{})",
+            ffmpeg_lang_code, track.index
+        ));
+    }
+
+    let lang_code = sanitize_lang_code_for_filename(Some(&request.target_lang));
+    translated_sidecar(
+        video_path,
+        request.naming_template.as_deref(),
+        &lang_code,
+        track_index,
+    )
+    .map(|path| format!("Translated subtitle already exists: {}", path.display()))
+}
+
+/// Result of a video left untranslated on purpose.
+fn skipped_output(video_path: &str, reason: String) -> TranslationJobOutput {
+    TranslationJobOutput {
+        video_path: video_path.to_string(),
+        output_video_path: None,
+        subtitle_path: None,
+        embedded: false,
+        batch_providers: Vec::new(),
+        skipped: Some(reason),
+        subtitle_data: None,
     }
 }
 
@@ -601,263 +1884,755 @@ async fn cleanup_generated_file(file_path: Option<&str>) {
     }
 }
 
-#[tauri::command]
-pub async fn start_translation_job(
-    app: AppHandle,
-    request: TranslationJobRequest,
-) -> Result<TranslationJobResult, String> {
-    let total_files = request.video_paths.len();
-    let mut failures = Vec::new();
-    let mut outputs = Vec::new();
-    let mut completed_files = 0usize;
+/// Track translated for `video_path`: the one picked for it, the job's track, or
+/// the best ranked track for the source language.
+pub(crate) fn job_track_index(
+    request: &TranslationJobRequest,
+    video_path: &str,
+    video_info: &VideoInfo,
+) -> Result<u32, AnimeSubsError> {
+    let chosen_track = request.file_tracks.get(video_path).copied();
+    match chosen_track.or(request.subtitle_track) {
+        Some(index) => Ok(index),
+        None => {
+            let preferred: Vec<String> = Some(request.source_lang.clone())
+                .filter(|lang| !lang.is_empty() && lang != "auto")
+                .into_iter()
+                .collect();
+            Ok(super::track_selection::rank_subtitle_tracks(
+                &video_info.subtitle_tracks,
+                &preferred,
+                &HashMap::new(),
+            )
+            .first()
+            .map(|best| best.track_index)
+            .ok_or_else(|| "No subtitle tracks found".to_string())?)
+        }
+    }
+}
 
-    if total_files == 0 {
-        return Err("No video files selected".to_string());
+/// Applies the job's SDH stripping and comment handling to parsed subtitles.
+pub(crate) fn prepare_job_subtitle(
+    request: &TranslationJobRequest,
+    mut subtitle_data: SubtitleData,
+) -> SubtitleData {
+    if let Some(rules) = &request.strip_sdh {
+        let (lines, _) = super::cleanup::strip_sdh_lines(subtitle_data.lines, rules);
+        subtitle_data.line_count = lines.len();
+        subtitle_data.class_counts = LineClassCounts::from_lines(&lines);
+        subtitle_data.lines = lines;
+    }
+    if request.translate_comments {
+        super::subtitle::include_comment_lines(&mut subtitle_data);
     }
+    subtitle_data
+}
 
-    for (file_idx, video_path) in request.video_paths.iter().enumerate() {
-        let current_file = file_idx + 1;
-        let filename = Path::new(video_path)
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| video_path.clone());
-        let file_base_progress = (file_idx as f64 / total_files as f64) * 100.0;
-        let file_progress_span = 100.0 / total_files as f64;
-        let progress = |step: f64| file_base_progress + (step * file_progress_span);
+/// Runs one video of a translation job end to end. Remuxes take `remux_lock` so
+/// parallel workers never write two large videos at once. Failures come back as
+/// "<file>: <reason>".
+async fn process_job_file(
+    app: &AppHandle,
+    request: &TranslationJobRequest,
+    debug_log: Option<Arc<DebugLog>>,
+    limiters: &ChainLimiters,
+    remux_lock: &Semaphore,
+    file_idx: usize,
+) -> (JobFileReport, Result<TranslationJobOutput, AnimeSubsError>) {
+    let total_files = request.video_paths.len();
+    let video_path = &request.video_paths[file_idx];
+    let current_file = file_idx + 1;
+    let filename = Path::new(video_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| video_path.clone());
+
+    emit_file_progress(
+        app,
+        video_path,
+        current_file,
+        total_files,
+        0.0,
+        JobFileStage::Preparing,
+        format!("Processing {} ({}/{})", filename, current_file, total_files),
+    );
 
-        emit_job_progress(
-            &app,
-            current_file,
-            total_files,
-            progress(0.0),
-            format!("Processing {} ({}/{})", filename, current_file, total_files),
-        );
+    let started_at = chrono::Local::now();
+    let started = Instant::now();
+    let use_temporary_files = request.embed_subtitles;
+    let mut extracted_path: Option<String> = None;
+    let mut reference_path: Option<String> = None;
+    let mut translated_subtitle_path: Option<String> = None;
+    let mut report = JobFileReport {
+        video_path: video_path.clone(),
+        ..Default::default()
+    };
 
-        let use_temporary_files = request.embed_subtitles;
-        let mut extracted_path: Option<String> = None;
-        let mut translated_subtitle_path: Option<String> = None;
+    let control = app.state::<JobControl>();
+    let file_result: Result<TranslationJobOutput, AnimeSubsError> = async {
+        control.checkpoint().await?;
+        let video_info =
+            super::video::get_video_info(video_path.clone(), request.ffmpeg_path.clone()).await?;
 
-        let file_result: Result<TranslationJobOutput, String> = async {
-            let video_info =
-                super::video::get_video_info(video_path.clone(), request.ffmpeg_path.clone())
+        if request.skip_translated {
+            let track_index = if request.file_subtitles.contains_key(video_path) {
+                None
+            } else {
+                job_track_index(request, video_path, &video_info).ok()
+            };
+            if let Some(reason) =
+                existing_translation(request, video_path, &video_info, track_index)
+            {
+                info!("Skipping {}: {}", filename, reason);
+                return Ok(skipped_output(video_path, reason));
+            }
+        }
+
+        let (track_index, track_language, format, source_subtitle) =
+            match request.file_subtitles.get(video_path) {
+                Some(subtitle_path) => {
+                    let format = Path::new(subtitle_path)
+                        .extension()
+                        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+                        .unwrap_or_default();
+                    report.track_title = Path::new(subtitle_path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string());
+                    (None, None, format, subtitle_path.clone())
+                }
+                None => {
+                    let track_index = job_track_index(request, video_path, &video_info)?;
+                    let track = video_info
+                        .subtitle_tracks
+                        .get(track_index as usize)
+                        .ok_or_else(|| format!("Track {} not found", track_index))?;
+                    report.subtitle_track = Some(track_index);
+                    report.track_language = track.language.clone();
+                    report.track_title = track.title.clone();
+                    let track_language = track.language.clone();
+
+                    let format = select_subtitle_format(&request.output_format, &track.codec);
+
+                    emit_file_progress(
+                        app,
+                        video_path,
+                        current_file,
+                        total_files,
+                        0.05,
+                        JobFileStage::Preparing,
+                        format!("Extracting subtitles from {}...", filename),
+                    );
+
+                    let extract_result = super::subtitle::extract_subtitle(
+                        app.clone(),
+                        video_path.clone(),
+                        track_index,
+                        None,
+                        Some(format.clone()),
+                        Some(use_temporary_files),
+                        request.ffmpeg_path.clone(),
+                        None,
+                    )
                     .await?;
 
-            let track_index = request.subtitle_track.unwrap_or(0);
-            let track = video_info
-                .subtitle_tracks
-                .get(track_index as usize)
-                .ok_or_else(|| format!("Track {} not found", track_index))?;
+                    if !extract_result.success {
+                        return Err(extract_result
+                            .error
+                            .unwrap_or_else(|| "Failed to extract subtitle track".to_string())
+                            .into());
+                    }
 
-            let format = select_subtitle_format(&request.output_format, &track.codec);
+                    let extracted = extract_result
+                        .output_path
+                        .ok_or_else(|| "Subtitle extraction returned no output path".to_string())?;
+                    extracted_path = Some(extracted.clone());
+                    (Some(track_index), track_language, format, extracted)
+                }
+            };
 
-            emit_job_progress(
-                &app,
-                current_file,
-                total_files,
-                progress(0.05),
-                format!("Extracting subtitles from {}...", filename),
-            );
+        emit_file_progress(
+            app,
+            video_path,
+            current_file,
+            total_files,
+            0.10,
+            JobFileStage::Preparing,
+            format!("Parsing subtitles from {}...", filename),
+        );
+
+        let mut subtitle_data = prepare_job_subtitle(
+            request,
+            super::subtitle::parse_subtitle_file(source_subtitle.clone()).await?,
+        );
+        if subtitle_data.lines.iter().all(|line| line.skip) {
+            return Err("No dialog lines found in extracted subtitle".into());
+        }
+
+        report.detected_language = super::language::detect_language(
+            subtitle_data
+                .lines
+                .iter()
+                .filter(|line| !line.skip)
+                .map(|line| line.text.as_str()),
+        );
+        if let Some(detected) = report.detected_language.as_deref() {
+            if request.skip_target_language
+                && super::language::is_language(detected, &request.target_lang)
+            {
+                info!(
+                    "Skipping {}: subtitles are already in {}",
+                    filename, detected
+                );
+                return Ok(skipped_output(
+                    video_path,
+                    format!("Subtitles are already in {}", detected),
+                ));
+            }
+        }
 
-            let extract_result = super::subtitle::extract_subtitle(
+        if let Some(reference_index) = request.reference_track.filter(|i| Some(*i) != track_index) {
+            let reference_track = video_info
+                .subtitle_tracks
+                .get(reference_index as usize)
+                .ok_or_else(|| format!("Reference track {} not found", reference_index))?;
+            let reference_format = select_subtitle_format("", &reference_track.codec);
+            let reference_result = super::subtitle::extract_subtitle(
+                app.clone(),
                 video_path.clone(),
-                track_index,
+                reference_index,
                 None,
-                Some(format.clone()),
-                Some(use_temporary_files),
+                Some(reference_format),
+                Some(true),
                 request.ffmpeg_path.clone(),
+                None,
             )
             .await?;
-
-            if !extract_result.success {
-                return Err(extract_result
+            let reference_file = reference_result.output_path.ok_or_else(|| {
+                reference_result
                     .error
-                    .unwrap_or_else(|| "Failed to extract subtitle track".to_string()));
-            }
+                    .unwrap_or_else(|| "Failed to extract reference track".to_string())
+            })?;
+            reference_path = Some(reference_file.clone());
 
-            let extracted = extract_result
-                .output_path
-                .ok_or_else(|| "Subtitle extraction returned no output path".to_string())?;
-            extracted_path = Some(extracted.clone());
+            let reference_data = super::subtitle::parse_subtitle_file(reference_file).await?;
+            subtitle_data = attach_reference_track(subtitle_data, reference_data).await?;
+        }
 
-            emit_job_progress(
-                &app,
-                current_file,
-                total_files,
-                progress(0.10),
-                format!("Parsing subtitles from {}...", filename),
-            );
+        emit_file_progress(
+            app,
+            video_path,
+            current_file,
+            total_files,
+            0.20,
+            JobFileStage::Translating,
+            format!(
+                "Translating {} ({} lines)...",
+                filename,
+                subtitle_data.line_count - subtitle_data.class_counts.skipped
+            ),
+        );
 
-            let subtitle_data = super::subtitle::parse_subtitle_file(extracted.clone()).await?;
-            if subtitle_data.lines.is_empty() {
-                return Err("No dialog lines found in extracted subtitle".to_string());
+        // An unset source language becomes the detected one when detection is sure.
+        let source_lang = match request.source_lang.as_str() {
+            "" | "auto" => report
+                .detected_language
+                .clone()
+                .unwrap_or_else(|| "auto".to_string()),
+            _ => request.source_lang.clone(),
+        };
+        let mut config = request.config.clone();
+        config.debug_log = debug_log.clone();
+        if config.series_context.is_none() {
+            if let Some(folder) = Path::new(video_path).parent() {
+                config.series_context =
+                    super::series::read_series_context(&folder.to_string_lossy())?
+                        .map(|context| context.context);
             }
-
-            emit_job_progress(
-                &app,
+        }
+        // Planned the way `translate_subtitles` plans them, for the report's tokens.
+        let repeats = repeated_lines(&config, &subtitle_data.lines);
+        let unique_lines: Vec<DialogLine> = subtitle_data
+            .lines
+            .iter()
+            .filter(|line| !line.skip && !repeats.contains_key(&line.index))
+            .cloned()
+            .collect();
+        let mut prompt_config = config.clone();
+        super::settings::apply_saved_llm_defaults(&mut prompt_config);
+        super::prompts::resolve_prompt_template(app, &mut prompt_config)?;
+        let batch_input_tokens = chain_max_input_tokens(&provider_chain(&prompt_config));
+        let batches =
+            plan_translation_batches(&unique_lines, prompt_config.batching, batch_input_tokens);
+        let target_lang_value = if request.target_lang.is_empty() {
+            track_language.as_deref().unwrap_or("und")
+        } else {
+            request.target_lang.as_str()
+        };
+        let filename_lang_code = sanitize_lang_code_for_filename(Some(target_lang_value));
+        let ffmpeg_lang_code = to_ffmpeg_lang_code(Some(target_lang_value));
+        let persistent_path = persistent_output_path(
+            video_path,
+            None,
+            request.naming_template.as_deref(),
+            &filename_lang_code,
+            track_index,
+            &format,
+        )?;
+
+        let (mut translated_data, stopped) = translate_subtitles_with(
+            app.clone(),
+            subtitle_data,
+            config.clone(),
+            source_lang.clone(),
+            request.target_lang.clone(),
+            request.rate_limit.clone(),
+            Some(limiters.clone()),
+            Some(control.inner()),
+        )
+        .await?;
+        if stopped {
+            // Keep the batches that finished next to the video instead of losing them.
+            let partial_path = partial_output_path(&persistent_path);
+            let saved = save_translated_subtitles(
+                translated_data,
+                Some(partial_path.clone()),
+                Some(source_subtitle.clone()),
+                Some(false),
+                None,
+                Some(request.target_lang.clone()),
+                request.output_encoding.clone(),
+                request.dialogue_style.clone(),
+                request.ass_metadata.clone(),
+            )
+            .await;
+            match saved {
+                Ok(result) if result.success => report.partial_path = Some(partial_path),
+                Ok(result) => warn!("Failed to save partial translation: {}", result.message),
+                Err(e) => warn!("Failed to save partial translation: {}", e),
+            }
+            return Err(AnimeSubsError::Cancelled);
+        }
+        if request.refine {
+            control.checkpoint().await?;
+            emit_file_progress(
+                app,
+                video_path,
                 current_file,
                 total_files,
-                progress(0.20),
-                format!(
-                    "Translating {} ({} lines)...",
-                    filename,
-                    subtitle_data.lines.len()
-                ),
+                0.60,
+                JobFileStage::Translating,
+                format!("Proofreading {}...", filename),
             );
-
-            let translated_data = translate_subtitles(
+            translated_data = refine_translations(
                 app.clone(),
-                subtitle_data,
-                request.config.clone(),
-                if request.source_lang.is_empty() {
-                    "auto".to_string()
-                } else {
-                    request.source_lang.clone()
-                },
+                translated_data,
+                config.clone(),
+                source_lang.clone(),
+                request.target_lang.clone(),
+            )
+            .await?;
+        }
+        let batch_providers = translated_data.batch_providers.clone();
+        let output_tokens: usize = translated_data
+            .lines
+            .iter()
+            .filter(|line| !line.skip && !repeats.contains_key(&line.index))
+            .map(|line| estimate_tokens(&line.text))
+            .sum();
+        (report.input_tokens, report.output_tokens) = batch_token_usage(
+            &prompt_config,
+            &batches,
+            &source_lang,
+            &request.target_lang,
+            output_tokens,
+            request.refine,
+        );
+        report.lines_total = translated_data.lines.len();
+        report.lines_skipped = translated_data.lines.iter().filter(|l| l.skip).count();
+        report.lines_failed = translated_data.failed_line_ids.len();
+        report.lines_deduplicated = translated_data.deduplicated_lines;
+        report.lines_translated = report
+            .lines_total
+            .saturating_sub(report.lines_skipped + report.lines_failed);
+        if request.honorific_second_pass && request.config.system_prompt == "honorifics" {
+            let (input_tokens, output_tokens) = super::honorifics::honorific_pass_tokens(
+                &prompt_config,
+                &translated_data,
+                &source_lang,
+                &request.target_lang,
+            );
+            report.input_tokens += input_tokens;
+            report.output_tokens += output_tokens;
+            translated_data = super::honorifics::fix_honorifics(
+                app.clone(),
+                translated_data,
+                config.clone(),
+                source_lang.clone(),
                 request.target_lang.clone(),
             )
             .await?;
+        }
+        report.estimated_cost = super::reports::estimate_cost(
+            &super::settings::current_settings().model_pricing,
+            &request.config.model,
+            &batch_providers,
+            report.input_tokens,
+            report.output_tokens,
+        );
+        if let Some(wrap) = &request.wrap {
+            super::wrapping::wrap_subtitle_data(&mut translated_data, wrap);
+        }
+        report.warnings =
+            super::validation::check_lines(&translated_data.lines, &ValidationOptions::default());
+
+        control.checkpoint().await?;
+        emit_file_progress(
+            app,
+            video_path,
+            current_file,
+            total_files,
+            0.80,
+            JobFileStage::Saving,
+            format!("Saving translated subtitles for {}...", filename),
+        );
 
-            let target_lang_value = if request.target_lang.is_empty() {
-                track.language.as_deref().unwrap_or("und")
+        let kept_data = request.keep_subtitle_data.then(|| translated_data.clone());
+        let save_result = save_translated_subtitles(
+            translated_data,
+            if use_temporary_files {
+                None
             } else {
-                request.target_lang.as_str()
-            };
-            let filename_lang_code = sanitize_lang_code_for_filename(Some(target_lang_value));
-            let ffmpeg_lang_code = to_ffmpeg_lang_code(Some(target_lang_value));
-            let persistent_path =
-                persistent_output_path(video_path, None, &filename_lang_code, track_index, &format);
+                Some(persistent_path)
+            },
+            Some(source_subtitle.clone()),
+            Some(use_temporary_files),
+            None,
+            Some(request.target_lang.clone()),
+            request.output_encoding.clone(),
+            request.dialogue_style.clone(),
+            request.ass_metadata.clone(),
+        )
+        .await?;
+
+        if !save_result.success {
+            return Err(save_result.message.into());
+        }
+
+        let saved_subtitle = save_result
+            .data
+            .ok_or_else(|| "Save returned no subtitle path".to_string())?;
+        translated_subtitle_path = Some(saved_subtitle.clone());
 
-            emit_job_progress(
-                &app,
+        let mut output_video_path: Option<String> = None;
+        if request.embed_subtitles {
+            emit_file_progress(
+                app,
+                video_path,
                 current_file,
                 total_files,
-                progress(0.80),
-                format!("Saving translated subtitles for {}...", filename),
+                0.90,
+                JobFileStage::Embedding,
+                format!("Embedding translated subtitles in {}...", filename),
             );
+            let _remux_permit = remux_lock
+                .acquire()
+                .await
+                .map_err(|e| format!("Remux queue closed: {}", e))?;
 
-            let save_result = save_translated_subtitles(
-                translated_data,
-                if use_temporary_files {
-                    None
-                } else {
-                    Some(persistent_path)
-                },
-                extracted_path.clone(),
-                Some(use_temporary_files),
+            let current_info =
+                super::video::get_video_info(video_path.clone(), request.ffmpeg_path.clone())
+                    .await?;
+            let translated_title = format!("Translated ({})", filename_lang_code);
+            let mut tracks_to_remove: Vec<u32> = current_info
+                .subtitle_tracks
+                .iter()
+                .filter(|t| {
+                    t.title.as_deref() == Some(translated_title.as_str())
+                        || t.title
+                            .as_deref()
+                            .map(|title| title.starts_with(TRANSLATED_TITLE_PREFIX))
+                            .unwrap_or(false)
+                        || (to_ffmpeg_lang_code(t.language.as_deref()) == ffmpeg_lang_code
+                            && Some(t.index) != track_index)
+                })
+                .map(|t| t.index)
+                .collect();
+            tracks_to_remove.sort_by(|a, b| b.cmp(a));
+
+            // Embed first so the source is only touched once under non-overwrite
+            // policies; the new track is appended, so the indices above stay valid.
+            let embed_result = super::embedding::embed_subtitle(
+                app.clone(),
+                video_path.clone(),
+                saved_subtitle,
+                Some(ffmpeg_lang_code),
+                Some(translated_title),
+                true,
+                request.ffmpeg_path.clone(),
+                Some(request.use_mkvmerge),
+                Some(request.output_policy.clone()),
+                None,
+                Some(EmbedTrackOptions {
+                    clear_existing_defaults: true,
+                    ..Default::default()
+                }),
             )
             .await?;
 
-            if !save_result.success {
-                return Err(save_result.message);
+            if !embed_result.success {
+                return Err(embed_result.message.into());
             }
+            let embedded_path = embed_result.data.unwrap_or_else(|| video_path.clone());
 
-            let saved_subtitle = save_result
-                .data
-                .ok_or_else(|| "Save returned no subtitle path".to_string())?;
-            translated_subtitle_path = Some(saved_subtitle.clone());
-
-            if request.embed_subtitles {
-                emit_job_progress(
-                    &app,
-                    current_file,
-                    total_files,
-                    progress(0.90),
-                    format!("Embedding translated subtitles in {}...", filename),
-                );
-
-                let current_info =
-                    super::video::get_video_info(video_path.clone(), request.ffmpeg_path.clone())
-                        .await?;
-                let translated_title = format!("Translated ({})", filename_lang_code);
-                let mut tracks_to_remove: Vec<u32> = current_info
-                    .subtitle_tracks
-                    .iter()
-                    .filter(|t| {
-                        t.title.as_deref() == Some(translated_title.as_str())
-                            || t.title
-                                .as_deref()
-                                .map(|title| title.starts_with("Translated ("))
-                                .unwrap_or(false)
-                            || (to_ffmpeg_lang_code(t.language.as_deref()) == ffmpeg_lang_code
-                                && t.index != track_index)
-                    })
-                    .map(|t| t.index)
-                    .collect();
-                tracks_to_remove.sort_by(|a, b| b.cmp(a));
-
-                for track_to_remove in tracks_to_remove {
-                    let remove_result = super::embedding::remove_subtitle_track(
-                        video_path.clone(),
-                        track_to_remove,
-                        request.ffmpeg_path.clone(),
-                    )
-                    .await?;
-                    if !remove_result.success {
-                        return Err(remove_result.message);
-                    }
-                }
-
-                let embed_result = super::embedding::embed_subtitle(
-                    video_path.clone(),
-                    saved_subtitle,
-                    Some(ffmpeg_lang_code),
-                    Some(translated_title),
-                    true,
+            for track_to_remove in tracks_to_remove {
+                let remove_result = super::embedding::remove_subtitle_track(
+                    app.clone(),
+                    embedded_path.clone(),
+                    track_to_remove,
                     request.ffmpeg_path.clone(),
-                    Some(request.use_mkvmerge),
+                    Some(OutputPolicy::Overwrite),
+                    None,
                 )
                 .await?;
-
-                if !embed_result.success {
-                    return Err(embed_result.message);
+                if !remove_result.success {
+                    return Err(remove_result.message.into());
                 }
             }
+            output_video_path = Some(embedded_path);
+        }
 
-            Ok(TranslationJobOutput {
-                video_path: video_path.clone(),
-                subtitle_path: if request.embed_subtitles {
-                    None
-                } else {
-                    translated_subtitle_path.clone()
-                },
-                embedded: request.embed_subtitles,
-            })
+        Ok(TranslationJobOutput {
+            video_path: video_path.clone(),
+            output_video_path,
+            subtitle_path: if request.embed_subtitles {
+                None
+            } else {
+                translated_subtitle_path.clone()
+            },
+            embedded: request.embed_subtitles,
+            batch_providers,
+            skipped: None,
+            subtitle_data: kept_data,
+        })
+    }
+    .await;
+
+    let skipped = matches!(&file_result, Ok(output) if output.skipped.is_some());
+    if use_temporary_files || skipped {
+        cleanup_generated_file(extracted_path.as_deref()).await;
+        cleanup_generated_file(translated_subtitle_path.as_deref()).await;
+    }
+    cleanup_generated_file(reference_path.as_deref()).await;
+
+    report.duration_ms = started.elapsed().as_millis() as u64;
+    match &file_result {
+        Ok(output) if output.skipped.is_some() => {
+            report.status = ProjectFileStatus::Skipped;
+            report.skip_reason = output.skipped.clone();
+        }
+        Ok(output) => {
+            report.status = ProjectFileStatus::Done;
+            report.output_path = output
+                .output_video_path
+                .clone()
+                .or(output.subtitle_path.clone());
         }
-        .await;
+        // Stopped files stay pending so the next run picks them up again.
+        Err(AnimeSubsError::Cancelled) => report.status = ProjectFileStatus::Pending,
+        Err(e) => {
+            report.status = ProjectFileStatus::Failed;
+            report.error = Some(e.to_string());
+        }
+    }
+    if let Some(output_path) = &report.output_path {
+        super::media_server::refresh_library(app, output_path).await;
+    }
+
+    super::history::record_operation(
+        app,
+        &HistoryEntry {
+            id: format!("job-{}-{}", started_at.timestamp_millis(), current_file),
+            operation: "translate".to_string(),
+            video_path: video_path.clone(),
+            started_at: started_at.to_rfc3339(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            outcome: match &file_result {
+                Ok(_) => HistoryOutcome::Success,
+                Err(AnimeSubsError::Cancelled) => HistoryOutcome::Cancelled,
+                Err(_) => HistoryOutcome::Failed,
+            },
+            message: file_result.as_ref().err().map(ToString::to_string),
+            parameters: serde_json::json!({
+                "provider": request.config.provider,
+                "model": request.config.model,
+                "sourceLang": request.source_lang,
+                "targetLang": request.target_lang,
+                "subtitleTrack": request.subtitle_track,
+                "embedSubtitles": request.embed_subtitles,
+                "output": file_result.as_ref().ok().and_then(|output| {
+                    output.output_video_path.clone().or(output.subtitle_path.clone())
+                }),
+            }),
+        },
+    );
 
-        if use_temporary_files {
-            cleanup_generated_file(extracted_path.as_deref()).await;
-            cleanup_generated_file(translated_subtitle_path.as_deref()).await;
+    match file_result {
+        Ok(output) => {
+            emit_file_progress(
+                app,
+                video_path,
+                current_file,
+                total_files,
+                1.0,
+                JobFileStage::Done,
+                format!("Finished {}", filename),
+            );
+            (report, Ok(output))
+        }
+        Err(AnimeSubsError::Cancelled) => {
+            emit_file_progress(
+                app,
+                video_path,
+                current_file,
+                total_files,
+                0.0,
+                JobFileStage::Cancelled,
+                format!("Stopped {}", filename),
+            );
+            (report, Err(AnimeSubsError::Cancelled))
+        }
+        Err(reason) => {
+            let failure = format!("{}: {}", filename, reason);
+            error!("{}", failure);
+            emit_file_progress(
+                app,
+                video_path,
+                current_file,
+                total_files,
+                1.0,
+                JobFileStage::Failed,
+                format!("Error in {}: {}", filename, reason),
+            );
+            (report, Err(failure.into()))
         }
+    }
+}
 
-        match file_result {
+#[tauri::command]
+pub async fn start_translation_job(
+    app: AppHandle,
+    mut request: TranslationJobRequest,
+) -> Result<TranslationJobResult, AnimeSubsError> {
+    let control = app.state::<JobControl>();
+    let _running = control.exclusive().await;
+    control.reset();
+    super::settings::apply_saved_job_defaults(&mut request);
+    let started_at = chrono::Local::now();
+    let total_files = request.video_paths.len();
+    let mut failures = Vec::new();
+    let mut outputs = Vec::new();
+    let mut completed_files = 0usize;
+    let mut cancelled = false;
+
+    if total_files == 0 {
+        return Err("No video files selected".into());
+    }
+
+    // A local server that is down fails the job up front, unless a fallback can
+    // take over; remote providers are not checked and count as available.
+    let mut unavailable: Option<String> = None;
+    for config in provider_chain(&request.config) {
+        let provider = config.provider.trim().to_ascii_lowercase();
+        if !super::connection::is_local_provider(&provider) {
+            unavailable = None;
+            break;
+        }
+        let message = match super::connection::check_provider_health(
+            provider,
+            config.endpoint.clone(),
+            Some(config.api_key.clone()),
+            Some(config.model.clone()),
+        )
+        .await
+        {
+            Ok(health) if health.reachable && health.model_available != Some(false) => {
+                unavailable = None;
+                break;
+            }
+            Ok(health) => health.message,
+            Err(e) => e.to_string(),
+        };
+        unavailable.get_or_insert(message);
+    }
+    if let Some(message) = unavailable {
+        return Err(message.into());
+    }
+
+    let debug_log = if request.debug_log {
+        let log = super::history::create_job_debug_log(&app)?;
+        log.add_secret(&request.config.api_key);
+        for fallback in &request.config.fallbacks {
+            log.add_secret(&fallback.api_key);
+        }
+        info!("Writing LLM debug log to {}", log.path().display());
+        Some(Arc::new(log))
+    } else {
+        None
+    };
+
+    // Each file runs as its own task, so one file waiting on ffmpeg does not hold
+    // up the others. Parsing and LLM calls overlap across files; remuxes are serialized.
+    let workers = request
+        .max_parallel_files
+        .unwrap_or(1)
+        .clamp(1, MAX_PARALLEL_FILES);
+    let limiters = chain_limiters(
+        provider_chain(&request.config).len(),
+        request.rate_limit.as_ref(),
+    );
+    let request = Arc::new(request);
+    let file_slots = Arc::new(Semaphore::new(workers));
+    let remux_lock = Arc::new(Semaphore::new(1));
+    let tasks: Vec<_> = (0..total_files)
+        .map(|file_idx| {
+            let (app, request, debug_log) = (app.clone(), request.clone(), debug_log.clone());
+            let (limiters, file_slots, remux_lock) =
+                (limiters.clone(), file_slots.clone(), remux_lock.clone());
+            tauri::async_runtime::spawn(async move {
+                let _slot = file_slots.acquire_owned().await;
+                process_job_file(&app, &request, debug_log, &limiters, &remux_lock, file_idx).await
+            })
+        })
+        .collect();
+    let mut file_reports = Vec::with_capacity(total_files);
+    for (file_idx, task) in tasks.into_iter().enumerate() {
+        let (file_report, result) = task.await.unwrap_or_else(|e| {
+            let error: AnimeSubsError = format!("File task failed: {}", e).into();
+            let report = JobFileReport {
+                video_path: request.video_paths[file_idx].clone(),
+                status: ProjectFileStatus::Failed,
+                error: Some(error.to_string()),
+                ..Default::default()
+            };
+            (report, Err(error))
+        });
+        file_reports.push(file_report);
+        match result {
             Ok(output) => {
                 completed_files += 1;
                 outputs.push(output);
-                emit_job_progress(
-                    &app,
-                    current_file,
-                    total_files,
-                    progress(1.0),
-                    format!("Finished {}", filename),
-                );
-            }
-            Err(reason) => {
-                let failure = format!("{}: {}", filename, reason);
-                eprintln!("{}", failure);
-                emit_job_progress(
-                    &app,
-                    current_file,
-                    total_files,
-                    progress(1.0),
-                    format!("Error in {}: {}", filename, reason),
-                );
-                failures.push(failure);
             }
+            Err(AnimeSubsError::Cancelled) => cancelled = true,
+            Err(failure) => failures.push(failure.to_string()),
         }
     }
 
-    let status = if failures.is_empty() {
+    let status = if cancelled {
+        format!(
+            "Translation stopped ({}/{} files finished)",
+            completed_files, total_files
+        )
+    } else if failures.is_empty() {
         "Translation complete!".to_string()
     } else if completed_files == 0 {
         format!("Translation failed: {}", failures[0])
@@ -868,12 +2643,29 @@ pub async fn start_translation_job(
         )
     };
     emit_job_progress(&app, total_files, total_files, 100.0, status);
+    // A stop or pause only ever applies to the job it was sent to.
+    control.reset();
+
+    let mut report = super::reports::build_job_report(&request, started_at, file_reports);
+    if let Err(e) =
+        super::reports::save_job_report(&app, request.project_path.as_deref(), &mut report)
+    {
+        report.path = None;
+        warn!("Failed to save job report: {}", e);
+    }
+    if let Some(project_path) = request.project_path.as_deref() {
+        if let Err(e) = super::project::record_job_results(&app, project_path, &report.files) {
+            warn!("Failed to update project {}: {}", project_path, e);
+        }
+    }
 
     Ok(TranslationJobResult {
         completed_files,
         total_files,
         failures,
         outputs,
+        report: Some(report),
+        cancelled,
     })
 }
 
@@ -890,6 +2682,11 @@ mod tests {
             end: end.to_string(),
             style: Some("Default".to_string()),
             name: None,
+            reference: None,
+            formatting: None,
+            event: AssEventKind::Dialogue,
+            classification: LineClass::Dialogue,
+            skip: false,
         }
     }
 
@@ -906,6 +2703,22 @@ mod tests {
         assert!(output.contains("2\n00:00:03,000 --> 00:00:04,000\nMundo"));
     }
 
+    #[test]
+    fn reconstruct_srt_reapplies_cue_formatting() {
+        let mut italic = line(0, "Que estranho", "How odd", "00:00:01,000", "00:00:02,000");
+        italic.formatting = Some(LineFormatting {
+            italic: true,
+            color: Some("#ffff00".to_string()),
+            ..LineFormatting::default()
+        });
+        let plain = line(1, "Mundo", "World", "00:00:03,000", "00:00:04,000");
+
+        let output = reconstruct_srt(&[italic, plain]);
+
+        assert!(output.contains("<font color=\"#ffff00\"><i>Que estranho</i></font>"));
+        assert!(output.contains("00:00:04,000\nMundo\n"));
+    }
+
     #[test]
     fn reconstruct_vtt_writes_webvtt_header() {
         let lines = vec![line(0, "Bonjour", "Hello", "00:00:01.000", "00:00:02.000")];
@@ -939,6 +2752,270 @@ Dialogue: 0,0:00:03.00,0:00:04.00,Signs,,0,0,0,,Shop sign
         assert!(output.contains("Shop sign"));
     }
 
+    #[test]
+    fn reconstruct_ass_replaces_the_text_field_of_reordered_events() {
+        let original = r#"[Events]
+Format: Layer, Start, End, Style, Text, Name
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,Hello,Alice
+"#;
+        let lines = vec![line(0, "Olá", "Hello", "0:00:01.00", "0:00:02.00")];
+
+        let output = reconstruct_ass(original, &lines);
+
+        assert!(output.contains("Dialogue: 0,0:00:01.00,0:00:02.00,Default,Olá,Alice"));
+    }
+
+    #[test]
+    fn reconstruct_ass_keeps_repeated_lines_apart() {
+        let original = r#"[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,Kurisu,0,0,0,,Yes.
+Comment: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,Timing note
+Dialogue: 0,0:00:03.00,0:00:04.00,Default,Okabe,0,0,0,,Yes.
+"#;
+        let mut note = line(1, "Timing note", "Timing note", "0:00:02.00", "0:00:03.00");
+        note.event = AssEventKind::Comment;
+        note.skip = true;
+        let mut lines = vec![
+            line(0, "Sim.", "Yes.", "0:00:01.00", "0:00:02.00"),
+            note,
+            line(2, "Pois é.", "Yes.", "0:00:03.00", "0:00:04.00"),
+        ];
+
+        let output = reconstruct_ass(original, &lines);
+
+        assert!(output.contains("Default,Kurisu,0,0,0,,Sim."));
+        assert!(output.contains("Default,Okabe,0,0,0,,Pois é."));
+
+        // After cleanup renumbers the lines, speakers still tell them apart.
+        lines.remove(1);
+        for (index, (line, name)) in lines.iter_mut().zip(["Kurisu", "Okabe"]).enumerate() {
+            line.index = index + 10;
+            line.name = Some(name.to_string());
+        }
+
+        let output = reconstruct_ass(original, &lines);
+
+        assert!(output.contains("Default,Kurisu,0,0,0,,Sim."));
+        assert!(output.contains("Default,Okabe,0,0,0,,Pois é."));
+    }
+
+    #[test]
+    fn updates_script_info_metadata() {
+        let content = "[Script Info]\nTitle: 日本語\nScriptType: v4.00+\n\n[Events]\n";
+        let metadata = AssMetadataOptions {
+            title: Some("Episode 1".to_string()),
+            credit: true,
+            language: None,
+            scaled_border_and_shadow: Some(true),
+        };
+
+        let output = update_script_info(content, &metadata, Some("pt-BR"));
+
+        assert_eq!(
+            output,
+            "[Script Info]\n; Translated by AnimeSubs\nTitle: Episode 1\nScriptType: v4.00+\n\
+             Language: pt-BR\nScaledBorderAndShadow: yes\n\n[Events]\n"
+        );
+        assert_eq!(
+            update_script_info(&output, &metadata, Some("pt-BR")),
+            output
+        );
+    }
+
+    #[test]
+    fn reconstruct_ass_leaves_skipped_lines_untouched() {
+        let original = r#"[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Dialogue: 0,0:00:01.00,0:00:02.00,OP,,0,0,0,,{\k20}Ka{\k30}ze
+Dialogue: 0,0:00:03.00,0:00:04.00,Default,,0,0,0,,Signs here
+"#;
+        let mut karaoke = line(0, "Kaze", "{\\k20}Ka{\\k30}ze", "0:00:01.00", "0:00:02.00");
+        karaoke.classification = LineClass::Karaoke;
+        karaoke.skip = true;
+        let lines = vec![
+            karaoke,
+            line(1, "Placas aqui", "Signs here", "0:00:03.00", "0:00:04.00"),
+        ];
+
+        let output = reconstruct_ass(original, &lines);
+
+        assert!(output.contains(",,{\\k20}Ka{\\k30}ze"));
+        assert!(output.contains(",,Placas aqui"));
+    }
+
+    #[test]
+    fn scene_batching_cuts_at_the_largest_gap() {
+        let dialog = vec![
+            line(0, "a", "a", "0:00:01.00", "0:00:02.00"),
+            line(1, "b", "b", "0:00:02.50", "0:00:03.00"),
+            line(2, "c", "c", "0:00:20.00", "0:00:21.00"),
+            line(3, "d", "d", "0:00:21.20", "0:00:22.00"),
+        ];
+        let gaps = scene_gaps(&dialog);
+        assert_eq!(gaps[0], 0.0);
+        assert!((gaps[2] - 17.0).abs() < 1e-9);
+
+        let lines: Vec<TranslationLine> = dialog
+            .iter()
+            .map(|l| TranslationLine {
+                id: l.index,
+                text: "x".repeat(MAX_COMPACTION_TOKENS * 2),
+                reference: None,
+                draft: None,
+                rejected: None,
+            })
+            .collect();
+        let per_line = batch_line_tokens(&lines[0]);
+        let ids = |chunks: &[Vec<TranslationLine>]| -> Vec<Vec<usize>> {
+            chunks
+                .iter()
+                .map(|c| c.iter().map(|l| l.id).collect())
+                .collect()
+        };
+
+        // Room for three lines: sequential fills the first chunk, scene stops at the gap.
+        let budget = per_line * 3;
+        assert_eq!(
+            ids(&plan_chunks(lines.clone(), budget)),
+            [vec![0, 1, 2], vec![3]]
+        );
+        assert_eq!(
+            ids(&plan_scene_chunks(lines, &gaps, budget)),
+            [vec![0, 1], vec![2, 3]]
+        );
+    }
+
+    #[test]
+    fn flags_translations_that_fail_sanity_checks() {
+        let check = |source: &str, translated: &str| sanity_issue(source, translated, true, 5.0);
+
+        assert_eq!(check("元気？", "How are you?"), None);
+        assert_eq!(check("元気？", "  "), Some(SanityIssue::Empty));
+        assert_eq!(
+            check("Good morning", "Good morning"),
+            Some(SanityIssue::Untranslated)
+        );
+        assert_eq!(check("Naruto!", "Naruto!"), None);
+        assert_eq!(
+            check("元気？", r#"{"id": 3, "text": "Fine"}"#),
+            Some(SanityIssue::JsonScaffolding)
+        );
+        assert_eq!(
+            check("元気？", "I'm sorry, but I cannot translate this."),
+            Some(SanityIssue::Refusal)
+        );
+        assert!(!SanityIssue::Refusal.is_fatal());
+        assert_eq!(
+            check("はい", &"yes ".repeat(20)),
+            Some(SanityIssue::TooLong)
+        );
+        assert_eq!(
+            sanity_issue("Good morning", "Good morning", false, 5.0),
+            None
+        );
+
+        assert!(languages_differ("ja", "pt-BR"));
+        assert!(!languages_differ("en", "eng"));
+    }
+
+    #[test]
+    fn reconciles_missing_duplicate_and_unknown_ids() {
+        let requested: Vec<TranslationLine> = (0..3)
+            .map(|id| TranslationLine {
+                id,
+                text: format!("line {}", id),
+                reference: None,
+                draft: None,
+                rejected: None,
+            })
+            .collect();
+        let translated = |id: usize, text: &str| TranslatedLine {
+            id,
+            text: text.to_string(),
+        };
+
+        let result = reconcile_translations(
+            &requested,
+            vec![
+                translated(0, "first"),
+                translated(0, "again"),
+                translated(7, "stray"),
+                translated(2, "third"),
+            ],
+        );
+        let accepted: Vec<(usize, &str)> = result
+            .accepted
+            .iter()
+            .map(|t| (t.id, t.text.as_str()))
+            .collect();
+        assert_eq!(accepted, [(0, "first"), (2, "third")]);
+        assert_eq!(result.missing, [1]);
+        assert_eq!(result.duplicates, [0]);
+        assert_eq!(result.unknown, [7]);
+    }
+
+    #[test]
+    fn sizes_batches_from_the_model_context() {
+        let mut config = LLMConfig {
+            provider: "ollama".to_string(),
+            model: "mystery-model".to_string(),
+            ..Default::default()
+        };
+        let prompt = system_prompt_tokens(&config);
+        assert!(prompt > 0);
+        assert_eq!(max_input_tokens(&config), LOCAL_CONTEXT_WINDOW - prompt);
+
+        config.model = "llama3.1:8b".to_string();
+        assert_eq!(max_input_tokens(&config), 128_000 - prompt);
+
+        // Other llama and gemma models on a hosted provider are not assumed to be small.
+        config.provider = "openrouter".to_string();
+        config.model = "google/gemma-3-27b-it".to_string();
+        assert_eq!(max_input_tokens(&config), DEFAULT_CONTEXT_WINDOW - prompt);
+
+        config.context_window = Some(4_096);
+        assert_eq!(max_input_tokens(&config), 4_096 - prompt);
+
+        config.batch_token_budget = Some(1_000);
+        assert_eq!(max_input_tokens(&config), 1_000);
+        assert_eq!(compaction_reserve(1_000), 250);
+        assert_eq!(compaction_reserve(83_200), MAX_COMPACTION_TOKENS);
+        let small = LLMConfig {
+            batch_token_budget: Some(600),
+            ..config.clone()
+        };
+        assert_eq!(chain_max_input_tokens(&[config.clone(), small]), 600);
+
+        // A line's translation is reserved next to it.
+        let line = TranslationLine {
+            id: 0,
+            text: "元気？".to_string(),
+            reference: None,
+            draft: None,
+            rejected: None,
+        };
+        assert!(batch_line_tokens(&line) > estimate_line_tokens(&line) * 2);
+    }
+
+    #[test]
+    fn aligns_reference_lines_by_time_overlap() {
+        let mut lines = vec![
+            line(0, "こんにちは", "こんにちは", "0:00:01.00", "0:00:03.00"),
+            line(1, "元気？", "元気？", "0:00:10.00", "0:00:12.00"),
+        ];
+        let reference = vec![
+            line(0, "Hello", "Hello", "00:00:01,100", "00:00:02,900"),
+            line(1, "Unrelated", "Unrelated", "00:00:02,950", "00:00:09,000"),
+        ];
+
+        let aligned = align_reference_lines(&mut lines, &reference);
+
+        assert_eq!(aligned, 1);
+        assert_eq!(lines[0].reference.as_deref(), Some("Hello"));
+        assert_eq!(lines[1].reference, None);
+    }
+
     #[test]
     fn helper_maps_language_codes_for_embedding_and_filenames() {
         assert_eq!(to_ffmpeg_lang_code(Some("pt-BR")), "por");
@@ -962,13 +3039,133 @@ Dialogue: 0,0:00:03.00,0:00:04.00,Signs,,0,0,0,,Shop sign
         let path = persistent_output_path(
             "/videos/Episode 01.mkv",
             Some("/tmp/animesubs-out"),
+            None,
             "por",
-            2,
+            Some(2),
             "srt",
-        );
+        )
+        .unwrap();
 
         assert!(path.starts_with("/tmp/animesubs-out/"));
         assert!(path.contains("Episode 01_por_"));
         assert!(path.ends_with("_track2.srt"));
+
+        let named = persistent_output_path(
+            "/videos/[Group] Show - 03.mkv",
+            None,
+            Some("{title} - E{episode:02}.{lang}.{ext}"),
+            "por",
+            Some(2),
+            "srt",
+        )
+        .unwrap();
+        assert_eq!(named, "/videos/Show - E03.por.srt");
+
+        let external =
+            persistent_output_path("/videos/Episode 01.mkv", None, None, "por", None, "ass")
+                .unwrap();
+        assert!(external.ends_with("_external.ass"));
+    }
+
+    #[test]
+    fn fallbacks_inherit_prompt_settings_and_are_recorded_per_batch() {
+        let config = LLMConfig {
+            provider: "gemini".to_string(),
+            model: "gemini-2.5-flash".to_string(),
+            system_prompt: "natural".to_string(),
+            series_context: Some("Space opera".to_string()),
+            fallbacks: vec![
+                LLMConfig {
+                    provider: "openrouter".to_string(),
+                    model: "qwen/qwen3-32b".to_string(),
+                    ..Default::default()
+                },
+                LLMConfig {
+                    provider: "ollama".to_string(),
+                    model: "qwen2.5".to_string(),
+                    system_prompt: "literal".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let chain = provider_chain(&config);
+        assert_eq!(chain.len(), 3);
+        assert!(chain.iter().all(|c| c.fallbacks.is_empty()));
+        assert_eq!(chain[1].system_prompt, "natural");
+        assert_eq!(chain[2].system_prompt, "literal");
+        assert_eq!(chain[2].series_context.as_deref(), Some("Space opera"));
+
+        let translated = |id: usize| TranslatedLine {
+            id,
+            text: "x".to_string(),
+        };
+        let results = vec![
+            (0, vec![translated(0)]),
+            (2, vec![translated(1), translated(2)]),
+        ];
+        assert_eq!(
+            batch_provider_entries(&chain, 4, &results),
+            [
+                BatchProvider {
+                    batch: 4,
+                    provider: "gemini".to_string(),
+                    model: "gemini-2.5-flash".to_string(),
+                    line_count: 1,
+                },
+                BatchProvider {
+                    batch: 4,
+                    provider: "ollama".to_string(),
+                    model: "qwen2.5".to_string(),
+                    line_count: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn repeats_keep_speakers_styles_and_sources_apart() {
+        let (start, end) = ("0:00:01.00", "0:00:02.00");
+        let mut lines = vec![
+            line(0, "Vamos embora!", "Get out of here!", start, end),
+            line(1, "Vamos embora!", "{\\i1}Get out of here!", start, end),
+            line(2, "Vamos embora!", "Get out of here now!", start, end),
+            line(3, "Vamos embora!", "Get out of here!", start, end),
+            line(4, "Vamos embora!", "Get out of here!", start, end),
+            line(5, "Vamos embora!", "Get out of here!", start, end),
+            line(6, "Sim.", "Yes.", start, end),
+            line(7, "Sim.", "Yes.", start, end),
+        ];
+        lines[3].name = Some("Rin".to_string());
+        lines[4].skip = true;
+        lines[5].style = Some("Sign".to_string());
+        let config = LLMConfig::default();
+
+        assert_eq!(repeated_lines(&config, &lines), HashMap::from([(1, 0)]));
+
+        let config = LLMConfig {
+            reuse_repeats: Some(false),
+            ..LLMConfig::default()
+        };
+        assert!(repeated_lines(&config, &lines).is_empty());
+    }
+
+    #[test]
+    fn finds_sidecars_from_earlier_jobs() {
+        let dir = std::env::temp_dir().join(format!("animesubs-sidecar-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("Episode 01.mkv");
+        let video_path = video.to_string_lossy().to_string();
+        let sidecar = dir.join("Episode 01_pt-br_20250101_120000_track0.ass");
+        fs::write(&sidecar, b"").unwrap();
+        fs::write(dir.join("Episode 01_en.srt"), b"").unwrap();
+
+        assert_eq!(
+            translated_sidecar(&video_path, None, "pt-br", None),
+            Some(sidecar)
+        );
+        assert_eq!(translated_sidecar(&video_path, None, "es", None), None);
+        assert_eq!(translated_sidecar(&video_path, None, "en", None), None);
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
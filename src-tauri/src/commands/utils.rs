@@ -1,58 +1,78 @@
+use crate::error::AnimeSubsError;
 use crate::models::*;
+use crate::providers::http::{self, http_client};
 use crate::utils::*;
-use reqwest::Client;
 use std::fs;
 use std::path::Path;
 use tauri::{AppHandle, Manager};
 
-#[cfg(unix)]
-use std::os::unix::fs::PermissionsExt;
-
-fn secrets_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("Failed to resolve app config directory: {}", e))?;
+/// Path of `file_name` inside the app config directory, which is created if missing.
+pub(crate) fn app_config_path(
+    app: &AppHandle,
+    file_name: &str,
+) -> Result<std::path::PathBuf, AnimeSubsError> {
+    let config_dir = app.path().app_config_dir().map_err(|e| {
+        AnimeSubsError::Io(format!("Failed to resolve app config directory: {}", e))
+    })?;
     fs::create_dir_all(&config_dir)
-        .map_err(|e| format!("Failed to create app config directory: {}", e))?;
-    Ok(config_dir.join("secrets.json"))
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create app config directory: {}", e)))?;
+    Ok(config_dir.join(file_name))
+}
+
+fn app_state_path(app: &AppHandle) -> Result<std::path::PathBuf, AnimeSubsError> {
+    app_config_path(app, "app_state.json")
+}
+
+const SECRET_STATE_KEYS: [&str; 2] = ["apiKey", "api_key"];
+
+/// Drops API keys from persisted GUI state; those live in the OS keyring only.
+fn strip_state_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for key in SECRET_STATE_KEYS {
+                map.remove(key);
+            }
+            map.values_mut().for_each(strip_state_secrets);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(strip_state_secrets),
+        _ => {}
+    }
 }
 
-fn read_secrets(app: &AppHandle) -> Result<serde_json::Map<String, serde_json::Value>, String> {
-    let path = secrets_path(app)?;
+#[tauri::command]
+pub async fn load_app_state(app: AppHandle) -> Result<serde_json::Value, AnimeSubsError> {
+    let path = app_state_path(&app)?;
     if !path.exists() {
-        return Ok(serde_json::Map::new());
+        return Ok(serde_json::Value::Null);
     }
 
-    let content =
-        fs::read_to_string(&path).map_err(|e| format!("Failed to read secrets file: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Failed to parse secrets file: {}", e))
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read app state: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse app state: {}", e)))
 }
 
-fn write_secrets(
-    app: &AppHandle,
-    secrets: &serde_json::Map<String, serde_json::Value>,
-) -> Result<(), String> {
-    let path = secrets_path(app)?;
-    let data = serde_json::to_string_pretty(secrets)
-        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
-    fs::write(&path, data).map_err(|e| format!("Failed to write secrets file: {}", e))?;
-
-    #[cfg(unix)]
-    {
-        let mut permissions = fs::metadata(&path)
-            .map_err(|e| format!("Failed to read secrets permissions: {}", e))?
-            .permissions();
-        permissions.set_mode(0o600);
-        fs::set_permissions(&path, permissions)
-            .map_err(|e| format!("Failed to restrict secrets permissions: {}", e))?;
-    }
+#[tauri::command]
+pub async fn save_app_state(
+    app: AppHandle,
+    mut state: serde_json::Value,
+) -> Result<OperationResult, AnimeSubsError> {
+    strip_state_secrets(&mut state);
+    let path = app_state_path(&app)?;
+    let data = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize app state: {}", e))?;
+    write_file_atomic(&path, data.as_bytes())?;
 
-    Ok(())
+    Ok(OperationResult {
+        success: true,
+        message: "App state saved".to_string(),
+        data: None,
+        verification: None,
+    })
 }
 
 #[tauri::command]
-pub async fn check_ffmpeg(ffmpeg_path: Option<String>) -> Result<OperationResult, String> {
+pub async fn check_ffmpeg(ffmpeg_path: Option<String>) -> Result<OperationResult, AnimeSubsError> {
     let ffmpeg = get_ffmpeg_path(ffmpeg_path);
 
     let result = create_command(&ffmpeg).arg("-version").output();
@@ -66,12 +86,14 @@ pub async fn check_ffmpeg(ffmpeg_path: Option<String>) -> Result<OperationResult
                 success: true,
                 message: first_line.to_string(),
                 data: Some(ffmpeg),
+                verification: None,
             })
         }
         _ => Ok(OperationResult {
             success: false,
             message: "FFmpeg not found. Please install FFmpeg or specify its path.".to_string(),
             data: None,
+            verification: None,
         }),
     }
 }
@@ -82,7 +104,7 @@ pub fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-pub async fn delete_file(file_path: String) -> Result<OperationResult, String> {
+pub async fn delete_file(file_path: String) -> Result<OperationResult, AnimeSubsError> {
     let path = Path::new(&file_path);
 
     if !path.exists() {
@@ -90,157 +112,231 @@ pub async fn delete_file(file_path: String) -> Result<OperationResult, String> {
             success: true,
             message: "File already removed".to_string(),
             data: Some(file_path),
+            verification: None,
         });
     }
 
-    fs::remove_file(path).map_err(|e| format!("Failed to delete file: {}", e))?;
+    fs::remove_file(path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to delete file: {}", e)))?;
 
     Ok(OperationResult {
         success: true,
         message: "File deleted successfully".to_string(),
         data: Some(file_path),
+        verification: None,
     })
 }
 
+/// Free space where a remux of `video_path` would be written under `output_policy`.
 #[tauri::command]
-pub async fn load_api_key(app: AppHandle, provider: String) -> Result<OperationResult, String> {
-    let secrets = read_secrets(&app)?;
-    let api_key = secrets
-        .get(&provider)
-        .and_then(|value| value.as_str())
-        .unwrap_or_default()
-        .to_string();
+pub async fn check_disk_space(
+    video_path: String,
+    output_policy: Option<OutputPolicy>,
+) -> Result<DiskSpaceCheck, AnimeSubsError> {
+    let target = resolve_remux_output_path(&video_path, &output_policy.unwrap_or_default());
+    check_disk_space_for(&video_path, target.parent().unwrap_or(Path::new(".")))
+}
 
-    Ok(OperationResult {
-        success: true,
-        message: "API key loaded".to_string(),
-        data: Some(api_key),
-    })
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ModelEntry {
+    pub label: String,
+    pub value: String,
 }
 
-#[tauri::command]
-pub async fn save_api_key(
-    app: AppHandle,
-    provider: String,
-    api_key: String,
-) -> Result<OperationResult, String> {
-    let mut secrets = read_secrets(&app)?;
-    if api_key.is_empty() {
-        secrets.remove(&provider);
-    } else {
-        secrets.insert(provider, serde_json::Value::String(api_key));
+const GEMINI_MODELS_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
+fn models_url(provider: &str, endpoint: &str, api_key: Option<&str>) -> String {
+    let base = endpoint
+        .trim()
+        .trim_end_matches('/')
+        .trim_end_matches("/chat/completions");
+    match provider {
+        // Gemini uses the native API for listing models
+        "gemini" => format!(
+            "{}?pageSize=1000&key={}",
+            GEMINI_MODELS_URL,
+            api_key.unwrap_or_default()
+        ),
+        "ollama" if !base.contains("/v1") => {
+            let base = base.trim_end_matches("/api/chat").trim_end_matches("/api");
+            format!("{}/api/tags", base)
+        }
+        _ => format!("{}/models", base),
     }
-    write_secrets(&app, &secrets)?;
+}
 
-    Ok(OperationResult {
-        success: true,
-        message: "API key saved".to_string(),
-        data: None,
-    })
+fn json_u64(item: &serde_json::Value, keys: &[&str]) -> Option<u64> {
+    keys.iter()
+        .find_map(|key| item.get(*key).and_then(|v| v.as_u64()))
 }
 
-#[derive(serde::Serialize, Clone, Debug)]
-pub struct ModelEntry {
-    pub label: String,
-    pub value: String,
+fn parse_model_list(data: &serde_json::Value) -> Vec<ProviderModel> {
+    let mut models = Vec::new();
+
+    // OpenAI-compatible format (OpenAI, OpenRouter, LM Studio, vLLM): { "data": [{ "id": "..." }] }
+    if let Some(arr) = data.get("data").and_then(|v| v.as_array()) {
+        for item in arr {
+            let Some(id) = item.get("id").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let name = item.get("name").and_then(|v| v.as_str()).unwrap_or(id);
+            models.push(ProviderModel {
+                id: id.to_string(),
+                name: name.to_string(),
+                context_length: json_u64(
+                    item,
+                    &[
+                        "context_length",
+                        "max_context_length",
+                        "context_window",
+                        "max_model_len",
+                    ],
+                ),
+            });
+        }
+    }
+    // Ollama tags ({ "name": "llama3:8b" }) and Gemini ({ "name": "models/gemini-..." })
+    else if let Some(arr) = data.get("models").and_then(|v| v.as_array()) {
+        for item in arr {
+            let generates = item
+                .get("supportedGenerationMethods")
+                .and_then(|v| v.as_array())
+                .is_none_or(|methods| methods.iter().any(|m| m == "generateContent"));
+            let name = item
+                .get("name")
+                .or_else(|| item.get("model"))
+                .and_then(|v| v.as_str());
+            let Some(name) = name.filter(|_| generates) else {
+                continue;
+            };
+            let id = name.strip_prefix("models/").unwrap_or(name);
+            let display_name = item
+                .get("displayName")
+                .and_then(|v| v.as_str())
+                .unwrap_or(id);
+            models.push(ProviderModel {
+                id: id.to_string(),
+                name: display_name.to_string(),
+                context_length: json_u64(item, &["inputTokenLimit"]),
+            });
+        }
+    }
+
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    models.dedup_by(|a, b| a.id == b.id);
+    models
 }
 
+/// Queries the provider's model listing endpoint so the GUI can offer a dropdown.
 #[tauri::command]
-pub async fn fetch_models(
+pub async fn list_models(
+    provider: String,
     endpoint: String,
     api_key: Option<String>,
-    provider: Option<String>,
-) -> Result<Vec<ModelEntry>, String> {
-    let client = Client::new();
-    let provider = provider
-        .unwrap_or_default()
-        .trim()
-        .to_ascii_lowercase();
-
-    // Gemini uses the native API for listing models
-    let (url, use_bearer) = if provider == "gemini" {
-        let key = api_key.as_deref().unwrap_or_default();
-        (
-            format!(
-                "https://generativelanguage.googleapis.com/v1beta/models?key={}",
-                key
-            ),
-            false,
-        )
-    } else {
-        let base = endpoint.trim_end_matches('/');
-        (format!("{}/models", base), true)
-    };
-
-    let mut request = client.get(&url);
-
-    if use_bearer {
+) -> Result<Vec<ProviderModel>, AnimeSubsError> {
+    let provider = provider.trim().to_ascii_lowercase();
+    let api_key = api_key.filter(|key| !key.is_empty());
+    let url = models_url(&provider, &endpoint, api_key.as_deref());
+
+    let mut request = http_client()?.get(&url);
+    if provider != "gemini" {
         if let Some(ref key) = api_key {
-            if !key.is_empty() {
-                request = request.header("Authorization", format!("Bearer {}", key));
-            }
+            request = request.header("Authorization", format!("Bearer {}", key));
         }
     }
 
     let response = request
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch models: {}", e))?;
+        .map_err(|e| AnimeSubsError::Provider(format!("Failed to fetch models: {}", e)))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Models API error ({}): {}", status, error_text));
+        return Err(http::status_error(
+            status,
+            None,
+            format!("Models API error ({}): {}", status, error_text),
+        ));
     }
 
     let data: serde_json::Value = response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse models response: {}", e))?;
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse models response: {}", e)))?;
 
-    let mut models: Vec<ModelEntry> = Vec::new();
+    Ok(parse_model_list(&data))
+}
 
-    // OpenAI / OpenAI-compatible format: { "data": [{ "id": "..." }] }
-    if let Some(arr) = data.get("data").and_then(|v| v.as_array()) {
-        for item in arr {
-            if let Some(id) = item.get("id").and_then(|v| v.as_str()) {
-                models.push(ModelEntry {
-                    label: id.to_string(),
-                    value: id.to_string(),
-                });
-            }
-        }
-    }
-    // Ollama format: { "models": [{ "name": "..." }] }
-    else if let Some(arr) = data.get("models").and_then(|v| v.as_array()) {
-        for item in arr {
-            let name = item
-                .get("name")
-                .or_else(|| item.get("model"))
-                .and_then(|v| v.as_str());
-            if let Some(name) = name {
-                models.push(ModelEntry {
-                    label: name.to_string(),
-                    value: name.to_string(),
-                });
-            }
-        }
+#[tauri::command]
+pub async fn fetch_models(
+    endpoint: String,
+    api_key: Option<String>,
+    provider: Option<String>,
+) -> Result<Vec<ModelEntry>, AnimeSubsError> {
+    let models = list_models(provider.unwrap_or_default(), endpoint, api_key).await?;
+    Ok(models
+        .into_iter()
+        .map(|model| ModelEntry {
+            label: model.id.clone(),
+            value: model.id,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_provider_model_urls() {
+        assert_eq!(
+            models_url("ollama", "http://localhost:11434/api/chat", None),
+            "http://localhost:11434/api/tags"
+        );
+        assert_eq!(
+            models_url("lmstudio", "http://localhost:1234/v1/", None),
+            "http://localhost:1234/v1/models"
+        );
+        assert_eq!(
+            models_url("vllm", "http://localhost:8000/v1/chat/completions", None),
+            "http://localhost:8000/v1/models"
+        );
+        assert!(models_url("gemini", "", Some("k")).ends_with("&key=k"));
     }
-    // Gemini format: array of { "name": "models/gemini-..." }
-    else if let Some(arr) = data.as_array() {
-        for item in arr {
-            if let Some(name) = item.get("name").and_then(|v| v.as_str()) {
-                if name.contains("gemini") {
-                    let clean = name.strip_prefix("models/").unwrap_or(name);
-                    models.push(ModelEntry {
-                        label: clean.to_string(),
-                        value: clean.to_string(),
-                    });
-                }
+
+    #[test]
+    fn parses_openrouter_ollama_and_gemini_listings() {
+        let openrouter = serde_json::json!({
+            "data": [{ "id": "qwen/qwen3-32b", "name": "Qwen3 32B", "context_length": 40960 }]
+        });
+        let models = parse_model_list(&openrouter);
+        assert_eq!(models[0].name, "Qwen3 32B");
+        assert_eq!(models[0].context_length, Some(40960));
+
+        let vllm = serde_json::json!({
+            "data": [{ "id": "Qwen/Qwen2.5-7B-Instruct", "max_model_len": 32768 }]
+        });
+        assert_eq!(parse_model_list(&vllm)[0].context_length, Some(32768));
+
+        let ollama = serde_json::json!({ "models": [{ "name": "llama3:8b" }] });
+        assert_eq!(parse_model_list(&ollama)[0].id, "llama3:8b");
+
+        let gemini = serde_json::json!({ "models": [
+            {
+                "name": "models/gemini-2.5-flash",
+                "displayName": "Gemini 2.5 Flash",
+                "inputTokenLimit": 1048576,
+                "supportedGenerationMethods": ["generateContent"]
+            },
+            {
+                "name": "models/text-embedding-004",
+                "supportedGenerationMethods": ["embedContent"]
             }
-        }
+        ]});
+        let models = parse_model_list(&gemini);
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "gemini-2.5-flash");
+        assert_eq!(models[0].context_length, Some(1048576));
     }
-
-    models.sort_by(|a, b| a.label.cmp(&b.label));
-    Ok(models)
 }
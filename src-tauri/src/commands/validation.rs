@@ -0,0 +1,179 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+
+const DEFAULT_MAX_CPS: f64 = 20.0;
+const DEFAULT_MAX_LINE_LENGTH: usize = 42;
+const DEFAULT_MIN_DURATION_MS: u64 = 700;
+
+fn visible_char_count(text: &str) -> usize {
+    text.chars().filter(|c| *c != '\n').count()
+}
+
+pub(crate) fn check_lines(
+    lines: &[DialogLine],
+    options: &ValidationOptions,
+) -> Vec<ValidationIssue> {
+    let max_cps = options.max_cps.unwrap_or(DEFAULT_MAX_CPS);
+    let max_line_length = options.max_line_length.unwrap_or(DEFAULT_MAX_LINE_LENGTH);
+    let min_duration = options.min_duration_ms.unwrap_or(DEFAULT_MIN_DURATION_MS) as f64 / 1000.0;
+
+    let mut issues = Vec::new();
+    let mut timed = Vec::new();
+
+    for line in lines.iter().filter(|line| !line.skip) {
+        let text = strip_ass_tags(&line.text);
+        let mut issue = |kind, message| {
+            issues.push(ValidationIssue {
+                line_index: line.index,
+                kind,
+                message,
+            })
+        };
+
+        for row in text.lines() {
+            let length = row.chars().count();
+            if length > max_line_length {
+                issue(
+                    ValidationIssueKind::LineLength,
+                    format!("Line has {} characters (max {})", length, max_line_length),
+                );
+            }
+        }
+
+        let (Some(start), Some(end)) = (
+            parse_subtitle_timestamp(&line.start),
+            parse_subtitle_timestamp(&line.end),
+        ) else {
+            issue(
+                ValidationIssueKind::InvalidTiming,
+                format!("Unreadable timestamps {} --> {}", line.start, line.end),
+            );
+            continue;
+        };
+
+        if end <= start {
+            issue(
+                ValidationIssueKind::InvalidTiming,
+                format!("End {} is not after start {}", line.end, line.start),
+            );
+            continue;
+        }
+
+        let duration = end - start;
+        if duration < min_duration {
+            issue(
+                ValidationIssueKind::ShortDuration,
+                format!(
+                    "Shown for {:.0} ms (min {:.0} ms)",
+                    duration * 1000.0,
+                    min_duration * 1000.0
+                ),
+            );
+        }
+
+        let cps = visible_char_count(&text) as f64 / duration;
+        if cps > max_cps {
+            issue(
+                ValidationIssueKind::ReadingSpeed,
+                format!("{:.1} characters per second (max {:.1})", cps, max_cps),
+            );
+        }
+
+        timed.push((start, end, line.index));
+    }
+
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+    for pair in timed.windows(2) {
+        let (_, previous_end, previous_index) = pair[0];
+        let (next_start, _, next_index) = pair[1];
+        if next_start < previous_end {
+            issues.push(ValidationIssue {
+                line_index: next_index,
+                kind: ValidationIssueKind::Overlap,
+                message: format!(
+                    "Overlaps line {} by {:.0} ms",
+                    previous_index,
+                    (previous_end - next_start) * 1000.0
+                ),
+            });
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.line_index);
+    issues
+}
+
+/// Runs reading-speed, line-length, duration and overlap checks so problems can be
+/// reviewed before a translation is saved.
+#[tauri::command]
+pub async fn validate_subtitle(
+    subtitle_data: SubtitleData,
+    options: Option<ValidationOptions>,
+) -> Result<ValidationReport, AnimeSubsError> {
+    let issues = check_lines(&subtitle_data.lines, &options.unwrap_or_default());
+
+    Ok(ValidationReport {
+        line_count: subtitle_data.lines.len(),
+        passed: issues.is_empty(),
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(index: usize, start: &str, end: &str, text: &str) -> DialogLine {
+        DialogLine {
+            index,
+            text: text.to_string(),
+            original_with_formatting: text.to_string(),
+            start: start.to_string(),
+            end: end.to_string(),
+            style: None,
+            name: None,
+            reference: None,
+            formatting: None,
+            event: AssEventKind::Dialogue,
+            classification: LineClass::Dialogue,
+            skip: false,
+        }
+    }
+
+    #[test]
+    fn reports_each_kind_of_problem() {
+        let lines = vec![
+            line(0, "00:00:01,000", "00:00:04,000", "Fine line."),
+            line(
+                1,
+                "00:00:03,500",
+                "00:00:03,900",
+                "Fast and short and overlapping",
+            ),
+            line(2, "00:00:06,000", "00:00:05,000", "Backwards"),
+            line(
+                3,
+                "00:00:10,000",
+                "00:00:15,000",
+                "This single row is definitely longer than forty-two characters",
+            ),
+        ];
+        let kinds: Vec<(usize, ValidationIssueKind)> =
+            check_lines(&lines, &ValidationOptions::default())
+                .into_iter()
+                .map(|issue| (issue.line_index, issue.kind))
+                .collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                (1, ValidationIssueKind::ShortDuration),
+                (1, ValidationIssueKind::ReadingSpeed),
+                (1, ValidationIssueKind::Overlap),
+                (2, ValidationIssueKind::InvalidTiming),
+                (3, ValidationIssueKind::LineLength),
+            ]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use super::operations::run_ffmpeg_with_progress;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::*;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Allowed duration drift between source and output, in seconds and as a share
+/// of the source duration; whichever is larger applies.
+const DURATION_TOLERANCE_SECONDS: f64 = 1.0;
+const DURATION_TOLERANCE_RATIO: f64 = 0.005;
+
+fn check(name: &str, passed: bool, detail: String) -> VerificationCheck {
+    VerificationCheck {
+        name: name.to_string(),
+        passed,
+        detail,
+    }
+}
+
+/// Compares the probed output with its source: video and audio streams must be
+/// unchanged, the subtitle count must match `expected_subtitles` and the duration
+/// must stay within tolerance.
+fn compare_layout(
+    source: &VideoInfo,
+    output: &VideoInfo,
+    expected_subtitles: usize,
+) -> Vec<VerificationCheck> {
+    let video = |info: &VideoInfo| -> Vec<String> {
+        info.video_streams.iter().map(|s| s.codec.clone()).collect()
+    };
+    let audio = |info: &VideoInfo| -> Vec<String> {
+        info.audio_tracks.iter().map(|t| t.codec.clone()).collect()
+    };
+
+    let mut checks = vec![
+        check(
+            "video",
+            video(source) == video(output),
+            format!("{:?} -> {:?}", video(source), video(output)),
+        ),
+        check(
+            "audio",
+            audio(source) == audio(output),
+            format!("{:?} -> {:?}", audio(source), audio(output)),
+        ),
+        check(
+            "subtitles",
+            output.subtitle_tracks.len() == expected_subtitles,
+            format!(
+                "{} subtitle tracks, expected {}",
+                output.subtitle_tracks.len(),
+                expected_subtitles
+            ),
+        ),
+    ];
+
+    if let (Some(expected), Some(actual)) = (source.duration, output.duration) {
+        let tolerance = (expected * DURATION_TOLERANCE_RATIO).max(DURATION_TOLERANCE_SECONDS);
+        checks.push(check(
+            "duration",
+            (expected - actual).abs() <= tolerance,
+            format!("{:.2}s, expected {:.2}s", actual, expected),
+        ));
+    }
+    checks
+}
+
+/// Probes `output` (and optionally decodes it completely) before it replaces
+/// `source_path`. `subtitle_delta` is the change in subtitle track count the
+/// operation was meant to make.
+pub(crate) async fn verify_remux_output(
+    app: &AppHandle,
+    source_path: &str,
+    output: &Path,
+    subtitle_delta: i64,
+    ffmpeg_path: Option<String>,
+    decode: bool,
+) -> RemuxVerification {
+    let output_path = output.to_string_lossy().to_string();
+    let source = super::video::get_video_info(source_path.to_string(), ffmpeg_path.clone()).await;
+    let probed = super::video::get_video_info(output_path.clone(), ffmpeg_path.clone()).await;
+
+    let mut checks = match (&source, &probed) {
+        (Ok(source), Ok(probed)) => {
+            let expected = (source.subtitle_tracks.len() as i64 + subtitle_delta).max(0) as usize;
+            let mut checks = vec![check("probe", true, "Output is readable".to_string())];
+            checks.extend(compare_layout(source, probed, expected));
+            checks
+        }
+        (Err(e), _) | (_, Err(e)) => vec![check("probe", false, e.to_string())],
+    };
+
+    if decode && checks.iter().all(|c| c.passed) {
+        let args = [
+            "-v",
+            "error",
+            "-i",
+            &ffmpeg_file(&output_path),
+            "-map",
+            "0:v?",
+            "-map",
+            "0:a?",
+            "-f",
+            "null",
+            "-",
+        ]
+        .map(String::from);
+        let duration = probed.as_ref().ok().and_then(|info| info.duration);
+        let result = run_ffmpeg_with_progress(
+            app,
+            &get_ffmpeg_path(ffmpeg_path),
+            &args,
+            "verify",
+            source_path,
+            duration,
+            Vec::new(),
+        );
+        checks.push(match result {
+            Ok(result) => {
+                let errors = String::from_utf8_lossy(&result.stderr).trim().to_string();
+                let passed = result.status.success() && errors.is_empty();
+                let detail = if passed {
+                    "Decoded without errors".to_string()
+                } else {
+                    errors
+                };
+                check("decode", passed, detail)
+            }
+            Err(e) => check("decode", false, format!("Failed to run ffmpeg: {}", e)),
+        });
+    }
+
+    RemuxVerification {
+        passed: checks.iter().all(|c| c.passed),
+        checks,
+    }
+}
+
+/// Summary for an OperationResult message when verification failed.
+fn failure_message(verification: &RemuxVerification) -> String {
+    let failed: Vec<String> = verification
+        .checks
+        .iter()
+        .filter(|c| !c.passed)
+        .map(|c| format!("{} ({})", c.name, c.detail))
+        .collect();
+    format!(
+        "Output verification failed, original left untouched: {}",
+        failed.join("; ")
+    )
+}
+
+/// Verifies a finished remux and only then moves it over the original (or to its
+/// policy destination). A failed check leaves the source untouched.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn finalize_verified_remux(
+    app: &AppHandle,
+    video_path: &str,
+    temp_output: &Path,
+    output_policy: &OutputPolicy,
+    subtitle_delta: i64,
+    ffmpeg_path: Option<String>,
+    decode: bool,
+    success_message: &str,
+) -> Result<OperationResult, AnimeSubsError> {
+    let verification = verify_remux_output(
+        app,
+        video_path,
+        temp_output,
+        subtitle_delta,
+        ffmpeg_path,
+        decode,
+    )
+    .await;
+    if !verification.passed {
+        return Ok(OperationResult {
+            success: false,
+            message: failure_message(&verification),
+            data: None,
+            verification: Some(verification),
+        });
+    }
+
+    let final_path = finalize_remux_output(temp_output, video_path, output_policy)?;
+    Ok(OperationResult {
+        success: true,
+        message: success_message.to_string(),
+        data: Some(final_path),
+        verification: Some(verification),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(subtitles: usize, duration: f64, audio_codec: &str) -> VideoInfo {
+        VideoInfo {
+            path: "v.mkv".to_string(),
+            filename: "v.mkv".to_string(),
+            duration: Some(duration),
+            subtitle_tracks: (0..subtitles as u32)
+                .map(|index| SubtitleTrack {
+                    index,
+                    stream_index: index + 2,
+                    codec: "ass".to_string(),
+                    language: None,
+                    title: None,
+                    default: false,
+                    forced: false,
+                    detected_language: None,
+                })
+                .collect(),
+            audio_tracks: vec![AudioTrack {
+                index: 0,
+                stream_index: 1,
+                codec: audio_codec.to_string(),
+                language: None,
+                title: None,
+                channels: Some(2),
+                default: true,
+            }],
+            video_streams: vec![VideoStream {
+                index: 0,
+                stream_index: 0,
+                codec: "hevc".to_string(),
+                width: Some(1920),
+                height: Some(1080),
+                default: true,
+            }],
+            stream_count: subtitles as u32 + 2,
+        }
+    }
+
+    #[test]
+    fn compares_remuxed_layout_with_source() {
+        let source = info(1, 1420.0, "aac");
+        let checks = compare_layout(&source, &info(2, 1420.4, "aac"), 2);
+        assert!(checks.iter().all(|c| c.passed));
+
+        let failed: Vec<String> = compare_layout(&source, &info(1, 700.0, "opus"), 2)
+            .into_iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(failed, ["audio", "subtitles", "duration"]);
+    }
+}
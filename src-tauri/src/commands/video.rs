@@ -1,16 +1,23 @@
+use super::sidecar::{pair_sidecars, SIDECAR_EXTENSIONS};
+use crate::error::AnimeSubsError;
 use crate::models::*;
 use crate::utils::*;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Extensions listed as videos by folder scans and the folder watch.
+pub(crate) const VIDEO_EXTENSIONS: [&str; 8] =
+    ["mkv", "mp4", "webm", "avi", "mov", "wmv", "flv", "m4v"];
 
 #[tauri::command]
 pub async fn get_video_info(
     video_path: String,
     ffmpeg_path: Option<String>,
-) -> Result<VideoInfo, String> {
+) -> Result<VideoInfo, AnimeSubsError> {
     let ffprobe = get_ffprobe_path(ffmpeg_path);
 
-    let output = create_command(&ffprobe)
+    let mut command = create_command(&ffprobe);
+    command
         .args([
             "-v",
             "quiet",
@@ -18,45 +25,80 @@ pub async fn get_video_info(
             "json",
             "-show_format",
             "-show_streams",
-            &video_path,
         ])
+        .arg(ffmpeg_file_arg(&video_path));
+    let output = tokio::process::Command::from(command)
         .output()
-        .map_err(|e| format!("Failed to run ffprobe: {}. Is FFmpeg installed?", e))?;
+        .await
+        .map_err(|e| {
+            AnimeSubsError::from_tool(
+                &e,
+                format!("Failed to run ffprobe: {}. Is FFmpeg installed?", e),
+            )
+        })?;
 
     if !output.status.success() {
         return Err(format!(
             "ffprobe failed: {}",
             String::from_utf8_lossy(&output.stderr)
-        ));
+        )
+        .into());
     }
 
     let json_str = String::from_utf8_lossy(&output.stdout);
     let json: serde_json::Value = serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse ffprobe output: {}", e))?;
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse ffprobe output: {}", e)))?;
 
     let streams = json["streams"]
         .as_array()
         .ok_or("No streams found in video")?;
 
     let mut subtitle_tracks: Vec<SubtitleTrack> = Vec::new();
+    let mut audio_tracks: Vec<AudioTrack> = Vec::new();
+    let mut video_streams: Vec<VideoStream> = Vec::new();
     let mut sub_index = 0u32;
 
     for stream in streams {
-        if stream["codec_type"].as_str() == Some("subtitle") {
-            let tags = &stream["tags"];
-            subtitle_tracks.push(SubtitleTrack {
-                index: sub_index,
-                stream_index: stream["index"].as_u64().unwrap_or(0) as u32,
-                codec: stream["codec_name"]
-                    .as_str()
-                    .unwrap_or("unknown")
-                    .to_string(),
+        let tags = &stream["tags"];
+        let stream_index = stream["index"].as_u64().unwrap_or(0) as u32;
+        let codec = stream["codec_name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string();
+        let default = stream["disposition"]["default"].as_i64() == Some(1);
+
+        match stream["codec_type"].as_str() {
+            Some("subtitle") => {
+                subtitle_tracks.push(SubtitleTrack {
+                    index: sub_index,
+                    stream_index,
+                    codec,
+                    language: tags["language"].as_str().map(String::from),
+                    title: tags["title"].as_str().map(String::from),
+                    default,
+                    forced: stream["disposition"]["forced"].as_i64() == Some(1),
+                    detected_language: None,
+                });
+                sub_index += 1;
+            }
+            Some("audio") => audio_tracks.push(AudioTrack {
+                index: audio_tracks.len() as u32,
+                stream_index,
+                codec,
                 language: tags["language"].as_str().map(String::from),
                 title: tags["title"].as_str().map(String::from),
-                default: stream["disposition"]["default"].as_i64() == Some(1),
-                forced: stream["disposition"]["forced"].as_i64() == Some(1),
-            });
-            sub_index += 1;
+                channels: stream["channels"].as_u64().map(|c| c as u32),
+                default,
+            }),
+            Some("video") => video_streams.push(VideoStream {
+                index: video_streams.len() as u32,
+                stream_index,
+                codec,
+                width: stream["width"].as_u64().map(|w| w as u32),
+                height: stream["height"].as_u64().map(|h| h as u32),
+                default,
+            }),
+            _ => {}
         }
     }
 
@@ -74,29 +116,44 @@ pub async fn get_video_info(
         filename,
         duration,
         subtitle_tracks,
+        audio_tracks,
+        video_streams,
+        stream_count: streams.len() as u32,
     })
 }
 
+/// Lists the videos in a folder together with loose subtitle files named after them.
 #[tauri::command]
-pub async fn scan_folder_for_videos(folder_path: String) -> Result<Vec<String>, String> {
-    let video_extensions = ["mkv", "mp4", "webm", "avi", "mov", "wmv", "flv", "m4v"];
-    let mut videos: Vec<String> = Vec::new();
+pub async fn scan_folder_for_videos(
+    folder_path: String,
+) -> Result<Vec<ScannedVideo>, AnimeSubsError> {
+    let mut videos: Vec<PathBuf> = Vec::new();
+    let mut subtitles: Vec<PathBuf> = Vec::new();
 
-    let entries =
-        fs::read_dir(&folder_path).map_err(|e| format!("Failed to read directory: {}", e))?;
+    let entries = fs::read_dir(&folder_path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read directory: {}", e)))?;
 
     for entry in entries.flatten() {
         let path = entry.path();
         if path.is_file() {
             if let Some(ext) = path.extension() {
                 let ext_str = ext.to_string_lossy().to_lowercase();
-                if video_extensions.contains(&ext_str.as_str()) {
-                    videos.push(path.to_string_lossy().to_string());
+                if VIDEO_EXTENSIONS.contains(&ext_str.as_str()) {
+                    videos.push(path);
+                } else if SIDECAR_EXTENSIONS.contains(&ext_str.as_str()) {
+                    subtitles.push(path);
                 }
             }
         }
     }
 
     videos.sort();
-    Ok(videos)
+    let mut sidecars = pair_sidecars(&videos, &subtitles);
+    Ok(videos
+        .into_iter()
+        .map(|video| ScannedVideo {
+            sidecars: sidecars.remove(&video).unwrap_or_default(),
+            path: video.to_string_lossy().to_string(),
+        })
+        .collect())
 }
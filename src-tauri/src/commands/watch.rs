@@ -0,0 +1,349 @@
+use super::automation::enqueue_with_preset;
+use super::presets::read_presets;
+use super::utils::app_config_path;
+use super::video::VIDEO_EXTENSIONS;
+use crate::error::AnimeSubsError;
+use crate::models::*;
+use crate::utils::write_file_atomic;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+const WATCH_FILE: &str = "watch.json";
+/// How often the size of files still being copied is checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// A new file counts as fully copied once its size has not changed for this long.
+const SETTLE_TIME: Duration = Duration::from_secs(10);
+/// Suffix of the temporary remux output `embed_subtitles` writes next to a video.
+const REMUX_SUFFIX: &str = "_with_subs";
+
+/// The running folder watch; dropping the watcher stops it.
+#[derive(Default)]
+pub struct FolderWatch {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+/// A new file waiting for its copy to finish.
+struct Pending {
+    size: u64,
+    since: Instant,
+}
+
+fn read_watch_settings(app: &AppHandle) -> Result<WatchSettings, AnimeSubsError> {
+    let path = app_config_path(app, WATCH_FILE)?;
+    if !path.exists() {
+        return Ok(WatchSettings::default());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read watch settings: {}", e)))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AnimeSubsError::parse(format!("Failed to parse watch settings: {}", e)))
+}
+
+fn write_watch_settings(app: &AppHandle, settings: &WatchSettings) -> Result<(), AnimeSubsError> {
+    let data = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize watch settings: {}", e))?;
+    write_file_atomic(&app_config_path(app, WATCH_FILE)?, data.as_bytes())
+}
+
+fn is_running(app: &AppHandle) -> bool {
+    let state = app.state::<FolderWatch>();
+    let watcher = state.watcher.lock().unwrap_or_else(|e| e.into_inner());
+    watcher.is_some()
+}
+
+/// Videos only; our own remux output would otherwise be queued again.
+fn is_new_video(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+    VIDEO_EXTENSIONS.contains(&extension.as_str()) && !stem.ends_with(REMUX_SUFFIX)
+}
+
+/// The watched folder `path` belongs to; the deepest one wins when folders are
+/// nested.
+fn folder_for<'a>(path: &Path, folders: &'a [WatchFolder]) -> Option<&'a WatchFolder> {
+    folders
+        .iter()
+        .filter(|folder| {
+            let root = Path::new(&folder.path);
+            if folder.recursive {
+                path.starts_with(root)
+            } else {
+                path.parent() == Some(root)
+            }
+        })
+        .max_by_key(|folder| folder.path.len())
+}
+
+/// Removes and returns the pending files whose size stayed the same for
+/// `SETTLE_TIME`. Files that disappeared are dropped.
+fn take_settled(
+    pending: &mut HashMap<PathBuf, Pending>,
+    now: Instant,
+    size_of: impl Fn(&Path) -> Option<u64>,
+) -> Vec<PathBuf> {
+    let mut settled = Vec::new();
+    pending.retain(|path, file| {
+        let Some(size) = size_of(path) else {
+            return false;
+        };
+        if size != file.size {
+            file.size = size;
+            file.since = now;
+            return true;
+        }
+        if now.duration_since(file.since) < SETTLE_TIME {
+            return true;
+        }
+        settled.push(path.clone());
+        false
+    });
+    settled.sort();
+    settled
+}
+
+fn file_size(path: &Path) -> Option<u64> {
+    fs::metadata(path).ok().map(|metadata| metadata.len())
+}
+
+/// Collects new videos from watcher events and queues each once its copy has
+/// finished. Ends when the watcher is dropped.
+async fn settle_new_files(
+    app: AppHandle,
+    folders: Vec<WatchFolder>,
+    mut events: mpsc::UnboundedReceiver<Event>,
+) {
+    let mut pending: HashMap<PathBuf, Pending> = HashMap::new();
+    // Files already queued; translating them rewrites the video in place.
+    let mut queued: HashSet<PathBuf> = HashSet::new();
+    let mut ticks = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                    continue;
+                }
+                for path in event.paths {
+                    if queued.contains(&path) || !is_new_video(&path) {
+                        continue;
+                    }
+                    let size = file_size(&path).unwrap_or_default();
+                    pending.entry(path).or_insert(Pending {
+                        size,
+                        since: Instant::now(),
+                    });
+                }
+            }
+            _ = ticks.tick() => {
+                for path in take_settled(&mut pending, Instant::now(), file_size) {
+                    let Some(folder) = folder_for(&path, &folders) else {
+                        continue;
+                    };
+                    let video = path.to_string_lossy().to_string();
+                    if enqueue_with_preset(&app, &[video.clone()], &folder.preset).is_empty() {
+                        continue;
+                    }
+                    info!("Folder watch queued {} with preset '{}'", video, folder.preset);
+                    queued.insert(path);
+                }
+            }
+        }
+    }
+}
+
+fn start_watch(app: &AppHandle, folders: &[WatchFolder]) -> Result<(), AnimeSubsError> {
+    if folders.is_empty() {
+        return Err("No folders to watch".into());
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                let _ = sender.send(event);
+            }
+            Err(e) => warn!("Folder watch error: {}", e),
+        })
+        .map_err(|e| format!("Failed to start folder watch: {}", e))?;
+    for folder in folders {
+        let mode = if folder.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher
+            .watch(Path::new(&folder.path), mode)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to watch {}: {}", folder.path, e)))?;
+    }
+
+    tauri::async_runtime::spawn(settle_new_files(app.clone(), folders.to_vec(), receiver));
+    let state = app.state::<FolderWatch>();
+    *state.watcher.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
+    info!("Watching {} folders for new videos", folders.len());
+    Ok(())
+}
+
+fn stop_watch(app: &AppHandle) {
+    let state = app.state::<FolderWatch>();
+    if state
+        .watcher
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .is_some()
+    {
+        info!("Folder watch stopped");
+    }
+}
+
+fn watch_status(app: &AppHandle) -> Result<WatchSettings, AnimeSubsError> {
+    Ok(WatchSettings {
+        active: is_running(app),
+        ..read_watch_settings(app)?
+    })
+}
+
+/// Restarts the folder watch left running when the app closed.
+pub(crate) fn start_saved_watch(app: &AppHandle) -> Result<(), AnimeSubsError> {
+    let settings = read_watch_settings(app)?;
+    if settings.active {
+        start_watch(app, &settings.folders)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_folder_watch(app: AppHandle) -> Result<WatchSettings, AnimeSubsError> {
+    watch_status(&app)
+}
+
+/// Saves the watch list; a running watch switches to the new folders.
+#[tauri::command]
+pub async fn save_watch_folders(
+    app: AppHandle,
+    folders: Vec<WatchFolder>,
+) -> Result<WatchSettings, AnimeSubsError> {
+    let presets = read_presets(&app)?;
+    for folder in &folders {
+        if !Path::new(&folder.path).is_dir() {
+            return Err(AnimeSubsError::Io(format!(
+                "Folder not found: {}",
+                folder.path
+            )));
+        }
+        if !presets.iter().any(|preset| preset.name == folder.preset) {
+            return Err(format!("Preset not found: {}", folder.preset).into());
+        }
+    }
+
+    let mut settings = read_watch_settings(&app)?;
+    settings.folders = folders;
+    if is_running(&app) {
+        stop_watch(&app);
+        if !settings.folders.is_empty() {
+            start_watch(&app, &settings.folders)?;
+        }
+    }
+    settings.active = is_running(&app);
+    write_watch_settings(&app, &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn start_folder_watch(app: AppHandle) -> Result<WatchSettings, AnimeSubsError> {
+    let mut settings = read_watch_settings(&app)?;
+    stop_watch(&app);
+    start_watch(&app, &settings.folders)?;
+    settings.active = true;
+    write_watch_settings(&app, &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn stop_folder_watch(app: AppHandle) -> Result<WatchSettings, AnimeSubsError> {
+    let mut settings = read_watch_settings(&app)?;
+    stop_watch(&app);
+    settings.active = false;
+    write_watch_settings(&app, &settings)?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder(path: &str, preset: &str, recursive: bool) -> WatchFolder {
+        WatchFolder {
+            path: path.to_string(),
+            preset: preset.to_string(),
+            recursive,
+        }
+    }
+
+    #[test]
+    fn queues_files_once_their_size_settles() {
+        let start = Instant::now();
+        let path = PathBuf::from("/downloads/ep01.mkv");
+        let mut pending = HashMap::from([(
+            path.clone(),
+            Pending {
+                size: 100,
+                since: start,
+            },
+        )]);
+
+        let later = start + SETTLE_TIME;
+        assert!(take_settled(&mut pending, later, |_| Some(200)).is_empty());
+        assert!(take_settled(&mut pending, later + POLL_INTERVAL, |_| Some(200)).is_empty());
+        assert_eq!(
+            take_settled(&mut pending, later + SETTLE_TIME, |_| Some(200)),
+            [path.clone()]
+        );
+        assert!(pending.is_empty());
+
+        pending.insert(
+            path,
+            Pending {
+                size: 100,
+                since: start,
+            },
+        );
+        assert!(take_settled(&mut pending, later, |_| None).is_empty());
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn matches_videos_to_the_deepest_watched_folder() {
+        let folders = [
+            folder("/media", "default", true),
+            folder("/media/anime", "anime", true),
+            folder("/downloads", "quick", false),
+        ];
+        let preset = |path: &str| folder_for(Path::new(path), &folders).map(|f| f.preset.as_str());
+
+        assert_eq!(preset("/media/anime/Show/ep01.mkv"), Some("anime"));
+        assert_eq!(preset("/media/movies/film.mkv"), Some("default"));
+        assert_eq!(preset("/downloads/ep01.mkv"), Some("quick"));
+        assert_eq!(preset("/downloads/Show/ep01.mkv"), None);
+
+        assert!(is_new_video(Path::new("/downloads/ep01.MKV")));
+        assert!(!is_new_video(Path::new("/downloads/ep01_with_subs.mkv")));
+        assert!(!is_new_video(Path::new("/downloads/ep01.srt")));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::error::AnimeSubsError;
+use crate::models::*;
+
+const DEFAULT_MAX_LINE_LENGTH: usize = 42;
+const DEFAULT_MAX_LINES: usize = 2;
+const CLAUSE_PUNCTUATION: [char; 9] = [',', '.', ';', ':', '!', '?', '…', '—', ')'];
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Two-speaker cues ("- Hi\n- Bye") keep their breaks no matter how long they are.
+fn is_dialogue_exchange(rows: &[&str]) -> bool {
+    rows.len() > 1
+        && rows
+            .iter()
+            .all(|row| row.trim_start().starts_with(['-', '–', '—']))
+}
+
+/// Picks where the first of `line_count` lines ends, preferring an even split and
+/// breaks right after clause punctuation.
+fn choose_break(words: &[&str], line_count: usize, max_line_length: usize) -> usize {
+    let lengths: Vec<usize> = words.iter().map(|w| char_len(w)).collect();
+    let total: usize = lengths.iter().sum::<usize>() + words.len() - 1;
+    let target = total / line_count;
+
+    let mut best: Option<(i64, usize)> = None;
+    let mut first_len = 0;
+    for split in 1..words.len() {
+        first_len += lengths[split - 1] + usize::from(split > 1);
+        let rest_len = total - first_len - 1;
+        let fits = first_len <= max_line_length
+            && rest_len <= max_line_length * (line_count - 1) + (line_count - 2);
+
+        let mut cost = (first_len as i64 - target as i64).abs();
+        if words[split - 1].ends_with(CLAUSE_PUNCTUATION) {
+            cost -= 8;
+        }
+        if !fits {
+            cost += 1000 + first_len.saturating_sub(max_line_length) as i64;
+        }
+        if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+            best = Some((cost, split));
+        }
+    }
+    best.map_or(words.len(), |(_, split)| split)
+}
+
+/// Re-wraps `text` to at most `max_lines` rows of `max_line_length` characters.
+/// Text that already fits keeps its breaks; literal `\N` from the model counts as a break.
+pub(crate) fn wrap_text(text: &str, max_line_length: usize, max_lines: usize) -> String {
+    let normalized = text.replace("\\N", "\n").replace("\\n", "\n");
+    let rows: Vec<&str> = normalized
+        .lines()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .collect();
+
+    let fits = rows.len() <= max_lines && rows.iter().all(|r| char_len(r) <= max_line_length);
+    if fits || is_dialogue_exchange(&rows) {
+        return rows.join("\n");
+    }
+
+    let words: Vec<&str> = rows.iter().flat_map(|r| r.split_whitespace()).collect();
+    let total = words.iter().map(|w| char_len(w)).sum::<usize>() + words.len().saturating_sub(1);
+    let line_count = total
+        .div_ceil(max_line_length.max(1))
+        .clamp(1, max_lines.max(1));
+
+    let mut lines = Vec::with_capacity(line_count);
+    let mut remaining = &words[..];
+    for lines_left in (2..=line_count).rev() {
+        if remaining.len() < 2 {
+            break;
+        }
+        let split = choose_break(remaining, lines_left, max_line_length);
+        lines.push(remaining[..split].join(" "));
+        remaining = &remaining[split..];
+    }
+    if !remaining.is_empty() {
+        lines.push(remaining.join(" "));
+    }
+    lines.join("\n")
+}
+
+/// Re-wraps every line of `subtitle_data` in place, leaving skipped signs and songs alone.
+/// Breaks are stored as `\n` and written back as `\N` for ASS by the subtitle writers.
+pub(crate) fn wrap_subtitle_data(subtitle_data: &mut SubtitleData, options: &WrapOptions) {
+    let max_line_length = options.max_line_length.unwrap_or(DEFAULT_MAX_LINE_LENGTH);
+    let max_lines = options.max_lines.unwrap_or(DEFAULT_MAX_LINES);
+    for line in subtitle_data.lines.iter_mut().filter(|line| !line.skip) {
+        line.text = wrap_text(&line.text, max_line_length, max_lines);
+    }
+}
+
+#[tauri::command]
+pub async fn rewrap_subtitle_lines(
+    mut subtitle_data: SubtitleData,
+    options: Option<WrapOptions>,
+) -> Result<SubtitleData, AnimeSubsError> {
+    wrap_subtitle_data(&mut subtitle_data, &options.unwrap_or_default());
+    Ok(subtitle_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_long_lines_at_clause_boundaries() {
+        assert_eq!(
+            wrap_text(
+                "Eu não sabia que você viria hoje, então não preparei nada para o jantar.",
+                42,
+                2
+            ),
+            "Eu não sabia que você viria hoje,\nentão não preparei nada para o jantar."
+        );
+    }
+
+    #[test]
+    fn keeps_fitting_text_and_dialogue_breaks() {
+        assert_eq!(
+            wrap_text("Short line\\Nsecond", 42, 2),
+            "Short line\nsecond"
+        );
+        let exchange = "- Was machst du hier eigentlich so spät in der Nacht?\n- Nichts.";
+        assert_eq!(wrap_text(exchange, 42, 2), exchange);
+    }
+}
@@ -0,0 +1,250 @@
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use std::fmt;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use crate::utils::format_bytes;
+
+/// Error returned by every command and by the helpers behind them.
+///
+/// Serializes as `{ code, message }`, plus `line` for parse errors,
+/// `retryAfterSecs` for rate limits and the byte counts for disk space, so the
+/// frontend can branch on `code` while still showing the same message text as
+/// before.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimeSubsError {
+    /// ffmpeg, ffprobe or mkvmerge could not be started.
+    FfmpegNotFound(String),
+    /// The requested subtitle or audio track is not in the file.
+    TrackNotFound(String),
+    /// The provider rejected the API key (401/403).
+    ProviderAuth(String),
+    /// The provider answered 429; `retry_after` comes from its Retry-After header.
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    /// A request timed out or a response body stalled; worth retrying.
+    Timeout(String),
+    /// Any other provider or network failure.
+    Provider(String),
+    /// Subtitle, config or response content could not be parsed; `line` is the
+    /// subtitle line the problem was found on, when there is one.
+    ParseError {
+        message: String,
+        line: Option<usize>,
+    },
+    /// A file could not be read, written, moved or removed.
+    Io(String),
+    /// `path` has `available` bytes free but the output needs `needed`.
+    InsufficientSpace {
+        path: String,
+        needed: u64,
+        available: u64,
+    },
+    /// The user cancelled the operation.
+    Cancelled,
+    Other(String),
+}
+
+impl AnimeSubsError {
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::ParseError {
+            message: message.into(),
+            line: None,
+        }
+    }
+
+    pub fn parse_at(line: usize, message: impl Into<String>) -> Self {
+        Self::ParseError {
+            message: message.into(),
+            line: Some(line),
+        }
+    }
+
+    /// Error for an external tool (ffmpeg, ffprobe, mkvmerge) that could not run.
+    /// Tracked runs report `Interrupted` when the user cancelled them.
+    pub fn from_tool(error: &std::io::Error, message: String) -> Self {
+        match error.kind() {
+            ErrorKind::Interrupted => Self::Cancelled,
+            _ => Self::FfmpegNotFound(message),
+        }
+    }
+
+    /// Stable identifier the frontend matches on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::FfmpegNotFound(_) => "FFMPEG_NOT_FOUND",
+            Self::TrackNotFound(_) => "TRACK_NOT_FOUND",
+            Self::ProviderAuth(_) => "PROVIDER_AUTH",
+            Self::RateLimited { .. } => "RATE_LIMITED",
+            Self::Timeout(_) => "TIMEOUT",
+            Self::Provider(_) => "PROVIDER",
+            Self::ParseError { .. } => "PARSE_ERROR",
+            Self::Io(_) => "IO",
+            Self::InsufficientSpace { .. } => "INSUFFICIENT_SPACE",
+            Self::Cancelled => "CANCELLED",
+            Self::Other(_) => "OTHER",
+        }
+    }
+}
+
+impl fmt::Display for AnimeSubsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FfmpegNotFound(message)
+            | Self::TrackNotFound(message)
+            | Self::ProviderAuth(message)
+            | Self::RateLimited { message, .. }
+            | Self::Timeout(message)
+            | Self::Provider(message)
+            | Self::Io(message)
+            | Self::Other(message) => f.write_str(message),
+            Self::ParseError {
+                message,
+                line: Some(line),
+            } => write!(f, "{} (line {})", message, line),
+            Self::ParseError {
+                message,
+                line: None,
+            } => f.write_str(message),
+            Self::InsufficientSpace {
+                path,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Not enough disk space in {}: {} needed, {} available",
+                path,
+                format_bytes(*needed),
+                format_bytes(*available)
+            ),
+            Self::Cancelled => f.write_str("Operation cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for AnimeSubsError {}
+
+impl Serialize for AnimeSubsError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AnimeSubsError", 5)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            Self::ParseError { line, .. } => state.serialize_field("line", line)?,
+            Self::RateLimited { retry_after, .. } => state.serialize_field(
+                "retryAfterSecs",
+                &retry_after.map(|duration| duration.as_secs()),
+            )?,
+            Self::InsufficientSpace {
+                path,
+                needed,
+                available,
+            } => {
+                state.serialize_field("path", path)?;
+                state.serialize_field("neededBytes", needed)?;
+                state.serialize_field("availableBytes", available)?;
+            }
+            _ => {}
+        }
+        state.end()
+    }
+}
+
+impl From<String> for AnimeSubsError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for AnimeSubsError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+impl From<AnimeSubsError> for String {
+    fn from(error: AnimeSubsError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<std::io::Error> for AnimeSubsError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::Interrupted => Self::Cancelled,
+            _ => Self::Io(error.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AnimeSubsError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::ParseError {
+            message: error.to_string(),
+            line: Some(error.line()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_message_and_details() {
+        let error = AnimeSubsError::parse_at(12, "Invalid timestamp");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "PARSE_ERROR",
+                "message": "Invalid timestamp (line 12)",
+                "line": 12,
+            })
+        );
+
+        let error = AnimeSubsError::RateLimited {
+            message: "LLM API error (429 Too Many Requests)".to_string(),
+            retry_after: Some(Duration::from_secs(7)),
+        };
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "RATE_LIMITED");
+        assert_eq!(value["retryAfterSecs"], 7);
+
+        let error = AnimeSubsError::InsufficientSpace {
+            path: "/media".to_string(),
+            needed: 3 * 1024 * 1024 * 1024,
+            available: 512 * 1024 * 1024,
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "INSUFFICIENT_SPACE",
+                "message": "Not enough disk space in /media: 3.0 GB needed, 512.0 MB available",
+                "path": "/media",
+                "neededBytes": 3_221_225_472u64,
+                "availableBytes": 536_870_912u64,
+            })
+        );
+
+        let value = serde_json::to_value(AnimeSubsError::Cancelled).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "code": "CANCELLED", "message": "Operation cancelled" })
+        );
+    }
+
+    #[test]
+    fn converts_plain_messages_and_io_errors() {
+        let error: AnimeSubsError = "boom".into();
+        assert_eq!(error, AnimeSubsError::Other("boom".to_string()));
+        assert_eq!(String::from(error), "boom");
+
+        let interrupted = std::io::Error::new(ErrorKind::Interrupted, "stopped");
+        assert_eq!(AnimeSubsError::from(interrupted), AnimeSubsError::Cancelled);
+        let missing = std::io::Error::new(ErrorKind::NotFound, "missing");
+        assert_eq!(AnimeSubsError::from(missing).code(), "IO");
+    }
+}
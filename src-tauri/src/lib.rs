@@ -1,35 +1,190 @@
 pub mod commands;
+pub mod error;
 pub mod models;
 pub mod providers;
 pub mod utils;
 
-use commands::{backup, embedding, subtitle, translation, utils as utility_commands, video};
+use commands::{
+    anilist, audio, automation, backup, bootstrap, burn, capabilities, chapters, cleanup,
+    connection, convert, embedding, estimate, fonts, history, honorifics, job_control, language,
+    logging, media_server, names, naming, operations, parser_suite, presets, preview, project,
+    prompts, proxy, release, reports, review, secrets, series, server, settings, styles, subtitle,
+    temp_files, timing, track_selection, transcription, translation, utils as utility_commands,
+    validation, video, watch, wrapping,
+};
+use tracing::warn;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .manage(operations::OperationRegistry::default())
+        .manage(job_control::JobControl::default())
+        .manage(server::RemoteServer::default())
+        .manage(automation::AutomationState::default())
+        .manage(watch::FolderWatch::default())
+        .setup(|app| {
+            if let Err(e) = logging::init_logging(app.handle()) {
+                eprintln!("Failed to set up logging: {}", e);
+            }
+            if let Err(e) = secrets::migrate_plaintext_secrets(app.handle()) {
+                warn!("Failed to migrate API keys to the keyring: {}", e);
+            }
+            if let Err(e) = settings::load_settings(app.handle()) {
+                warn!("Failed to load settings: {}", e);
+            }
+            if let Err(e) = bootstrap::load_bundled_tools(app.handle()) {
+                warn!("Failed to locate downloaded tools: {}", e);
+            }
+            if let Err(e) = proxy::load_proxy_settings(app.handle()) {
+                warn!("Failed to load proxy settings: {}", e);
+            }
+            // Nothing is running yet, so every recorded temp file is orphaned.
+            if let Err(e) = temp_files::cleanup_orphans(app.handle()) {
+                warn!("Failed to clean up orphaned temp files: {}", e);
+            }
+            if let Err(e) = watch::start_saved_watch(app.handle()) {
+                warn!("Failed to start the folder watch: {}", e);
+            }
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = server::start_saved_server(&handle).await {
+                    warn!("Failed to start the remote server: {}", e);
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             utility_commands::greet,
             video::get_video_info,
             video::scan_folder_for_videos,
             subtitle::extract_subtitle,
+            subtitle::extract_all_subtitles,
             backup::backup_subtitle,
             backup::list_backups,
+            backup::list_all_backups,
+            backup::prune_backups,
+            backup::relocate_backups,
             backup::restore_subtitle,
             backup::delete_backup,
             embedding::embed_subtitle,
+            embedding::embed_subtitles_batch,
             embedding::remove_subtitle_track,
+            embedding::check_embed_compatibility,
+            audio::set_default_audio_track,
+            audio::remove_audio_track,
+            burn::burn_subtitle,
             utility_commands::check_ffmpeg,
+            bootstrap::download_ffmpeg,
+            bootstrap::download_mkvtoolnix,
+            capabilities::check_mkvtoolnix,
+            capabilities::probe_capabilities,
             utility_commands::delete_file,
-            utility_commands::load_api_key,
-            utility_commands::save_api_key,
+            utility_commands::check_disk_space,
+            secrets::get_api_key,
+            secrets::store_api_key,
+            utility_commands::load_app_state,
+            utility_commands::save_app_state,
             utility_commands::fetch_models,
+            utility_commands::list_models,
+            connection::test_llm_connection,
+            connection::check_provider_health,
+            proxy::get_proxy_settings,
+            proxy::save_proxy_settings,
+            project::save_project,
+            project::open_project,
+            project::list_recent_projects,
+            prompts::list_prompt_templates,
+            prompts::save_prompt_template,
+            prompts::delete_prompt_template,
+            series::load_series_context,
+            series::save_series_context,
+            anilist::fetch_anilist_synopsis,
+            anilist::lookup_anime,
+            anilist::identify_episode,
+            release::parse_release_filename,
+            release::group_release_files,
+            naming::validate_naming_template,
+            naming::preview_output_name,
             subtitle::parse_subtitle_file,
+            subtitle::parse_embedded_subtitle,
+            subtitle::preview_subtitle_track,
+            subtitle::override_line_skips,
+            subtitle::split_signs_track,
+            track_selection::choose_best_subtitle_track,
+            temp_files::cleanup_orphaned_temp_files,
+            timing::shift_subtitle_timing,
+            timing::shift_subtitle_data_timing,
+            timing::convert_subtitle_framerate,
+            convert::convert_subtitle_format,
+            styles::list_ass_styles,
+            styles::save_ass_styles,
+            styles::apply_ass_style_preset,
+            fonts::analyze_subtitle_fonts,
+            transcription::transcribe_audio,
             translation::translate_subtitles,
+            translation::refine_translations,
+            translation::attach_reference_track,
             translation::save_translated_subtitles,
+            review::get_translation_diff,
+            review::apply_line_edits,
+            validation::validate_subtitle,
+            history::get_history,
+            history::export_history,
+            history::get_last_job_debug_log,
+            honorifics::check_honorifics,
+            honorifics::fix_honorifics,
+            wrapping::rewrap_subtitle_lines,
+            cleanup::clean_subtitle,
+            names::analyze_character_names,
+            names::load_name_table,
+            names::save_name_table,
             translation::start_translation_job,
+            chapters::list_chapters,
+            chapters::translate_chapters,
+            chapters::write_chapters,
+            operations::cancel_operation,
+            parser_suite::run_parser_suite,
+            logging::get_log_settings,
+            logging::set_log_level,
+            logging::tail_logs,
+            settings::get_settings,
+            settings::update_settings,
+            presets::list_presets,
+            presets::save_preset,
+            presets::delete_preset,
+            presets::export_presets,
+            presets::import_presets,
+            reports::list_job_reports,
+            reports::export_job_report,
+            review::export_translation_table,
+            review::import_reviewed_table,
+            preview::render_preview_frame,
+            estimate::estimate_translation_job,
+            job_control::pause_translation_job,
+            job_control::resume_translation_job,
+            job_control::cancel_translation_job,
+            names::suggest_glossary_terms,
+            names::import_glossary,
+            names::export_glossary,
+            logging::export_logs,
+            server::get_server_status,
+            server::save_server_settings,
+            server::regenerate_server_token,
+            media_server::get_media_server_settings,
+            media_server::save_media_server_settings,
+            media_server::test_media_server,
+            automation::get_automation_settings,
+            automation::save_automation_settings,
+            automation::get_automation_queue,
+            automation::enqueue_automation,
+            watch::get_folder_watch,
+            watch::save_watch_folders,
+            watch::start_folder_watch,
+            watch::stop_folder_watch,
+            language::detect_subtitle_languages,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -1,4 +1,7 @@
+use crate::providers::debug_log::DebugLog;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SubtitleTrack {
@@ -9,6 +12,30 @@ pub struct SubtitleTrack {
     pub title: Option<String>,
     pub default: bool,
     pub forced: bool,
+    /// Language read from the track's dialog by `detect_subtitle_languages`.
+    #[serde(default)]
+    pub detected_language: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AudioTrack {
+    pub index: u32,
+    pub stream_index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub channels: Option<u32>,
+    pub default: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VideoStream {
+    pub index: u32,
+    pub stream_index: u32,
+    pub codec: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub default: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,6 +44,13 @@ pub struct VideoInfo {
     pub filename: String,
     pub duration: Option<f64>,
     pub subtitle_tracks: Vec<SubtitleTrack>,
+    #[serde(default)]
+    pub audio_tracks: Vec<AudioTrack>,
+    #[serde(default)]
+    pub video_streams: Vec<VideoStream>,
+    /// All streams in the container, including attachments and data streams.
+    #[serde(default)]
+    pub stream_count: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +60,88 @@ pub struct BackupInfo {
     pub track_index: u32,
     pub format: String,
     pub created_at: String,
+    /// The backed-up track as it was in the container; unset for backups made
+    /// before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track: Option<BackupTrack>,
+}
+
+/// Identity of a backed-up subtitle track, checked before a restore replaces it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupTrack {
+    pub stream_index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+}
+
+/// Where remux commands put their result instead of the source video.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum OutputPolicy {
+    /// Atomically replace the source video (fsync before rename).
+    #[default]
+    Overwrite,
+    /// Write `<stem><suffix>.<ext>` next to the source video.
+    Alongside {
+        suffix: String,
+        /// Replace a file already at the output path instead of failing.
+        #[serde(default, rename = "replaceExisting")]
+        replace_existing: bool,
+    },
+    /// Write a file with the same name into another directory.
+    Directory {
+        path: String,
+        #[serde(default, rename = "replaceExisting")]
+        replace_existing: bool,
+    },
+}
+
+/// Video encoder used when subtitles are burned into the picture.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoEncoder {
+    #[default]
+    X264,
+    X265,
+    Nvenc,
+    Qsv,
+    Vaapi,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BurnOptions {
+    #[serde(default)]
+    pub encoder: VideoEncoder,
+    /// Constant quality value (CRF/CQ/QP depending on the encoder).
+    #[serde(default)]
+    pub crf: Option<u32>,
+    /// Target bitrate such as `"4M"`; takes precedence over `crf`.
+    #[serde(default)]
+    pub bitrate: Option<String>,
+    #[serde(default)]
+    pub preset: Option<String>,
+}
+
+/// How subtitle timestamps are corrected when a subtitle comes from another release.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum TimingAdjustment {
+    /// Moves every cue by a constant offset (negative values move earlier).
+    #[serde(rename_all = "camelCase")]
+    Shift { offset_ms: i64 },
+    /// Linear stretch through two anchors: `source_*` times map onto `target_*` times.
+    #[serde(rename_all = "camelCase")]
+    Scale {
+        source_a_ms: i64,
+        target_a_ms: i64,
+        source_b_ms: i64,
+        target_b_ms: i64,
+    },
+    /// Keeps frame numbers and swaps the frame rate, e.g. PAL 25 → NTSC-film 23.976.
+    #[serde(rename_all = "camelCase")]
+    FrameRate { source_fps: f64, target_fps: f64 },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,11 +151,53 @@ pub struct ExtractResult {
     pub error: Option<String>,
 }
 
+/// A subtitle track handled by `extract_all_subtitles`: where it was written, or why not.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedTrack {
+    pub video_path: String,
+    pub track_index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionManifest {
+    pub folder: String,
+    pub video_count: usize,
+    pub extracted: usize,
+    pub failed: usize,
+    pub tracks: Vec<ExtractedTrack>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OperationResult {
     pub success: bool,
     pub message: String,
     pub data: Option<String>,
+    /// Checks run on a remuxed file before it replaced the original.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<RemuxVerification>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationCheck {
+    /// "probe", "video", "audio", "subtitles", "duration" or "decode".
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemuxVerification {
+    pub passed: bool,
+    pub checks: Vec<VerificationCheck>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -51,6 +209,135 @@ pub struct DialogLine {
     pub end: String,
     pub style: Option<String>,
     pub name: Option<String>,
+    /// Time-aligned line from a second track (e.g. an existing English translation).
+    #[serde(default)]
+    pub reference: Option<String>,
+    /// SRT tags that wrapped the whole cue, re-applied to the translated text on save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatting: Option<LineFormatting>,
+    /// ASS event type; SRT/VTT cues are always dialogue.
+    #[serde(default)]
+    pub event: AssEventKind,
+    #[serde(default)]
+    pub classification: LineClass,
+    /// Kept out of translation; parsers set it for everything but dialogue and users
+    /// can flip it to correct a misclassification.
+    #[serde(default)]
+    pub skip: bool,
+}
+
+/// What a parsed subtitle event is, as decided when the file was parsed.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LineClass {
+    #[default]
+    Dialogue,
+    /// Sign, song or credit styles, drawings and events without visible text.
+    Sign,
+    /// Events timed with `\k` karaoke tags.
+    Karaoke,
+    /// Music notes and cues such as "[Music]".
+    Music,
+    /// ASS events under three characters, usually typesetting fragments.
+    Short,
+    /// `Comment:` events, e.g. timing or TL notes.
+    Comment,
+    /// `Picture:`, `Sound:`, `Movie:` and `Command:` events.
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum AssEventKind {
+    #[default]
+    Dialogue,
+    Comment,
+    Picture,
+    Sound,
+    Movie,
+    Command,
+}
+
+impl AssEventKind {
+    /// The event line prefix, e.g. `Comment` in `Comment: 0,...`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Dialogue => "Dialogue",
+            Self::Comment => "Comment",
+            Self::Picture => "Picture",
+            Self::Sound => "Sound",
+            Self::Movie => "Movie",
+            Self::Command => "Command",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        [
+            Self::Dialogue,
+            Self::Comment,
+            Self::Picture,
+            Self::Sound,
+            Self::Movie,
+            Self::Command,
+        ]
+        .into_iter()
+        .find(|kind| kind.label() == label)
+    }
+}
+
+/// Number of parsed lines per [`LineClass`], plus how many are skipped.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LineClassCounts {
+    pub dialogue: usize,
+    pub sign: usize,
+    pub karaoke: usize,
+    pub music: usize,
+    pub short: usize,
+    pub comment: usize,
+    pub other: usize,
+    pub skipped: usize,
+}
+
+impl LineClassCounts {
+    pub fn from_lines(lines: &[DialogLine]) -> Self {
+        let mut counts = Self::default();
+        for line in lines {
+            let slot = match line.classification {
+                LineClass::Dialogue => &mut counts.dialogue,
+                LineClass::Sign => &mut counts.sign,
+                LineClass::Karaoke => &mut counts.karaoke,
+                LineClass::Music => &mut counts.music,
+                LineClass::Short => &mut counts.short,
+                LineClass::Comment => &mut counts.comment,
+                LineClass::Other => &mut counts.other,
+            };
+            *slot += 1;
+            if line.skip {
+                counts.skipped += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Formatting mask of an SRT cue: `<i>`, `<b>`, `<u>` and `<font color>`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct LineFormatting {
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub underline: bool,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+impl LineFormatting {
+    pub fn is_plain(&self) -> bool {
+        !self.italic && !self.bold && !self.underline && self.color.is_none()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -60,6 +347,54 @@ pub struct SubtitleData {
     pub line_count: usize,
     pub source_path: String,
     pub ass_header: Option<String>,
+    /// Lines the model never returned after retries; they keep their source text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_line_ids: Vec<usize>,
+    /// Which provider translated each request batch, in batch order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub batch_providers: Vec<BatchProvider>,
+    #[serde(default)]
+    pub class_counts: LineClassCounts,
+    /// Repeats of an earlier line that were given its translation instead of being sent.
+    #[serde(default)]
+    pub deduplicated_lines: usize,
+}
+
+/// User correction of a parsed line's skip flag.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LineSkipOverride {
+    pub index: usize,
+    pub skip: bool,
+}
+
+/// Provider and model that translated `line_count` lines of request batch `batch`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProvider {
+    pub batch: usize,
+    pub provider: String,
+    pub model: String,
+    pub line_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SignsSplitResult {
+    pub dialogue_path: String,
+    pub signs_path: String,
+    pub dialogue_events: usize,
+    pub sign_events: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleTrackPreview {
+    pub track_index: u32,
+    pub format: String,
+    /// Dialog lines in the whole track; signs-only tracks come out near zero.
+    pub total_lines: usize,
+    pub lines: Vec<DialogLine>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -74,6 +409,14 @@ pub struct TranslationRequest {
 pub struct TranslationLine {
     pub id: usize,
     pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// First-pass translation sent back for proofreading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft: Option<String>,
+    /// Why the previous answer for this line was refused, sent with the retry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -96,16 +439,100 @@ pub struct TranslationProgress {
     pub status: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct LLMConfig {
     pub provider: String,
     pub api_key: String,
     pub endpoint: String,
     pub model: String,
     pub system_prompt: String,
+    /// Canonical character-name spellings added to every prompt.
+    #[serde(default)]
+    pub name_table: Vec<CharacterName>,
+    /// Saved prompt template used instead of the built-in `system_prompt` style.
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+    /// Synopsis, character notes and tone for the show, exposed as `{series_context}`.
+    #[serde(default)]
+    pub series_context: Option<String>,
+    /// Body of `prompt_template`, loaded by the backend before any request is built.
+    #[serde(skip)]
+    pub template_content: Option<String>,
+    #[serde(default)]
+    pub batching: BatchingStrategy,
+    /// Context length of the model in tokens; guessed from the model name when unset.
+    #[serde(default)]
+    pub context_window: Option<usize>,
+    /// Tokens per request for lines and their expected translations, overriding what
+    /// `context_window` leaves after the system prompt.
+    #[serde(default)]
+    pub batch_token_budget: Option<usize>,
+    /// Times lines missing from a response, or failing the output checks, are requested again.
+    #[serde(default)]
+    pub missing_line_retries: Option<u32>,
+    /// Longest accepted translation, as a multiple of the source line's length.
+    #[serde(default)]
+    pub max_length_ratio: Option<f64>,
+    /// Gives repeated lines the translation of their first occurrence instead of
+    /// sending them again; on unless set to false.
+    #[serde(default)]
+    pub reuse_repeats: Option<bool>,
+    /// Sends `response_format: json_object`; defaults to off for llama.cpp and vLLM.
+    #[serde(default)]
+    pub json_mode: Option<bool>,
+    /// Azure OpenAI deployment name; defaults to `model`.
+    #[serde(default)]
+    pub azure_deployment: Option<String>,
+    /// Azure OpenAI `api-version` query parameter.
+    #[serde(default)]
+    pub azure_api_version: Option<String>,
+    /// Providers tried in order when a batch fails on this one. Fallbacks without their
+    /// own prompt settings or API key use the primary's prompt and the stored key.
+    #[serde(default)]
+    pub fallbacks: Vec<LLMConfig>,
+    /// Seconds allowed to open the connection.
+    #[serde(default)]
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds allowed for a whole request, including generation.
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+    /// Seconds without any response bytes before the request counts as stalled.
+    #[serde(default)]
+    pub stall_timeout_secs: Option<u64>,
+    /// Request/response log of the running job, when it was started with `debug_log`.
+    #[serde(skip)]
+    pub debug_log: Option<Arc<DebugLog>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Provider limits for one translation run; requests are scheduled to stay under them.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitOptions {
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub tokens_per_minute: Option<u32>,
+    /// Upper bound for chunks in flight; lowered on 429 responses and ramped back up.
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Retries of a chunk rejected with 429 before the run fails.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+}
+
+/// How long subtitle files are split into requests.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchingStrategy {
+    /// Fill each request up to the token budget in file order.
+    #[default]
+    Sequential,
+    /// Prefer cutting at the largest timing gap (scene change) near the budget so
+    /// conversations stay in one request.
+    Scene,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslationJobRequest {
     pub video_paths: Vec<String>,
@@ -116,10 +543,67 @@ pub struct TranslationJobRequest {
     pub output_directory: Option<String>,
     pub ffmpeg_path: Option<String>,
     pub subtitle_track: Option<u32>,
+    #[serde(default)]
+    pub output_policy: OutputPolicy,
+    /// Second track (e.g. an existing English translation) used as reference per line.
+    #[serde(default)]
+    pub reference_track: Option<u32>,
+    /// Re-wraps translated lines when set; long translations otherwise keep the model's breaks.
+    #[serde(default)]
+    pub wrap: Option<WrapOptions>,
+    /// Removes SDH sound descriptions and speaker labels before translating.
+    #[serde(default)]
+    pub strip_sdh: Option<SdhOptions>,
+    /// Sends the finished draft back for a proofreading pass before saving.
+    #[serde(default)]
+    pub refine: bool,
+    /// With the "honorifics" style, re-translates lines that dropped -san/-kun/... once.
+    #[serde(default)]
+    pub honorific_second_pass: bool,
+    /// Leaves videos whose subtitle dialog is already in the target language untranslated.
+    #[serde(default)]
+    pub skip_target_language: bool,
+    /// Leaves videos an earlier job already translated (an embedded "Translated (...)"
+    /// track or a saved subtitle file in the target language) untranslated.
+    #[serde(default)]
+    pub skip_translated: bool,
+    /// Output file name template, e.g. "{title} - S{season:02}E{episode:02}.{lang}.{ext}".
+    #[serde(default)]
+    pub naming_template: Option<String>,
     pub embed_subtitles: bool,
     pub use_mkvmerge: bool,
     pub auto_backup: bool,
     pub keep_original_track: bool,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitOptions>,
+    /// Writes every LLM request and response, keys redacted, to a per-job log file.
+    #[serde(default)]
+    pub debug_log: bool,
+    #[serde(default)]
+    pub output_encoding: Option<SubtitleEncodingOptions>,
+    /// Applied to every dialogue style of ASS output (sign and song styles are kept).
+    #[serde(default)]
+    pub dialogue_style: Option<AssStylePatch>,
+    /// Also translates ASS `Comment:` lines, which often carry TL notes.
+    #[serde(default)]
+    pub translate_comments: bool,
+    #[serde(default)]
+    pub ass_metadata: Option<AssMetadataOptions>,
+    /// Number of videos processed concurrently (default 1); remuxes still run one at a time.
+    #[serde(default)]
+    pub max_parallel_files: Option<usize>,
+    /// `.animesubs` project the job belongs to; its report is saved next to it.
+    #[serde(default)]
+    pub project_path: Option<String>,
+    /// Track to translate per video path, overriding `subtitle_track` for those videos.
+    #[serde(default)]
+    pub file_tracks: BTreeMap<String, u32>,
+    /// Subtitle file to translate per video path instead of one of its embedded tracks.
+    #[serde(default)]
+    pub file_subtitles: BTreeMap<String, String>,
+    /// Return each video's translated lines in its output, for review after the job.
+    #[serde(default)]
+    pub keep_subtitle_data: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -129,14 +613,67 @@ pub struct TranslationJobProgress {
     pub total_files: usize,
     pub progress: f64,
     pub status: String,
+    /// Video the update is about; unset for the job-wide summary.
+    #[serde(default)]
+    pub video_path: Option<String>,
+    #[serde(default)]
+    pub stage: Option<JobFileStage>,
+    /// Progress within `video_path`, 0–100.
+    #[serde(default)]
+    pub file_progress: Option<f64>,
+}
+
+/// Where a video of a running translation job is.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobFileStage {
+    /// Extracting and parsing the subtitle track.
+    Preparing,
+    /// Translating, proofreading and fixing honorifics.
+    Translating,
+    Saving,
+    Embedding,
+    Done,
+    Failed,
+    /// Stopped by the user before it finished; the next run starts it over.
+    Cancelled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationStarted {
+    pub operation_id: String,
+    pub operation: String,
+    pub video_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegProgress {
+    pub operation_id: String,
+    pub operation: String,
+    pub video_path: String,
+    pub processed_seconds: f64,
+    pub duration: Option<f64>,
+    pub progress: Option<f64>,
+    pub done: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TranslationJobOutput {
     pub video_path: String,
+    pub output_video_path: Option<String>,
     pub subtitle_path: Option<String>,
     pub embedded: bool,
+    #[serde(default)]
+    pub batch_providers: Vec<BatchProvider>,
+    /// Why the video was left untranslated; nothing was written for it.
+    #[serde(default)]
+    pub skipped: Option<String>,
+    /// Translated lines as saved; only with `keep_subtitle_data`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle_data: Option<SubtitleData>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -146,4 +683,1097 @@ pub struct TranslationJobResult {
     pub total_files: usize,
     pub failures: Vec<String>,
     pub outputs: Vec<TranslationJobOutput>,
+    #[serde(default)]
+    pub report: Option<JobReport>,
+    /// The user stopped the job; files that had not finished are left pending.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// What one video of a translation job produced.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFileReport {
+    pub video_path: String,
+    pub status: ProjectFileStatus,
+    pub subtitle_track: Option<u32>,
+    pub track_language: Option<String>,
+    pub track_title: Option<String>,
+    /// Language detected from the subtitle's dialog.
+    #[serde(default)]
+    pub detected_language: Option<String>,
+    pub lines_total: usize,
+    pub lines_translated: usize,
+    pub lines_skipped: usize,
+    /// Lines the model never returned; they kept their source text.
+    pub lines_failed: usize,
+    /// Repeated lines copied from their first occurrence, i.e. lines not sent.
+    #[serde(default)]
+    pub lines_deduplicated: usize,
+    /// Estimated from the subtitle text and system prompts sent and the text received.
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    /// USD, from `model_pricing` in the settings; unset when a model has no price.
+    pub estimated_cost: Option<f64>,
+    pub duration_ms: u64,
+    /// Reading-speed, length and timing findings on the translated lines.
+    pub warnings: Vec<ValidationIssue>,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+    /// Why a `Skipped` video was left untranslated.
+    #[serde(default)]
+    pub skip_reason: Option<String>,
+    /// Subtitle with the batches a stopped video finished; its other lines keep
+    /// their source text.
+    #[serde(default)]
+    pub partial_path: Option<String>,
+}
+
+/// Summary of one `start_translation_job` run, saved as JSON after every job.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JobReport {
+    pub id: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub provider: String,
+    pub model: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub duration_ms: u64,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    pub estimated_cost: Option<f64>,
+    pub files: Vec<JobFileReport>,
+    /// Where the report was saved.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Expected size of one video's translation, before the job runs.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEstimate {
+    pub video_path: String,
+    /// Dialog lines that will be sent; skipped lines and repeats are not counted.
+    pub lines: usize,
+    pub batches: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    /// Why the subtitles could not be read; the file is left out of the totals.
+    pub error: Option<String>,
+}
+
+/// Totals of `estimate_translation_job`, comparable with the tokens of a `JobReport`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct JobEstimate {
+    pub model: String,
+    pub lines: usize,
+    pub batches: usize,
+    pub input_tokens: usize,
+    pub output_tokens: usize,
+    /// USD, from `model_pricing` in the settings; unset when the model has no price.
+    pub estimated_cost: Option<f64>,
+    pub files: Vec<FileEstimate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChapterInfo {
+    pub index: u32,
+    pub start: f64,
+    pub end: f64,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParserSuiteFileReport {
+    pub path: String,
+    pub format: String,
+    pub parsed_lines: usize,
+    pub lost_count: usize,
+    pub added_count: usize,
+    pub lost_samples: Vec<String>,
+    pub added_samples: Vec<String>,
+    pub error: Option<String>,
+    pub round_trip_ok: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParserSuiteReport {
+    pub folder: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub files: Vec<ParserSuiteFileReport>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilityReport {
+    pub ffmpeg_path: String,
+    pub ffmpeg_version: Option<String>,
+    pub ffprobe_available: bool,
+    /// Burn-in encoders this ffmpeg build was compiled with.
+    pub burn_encoders: Vec<VideoEncoder>,
+    pub hardware_encoders: Vec<String>,
+    pub hwaccels: Vec<String>,
+    /// Whether the `ass`/`subtitles` filters (libass) are available for burn-in.
+    pub libass: bool,
+    pub mkvmerge_path: Option<String>,
+    pub mkvmerge_version: Option<String>,
+    pub mkvpropedit_available: bool,
+}
+
+/// Which MKVToolNix embedding and track edits will use; without it MKV output
+/// falls back to ffmpeg.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MkvToolNixStatus {
+    pub available: bool,
+    pub mkvmerge_path: Option<String>,
+    pub mkvmerge_version: Option<String>,
+    pub mkvpropedit_path: Option<String>,
+    /// `mkvtoolnix_path` from the settings, whether or not the tools were found there.
+    pub configured_path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackScore {
+    pub track_index: u32,
+    pub score: i32,
+    pub dialog_lines: Option<usize>,
+    /// Human-readable notes on what raised or lowered the score.
+    pub reasons: Vec<String>,
+}
+
+/// Thresholds for `validate_subtitle`; unset values fall back to common broadcast limits.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationOptions {
+    #[serde(default)]
+    pub max_cps: Option<f64>,
+    #[serde(default)]
+    pub max_line_length: Option<usize>,
+    #[serde(default)]
+    pub min_duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationIssueKind {
+    ReadingSpeed,
+    LineLength,
+    ShortDuration,
+    Overlap,
+    InvalidTiming,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    pub line_index: usize,
+    pub kind: ValidationIssueKind,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationReport {
+    pub line_count: usize,
+    pub passed: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WrapOptions {
+    #[serde(default)]
+    pub max_line_length: Option<usize>,
+    #[serde(default)]
+    pub max_lines: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+/// Byte-level layout of a written subtitle file, for players that only read legacy
+/// encodings or Windows line endings.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleEncodingOptions {
+    /// encoding_rs label such as "windows-1252", "shift_jis" or "gbk"; UTF-8 when unset.
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Writes a UTF-8 byte order mark; ignored for other encodings. Unset keeps the
+    /// caller's default.
+    #[serde(default)]
+    pub bom: Option<bool>,
+    #[serde(default)]
+    pub line_ending: LineEnding,
+}
+
+/// One `Style:` line of an ASS `[V4+ Styles]` section. Colours keep the ASS
+/// `&HAABBGGRR` notation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssStyle {
+    pub name: String,
+    pub fontname: String,
+    pub fontsize: f64,
+    pub primary_colour: String,
+    pub secondary_colour: String,
+    pub outline_colour: String,
+    pub back_colour: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike_out: bool,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub spacing: f64,
+    pub angle: f64,
+    pub border_style: u32,
+    pub outline: f64,
+    pub shadow: f64,
+    /// Numpad position, 2 = bottom center.
+    pub alignment: u32,
+    pub margin_l: i32,
+    pub margin_r: i32,
+    pub margin_v: i32,
+    pub encoding: i32,
+}
+
+/// Style fields to change; unset fields keep their current value.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssStylePatch {
+    #[serde(default)]
+    pub fontname: Option<String>,
+    #[serde(default)]
+    pub fontsize: Option<f64>,
+    #[serde(default)]
+    pub primary_colour: Option<String>,
+    #[serde(default)]
+    pub secondary_colour: Option<String>,
+    #[serde(default)]
+    pub outline_colour: Option<String>,
+    #[serde(default)]
+    pub back_colour: Option<String>,
+    #[serde(default)]
+    pub bold: Option<bool>,
+    #[serde(default)]
+    pub italic: Option<bool>,
+    #[serde(default)]
+    pub outline: Option<f64>,
+    #[serde(default)]
+    pub shadow: Option<f64>,
+    #[serde(default)]
+    pub alignment: Option<u32>,
+    #[serde(default)]
+    pub margin_l: Option<i32>,
+    #[serde(default)]
+    pub margin_r: Option<i32>,
+    #[serde(default)]
+    pub margin_v: Option<i32>,
+}
+
+/// `[Script Info]` changes written into translated ASS output.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssMetadataOptions {
+    /// Replaces the script `Title:`.
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Adds a "; Translated by AnimeSubs" comment line.
+    #[serde(default)]
+    pub credit: bool,
+    /// `Language:` value; defaults to the target language.
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub scaled_border_and_shadow: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FontUsage {
+    pub name: String,
+    /// Styles whose Fontname is this font.
+    pub styles: Vec<String>,
+    /// Dialogue events that switch to the font with an inline `\fn` tag.
+    pub inline_uses: usize,
+    pub attached: bool,
+    pub installed: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FontReport {
+    pub fonts: Vec<FontUsage>,
+    /// Fonts neither attached to the video nor installed on this system.
+    pub missing: Vec<String>,
+    /// Copy of the subtitle with missing fonts replaced by the fallback, when requested.
+    pub remapped_path: Option<String>,
+}
+
+/// How ASS styling is carried into SRT/VTT, which only know a few HTML-like tags.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssDowngrade {
+    /// Italic, bold, underline and primary color become `<i>`, `<b>`, `<u>` and
+    /// `<font color>`, from both override tags and the line's style.
+    #[default]
+    Tags,
+    /// Plain text only.
+    Strip,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatConversionOptions {
+    #[serde(default)]
+    pub ass_styling: AssDowngrade,
+    /// Drops ASS events on sign/song styles, which make no sense without positioning.
+    #[serde(default)]
+    pub skip_signs: bool,
+    #[serde(default)]
+    pub output_encoding: Option<SubtitleEncodingOptions>,
+}
+
+/// Hearing-impaired (SDH) artifacts to remove, one switch per rule.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SdhOptions {
+    /// Sound descriptions in brackets, e.g. `[door slams]`.
+    #[serde(default)]
+    pub brackets: bool,
+    /// Parenthetical cues, e.g. `(laughs)`.
+    #[serde(default)]
+    pub parentheticals: bool,
+    /// Upper-case speaker labels at the start of a row, e.g. `JOHN:`.
+    #[serde(default)]
+    pub speaker_labels: bool,
+}
+
+/// Which `clean_subtitle` fixes to apply; thresholds fall back to common defaults.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupOptions {
+    #[serde(default)]
+    pub merge_identical: bool,
+    #[serde(default)]
+    pub extend_short: bool,
+    #[serde(default)]
+    pub enforce_gaps: bool,
+    #[serde(default)]
+    pub merge_tolerance_ms: Option<u64>,
+    #[serde(default)]
+    pub min_duration_ms: Option<u64>,
+    #[serde(default)]
+    pub min_gap_ms: Option<u64>,
+    #[serde(default)]
+    pub strip_sdh: Option<SdhOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupResult {
+    pub subtitle_data: SubtitleData,
+    pub merged: usize,
+    pub extended: usize,
+    pub gaps_fixed: usize,
+    #[serde(default)]
+    pub sdh_cleaned: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CharacterName {
+    /// Name as it appears in the source lines or ASS Name/Actor field.
+    pub source: String,
+    /// Spelling the translation must use.
+    pub canonical: String,
+    #[serde(default)]
+    pub occurrences: usize,
+    /// Pinned entries keep their canonical spelling when the series is re-analyzed.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HonorificIssue {
+    pub line_index: usize,
+    /// Honorifics present in the source line but missing from the translation.
+    pub missing: Vec<String>,
+    pub source_text: String,
+    pub translated_text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewRow {
+    pub id: usize,
+    pub start: String,
+    pub end: String,
+    pub source: String,
+    pub translation: String,
+    /// Reasons to look at the row: "untranslated", "empty", "honorifics" or a QC kind.
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LineEdit {
+    pub id: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderModel {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub context_length: Option<u64>,
+}
+
+/// Result of probing a provider's health and model listing before a job starts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHealth {
+    pub reachable: bool,
+    pub latency_ms: u64,
+    /// Models reported by the server's `/v1/models` listing.
+    pub models: Vec<String>,
+    /// Whether the configured model is served; `None` when the server ignores model names.
+    pub model_available: Option<bool>,
+    pub message: String,
+}
+
+/// Proxy for every outgoing HTTP request (LLM providers, AniList, transcription).
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    /// `http://`, `https://` or `socks5://` proxy address, used in manual mode.
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Kept in the OS keyring, never in `proxy.json`.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Comma-separated hosts reached directly, e.g. `localhost,127.0.0.1`.
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Honour the `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY` environment variables.
+    #[default]
+    System,
+    Off,
+    Manual,
+}
+
+/// Media server told to pick up new subtitles once a file is translated.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaServerKind {
+    #[default]
+    None,
+    Jellyfin,
+    Plex,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaServerSettings {
+    #[serde(default)]
+    pub kind: MediaServerKind,
+    /// Base address, e.g. `http://nas:8096` (Jellyfin) or `http://nas:32400` (Plex).
+    #[serde(default)]
+    pub url: String,
+    /// Jellyfin API key or Plex token. Kept in the OS keyring, never in
+    /// `media_server.json`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// Request/response log written by a translation job started with `debug_log`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDebugLog {
+    pub path: String,
+    pub content: String,
+}
+
+/// Why a connection probe failed, so the settings can point at what to fix.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionErrorKind {
+    BadKey,
+    Unreachable,
+    ModelMissing,
+    RateLimited,
+    /// The model answered, but not with the requested translations.
+    BadResponse,
+    Other,
+}
+
+/// Outcome of `test_llm_connection`'s two-line probe batch.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmConnectionReport {
+    pub success: bool,
+    pub latency_ms: u64,
+    /// The model answered with bare JSON, without fences or reasoning text to strip.
+    pub json_mode: bool,
+    pub auth_error: bool,
+    pub message: String,
+    pub translations: Vec<TranslatedLine>,
+    /// Model the probe was sent to.
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub error_kind: Option<ConnectionErrorKind>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTemplate {
+    pub name: String,
+    /// Prompt text with `{source_lang}`, `{target_lang}`, `{series_context}` and
+    /// `{glossary}` placeholders.
+    pub content: String,
+}
+
+/// Per-series notes kept next to the episodes and prepended to translation prompts.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesContext {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub anilist_id: Option<u64>,
+    /// Synopsis, character list and tone notes, free-form.
+    pub context: String,
+}
+
+/// Fields recognised in a release filename such as "[Group] Title - 05 [1080p].mkv".
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseInfo {
+    pub title: String,
+    #[serde(default)]
+    pub release_group: Option<String>,
+    #[serde(default)]
+    pub episode: Option<u32>,
+    #[serde(default)]
+    pub season: Option<u32>,
+    #[serde(default)]
+    pub resolution: Option<String>,
+    /// Release source such as "BD", "WEB-DL" or "HDTV".
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Revision from "05v2" style episode numbers.
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+/// Files of one series from `group_release_files`, ordered by season and episode.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseSeries {
+    pub title: String,
+    pub files: Vec<ReleaseFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseFile {
+    pub path: String,
+    pub release: ReleaseInfo,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimeMetadata {
+    pub anilist_id: u64,
+    #[serde(default)]
+    pub mal_id: Option<u64>,
+    pub title_romaji: String,
+    #[serde(default)]
+    pub title_english: Option<String>,
+    #[serde(default)]
+    pub title_native: Option<String>,
+    #[serde(default)]
+    pub episodes: Option<u32>,
+    #[serde(default)]
+    pub season_year: Option<u32>,
+    #[serde(default)]
+    pub cover_image: Option<String>,
+    #[serde(default)]
+    pub episode: Option<u32>,
+    #[serde(default)]
+    pub episode_title: Option<String>,
+    #[serde(default)]
+    pub release: Option<ReleaseInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionEngine {
+    #[default]
+    WhisperCpp,
+    OpenAi,
+    Deepgram,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionOptions {
+    #[serde(default)]
+    pub engine: TranscriptionEngine,
+    /// Spoken language passed to the engine; defaults to Japanese.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Audio track to transcribe; defaults to the first Japanese track.
+    #[serde(default)]
+    pub audio_track: Option<u32>,
+    /// whisper.cpp binary; `whisper-cli` is looked up in PATH otherwise.
+    #[serde(default)]
+    pub whisper_path: Option<String>,
+    /// ggml model file for whisper.cpp.
+    #[serde(default)]
+    pub whisper_model: Option<String>,
+    /// API key for OpenAI/Deepgram; falls back to the key stored for that provider.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// A loose subtitle file next to a video, e.g. "Show - 01.en.ass".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SidecarSubtitle {
+    pub path: String,
+    pub format: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Remaining name parts such as a fansub group ("Show - 01.[Group].ass").
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub forced: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedVideo {
+    pub path: String,
+    pub sidecars: Vec<SidecarSubtitle>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectFileStatus {
+    #[default]
+    Pending,
+    Done,
+    Failed,
+    /// Left untranslated on purpose, e.g. already in the target language.
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectFile {
+    pub path: String,
+    #[serde(default)]
+    pub subtitle_track: Option<u32>,
+    #[serde(default)]
+    pub reference_track: Option<u32>,
+    #[serde(default)]
+    pub status: ProjectFileStatus,
+    #[serde(default)]
+    pub output_path: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// A multi-episode session saved as a `.animesubs` file. The glossary and prompt
+/// template travel inside `config` (`name_table`, `prompt_template`); the API key never does.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationProject {
+    #[serde(default)]
+    pub version: u32,
+    pub name: String,
+    pub folder: String,
+    #[serde(default)]
+    pub files: Vec<ProjectFile>,
+    pub source_lang: String,
+    pub target_lang: String,
+    pub config: LLMConfig,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProject {
+    pub path: String,
+    pub name: String,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Folder the project's videos came from.
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub total_files: usize,
+    /// Videos whose translation finished, as of the last save or job.
+    #[serde(default)]
+    pub done_files: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOutcome {
+    Success,
+    Failed,
+    Cancelled,
+}
+
+/// One line of the operation history (`history.jsonl` in the app data directory).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: String,
+    /// "extract", "embed", "remove_track", "restore", "translate", ...
+    pub operation: String,
+    pub video_path: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub outcome: HistoryOutcome,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Command line for ffmpeg/mkvmerge runs, job settings for translations.
+    #[serde(default)]
+    pub parameters: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSpaceCheck {
+    /// Directory whose filesystem was checked.
+    pub path: String,
+    pub required_bytes: u64,
+    pub available_bytes: u64,
+    pub sufficient: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanCleanupResult {
+    pub removed: Vec<String>,
+    /// Recorded temp files that could not be deleted.
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRetention {
+    /// Newest backups kept per video and track.
+    #[serde(default)]
+    pub keep_last: Option<usize>,
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+/// A subtitle file to mux into a video, with the flags of the new track.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleEmbed {
+    pub path: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub default: bool,
+    #[serde(default)]
+    pub forced: bool,
+    /// Zero-based position among the output's subtitle tracks; appended last when unset.
+    #[serde(default)]
+    pub position: Option<u32>,
+}
+
+/// Track flags and placement for `embed_subtitle`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedTrackOptions {
+    #[serde(default)]
+    pub forced: bool,
+    #[serde(default)]
+    pub position: Option<u32>,
+    /// Drops the default flag from the subtitle tracks already in the video.
+    #[serde(default)]
+    pub clear_existing_defaults: bool,
+    #[serde(default)]
+    pub on_incompatible: IncompatibleSubtitleAction,
+}
+
+/// What embedding does when the container cannot hold a subtitle as-is
+/// (e.g. ASS into MP4, which only takes mov_text).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IncompatibleSubtitleAction {
+    /// Convert to the container's text codec, dropping styling.
+    #[default]
+    Convert,
+    /// Write an MKV next to the source instead.
+    Mkv,
+    /// Refuse and leave the video untouched.
+    Fail,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleCompatibility {
+    pub path: String,
+    pub format: String,
+    /// Codec the subtitle would be stored as; `None` when the container cannot hold it.
+    #[serde(default)]
+    pub codec: Option<String>,
+    /// Styling/positioning is lost by the conversion.
+    #[serde(default)]
+    pub lossy: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbedCompatibilityReport {
+    pub container: String,
+    pub subtitles: Vec<SubtitleCompatibility>,
+    /// Every subtitle fits the container without conversion loss.
+    pub compatible: bool,
+    /// Container to remux into when `compatible` is false.
+    #[serde(default)]
+    pub suggested_container: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSettings {
+    /// `tracing` filter directives, e.g. `info` or `warn,animesubs_lib::providers=debug`.
+    pub level: String,
+    /// Directory holding the rolling log files; only filled in by the commands.
+    #[serde(default)]
+    pub log_dir: Option<String>,
+}
+
+/// One backend log event, as stored for `tail_logs` and emitted on `backend-log`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    /// Module that logged the event, e.g. `animesubs_lib::providers`.
+    pub target: String,
+    pub message: String,
+}
+
+/// Rewrites paths reported by another machine or container, e.g. Sonarr's
+/// `/tv` to the local `/mnt/media/tv`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMapping {
+    pub from: String,
+    pub to: String,
+}
+
+/// Automatic translation of imported episodes and movies, saved in `automation.json`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Name of the saved preset providing provider, model, prompt and languages.
+    #[serde(default)]
+    pub preset: String,
+    /// Same values as the GUI's output format; empty keeps the source format.
+    #[serde(default)]
+    pub output_format: String,
+    #[serde(default)]
+    pub embed_subtitles: bool,
+    #[serde(default)]
+    pub path_mappings: Vec<PathMapping>,
+}
+
+/// Files waiting for, or going through, automatic translation.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationQueue {
+    pub queued: Vec<String>,
+    /// Files of the job running now.
+    pub running: Vec<String>,
+}
+
+/// A folder watched for new videos.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolder {
+    pub path: String,
+    /// Saved preset new videos in this folder are translated with.
+    pub preset: String,
+    /// Also watches subfolders, e.g. the season folders of a series.
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+/// Watched folders, saved in `watch.json`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchSettings {
+    /// Saved: the watch restarts with the app. Returned: the watch is running.
+    #[serde(default)]
+    pub active: bool,
+    #[serde(default)]
+    pub folders: Vec<WatchFolder>,
+}
+
+/// Remote control server settings, saved in `server.json`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerSettings {
+    /// Start the server together with the app.
+    #[serde(default)]
+    pub enabled: bool,
+    /// `host:port` to listen on; empty for `127.0.0.1:7318`. Use `0.0.0.0` to
+    /// accept other machines on the network.
+    #[serde(default)]
+    pub bind: String,
+}
+
+/// Remote control server state, for the settings screen.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub settings: ServerSettings,
+    /// Address the server listens on; `None` while it is stopped.
+    pub address: Option<String>,
+    /// Bearer token clients must send. Kept in the OS keyring.
+    pub token: String,
+}
+
+/// Backend defaults saved in `settings.json`. Commands fall back to these when a
+/// call leaves the matching argument unset.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    #[serde(default)]
+    pub version: u32,
+    /// Endpoint per provider id (`openai`, `ollama`, ...), used when a request has none.
+    #[serde(default)]
+    pub provider_endpoints: BTreeMap<String, String>,
+    #[serde(default)]
+    pub ffmpeg_path: Option<String>,
+    /// MKVToolNix folder, or the mkvmerge binary in it, tried before any other install.
+    #[serde(default)]
+    pub mkvtoolnix_path: Option<String>,
+    #[serde(default)]
+    pub batch_token_budget: Option<usize>,
+    #[serde(default)]
+    pub max_parallel_files: Option<usize>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitOptions>,
+    #[serde(default)]
+    pub naming_template: Option<String>,
+    /// SDH filter rules for jobs that don't set their own.
+    #[serde(default)]
+    pub strip_sdh: Option<SdhOptions>,
+    /// Price per model name, used to estimate the cost in job reports.
+    #[serde(default)]
+    pub model_pricing: BTreeMap<String, ModelPricing>,
+}
+
+/// USD per million tokens.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Named provider + prompt + language setup, e.g. "Gemini fast draft EN→PT-BR".
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Preset {
+    pub name: String,
+    /// Provider, model, prompt and batching parameters; API keys are never stored.
+    pub config: LLMConfig,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// Translation style the system prompt is built from (`natural`, `honorifics`, ...).
+    #[serde(default)]
+    pub style: Option<String>,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitOptions>,
+    #[serde(default)]
+    pub max_parallel_files: Option<usize>,
+    #[serde(default)]
+    pub strip_sdh: Option<SdhOptions>,
+    #[serde(default)]
+    pub honorific_second_pass: bool,
+}
+
+/// File written by `export_presets` and read by `import_presets`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetBundle {
+    #[serde(default)]
+    pub version: u32,
+    pub presets: Vec<Preset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolDownloadStage {
+    Downloading,
+    Verifying,
+    Extracting,
+    Done,
+}
+
+/// Payload of `tool-download-progress` while `download_ffmpeg` or
+/// `download_mkvtoolnix` runs.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDownloadProgress {
+    /// `ffmpeg` or `mkvtoolnix`.
+    pub tool: String,
+    /// Archive being handled; empty once everything is installed.
+    pub file_name: String,
+    pub stage: ToolDownloadStage,
+    pub downloaded_bytes: u64,
+    /// Size announced by the server, when it sent one.
+    #[serde(default)]
+    pub total_bytes: Option<u64>,
 }
@@ -1,10 +1,15 @@
+use crate::error::AnimeSubsError;
 use crate::models::{LLMConfig, TranslatedLine, TranslationLine};
-use crate::utils::build_translation_prompt;
 use regex::Regex;
-use reqwest::Client;
+use reqwest::StatusCode;
+use std::time::Duration;
+use tracing::{debug, info};
 
+use super::debug_log;
+use super::http::{self, Timeouts};
 use super::{
-    build_gemini_generate_content_endpoint, extract_response_content, parse_translation_response_content,
+    build_azure_chat_endpoint, build_gemini_generate_content_endpoint, build_system_prompt,
+    extract_response_content, parse_translation_response_content, uses_json_response_format,
     ProviderRequest, ResponseFormat,
 };
 
@@ -15,8 +20,8 @@ pub(crate) fn build_provider_request_with_context(
     source_lang: &str,
     target_lang: &str,
     compact_context: Option<&str>,
-) -> Result<ProviderRequest, String> {
-    let mut system_prompt = build_translation_prompt(&config.system_prompt, source_lang, target_lang);
+) -> Result<ProviderRequest, AnimeSubsError> {
+    let mut system_prompt = build_system_prompt(config, lines, source_lang, target_lang);
     if let Some(ctx) = compact_context.filter(|c| !c.trim().is_empty()) {
         system_prompt = format!(
             "{}\n\nCONTEXT FROM PREVIOUS SUBTITLES (characters, plot, terminology):\n{}",
@@ -29,28 +34,42 @@ pub(crate) fn build_provider_request_with_context(
     let uses_ollama_native_api = provider == "ollama" && !config.endpoint.contains("/v1");
     let is_openai_compatible = matches!(
         provider.as_str(),
-        "openai" | "openrouter" | "custom" | "minimax" | "nvidia" | "lmstudio" | "llamacpp"
+        "openai"
+            | "openrouter"
+            | "custom"
+            | "minimax"
+            | "nvidia"
+            | "lmstudio"
+            | "llamacpp"
+            | "vllm"
+            | "azure"
     ) || is_gemini_openai_compat
         || (provider == "ollama" && !uses_ollama_native_api);
 
     if is_openai_compatible {
         let base = config.endpoint.trim_end_matches('/');
-        let endpoint_url = if base.ends_with("/chat/completions") {
+        let endpoint_url = if provider == "azure" {
+            build_azure_chat_endpoint(config)
+        } else if base.ends_with("/chat/completions") {
             base.to_string()
         } else {
             format!("{}/chat/completions", base)
         };
 
+        let mut body = serde_json::json!({
+            "model": config.model,
+            "messages": [
+                {"role": "system", "content": system_prompt},
+                {"role": "user", "content": user_content.to_string()}
+            ],
+            "temperature": 0.3
+        });
+        if uses_json_response_format(config, &provider) {
+            body["response_format"] = serde_json::json!({"type": "json_object"});
+        }
+
         return Ok(ProviderRequest {
-            body: serde_json::json!({
-                "model": config.model,
-                "messages": [
-                    {"role": "system", "content": system_prompt},
-                    {"role": "user", "content": user_content.to_string()}
-                ],
-                "temperature": 0.3,
-                "response_format": {"type": "json_object"}
-            }),
+            body,
             endpoint_url,
             response_format: ResponseFormat::OpenAiCompatible,
             provider,
@@ -113,7 +132,7 @@ pub(crate) fn build_provider_request_with_context(
         });
     }
 
-    Err(format!("Unsupported provider: {}", config.provider))
+    Err(format!("Unsupported provider: {}", config.provider).into())
 }
 
 /// Calls the LLM API with optional compacted context from previous translation chunks.
@@ -123,8 +142,9 @@ pub async fn call_llm_api_with_context(
     source_lang: &str,
     target_lang: &str,
     compact_context: Option<&str>,
-) -> Result<Vec<TranslatedLine>, String> {
-    let client = Client::new();
+) -> Result<Vec<TranslatedLine>, AnimeSubsError> {
+    let timeouts = Timeouts::from_config(config);
+    let client = http::llm_client(&timeouts)?;
     let provider_request = build_provider_request_with_context(
         config,
         lines,
@@ -140,6 +160,9 @@ pub async fn call_llm_api_with_context(
         request = request.header("Authorization", format!("Bearer {}", config.api_key));
     } else {
         match provider_request.provider.as_str() {
+            "azure" if !config.api_key.is_empty() => {
+                request = request.header("api-key", &config.api_key);
+            }
             "openai" | "openrouter" | "custom" | "minimax" | "nvidia" => {
                 if !config.api_key.is_empty() {
                     request =
@@ -153,30 +176,48 @@ pub async fn call_llm_api_with_context(
         }
     }
 
-    eprintln!(
+    info!(
         "Calling LLM API (context-aware): {} with model {}",
         provider_request.endpoint_url, config.model
     );
 
-    let response = request
-        .send()
+    let url = provider_request.endpoint_url.as_str();
+    debug_log::record_request(config, url, &provider_request.body);
+    let response = http::send(request, &timeouts, "Failed to call LLM API")
         .await
-        .map_err(|e| format!("Failed to call LLM API: {}", e))?;
+        .inspect_err(|e| debug_log::record(config, "ERROR", url, &e.to_string()))?;
 
     if !response.status().is_success() {
         let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("LLM API error ({}): {}", status, error_text));
+        debug_log::record(config, &format!("RESPONSE {}", status), url, &error_text);
+        let message = match retry_after.filter(|_| status == StatusCode::TOO_MANY_REQUESTS) {
+            Some(seconds) => format!(
+                "LLM API error ({}, retry after {}s): {}",
+                status, seconds, error_text
+            ),
+            None => format!("LLM API error ({}): {}", status, error_text),
+        };
+        return Err(http::status_error(
+            status,
+            retry_after.map(Duration::from_secs),
+            message,
+        ));
     }
 
-    let response_json: serde_json::Value = response
-        .json()
+    let response_json = http::read_json(response, &timeouts, "LLM response")
         .await
-        .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+        .inspect_err(|e| debug_log::record(config, "ERROR", url, &e.to_string()))?;
+    debug_log::record_response(config, url, &response_json);
 
     let content = extract_response_content(&response_json, provider_request.response_format)?;
 
-    eprintln!("LLM response content: {}", content);
+    debug!("LLM response content: {}", content);
     parse_translation_response_content(&content)
 }
 
@@ -187,7 +228,7 @@ pub async fn generate_compaction_summary(
     translated_pairs: &[String],
     source_lang: &str,
     target_lang: &str,
-) -> Result<String, String> {
+) -> Result<String, AnimeSubsError> {
     let pairs_text = translated_pairs.join("\n");
     let prompt = format!(
         "You are a translation context summarizer.\n\
@@ -209,7 +250,8 @@ pub async fn generate_compaction_summary(
         source_lang, target_lang, pairs_text
     );
 
-    let client = Client::new();
+    let timeouts = Timeouts::from_config(config);
+    let client = http::llm_client(&timeouts)?;
     let provider = config.provider.trim().to_ascii_lowercase();
     let is_gemini_openai_compat =
         provider == "gemini" && config.endpoint.contains("/openai");
@@ -217,13 +259,23 @@ pub async fn generate_compaction_summary(
         provider == "ollama" && !config.endpoint.contains("/v1");
     let is_openai_compatible = matches!(
         provider.as_str(),
-        "openai" | "openrouter" | "custom" | "minimax" | "nvidia" | "lmstudio" | "llamacpp"
+        "openai"
+            | "openrouter"
+            | "custom"
+            | "minimax"
+            | "nvidia"
+            | "lmstudio"
+            | "llamacpp"
+            | "vllm"
+            | "azure"
     ) || is_gemini_openai_compat
         || (provider == "ollama" && !uses_ollama_native_api);
 
     let (endpoint_url, body) = if is_openai_compatible {
         let base = config.endpoint.trim_end_matches('/');
-        let url = if base.ends_with("/chat/completions") {
+        let url = if provider == "azure" {
+            build_azure_chat_endpoint(config)
+        } else if base.ends_with("/chat/completions") {
             base.to_string()
         } else {
             format!("{}/chat/completions", base)
@@ -279,7 +331,7 @@ pub async fn generate_compaction_summary(
             }),
         )
     } else {
-        return Err(format!("Unsupported provider: {}", config.provider));
+        return Err(format!("Unsupported provider: {}", config.provider).into());
     };
 
     let mut request = client.post(&endpoint_url).json(&body);
@@ -288,6 +340,9 @@ pub async fn generate_compaction_summary(
         request = request.header("Authorization", format!("Bearer {}", config.api_key));
     } else {
         match provider.as_str() {
+            "azure" if !config.api_key.is_empty() => {
+                request = request.header("api-key", &config.api_key);
+            }
             "openai" | "openrouter" | "custom" | "minimax" | "nvidia"
                 if !config.api_key.is_empty() =>
             {
@@ -298,21 +353,27 @@ pub async fn generate_compaction_summary(
         }
     }
 
-    let response = request
-        .send()
+    let url = endpoint_url.as_str();
+    debug_log::record_request(config, url, &body);
+    let response = http::send(request, &timeouts, "Compaction request failed")
         .await
-        .map_err(|e| format!("Compaction request failed: {}", e))?;
+        .inspect_err(|e| debug_log::record(config, "ERROR", url, &e.to_string()))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("Compaction API error ({}): {}", status, error_text));
+        debug_log::record(config, &format!("RESPONSE {}", status), url, &error_text);
+        return Err(http::status_error(
+            status,
+            None,
+            format!("Compaction API error ({}): {}", status, error_text),
+        ));
     }
 
-    let response_json: serde_json::Value = response
-        .json()
+    let response_json = http::read_json(response, &timeouts, "compaction response")
         .await
-        .map_err(|e| format!("Failed to parse compaction response: {}", e))?;
+        .inspect_err(|e| debug_log::record(config, "ERROR", url, &e.to_string()))?;
+    debug_log::record_response(config, url, &response_json);
 
     let content = if uses_ollama_native_api && !is_gemini_openai_compat {
         response_json["message"]["content"]
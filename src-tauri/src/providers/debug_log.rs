@@ -0,0 +1,156 @@
+use crate::error::AnimeSubsError;
+use crate::models::LLMConfig;
+use regex::Regex;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+const REDACTED: &str = "[REDACTED]";
+/// Shorter values are too likely to appear in normal text to be replaced blindly.
+const MIN_SECRET_LEN: usize = 6;
+
+static KEY_PATTERNS: LazyLock<[(Regex, &'static str); 3]> = LazyLock::new(|| {
+    [
+        (
+            Regex::new(r"(?i)\b((?:api[-_]?)?key=)[^&\s]+").unwrap(),
+            "${1}[REDACTED]",
+        ),
+        (
+            Regex::new(r"(?i)\b(bearer\s+)[A-Za-z0-9._~+/=-]+").unwrap(),
+            "${1}[REDACTED]",
+        ),
+        (
+            Regex::new(r"\b(?:sk-|sk-or-|AIza|nvapi-)[A-Za-z0-9_-]{8,}").unwrap(),
+            REDACTED,
+        ),
+    ]
+});
+
+/// Replaces `secrets` and anything shaped like an API key in `text`.
+pub(crate) fn redact(text: &str, secrets: &[String]) -> String {
+    let mut redacted = text.to_string();
+    for secret in secrets.iter().filter(|s| s.len() >= MIN_SECRET_LEN) {
+        redacted = redacted.replace(secret.as_str(), REDACTED);
+    }
+    for (pattern, replacement) in KEY_PATTERNS.iter() {
+        redacted = pattern.replace_all(&redacted, *replacement).into_owned();
+    }
+    redacted
+}
+
+/// Per-job log of every LLM request and response, with API keys redacted.
+#[derive(Debug)]
+pub struct DebugLog {
+    path: PathBuf,
+    file: Mutex<File>,
+    secrets: Mutex<Vec<String>>,
+}
+
+impl DebugLog {
+    pub fn create(path: &Path) -> Result<Self, AnimeSubsError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to create debug log: {}", e)))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+            secrets: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Adds a value (API key, proxy password) that must never reach the file.
+    pub fn add_secret(&self, secret: &str) {
+        if secret.len() >= MIN_SECRET_LEN {
+            let mut secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+            if !secrets.iter().any(|s| s == secret) {
+                secrets.push(secret.to_string());
+            }
+        }
+    }
+
+    pub fn write(&self, kind: &str, url: &str, text: &str) {
+        let entry = {
+            let secrets = self.secrets.lock().unwrap_or_else(|e| e.into_inner());
+            format!(
+                "[{}] {} {}\n{}\n\n",
+                chrono::Local::now().to_rfc3339(),
+                kind,
+                redact(url, &secrets),
+                redact(text, &secrets)
+            )
+        };
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        // Logging must never fail a translation.
+        let _ = file.write_all(entry.as_bytes());
+    }
+}
+
+/// Writes to `config.debug_log` when the job enabled it.
+pub(crate) fn record(config: &LLMConfig, kind: &str, url: &str, text: &str) {
+    if let Some(log) = &config.debug_log {
+        log.write(kind, url, text);
+    }
+}
+
+pub(crate) fn record_request(config: &LLMConfig, url: &str, body: &serde_json::Value) {
+    if config.debug_log.is_some() {
+        let text = serde_json::to_string_pretty(body).unwrap_or_default();
+        record(config, &format!("REQUEST {}", config.model), url, &text);
+    }
+}
+
+pub(crate) fn record_response(config: &LLMConfig, url: &str, body: &serde_json::Value) {
+    if config.debug_log.is_some() {
+        let text = serde_json::to_string_pretty(body).unwrap_or_default();
+        record(config, "RESPONSE", url, &text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_known_secrets_and_key_shaped_values() {
+        let secrets = vec!["my-azure-resource-key".to_string(), "abc".to_string()];
+        let text = "url=https://x/v1beta/models/m:generateContent?key=AIzaSyExample123 \
+                    Authorization: Bearer tok.en-123 api-key=my-azure-resource-key \
+                    leaked sk-proj1234567890abcdef and abc stays";
+        let redacted = redact(text, &secrets);
+
+        assert!(!redacted.contains("AIzaSyExample123"));
+        assert!(!redacted.contains("tok.en-123"));
+        assert!(!redacted.contains("my-azure-resource-key"));
+        assert!(!redacted.contains("sk-proj1234567890abcdef"));
+        assert!(redacted.contains("key=[REDACTED]"));
+        assert!(redacted.contains("Bearer [REDACTED]"));
+        assert!(redacted.contains("abc stays"));
+    }
+
+    #[test]
+    fn writes_redacted_entries_to_the_file() {
+        let dir = std::env::temp_dir().join(format!("animesubs-debug-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("job.log");
+        let log = DebugLog::create(&path).unwrap();
+        log.add_secret("secret-key-value");
+        log.write(
+            "REQUEST",
+            "http://localhost/chat/completions",
+            "{\"auth\":\"secret-key-value\",\"text\":\"Olá\"}",
+        );
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("REQUEST http://localhost/chat/completions"));
+        assert!(content.contains("Olá"));
+        assert!(!content.contains("secret-key-value"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,188 @@
+use crate::models::{TranslatedLine, TranslationResponse};
+
+/// Returns the body of the first Markdown code fence, or `content` without one.
+fn strip_code_fence(content: &str) -> &str {
+    let trimmed = content.trim();
+    let Some(start) = trimmed.find("```") else {
+        return trimmed;
+    };
+    let after = &trimmed[start + 3..];
+    let tag_len = after
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .count();
+    let body = &after[tag_len..];
+    match body.find("```") {
+        Some(end) => &body[..end],
+        None => body,
+    }
+}
+
+/// Drops a comma (and whitespace after it) at the end of `out`.
+fn trim_trailing_comma(out: &mut String) {
+    let trimmed_len = out.trim_end().len();
+    if out[..trimmed_len].ends_with(',') {
+        out.truncate(trimmed_len - 1);
+    }
+}
+
+/// Best-effort repair of almost-JSON from an LLM: code fences and surrounding prose,
+/// trailing commas, raw newlines and unescaped quotes inside strings, and output cut
+/// off mid-array (complete entries are kept, the partial one is dropped).
+pub(crate) fn repair_json(content: &str) -> String {
+    let content = strip_code_fence(content);
+    let Some(start) = content.find(['{', '[']) else {
+        return content.to_string();
+    };
+    let chars: Vec<char> = content[start..].chars().collect();
+
+    let mut out = String::with_capacity(content.len() - start);
+    let mut closers: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Output length and open brackets right after the last complete array element.
+    let mut checkpoint: Option<(usize, Vec<char>)> = None;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+                out.push(c);
+                continue;
+            }
+            match c {
+                '\\' => {
+                    escaped = true;
+                    out.push(c);
+                }
+                '"' => {
+                    // A quote only ends the string when JSON structure follows it.
+                    let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                    if matches!(next, None | Some(',' | '}' | ']' | ':')) {
+                        in_string = false;
+                        out.push(c);
+                    } else {
+                        out.push_str("\\\"");
+                    }
+                }
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                '\r' => {}
+                _ => out.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '{' => {
+                closers.push('}');
+                out.push(c);
+            }
+            '[' => {
+                closers.push(']');
+                out.push(c);
+            }
+            '}' | ']' => {
+                if closers.last() != Some(&c) {
+                    continue;
+                }
+                trim_trailing_comma(&mut out);
+                closers.pop();
+                out.push(c);
+                if closers.is_empty() {
+                    return out;
+                }
+                if closers.last() == Some(&']') {
+                    checkpoint = Some((out.len(), closers.clone()));
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    // Truncated response: fall back to the last complete element and close the rest.
+    if let Some((len, open)) = checkpoint {
+        out.truncate(len);
+        closers = open;
+    } else if in_string {
+        out.push('"');
+    }
+    trim_trailing_comma(&mut out);
+    while let Some(closer) = closers.pop() {
+        out.push(closer);
+    }
+    out
+}
+
+/// Parses `content` after `repair_json`, accepting either `{"translations": [...]}`
+/// or a bare array of lines.
+pub(crate) fn parse_repaired_translations(content: &str) -> Option<Vec<TranslatedLine>> {
+    let repaired = repair_json(content);
+    serde_json::from_str::<TranslationResponse>(&repaired)
+        .map(|response| response.translations)
+        .or_else(|_| serde_json::from_str::<Vec<TranslatedLine>>(&repaired))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(content: &str) -> Vec<(usize, String)> {
+        parse_repaired_translations(content)
+            .unwrap_or_else(|| panic!("could not repair: {}", repair_json(content)))
+            .into_iter()
+            .map(|t| (t.id, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn repairs_fences_and_trailing_commas() {
+        let content = "Here you go:\n```json\n{\"translations\": [\n  {\"id\": 0, \"text\": \"Olá\"},\n  {\"id\": 1, \"text\": \"Mundo\",},\n],}\n```\nLet me know!";
+        assert_eq!(
+            texts(content),
+            [(0, "Olá".to_string()), (1, "Mundo".to_string())]
+        );
+    }
+
+    #[test]
+    fn escapes_raw_newlines_and_stray_quotes() {
+        let content =
+            "{\"translations\":[{\"id\":3,\"text\":\"Ele disse \"oi\"\nde novo\"},{\"id\":4,\"text\":\"Tab\there\"}]}";
+        assert_eq!(
+            texts(content),
+            [
+                (3, "Ele disse \"oi\"\nde novo".to_string()),
+                (4, "Tab\there".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn salvages_complete_entries_from_truncated_output() {
+        let content = r#"{"translations": [{"id": 1, "text": "Um"}, {"id": 2, "text": "Dois"}, {"id": 3, "te"#;
+        assert_eq!(
+            texts(content),
+            [(1, "Um".to_string()), (2, "Dois".to_string())]
+        );
+
+        let cut_in_string = r#"{"translations": [{"id": 1, "text": "Um"}, {"id": 2, "text": "Do"#;
+        assert_eq!(texts(cut_in_string), [(1, "Um".to_string())]);
+    }
+
+    #[test]
+    fn accepts_a_bare_array() {
+        let content = "```\n[{\"id\": 5, \"text\": \"Sim\"}]\n```";
+        assert_eq!(texts(content), [(5, "Sim".to_string())]);
+    }
+
+    #[test]
+    fn leaves_prose_without_json_unparsed() {
+        assert!(parse_repaired_translations("I cannot translate this.").is_none());
+        assert_eq!(repair_json("{\"a\": [1, 2,]} trailing"), "{\"a\": [1, 2]}");
+    }
+}
@@ -1,11 +1,20 @@
 pub mod context;
+pub mod debug_log;
+pub(crate) mod http;
+mod json_repair;
+pub mod rate_limit;
 
 pub use context::{call_llm_api_with_context, generate_compaction_summary};
 
-use crate::models::{LLMConfig, TranslatedLine, TranslationLine, TranslationResponse};
-use crate::utils::{build_translation_prompt, clean_json_response};
+use crate::error::AnimeSubsError;
+use crate::models::{
+    CharacterName, LLMConfig, TranslatedLine, TranslationLine, TranslationResponse,
+};
+use crate::utils::{
+    build_translation_prompt, clean_json_response, render_prompt_template, TRANSLATION_RULES,
+};
 use regex::Regex;
-use reqwest::Client;
+use tracing::{debug, info, warn};
 
 #[derive(Debug)]
 pub(crate) struct ProviderRequest {
@@ -45,41 +54,271 @@ pub(crate) fn build_gemini_generate_content_endpoint(
     format!("{}/models/{}:generateContent?key={}", base, model, api_key)
 }
 
+const AZURE_DEFAULT_API_VERSION: &str = "2024-10-21";
+
+/// Azure OpenAI chat URL for the configured deployment. The endpoint is either the
+/// resource URL or a template with `{deployment}` and `{api_version}` placeholders.
+pub(crate) fn build_azure_chat_endpoint(config: &LLMConfig) -> String {
+    let deployment = config
+        .azure_deployment
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .unwrap_or(config.model.trim());
+    let api_version = config
+        .azure_api_version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(AZURE_DEFAULT_API_VERSION);
+
+    let mut url = config
+        .endpoint
+        .trim()
+        .replace("{deployment}", deployment)
+        .replace("{api_version}", api_version);
+    let (base, query) = match url.split_once('?') {
+        Some((base, query)) => (
+            base.trim_end_matches('/').to_string(),
+            Some(query.to_string()),
+        ),
+        None => (url.trim_end_matches('/').to_string(), None),
+    };
+
+    url = if base.ends_with("/chat/completions") {
+        base
+    } else if base.contains("/openai/deployments/") {
+        format!("{}/chat/completions", base)
+    } else {
+        let resource = base.trim_end_matches("/openai");
+        format!(
+            "{}/openai/deployments/{}/chat/completions",
+            resource, deployment
+        )
+    };
+    match query {
+        Some(query) if query.contains("api-version=") => format!("{}?{}", url, query),
+        Some(query) => format!("{}?{}&api-version={}", url, query, api_version),
+        None => format!("{}?api-version={}", url, api_version),
+    }
+}
+
+/// Appends compare-and-merge rules when any line carries a reference translation.
+pub(crate) fn with_reference_instruction(
+    system_prompt: String,
+    lines: &[TranslationLine],
+) -> String {
+    if lines.iter().all(|line| line.reference.is_none()) {
+        return system_prompt;
+    }
+
+    format!(
+        "{}\n\nREFERENCE TRACK:\n\
+         Some lines include a \"reference\" field: an existing translation of the same \
+         line taken from another subtitle track. Treat \"text\" as the source of truth \
+         and use the reference to resolve ambiguity, names, and intent, producing a \
+         better target-language line than either version alone. Never copy the \
+         \"reference\" field into the output.",
+        system_prompt
+    )
+}
+
+/// Turns the request into a proofreading pass when lines carry a draft translation.
+pub(crate) fn with_draft_instruction(system_prompt: String, lines: &[TranslationLine]) -> String {
+    if lines.iter().all(|line| line.draft.is_none()) {
+        return system_prompt;
+    }
+
+    format!(
+        "{}\n\nPROOFREADING PASS:\n\
+         Lines include a \"draft\" field: a first-pass translation of \"text\". Proofread \
+         each draft against the source, fix mistranslations, awkward phrasing, and \
+         inconsistent terminology, and return the improved translation in \"text\". \
+         If a draft is already good, return it unchanged. Keep every id.",
+        system_prompt
+    )
+}
+
+/// Explains the `rejected` field on lines re-sent after failing the output checks.
+pub(crate) fn with_correction_instruction(
+    system_prompt: String,
+    lines: &[TranslationLine],
+) -> String {
+    if lines.iter().all(|line| line.rejected.is_none()) {
+        return system_prompt;
+    }
+
+    format!(
+        "{}\n\nCORRECTIONS:\n\
+         Some lines include a \"rejected\" field: your previous answer for that line was \
+         refused for the reason given. Return a complete translation of \"text\" in the \
+         target language with no apologies, notes, JSON, or untranslated source text. \
+         Never copy the \"rejected\" field into the output.",
+        system_prompt
+    )
+}
+
+const MAX_PROMPT_NAMES: usize = 80;
+
+fn name_table_entries(names: &[CharacterName]) -> Vec<String> {
+    names
+        .iter()
+        .filter(|name| !name.source.trim().is_empty())
+        .take(MAX_PROMPT_NAMES)
+        .map(|name| {
+            let canonical = name.canonical.trim();
+            if canonical.is_empty() || canonical == name.source {
+                format!("- {}", name.source)
+            } else {
+                format!("- {} → {}", name.source, canonical)
+            }
+        })
+        .collect()
+}
+
+/// Lists the character-name table so spellings stay consistent across batches and episodes.
+pub(crate) fn with_name_table(system_prompt: String, names: &[CharacterName]) -> String {
+    let entries = name_table_entries(names);
+    if entries.is_empty() {
+        return system_prompt;
+    }
+
+    format!(
+        "{}\n\nCHARACTER NAMES:\n\
+         Always write these names exactly as given (source → required spelling):\n{}",
+        system_prompt,
+        entries.join("\n")
+    )
+}
+
+/// Puts the show's synopsis and tone notes ahead of the translation instructions.
+pub(crate) fn with_series_context(system_prompt: String, series_context: Option<&str>) -> String {
+    let context = series_context.map(str::trim).unwrap_or_default();
+    if context.is_empty() {
+        return system_prompt;
+    }
+
+    format!(
+        "SERIES CONTEXT (background for tone, names and references; do not translate it):\n\
+         {}\n\n{}",
+        context, system_prompt
+    )
+}
+
+/// Renders a user prompt template and appends the JSON output rules, which templates
+/// cannot override. The name table is only appended when `{glossary}` is not used.
+fn build_template_prompt(
+    template: &str,
+    config: &LLMConfig,
+    source_lang: &str,
+    target_lang: &str,
+) -> String {
+    let glossary = name_table_entries(&config.name_table).join("\n");
+    let series_context = config.series_context.as_deref().unwrap_or_default().trim();
+    let rendered = render_prompt_template(
+        template,
+        &[
+            ("source_lang", source_lang),
+            ("target_lang", target_lang),
+            ("series_context", series_context),
+            ("glossary", &glossary),
+        ],
+    );
+
+    let mut prompt = format!("{}\n\n{}", rendered.trim(), TRANSLATION_RULES);
+    if !template.contains("{series_context}") {
+        prompt = with_series_context(prompt, Some(series_context));
+    }
+    if template.contains("{glossary}") {
+        prompt
+    } else {
+        with_name_table(prompt, &config.name_table)
+    }
+}
+
+/// Style prompt (or the selected template) plus the optional name table,
+/// reference-track, proofreading and correction rules.
+pub(crate) fn build_system_prompt(
+    config: &LLMConfig,
+    lines: &[TranslationLine],
+    source_lang: &str,
+    target_lang: &str,
+) -> String {
+    let system_prompt = match config.template_content.as_deref() {
+        Some(template) => build_template_prompt(template, config, source_lang, target_lang),
+        None => {
+            let system_prompt =
+                build_translation_prompt(&config.system_prompt, source_lang, target_lang);
+            with_name_table(
+                with_series_context(system_prompt, config.series_context.as_deref()),
+                &config.name_table,
+            )
+        }
+    };
+    with_correction_instruction(
+        with_draft_instruction(with_reference_instruction(system_prompt, lines), lines),
+        lines,
+    )
+}
+
+/// Whether OpenAI-compatible requests ask for `response_format: json_object`. Some
+/// llama.cpp server builds reject the field and vLLM only honours it with guided
+/// decoding enabled, so those presets rely on the prompt's JSON instructions instead.
+pub(crate) fn uses_json_response_format(config: &LLMConfig, provider: &str) -> bool {
+    config
+        .json_mode
+        .unwrap_or(!matches!(provider, "llamacpp" | "vllm"))
+}
+
 fn build_provider_request(
     config: &LLMConfig,
     lines: &[TranslationLine],
     source_lang: &str,
     target_lang: &str,
-) -> Result<ProviderRequest, String> {
-    let system_prompt = build_translation_prompt(&config.system_prompt, source_lang, target_lang);
+) -> Result<ProviderRequest, AnimeSubsError> {
+    let system_prompt = build_system_prompt(config, lines, source_lang, target_lang);
     let user_content = serde_json::json!({ "lines": lines });
     let provider = config.provider.trim().to_ascii_lowercase();
     let is_gemini_openai_compat = provider == "gemini" && config.endpoint.contains("/openai");
     let uses_ollama_native_api = provider == "ollama" && !config.endpoint.contains("/v1");
     let is_openai_compatible = matches!(
         provider.as_str(),
-        "openai" | "openrouter" | "custom" | "minimax" | "nvidia" | "lmstudio" | "llamacpp"
+        "openai"
+            | "openrouter"
+            | "custom"
+            | "minimax"
+            | "nvidia"
+            | "lmstudio"
+            | "llamacpp"
+            | "vllm"
+            | "azure"
     ) || is_gemini_openai_compat
         || (provider == "ollama" && !uses_ollama_native_api);
 
     if is_openai_compatible {
         let base = config.endpoint.trim_end_matches('/');
-        let endpoint_url = if base.ends_with("/chat/completions") {
+        let endpoint_url = if provider == "azure" {
+            build_azure_chat_endpoint(config)
+        } else if base.ends_with("/chat/completions") {
             base.to_string()
         } else {
             format!("{}/chat/completions", base)
         };
 
+        let mut body = serde_json::json!({
+            "model": config.model,
+            "messages": [
+                {"role": "system", "content": system_prompt},
+                {"role": "user", "content": user_content.to_string()}
+            ],
+            "temperature": 0.3
+        });
+        if uses_json_response_format(config, &provider) {
+            body["response_format"] = serde_json::json!({"type": "json_object"});
+        }
+
         return Ok(ProviderRequest {
-            body: serde_json::json!({
-                "model": config.model,
-                "messages": [
-                    {"role": "system", "content": system_prompt},
-                    {"role": "user", "content": user_content.to_string()}
-                ],
-                "temperature": 0.3,
-                "response_format": {"type": "json_object"}
-            }),
+            body,
             endpoint_url,
             response_format: ResponseFormat::OpenAiCompatible,
             provider,
@@ -142,7 +381,7 @@ fn build_provider_request(
         });
     }
 
-    Err(format!("Unsupported provider: {}", config.provider))
+    Err(format!("Unsupported provider: {}", config.provider).into())
 }
 
 pub async fn call_llm_api(
@@ -150,8 +389,20 @@ pub async fn call_llm_api(
     lines: &[TranslationLine],
     source_lang: &str,
     target_lang: &str,
-) -> Result<Vec<TranslatedLine>, String> {
-    let client = Client::new();
+) -> Result<Vec<TranslatedLine>, AnimeSubsError> {
+    let content = request_llm_content(config, lines, source_lang, target_lang).await?;
+    parse_translation_response_content(&content)
+}
+
+/// Sends one translation batch and returns the model's raw message content.
+pub(crate) async fn request_llm_content(
+    config: &LLMConfig,
+    lines: &[TranslationLine],
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<String, AnimeSubsError> {
+    let timeouts = http::Timeouts::from_config(config);
+    let client = http::llm_client(&timeouts)?;
     let provider_request = build_provider_request(config, lines, source_lang, target_lang)?;
     let mut request = client
         .post(&provider_request.endpoint_url)
@@ -161,6 +412,9 @@ pub async fn call_llm_api(
         request = request.header("Authorization", format!("Bearer {}", config.api_key));
     } else {
         match provider_request.provider.as_str() {
+            "azure" if !config.api_key.is_empty() => {
+                request = request.header("api-key", &config.api_key);
+            }
             "openai" | "openrouter" | "custom" | "minimax" | "nvidia" => {
                 if !config.api_key.is_empty() {
                     request = request.header("Authorization", format!("Bearer {}", config.api_key));
@@ -173,49 +427,60 @@ pub async fn call_llm_api(
         }
     }
 
-    eprintln!(
+    info!(
         "Calling LLM API: {} with model {}",
         provider_request.endpoint_url, config.model
     );
 
-    let response = request
-        .send()
+    let url = provider_request.endpoint_url.as_str();
+    debug_log::record_request(config, url, &provider_request.body);
+    let response = http::send(request, &timeouts, "Failed to call LLM API")
         .await
-        .map_err(|e| format!("Failed to call LLM API: {}", e))?;
+        .inspect_err(|e| debug_log::record(config, "ERROR", url, &e.to_string()))?;
 
     if !response.status().is_success() {
         let status = response.status();
         let error_text = response.text().await.unwrap_or_default();
-        return Err(format!("LLM API error ({}): {}", status, error_text));
+        debug_log::record(config, &format!("RESPONSE {}", status), url, &error_text);
+        return Err(http::status_error(
+            status,
+            None,
+            format!("LLM API error ({}): {}", status, error_text),
+        ));
     }
 
-    let response_json: serde_json::Value = response
-        .json()
+    let response_json = http::read_json(response, &timeouts, "LLM response")
         .await
-        .map_err(|e| format!("Failed to parse LLM response: {}", e))?;
+        .inspect_err(|e| debug_log::record(config, "ERROR", url, &e.to_string()))?;
+    debug_log::record_response(config, url, &response_json);
 
     let content = extract_response_content(&response_json, provider_request.response_format)?;
 
-    eprintln!("LLM response content: {}", content);
-    parse_translation_response_content(&content)
+    debug!("LLM response content: {}", content);
+    Ok(content)
 }
 
 pub(crate) fn parse_translation_response_content(
     content: &str,
-) -> Result<Vec<TranslatedLine>, String> {
+) -> Result<Vec<TranslatedLine>, AnimeSubsError> {
     let thinking_regex = Regex::new(r"(?is)<(?:thinking|think)>.*?</(?:thinking|think)>").unwrap();
     let content_without_thinking = thinking_regex.replace_all(content, "").to_string();
     let cleaned_content = clean_json_response(&content_without_thinking);
 
-    let translation_response: TranslationResponse = serde_json::from_str(&cleaned_content)
-        .map_err(|e| {
-            format!(
-                "Failed to parse translation JSON: {}. Response was: {}",
-                e, cleaned_content
-            )
-        })?;
-
-    Ok(translation_response.translations)
+    match serde_json::from_str::<TranslationResponse>(&cleaned_content) {
+        Ok(translation_response) => Ok(translation_response.translations),
+        Err(e) => {
+            let repaired = json_repair::parse_repaired_translations(&content_without_thinking)
+                .ok_or_else(|| {
+                    AnimeSubsError::parse(format!(
+                        "Failed to parse translation JSON: {}. Response was: {}",
+                        e, cleaned_content
+                    ))
+                })?;
+            warn!("Repaired malformed translation JSON ({})", e);
+            Ok(repaired)
+        }
+    }
 }
 
 fn text_from_content_value(value: &serde_json::Value) -> Option<String> {
@@ -244,17 +509,17 @@ fn text_from_content_value(value: &serde_json::Value) -> Option<String> {
 pub(crate) fn extract_response_content(
     response_json: &serde_json::Value,
     response_format: ResponseFormat,
-) -> Result<String, String> {
+) -> Result<String, AnimeSubsError> {
     match response_format {
         ResponseFormat::OpenAiCompatible => {
             let content = &response_json["choices"][0]["message"]["content"];
             text_from_content_value(content)
-                .ok_or_else(|| "Missing content in OpenAI response".to_string())
+                .ok_or_else(|| AnimeSubsError::parse("Missing content in OpenAI response"))
         }
         ResponseFormat::Gemini => {
             let parts = response_json["candidates"][0]["content"]["parts"]
                 .as_array()
-                .ok_or_else(|| "Missing content in Gemini response".to_string())?;
+                .ok_or_else(|| AnimeSubsError::parse("Missing content in Gemini response"))?;
             let text = parts
                 .iter()
                 .filter(|part| part.get("thought").and_then(|value| value.as_bool()) != Some(true))
@@ -263,7 +528,7 @@ pub(crate) fn extract_response_content(
                 .join("");
 
             if text.is_empty() {
-                Err("Missing content in Gemini response".to_string())
+                Err(AnimeSubsError::parse("Missing content in Gemini response"))
             } else {
                 Ok(text)
             }
@@ -271,7 +536,7 @@ pub(crate) fn extract_response_content(
         ResponseFormat::OllamaNative => response_json["message"]["content"]
             .as_str()
             .map(|content| content.to_string())
-            .ok_or_else(|| "Missing content in Ollama response".to_string()),
+            .ok_or_else(|| AnimeSubsError::parse("Missing content in Ollama response")),
     }
 }
 
@@ -303,6 +568,9 @@ mod tests {
         vec![TranslationLine {
             id: 7,
             text: "こんにちは".to_string(),
+            reference: None,
+            draft: None,
+            rejected: None,
         }]
     }
 
@@ -313,6 +581,8 @@ mod tests {
             endpoint,
             model: "test-model".to_string(),
             system_prompt: "natural".to_string(),
+            name_table: Vec::new(),
+            ..Default::default()
         }
     }
 
@@ -478,7 +748,58 @@ after JSON"#;
     fn reports_invalid_json() {
         let error = parse_translation_response_content("not json").unwrap_err();
 
-        assert!(error.contains("Failed to parse translation JSON"));
+        assert_eq!(error.code(), "PARSE_ERROR");
+        assert!(error
+            .to_string()
+            .contains("Failed to parse translation JSON"));
+    }
+
+    #[test]
+    fn system_prompt_lists_character_name_table() {
+        let mut config = config("openai", "http://api.test".to_string());
+        assert!(!build_system_prompt(&config, &sample_lines(), "ja", "en").contains("CHARACTER"));
+
+        config.name_table = vec![CharacterName {
+            source: "Kurisu".to_string(),
+            canonical: "Kurisu Makise".to_string(),
+            occurrences: 4,
+            pinned: true,
+        }];
+        let prompt = build_system_prompt(&config, &sample_lines(), "ja", "en");
+        assert!(prompt.contains("CHARACTER NAMES"));
+        assert!(prompt.contains("- Kurisu → Kurisu Makise"));
+    }
+
+    #[test]
+    fn system_prompt_renders_selected_template() {
+        let mut config = config("openai", "http://api.test".to_string());
+        config.series_context = Some("A time-travel thriller.".to_string());
+        config.name_table = vec![CharacterName {
+            source: "Okabe".to_string(),
+            canonical: "Okabe".to_string(),
+            occurrences: 9,
+            pinned: false,
+        }];
+        config.template_content = Some(
+            "Translate {source_lang} to {target_lang}.\nShow: {series_context}\nNames:\n{glossary}"
+                .to_string(),
+        );
+
+        let prompt = build_system_prompt(&config, &sample_lines(), "ja", "en");
+        assert!(prompt
+            .starts_with("Translate ja to en.\nShow: A time-travel thriller.\nNames:\n- Okabe"));
+        assert!(prompt.contains("CRITICAL RULES"));
+        assert!(!prompt.contains("CHARACTER NAMES"));
+    }
+
+    #[test]
+    fn system_prompt_starts_with_series_context() {
+        let mut config = config("openai", "http://api.test".to_string());
+        config.series_context = Some("  A time-travel thriller.  ".to_string());
+
+        let prompt = build_system_prompt(&config, &sample_lines(), "ja", "en");
+        assert!(prompt.starts_with("SERIES CONTEXT"));
+        assert!(prompt.contains("do not translate it):\nA time-travel thriller.\n\nYou are"));
     }
 
     #[test]
@@ -604,7 +925,11 @@ after JSON"#;
             );
             assert_eq!(request.body["model"], "test-model");
             assert_eq!(request.body["temperature"], 0.3);
-            assert_eq!(request.body["response_format"]["type"], "json_object");
+            if case.provider == "llamacpp" {
+                assert!(request.body.get("response_format").is_none());
+            } else {
+                assert_eq!(request.body["response_format"]["type"], "json_object");
+            }
             assert!(request.body["messages"][0]["content"]
                 .as_str()
                 .unwrap()
@@ -631,6 +956,43 @@ after JSON"#;
         }
     }
 
+    #[test]
+    fn builds_azure_deployment_endpoints() {
+        let mut config = config("azure", "https://res.openai.azure.com/".to_string());
+        assert_eq!(
+            build_azure_chat_endpoint(&config),
+            "https://res.openai.azure.com/openai/deployments/test-model/chat/completions?api-version=2024-10-21"
+        );
+
+        config.azure_deployment = Some("gpt4o-prod".to_string());
+        config.azure_api_version = Some("2025-01-01-preview".to_string());
+        config.endpoint =
+            "https://res.openai.azure.com/openai/deployments/{deployment}?api-version={api_version}"
+                .to_string();
+        assert_eq!(
+            build_azure_chat_endpoint(&config),
+            "https://res.openai.azure.com/openai/deployments/gpt4o-prod/chat/completions?api-version=2025-01-01-preview"
+        );
+    }
+
+    #[tokio::test]
+    async fn azure_uses_deployment_url_and_api_key_header() {
+        let (base_url, request) =
+            start_test_server(200, openai_response(&translation_content("Olá"))).await;
+        let translations = call_llm_api(&config("azure", base_url), &sample_lines(), "ja", "pt")
+            .await
+            .unwrap();
+        let request = request.await.unwrap();
+
+        assert_translated(translations, "Olá");
+        assert_eq!(
+            request.path,
+            "/openai/deployments/test-model/chat/completions?api-version=2024-10-21"
+        );
+        assert_eq!(request.header("api-key"), Some("test-key"));
+        assert_eq!(request.header("authorization"), None);
+    }
+
     #[tokio::test]
     async fn openai_compatible_reasoning_models_ignore_reasoning_and_parse_content() {
         for provider in ["openrouter", "nvidia", "minimax"] {
@@ -728,7 +1090,8 @@ after JSON"#;
 
         let error = call_llm_api(&config("openai", base_url), &sample_lines(), "ja", "en")
             .await
-            .unwrap_err();
+            .unwrap_err()
+            .to_string();
 
         assert!(error.contains("LLM API error (400 Bad Request)"));
         assert!(error.contains("bad request"));
@@ -741,7 +1104,8 @@ after JSON"#;
                 &serde_json::json!({"choices":[{"message":{}}]}),
                 ResponseFormat::OpenAiCompatible
             )
-            .unwrap_err(),
+            .unwrap_err()
+            .to_string(),
             "Missing content in OpenAI response"
         );
         assert_eq!(
@@ -749,7 +1113,8 @@ after JSON"#;
                 &serde_json::json!({"candidates":[{"content":{"parts":[]}}]}),
                 ResponseFormat::Gemini
             )
-            .unwrap_err(),
+            .unwrap_err()
+            .to_string(),
             "Missing content in Gemini response"
         );
         assert_eq!(
@@ -757,7 +1122,8 @@ after JSON"#;
                 &serde_json::json!({"message":{}}),
                 ResponseFormat::OllamaNative
             )
-            .unwrap_err(),
+            .unwrap_err()
+            .to_string(),
             "Missing content in Ollama response"
         );
     }
@@ -773,6 +1139,6 @@ after JSON"#;
         .await
         .unwrap_err();
 
-        assert_eq!(error, "Unsupported provider: unknown");
+        assert_eq!(error.to_string(), "Unsupported provider: unknown");
     }
 }
@@ -0,0 +1,279 @@
+use super::call_llm_api_with_context;
+use crate::error::AnimeSubsError;
+use crate::models::{LLMConfig, RateLimitOptions, TranslatedLine, TranslationLine};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+const WINDOW: Duration = Duration::from_secs(60);
+/// How often a request waiting for a free concurrency slot checks again.
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// Returns `Some(retry_after)` when `error` is a provider 429 response.
+pub(crate) fn rate_limit_retry_after(error: &AnimeSubsError) -> Option<Option<Duration>> {
+    match error {
+        AnimeSubsError::RateLimited { retry_after, .. } => Some(*retry_after),
+        _ => None,
+    }
+}
+
+/// Sliding-window bookkeeping behind `RateLimiter`: requests/tokens sent in the last
+/// minute, the current concurrency target and the 429 backoff.
+#[derive(Debug)]
+pub(crate) struct LimiterState {
+    requests_per_minute: Option<u32>,
+    tokens_per_minute: Option<u32>,
+    max_concurrency: usize,
+    concurrency: usize,
+    in_flight: usize,
+    sent: VecDeque<(Instant, usize)>,
+    blocked_until: Option<Instant>,
+    backoff: Duration,
+    successes: usize,
+}
+
+impl LimiterState {
+    pub(crate) fn new(options: &RateLimitOptions) -> Self {
+        let max_concurrency = options.max_concurrency.unwrap_or(1).max(1);
+        Self {
+            requests_per_minute: options.requests_per_minute.filter(|r| *r > 0),
+            tokens_per_minute: options.tokens_per_minute.filter(|t| *t > 0),
+            max_concurrency,
+            concurrency: max_concurrency,
+            in_flight: 0,
+            sent: VecDeque::new(),
+            blocked_until: None,
+            backoff: INITIAL_BACKOFF,
+            successes: 0,
+        }
+    }
+
+    /// How long a request of `tokens` must wait before it may be sent; `None` means now.
+    pub(crate) fn wait_time(&mut self, now: Instant, tokens: usize) -> Option<Duration> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= WINDOW)
+        {
+            self.sent.pop_front();
+        }
+
+        if let Some(until) = self.blocked_until.filter(|until| *until > now) {
+            return Some(until - now);
+        }
+        if self.in_flight >= self.concurrency {
+            return Some(SLOT_POLL_INTERVAL);
+        }
+        if let Some(rpm) = self.requests_per_minute {
+            if self.sent.len() >= rpm as usize {
+                return self.sent.front().map(|(at, _)| *at + WINDOW - now);
+            }
+        }
+        if let Some(tpm) = self.tokens_per_minute.map(|t| t as usize) {
+            let mut used: usize = self.sent.iter().map(|(_, t)| t).sum();
+            // A request larger than the whole budget still goes out on an idle window.
+            for (at, sent_tokens) in &self.sent {
+                if used + tokens <= tpm {
+                    break;
+                }
+                used -= sent_tokens;
+                if used + tokens <= tpm || used == 0 {
+                    return Some(*at + WINDOW - now);
+                }
+            }
+        }
+        None
+    }
+
+    pub(crate) fn record(&mut self, now: Instant, tokens: usize) {
+        self.sent.push_back((now, tokens));
+        self.in_flight += 1;
+    }
+
+    pub(crate) fn finished(&mut self) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+    }
+
+    /// A 429: halve concurrency and pause every request for `retry_after` (or the
+    /// doubling backoff when the provider gave none).
+    pub(crate) fn rate_limited(&mut self, now: Instant, retry_after: Option<Duration>) {
+        self.concurrency = (self.concurrency / 2).max(1);
+        self.blocked_until = Some(now + retry_after.unwrap_or(self.backoff));
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+        self.successes = 0;
+    }
+
+    /// Ramps concurrency back up by one after a full round of successful requests.
+    pub(crate) fn succeeded(&mut self) {
+        self.backoff = INITIAL_BACKOFF;
+        self.successes += 1;
+        if self.successes >= self.concurrency && self.concurrency < self.max_concurrency {
+            self.concurrency += 1;
+            self.successes = 0;
+        }
+    }
+
+    pub(crate) fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+}
+
+/// Schedules LLM requests for one translation run under the provider's limits.
+pub struct RateLimiter {
+    state: Mutex<LimiterState>,
+    max_retries: u32,
+}
+
+impl RateLimiter {
+    pub fn new(options: &RateLimitOptions) -> Self {
+        Self {
+            state: Mutex::new(LimiterState::new(options)),
+            max_retries: options.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+        }
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.state.lock().map(|s| s.max_concurrency).unwrap_or(1)
+    }
+
+    async fn acquire(&self, tokens: usize) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let wait = state.wait_time(now, tokens);
+                if wait.is_none() {
+                    state.record(now, tokens);
+                }
+                wait
+            };
+            match wait {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return,
+            }
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LimiterState)) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut state);
+    }
+
+    /// `call_llm_api_with_context` behind the limiter, retrying 429 responses.
+    pub async fn call_with_context(
+        &self,
+        config: &LLMConfig,
+        lines: &[TranslationLine],
+        tokens: usize,
+        source_lang: &str,
+        target_lang: &str,
+        compact_context: Option<&str>,
+    ) -> Result<Vec<TranslatedLine>, AnimeSubsError> {
+        let mut attempt = 0;
+        loop {
+            self.acquire(tokens).await;
+            let result =
+                call_llm_api_with_context(config, lines, source_lang, target_lang, compact_context)
+                    .await;
+            self.update(LimiterState::finished);
+
+            match result {
+                Ok(translations) => {
+                    self.update(LimiterState::succeeded);
+                    return Ok(translations);
+                }
+                Err(error) => {
+                    let Some(retry_after) = rate_limit_retry_after(&error) else {
+                        return Err(error);
+                    };
+                    if attempt >= self.max_retries {
+                        return Err(error);
+                    }
+                    attempt += 1;
+                    self.update(|state| {
+                        state.rate_limited(Instant::now(), retry_after);
+                        warn!(
+                            "Rate limited, retry {}/{} with concurrency {}",
+                            attempt,
+                            self.max_retries,
+                            state.concurrency()
+                        );
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(rpm: Option<u32>, tpm: Option<u32>, concurrency: usize) -> RateLimitOptions {
+        RateLimitOptions {
+            requests_per_minute: rpm,
+            tokens_per_minute: tpm,
+            max_concurrency: Some(concurrency),
+            max_retries: None,
+        }
+    }
+
+    #[test]
+    fn enforces_request_and_token_ceilings() {
+        let start = Instant::now();
+        let mut state = LimiterState::new(&options(Some(2), None, 4));
+        state.record(start, 10);
+        state.record(start + Duration::from_secs(10), 10);
+        assert_eq!(
+            state.wait_time(start + Duration::from_secs(20), 10),
+            Some(Duration::from_secs(40))
+        );
+        assert_eq!(state.wait_time(start + Duration::from_secs(60), 10), None);
+
+        let mut state = LimiterState::new(&options(None, Some(1_000), 4));
+        state.record(start, 600);
+        state.record(start + Duration::from_secs(5), 300);
+        assert_eq!(state.wait_time(start + Duration::from_secs(6), 100), None);
+        assert_eq!(
+            state.wait_time(start + Duration::from_secs(6), 300),
+            Some(Duration::from_secs(54))
+        );
+    }
+
+    #[test]
+    fn backs_off_on_429_and_ramps_back_up() {
+        let start = Instant::now();
+        let mut state = LimiterState::new(&options(None, None, 4));
+        state.rate_limited(start, None);
+        assert_eq!(state.concurrency(), 2);
+        assert_eq!(state.wait_time(start, 1), Some(INITIAL_BACKOFF));
+
+        state.rate_limited(start, Some(Duration::from_secs(30)));
+        assert_eq!(state.concurrency(), 1);
+        assert_eq!(state.wait_time(start, 1), Some(Duration::from_secs(30)));
+
+        state.succeeded();
+        assert_eq!(state.concurrency(), 2);
+        state.succeeded();
+        state.succeeded();
+        assert_eq!(state.concurrency(), 3);
+
+        assert_eq!(
+            rate_limit_retry_after(&AnimeSubsError::RateLimited {
+                message: "LLM API error (429 Too Many Requests, retry after 7s): x".to_string(),
+                retry_after: Some(Duration::from_secs(7)),
+            }),
+            Some(Some(Duration::from_secs(7)))
+        );
+        assert_eq!(
+            rate_limit_retry_after(&AnimeSubsError::Provider(
+                "LLM API error (500 Internal Server Error): x".to_string()
+            )),
+            None
+        );
+    }
+}
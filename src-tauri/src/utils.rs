@@ -1,9 +1,12 @@
+use crate::error::AnimeSubsError;
+use crate::models::{DiskSpaceCheck, LineEnding, OutputPolicy, SubtitleEncodingOptions};
 use chardetng::EncodingDetector;
-use encoding_rs::Encoding;
+use encoding_rs::{Encoding, UTF_8};
 use regex::Regex;
 use std::env;
-use std::ffi::OsStr;
+use std::ffi::{OsStr, OsString};
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -27,6 +30,143 @@ pub fn create_command(program: impl AsRef<OsStr>) -> Command {
     }
 }
 
+/// Longest path plain Win32 calls accept; tools need the verbatim form beyond it.
+const WINDOWS_MAX_PATH: usize = 260;
+
+/// Verbatim (`\\?\`) form of an absolute Windows path, or `None` for relative
+/// and already-verbatim paths. Verbatim paths skip Win32 normalization, so `/`,
+/// `.` and `..` are resolved here.
+fn verbatim_windows_path(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    let (prefix, rest, root_parts) =
+        if let Some(unc) = path.strip_prefix(r"\\").or_else(|| path.strip_prefix("//")) {
+            // `server\share` can't be walked out of with `..`.
+            (r"\\?\UNC\".to_string(), unc, 2)
+        } else {
+            let bytes = path.as_bytes();
+            let is_drive = bytes.len() >= 3
+                && bytes[0].is_ascii_alphabetic()
+                && bytes[1] == b':'
+                && matches!(bytes[2], b'\\' | b'/');
+            if !is_drive {
+                return None;
+            }
+            (format!(r"\\?\{}:\", &path[..1]), &path[3..], 0)
+        };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split(['\\', '/']) {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.len() > root_parts {
+                    parts.pop();
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    Some(format!("{}{}", prefix, parts.join(r"\")))
+}
+
+/// Path argument for ffmpeg, ffprobe and MKVToolNix, kept as an OS string so
+/// non-UTF-8 names reach the tool untouched. On Windows, paths too long for
+/// MAX_PATH are passed in verbatim `\\?\` form.
+pub fn tool_path_arg(path: impl AsRef<Path>) -> OsString {
+    let path = path.as_ref();
+    if cfg!(windows) {
+        let verbatim = path
+            .to_str()
+            .filter(|p| p.encode_utf16().count() >= WINDOWS_MAX_PATH)
+            .and_then(verbatim_windows_path);
+        if let Some(verbatim) = verbatim {
+            return verbatim.into();
+        }
+    }
+    path.as_os_str().to_owned()
+}
+
+/// [`tool_path_arg`] for argument lists built as strings; paths arrive from the
+/// frontend as UTF-8, so nothing is lost.
+pub fn tool_path(path: &str) -> String {
+    tool_path_arg(path).to_string_lossy().into_owned()
+}
+
+/// Path argument for ffmpeg and ffprobe. The `file:` prefix keeps names such as
+/// `-ep01.mkv` or `concat:a.mkv|b.mkv` from being read as an option or protocol.
+pub fn ffmpeg_file_arg(path: impl AsRef<Path>) -> OsString {
+    let mut arg = OsString::from("file:");
+    arg.push(tool_path_arg(path));
+    arg
+}
+
+/// [`ffmpeg_file_arg`] for argument lists built as strings.
+pub fn ffmpeg_file(path: &str) -> String {
+    ffmpeg_file_arg(path).to_string_lossy().into_owned()
+}
+
+/// Path argument for MKVToolNix, which has no `--` separator and reads `@name` as an
+/// options file; relative names starting with `-` or `@` are prefixed with `./`.
+pub fn mkvtoolnix_file_arg(path: impl AsRef<Path>) -> OsString {
+    let path = path.as_ref();
+    if path.to_string_lossy().starts_with(['-', '@']) {
+        return tool_path_arg(Path::new(".").join(path));
+    }
+    tool_path_arg(path)
+}
+
+/// [`mkvtoolnix_file_arg`] for argument lists built as strings.
+pub fn mkvtoolnix_file(path: &str) -> String {
+    mkvtoolnix_file_arg(path).to_string_lossy().into_owned()
+}
+
+/// Track title or other free text for a tool argument: control characters,
+/// newlines included, become spaces.
+pub fn tool_metadata(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Whether `code` is shaped like an ISO 639 code or BCP 47 tag (`jpn`, `pt-BR`,
+/// `zh-Hant`), the only language values passed to ffmpeg and mkvmerge.
+pub fn is_valid_language_code(code: &str) -> bool {
+    let mut parts = code.split('-');
+    let primary = parts.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+/// `code` for a tool argument, or `und` when it is not a valid language code.
+pub fn tool_language(code: &str) -> &str {
+    if is_valid_language_code(code) {
+        code
+    } else {
+        "und"
+    }
+}
+
+/// Parses the processed time, in seconds, from a `-progress` key/value line.
+pub fn parse_ffmpeg_progress_time(line: &str) -> Option<f64> {
+    let (key, value) = line.trim().split_once('=')?;
+    match key {
+        "out_time_us" | "out_time_ms" => value
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .map(|micros| micros.max(0) as f64 / 1_000_000.0),
+        _ => None,
+    }
+}
+
 pub fn find_executable_in_path(names: &[&str]) -> Option<PathBuf> {
     if let Some(paths) = env::var_os("PATH") {
         for p in env::split_paths(&paths) {
@@ -41,12 +181,24 @@ pub fn find_executable_in_path(names: &[&str]) -> Option<PathBuf> {
     None
 }
 
+fn saved_ffmpeg_path() -> Option<String> {
+    crate::commands::settings::current_settings().ffmpeg_path
+}
+
+/// Path of ffmpeg: `custom_path`, else the saved setting, else the build fetched by
+/// `download_ffmpeg`, else a PATH or well-known install location.
 pub fn get_ffmpeg_path(custom_path: Option<String>) -> String {
+    let custom_path = custom_path
+        .filter(|p| !p.is_empty())
+        .or_else(saved_ffmpeg_path);
     if let Some(path) = custom_path {
         if !path.is_empty() {
             return path;
         }
     }
+    if let Some(path) = crate::commands::bootstrap::bundled_tool_path("ffmpeg") {
+        return path;
+    }
 
     let exe_name = if cfg!(windows) {
         "ffmpeg.exe"
@@ -89,10 +241,10 @@ pub fn build_temp_subtitle_path(
     source_path: &str,
     label: &str,
     extension: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, AnimeSubsError> {
     let temp_dir = env::temp_dir().join("animesubs");
     fs::create_dir_all(&temp_dir)
-        .map_err(|e| format!("Failed to create temporary directory: {}", e))?;
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to create temporary directory: {}", e)))?;
 
     let stem = Path::new(source_path)
         .file_stem()
@@ -104,32 +256,68 @@ pub fn build_temp_subtitle_path(
     Ok(temp_dir.join(format!("{}_{}_{}.{}", stem, label, timestamp, extension)))
 }
 
+/// Temp file next to `video_path` that a remux writes before being finalized.
+pub fn build_remux_temp_path(video_path: &str, label: &str) -> PathBuf {
+    let video_pathbuf = Path::new(video_path);
+    let parent = video_pathbuf.parent().unwrap_or(Path::new("."));
+    let stem = video_pathbuf
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "video".to_string());
+    let ext = video_pathbuf
+        .extension()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mkv".to_string());
+    parent.join(format!("{}_{}.{}", stem, label, ext))
+}
+
 pub fn is_mkv_container(extension: &str) -> bool {
     matches!(extension.to_ascii_lowercase().as_str(), "mkv")
 }
 
-pub fn resolve_ffmpeg_subtitle_codec(
+/// Codec a subtitle format is stored as in a container, and whether that conversion
+/// drops styling (ASS into mov_text/WebVTT). `None` when the container holds no text
+/// subtitles ffmpeg can write.
+pub fn subtitle_container_codec(
     container_ext: &str,
     subtitle_ext: &str,
-) -> Result<&'static str, String> {
+) -> Option<(&'static str, bool)> {
+    let styled = matches!(subtitle_ext, "ass" | "ssa");
     match container_ext.to_ascii_lowercase().as_str() {
-        "mkv" => Ok(match subtitle_ext {
-            "ass" | "ssa" => "ass",
-            "srt" | "subrip" => "srt",
-            "vtt" | "webvtt" => "webvtt",
-            _ => "srt",
-        }),
-        "mp4" | "m4v" | "mov" => Ok("mov_text"),
-        "webm" => Ok("webvtt"),
-        _ => Err(format!(
+        "mkv" => Some((
+            match subtitle_ext {
+                "ass" | "ssa" => "ass",
+                "srt" | "subrip" => "srt",
+                "vtt" | "webvtt" => "webvtt",
+                _ => "srt",
+            },
+            false,
+        )),
+        "mp4" | "m4v" | "mov" => Some(("mov_text", styled)),
+        "webm" => Some(("webvtt", styled)),
+        _ => None,
+    }
+}
+
+pub fn resolve_ffmpeg_subtitle_codec(
+    container_ext: &str,
+    subtitle_ext: &str,
+) -> Result<&'static str, AnimeSubsError> {
+    match subtitle_container_codec(container_ext, subtitle_ext) {
+        Some((codec, _)) => Ok(codec),
+        None => Err(format!(
             "Embedding subtitles into .{} with ffmpeg is not supported \
              reliably. Use MKV/mkvmerge or disable embed for this file.",
             container_ext
-        )),
+        )
+        .into()),
     }
 }
 
 pub fn get_ffprobe_path(custom_ffmpeg_path: Option<String>) -> String {
+    let custom_ffmpeg_path = custom_ffmpeg_path
+        .filter(|p| !p.is_empty())
+        .or_else(saved_ffmpeg_path);
     if let Some(path) = custom_ffmpeg_path {
         if !path.is_empty() {
             let path = Path::new(&path);
@@ -146,6 +334,9 @@ pub fn get_ffprobe_path(custom_ffmpeg_path: Option<String>) -> String {
             }
         }
     }
+    if let Some(path) = crate::commands::bootstrap::bundled_tool_path("ffprobe") {
+        return path;
+    }
 
     let exe_name = if cfg!(windows) {
         "ffprobe.exe"
@@ -185,41 +376,60 @@ pub fn get_ffprobe_path(custom_ffmpeg_path: Option<String>) -> String {
 }
 
 pub fn resolve_mkvmerge_path() -> Option<String> {
-    let exe_names: &[&str] = if cfg!(windows) {
-        &["mkvmerge.exe"]
+    resolve_mkvtoolnix_path("mkvmerge")
+}
+
+pub fn resolve_mkvpropedit_path() -> Option<String> {
+    resolve_mkvtoolnix_path("mkvpropedit")
+}
+
+/// `tool` next to a configured MKVToolNix path, which names either the folder or
+/// one of the binaries in it.
+pub fn mkvtoolnix_tool_in(configured: &str, tool: &str) -> Option<PathBuf> {
+    let configured = Path::new(configured);
+    let dir = if configured.is_dir() {
+        configured
     } else {
-        &["mkvmerge"]
+        configured.parent()?
     };
+    let candidate = dir.join(crate::commands::bootstrap::exe_name(tool));
+    candidate.is_file().then_some(candidate)
+}
+
+/// Path of an MKVToolNix tool: the folder saved in the settings, else the copy
+/// fetched by `download_mkvtoolnix`, else a PATH or well-known install location.
+fn resolve_mkvtoolnix_path(tool: &str) -> Option<String> {
+    let configured = crate::commands::settings::current_settings().mkvtoolnix_path;
+    if let Some(path) = configured.and_then(|dir| mkvtoolnix_tool_in(&dir, tool)) {
+        return Some(path.to_string_lossy().to_string());
+    }
+    if let Some(path) = crate::commands::bootstrap::bundled_tool_path(tool) {
+        return Some(path);
+    }
+
+    let exe_name = crate::commands::bootstrap::exe_name(tool);
 
-    if let Some(p) = find_executable_in_path(exe_names) {
+    if let Some(p) = find_executable_in_path(&[exe_name.as_str()]) {
         return Some(p.to_string_lossy().to_string());
     }
 
-    if cfg!(windows) {
-        let candidates = [
-            r"C:\Program Files\MKVToolNix\mkvmerge.exe",
-            r"C:\Program Files (x86)\MKVToolNix\mkvmerge.exe",
-        ];
-        for c in candidates {
-            if Path::new(c).exists() {
-                return Some(c.to_string());
-            }
-        }
+    let candidates: Vec<String> = if cfg!(windows) {
+        vec![
+            format!(r"C:\Program Files\MKVToolNix\{}", exe_name),
+            format!(r"C:\Program Files (x86)\MKVToolNix\{}", exe_name),
+        ]
     } else if cfg!(target_os = "macos") {
-        let candidates = [
-            "/opt/homebrew/bin/mkvmerge",
-            "/usr/local/bin/mkvmerge",
-            "/opt/local/bin/mkvmerge",
-            "/Applications/MKVToolNix.app/Contents/MacOS/mkvmerge",
-        ];
-        for c in candidates {
-            if Path::new(c).exists() {
-                return Some(c.to_string());
-            }
-        }
-    }
+        vec![
+            format!("/opt/homebrew/bin/{}", exe_name),
+            format!("/usr/local/bin/{}", exe_name),
+            format!("/opt/local/bin/{}", exe_name),
+            format!("/Applications/MKVToolNix.app/Contents/MacOS/{}", exe_name),
+        ]
+    } else {
+        Vec::new()
+    };
 
-    None
+    candidates.into_iter().find(|c| Path::new(c).exists())
 }
 
 pub fn strip_utf8_bom(mut content: String) -> String {
@@ -229,8 +439,24 @@ pub fn strip_utf8_bom(mut content: String) -> String {
     content
 }
 
-pub fn read_file_as_utf8(file_path: &str) -> Result<String, String> {
-    let bytes = fs::read(file_path).map_err(|e| format!("Failed to read subtitle file: {}", e))?;
+/// Escapes text for HTML element content and quoted attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn read_file_as_utf8(file_path: &str) -> Result<String, AnimeSubsError> {
+    let bytes = fs::read(file_path)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read subtitle file: {}", e)))?;
 
     if let Some((encoding, _)) = Encoding::for_bom(&bytes) {
         let (decoded, _) = encoding.decode_with_bom_removal(&bytes);
@@ -248,16 +474,313 @@ pub fn read_file_as_utf8(file_path: &str) -> Result<String, String> {
     Ok(decoded.into_owned())
 }
 
-pub fn write_utf8_file(path: &str, content: &str, include_bom: bool) -> Result<(), String> {
-    let mut data = Vec::with_capacity(content.len() + if include_bom { 3 } else { 0 });
-    if include_bom {
-        data.extend_from_slice(b"\xEF\xBB\xBF");
+pub fn write_utf8_file(path: &str, content: &str, include_bom: bool) -> Result<(), AnimeSubsError> {
+    write_subtitle_file(
+        path,
+        content,
+        &SubtitleEncodingOptions::default(),
+        include_bom,
+    )
+}
+
+/// Encodes subtitle text as `options` ask. Characters the target encoding cannot
+/// represent are an error rather than the HTML escapes encoding_rs would write.
+pub fn encode_subtitle_content(
+    content: &str,
+    options: &SubtitleEncodingOptions,
+    default_bom: bool,
+) -> Result<Vec<u8>, AnimeSubsError> {
+    let content = content.replace("\r\n", "\n");
+    let content = match options.line_ending {
+        LineEnding::Lf => content,
+        LineEnding::Crlf => content.replace('\n', "\r\n"),
+    };
+
+    let encoding = match options.encoding.as_deref().map(str::trim) {
+        None | Some("") => UTF_8,
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("Unknown subtitle encoding: {}", label))?,
+    };
+    // UTF-16 and the replacement encoding are decode-only in encoding_rs.
+    if encoding.output_encoding() != encoding {
+        return Err(format!("Cannot write subtitles as {}", encoding.name()).into());
+    }
+
+    if encoding == UTF_8 {
+        let include_bom = options.bom.unwrap_or(default_bom);
+        let mut data = Vec::with_capacity(content.len() + if include_bom { 3 } else { 0 });
+        if include_bom {
+            data.extend_from_slice(b"\xEF\xBB\xBF");
+        }
+        data.extend_from_slice(content.as_bytes());
+        return Ok(data);
+    }
+
+    let (encoded, _, had_errors) = encoding.encode(&content);
+    if had_errors {
+        let unmappable = content
+            .chars()
+            .find(|c| encoding.encode(c.encode_utf8(&mut [0; 4])).2)
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+        return Err(format!(
+            "'{}' cannot be written as {}; save as UTF-8 or edit the line",
+            unmappable,
+            encoding.name()
+        )
+        .into());
+    }
+    Ok(encoded.into_owned())
+}
+
+pub fn write_subtitle_file(
+    path: &str,
+    content: &str,
+    options: &SubtitleEncodingOptions,
+    default_bom: bool,
+) -> Result<(), AnimeSubsError> {
+    let data = encode_subtitle_content(content, options, default_bom)?;
+    fs::write(path, data)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to write subtitle file: {}", e)))
+}
+
+/// Writes `data` to a sibling temp file, fsyncs it and renames it over `path`,
+/// so readers never observe a partially written file.
+pub fn write_file_atomic(path: &Path, data: &[u8]) -> Result<(), AnimeSubsError> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "file".to_string());
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let mut file = fs::File::create(&temp_path).map_err(|e| {
+        AnimeSubsError::Io(format!("Failed to create {}: {}", temp_path.display(), e))
+    })?;
+    file.write_all(data)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| {
+            AnimeSubsError::Io(format!("Failed to write {}: {}", temp_path.display(), e))
+        })?;
+    drop(file);
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        AnimeSubsError::Io(format!("Failed to replace {}: {}", path.display(), e))
+    })
+}
+
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    {
+        if let Some(parent) = path.parent() {
+            if let Ok(dir) = fs::File::open(parent) {
+                let _ = dir.sync_all();
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+    }
+}
+
+/// Resolves where a remux result for `video_path` should end up under `policy`.
+pub fn resolve_remux_output_path(video_path: &str, policy: &OutputPolicy) -> PathBuf {
+    let source = Path::new(video_path);
+    match policy {
+        OutputPolicy::Overwrite => source.to_path_buf(),
+        OutputPolicy::Alongside { suffix, .. } => {
+            let parent = source.parent().unwrap_or(Path::new("."));
+            let stem = source
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "video".to_string());
+            let suffix = if suffix.is_empty() { "_subbed" } else { suffix };
+            let filename = match source.extension() {
+                Some(ext) => format!("{}{}.{}", stem, suffix, ext.to_string_lossy()),
+                None => format!("{}{}", stem, suffix),
+            };
+            parent.join(filename)
+        }
+        OutputPolicy::Directory { path, .. } => {
+            let filename = source
+                .file_name()
+                .map(|n| n.to_os_string())
+                .unwrap_or_else(|| "video.mkv".into());
+            Path::new(path).join(filename)
+        }
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Final path of a remux of `video_path` written to `temp_output`. Only `Overwrite`
+/// may replace the source video, and never another file when the container changed;
+/// the other policies only replace an existing file when they set `replace_existing`.
+pub fn remux_target(
+    video_path: &str,
+    temp_output: &Path,
+    policy: &OutputPolicy,
+) -> Result<PathBuf, AnimeSubsError> {
+    let mut target = resolve_remux_output_path(video_path, policy);
+    // A remux that switched containers (e.g. MP4 to MKV) keeps its new extension, so
+    // under Overwrite it lands next to the source instead of replacing it.
+    if let Some(ext) = temp_output.extension() {
+        if target.extension() != Some(ext) {
+            target.set_extension(ext);
+        }
+    }
+
+    match policy {
+        OutputPolicy::Overwrite => {
+            if target.exists() && !same_path(&target, Path::new(video_path)) {
+                return Err(AnimeSubsError::Io(format!(
+                    "Output {} already exists and is not the source video",
+                    target.display()
+                )));
+            }
+        }
+        OutputPolicy::Alongside {
+            replace_existing, ..
+        }
+        | OutputPolicy::Directory {
+            replace_existing, ..
+        } => {
+            if same_path(&target, Path::new(video_path)) {
+                return Err(AnimeSubsError::Io(format!(
+                    "Output {} would replace the source video; choose another folder or suffix",
+                    target.display()
+                )));
+            }
+            if target.exists() && !replace_existing {
+                return Err(AnimeSubsError::Io(format!(
+                    "Output {} already exists",
+                    target.display()
+                )));
+            }
+        }
+    }
+    Ok(target)
+}
+
+/// Moves a finished remux from `temp_output` to its final location and returns that path.
+/// Overwrites are made durable with an fsync of the data before the rename.
+pub fn finalize_remux_output(
+    temp_output: &Path,
+    video_path: &str,
+    policy: &OutputPolicy,
+) -> Result<String, AnimeSubsError> {
+    let target = remux_target(video_path, temp_output, policy)?;
+
+    fs::File::open(temp_output)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to flush remuxed file: {}", e)))?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to create output directory: {}", e)))?;
     }
-    data.extend_from_slice(content.as_bytes());
-    fs::write(path, data).map_err(|e| format!("Failed to write subtitle file: {}", e))
+
+    if let Err(rename_error) = fs::rename(temp_output, &target) {
+        // Renames fail across filesystems; fall back to copy + remove for directory output.
+        if *policy == OutputPolicy::Overwrite {
+            return Err(AnimeSubsError::Io(format!(
+                "Failed to replace original file: {}",
+                rename_error
+            )));
+        }
+        fs::copy(temp_output, &target)
+            .map_err(|e| AnimeSubsError::Io(format!("Failed to write output: {}", e)))?;
+        let _ = fs::remove_file(temp_output);
+    }
+    sync_parent_dir(&target);
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Remuxed copies can end up slightly larger than the source (new tracks, muxer overhead).
+const DISK_SPACE_MARGIN_RATIO: f64 = 0.05;
+const DISK_SPACE_MIN_MARGIN: u64 = 64 * 1024 * 1024;
+
+pub fn required_remux_space(source_size: u64) -> u64 {
+    let margin = ((source_size as f64 * DISK_SPACE_MARGIN_RATIO) as u64).max(DISK_SPACE_MIN_MARGIN);
+    source_size.saturating_add(margin)
 }
 
-pub fn convert_subtitle_to_utf8(subtitle_path: &str) -> Result<(String, Option<PathBuf>), String> {
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Compares free space in `target_dir` (or its nearest existing ancestor) with what a
+/// full copy of `source` needs.
+pub fn check_disk_space_for(
+    source: &str,
+    target_dir: &Path,
+) -> Result<DiskSpaceCheck, AnimeSubsError> {
+    let source_size = fs::metadata(source)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to read video file: {}", e)))?
+        .len();
+    let dir = target_dir
+        .ancestors()
+        .find(|dir| dir.is_dir())
+        .unwrap_or(Path::new("."));
+    let available_bytes = fs2::available_space(dir)
+        .map_err(|e| AnimeSubsError::Io(format!("Failed to query free disk space: {}", e)))?;
+    let required_bytes = required_remux_space(source_size);
+
+    Ok(DiskSpaceCheck {
+        path: dir.to_string_lossy().to_string(),
+        required_bytes,
+        available_bytes,
+        sufficient: available_bytes >= required_bytes,
+    })
+}
+
+/// Fails before ffmpeg/mkvmerge runs when the temp output or the final location
+/// cannot hold another copy of the video.
+pub fn ensure_remux_space(
+    video_path: &str,
+    temp_output: &Path,
+    policy: &OutputPolicy,
+) -> Result<(), AnimeSubsError> {
+    let target = remux_target(video_path, temp_output, policy)?;
+    let mut dirs: Vec<&Path> = temp_output.parent().into_iter().collect();
+    if let Some(target_dir) = target.parent().filter(|dir| !dirs.contains(dir)) {
+        dirs.push(target_dir);
+    }
+
+    for dir in dirs {
+        let check = check_disk_space_for(video_path, dir)?;
+        if !check.sufficient {
+            return Err(AnimeSubsError::InsufficientSpace {
+                path: check.path,
+                needed: check.required_bytes,
+                available: check.available_bytes,
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn convert_subtitle_to_utf8(
+    subtitle_path: &str,
+) -> Result<(String, Option<PathBuf>), AnimeSubsError> {
     let ext = Path::new(subtitle_path)
         .extension()
         .map(|e| e.to_string_lossy().to_ascii_lowercase())
@@ -282,12 +805,147 @@ pub fn convert_subtitle_to_utf8(subtitle_path: &str) -> Result<(String, Option<P
     Ok((temp_path.to_string_lossy().to_string(), Some(temp_path)))
 }
 
+/// Parses ASS (`0:00:01.00`), SRT (`00:00:01,000`) and VTT (`00:01.000`) timestamps
+/// into seconds.
+pub fn parse_subtitle_timestamp(value: &str) -> Option<f64> {
+    let value = value.trim().replace(',', ".");
+    let parts: Vec<&str> = value.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m, s] => (
+            h.parse::<f64>().ok()?,
+            m.parse::<f64>().ok()?,
+            s.parse::<f64>().ok()?,
+        ),
+        [m, s] => (0.0, m.parse::<f64>().ok()?, s.parse::<f64>().ok()?),
+        _ => return None,
+    };
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Formats `seconds` in the same style as `template`: ASS `H:MM:SS.cc`,
+/// SRT `HH:MM:SS,mmm` or VTT `[HH:]MM:SS.mmm`.
+pub fn format_subtitle_timestamp_like(template: &str, seconds: f64) -> String {
+    let template = template.trim();
+    let separator = if template.contains(',') { ',' } else { '.' };
+    let fraction_digits = template
+        .rsplit([',', '.'])
+        .next()
+        .filter(|_| template.contains([',', '.']))
+        .map_or(3, |fraction| fraction.len().clamp(1, 3));
+    let hour_width = match template.split(':').count() {
+        3 => template.split(':').next().map_or(2, str::len),
+        _ => 0,
+    };
+
+    let scale = 10u64.pow(fraction_digits as u32);
+    let total_units = (seconds.max(0.0) * scale as f64).round() as u64;
+    let fraction = total_units % scale;
+    let total_seconds = total_units / scale;
+    let (hours, minutes, secs) = (
+        total_seconds / 3600,
+        (total_seconds % 3600) / 60,
+        total_seconds % 60,
+    );
+
+    if hour_width == 0 && hours == 0 {
+        format!(
+            "{:02}:{:02}{}{:0width$}",
+            minutes,
+            secs,
+            separator,
+            fraction,
+            width = fraction_digits
+        )
+    } else {
+        format!(
+            "{:0hw$}:{:02}:{:02}{}{:0width$}",
+            hours,
+            minutes,
+            secs,
+            separator,
+            fraction,
+            hw = hour_width.max(1),
+            width = fraction_digits
+        )
+    }
+}
+
 pub fn strip_ass_tags(text: &str) -> String {
     let tag_regex = Regex::new(r"\{[^}]*\}").unwrap();
     let result = tag_regex.replace_all(text, "");
     result.replace("\\N", "\n").replace("\\n", "\n")
 }
 
+const DEFAULT_EVENT_FIELDS: [&str; 10] = [
+    "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+];
+
+/// Field order of an ASS `[Events]` section, read from its `Format:` line. Scripts
+/// that never declare one get the standard ten-field layout.
+#[derive(Debug, Clone)]
+pub struct AssEventFormat {
+    fields: Vec<String>,
+}
+
+impl Default for AssEventFormat {
+    fn default() -> Self {
+        Self {
+            fields: DEFAULT_EVENT_FIELDS.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+impl AssEventFormat {
+    /// Reads the field list following `Format:`.
+    pub fn parse(format: &str) -> Self {
+        let fields: Vec<String> = format
+            .split(',')
+            .map(|f| f.trim().to_ascii_lowercase())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if fields.is_empty() {
+            Self::default()
+        } else {
+            Self { fields }
+        }
+    }
+
+    /// Splits the values of an event (everything after `Dialogue:`) into one value per
+    /// field; the last field keeps its commas. `None` when fields are missing.
+    pub fn split<'a>(&self, values: &'a str) -> Option<Vec<&'a str>> {
+        let parts: Vec<&str> = values.splitn(self.fields.len(), ',').collect();
+        (parts.len() == self.fields.len()).then_some(parts)
+    }
+
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|f| f.eq_ignore_ascii_case(name))
+    }
+
+    /// Value of field `name` in `parts`, untrimmed.
+    pub fn get<'a>(&self, parts: &[&'a str], name: &str) -> Option<&'a str> {
+        self.position(name).and_then(|idx| parts.get(idx).copied())
+    }
+}
+
+const SIGN_SONG_STYLE_WORDS: [&str; 15] = [
+    "op", "ed", "opening", "ending", "karaoke", "romaji", "japanese", "sign", "signs", "title",
+    "song", "lyrics", "insert", "credit", "credits",
+];
+
+/// ASS styles used for typesetting, songs and credits rather than dialogue.
+pub fn is_sign_or_song_style(style: &str) -> bool {
+    let style = style.trim().to_lowercase();
+    SIGN_SONG_STYLE_WORDS
+        .iter()
+        .any(|&skip| style.contains(skip) || style.split_whitespace().any(|word| word == skip))
+}
+
 pub fn is_music_or_karaoke_line(original_text: &str, clean_text: &str) -> bool {
     let lowered = clean_text.to_ascii_lowercase();
     let original_lower = original_text.to_ascii_lowercase();
@@ -328,6 +986,26 @@ pub fn is_music_or_karaoke_line(original_text: &str, clean_text: &str) -> bool {
         || ((is_very_short || mostly_short) && looks_like_romaji && repeating_tokens)
 }
 
+/// Output contract shared by the built-in styles and user prompt templates.
+pub const TRANSLATION_RULES: &str = r#"CRITICAL RULES:
+1. You will receive a JSON array of subtitle lines with "id" and "text" fields
+2. Return ONLY a valid JSON object with "translations" array containing objects with "id" and "text"
+3. NEVER change line IDs - they must match exactly for correct subtitle replacement
+4. Keep translations concise - subtitles need to be readable quickly
+5. Preserve line breaks (\n) where present in the source
+6. Do not add explanations or notes - only the translated text
+7. If a line contains only sound effects like "(笑)" or "♪", \
+translate the sound description appropriately
+8. If a line is clearly music/lyrics (karaoke tags, music notes, \
+or ending/opening song cues), leave it unchanged
+
+Example input:
+{"lines": [{"id": 0, "text": "Hello, how are you?"}, {"id": 1, "text": "I'm fine, thanks!"}]}
+
+Example output:
+{"translations": [{"id": 0, "text": "Translated line 0"}, \
+{"id": 1, "text": "Translated line 1"}]}"#;
+
 pub fn build_translation_prompt(style: &str, source_lang: &str, target_lang: &str) -> String {
     let base_instruction = format!(
         "You are a professional subtitle translator. Translate from {} to {}.",
@@ -359,32 +1037,20 @@ pub fn build_translation_prompt(style: &str, source_lang: &str, target_lang: &st
     };
 
     format!(
-        r#"{}
-
-Style: {}
-
-CRITICAL RULES:
-1. You will receive a JSON array of subtitle lines with "id" and "text" fields
-2. Return ONLY a valid JSON object with "translations" array containing objects with "id" and "text"
-3. NEVER change line IDs - they must match exactly for correct subtitle replacement
-4. Keep translations concise - subtitles need to be readable quickly
-5. Preserve line breaks (\n) where present in the source
-6. Do not add explanations or notes - only the translated text
-7. If a line contains only sound effects like "(笑)" or "♪", \
-translate the sound description appropriately
-8. If a line is clearly music/lyrics (karaoke tags, music notes, \
-or ending/opening song cues), leave it unchanged
-
-Example input:
-{{"lines": [{{"id": 0, "text": "Hello, how are you?"}}, {{"id": 1, "text": "I'm fine, thanks!"}}]}}
-
-Example output:
-{{"translations": [{{"id": 0, "text": "Translated line 0"}}, \
-{{"id": 1, "text": "Translated line 1"}}]}}"#,
-        base_instruction, style_instruction
+        "{}\n\nStyle: {}\n\n{}",
+        base_instruction, style_instruction, TRANSLATION_RULES
     )
 }
 
+/// Expands `{name}` placeholders; unknown placeholders are left as written.
+pub fn render_prompt_template(template: &str, variables: &[(&str, &str)]) -> String {
+    variables
+        .iter()
+        .fold(template.to_string(), |rendered, (name, value)| {
+            rendered.replace(&format!("{{{}}}", name), value)
+        })
+}
+
 pub fn clean_json_response(content: &str) -> String {
     let content = content.trim();
 
@@ -419,3 +1085,246 @@ pub fn get_backup_dir(video_path: &str) -> PathBuf {
     let parent = video_path.parent().unwrap_or(Path::new("."));
     parent.join(".animesubs_backup")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ffmpeg_progress_out_time() {
+        assert_eq!(
+            parse_ffmpeg_progress_time("out_time_us=12500000"),
+            Some(12.5)
+        );
+        assert_eq!(parse_ffmpeg_progress_time("out_time_ms=1000000"), Some(1.0));
+        assert_eq!(parse_ffmpeg_progress_time("out_time_us=N/A"), None);
+        assert_eq!(parse_ffmpeg_progress_time("progress=continue"), None);
+    }
+
+    #[test]
+    fn resolves_remux_output_path_for_each_policy() {
+        let video = "/videos/Episode 01.mkv";
+
+        assert_eq!(
+            resolve_remux_output_path(video, &OutputPolicy::Overwrite),
+            PathBuf::from(video)
+        );
+        assert_eq!(
+            resolve_remux_output_path(
+                video,
+                &OutputPolicy::Alongside {
+                    suffix: ".subbed".to_string(),
+                    replace_existing: false,
+                }
+            ),
+            PathBuf::from("/videos/Episode 01.subbed.mkv")
+        );
+        assert_eq!(
+            resolve_remux_output_path(
+                video,
+                &OutputPolicy::Directory {
+                    path: "/out".to_string(),
+                    replace_existing: false,
+                }
+            ),
+            PathBuf::from("/out/Episode 01.mkv")
+        );
+    }
+
+    #[test]
+    fn keeps_safe_output_policies_off_existing_files() {
+        let dir = std::env::temp_dir().join(format!("animesubs-finalize-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("Episode 01.mkv");
+        fs::write(&video, "source").unwrap();
+        let video_path = video.to_string_lossy().to_string();
+        let temp = dir.join("remux.tmp.mkv");
+        let same_dir = OutputPolicy::Directory {
+            path: dir.to_string_lossy().to_string(),
+            replace_existing: true,
+        };
+        let alongside = |replace_existing| OutputPolicy::Alongside {
+            suffix: "_subbed".to_string(),
+            replace_existing,
+        };
+
+        fs::write(&temp, "remuxed").unwrap();
+        assert!(finalize_remux_output(&temp, &video_path, &same_dir).is_err());
+        fs::write(dir.join("Episode 01_subbed.mkv"), "older").unwrap();
+        assert!(finalize_remux_output(&temp, &video_path, &alongside(false)).is_err());
+        assert_eq!(fs::read_to_string(&video).unwrap(), "source");
+
+        let output = finalize_remux_output(&temp, &video_path, &alongside(true)).unwrap();
+        assert_eq!(fs::read_to_string(output).unwrap(), "remuxed");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn overwrite_never_replaces_a_sibling_with_the_new_container() {
+        let dir = std::env::temp_dir().join(format!("animesubs-retarget-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let video = dir.join("Show - 01.mp4");
+        fs::write(&video, "source").unwrap();
+        let video_path = video.to_string_lossy().to_string();
+        let temp = dir.join("remux.tmp.mkv");
+        fs::write(&temp, "remuxed").unwrap();
+
+        let sibling = dir.join("Show - 01.mkv");
+        fs::write(&sibling, "another release").unwrap();
+        assert!(finalize_remux_output(&temp, &video_path, &OutputPolicy::Overwrite).is_err());
+        assert_eq!(fs::read_to_string(&sibling).unwrap(), "another release");
+
+        fs::remove_file(&sibling).unwrap();
+        let output = finalize_remux_output(&temp, &video_path, &OutputPolicy::Overwrite).unwrap();
+        assert_eq!(PathBuf::from(output), sibling);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn maps_subtitle_formats_to_container_codecs() {
+        assert_eq!(subtitle_container_codec("MKV", "ass"), Some(("ass", false)));
+        assert_eq!(
+            subtitle_container_codec("mp4", "ass"),
+            Some(("mov_text", true))
+        );
+        assert_eq!(
+            subtitle_container_codec("mp4", "srt"),
+            Some(("mov_text", false))
+        );
+        assert_eq!(
+            subtitle_container_codec("webm", "ssa"),
+            Some(("webvtt", true))
+        );
+        assert_eq!(subtitle_container_codec("avi", "srt"), None);
+    }
+
+    #[test]
+    fn sizes_disk_space_requirements() {
+        assert_eq!(required_remux_space(0), 64 * 1024 * 1024);
+        assert_eq!(
+            required_remux_space(10 * 1024 * 1024 * 1024),
+            11_274_289_152
+        );
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1_610_612_736), "1.5 GB");
+    }
+
+    #[test]
+    fn encodes_subtitles_with_bom_line_endings_and_legacy_encodings() {
+        let utf8 = SubtitleEncodingOptions::default();
+        assert_eq!(
+            encode_subtitle_content("1\nCafé", &utf8, true).unwrap(),
+            b"\xEF\xBB\xBF1\nCaf\xC3\xA9"
+        );
+
+        let cp1252 = SubtitleEncodingOptions {
+            encoding: Some("windows-1252".to_string()),
+            bom: Some(true),
+            line_ending: LineEnding::Crlf,
+        };
+        assert_eq!(
+            encode_subtitle_content("1\r\nCafé\n", &cp1252, true).unwrap(),
+            b"1\r\nCaf\xE9\r\n"
+        );
+
+        let sjis = SubtitleEncodingOptions {
+            encoding: Some("shift_jis".to_string()),
+            ..SubtitleEncodingOptions::default()
+        };
+        assert_eq!(
+            encode_subtitle_content("はい", &sjis, false).unwrap(),
+            b"\x82\xCD\x82\xA2"
+        );
+        assert!(encode_subtitle_content("はい", &cp1252, false)
+            .unwrap_err()
+            .to_string()
+            .contains("'は'"));
+
+        let utf16 = SubtitleEncodingOptions {
+            encoding: Some("utf-16le".to_string()),
+            ..SubtitleEncodingOptions::default()
+        };
+        assert!(encode_subtitle_content("a", &utf16, false).is_err());
+    }
+
+    #[test]
+    fn formats_timestamps_in_the_template_style() {
+        assert_eq!(
+            format_subtitle_timestamp_like("0:00:01.00", 3725.456),
+            "1:02:05.46"
+        );
+        assert_eq!(
+            format_subtitle_timestamp_like("00:00:01,000", 61.5),
+            "00:01:01,500"
+        );
+        assert_eq!(
+            format_subtitle_timestamp_like("00:01.000", 2.25),
+            "00:02.250"
+        );
+        assert_eq!(
+            format_subtitle_timestamp_like("00:01.000", -3.0),
+            "00:00.000"
+        );
+    }
+
+    #[test]
+    fn builds_verbatim_paths_for_long_and_exotic_windows_paths() {
+        let japanese = r"D:\アニメ\進撃の巨人\第01話 「二千年後の君へ」.mkv";
+        assert_eq!(
+            verbatim_windows_path(japanese).as_deref(),
+            Some(r"\\?\D:\アニメ\進撃の巨人\第01話 「二千年後の君へ」.mkv")
+        );
+        assert_eq!(
+            verbatim_windows_path("C:/Anime/./Show/../Show/ep.mkv").as_deref(),
+            Some(r"\\?\C:\Anime\Show\ep.mkv")
+        );
+        assert_eq!(
+            verbatim_windows_path(r"\\nas\media\..\..\Série\ep.mkv").as_deref(),
+            Some(r"\\?\UNC\nas\media\Série\ep.mkv")
+        );
+        assert_eq!(verbatim_windows_path(r"\\?\C:\already.mkv"), None);
+        assert_eq!(verbatim_windows_path(r"relative\ep.mkv"), None);
+
+        let long = format!(r"C:\{}\ep.mkv", "長い名前".repeat(80));
+        assert!(long.encode_utf16().count() > WINDOWS_MAX_PATH);
+        let verbatim = verbatim_windows_path(&long).unwrap();
+        assert!(verbatim.starts_with(r"\\?\C:\長い名前"));
+        assert!(verbatim.ends_with(r"\ep.mkv"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn passes_non_utf8_paths_to_tools_unchanged() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = OsStr::from_bytes(b"/anime/\xff\xfe-ep01.mkv");
+        assert_eq!(tool_path_arg(Path::new(raw)), raw.to_os_string());
+        assert_eq!(
+            tool_path("/アニメ/-ep01 [1080p].mkv"),
+            "/アニメ/-ep01 [1080p].mkv"
+        );
+    }
+
+    #[test]
+    fn keeps_file_names_and_metadata_from_reading_as_options() {
+        assert_eq!(ffmpeg_file("-ep01.mkv"), "file:-ep01.mkv");
+        assert_eq!(ffmpeg_file("concat:a.mkv|b.mkv"), "file:concat:a.mkv|b.mkv");
+        assert_eq!(
+            mkvtoolnix_file("--title x.mkv"),
+            Path::new(".").join("--title x.mkv").to_string_lossy()
+        );
+        assert_eq!(
+            mkvtoolnix_file("@options.json"),
+            Path::new(".").join("@options.json").to_string_lossy()
+        );
+        assert_eq!(mkvtoolnix_file("/anime/-ep01.mkv"), "/anime/-ep01.mkv");
+
+        assert_eq!(tool_metadata(" Signs\n-map 0:a "), "Signs -map 0:a");
+        assert!(is_valid_language_code("jpn"));
+        assert!(is_valid_language_code("pt-BR"));
+        assert!(is_valid_language_code("zh-Hant"));
+        assert_eq!(tool_language("eng -map 0"), "und");
+        assert_eq!(tool_language("-i"), "und");
+        assert_eq!(tool_language(""), "und");
+    }
+}
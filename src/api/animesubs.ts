@@ -1,23 +1,118 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import type {
+  AnimeMetadata,
+  AppSettings,
+  AnimeSubsError,
+  AssMetadataOptions,
+  AssStyle,
+  AssStylePatch,
+  AutomationQueue,
+  AutomationSettings,
   BackupInfo,
+  BackupRetention,
+  BurnOptions,
+  CapabilityReport,
+  CharacterName,
+  ChapterInfo,
+  CleanupOptions,
+  CleanupResult,
+  DiskSpaceCheck,
+  EmbedCompatibilityReport,
+  EmbedTrackOptions,
+  ExtractionManifest,
   ExtractResult,
+  FontReport,
+  FormatConversionOptions,
+  HistoryEntry,
+  HonorificIssue,
+  IncompatibleSubtitleAction,
+  JobDebugLog,
+  JobEstimate,
+  JobReport,
+  LineEdit,
+  LineSkipOverride,
+  LlmConnectionReport,
   LlmConfig,
+  LogEntry,
+  LogSettings,
+  MediaServerSettings,
+  MkvToolNixStatus,
   OperationResult,
+  OrphanCleanupResult,
+  OutputPolicy,
+  ParserSuiteReport,
+  Preset,
+  PromptTemplate,
+  ProviderHealth,
+  ProviderModel,
+  ProxySettings,
+  RateLimitOptions,
+  RecentProject,
+  ReleaseInfo,
+  ReleaseSeries,
+  ReviewRow,
+  ScannedVideo,
+  SeriesContext,
+  ServerSettings,
+  ServerStatus,
+  SignsSplitResult,
   SubtitleData,
+  SubtitleEmbed,
+  SubtitleEncodingOptions,
+  SubtitleFormat,
+  SubtitleTrackPreview,
+  TimingAdjustment,
+  ToolDownloadProgress,
+  TrackScore,
+  TranscriptionOptions,
   TranslationJobRequest,
   TranslationJobResult,
-  VideoInfo
+  TranslationProject,
+  ValidationOptions,
+  ValidationReport,
+  VideoInfo,
+  WatchFolder,
+  WatchSettings,
+  WrapOptions
 } from '../types/domain'
 
+export const isAnimeSubsError = (error: unknown): error is AnimeSubsError =>
+  typeof error === 'object' && error !== null && 'code' in error && 'message' in error
+
+/** Message text of a rejected command, whatever shape the rejection has. */
+export const errorMessage = (error: unknown): string =>
+  isAnimeSubsError(error) ? error.message : error instanceof Error ? error.message : `${error}`
+
 export const checkFfmpeg = (ffmpegPath?: string | null) =>
   invoke<OperationResult>('check_ffmpeg', { ffmpegPath: ffmpegPath || null })
 
+/** Installs a static ffmpeg/ffprobe build into the app data dir. */
+export const downloadFfmpeg = () =>
+  invoke<OperationResult>('download_ffmpeg')
+
+export const checkMkvtoolnix = () =>
+  invoke<MkvToolNixStatus>('check_mkvtoolnix')
+
+/** Installs the portable MKVToolNix build into the app data dir (Windows only). */
+export const downloadMkvtoolnix = () =>
+  invoke<OperationResult>('download_mkvtoolnix')
+
+export const onToolDownloadProgress = (handler: (progress: ToolDownloadProgress) => void) =>
+  listen<ToolDownloadProgress>('tool-download-progress', (event) => handler(event.payload))
+
+export const probeCapabilities = (ffmpegPath?: string | null, refresh = false) =>
+  invoke<CapabilityReport>('probe_capabilities', { ffmpegPath: ffmpegPath || null, refresh })
+
 export const getVideoInfo = (videoPath: string, ffmpegPath?: string | null) =>
   invoke<VideoInfo>('get_video_info', { videoPath, ffmpegPath: ffmpegPath || null })
 
+/** `getVideoInfo` with `detected_language` set on text tracks; extracts every track. */
+export const detectSubtitleLanguages = (videoPath: string, ffmpegPath?: string | null) =>
+  invoke<VideoInfo>('detect_subtitle_languages', { videoPath, ffmpegPath: ffmpegPath || null })
+
 export const scanFolderForVideos = (folderPath: string) =>
-  invoke<string[]>('scan_folder_for_videos', { folderPath })
+  invoke<ScannedVideo[]>('scan_folder_for_videos', { folderPath })
 
 export const extractSubtitle = (params: {
   videoPath: string
@@ -26,43 +121,315 @@ export const extractSubtitle = (params: {
   format?: string | null
   temporary?: boolean | null
   ffmpegPath?: string | null
+  namingTemplate?: string | null
 }) => invoke<ExtractResult>('extract_subtitle', params)
 
+export const extractAllSubtitles = (params: {
+  folderPath: string
+  languages?: string[] | null
+  format?: string | null
+  namingTemplate?: string | null
+  ffmpegPath?: string | null
+}) => invoke<ExtractionManifest>('extract_all_subtitles', params)
+
 export const parseSubtitleFile = (filePath: string) =>
   invoke<SubtitleData>('parse_subtitle_file', { filePath })
 
+export const parseEmbeddedSubtitle = (
+  videoPath: string,
+  trackIndex: number,
+  ffmpegPath?: string | null
+) =>
+  invoke<SubtitleData>('parse_embedded_subtitle', {
+    videoPath,
+    trackIndex,
+    ffmpegPath: ffmpegPath || null
+  })
+
+export const previewSubtitleTrack = (
+  videoPath: string,
+  trackIndex: number,
+  maxLines?: number | null,
+  ffmpegPath?: string | null
+) =>
+  invoke<SubtitleTrackPreview>('preview_subtitle_track', {
+    videoPath,
+    trackIndex,
+    maxLines: maxLines ?? null,
+    ffmpegPath: ffmpegPath || null
+  })
+
+export const chooseBestSubtitleTrack = (
+  videoPath: string,
+  preferredLanguages: string[],
+  countLines = false,
+  ffmpegPath?: string | null
+) =>
+  invoke<TrackScore[]>('choose_best_subtitle_track', {
+    videoPath,
+    preferredLanguages,
+    countLines,
+    ffmpegPath: ffmpegPath || null
+  })
+
+export const shiftSubtitleTiming = (
+  subtitlePath: string,
+  adjustment: TimingAdjustment,
+  outputPath?: string | null
+) =>
+  invoke<OperationResult>('shift_subtitle_timing', {
+    subtitlePath,
+    adjustment,
+    outputPath: outputPath || null
+  })
+
+export const convertSubtitleFramerate = (
+  subtitlePath: string,
+  sourceFps: string,
+  targetFps: string,
+  outputPath?: string | null
+) =>
+  invoke<OperationResult>('convert_subtitle_framerate', {
+    subtitlePath,
+    sourceFps,
+    targetFps,
+    outputPath: outputPath || null
+  })
+
+export const convertSubtitleFormat = (
+  subtitlePath: string,
+  targetFormat: SubtitleFormat,
+  outputPath?: string | null,
+  options?: FormatConversionOptions | null
+) =>
+  invoke<OperationResult>('convert_subtitle_format', {
+    subtitlePath,
+    targetFormat,
+    outputPath: outputPath || null,
+    options: options || null
+  })
+
+export const listAssStyles = (subtitlePath: string) =>
+  invoke<AssStyle[]>('list_ass_styles', { subtitlePath })
+
+export const saveAssStyles = (
+  subtitlePath: string,
+  styles: AssStyle[],
+  outputPath?: string | null
+) =>
+  invoke<OperationResult>('save_ass_styles', {
+    subtitlePath,
+    styles,
+    outputPath: outputPath || null
+  })
+
+export const applyAssStylePreset = (
+  subtitlePath: string,
+  preset: AssStylePatch,
+  styleNames?: string[] | null,
+  outputPath?: string | null
+) =>
+  invoke<OperationResult>('apply_ass_style_preset', {
+    subtitlePath,
+    preset,
+    styleNames: styleNames || null,
+    outputPath: outputPath || null
+  })
+
+export const analyzeSubtitleFonts = (params: {
+  subtitlePath: string
+  videoPath?: string | null
+  ffmpegPath?: string | null
+  fallbackFont?: string | null
+  outputPath?: string | null
+}) => invoke<FontReport>('analyze_subtitle_fonts', params)
+
+export const overrideLineSkips = (subtitleData: SubtitleData, overrides: LineSkipOverride[]) =>
+  invoke<SubtitleData>('override_line_skips', { subtitleData, overrides })
+
+export const splitSignsTrack = (
+  subtitlePath: string,
+  dialogueOutput?: string | null,
+  signsOutput?: string | null
+) =>
+  invoke<SignsSplitResult>('split_signs_track', {
+    subtitlePath,
+    dialogueOutput: dialogueOutput || null,
+    signsOutput: signsOutput || null
+  })
+
+export const shiftSubtitleDataTiming = (subtitleData: SubtitleData, adjustment: TimingAdjustment) =>
+  invoke<SubtitleData>('shift_subtitle_data_timing', { subtitleData, adjustment })
+
+export const validateSubtitle = (subtitleData: SubtitleData, options?: ValidationOptions | null) =>
+  invoke<ValidationReport>('validate_subtitle', { subtitleData, options: options || null })
+
+export const rewrapSubtitleLines = (subtitleData: SubtitleData, options?: WrapOptions | null) =>
+  invoke<SubtitleData>('rewrap_subtitle_lines', { subtitleData, options: options || null })
+
+export const cleanSubtitle = (subtitleData: SubtitleData, options: CleanupOptions) =>
+  invoke<CleanupResult>('clean_subtitle', { subtitleData, options })
+
+export const analyzeCharacterNames = (subtitlePaths: string[], seriesFolder?: string | null) =>
+  invoke<CharacterName[]>('analyze_character_names', {
+    subtitlePaths,
+    seriesFolder: seriesFolder || null
+  })
+
+export const loadNameTable = (seriesFolder: string) =>
+  invoke<CharacterName[]>('load_name_table', { seriesFolder })
+
+export const saveNameTable = (seriesFolder: string, names: CharacterName[]) =>
+  invoke<OperationResult>('save_name_table', { seriesFolder, names })
+
+export const suggestGlossaryTerms = (request: TranslationJobRequest, existing: CharacterName[]) =>
+  invoke<CharacterName[]>('suggest_glossary_terms', { request, existing })
+
+export const importGlossary = (path: string) =>
+  invoke<CharacterName[]>('import_glossary', { path })
+
+export const exportGlossary = (path: string, names: CharacterName[]) =>
+  invoke<OperationResult>('export_glossary', { path, names })
+
+export const checkHonorifics = (subtitleData: SubtitleData) =>
+  invoke<HonorificIssue[]>('check_honorifics', { subtitleData })
+
+export const fixHonorifics = (params: {
+  subtitleData: SubtitleData
+  config: LlmConfig
+  sourceLang: string
+  targetLang: string
+}) => invoke<SubtitleData>('fix_honorifics', params)
+
+export const getTranslationDiff = (subtitleData: SubtitleData) =>
+  invoke<ReviewRow[]>('get_translation_diff', { subtitleData })
+
+export const applyLineEdits = (subtitleData: SubtitleData, edits: LineEdit[]) =>
+  invoke<SubtitleData>('apply_line_edits', { subtitleData, edits })
+
+/** PNG bytes of the frame at `timeSeconds` with `subtitleData` rendered over it. */
+export const renderPreviewFrame = (
+  videoPath: string,
+  timeSeconds: number,
+  subtitleData?: SubtitleData | null,
+  ffmpegPath?: string | null
+) =>
+  invoke<ArrayBuffer>('render_preview_frame', {
+    videoPath,
+    timeSeconds,
+    subtitleData: subtitleData ?? null,
+    ffmpegPath: ffmpegPath || null
+  })
+
+export const exportTranslationTable = (
+  subtitleData: SubtitleData,
+  outputPath: string,
+  format?: 'csv' | 'xlsx' | 'html' | null
+) =>
+  invoke<OperationResult>('export_translation_table', {
+    subtitleData,
+    outputPath,
+    format: format ?? null
+  })
+
+export const importReviewedTable = (subtitleData: SubtitleData, path: string) =>
+  invoke<SubtitleData>('import_reviewed_table', { subtitleData, path })
+
 export const translateSubtitles = (params: {
   subtitleData: SubtitleData
   config: LlmConfig
   sourceLang: string
   targetLang: string
+  rateLimit?: RateLimitOptions | null
 }) => invoke<SubtitleData>('translate_subtitles', params)
 
+export const refineTranslations = (params: {
+  subtitleData: SubtitleData
+  config: LlmConfig
+  sourceLang: string
+  targetLang: string
+}) => invoke<SubtitleData>('refine_translations', params)
+
+export const attachReferenceTrack = (subtitleData: SubtitleData, referenceData: SubtitleData) =>
+  invoke<SubtitleData>('attach_reference_track', { subtitleData, referenceData })
+
 export const saveTranslatedSubtitles = (params: {
   translatedData: SubtitleData
   outputPath?: string | null
   originalFilePath?: string | null
   temporary?: boolean | null
+  namingTemplate?: string | null
+  targetLang?: string | null
+  outputEncoding?: SubtitleEncodingOptions | null
+  dialogueStyle?: AssStylePatch | null
+  assMetadata?: AssMetadataOptions | null
 }) => invoke<OperationResult>('save_translated_subtitles', params)
 
-export const backupSubtitle = (videoPath: string, trackIndex: number, ffmpegPath?: string | null) =>
-  invoke<BackupInfo>('backup_subtitle', { videoPath, trackIndex, ffmpegPath: ffmpegPath || null })
+export const backupSubtitle = (
+  videoPath: string,
+  trackIndex: number,
+  ffmpegPath?: string | null,
+  central = false
+) =>
+  invoke<BackupInfo>('backup_subtitle', {
+    videoPath,
+    trackIndex,
+    ffmpegPath: ffmpegPath || null,
+    central
+  })
 
 export const listBackups = (videoPath: string) =>
   invoke<BackupInfo[]>('list_backups', { videoPath })
 
+export const listAllBackups = (root: string) =>
+  invoke<BackupInfo[]>('list_all_backups', { root })
+
+export const pruneBackups = (root: string, retention: BackupRetention) =>
+  invoke<OperationResult>('prune_backups', { root, retention })
+
+export const relocateBackups = (root: string) =>
+  invoke<OperationResult>('relocate_backups', { root })
+
 export const restoreSubtitle = (params: {
   videoPath: string
   backupPath: string
   trackIndex: number
   ffmpegPath?: string | null
+  outputPolicy?: OutputPolicy | null
+  verifyDecode?: boolean
 }) => invoke<OperationResult>('restore_subtitle', params)
 
 export const deleteBackup = (backupPath: string, videoPath: string) =>
   invoke<OperationResult>('delete_backup', { backupPath, videoPath })
 
-export const removeSubtitleTrack = (videoPath: string, trackIndex: number, ffmpegPath?: string | null) =>
-  invoke<OperationResult>('remove_subtitle_track', { videoPath, trackIndex, ffmpegPath: ffmpegPath || null })
+export const removeSubtitleTrack = (
+  videoPath: string,
+  trackIndex: number,
+  ffmpegPath?: string | null,
+  outputPolicy?: OutputPolicy | null,
+  verifyDecode = false
+) =>
+  invoke<OperationResult>('remove_subtitle_track', {
+    videoPath,
+    trackIndex,
+    ffmpegPath: ffmpegPath || null,
+    outputPolicy: outputPolicy || null,
+    verifyDecode
+  })
+
+export const setDefaultAudioTrack = (params: {
+  videoPath: string
+  trackIndex: number
+  ffmpegPath?: string | null
+  outputPolicy?: OutputPolicy | null
+}) => invoke<OperationResult>('set_default_audio_track', params)
+
+export const removeAudioTrack = (params: {
+  videoPath: string
+  trackIndex: number
+  ffmpegPath?: string | null
+  outputPolicy?: OutputPolicy | null
+}) => invoke<OperationResult>('remove_audio_track', params)
 
 export const embedSubtitle = (params: {
   videoPath: string
@@ -72,19 +439,172 @@ export const embedSubtitle = (params: {
   setDefault: boolean
   ffmpegPath?: string | null
   useMkvmerge: boolean
+  outputPolicy?: OutputPolicy | null
+  verifyDecode?: boolean
+  trackOptions?: EmbedTrackOptions | null
 }) => invoke<OperationResult>('embed_subtitle', params)
 
+export const embedSubtitlesBatch = (params: {
+  videoPath: string
+  subtitles: SubtitleEmbed[]
+  ffmpegPath?: string | null
+  useMkvmerge: boolean
+  outputPolicy?: OutputPolicy | null
+  verifyDecode?: boolean
+  clearExistingDefaults?: boolean
+  onIncompatible?: IncompatibleSubtitleAction | null
+}) => invoke<OperationResult>('embed_subtitles_batch', params)
+
+export const checkEmbedCompatibility = (videoPath: string, subtitlePaths: string[]) =>
+  invoke<EmbedCompatibilityReport>('check_embed_compatibility', { videoPath, subtitlePaths })
+
 export const deleteFile = (filePath: string) =>
   invoke<OperationResult>('delete_file', { filePath })
 
+export const checkDiskSpace = (videoPath: string, outputPolicy?: OutputPolicy | null) =>
+  invoke<DiskSpaceCheck>('check_disk_space', { videoPath, outputPolicy: outputPolicy ?? null })
+
+export const estimateTranslationJob = (request: TranslationJobRequest) =>
+  invoke<JobEstimate>('estimate_translation_job', { request })
+
 export const startTranslationJob = (request: TranslationJobRequest) =>
   invoke<TranslationJobResult>('start_translation_job', { request })
 
-export const loadApiKey = (provider: string) =>
-  invoke<OperationResult>('load_api_key', { provider })
+export const pauseTranslationJob = () =>
+  invoke<OperationResult>('pause_translation_job')
+
+export const resumeTranslationJob = () =>
+  invoke<OperationResult>('resume_translation_job')
+
+export const cancelTranslationJob = () =>
+  invoke<OperationResult>('cancel_translation_job')
+
+export const getApiKey = (provider: string) =>
+  invoke<OperationResult>('get_api_key', { provider })
+
+export const storeApiKey = (provider: string, apiKey: string) =>
+  invoke<OperationResult>('store_api_key', { provider, apiKey })
+
+export const loadAppState = <T>() =>
+  invoke<T | null>('load_app_state')
+
+export const saveAppState = (state: unknown) =>
+  invoke<OperationResult>('save_app_state', { state })
+
+export const listModels = (provider: string, endpoint: string, apiKey?: string | null) =>
+  invoke<ProviderModel[]>('list_models', { provider, endpoint, apiKey: apiKey || null })
+
+export const testLlmConnection = (config: LlmConfig, targetLang: string) =>
+  invoke<LlmConnectionReport>('test_llm_connection', { config, targetLang })
+
+export const getLastJobDebugLog = () =>
+  invoke<JobDebugLog | null>('get_last_job_debug_log')
+
+export const getMediaServerSettings = () =>
+  invoke<MediaServerSettings>('get_media_server_settings')
+
+export const saveMediaServerSettings = (settings: MediaServerSettings) =>
+  invoke<OperationResult>('save_media_server_settings', { settings })
+
+/** Checks the URL and key with a request that needs both. */
+export const testMediaServer = (settings: MediaServerSettings) =>
+  invoke<OperationResult>('test_media_server', { settings })
+
+export const getServerStatus = () =>
+  invoke<ServerStatus>('get_server_status')
+
+/** Saves the settings and starts, restarts or stops the remote server to match. */
+export const saveServerSettings = (settings: ServerSettings) =>
+  invoke<ServerStatus>('save_server_settings', { settings })
+
+export const regenerateServerToken = () =>
+  invoke<ServerStatus>('regenerate_server_token')
+
+export const getAutomationSettings = () =>
+  invoke<AutomationSettings>('get_automation_settings')
+
+export const saveAutomationSettings = (settings: AutomationSettings) =>
+  invoke<OperationResult>('save_automation_settings', { settings })
+
+export const getAutomationQueue = () =>
+  invoke<AutomationQueue>('get_automation_queue')
+
+/** Queues videos for automatic translation; returns the paths actually added. */
+export const enqueueAutomation = (paths: string[]) =>
+  invoke<string[]>('enqueue_automation', { paths })
+
+export const getFolderWatch = () =>
+  invoke<WatchSettings>('get_folder_watch')
+
+/** Saves the watched folders; a running watch switches to them right away. */
+export const saveWatchFolders = (folders: WatchFolder[]) =>
+  invoke<WatchSettings>('save_watch_folders', { folders })
+
+export const startFolderWatch = () =>
+  invoke<WatchSettings>('start_folder_watch')
+
+export const stopFolderWatch = () =>
+  invoke<WatchSettings>('stop_folder_watch')
+
+export const getProxySettings = () =>
+  invoke<ProxySettings>('get_proxy_settings')
+
+export const saveProxySettings = (settings: ProxySettings) =>
+  invoke<OperationResult>('save_proxy_settings', { settings })
+
+export const checkProviderHealth = (
+  provider: string,
+  endpoint: string,
+  apiKey?: string | null,
+  model?: string | null
+) =>
+  invoke<ProviderHealth>('check_provider_health', {
+    provider,
+    endpoint,
+    apiKey: apiKey || null,
+    model: model || null
+  })
+
+export const listPromptTemplates = () =>
+  invoke<PromptTemplate[]>('list_prompt_templates')
+
+export const savePromptTemplate = (template: PromptTemplate) =>
+  invoke<OperationResult>('save_prompt_template', { template })
+
+export const deletePromptTemplate = (name: string) =>
+  invoke<OperationResult>('delete_prompt_template', { name })
+
+export const loadSeriesContext = (seriesFolder: string) =>
+  invoke<SeriesContext | null>('load_series_context', { seriesFolder })
 
-export const saveApiKey = (provider: string, apiKey: string) =>
-  invoke<OperationResult>('save_api_key', { provider, apiKey })
+export const saveSeriesContext = (seriesFolder: string, context: SeriesContext) =>
+  invoke<OperationResult>('save_series_context', { seriesFolder, context })
+
+export const fetchAnilistSynopsis = (title: string) =>
+  invoke<SeriesContext>('fetch_anilist_synopsis', { title })
+
+export const lookupAnime = (title: string, episode?: number | null) =>
+  invoke<AnimeMetadata>('lookup_anime', { title, episode: episode ?? null })
+
+export const identifyEpisode = (filePath: string) =>
+  invoke<AnimeMetadata>('identify_episode', { filePath })
+
+export const parseReleaseFilename = (fileName: string) =>
+  invoke<ReleaseInfo>('parse_release_filename', { fileName })
+
+export const groupReleaseFiles = (paths: string[]) =>
+  invoke<ReleaseSeries[]>('group_release_files', { paths })
+
+export const validateNamingTemplate = (template: string) =>
+  invoke<OperationResult>('validate_naming_template', { template })
+
+export const previewOutputName = (params: {
+  template: string
+  sourcePath: string
+  lang?: string | null
+  ext?: string | null
+  trackIndex?: number | null
+}) => invoke<string>('preview_output_name', params)
 
 export const fetchModels = (endpoint: string, apiKey?: string | null, provider?: string | null) =>
   invoke<{ label: string; value: string }[]>('fetch_models', {
@@ -92,3 +612,109 @@ export const fetchModels = (endpoint: string, apiKey?: string | null, provider?:
     apiKey: apiKey || null,
     provider: provider || null
   })
+
+export const listChapters = (videoPath: string, ffmpegPath?: string | null) =>
+  invoke<ChapterInfo[]>('list_chapters', { videoPath, ffmpegPath: ffmpegPath || null })
+
+export const translateChapters = (params: {
+  chapters: ChapterInfo[]
+  config: LlmConfig
+  sourceLang: string
+  targetLang: string
+}) => invoke<ChapterInfo[]>('translate_chapters', params)
+
+export const writeChapters = (videoPath: string, chapters: ChapterInfo[], language?: string | null) =>
+  invoke<OperationResult>('write_chapters', { videoPath, chapters, language: language || null })
+
+export const burnSubtitle = (params: {
+  videoPath: string
+  subtitlePath?: string | null
+  trackIndex?: number | null
+  options?: BurnOptions | null
+  ffmpegPath?: string | null
+  outputPolicy?: OutputPolicy | null
+}) => invoke<OperationResult>('burn_subtitle', params)
+
+export const cancelOperation = (operationId: string) =>
+  invoke<OperationResult>('cancel_operation', { operationId })
+
+export const runParserSuite = (folder: string) =>
+  invoke<ParserSuiteReport>('run_parser_suite', { folder })
+
+export const transcribeAudio = (
+  videoPath: string,
+  options?: TranscriptionOptions | null,
+  ffmpegPath?: string | null
+) =>
+  invoke<SubtitleData>('transcribe_audio', {
+    videoPath,
+    options: options ?? null,
+    ffmpegPath: ffmpegPath || null
+  })
+
+export const saveProject = (path: string, project: TranslationProject) =>
+  invoke<TranslationProject>('save_project', { path, project })
+
+export const openProject = (path: string) =>
+  invoke<TranslationProject>('open_project', { path })
+
+export const listRecentProjects = () =>
+  invoke<RecentProject[]>('list_recent_projects')
+
+export const getHistory = (videoPath?: string | null, limit?: number | null) =>
+  invoke<HistoryEntry[]>('get_history', { videoPath: videoPath || null, limit: limit ?? null })
+
+export const exportHistory = (outputPath: string, format: 'jsonl' | 'csv' = 'jsonl') =>
+  invoke<OperationResult>('export_history', { outputPath, format })
+
+export const cleanupOrphanedTempFiles = () =>
+  invoke<OrphanCleanupResult>('cleanup_orphaned_temp_files')
+
+export const getLogSettings = () =>
+  invoke<LogSettings>('get_log_settings')
+
+export const setLogLevel = (level: string) =>
+  invoke<LogSettings>('set_log_level', { level })
+
+export const tailLogs = (limit?: number | null) =>
+  invoke<LogEntry[]>('tail_logs', { limit: limit ?? null })
+
+/** Writes the given entries to `path` as plain text, one line each. */
+export const exportLogs = (path: string, entries: LogEntry[]) =>
+  invoke<OperationResult>('export_logs', { path, entries })
+
+/** Streams backend log entries as they happen; resolves to the unlisten function. */
+export const onBackendLog = (handler: (entry: LogEntry) => void) =>
+  listen<LogEntry>('backend-log', (event) => handler(event.payload))
+
+export const getSettings = () =>
+  invoke<AppSettings>('get_settings')
+
+/** Saves the given fields over the current settings; `null` clears a field. */
+export const updateSettings = (patch: Partial<AppSettings>) =>
+  invoke<AppSettings>('update_settings', { patch })
+
+export const listPresets = () =>
+  invoke<Preset[]>('list_presets')
+
+export const savePreset = (preset: Preset) =>
+  invoke<OperationResult>('save_preset', { preset })
+
+export const deletePreset = (name: string) =>
+  invoke<OperationResult>('delete_preset', { name })
+
+export const exportPresets = (outputPath: string, names?: string[] | null) =>
+  invoke<OperationResult>('export_presets', { outputPath, names: names ?? null })
+
+export const importPresets = (path: string, overwrite = false) =>
+  invoke<OperationResult>('import_presets', { path, overwrite })
+
+export const listJobReports = (projectPath?: string | null) =>
+  invoke<JobReport[]>('list_job_reports', { projectPath: projectPath ?? null })
+
+export const exportJobReport = (
+  report: JobReport,
+  outputPath: string,
+  format?: 'json' | 'html' | null
+) =>
+  invoke<OperationResult>('export_job_report', { report, outputPath, format: format ?? null })
@@ -1,8 +1,68 @@
-import { computed, ref } from 'vue'
+import { computed, ref, watchEffect } from 'vue'
 import { darkTheme, type GlobalThemeOverrides } from 'naive-ui'
+import { getCurrentWebview } from '@tauri-apps/api/webview'
+import {
+  defaultSettings,
+  type AccentColor,
+  type LogFont,
+  type Settings,
+  type UiDensity
+} from '../config/settings'
 
 const THEME_KEY = 'animesubs-theme'
 
+interface AccentShades {
+  primary: string
+  bright: string
+  pressed: string
+  muted: string
+  /** `r, g, b` of `primary`, for translucent borders and glows. */
+  rgb: string
+}
+
+/**
+ * Accent shades per theme: the bright set suits the dark shell and dark
+ * naive-ui surfaces, the deep set keeps contrast on light surfaces.
+ */
+const ACCENTS: Record<AccentColor, { dark: AccentShades; light: AccentShades }> = {
+  wired: {
+    dark: { primary: '#7ce8a0', bright: '#8fffb8', pressed: '#5ab878', muted: '#4a7a5c', rgb: '124, 232, 160' },
+    light: { primary: '#2f8a52', bright: '#3aa563', pressed: '#226a3e', muted: '#5c7a66', rgb: '47, 138, 82' }
+  },
+  amber: {
+    dark: { primary: '#f0c060', bright: '#ffd98a', pressed: '#c89a40', muted: '#7a6a44', rgb: '240, 192, 96' },
+    light: { primary: '#a86e08', bright: '#c4840e', pressed: '#85570a', muted: '#7a6a50', rgb: '168, 110, 8' }
+  },
+  cyan: {
+    dark: { primary: '#6cd8f0', bright: '#9aeaff', pressed: '#48aac4', muted: '#46707c', rgb: '108, 216, 240' },
+    light: { primary: '#107a94', bright: '#1492b0', pressed: '#0c5e72', muted: '#50707a', rgb: '16, 122, 148' }
+  },
+  violet: {
+    dark: { primary: '#b49cff', bright: '#cdbcff', pressed: '#8c74d8', muted: '#625a82', rgb: '180, 156, 255' },
+    light: { primary: '#5e3fc4', bright: '#7252dc', pressed: '#48309a', muted: '#6a6282', rgb: '94, 63, 196' }
+  },
+  rose: {
+    dark: { primary: '#f08cb4', bright: '#ffb0cf', pressed: '#c4688e', muted: '#7a5064', rgb: '240, 140, 180' },
+    light: { primary: '#b02a62', bright: '#c83a76', pressed: '#8a204c', muted: '#7a5a68', rgb: '176, 42, 98' }
+  }
+}
+
+const DENSITY_SCALE: Record<UiDensity, number> = {
+  compact: 0.75,
+  comfortable: 1,
+  spacious: 1.25
+}
+
+const LOG_FONTS: Record<LogFont, string> = {
+  system: 'ui-monospace, "SFMono-Regular", "Cascadia Code", "Liberation Mono", Menlo, monospace',
+  jetbrains: '"JetBrains Mono", ui-monospace, monospace',
+  fira: '"Fira Code", "Fira Mono", ui-monospace, monospace',
+  dejavu: '"DejaVu Sans Mono", ui-monospace, monospace',
+  ubuntu: '"Ubuntu Mono", ui-monospace, monospace'
+}
+
+type Appearance = Pick<Settings, 'accentColor' | 'uiDensity' | 'logFont' | 'uiScale'>
+
 const getInitialTheme = () => {
   try {
     const saved = localStorage.getItem(THEME_KEY)
@@ -18,31 +78,76 @@ const getInitialTheme = () => {
 export const useAppTheme = () => {
   const isDark = ref(getInitialTheme())
   const theme = computed(() => isDark.value ? darkTheme : null)
+  const appearance = ref<Appearance>({
+    accentColor: defaultSettings.accentColor,
+    uiDensity: defaultSettings.uiDensity,
+    logFont: defaultSettings.logFont,
+    uiScale: defaultSettings.uiScale
+  })
+
+  const accent = computed(() => ACCENTS[appearance.value.accentColor] ?? ACCENTS.wired)
+
+  const themeOverrides = computed<GlobalThemeOverrides>(() => {
+    const shades = isDark.value ? accent.value.dark : accent.value.light
+    return {
+      common: {
+        primaryColor: shades.primary,
+        primaryColorHover: shades.bright,
+        primaryColorPressed: shades.pressed,
+        primaryColorSuppl: '#8088cc',
+        borderRadius: '0px',
+        ...(isDark.value ? {
+          bodyColor: '#020205',
+          cardColor: '#0a0a18',
+          modalColor: '#0a0a18',
+          popoverColor: '#111128',
+          tableColor: '#0a0a18',
+          inputColor: '#020208',
+          actionColor: '#111128',
+          hoverColor: `rgba(${shades.rgb}, 0.10)`,
+          borderColor: `rgba(${shades.rgb}, 0.16)`,
+          dividerColor: `rgba(${shades.rgb}, 0.16)`,
+          textColor1: shades.bright,
+          textColor2: shades.primary,
+          textColor3: shades.muted
+        } : {})
+      }
+    }
+  })
+
+  // The app shell keeps its dark look in both themes, so its variables always
+  // take the dark shades; naive-ui surfaces follow the theme above.
+  watchEffect(() => {
+    const root = document.documentElement
+    const shades = accent.value.dark
+    root.dataset.theme = isDark.value ? 'dark' : 'light'
+    root.style.setProperty('--wired-paper', shades.primary)
+    root.style.setProperty('--wired-paper-bright', shades.bright)
+    root.style.setProperty('--wired-muted', shades.muted)
+    root.style.setProperty('--wired-border', `rgba(${shades.rgb}, 0.16)`)
+    root.style.setProperty('--wired-border-strong', `rgba(${shades.rgb}, 0.32)`)
+    root.style.setProperty('--wired-glow', `rgba(${shades.rgb}, 0.12)`)
+    root.style.setProperty('--wired-density', `${DENSITY_SCALE[appearance.value.uiDensity] ?? 1}`)
+    root.style.setProperty('--font-log', LOG_FONTS[appearance.value.logFont] ?? LOG_FONTS.system)
+  })
 
-  const themeOverrides = computed<GlobalThemeOverrides>(() => ({
-    common: {
-      primaryColor: '#7ce8a0',
-      primaryColorHover: '#8fffb8',
-      primaryColorPressed: '#5ab878',
-      primaryColorSuppl: '#8088cc',
-      borderRadius: '0px',
-      ...(isDark.value ? {
-        bodyColor: '#020205',
-        cardColor: '#0a0a18',
-        modalColor: '#0a0a18',
-        popoverColor: '#111128',
-        tableColor: '#0a0a18',
-        inputColor: '#020208',
-        actionColor: '#111128',
-        hoverColor: 'rgba(124, 232, 160, 0.10)',
-        borderColor: 'rgba(124, 232, 160, 0.16)',
-        dividerColor: 'rgba(124, 232, 160, 0.16)',
-        textColor1: '#8fffb8',
-        textColor2: '#7ce8a0',
-        textColor3: '#4a7a5c'
-      } : {})
+  watchEffect(() => {
+    const scale = appearance.value.uiScale
+    getCurrentWebview().setZoom(scale).catch((e) => {
+      console.error('Failed to apply UI scale:', e)
+    })
+  })
+
+  /** Applies the appearance part of saved settings. */
+  const setAppearance = (settings: Settings | null) => {
+    const source = settings ?? defaultSettings
+    appearance.value = {
+      accentColor: source.accentColor,
+      uiDensity: source.uiDensity,
+      logFont: source.logFont,
+      uiScale: source.uiScale
     }
-  }))
+  }
 
   const toggleTheme = () => {
     isDark.value = !isDark.value
@@ -57,6 +162,8 @@ export const useAppTheme = () => {
     isDark,
     theme,
     themeOverrides,
+    appearance,
+    setAppearance,
     toggleTheme
   }
 }
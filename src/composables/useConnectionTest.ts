@@ -0,0 +1,68 @@
+import { ref, watch, type Ref } from 'vue'
+import { errorMessage, testLlmConnection } from '../api/animesubs'
+import type { Settings } from '../config/settings'
+import { localizeBackendMessage } from '../i18n'
+import type { LlmConfig, LlmConnectionReport } from '../types/domain'
+
+type TranslateFn = (key: string, named?: Record<string, unknown>) => string
+
+interface UseConnectionTestParams {
+  cachedSettings: Ref<Settings | null>
+  getSettings: () => Settings | null
+  /** Provider config the translation job would send. */
+  buildConfig: (settings: Settings) => LlmConfig
+  t: TranslateFn
+}
+
+/** Runs the backend's two-line probe against the current provider settings. */
+export const useConnectionTest = ({
+  cachedSettings,
+  getSettings,
+  buildConfig,
+  t
+}: UseConnectionTestParams) => {
+  const testingConnection = ref(false)
+  const connectionReport = ref<LlmConnectionReport | null>(null)
+
+  const testConnection = async () => {
+    const settings = getSettings()
+    if (!settings || testingConnection.value) return
+    testingConnection.value = true
+    try {
+      const report = await testLlmConnection(buildConfig(settings), settings.targetLanguage)
+      connectionReport.value = { ...report, message: localizeBackendMessage(report.message, t) }
+    } catch (e) {
+      connectionReport.value = {
+        success: false,
+        latencyMs: 0,
+        jsonMode: false,
+        authError: false,
+        message: localizeBackendMessage(errorMessage(e), t),
+        translations: [],
+        model: settings.selectedModel || '',
+        errorKind: 'other'
+      }
+    } finally {
+      testingConnection.value = false
+    }
+  }
+
+  // A result only describes the settings it was run with.
+  watch(
+    () => [
+      cachedSettings.value?.provider,
+      cachedSettings.value?.apiEndpoint,
+      cachedSettings.value?.apiKey,
+      cachedSettings.value?.selectedModel
+    ],
+    () => {
+      connectionReport.value = null
+    }
+  )
+
+  return {
+    testingConnection,
+    connectionReport,
+    testConnection
+  }
+}
@@ -0,0 +1,91 @@
+import { defaultWindowIcon } from '@tauri-apps/api/app'
+import { TrayIcon } from '@tauri-apps/api/tray'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import {
+  isPermissionGranted,
+  requestPermission,
+  sendNotification
+} from '@tauri-apps/plugin-notification'
+import type { Settings } from '../config/settings'
+
+const TRAY_ID = 'animesubs-progress'
+
+/**
+ * Desktop notifications and the optional tray icon for long batch jobs. Both
+ * follow the Interface settings and stay quiet while the window has focus.
+ */
+export const useDesktopNotifications = (getSettings: () => Settings | null) => {
+  let permission: Promise<boolean> | null = null
+  let tray: Promise<TrayIcon | null> | null = null
+  let trayTooltip: string | null = null
+
+  const allowed = () => {
+    permission ??= isPermissionGranted()
+      .then(granted => granted || requestPermission().then(result => result === 'granted'))
+      .catch((e) => {
+        console.error('Failed to check notification permission:', e)
+        return false
+      })
+    return permission
+  }
+
+  const notify = async (title: string, body: string) => {
+    if (!getSettings()?.desktopNotifications) return
+    if (document.hasFocus()) return
+    if (!await allowed()) return
+    try {
+      sendNotification({ title, body })
+    } catch (e) {
+      console.error('Failed to send notification:', e)
+    }
+  }
+
+  const showWindow = async () => {
+    const window = getCurrentWindow()
+    await window.unminimize()
+    await window.show()
+    await window.setFocus()
+  }
+
+  const createTray = async (tooltip: string) => {
+    try {
+      return await TrayIcon.new({
+        id: TRAY_ID,
+        icon: await defaultWindowIcon() ?? undefined,
+        tooltip,
+        action: (event) => {
+          if (event.type === 'Click') void showWindow().catch(() => {})
+        }
+      })
+    } catch (e) {
+      console.error('Failed to create tray icon:', e)
+      return null
+    }
+  }
+
+  /** Shows `tooltip` on the tray icon, or removes the icon when null or disabled. */
+  const setTrayStatus = async (tooltip: string | null) => {
+    const text = getSettings()?.trayProgress ? tooltip : null
+    if (text === trayTooltip) return
+    trayTooltip = text
+
+    if (text === null) {
+      const current = tray
+      tray = null
+      const icon = await current
+      await icon?.close().catch(() => {})
+      return
+    }
+    if (!tray) {
+      tray = createTray(text)
+      return
+    }
+    const icon = await tray
+    await icon?.setTooltip(text).catch(() => {})
+  }
+
+  return {
+    notify,
+    setTrayStatus
+  }
+}
@@ -0,0 +1,53 @@
+import { computed, ref, watch, type Ref } from 'vue'
+import { errorMessage, loadNameTable, saveNameTable } from '../api/animesubs'
+import { localizeBackendMessage } from '../i18n'
+import type { CharacterName, SelectedFile } from '../types/domain'
+import { commonFolder } from './useProjects'
+
+type TranslateFn = (key: string, named?: Record<string, unknown>) => string
+
+/**
+ * Series glossary (the backend's name table) of the queued videos' folder. It
+ * is loaded when the folder changes and sent with every translation request.
+ */
+export const useGlossary = (selectedFiles: Ref<SelectedFile[]>, t: TranslateFn) => {
+  const glossary = ref<CharacterName[]>([])
+  const glossaryError = ref<string | null>(null)
+
+  const seriesFolder = computed(() => selectedFiles.value.length > 0
+    ? commonFolder(selectedFiles.value.map(file => file.path))
+    : null)
+
+  watch(seriesFolder, async (folder) => {
+    glossaryError.value = null
+    if (!folder) {
+      glossary.value = []
+      return
+    }
+    try {
+      const names = await loadNameTable(folder)
+      if (folder === seriesFolder.value) glossary.value = names
+    } catch (e) {
+      glossaryError.value = localizeBackendMessage(errorMessage(e), t)
+    }
+  })
+
+  /** Keeps `names` for the next requests and writes them next to the series. */
+  const saveGlossary = async (names: CharacterName[]) => {
+    glossary.value = names
+    glossaryError.value = null
+    if (!seriesFolder.value) return
+    try {
+      await saveNameTable(seriesFolder.value, names)
+    } catch (e) {
+      glossaryError.value = localizeBackendMessage(errorMessage(e), t)
+    }
+  }
+
+  return {
+    glossary,
+    glossaryError,
+    seriesFolder,
+    saveGlossary
+  }
+}
@@ -0,0 +1,101 @@
+import { watch, type Ref } from 'vue'
+import { loadAppState, saveAppState } from '../api/animesubs'
+import {
+  SETTINGS_STORAGE_KEY,
+  settingsForStorage,
+  type Settings
+} from '../config/settings'
+import type { SelectedFile } from '../types/domain'
+import type { TranslationOptions } from './useTranslationOptions'
+
+const APP_STATE_VERSION = 1
+const SAVE_DEBOUNCE_MS = 1000
+
+interface PersistedAppState {
+  version: number
+  settings: Settings | null
+  translationOptions: TranslationOptions
+  queue: string[]
+}
+
+interface UsePersistedAppStateParams {
+  cachedSettings: Ref<Settings | null>
+  translationOptions: TranslationOptions
+  selectedFiles: Ref<SelectedFile[]>
+  addFiles: (paths: string[]) => Promise<void>
+}
+
+export const usePersistedAppState = ({
+  cachedSettings,
+  translationOptions,
+  selectedFiles,
+  addFiles
+}: UsePersistedAppStateParams) => {
+  let saveTimer: ReturnType<typeof setTimeout> | null = null
+  let restored = false
+
+  const snapshot = (): PersistedAppState => ({
+    version: APP_STATE_VERSION,
+    settings: cachedSettings.value ? settingsForStorage(cachedSettings.value) : null,
+    translationOptions: { ...translationOptions },
+    queue: selectedFiles.value.map(file => file.path)
+  })
+
+  const flushSave = async () => {
+    if (saveTimer !== null) {
+      clearTimeout(saveTimer)
+      saveTimer = null
+    }
+    try {
+      await saveAppState(snapshot())
+    } catch (e) {
+      console.error('Failed to save app state:', e)
+    }
+  }
+
+  const scheduleSave = () => {
+    if (!restored) return
+    if (saveTimer !== null) clearTimeout(saveTimer)
+    saveTimer = setTimeout(() => {
+      void flushSave()
+    }, SAVE_DEBOUNCE_MS)
+  }
+
+  // Seeds localStorage settings from the backend copy so a lost webview
+  // profile does not reset the app. Must run before settings are loaded.
+  const restoreSettings = async (): Promise<PersistedAppState | null> => {
+    try {
+      const state = await loadAppState<PersistedAppState>()
+      if (!state || state.version !== APP_STATE_VERSION) return null
+      if (state.settings && !localStorage.getItem(SETTINGS_STORAGE_KEY)) {
+        localStorage.setItem(SETTINGS_STORAGE_KEY, JSON.stringify(state.settings))
+      }
+      return state
+    } catch (e) {
+      console.error('Failed to load app state:', e)
+      return null
+    }
+  }
+
+  const restoreSession = async (state: PersistedAppState | null) => {
+    if (state) {
+      Object.assign(translationOptions, state.translationOptions)
+      if (state.queue.length > 0) {
+        await addFiles(state.queue)
+      }
+    }
+    restored = true
+  }
+
+  watch(
+    [cachedSettings, () => ({ ...translationOptions }), () => selectedFiles.value.map(f => f.path)],
+    scheduleSave,
+    { deep: true }
+  )
+
+  return {
+    restoreSettings,
+    restoreSession,
+    flushSave
+  }
+}
@@ -0,0 +1,182 @@
+import { save } from '@tauri-apps/plugin-dialog'
+import { ref, watch, type Ref } from 'vue'
+import {
+  errorMessage,
+  listRecentProjects,
+  openProject,
+  saveProject
+} from '../api/animesubs'
+import type { Settings } from '../config/settings'
+import { localizeBackendMessage } from '../i18n'
+import type {
+  FileJobState,
+  ProjectFileStatus,
+  RecentProject,
+  SelectedFile,
+  TranslationJobRequest
+} from '../types/domain'
+
+type TranslateFn = (key: string, named?: Record<string, unknown>) => string
+
+const PROJECT_EXTENSION = 'animesubs'
+
+const parentOf = (path: string) => path.replace(/[\\/][^\\/]*$/, '')
+const baseName = (path: string) => path.split(/[\\/]/).pop() || path
+
+/** Folder shared by every path, or the first path's folder when they differ. */
+export const commonFolder = (paths: string[]) => {
+  const folders = paths.map(parentOf)
+  return folders.every(folder => folder === folders[0]) ? folders[0] : parentOf(folders[0])
+}
+
+const projectStatus = (state: FileJobState | undefined): ProjectFileStatus => {
+  if (state?.stage === 'done') return 'done'
+  if (state?.stage === 'failed') return 'failed'
+  if (state?.stage === 'skipped') return 'skipped'
+  return 'pending'
+}
+
+interface UseProjectsParams {
+  selectedFiles: Ref<SelectedFile[]>
+  fileStates: Ref<Record<string, FileJobState>>
+  isTranslating: Ref<boolean>
+  projectPath: Ref<string | null>
+  importPaths: (paths: string[]) => Promise<void>
+  clearFiles: () => void
+  buildJobRequest: (settings: Settings, files: SelectedFile[]) => TranslationJobRequest
+  startTranslation: () => Promise<void>
+  getSettings: () => Settings | null
+  t: TranslateFn
+}
+
+export const useProjects = ({
+  selectedFiles,
+  fileStates,
+  isTranslating,
+  projectPath,
+  importPaths,
+  clearFiles,
+  buildJobRequest,
+  startTranslation,
+  getSettings,
+  t
+}: UseProjectsParams) => {
+  const recentProjects = ref<RecentProject[]>([])
+  const projectName = ref<string | null>(null)
+  const openingProject = ref<string | null>(null)
+  const projectError = ref<string | null>(null)
+
+  const loadRecentProjects = async () => {
+    try {
+      recentProjects.value = await listRecentProjects()
+    } catch (e) {
+      console.error('Failed to load recent projects:', e)
+    }
+  }
+
+  /**
+   * Queues the project's videos with their saved tracks and outcomes, then
+   * translates whatever is not done yet.
+   */
+  const resumeProject = async (path: string) => {
+    if (isTranslating.value || openingProject.value) return
+    openingProject.value = path
+    projectError.value = null
+    try {
+      const project = await openProject(path)
+      clearFiles()
+      projectPath.value = path
+      projectName.value = project.name
+      await importPaths(project.files.map(file => file.path))
+
+      for (const file of project.files) {
+        const queued = selectedFiles.value.find(selected => selected.path === file.path)
+        if (queued && file.subtitleTrack != null && !queued.subtitleFile) {
+          queued.selectedTrack = file.subtitleTrack
+        }
+        if (file.status === 'pending') continue
+        fileStates.value[file.path] = {
+          stage: file.status,
+          progress: 100,
+          error: file.error ? localizeBackendMessage(file.error, t) : null,
+          outputPath: file.outputPath ?? null,
+          messages: [],
+          subtitleData: null,
+          subtitlePath: null
+        }
+      }
+
+      if (project.files.some(file => file.status !== 'done' && file.status !== 'skipped')) {
+        await startTranslation()
+      }
+    } catch (e) {
+      projectError.value = localizeBackendMessage(errorMessage(e), t)
+    } finally {
+      openingProject.value = null
+      await loadRecentProjects()
+    }
+  }
+
+  /** Saves the queue as a `.animesubs` project so it can be resumed later. */
+  const saveQueueAsProject = async () => {
+    const settings = getSettings()
+    const files = selectedFiles.value
+    if (!settings || files.length === 0) return
+
+    const folder = commonFolder(files.map(file => file.path))
+    const name = projectName.value || baseName(folder)
+    const selected = projectPath.value ?? await save({
+      defaultPath: `${folder}/${name}.${PROJECT_EXTENSION}`,
+      filters: [{ name: t('projects.fileFilter'), extensions: [PROJECT_EXTENSION] }]
+    })
+    if (!selected) return
+
+    projectError.value = null
+    try {
+      const request = buildJobRequest(settings, files)
+      const saved = await saveProject(selected, {
+        name,
+        folder,
+        files: files.map(file => ({
+          path: file.path,
+          subtitleTrack: file.subtitleFile ? null : file.selectedTrack ?? null,
+          status: projectStatus(fileStates.value[file.path]),
+          outputPath: fileStates.value[file.path]?.outputPath ?? null,
+          error: fileStates.value[file.path]?.error ?? null
+        })),
+        sourceLang: request.sourceLang,
+        targetLang: request.targetLang,
+        config: request.config
+      })
+      projectPath.value = selected.endsWith(`.${PROJECT_EXTENSION}`)
+        ? selected
+        : `${selected}.${PROJECT_EXTENSION}`
+      projectName.value = saved.name
+    } catch (e) {
+      projectError.value = localizeBackendMessage(errorMessage(e), t)
+    } finally {
+      await loadRecentProjects()
+    }
+  }
+
+  /** Detaches the queue from its project; later runs are not recorded in it. */
+  const closeProject = () => {
+    projectPath.value = null
+    projectName.value = null
+  }
+
+  watch(isTranslating, (running) => {
+    if (!running && projectPath.value) void loadRecentProjects()
+  })
+
+  return {
+    recentProjects,
+    projectName,
+    openingProject,
+    projectError,
+    loadRecentProjects,
+    resumeProject,
+    saveQueueAsProject,
+    closeProject
+  }
+}
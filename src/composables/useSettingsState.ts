@@ -1,5 +1,5 @@
 import { computed, ref, watch, type Ref } from 'vue'
-import { checkFfmpeg, loadApiKey } from '../api/animesubs'
+import { checkFfmpeg, errorMessage, getApiKey } from '../api/animesubs'
 import {
   defaultSettings,
   normalizeSettings,
@@ -29,7 +29,7 @@ export const useSettingsState = (
         ? normalizeSettings({ ...JSON.parse(saved), apiKey: '' })
         : { ...defaultSettings }
       setInterfaceLocale(loaded.interfaceLanguage)
-      const apiKey = await loadApiKey(loaded.provider)
+      const apiKey = await getApiKey(loaded.provider)
       cachedSettings.value = {
         ...loaded,
         apiKey: apiKey.data || ''
@@ -85,7 +85,7 @@ export const useSettingsState = (
     } catch (e) {
       ffmpegStatus.value = {
         success: false,
-        message: `Error: ${e}`,
+        message: `Error: ${errorMessage(e)}`,
         data: null
       }
     }
@@ -0,0 +1,129 @@
+import { ref, watch, type Ref } from 'vue'
+import { errorMessage, previewSubtitleTrack } from '../api/animesubs'
+import type { Settings } from '../config/settings'
+import { localizeBackendMessage } from '../i18n'
+import type {
+  SelectedFile,
+  SubtitleTrack,
+  SubtitleTrackPreview,
+  TrackRule
+} from '../types/domain'
+import type { TranslationOptions } from './useTranslationOptions'
+
+type TranslateFn = (key: string, named?: Record<string, unknown>) => string
+
+/** Dialog lines shown in a track preview. */
+const PREVIEW_LINES = 5
+
+export interface TrackPreviewState {
+  loading: boolean
+  preview: SubtitleTrackPreview | null
+  error: string | null
+}
+
+const normalize = (value: string | null) => value?.trim().toLowerCase() || null
+
+const trackRule = (track: SubtitleTrack): TrackRule => ({
+  language: track.language,
+  title: track.title,
+  codec: track.codec,
+  forced: track.forced
+})
+
+/**
+ * Track of `tracks` most like the one `rule` describes: the language and forced
+ * flag must match, then a matching title counts more than a matching codec.
+ */
+export const matchTrackRule = (tracks: SubtitleTrack[], rule: TrackRule) => {
+  let best: SubtitleTrack | null = null
+  let bestScore = 0
+  for (const track of tracks) {
+    if (normalize(track.language) !== normalize(rule.language)) continue
+    if (track.forced !== rule.forced) continue
+    const score = 1
+      + (normalize(track.title) === normalize(rule.title) ? 2 : 0)
+      + (track.codec === rule.codec ? 1 : 0)
+    if (score > bestScore) {
+      best = track
+      bestScore = score
+    }
+  }
+  return best
+}
+
+interface UseTrackPickerParams {
+  selectedFiles: Ref<SelectedFile[]>
+  translationOptions: TranslationOptions
+  getSettings: () => Settings | null
+  t: TranslateFn
+}
+
+export const useTrackPicker = ({
+  selectedFiles,
+  translationOptions,
+  getSettings,
+  t
+}: UseTrackPickerParams) => {
+  const previews = ref<Record<string, TrackPreviewState>>({})
+  const openPreview = ref<string | null>(null)
+
+  const previewKey = (path: string, trackIndex: number) => `${path}#${trackIndex}`
+
+  const togglePreview = async (file: SelectedFile, trackIndex: number) => {
+    const key = previewKey(file.path, trackIndex)
+    if (openPreview.value === key) {
+      openPreview.value = null
+      return
+    }
+    openPreview.value = key
+    if (previews.value[key]?.preview || previews.value[key]?.loading) return
+
+    previews.value[key] = { loading: true, preview: null, error: null }
+    try {
+      const preview = await previewSubtitleTrack(
+        file.path,
+        trackIndex,
+        PREVIEW_LINES,
+        getSettings()?.ffmpegPath || null
+      )
+      previews.value[key] = { loading: false, preview, error: null }
+    } catch (e) {
+      previews.value[key] = {
+        loading: false,
+        preview: null,
+        error: localizeBackendMessage(errorMessage(e), t)
+      }
+    }
+  }
+
+  const applyRememberedTrack = () => {
+    const rule = translationOptions.rememberedTrack
+    if (!translationOptions.rememberTrackChoice || !rule) return
+    for (const file of selectedFiles.value) {
+      if (file.selectedTrack != null || !file.videoInfo) continue
+      const track = matchTrackRule(file.videoInfo.subtitle_tracks, rule)
+      if (track) file.selectedTrack = track.index
+    }
+  }
+
+  /** Picks `trackIndex` for `file`, or clears the pick when it is already chosen. */
+  const selectTrack = (file: SelectedFile, trackIndex: number) => {
+    file.selectedTrack = file.selectedTrack === trackIndex ? null : trackIndex
+    const track = file.videoInfo?.subtitle_tracks.find(track => track.index === file.selectedTrack)
+    if (translationOptions.rememberTrackChoice && track) {
+      translationOptions.rememberedTrack = trackRule(track)
+      applyRememberedTrack()
+    }
+  }
+
+  watch(() => selectedFiles.value.map(file => file.videoInfo), applyRememberedTrack)
+  watch(() => translationOptions.rememberTrackChoice, applyRememberedTrack)
+
+  return {
+    previews,
+    openPreview,
+    previewKey,
+    togglePreview,
+    selectTrack
+  }
+}
@@ -1,23 +1,49 @@
 import { listen } from '@tauri-apps/api/event'
-import { computed, ref, type Ref } from 'vue'
-import { startTranslationJob } from '../api/animesubs'
+import { revealItemInDir } from '@tauri-apps/plugin-opener'
+import { ask } from '@tauri-apps/plugin-dialog'
+import { computed, ref, watch, type Ref } from 'vue'
+import {
+  cancelTranslationJob,
+  errorMessage,
+  estimateTranslationJob,
+  onBackendLog,
+  pauseTranslationJob,
+  resumeTranslationJob,
+  startTranslationJob
+} from '../api/animesubs'
 import {
   hasUsableApiConfig,
   providerRequiresApiKey,
   type Settings
 } from '../config/settings'
-import { localizeBackendMessage } from '../i18n'
+import { i18n, localizeBackendMessage } from '../i18n'
 import type {
+  CharacterName,
+  FileJobState,
+  JobEstimate,
+  LogEntry,
   OperationResult,
   SelectedFile,
   TranslationBatchProgress,
-  TranslationJobProgress
+  TranslationJobProgress,
+  TranslationJobRequest
 } from '../types/domain'
 import type { SettingsModalExpose } from './useSettingsState'
 import type { TranslationOptions } from './useTranslationOptions'
 
 type TranslateFn = (key: string, named?: Record<string, unknown>) => string
 
+/**
+ * Backend log entries kept for the job console, oldest dropped first, so long
+ * batch sessions stay bounded. Matches the backend's `tail_logs` buffer.
+ */
+const JOB_LOG_LIMIT = 1000
+/** Status messages kept per queued video. */
+const FILE_MESSAGE_LIMIT = 50
+const RUNNING_STAGES: FileJobState['stage'][] = ['preparing', 'translating', 'saving', 'embedding']
+/** Quiet time before the cost estimate follows a changed queue or model. */
+const ESTIMATE_DEBOUNCE_MS = 600
+
 const validateApiConnection = async (
   settings: Settings,
   t: TranslateFn
@@ -74,6 +100,12 @@ interface UseTranslationJobParams {
   translationOptions: TranslationOptions
   settingsRef: Ref<SettingsModalExpose | null>
   showSettings: Ref<boolean>
+  /** `.animesubs` project the queue was resumed from or saved as. */
+  projectPath: Ref<string | null>
+  /** Term → translation pairs sent with every request. */
+  glossary: Ref<CharacterName[]>
+  /** Desktop notification for events worth a look while the window is away. */
+  notify: (title: string, body: string) => void
   getSettings: () => Settings | null
   t: TranslateFn
 }
@@ -85,14 +117,26 @@ export const useTranslationJob = ({
   translationOptions,
   settingsRef,
   showSettings,
+  projectPath,
+  glossary,
+  notify,
   getSettings,
   t
 }: UseTranslationJobParams) => {
   const isTranslating = ref(false)
+  /** Whether the running job is held before its next request. */
+  const isPaused = ref(false)
+  /** Whether Stop was pressed and the job is winding down. */
+  const stopping = ref(false)
   const translationProgress = ref(0)
   const currentStatus = ref('')
   const estimatedTime = ref('')
   const currentFileIndex = ref(0)
+  const jobLog = ref<LogEntry[]>([])
+  const fileStates = ref<Record<string, FileJobState>>({})
+  const jobEstimate = ref<JobEstimate | null>(null)
+  const estimating = ref(false)
+  const estimateError = ref<string | null>(null)
   let queuedProgress: number | null = null
   let queuedStatus: string | null = null
   let progressFrame: number | null = null
@@ -125,6 +169,32 @@ export const useTranslationJob = ({
     }
   }
 
+  const fileState = (path: string): FileJobState => {
+    fileStates.value[path] ??= {
+      stage: 'pending',
+      progress: 0,
+      error: null,
+      outputPath: null,
+      messages: [],
+      subtitleData: null,
+      subtitlePath: null
+    }
+    return fileStates.value[path]
+  }
+
+  const isRunning = (path: string) => {
+    const stage = fileStates.value[path]?.stage
+    return stage !== undefined && RUNNING_STAGES.includes(stage)
+  }
+
+  const pushFileMessage = (path: string, message: string) => {
+    const messages = fileState(path).messages
+    messages.push(message)
+    if (messages.length > FILE_MESSAGE_LIMIT) {
+      messages.splice(0, messages.length - FILE_MESSAGE_LIMIT)
+    }
+  }
+
   const resetProgress = () => {
     if (progressFrame !== null) {
       window.cancelAnimationFrame(progressFrame)
@@ -135,17 +205,150 @@ export const useTranslationJob = ({
     setProgress(0)
     currentStatus.value = ''
     estimatedTime.value = ''
+    jobLog.value = []
+    fileStates.value = {}
+  }
+
+  const isReady = (file: SelectedFile) =>
+    !!file.videoInfo && (!!file.subtitleFile || file.videoInfo.subtitle_tracks.length > 0)
+
+  /** Whether a full run includes `path`; a project run resumes past finished videos. */
+  const isQueued = (path: string) => {
+    const stage = fileStates.value[path]?.stage
+    return stage !== 'skipped' && !(projectPath.value && stage === 'done')
   }
 
   const canStartTranslation = computed(() => {
     const settings = cachedSettings.value
     const hasApiConfig = hasUsableApiConfig(settings)
     const hasFiles = selectedFiles.value.length > 0
-    const filesReady = selectedFiles.value.some(f => f.videoInfo && f.videoInfo.subtitle_tracks.length > 0)
+    const filesReady = selectedFiles.value.some(f => isReady(f) && isQueued(f.path))
     return hasApiConfig && hasFiles && filesReady && ffmpegStatus.value?.success
   })
 
-  const startTranslation = async () => {
+  /** Job request for `files` with the current settings and options. */
+  const buildJobRequest = (settings: Settings, files: SelectedFile[]): TranslationJobRequest => {
+    const systemPrompt = settingsRef.value?.getSystemPrompt?.()
+      || t('prompts.fallbackSystemPrompt', { targetLanguage: settings.targetLanguage })
+    const fileTracks = Object.fromEntries(files
+      .filter(file => file.selectedTrack != null && !file.subtitleFile)
+      .map(file => [file.path, file.selectedTrack as number]))
+    const fileSubtitles = Object.fromEntries(files
+      .filter(file => !!file.subtitleFile)
+      .map(file => [file.path, file.subtitleFile as string]))
+
+    return {
+      videoPaths: files.map(file => file.path),
+      config: {
+        provider: settings.provider,
+        api_key: settings.apiKey,
+        endpoint: settings.apiEndpoint,
+        model: settings.selectedModel || '',
+        system_prompt: systemPrompt,
+        azure_deployment: settings.azureDeployment || null,
+        azure_api_version: settings.azureApiVersion || null,
+        batch_token_budget: translationOptions.batchTokenBudget || null,
+        reuse_repeats: translationOptions.reuseRepeats,
+        name_table: glossary.value
+      },
+      sourceLang: settings.sourceLanguage || 'auto',
+      targetLang: settings.targetLanguage,
+      outputFormat: settings.outputFormat,
+      outputDirectory: settings.outputDirectory || null,
+      ffmpegPath: settings.ffmpegPath || null,
+      subtitleTrack: translationOptions.subtitleTrack
+        ? parseInt(translationOptions.subtitleTrack)
+        : null,
+      fileTracks,
+      fileSubtitles,
+      keepSubtitleData: true,
+      projectPath: projectPath.value,
+      embedSubtitles: translationOptions.embedSubtitles,
+      useMkvmerge: translationOptions.useMkvmerge,
+      skipTargetLanguage: translationOptions.skipTargetLanguage,
+      skipTranslated: translationOptions.skipTranslated,
+      autoBackup: settings.autoBackup,
+      keepOriginalTrack: settings.keepOriginalTrack
+    }
+  }
+
+  const wantedFiles = () => selectedFiles.value
+    .filter(file => isReady(file) && isQueued(file.path))
+
+  let estimateTimer: ReturnType<typeof setTimeout> | null = null
+  let estimateRun = 0
+
+  /** Re-estimates lines, tokens and cost of the files a run would translate. */
+  const refreshEstimate = async () => {
+    const settings = getSettings()
+    const files = wantedFiles()
+    const run = ++estimateRun
+    if (!settings || files.length === 0) {
+      jobEstimate.value = null
+      estimateError.value = null
+      return null
+    }
+    estimating.value = true
+    try {
+      const estimate = await estimateTranslationJob(buildJobRequest(settings, files))
+      if (run === estimateRun) {
+        jobEstimate.value = estimate
+        estimateError.value = null
+      }
+      return estimate
+    } catch (e) {
+      if (run === estimateRun) {
+        jobEstimate.value = null
+        estimateError.value = localizeBackendMessage(errorMessage(e), t)
+      }
+      return null
+    } finally {
+      if (run === estimateRun) estimating.value = false
+    }
+  }
+
+  const scheduleEstimate = () => {
+    if (estimateTimer !== null) clearTimeout(estimateTimer)
+    estimateTimer = setTimeout(() => {
+      estimateTimer = null
+      if (!isTranslating.value) void refreshEstimate()
+    }, ESTIMATE_DEBOUNCE_MS)
+  }
+
+  /** Asks before a run whose estimate is above the configured threshold. */
+  const confirmCost = async (files: SelectedFile[], settings: Settings) => {
+    const threshold = translationOptions.costConfirmThreshold
+    if (threshold == null || threshold <= 0) return true
+    const estimate = await estimateTranslationJob(buildJobRequest(settings, files)).catch(() => null)
+    const cost = estimate?.estimatedCost
+    if (cost == null || cost <= threshold) return true
+    notify(t('notifications.costTitle'), t('notifications.costConfirm', {
+      cost: `$${cost.toFixed(2)}`,
+      threshold: `$${threshold.toFixed(2)}`
+    }))
+    return ask(t('estimate.confirmMessage', {
+      cost: `$${cost.toFixed(2)}`,
+      threshold: `$${threshold.toFixed(2)}`,
+      tokens: (estimate!.inputTokens + estimate!.outputTokens).toLocaleString(i18n.global.locale.value)
+    }), { title: t('estimate.confirmTitle'), kind: 'warning' })
+  }
+
+  watch(
+    () => [
+      wantedFiles().map(file => `${file.path}|${file.selectedTrack ?? ''}|${file.subtitleFile ?? ''}`),
+      cachedSettings.value?.provider,
+      cachedSettings.value?.selectedModel,
+      cachedSettings.value?.translationStyle,
+      translationOptions.subtitleTrack,
+      translationOptions.batchTokenBudget
+    ],
+    scheduleEstimate,
+    { deep: true }
+  )
+
+  /** Translates `onlyPaths`, or every ready file the user did not skip. */
+  const runTranslation = async (onlyPaths?: string[]) => {
+    if (isTranslating.value) return
     if (!canStartTranslation.value) {
       if (!ffmpegStatus.value?.success) {
         showSettings.value = true
@@ -167,25 +370,52 @@ export const useTranslationJob = ({
       return
     }
 
-    const filesToProcess = selectedFiles.value.filter(
-      f => f.videoInfo && f.videoInfo.subtitle_tracks.length > 0
-    )
+    const isWanted = (file: SelectedFile) => onlyPaths
+      ? onlyPaths.includes(file.path)
+      : isQueued(file.path)
+    const filesToProcess = selectedFiles.value.filter(f => isReady(f) && isWanted(f))
     const videoPaths = filesToProcess.map(file => file.path)
-    const systemPrompt = settingsRef.value?.getSystemPrompt?.()
-      || t('prompts.fallbackSystemPrompt', { targetLanguage: settings.targetLanguage })
-
+    if (videoPaths.length === 0) return
+    if (!await confirmCost(filesToProcess, settings)) {
+      currentStatus.value = t('estimate.cancelled')
+      return
+    }
+    for (const path of videoPaths) {
+      fileStates.value[path] = {
+        stage: 'pending',
+        progress: 0,
+        error: null,
+        outputPath: null,
+        messages: [],
+        subtitleData: null,
+        subtitlePath: null
+      }
+    }
     isTranslating.value = true
+    isPaused.value = false
+    stopping.value = false
     setProgress(0)
     currentFileIndex.value = 0
+    jobLog.value = []
     const latestJobProgress = ref<TranslationJobProgress | null>(null)
 
     const unlistenProgress = await listen<TranslationJobProgress>('translation-job-progress', (event) => {
       latestJobProgress.value = event.payload
       currentFileIndex.value = Math.max(0, event.payload.currentFile - 1)
-      queueProgressUpdate(
-        event.payload.progress,
-        localizeBackendMessage(event.payload.status, t)
-      )
+      const status = localizeBackendMessage(event.payload.status, t)
+      queueProgressUpdate(event.payload.progress, status)
+
+      const { videoPath, stage, fileProgress } = event.payload
+      if (videoPath) {
+        const state = fileState(videoPath)
+        if (stage === 'failed' && state.stage !== 'failed') {
+          const name = selectedFiles.value.find(file => file.path === videoPath)?.name ?? videoPath
+          notify(t('notifications.fileFailed', { file: name }), status)
+        }
+        if (stage) state.stage = stage
+        if (fileProgress != null) state.progress = fileProgress
+        pushFileMessage(videoPath, status)
+      }
     })
     const unlistenBatchProgress = await listen<TranslationBatchProgress>('translation-progress', (event) => {
       const jobProgress = latestJobProgress.value
@@ -196,6 +426,9 @@ export const useTranslationJob = ({
         : 0
       const fileBase = ((jobProgress.currentFile - 1) / jobProgress.totalFiles) * 100
       const fileSpan = 100 / jobProgress.totalFiles
+      if (jobProgress.videoPath && jobProgress.stage === 'translating') {
+        fileState(jobProgress.videoPath).progress = (0.2 + (0.6 * chunkRatio)) * 100
+      }
       queueProgressUpdate(
         fileBase + ((0.2 + (0.6 * chunkRatio)) * fileSpan),
         event.payload.status
@@ -207,33 +440,55 @@ export const useTranslationJob = ({
       )
     })
 
+    const unlistenPaused = await listen<boolean>('translation-job-paused', (event) => {
+      isPaused.value = event.payload
+    })
+
+    const unlistenLog = await onBackendLog((entry) => {
+      jobLog.value.push(entry)
+      if (jobLog.value.length > JOB_LOG_LIMIT) {
+        jobLog.value.splice(0, jobLog.value.length - JOB_LOG_LIMIT)
+      }
+    })
+
     try {
-      const result = await startTranslationJob({
-        videoPaths,
-        config: {
-          provider: settings.provider,
-          api_key: settings.apiKey,
-          endpoint: settings.apiEndpoint,
-          model: settings.selectedModel || '',
-          system_prompt: systemPrompt
-        },
-        sourceLang: settings.sourceLanguage || 'auto',
-        targetLang: settings.targetLanguage,
-        outputFormat: settings.outputFormat,
-        outputDirectory: settings.outputDirectory || null,
-        ffmpegPath: settings.ffmpegPath || null,
-        subtitleTrack: translationOptions.subtitleTrack
-          ? parseInt(translationOptions.subtitleTrack)
-          : null,
-        embedSubtitles: translationOptions.embedSubtitles,
-        useMkvmerge: translationOptions.useMkvmerge,
-        autoBackup: settings.autoBackup,
-        keepOriginalTrack: settings.keepOriginalTrack
-      })
+      const request = buildJobRequest(settings, filesToProcess)
+      // A retry asks for a fresh translation even when an earlier one exists.
+      if (onlyPaths) request.skipTranslated = false
+      const result = await startTranslationJob(request)
 
       flushProgressUpdate()
       setProgress(100)
-      if (result.failures.length === 0) {
+      for (const file of result.report?.files ?? []) {
+        const state = fileState(file.videoPath)
+        if (file.status === 'pending') {
+          state.stage = 'cancelled'
+          state.outputPath = null
+          if (file.partialPath) {
+            state.messages.push(t('queue.partialSaved', { path: file.partialPath }))
+          }
+          continue
+        }
+        state.stage = file.status
+        state.progress = 100
+        state.error = file.error ? localizeBackendMessage(file.error, t) : null
+        if (file.skipReason) state.messages.push(localizeBackendMessage(file.skipReason, t))
+        if (file.linesDeduplicated) {
+          state.messages.push(t('queue.deduplicated', { count: file.linesDeduplicated }))
+        }
+        state.outputPath = file.outputPath
+      }
+      for (const output of result.outputs) {
+        const state = fileState(output.videoPath)
+        state.subtitleData = output.subtitleData ?? null
+        state.subtitlePath = output.subtitlePath
+      }
+      if (result.cancelled) {
+        currentStatus.value = t('status.translationStopped', {
+          completed: result.completedFiles,
+          total: result.totalFiles
+        })
+      } else if (result.failures.length === 0) {
         currentStatus.value = t('status.translationComplete')
       } else if (result.completedFiles === 0) {
         currentStatus.value = t('status.translationFailed', {
@@ -246,25 +501,109 @@ export const useTranslationJob = ({
           failure: localizeBackendMessage(result.failures[0], t)
         })
       }
+      notify(t('notifications.jobFinished'), currentStatus.value)
+
+      const threshold = translationOptions.costConfirmThreshold
+      const spent = result.report?.estimatedCost
+      if (threshold != null && threshold > 0 && spent != null && spent > threshold) {
+        notify(t('notifications.costTitle'), t('notifications.costSpent', {
+          cost: `$${spent.toFixed(2)}`,
+          threshold: `$${threshold.toFixed(2)}`
+        }))
+      }
     } catch (e) {
       console.error('Translation error:', e)
-      currentStatus.value = t('status.error', { error: e })
+      const error = localizeBackendMessage(errorMessage(e), t)
+      currentStatus.value = t('status.error', { error })
+      notify(t('notifications.jobFinished'), currentStatus.value)
+      for (const path of videoPaths) {
+        const state = fileState(path)
+        if (!['done', 'failed', 'cancelled'].includes(state.stage)) {
+          state.stage = 'failed'
+          state.error = error
+        }
+      }
     } finally {
       unlistenProgress()
       unlistenBatchProgress()
+      unlistenPaused()
+      unlistenLog()
       isTranslating.value = false
+      isPaused.value = false
+      stopping.value = false
+    }
+  }
+
+  /** Holds the running job before its next request, or lets it continue. */
+  const togglePause = async () => {
+    if (!isTranslating.value || stopping.value) return
+    try {
+      const result = isPaused.value
+        ? await resumeTranslationJob()
+        : await pauseTranslationJob()
+      if (!result.success) console.error('Failed to toggle pause:', result.message)
+    } catch (e) {
+      console.error('Failed to toggle pause:', e)
+    }
+  }
+
+  /** Stops the running job; files that already finished keep their output. */
+  const stopTranslation = async () => {
+    if (!isTranslating.value || stopping.value) return
+    stopping.value = true
+    currentStatus.value = t('status.stoppingTranslation')
+    try {
+      await cancelTranslationJob()
+    } catch (e) {
+      stopping.value = false
+      console.error('Failed to stop translation:', e)
+    }
+  }
+
+  const startTranslation = () => runTranslation()
+
+  const retryFile = (path: string) => runTranslation([path])
+
+  /** Leaves a pending video out of the next run, or puts a skipped one back. */
+  const toggleSkipFile = (path: string) => {
+    if (isRunning(path)) return
+    const state = fileState(path)
+    state.stage = state.stage === 'skipped' ? 'pending' : 'skipped'
+  }
+
+  const openOutputFolder = async (path: string) => {
+    const outputPath = fileStates.value[path]?.outputPath
+    if (!outputPath) return
+    try {
+      await revealItemInDir(outputPath)
+    } catch (e) {
+      console.error('Failed to open output folder:', e)
     }
   }
 
   return {
     isTranslating,
+    isPaused,
+    stopping,
     translationProgress,
     currentStatus,
     estimatedTime,
     currentFileIndex,
+    jobLog,
+    fileStates,
+    jobEstimate,
+    estimating,
+    estimateError,
+    refreshEstimate,
     canStartTranslation,
+    buildJobRequest,
     setProgress,
     resetProgress,
-    startTranslation
+    startTranslation,
+    togglePause,
+    stopTranslation,
+    retryFile,
+    toggleSkipFile,
+    openOutputFolder
   }
 }
@@ -1,11 +1,25 @@
 import { reactive, watch } from 'vue'
 import { TRANSLATION_OPTIONS_STORAGE_KEY } from '../config/settings'
+import type { TrackRule } from '../types/domain'
 
 export interface TranslationOptions {
   subtitleTrack: string
   embedSubtitles: boolean
   useMkvmerge: boolean
+  /** Leave videos whose subtitles are already in the target language alone. */
+  skipTargetLanguage: boolean
+  /** Leave videos that already have a translated track or subtitle file alone. */
+  skipTranslated: boolean
+  /** Give repeated lines the translation of their first occurrence. */
+  reuseRepeats: boolean
   customPrompt: string
+  /** Pick tracks like the last one chosen when new files are added. */
+  rememberTrackChoice: boolean
+  rememberedTrack: TrackRule | null
+  /** Tokens per request for lines and their translations; the model's context window minus the system prompt when unset. */
+  batchTokenBudget: number | null
+  /** USD above which a run asks for confirmation; never asks when unset. */
+  costConfirmThreshold: number | null
 }
 
 export const useTranslationOptions = () => {
@@ -13,7 +27,14 @@ export const useTranslationOptions = () => {
     subtitleTrack: '',
     embedSubtitles: false,
     useMkvmerge: true,
-    customPrompt: ''
+    skipTargetLanguage: true,
+    skipTranslated: true,
+    reuseRepeats: true,
+    customPrompt: '',
+    rememberTrackChoice: false,
+    rememberedTrack: null,
+    batchTokenBudget: null,
+    costConfirmThreshold: null
   })
 
   const loadTranslationOptions = () => {
@@ -3,24 +3,38 @@ import { computed, ref, triggerRef } from 'vue'
 import {
   backupSubtitle as backupSubtitleCommand,
   deleteBackup as deleteBackupCommand,
+  detectSubtitleLanguages,
+  errorMessage,
   extractSubtitle as extractSubtitleCommand,
   getVideoInfo,
   listBackups,
   restoreSubtitle as restoreSubtitleCommand,
   scanFolderForVideos
 } from '../api/animesubs'
-import type { BackupInfo, SelectedFile } from '../types/domain'
+import type { BackupInfo, SelectedFile, SidecarSubtitle } from '../types/domain'
 import type { Settings } from '../config/settings'
 import { localizeBackendMessage } from '../i18n'
 
 type TranslateFn = (key: string, named?: Record<string, unknown>) => string
 
+const VIDEO_EXTENSIONS = ['mkv', 'mp4', 'webm', 'avi', 'mov', 'wmv', 'flv', 'm4v']
+const SUBTITLE_EXTENSIONS = ['ass', 'ssa', 'srt', 'vtt']
+
+const baseName = (path: string) => path.split(/[\\/]/).pop() || path
+const extensionOf = (path: string) => {
+  const name = baseName(path)
+  return name.includes('.') ? name.split('.').pop()!.toLowerCase() : ''
+}
+const stemOf = (path: string) => baseName(path).replace(/\.[^.]+$/, '')
+
 export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn) => {
   const selectedFiles = ref<SelectedFile[]>([])
   const isDragging = ref(false)
   const loadingFiles = ref(false)
   const extractingSubtitle = ref<string | null>(null)
   const backingUp = ref<string | null>(null)
+  /** Dropped subtitle files no queued video matched by name. */
+  const unmatchedSubtitles = ref<string[]>([])
 
   const subtitleTrackOptions = computed(() => {
     const options = [{ label: t('app.autoDetectFirstAvailable'), value: '' }]
@@ -47,6 +61,23 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
     return options
   })
 
+  /** Untagged tracks get the language read from their dialog, in the background. */
+  const labelUntaggedTracks = async (path: string, ffmpegPath: string | null) => {
+    const file = selectedFiles.value.find(f => f.path === path)
+    if (!file?.videoInfo?.subtitle_tracks.some(track => !track.language || track.language === 'und')) {
+      return
+    }
+    try {
+      const videoInfo = await detectSubtitleLanguages(path, ffmpegPath)
+      const index = selectedFiles.value.findIndex(f => f.path === path)
+      if (index === -1) return
+      selectedFiles.value[index] = { ...selectedFiles.value[index], videoInfo }
+      triggerRef(selectedFiles)
+    } catch (e) {
+      console.error('Language detection failed:', e)
+    }
+  }
+
   const addFiles = async (paths: string[]) => {
     const settings = getSettings()
 
@@ -79,10 +110,11 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
           loading: false
         }
         triggerRef(selectedFiles)
+        void labelUntaggedTracks(path, settings?.ffmpegPath || null)
       } catch (e) {
         selectedFiles.value[fileIndex] = {
           ...selectedFiles.value[fileIndex],
-          error: localizeBackendMessage(`${e}`, t),
+          error: localizeBackendMessage(errorMessage(e), t),
           loading: false
         }
         triggerRef(selectedFiles)
@@ -95,7 +127,7 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
       multiple: true,
       filters: [{
         name: t('dialogs.videoFiles'),
-        extensions: ['mkv', 'mp4', 'webm', 'avi', 'mov', 'wmv', 'flv', 'm4v']
+        extensions: VIDEO_EXTENSIONS
       }]
     })
 
@@ -112,7 +144,7 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
       loadingFiles.value = true
       try {
         const videos = await scanFolderForVideos(selected)
-        await addFiles(videos)
+        await addFiles(videos.map(video => video.path))
       } catch (e) {
         console.error('Failed to scan folder:', e)
       } finally {
@@ -121,6 +153,71 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
     }
   }
 
+  /** Queued video a subtitle belongs to: "Show 01.en.ass" goes with "Show 01.mkv". */
+  const videoForSubtitle = (subtitlePath: string) => {
+    const name = baseName(subtitlePath).toLowerCase()
+    return selectedFiles.value
+      .filter(file => name.startsWith(`${stemOf(file.path).toLowerCase()}.`))
+      .sort((a, b) => stemOf(b.path).length - stemOf(a.path).length)[0]
+  }
+
+  const attachSubtitle = (file: SelectedFile, subtitlePath: string) => {
+    file.subtitleFile = subtitlePath
+    file.selectedTrack = null
+  }
+
+  const detachSubtitle = (file: SelectedFile) => {
+    file.subtitleFile = null
+  }
+
+  /**
+   * Queues dropped paths by kind: videos as they are, folders through a scan,
+   * and subtitle files on the video they are named after. Sidecars found in a
+   * scanned folder are only used for videos without embedded subtitles.
+   */
+  const importPaths = async (paths: string[]) => {
+    const videos: string[] = []
+    const subtitles: string[] = []
+    const sidecars = new Map<string, SidecarSubtitle[]>()
+
+    for (const path of paths) {
+      const extension = extensionOf(path)
+      if (VIDEO_EXTENSIONS.includes(extension)) {
+        videos.push(path)
+      } else if (SUBTITLE_EXTENSIONS.includes(extension)) {
+        subtitles.push(path)
+      } else {
+        try {
+          for (const video of await scanFolderForVideos(path)) {
+            videos.push(video.path)
+            sidecars.set(video.path, video.sidecars)
+          }
+        } catch {
+          videos.push(path)
+        }
+      }
+    }
+
+    await addFiles(videos)
+
+    for (const file of selectedFiles.value) {
+      const found = sidecars.get(file.path)
+      if (!found?.length || file.subtitleFile || file.videoInfo?.subtitle_tracks.length !== 0) continue
+      attachSubtitle(file, (found.find(sidecar => !sidecar.forced) ?? found[0]).path)
+    }
+
+    const unmatched: string[] = []
+    for (const subtitle of subtitles) {
+      const file = videoForSubtitle(subtitle)
+      if (file) {
+        attachSubtitle(file, subtitle)
+      } else {
+        unmatched.push(subtitle)
+      }
+    }
+    unmatchedSubtitles.value = unmatched
+  }
+
   const handleDrop = async (e: DragEvent) => {
     e.preventDefault()
     isDragging.value = false
@@ -146,7 +243,7 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
     if (paths.length > 0) {
       loadingFiles.value = true
       try {
-        await addFiles(paths)
+        await importPaths(paths)
       } finally {
         loadingFiles.value = false
       }
@@ -246,7 +343,10 @@ export const useVideoFiles = (getSettings: () => Settings | null, t: TranslateFn
     loadingFiles,
     extractingSubtitle,
     backingUp,
+    unmatchedSubtitles,
     addFiles,
+    importPaths,
+    detachSubtitle,
     selectFiles,
     selectFolder,
     handleDrop,
@@ -4,11 +4,24 @@ import {
   type InterfaceLocale
 } from '../i18n'
 
+export type AccentColor = 'wired' | 'amber' | 'cyan' | 'violet' | 'rose'
+export type UiDensity = 'compact' | 'comfortable' | 'spacious'
+export type LogFont = 'system' | 'jetbrains' | 'fira' | 'dejavu' | 'ubuntu'
+
+export const accentColors: AccentColor[] = ['wired', 'amber', 'cyan', 'violet', 'rose']
+export const uiDensities: UiDensity[] = ['compact', 'comfortable', 'spacious']
+export const logFonts: LogFont[] = ['system', 'jetbrains', 'fira', 'dejavu', 'ubuntu']
+
+export const UI_SCALE_MIN = 0.75
+export const UI_SCALE_MAX = 2
+
 export interface Settings {
   provider: string
   apiEndpoint: string
   apiKey: string
   selectedModel: string | null
+  azureDeployment: string
+  azureApiVersion: string
   interfaceLanguage: InterfaceLocale
   hasSelectedInterfaceLanguage: boolean
   sourceLanguage: string
@@ -19,6 +32,16 @@ export interface Settings {
   ffmpegPath: string
   autoBackup: boolean
   keepOriginalTrack: boolean
+  accentColor: AccentColor
+  uiDensity: UiDensity
+  /** Font of the job log and other monospace readouts. */
+  logFont: LogFont
+  /** Webview zoom; above 1 for HiDPI screens where the default is too small. */
+  uiScale: number
+  /** Notify on job completion, failed files and cost warnings while unfocused. */
+  desktopNotifications: boolean
+  /** Show a tray icon with the job's progress while it runs. */
+  trayProgress: boolean
 }
 
 export const SETTINGS_STORAGE_KEY = 'animesubs-settings'
@@ -29,6 +52,8 @@ export const defaultSettings: Settings = {
   apiEndpoint: 'https://api.openai.com/v1',
   apiKey: '',
   selectedModel: null,
+  azureDeployment: '',
+  azureApiVersion: '',
   interfaceLanguage: defaultInterfaceLanguage,
   hasSelectedInterfaceLanguage: false,
   sourceLanguage: '',
@@ -38,7 +63,13 @@ export const defaultSettings: Settings = {
   outputFormat: '',
   ffmpegPath: '',
   autoBackup: true,
-  keepOriginalTrack: true
+  keepOriginalTrack: true,
+  accentColor: 'wired',
+  uiDensity: 'comfortable',
+  logFont: 'system',
+  uiScale: 1,
+  desktopNotifications: true,
+  trayProgress: false
 }
 
 export const settingsForStorage = (settings: Settings): Settings => ({
@@ -51,11 +82,21 @@ export const normalizeSettings = (settings: Partial<Settings>): Settings => {
     ? settings.interfaceLanguage
     : defaultInterfaceLanguage
 
+  const oneOf = <T extends string>(value: unknown, allowed: T[], fallback: T): T =>
+    allowed.includes(value as T) ? value as T : fallback
+  const uiScale = Number(settings.uiScale)
+
   return {
     ...defaultSettings,
     ...settings,
     interfaceLanguage,
-    hasSelectedInterfaceLanguage: Boolean(settings.hasSelectedInterfaceLanguage)
+    hasSelectedInterfaceLanguage: Boolean(settings.hasSelectedInterfaceLanguage),
+    accentColor: oneOf(settings.accentColor, accentColors, defaultSettings.accentColor),
+    uiDensity: oneOf(settings.uiDensity, uiDensities, defaultSettings.uiDensity),
+    logFont: oneOf(settings.logFont, logFonts, defaultSettings.logFont),
+    uiScale: Number.isFinite(uiScale)
+      ? Math.min(UI_SCALE_MAX, Math.max(UI_SCALE_MIN, uiScale))
+      : defaultSettings.uiScale
   }
 }
 
@@ -31,7 +31,7 @@ const messages = {
       settings: 'Settings',
       language: 'Language',
       terminalLine: 'connect_media_packet',
-      heroTitle: 'Drop video files into the Wired.',
+      heroTitle: 'Drop videos, subtitles or season folders into the Wired.',
       heroDescription: 'Extract, translate, backup, and embed subtitle tracks without leaving the node.',
       selectFiles: 'SELECT FILES',
       scanFolder: 'SCAN FOLDER',
@@ -60,6 +60,9 @@ const messages = {
       autoDetectFirstAvailable: 'Auto-detect first available',
       embedTranslatedSubtitles: 'Embed translated subtitles',
       routeThroughMkvmerge: 'Route through mkvmerge',
+      skipTargetLanguage: 'Skip files already in the target language',
+      skipTranslated: 'Skip files translated before',
+      reuseRepeats: 'Reuse translations of repeated lines',
       advancedSignalControls: 'Advanced signal controls',
       customPrompt: 'Custom Prompt',
       customPromptPlaceholder: 'Add temporary protocol instructions...',
@@ -67,7 +70,35 @@ const messages = {
       initiateTranslation: 'INITIATE TRANSLATION',
       disabledHint: 'Attach media with subtitle tracks and verify provider/FFmpeg settings.',
       sync: 'sync {progress}%',
-      awaitingPacketResponse: 'awaiting packet response...'
+      awaitingPacketResponse: 'awaiting packet response...',
+      jobLog: 'job log',
+      logLevels: {
+        error: 'error',
+        warn: 'warn',
+        info: 'info',
+        debug: 'debug',
+        trace: 'trace'
+      },
+      pauseTranslation: 'Pause',
+      resumeTranslation: 'Resume',
+      stopTranslation: 'Stop',
+      paused: 'paused'
+    },
+    logPane: {
+      count: '{shown} / {total}',
+      search: 'Search log...',
+      levels: {
+        all: 'all',
+        warn: 'warnings',
+        error: 'errors'
+      },
+      copy: 'Copy visible lines',
+      copied: 'Copied {count} lines',
+      copyFailed: 'Could not copy: {error}',
+      export: 'Export visible lines',
+      exported: 'Log saved to {path}',
+      fileFilter: 'Log file',
+      empty: 'No entries match the filter.'
     },
     setup: {
       title: 'Choose interface language',
@@ -79,9 +110,38 @@ const messages = {
       title: 'wired settings',
       interfaceTab: 'Interface',
       apiTab: 'API Configuration',
+      mediaServerTab: 'Media Server',
+      remoteTab: 'Remote Control',
+      automationTab: 'Automation',
       translationTab: 'Translation',
       outputTab: 'Output',
       interfaceLanguage: 'Interface Language',
+      accentColor: 'Accent color',
+      uiDensity: 'Spacing',
+      logFont: 'Log font',
+      uiScale: 'UI scale ({percent}%)',
+      notifications: 'Notifications',
+      desktopNotifications: 'Desktop notifications when a job finishes, a file fails or a cost threshold is crossed',
+      trayProgress: 'Tray icon with job progress',
+      accents: {
+        wired: 'Wired green',
+        amber: 'Amber',
+        cyan: 'Cyan',
+        violet: 'Violet',
+        rose: 'Rose'
+      },
+      densities: {
+        compact: 'Compact',
+        comfortable: 'Comfortable',
+        spacious: 'Spacious'
+      },
+      logFonts: {
+        system: 'System monospace',
+        jetbrains: 'JetBrains Mono',
+        fira: 'Fira Code',
+        dejavu: 'DejaVu Sans Mono',
+        ubuntu: 'Ubuntu Mono'
+      },
       provider: 'Provider',
       apiEndpoint: 'API Endpoint',
       apiKey: 'API Key',
@@ -119,7 +179,11 @@ const messages = {
       settingsReset: 'Settings reset to defaults',
       bearerToken: 'Bearer token from MiniMax Token Plan',
       optional: '(optional)',
-      apiKeyPlaceholder: 'API key'
+      apiKeyPlaceholder: 'API key',
+      azureDeployment: 'Deployment',
+      azureDeploymentPlaceholder: 'Defaults to the model name',
+      azureApiVersion: 'API version',
+      azureApiKey: 'Azure resource key'
     },
     styles: {
       natural: 'Natural & Fluent',
@@ -158,11 +222,205 @@ const messages = {
       turkish: 'Turkish'
     },
     track: {
-      title: 'Track {index}'
+      title: 'Track {index}',
+      detected: 'reads as {language}',
+      detectedHint: 'Language detected from the dialog text'
+    },
+    trackPicker: {
+      select: 'Translate this track',
+      preview: 'Preview dialog lines',
+      totalLines: '{count} lines in total',
+      remember: 'Remember choice for similar files'
+    },
+    queue: {
+      pending: 'pending',
+      skipped: 'skipped',
+      preparing: 'preparing',
+      translating: 'translating {progress}%',
+      saving: 'saving',
+      embedding: 'embedding',
+      done: 'done',
+      failed: 'failed',
+      cancelled: 'stopped',
+      skip: 'Skip this file',
+      unskip: 'Include this file again',
+      retry: 'Retry',
+      openOutputFolder: 'Open output folder',
+      viewLog: 'View log',
+      review: 'Review translation',
+      deduplicated: '{count} repeated lines reused an earlier translation',
+      partialSaved: 'Finished batches were saved to {path}'
+    },
+    connectionTest: {
+      label: '{provider} / {model}',
+      button: 'Test connection',
+      ok: '{model} answered in {latency} ms',
+      kind: {
+        badKey: 'API key rejected',
+        unreachable: 'Endpoint unreachable',
+        modelMissing: 'Model not available',
+        rateLimited: 'Rate limited',
+        badResponse: 'Unexpected response',
+        other: 'Connection failed'
+      }
+    },
+    glossary: {
+      title: 'Glossary',
+      open: 'Glossary ({count} terms)',
+      search: 'Filter terms...',
+      add: 'Add term',
+      suggest: 'Suggest from subtitles',
+      import: 'Import',
+      export: 'Export',
+      term: 'Term',
+      translation: 'Translation',
+      occurrences: 'Seen',
+      pinned: 'Pin',
+      pinnedHint: 'Pinned terms keep their translation when suggestions are refreshed',
+      remove: 'Remove term',
+      termPlaceholder: 'Source term',
+      translationPlaceholder: 'Keep as written',
+      empty: 'No terms yet. Add one or let the subtitles suggest some.',
+      noFolder: 'Queue videos to save the glossary next to them',
+      cancel: 'Cancel',
+      save: 'Save {count} terms',
+      suggested: '{count} new terms suggested',
+      imported: 'Imported {count} terms',
+      exported: 'Glossary exported to {path}',
+      fileFilter: 'Glossary'
+    },
+    projects: {
+      eyebrow: 'recent projects',
+      title: 'Pick up where you left off',
+      progress: '{name} — {done}/{total} episodes translated',
+      resume: 'Resume',
+      open: 'Open',
+      active: 'Project: {name}',
+      save: 'Save project',
+      saveAs: 'Save as project',
+      fileFilter: 'AnimeSubs project',
+      errorTitle: 'Project error'
+    },
+    estimate: {
+      title: 'Cost estimate',
+      lines: 'Lines',
+      batches: 'Requests',
+      tokens: 'Tokens',
+      tokenSplit: '{input} in / {output} out',
+      cost: 'Price',
+      noPrice: 'no price set for {model}',
+      unreadable: '{count} file(s) could not be read and are not counted.',
+      batchTokenBudget: 'Batch size (input tokens)',
+      batchTokenBudgetPlaceholder: 'Fit to the model context',
+      confirmThreshold: 'Confirm jobs above',
+      confirmThresholdPlaceholder: 'Never ask',
+      confirmTitle: 'Expensive translation',
+      confirmMessage: 'This job is estimated at {cost} ({tokens} tokens), above your {threshold} limit. Start it anyway?',
+      cancelled: 'Translation not started.'
+    },
+    drop: {
+      externalSubtitle: 'file',
+      detachSubtitle: 'Use an embedded track instead',
+      unmatchedTitle: 'Subtitles without a video',
+      unmatched: 'No queued video is named like {files}. Drop the video too, or rename the subtitle after it.'
+    },
+    review: {
+      title: 'Review · {file}',
+      search: 'Search source or translation',
+      flaggedOnly: 'Flagged only',
+      nextFlagged: 'Next flagged ({count})',
+      time: 'Time',
+      source: 'Source',
+      translation: 'Translation',
+      noRows: 'No lines match.',
+      editedCount: '{count} lines edited',
+      close: 'Close',
+      saveCorrected: 'Save corrected',
+      saved: 'Saved corrections to {path}',
+      preview: 'Preview this cue on the video',
+      previewAt: 'Line {id} at {time}',
+      previewAlt: 'Video frame with line {id}',
+      previewRefresh: 'Refresh',
+      previewClose: 'Hide preview',
+      flags: {
+        untranslated: 'untranslated',
+        empty: 'empty',
+        honorifics: 'honorifics',
+        readingSpeed: 'reading speed',
+        lineLength: 'line length',
+        shortDuration: 'short duration',
+        overlap: 'overlap',
+        invalidTiming: 'invalid timing'
+      }
     },
     dialogs: {
       videoFiles: 'Video Files'
     },
+    mediaServer: {
+      hint: 'After a file is translated, ask the server to rescan it so the new subtitle shows up right away.',
+      kind: 'Server',
+      kinds: {
+        none: 'None',
+        jellyfin: 'Jellyfin',
+        plex: 'Plex'
+      },
+      url: 'URL',
+      apiKey: 'API key',
+      plexToken: 'Plex token',
+      test: 'Test',
+      save: 'Save'
+    },
+    automation: {
+      hint: 'Translates episodes and movies as Sonarr or Radarr import them, using a saved preset. Jobs run one at a time after any job already running.',
+      enabled: 'Translate imported files automatically',
+      preset: 'Preset',
+      presetPlaceholder: 'Choose a saved preset',
+      embed: 'Embed the subtitle into the video',
+      pathMappings: 'Path mappings',
+      pathMappingsHint: 'When Sonarr or Radarr sees the library under another path (e.g. in Docker), map its folder to the local one.',
+      addMapping: 'Add mapping',
+      removeMapping: 'Remove mapping',
+      save: 'Save',
+      webhook: 'In Sonarr/Radarr, add a Webhook connection (On Import) to {url}, using the remote control token.',
+      webhookNeedsServer: 'Start the remote control server to receive Sonarr/Radarr webhooks.',
+      queue: 'Queue: {running} translating, {queued} waiting'
+    },
+    folderWatch: {
+      hint: 'New videos copied into these folders are queued with the preset chosen for that folder once the copy has finished. The output format and embedding come from the settings above.',
+      add: 'Add folder',
+      remove: 'Remove folder',
+      recursive: 'Subfolders',
+      running: 'Watching',
+      stopped: 'Not watching',
+      save: 'Save',
+      start: 'Start watching',
+      stop: 'Stop watching'
+    },
+    remoteServer: {
+      enabled: 'Run the remote control server',
+      bind: 'Listen on',
+      bindHint: 'Use 0.0.0.0 instead of 127.0.0.1 to accept other machines. Anyone with the token can run jobs and read paths on this machine.',
+      token: 'Token',
+      copyToken: 'Copy token',
+      tokenCopied: 'Token copied',
+      regenerate: 'New token',
+      regenerateConfirm: 'Create a new token? Clients using the current one will be refused.',
+      listening: 'Listening on {address}',
+      stopped: 'Stopped',
+      apply: 'Apply',
+      rpcEndpoint: 'JSON-RPC 2.0 calls, with the command names the app uses',
+      eventsEndpoint: 'WebSocket with job progress and log events',
+      healthEndpoint: 'version check, no token needed'
+    },
+    notifications: {
+      jobFinished: 'AnimeSubs: translation finished',
+      fileFailed: 'AnimeSubs: {file} failed',
+      costTitle: 'AnimeSubs: cost warning',
+      costConfirm: 'Estimated {cost} is above the {threshold} threshold. Confirm in the app to start.',
+      costSpent: 'The job cost about {cost}, above the {threshold} threshold.',
+      trayProgress: 'AnimeSubs: translating {progress}%',
+      trayPaused: 'AnimeSubs: paused at {progress}%'
+    },
     status: {
       invalidApiKey: 'Invalid API key. Please check your credentials in Settings.',
       accessDenied: 'Access denied. Check API key permissions.',
@@ -178,6 +436,30 @@ const messages = {
       translationComplete: 'Translation complete!',
       translationFailed: 'Translation failed: {failure}',
       translationFinishedWithErrors: 'Translation finished with errors ({completed}/{total}): {failure}',
+      translationStopped: 'Translation stopped ({completed}/{total} files finished)',
+      stoppingTranslation: 'Stopping after the current request...',
+      stoppedFile: 'Stopped {filename}',
+      connectionOk: 'Connection OK ({latency} ms)',
+      responseMissingLines: 'Response is missing line ids [{ids}]',
+      serverOk: 'Server OK ({count} models, {latency} ms)',
+      serverLoadingModel: 'Server is still loading the model',
+      projectNameRequired: 'Project name is required',
+      projectTooNew: 'Project was saved by a newer version (format {version})',
+      glossaryEmpty: 'Glossary file has no terms',
+      glossaryExported: 'Exported {count} terms',
+      serverListenFailed: 'Could not listen on {address}: {reason}',
+      mediaServerSaved: 'Media server settings saved',
+      mediaServerOk: 'Media server connection OK',
+      mediaServerMissing: 'No media server selected',
+      automationSaved: 'Automation settings saved',
+      automationDisabled: 'Automatic translation is disabled',
+      presetNotFound: 'Preset not found: {name}',
+      folderNotFound: 'Folder not found: {path}',
+      noWatchFolders: 'No folders to watch',
+      alreadyInLanguage: 'Subtitles are already in {language}',
+      alreadyTranslatedTrack: 'Already has a translated {language} track (#{index})',
+      alreadyTranslatedFile: 'Translated subtitle already exists: {path}',
+      namesSaved: 'Saved {count} names',
       error: 'Error: {error}',
       processingFile: 'Processing {file} ({current}/{total})',
       extractingSubtitlesFrom: 'Extracting subtitles from {file}...',
@@ -191,6 +473,10 @@ const messages = {
       translatingChunk: 'Translating chunk {current}/{total} ({lines} lines)...',
       noVideoFilesSelected: 'No video files selected',
       trackNotFound: 'Track {track} not found',
+      notEnoughDiskSpace: 'Not enough disk space in {path}: {required} needed, {available} available',
+      verificationFailed: 'Output verification failed, original left untouched: {checks}',
+      embeddedSubtitleTracks: 'Embedded {count} subtitle tracks successfully',
+      subtitlesIncompatible: 'Subtitles not compatible with .{container}: {details}',
       failedToExtractSubtitleTrack: 'Failed to extract subtitle track',
       subtitleExtractionNoOutput: 'Subtitle extraction returned no output path',
       noDialogLinesExtracted: 'No dialog lines found in extracted subtitle',
@@ -207,6 +493,7 @@ const messages = {
       apiKeySaved: 'API key saved',
       subtitleEmbedded: 'Subtitle embedded successfully',
       subtitleEmbeddedMkvmerge: 'Subtitle embedded successfully (mkvmerge)',
+      subtitleEmbeddedAsMkv: 'Subtitle embedded successfully (converted to MKV)',
       subtitleTrackRemoved: 'Subtitle track removed successfully',
       subtitleRestored: 'Subtitle restored successfully',
       backupDeleted: 'Backup deleted successfully',
@@ -245,7 +532,7 @@ const messages = {
       settings: 'Configurações',
       language: 'Idioma',
       terminalLine: 'connect_media_packet',
-      heroTitle: 'Solte arquivos de vídeo na Wired.',
+      heroTitle: 'Solte vídeos, legendas ou pastas de temporada na Wired.',
       heroDescription: 'Extraia, traduza, faça backup e incorpore faixas de legenda sem sair do nó.',
       selectFiles: 'SELECIONAR ARQUIVOS',
       scanFolder: 'VARRER PASTA',
@@ -274,6 +561,9 @@ const messages = {
       autoDetectFirstAvailable: 'Detectar automaticamente a primeira disponível',
       embedTranslatedSubtitles: 'Incorporar legendas traduzidas',
       routeThroughMkvmerge: 'Roteirizar pelo mkvmerge',
+      skipTargetLanguage: 'Pular arquivos já no idioma de destino',
+      skipTranslated: 'Pular arquivos já traduzidos',
+      reuseRepeats: 'Reaproveitar traduções de linhas repetidas',
       advancedSignalControls: 'Controles avançados de sinal',
       customPrompt: 'Prompt personalizado',
       customPromptPlaceholder: 'Adicione instruções temporárias ao protocolo...',
@@ -281,7 +571,35 @@ const messages = {
       initiateTranslation: 'INICIAR TRADUÇÃO',
       disabledHint: 'Anexe mídia com faixas de legenda e verifique as configurações de provedor/FFmpeg.',
       sync: 'sync {progress}%',
-      awaitingPacketResponse: 'aguardando resposta do pacote...'
+      awaitingPacketResponse: 'aguardando resposta do pacote...',
+      jobLog: 'log do trabalho',
+      logLevels: {
+        error: 'erro',
+        warn: 'aviso',
+        info: 'info',
+        debug: 'depuração',
+        trace: 'rastro'
+      },
+      pauseTranslation: 'Pausar',
+      resumeTranslation: 'Retomar',
+      stopTranslation: 'Parar',
+      paused: 'pausado'
+    },
+    logPane: {
+      count: '{shown} / {total}',
+      search: 'Buscar no log...',
+      levels: {
+        all: 'tudo',
+        warn: 'avisos',
+        error: 'erros'
+      },
+      copy: 'Copiar linhas visíveis',
+      copied: '{count} linhas copiadas',
+      copyFailed: 'Não foi possível copiar: {error}',
+      export: 'Exportar linhas visíveis',
+      exported: 'Log salvo em {path}',
+      fileFilter: 'Arquivo de log',
+      empty: 'Nenhuma entrada corresponde ao filtro.'
     },
     setup: {
       title: 'Escolha o idioma da interface',
@@ -293,9 +611,38 @@ const messages = {
       title: 'configurações wired',
       interfaceTab: 'Interface',
       apiTab: 'Configuração da API',
+      mediaServerTab: 'Servidor de Mídia',
+      remoteTab: 'Controle Remoto',
+      automationTab: 'Automação',
       translationTab: 'Tradução',
       outputTab: 'Saída',
       interfaceLanguage: 'Idioma da interface',
+      accentColor: 'Cor de destaque',
+      uiDensity: 'Espaçamento',
+      logFont: 'Fonte do log',
+      uiScale: 'Escala da interface ({percent}%)',
+      notifications: 'Notificações',
+      desktopNotifications: 'Notificações na área de trabalho quando um trabalho termina, um arquivo falha ou um limite de custo é ultrapassado',
+      trayProgress: 'Ícone na bandeja com o progresso do trabalho',
+      accents: {
+        wired: 'Verde wired',
+        amber: 'Âmbar',
+        cyan: 'Ciano',
+        violet: 'Violeta',
+        rose: 'Rosa'
+      },
+      densities: {
+        compact: 'Compacto',
+        comfortable: 'Confortável',
+        spacious: 'Espaçoso'
+      },
+      logFonts: {
+        system: 'Monoespaçada do sistema',
+        jetbrains: 'JetBrains Mono',
+        fira: 'Fira Code',
+        dejavu: 'DejaVu Sans Mono',
+        ubuntu: 'Ubuntu Mono'
+      },
       provider: 'Provedor',
       apiEndpoint: 'Endpoint da API',
       apiKey: 'Chave da API',
@@ -333,7 +680,11 @@ const messages = {
       settingsReset: 'Configurações redefinidas para o padrão',
       bearerToken: 'Token Bearer do plano de tokens MiniMax',
       optional: '(opcional)',
-      apiKeyPlaceholder: 'Chave da API'
+      apiKeyPlaceholder: 'Chave da API',
+      azureDeployment: 'Implantação',
+      azureDeploymentPlaceholder: 'Usa o nome do modelo por padrão',
+      azureApiVersion: 'Versão da API',
+      azureApiKey: 'Chave do recurso Azure'
     },
     styles: {
       natural: 'Natural e fluente',
@@ -372,11 +723,205 @@ const messages = {
       turkish: 'Turco'
     },
     track: {
-      title: 'Faixa {index}'
+      title: 'Faixa {index}',
+      detected: 'parece {language}',
+      detectedHint: 'Idioma detectado pelo texto dos diálogos'
+    },
+    trackPicker: {
+      select: 'Traduzir esta faixa',
+      preview: 'Pré-visualizar falas',
+      totalLines: '{count} falas no total',
+      remember: 'Lembrar escolha para arquivos semelhantes'
+    },
+    queue: {
+      pending: 'pendente',
+      skipped: 'ignorado',
+      preparing: 'preparando',
+      translating: 'traduzindo {progress}%',
+      saving: 'salvando',
+      embedding: 'incorporando',
+      done: 'concluído',
+      failed: 'falhou',
+      cancelled: 'interrompido',
+      skip: 'Ignorar este arquivo',
+      unskip: 'Incluir este arquivo novamente',
+      retry: 'Tentar novamente',
+      openOutputFolder: 'Abrir pasta de saída',
+      viewLog: 'Ver log',
+      review: 'Revisar tradução',
+      deduplicated: '{count} linhas repetidas reaproveitaram uma tradução anterior',
+      partialSaved: 'Os lotes concluídos foram salvos em {path}'
+    },
+    connectionTest: {
+      label: '{provider} / {model}',
+      button: 'Testar conexão',
+      ok: '{model} respondeu em {latency} ms',
+      kind: {
+        badKey: 'Chave de API rejeitada',
+        unreachable: 'Endpoint inacessível',
+        modelMissing: 'Modelo indisponível',
+        rateLimited: 'Limite de requisições atingido',
+        badResponse: 'Resposta inesperada',
+        other: 'Falha na conexão'
+      }
+    },
+    glossary: {
+      title: 'Glossário',
+      open: 'Glossário ({count} termos)',
+      search: 'Filtrar termos...',
+      add: 'Adicionar termo',
+      suggest: 'Sugerir a partir das legendas',
+      import: 'Importar',
+      export: 'Exportar',
+      term: 'Termo',
+      translation: 'Tradução',
+      occurrences: 'Visto',
+      pinned: 'Fixar',
+      pinnedHint: 'Termos fixados mantêm a tradução quando as sugestões são atualizadas',
+      remove: 'Remover termo',
+      termPlaceholder: 'Termo original',
+      translationPlaceholder: 'Manter como está',
+      empty: 'Nenhum termo ainda. Adicione um ou deixe as legendas sugerirem.',
+      noFolder: 'Adicione vídeos para salvar o glossário ao lado deles',
+      cancel: 'Cancelar',
+      save: 'Salvar {count} termos',
+      suggested: '{count} novos termos sugeridos',
+      imported: '{count} termos importados',
+      exported: 'Glossário exportado para {path}',
+      fileFilter: 'Glossário'
+    },
+    projects: {
+      eyebrow: 'projetos recentes',
+      title: 'Continue de onde parou',
+      progress: '{name} — {done}/{total} episódios traduzidos',
+      resume: 'Retomar',
+      open: 'Abrir',
+      active: 'Projeto: {name}',
+      save: 'Salvar projeto',
+      saveAs: 'Salvar como projeto',
+      fileFilter: 'Projeto AnimeSubs',
+      errorTitle: 'Erro no projeto'
+    },
+    estimate: {
+      title: 'Estimativa de custo',
+      lines: 'Falas',
+      batches: 'Requisições',
+      tokens: 'Tokens',
+      tokenSplit: '{input} entrada / {output} saída',
+      cost: 'Preço',
+      noPrice: 'sem preço definido para {model}',
+      unreadable: '{count} arquivo(s) não puderam ser lidos e não foram contados.',
+      batchTokenBudget: 'Tamanho do lote (tokens de entrada)',
+      batchTokenBudgetPlaceholder: 'Ajustar ao contexto do modelo',
+      confirmThreshold: 'Confirmar tarefas acima de',
+      confirmThresholdPlaceholder: 'Nunca perguntar',
+      confirmTitle: 'Tradução cara',
+      confirmMessage: 'Esta tarefa está estimada em {cost} ({tokens} tokens), acima do seu limite de {threshold}. Iniciar mesmo assim?',
+      cancelled: 'Tradução não iniciada.'
+    },
+    drop: {
+      externalSubtitle: 'arquivo',
+      detachSubtitle: 'Usar uma faixa incorporada',
+      unmatchedTitle: 'Legendas sem vídeo',
+      unmatched: 'Nenhum vídeo na fila tem o nome de {files}. Solte o vídeo também ou renomeie a legenda como ele.'
+    },
+    review: {
+      title: 'Revisão · {file}',
+      search: 'Buscar no original ou na tradução',
+      flaggedOnly: 'Somente sinalizadas',
+      nextFlagged: 'Próxima sinalizada ({count})',
+      time: 'Tempo',
+      source: 'Original',
+      translation: 'Tradução',
+      noRows: 'Nenhuma fala encontrada.',
+      editedCount: '{count} falas editadas',
+      close: 'Fechar',
+      saveCorrected: 'Salvar correções',
+      saved: 'Correções salvas em {path}',
+      preview: 'Ver esta fala no vídeo',
+      previewAt: 'Fala {id} em {time}',
+      previewAlt: 'Quadro do vídeo com a fala {id}',
+      previewRefresh: 'Atualizar',
+      previewClose: 'Ocultar prévia',
+      flags: {
+        untranslated: 'não traduzida',
+        empty: 'vazia',
+        honorifics: 'honoríficos',
+        readingSpeed: 'velocidade de leitura',
+        lineLength: 'comprimento',
+        shortDuration: 'duração curta',
+        overlap: 'sobreposição',
+        invalidTiming: 'tempo inválido'
+      }
     },
     dialogs: {
       videoFiles: 'Arquivos de vídeo'
     },
+    mediaServer: {
+      hint: 'Depois que um arquivo é traduzido, pede ao servidor para reexaminá-lo e a nova legenda aparece na hora.',
+      kind: 'Servidor',
+      kinds: {
+        none: 'Nenhum',
+        jellyfin: 'Jellyfin',
+        plex: 'Plex'
+      },
+      url: 'URL',
+      apiKey: 'Chave de API',
+      plexToken: 'Token do Plex',
+      test: 'Testar',
+      save: 'Salvar'
+    },
+    automation: {
+      hint: 'Traduz episódios e filmes assim que o Sonarr ou Radarr os importa, usando uma predefinição salva. Os trabalhos rodam um por vez, após o que já estiver em andamento.',
+      enabled: 'Traduzir arquivos importados automaticamente',
+      preset: 'Predefinição',
+      presetPlaceholder: 'Escolha uma predefinição salva',
+      embed: 'Embutir a legenda no vídeo',
+      pathMappings: 'Mapeamento de caminhos',
+      pathMappingsHint: 'Quando o Sonarr ou Radarr vê a biblioteca em outro caminho (ex.: no Docker), mapeie a pasta dele para a local.',
+      addMapping: 'Adicionar mapeamento',
+      removeMapping: 'Remover mapeamento',
+      save: 'Salvar',
+      webhook: 'No Sonarr/Radarr, adicione uma conexão Webhook (On Import) para {url}, usando o token do controle remoto.',
+      webhookNeedsServer: 'Inicie o servidor de controle remoto para receber webhooks do Sonarr/Radarr.',
+      queue: 'Fila: {running} traduzindo, {queued} aguardando'
+    },
+    folderWatch: {
+      hint: 'Vídeos novos copiados para estas pastas entram na fila com a predefinição da pasta assim que a cópia termina. O formato de saída e a incorporação vêm das configurações acima.',
+      add: 'Adicionar pasta',
+      remove: 'Remover pasta',
+      recursive: 'Subpastas',
+      running: 'Monitorando',
+      stopped: 'Sem monitoramento',
+      save: 'Salvar',
+      start: 'Começar a monitorar',
+      stop: 'Parar de monitorar'
+    },
+    remoteServer: {
+      enabled: 'Executar o servidor de controle remoto',
+      bind: 'Escutar em',
+      bindHint: 'Use 0.0.0.0 em vez de 127.0.0.1 para aceitar outras máquinas. Quem tiver o token pode executar trabalhos e ler caminhos nesta máquina.',
+      token: 'Token',
+      copyToken: 'Copiar token',
+      tokenCopied: 'Token copiado',
+      regenerate: 'Novo token',
+      regenerateConfirm: 'Criar um novo token? Clientes usando o atual serão recusados.',
+      listening: 'Escutando em {address}',
+      stopped: 'Parado',
+      apply: 'Aplicar',
+      rpcEndpoint: 'chamadas JSON-RPC 2.0, com os nomes de comando que o app usa',
+      eventsEndpoint: 'WebSocket com eventos de progresso e de log',
+      healthEndpoint: 'verificação de versão, sem token'
+    },
+    notifications: {
+      jobFinished: 'AnimeSubs: tradução concluída',
+      fileFailed: 'AnimeSubs: falha em {file}',
+      costTitle: 'AnimeSubs: aviso de custo',
+      costConfirm: 'A estimativa de {cost} passa do limite de {threshold}. Confirme no app para começar.',
+      costSpent: 'O trabalho custou cerca de {cost}, acima do limite de {threshold}.',
+      trayProgress: 'AnimeSubs: traduzindo {progress}%',
+      trayPaused: 'AnimeSubs: pausado em {progress}%'
+    },
     status: {
       invalidApiKey: 'Chave da API inválida. Verifique suas credenciais em Configurações.',
       accessDenied: 'Acesso negado. Verifique as permissões da chave da API.',
@@ -392,6 +937,30 @@ const messages = {
       translationComplete: 'Tradução concluída!',
       translationFailed: 'Tradução falhou: {failure}',
       translationFinishedWithErrors: 'Tradução concluída com erros ({completed}/{total}): {failure}',
+      translationStopped: 'Tradução interrompida ({completed}/{total} arquivos concluídos)',
+      stoppingTranslation: 'Parando após a requisição atual...',
+      stoppedFile: '{filename} interrompido',
+      connectionOk: 'Conexão OK ({latency} ms)',
+      responseMissingLines: 'A resposta não trouxe as linhas [{ids}]',
+      serverOk: 'Servidor OK ({count} modelos, {latency} ms)',
+      serverLoadingModel: 'O servidor ainda está carregando o modelo',
+      projectNameRequired: 'O nome do projeto é obrigatório',
+      projectTooNew: 'O projeto foi salvo por uma versão mais nova (formato {version})',
+      glossaryEmpty: 'O arquivo de glossário não tem termos',
+      glossaryExported: '{count} termos exportados',
+      serverListenFailed: 'Não foi possível escutar em {address}: {reason}',
+      mediaServerSaved: 'Configurações do servidor de mídia salvas',
+      mediaServerOk: 'Conexão com o servidor de mídia OK',
+      mediaServerMissing: 'Nenhum servidor de mídia selecionado',
+      automationSaved: 'Configurações de automação salvas',
+      automationDisabled: 'A tradução automática está desativada',
+      presetNotFound: 'Predefinição não encontrada: {name}',
+      folderNotFound: 'Pasta não encontrada: {path}',
+      noWatchFolders: 'Nenhuma pasta para monitorar',
+      alreadyInLanguage: 'As legendas já estão em {language}',
+      alreadyTranslatedTrack: 'Já tem uma faixa traduzida em {language} (#{index})',
+      alreadyTranslatedFile: 'Já existe uma legenda traduzida: {path}',
+      namesSaved: '{count} nomes salvos',
       error: 'Erro: {error}',
       processingFile: 'Processando {file} ({current}/{total})',
       extractingSubtitlesFrom: 'Extraindo legendas de {file}...',
@@ -405,6 +974,10 @@ const messages = {
       translatingChunk: 'Traduzindo bloco {current}/{total} ({lines} linhas)...',
       noVideoFilesSelected: 'Nenhum arquivo de vídeo selecionado',
       trackNotFound: 'Faixa {track} não encontrada',
+      notEnoughDiskSpace: 'Espaço em disco insuficiente em {path}: {required} necessários, {available} disponíveis',
+      verificationFailed: 'Falha na verificação do arquivo gerado, original mantido: {checks}',
+      embeddedSubtitleTracks: '{count} faixas de legenda incorporadas com sucesso',
+      subtitlesIncompatible: 'Legendas incompatíveis com .{container}: {details}',
       failedToExtractSubtitleTrack: 'Falha ao extrair faixa de legenda',
       subtitleExtractionNoOutput: 'A extração de legenda não retornou caminho de saída',
       noDialogLinesExtracted: 'Nenhuma linha de diálogo encontrada na legenda extraída',
@@ -421,6 +994,7 @@ const messages = {
       apiKeySaved: 'Chave da API salva',
       subtitleEmbedded: 'Legenda incorporada com sucesso',
       subtitleEmbeddedMkvmerge: 'Legenda incorporada com sucesso (mkvmerge)',
+      subtitleEmbeddedAsMkv: 'Legenda incorporada com sucesso (convertido para MKV)',
       subtitleTrackRemoved: 'Faixa de legenda removida com sucesso',
       subtitleRestored: 'Legenda restaurada com sucesso',
       backupDeleted: 'Backup excluído com sucesso',
@@ -456,6 +1030,8 @@ export const isInterfaceLocale = (value: unknown): value is InterfaceLocale => {
 export const setInterfaceLocale = (locale: string) => {
   if (isInterfaceLocale(locale)) {
     i18n.global.locale.value = locale
+    // Screen readers and spell checkers follow the document language.
+    document.documentElement.lang = locale
   }
 }
 
@@ -490,6 +1066,15 @@ type TranslateFn = (key: string, named?: Record<string, unknown>) => string
 export const localizeBackendMessage = (message: string, t: TranslateFn): string => {
   const exact: Record<string, string> = {
     'Translation complete!': 'status.translationComplete',
+    'Server is still loading the model': 'status.serverLoadingModel',
+    'Project name is required': 'status.projectNameRequired',
+    'Glossary file has no terms': 'status.glossaryEmpty',
+    'Media server settings saved': 'status.mediaServerSaved',
+    'Media server connection OK': 'status.mediaServerOk',
+    'No media server selected': 'status.mediaServerMissing',
+    'Automation settings saved': 'status.automationSaved',
+    'Automatic translation is disabled': 'status.automationDisabled',
+    'No folders to watch': 'status.noWatchFolders',
     'Failed to extract subtitle track': 'status.failedToExtractSubtitleTrack',
     'Subtitle extraction returned no output path': 'status.subtitleExtractionNoOutput',
     'No dialog lines found in extracted subtitle': 'status.noDialogLinesExtracted',
@@ -504,6 +1089,7 @@ export const localizeBackendMessage = (message: string, t: TranslateFn): string
     'API key saved': 'status.apiKeySaved',
     'Subtitle embedded successfully': 'status.subtitleEmbedded',
     'Subtitle embedded successfully (mkvmerge)': 'status.subtitleEmbeddedMkvmerge',
+    'Subtitle embedded successfully (converted to MKV)': 'status.subtitleEmbeddedAsMkv',
     'Subtitle track removed successfully': 'status.subtitleTrackRemoved',
     'Subtitle restored successfully': 'status.subtitleRestored',
     'Backup deleted successfully': 'status.backupDeleted',
@@ -555,6 +1141,21 @@ export const localizeBackendMessage = (message: string, t: TranslateFn): string
     [/^Track (\d+) not found$/, 'status.trackNotFound', m => ({
       track: m[1]
     })],
+    [/^Not enough disk space in (.+): (.+) needed, (.+) available$/, 'status.notEnoughDiskSpace', m => ({
+      path: m[1],
+      required: m[2],
+      available: m[3]
+    })],
+    [/^Embedded (\d+) subtitle tracks successfully$/, 'status.embeddedSubtitleTracks', m => ({
+      count: m[1]
+    })],
+    [/^Subtitles not compatible with \.(\S+): (.+)$/, 'status.subtitlesIncompatible', m => ({
+      container: m[1],
+      details: m[2]
+    })],
+    [/^Output verification failed, original left untouched: (.+)$/, 'status.verificationFailed', m => ({
+      checks: m[1]
+    })],
     [/^Unsupported format: (.+)$/, 'status.unsupportedFormat', m => ({
       format: m[1]
     })],
@@ -571,6 +1172,52 @@ export const localizeBackendMessage = (message: string, t: TranslateFn): string
       completed: m[1],
       total: m[2],
       failure: localizeBackendMessage(m[3], t)
+    })],
+    [/^Translation stopped \((\d+)\/(\d+) files finished\)$/, 'status.translationStopped', m => ({
+      completed: m[1],
+      total: m[2]
+    })],
+    [/^Stopped (.+)$/, 'status.stoppedFile', m => ({
+      filename: m[1]
+    })],
+    [/^Connection OK \((\d+) ms\)$/, 'status.connectionOk', m => ({
+      latency: m[1]
+    })],
+    [/^Response is missing line ids \[(.*)\]$/, 'status.responseMissingLines', m => ({
+      ids: m[1]
+    })],
+    [/^Server OK \((\d+) models, (\d+) ms\)$/, 'status.serverOk', m => ({
+      count: m[1],
+      latency: m[2]
+    })],
+    [/^Project was saved by a newer version \(format (\d+)\)$/, 'status.projectTooNew', m => ({
+      version: m[1]
+    })],
+    [/^Exported (\d+) terms$/, 'status.glossaryExported', m => ({
+      count: m[1]
+    })],
+    [/^Failed to listen on (.+?): (.+)$/, 'status.serverListenFailed', m => ({
+      address: m[1],
+      reason: m[2]
+    })],
+    [/^Preset not found: (.+)$/, 'status.presetNotFound', m => ({
+      name: m[1]
+    })],
+    [/^Folder not found: (.+)$/, 'status.folderNotFound', m => ({
+      path: m[1]
+    })],
+    [/^Subtitles are already in (.+)$/, 'status.alreadyInLanguage', m => ({
+      language: m[1]
+    })],
+    [/^Already has a translated (.+) track \(#(\d+)\)$/, 'status.alreadyTranslatedTrack', m => ({
+      language: m[1],
+      index: m[2]
+    })],
+    [/^Translated subtitle already exists: (.+)$/, 'status.alreadyTranslatedFile', m => ({
+      path: m[1]
+    })],
+    [/^Saved (\d+) names$/, 'status.namesSaved', m => ({
+      count: m[1]
     })]
   ]
 
@@ -6,6 +6,27 @@ export interface SubtitleTrack {
   title: string | null
   default: boolean
   forced: boolean
+  /** Language read from the track's dialog by `detectSubtitleLanguages`. */
+  detected_language?: string | null
+}
+
+export interface AudioTrack {
+  index: number
+  stream_index: number
+  codec: string
+  language: string | null
+  title: string | null
+  channels: number | null
+  default: boolean
+}
+
+export interface VideoStream {
+  index: number
+  stream_index: number
+  codec: string
+  width: number | null
+  height: number | null
+  default: boolean
 }
 
 export interface VideoInfo {
@@ -13,6 +34,16 @@ export interface VideoInfo {
   filename: string
   duration: number | null
   subtitle_tracks: SubtitleTrack[]
+  audio_tracks: AudioTrack[]
+  video_streams: VideoStream[]
+  stream_count?: number
+}
+
+export interface ChapterInfo {
+  index: number
+  start: number
+  end: number
+  title: string
 }
 
 export interface BackupInfo {
@@ -21,12 +52,80 @@ export interface BackupInfo {
   track_index: number
   format: string
   created_at: string
+  /** The backed-up track; a restore refuses to replace any other one. */
+  track?: BackupTrack | null
 }
 
+export interface BackupTrack {
+  stream_index: number
+  codec: string
+  language: string | null
+  title: string | null
+}
+
+export type OutputPolicy =
+  | { mode: 'overwrite' }
+  | { mode: 'alongside'; suffix: string; replaceExisting?: boolean }
+  | { mode: 'directory'; path: string; replaceExisting?: boolean }
+
+export type VideoEncoder = 'x264' | 'x265' | 'nvenc' | 'qsv' | 'vaapi'
+
+export interface BurnOptions {
+  encoder?: VideoEncoder
+  crf?: number | null
+  bitrate?: string | null
+  preset?: string | null
+}
+
+export interface CapabilityReport {
+  ffmpegPath: string
+  ffmpegVersion: string | null
+  ffprobeAvailable: boolean
+  burnEncoders: VideoEncoder[]
+  hardwareEncoders: string[]
+  hwaccels: string[]
+  libass: boolean
+  mkvmergePath: string | null
+  mkvmergeVersion: string | null
+  mkvpropeditAvailable: boolean
+}
+
+export interface MkvToolNixStatus {
+  available: boolean
+  mkvmergePath: string | null
+  mkvmergeVersion: string | null
+  mkvpropeditPath: string | null
+  configuredPath: string | null
+  message: string
+}
+
+export type TimingAdjustment =
+  | { mode: 'shift'; offsetMs: number }
+  | {
+      mode: 'scale'
+      sourceAMs: number
+      targetAMs: number
+      sourceBMs: number
+      targetBMs: number
+    }
+  | { mode: 'frameRate'; sourceFps: number; targetFps: number }
+
 export interface OperationResult {
   success: boolean
   message: string
   data: string | null
+  verification?: RemuxVerification
+}
+
+export interface VerificationCheck {
+  name: 'probe' | 'video' | 'audio' | 'subtitles' | 'duration' | 'decode'
+  passed: boolean
+  detail: string
+}
+
+export interface RemuxVerification {
+  passed: boolean
+  checks: VerificationCheck[]
 }
 
 export interface ExtractResult {
@@ -35,6 +134,24 @@ export interface ExtractResult {
   error: string | null
 }
 
+export interface ExtractedTrack {
+  videoPath: string
+  trackIndex: number
+  codec: string
+  language: string | null
+  title: string | null
+  outputPath: string | null
+  error: string | null
+}
+
+export interface ExtractionManifest {
+  folder: string
+  videoCount: number
+  extracted: number
+  failed: number
+  tracks: ExtractedTrack[]
+}
+
 export interface DialogLine {
   index: number
   start: string
@@ -43,6 +160,45 @@ export interface DialogLine {
   original_with_formatting: string
   style: string | null
   name: string | null
+  reference?: string | null
+  formatting?: LineFormatting | null
+  event?: AssEventKind
+  classification?: LineClass
+  skip?: boolean
+}
+
+export type LineClass =
+  | 'dialogue'
+  | 'sign'
+  | 'karaoke'
+  | 'music'
+  | 'short'
+  | 'comment'
+  | 'other'
+
+export type AssEventKind = 'dialogue' | 'comment' | 'picture' | 'sound' | 'movie' | 'command'
+
+export interface LineClassCounts {
+  dialogue: number
+  sign: number
+  karaoke: number
+  music: number
+  short: number
+  comment: number
+  other: number
+  skipped: number
+}
+
+export interface LineSkipOverride {
+  index: number
+  skip: boolean
+}
+
+export interface LineFormatting {
+  italic: boolean
+  bold: boolean
+  underline: boolean
+  color: string | null
 }
 
 export interface SubtitleData {
@@ -51,6 +207,301 @@ export interface SubtitleData {
   lines: DialogLine[]
   source_path: string | null
   ass_header: string | null
+  failed_line_ids?: number[]
+  batch_providers?: BatchProvider[]
+  class_counts?: LineClassCounts
+  /** Repeats of an earlier line that were given its translation instead of being sent. */
+  deduplicated_lines?: number
+}
+
+export interface BatchProvider {
+  batch: number
+  provider: string
+  model: string
+  lineCount: number
+}
+
+export interface SignsSplitResult {
+  dialoguePath: string
+  signsPath: string
+  dialogueEvents: number
+  signEvents: number
+}
+
+export interface SubtitleTrackPreview {
+  trackIndex: number
+  format: string
+  totalLines: number
+  lines: DialogLine[]
+}
+
+export interface TrackScore {
+  trackIndex: number
+  score: number
+  dialogLines: number | null
+  reasons: string[]
+}
+
+export interface ValidationOptions {
+  maxCps?: number | null
+  maxLineLength?: number | null
+  minDurationMs?: number | null
+}
+
+export type ValidationIssueKind =
+  | 'readingSpeed'
+  | 'lineLength'
+  | 'shortDuration'
+  | 'overlap'
+  | 'invalidTiming'
+
+export interface ValidationIssue {
+  lineIndex: number
+  kind: ValidationIssueKind
+  message: string
+}
+
+export interface ValidationReport {
+  lineCount: number
+  passed: boolean
+  issues: ValidationIssue[]
+}
+
+export interface WrapOptions {
+  maxLineLength?: number | null
+  maxLines?: number | null
+}
+
+export type LineEnding = 'lf' | 'crlf'
+
+export interface SubtitleEncodingOptions {
+  encoding?: string | null
+  bom?: boolean | null
+  lineEnding?: LineEnding
+}
+
+export interface AssStyle {
+  name: string
+  fontname: string
+  fontsize: number
+  primaryColour: string
+  secondaryColour: string
+  outlineColour: string
+  backColour: string
+  bold: boolean
+  italic: boolean
+  underline: boolean
+  strikeOut: boolean
+  scaleX: number
+  scaleY: number
+  spacing: number
+  angle: number
+  borderStyle: number
+  outline: number
+  shadow: number
+  alignment: number
+  marginL: number
+  marginR: number
+  marginV: number
+  encoding: number
+}
+
+export type AssStylePatch = Partial<
+  Pick<
+    AssStyle,
+    | 'fontname'
+    | 'fontsize'
+    | 'primaryColour'
+    | 'secondaryColour'
+    | 'outlineColour'
+    | 'backColour'
+    | 'bold'
+    | 'italic'
+    | 'outline'
+    | 'shadow'
+    | 'alignment'
+    | 'marginL'
+    | 'marginR'
+    | 'marginV'
+  >
+>
+
+export interface FontUsage {
+  name: string
+  styles: string[]
+  inlineUses: number
+  attached: boolean
+  installed: boolean
+}
+
+export interface FontReport {
+  fonts: FontUsage[]
+  missing: string[]
+  remappedPath: string | null
+}
+
+export type SubtitleFormat = 'ass' | 'srt' | 'vtt'
+
+export type AssDowngrade = 'tags' | 'strip'
+
+export interface FormatConversionOptions {
+  assStyling?: AssDowngrade
+  skipSigns?: boolean
+  outputEncoding?: SubtitleEncodingOptions | null
+}
+
+export interface SdhOptions {
+  brackets?: boolean
+  parentheticals?: boolean
+  speakerLabels?: boolean
+}
+
+export interface CleanupOptions {
+  mergeIdentical?: boolean
+  extendShort?: boolean
+  enforceGaps?: boolean
+  mergeToleranceMs?: number | null
+  minDurationMs?: number | null
+  minGapMs?: number | null
+  stripSdh?: SdhOptions | null
+}
+
+export interface CleanupResult {
+  subtitleData: SubtitleData
+  merged: number
+  extended: number
+  gapsFixed: number
+  sdhCleaned: number
+}
+
+export interface HonorificIssue {
+  lineIndex: number
+  missing: string[]
+  sourceText: string
+  translatedText: string
+}
+
+export type ConnectionErrorKind =
+  | 'badKey'
+  | 'unreachable'
+  | 'modelMissing'
+  | 'rateLimited'
+  | 'badResponse'
+  | 'other'
+
+export interface LlmConnectionReport {
+  success: boolean
+  latencyMs: number
+  jsonMode: boolean
+  authError: boolean
+  message: string
+  translations: { id: number; text: string }[]
+  /** Model the probe was sent to. */
+  model?: string
+  errorKind?: ConnectionErrorKind | null
+}
+
+export type MediaServerKind = 'none' | 'jellyfin' | 'plex'
+
+export interface MediaServerSettings {
+  kind: MediaServerKind
+  /** e.g. `http://nas:8096` (Jellyfin) or `http://nas:32400` (Plex). */
+  url: string
+  /** Jellyfin API key or Plex token; kept in the OS keyring. */
+  apiKey?: string | null
+}
+
+export interface ServerSettings {
+  enabled: boolean
+  /** `host:port`; empty for `127.0.0.1:7318`. */
+  bind: string
+}
+
+export interface ServerStatus {
+  settings: ServerSettings
+  /** Address the server listens on; null while it is stopped. */
+  address: string | null
+  token: string
+}
+
+export interface PathMapping {
+  /** Path prefix as Sonarr/Radarr report it, e.g. `/tv`. */
+  from: string
+  /** The same folder on this machine, e.g. `/mnt/media/tv`. */
+  to: string
+}
+
+export interface AutomationSettings {
+  enabled: boolean
+  /** Name of the saved preset used for automatic jobs. */
+  preset: string
+  /** Empty keeps the source format. */
+  outputFormat: string
+  embedSubtitles: boolean
+  pathMappings: PathMapping[]
+}
+
+export interface AutomationQueue {
+  queued: string[]
+  /** Files of the automatic job running now. */
+  running: string[]
+}
+
+export interface WatchFolder {
+  path: string
+  /** Saved preset new videos in this folder are translated with. */
+  preset: string
+  /** Also watches subfolders, e.g. season folders. */
+  recursive: boolean
+}
+
+export interface WatchSettings {
+  /** The watch is running (and restarts with the app). */
+  active: boolean
+  folders: WatchFolder[]
+}
+
+export type ProxyMode = 'system' | 'off' | 'manual'
+
+export interface ProxySettings {
+  mode: ProxyMode
+  url: string
+  username?: string | null
+  password?: string | null
+  noProxy?: string | null
+}
+
+export interface JobDebugLog {
+  path: string
+  content: string
+}
+
+export interface ProviderHealth {
+  reachable: boolean
+  latencyMs: number
+  models: string[]
+  modelAvailable?: boolean | null
+  message: string
+}
+
+export interface ProviderModel {
+  id: string
+  name: string
+  contextLength?: number | null
+}
+
+export interface ReviewRow {
+  id: number
+  start: string
+  end: string
+  source: string
+  translation: string
+  flags: string[]
+}
+
+export interface LineEdit {
+  id: number
+  text: string
 }
 
 export interface SelectedFile {
@@ -61,6 +512,18 @@ export interface SelectedFile {
   backups: BackupInfo[]
   loading: boolean
   error: string | null
+  /** Track picked for translation; the job's default track when unset. */
+  selectedTrack?: number | null
+  /** Subtitle file translated instead of an embedded track. */
+  subtitleFile?: string | null
+}
+
+/** Describes a picked track so the same kind can be picked in similar files. */
+export interface TrackRule {
+  language: string | null
+  title: string | null
+  codec: string
+  forced: boolean
 }
 
 export interface LlmConfig {
@@ -69,6 +532,77 @@ export interface LlmConfig {
   endpoint: string
   model: string
   system_prompt: string
+  name_table?: CharacterName[]
+  prompt_template?: string | null
+  series_context?: string | null
+  batching?: BatchingStrategy
+  context_window?: number | null
+  batch_token_budget?: number | null
+  missing_line_retries?: number | null
+  max_length_ratio?: number | null
+  /** Reuse the first translation of repeated lines; on unless false. */
+  reuse_repeats?: boolean | null
+  json_mode?: boolean | null
+  azure_deployment?: string | null
+  azure_api_version?: string | null
+  fallbacks?: LlmConfig[]
+  connect_timeout_secs?: number | null
+  request_timeout_secs?: number | null
+  stall_timeout_secs?: number | null
+}
+
+export type BatchingStrategy = 'sequential' | 'scene'
+
+export interface ReleaseInfo {
+  title: string
+  releaseGroup?: string | null
+  episode?: number | null
+  season?: number | null
+  resolution?: string | null
+  source?: string | null
+  version?: number | null
+}
+
+export interface ReleaseFile {
+  path: string
+  release: ReleaseInfo
+}
+
+export interface ReleaseSeries {
+  title: string
+  files: ReleaseFile[]
+}
+
+export interface AnimeMetadata {
+  anilistId: number
+  malId?: number | null
+  titleRomaji: string
+  titleEnglish?: string | null
+  titleNative?: string | null
+  episodes?: number | null
+  seasonYear?: number | null
+  coverImage?: string | null
+  episode?: number | null
+  episodeTitle?: string | null
+  release?: ReleaseInfo | null
+}
+
+export interface SeriesContext {
+  title?: string | null
+  anilistId?: number | null
+  context: string
+}
+
+export interface PromptTemplate {
+  name: string
+  content: string
+}
+
+export interface CharacterName {
+  source: string
+  canonical: string
+  occurrences: number
+  pinned: boolean
 }
 
 export interface TranslationJobRequest {
@@ -80,17 +614,119 @@ export interface TranslationJobRequest {
   outputDirectory: string | null
   ffmpegPath: string | null
   subtitleTrack: number | null
+  outputPolicy?: OutputPolicy
+  referenceTrack?: number | null
+  wrap?: WrapOptions | null
+  stripSdh?: SdhOptions | null
+  refine?: boolean
+  honorificSecondPass?: boolean
+  /** Leaves videos whose subtitles are already in the target language untranslated. */
+  skipTargetLanguage?: boolean
+  /** Leaves videos an earlier job already translated untranslated. */
+  skipTranslated?: boolean
+  namingTemplate?: string | null
   embedSubtitles: boolean
   useMkvmerge: boolean
   autoBackup: boolean
   keepOriginalTrack: boolean
+  rateLimit?: RateLimitOptions | null
+  debugLog?: boolean
+  outputEncoding?: SubtitleEncodingOptions | null
+  dialogueStyle?: AssStylePatch | null
+  translateComments?: boolean
+  assMetadata?: AssMetadataOptions | null
+  maxParallelFiles?: number | null
+  /** `.animesubs` project the job belongs to; its report is saved next to it. */
+  projectPath?: string | null
+  /** Track to translate per video path, overriding `subtitleTrack`. */
+  fileTracks?: Record<string, number>
+  /** Subtitle file to translate per video path instead of an embedded track. */
+  fileSubtitles?: Record<string, string>
+  /** Return each video's translated lines in `outputs`, for review. */
+  keepSubtitleData?: boolean
+}
+
+export interface AssMetadataOptions {
+  title?: string | null
+  credit?: boolean
+  language?: string | null
+  scaledBorderAndShadow?: boolean | null
+}
+
+export interface RateLimitOptions {
+  requestsPerMinute?: number | null
+  tokensPerMinute?: number | null
+  maxConcurrency?: number | null
+  maxRetries?: number | null
 }
 
+export type JobFileStage =
+  | 'preparing'
+  | 'translating'
+  | 'saving'
+  | 'embedding'
+  | 'done'
+  | 'failed'
+  | 'cancelled'
+
 export interface TranslationJobProgress {
   currentFile: number
   totalFiles: number
   progress: number
   status: string
+  /** Video the update is about; unset for the job-wide summary. */
+  videoPath?: string | null
+  stage?: JobFileStage | null
+  fileProgress?: number | null
+}
+
+/** Queue row state of a video in the GUI. */
+export interface FileJobState {
+  stage: JobFileStage | 'pending' | 'skipped'
+  progress: number
+  error: string | null
+  outputPath: string | null
+  /** Status messages of this video's last run, oldest first. */
+  messages: string[]
+  /** Translated lines of the last run, for the review screen. */
+  subtitleData: SubtitleData | null
+  /** Subtitle file the last run wrote, when it was not embedded. */
+  subtitlePath: string | null
+}
+
+export interface ParserSuiteFileReport {
+  path: string
+  format: string
+  parsedLines: number
+  lostCount: number
+  addedCount: number
+  lostSamples: string[]
+  addedSamples: string[]
+  error: string | null
+  roundTripOk: boolean
+}
+
+export interface ParserSuiteReport {
+  folder: string
+  passed: number
+  failed: number
+  files: ParserSuiteFileReport[]
+}
+
+export interface OperationStarted {
+  operationId: string
+  operation: string
+  videoPath: string
+}
+
+export interface FfmpegProgress {
+  operationId: string
+  operation: string
+  videoPath: string
+  processedSeconds: number
+  duration: number | null
+  progress: number | null
+  done: boolean
 }
 
 export interface TranslationBatchProgress {
@@ -103,8 +739,13 @@ export interface TranslationBatchProgress {
 
 export interface TranslationJobOutput {
   videoPath: string
+  outputVideoPath: string | null
   subtitlePath: string | null
   embedded: boolean
+  batchProviders?: BatchProvider[]
+  /** Why the video was left untranslated. */
+  skipped?: string | null
+  subtitleData?: SubtitleData | null
 }
 
 export interface TranslationJobResult {
@@ -112,4 +753,276 @@ export interface TranslationJobResult {
   totalFiles: number
   failures: string[]
   outputs: TranslationJobOutput[]
+  report?: JobReport | null
+  /** Whether the job was stopped before every file was processed. */
+  cancelled?: boolean
+}
+
+export interface JobFileReport {
+  videoPath: string
+  status: ProjectFileStatus
+  subtitleTrack: number | null
+  trackLanguage: string | null
+  trackTitle: string | null
+  /** Language detected from the subtitle's dialog. */
+  detectedLanguage?: string | null
+  linesTotal: number
+  linesTranslated: number
+  linesSkipped: number
+  linesFailed: number
+  /** Repeated lines copied from their first occurrence, i.e. lines not sent. */
+  linesDeduplicated?: number
+  /** Estimated from the subtitle text and system prompts sent and the text received. */
+  inputTokens: number
+  outputTokens: number
+  /** USD; null when a model has no price in `modelPricing`. */
+  estimatedCost: number | null
+  durationMs: number
+  warnings: ValidationIssue[]
+  outputPath: string | null
+  error: string | null
+  /** Why a skipped video was left untranslated. */
+  skipReason?: string | null
+  /** Subtitle with the batches a stopped video finished. */
+  partialPath?: string | null
+}
+
+export interface JobReport {
+  id: string
+  startedAt: string
+  finishedAt: string
+  provider: string
+  model: string
+  sourceLang: string
+  targetLang: string
+  durationMs: number
+  inputTokens: number
+  outputTokens: number
+  estimatedCost: number | null
+  files: JobFileReport[]
+  path?: string | null
+}
+
+export type TranscriptionEngine = 'whispercpp' | 'openai' | 'deepgram'
+
+export interface TranscriptionOptions {
+  engine?: TranscriptionEngine
+  language?: string | null
+  audioTrack?: number | null
+  whisperPath?: string | null
+  whisperModel?: string | null
+  apiKey?: string | null
+  endpoint?: string | null
+  model?: string | null
+}
+
+export interface SidecarSubtitle {
+  path: string
+  format: string
+  language?: string | null
+  label?: string | null
+  forced: boolean
+}
+
+export interface ScannedVideo {
+  path: string
+  sidecars: SidecarSubtitle[]
+}
+
+export type ProjectFileStatus = 'pending' | 'done' | 'failed' | 'skipped'
+
+export interface ProjectFile {
+  path: string
+  subtitleTrack?: number | null
+  referenceTrack?: number | null
+  status: ProjectFileStatus
+  outputPath?: string | null
+  error?: string | null
+}
+
+export interface TranslationProject {
+  version?: number
+  name: string
+  folder: string
+  files: ProjectFile[]
+  sourceLang: string
+  targetLang: string
+  config: LlmConfig
+  updatedAt?: string | null
+}
+
+export interface RecentProject {
+  path: string
+  name: string
+  updatedAt?: string | null
+  folder?: string
+  totalFiles?: number
+  doneFiles?: number
+}
+
+export type HistoryOutcome = 'success' | 'failed' | 'cancelled'
+
+export interface HistoryEntry {
+  id: string
+  operation: string
+  videoPath: string
+  startedAt: string
+  durationMs: number
+  outcome: HistoryOutcome
+  message?: string | null
+  parameters: Record<string, unknown>
+}
+
+export interface DiskSpaceCheck {
+  path: string
+  requiredBytes: number
+  availableBytes: number
+  sufficient: boolean
+}
+
+export interface OrphanCleanupResult {
+  removed: string[]
+  failed: string[]
+}
+
+export interface BackupRetention {
+  keepLast?: number | null
+  maxAgeDays?: number | null
+}
+
+export interface SubtitleEmbed {
+  path: string
+  language?: string | null
+  title?: string | null
+  default?: boolean
+  forced?: boolean
+  position?: number | null
+}
+
+export interface EmbedTrackOptions {
+  forced?: boolean
+  position?: number | null
+  clearExistingDefaults?: boolean
+  onIncompatible?: IncompatibleSubtitleAction
+}
+
+export type IncompatibleSubtitleAction = 'convert' | 'mkv' | 'fail'
+
+export interface SubtitleCompatibility {
+  path: string
+  format: string
+  codec?: string | null
+  lossy?: boolean
+}
+
+export interface EmbedCompatibilityReport {
+  container: string
+  subtitles: SubtitleCompatibility[]
+  compatible: boolean
+  suggestedContainer?: string | null
+}
+
+/** Backend defaults used when a command leaves the matching argument unset. */
+export interface AppSettings {
+  version: number
+  providerEndpoints: Record<string, string>
+  ffmpegPath?: string | null
+  /** MKVToolNix folder, or the mkvmerge binary in it. */
+  mkvtoolnixPath?: string | null
+  batchTokenBudget?: number | null
+  maxParallelFiles?: number | null
+  rateLimit?: RateLimitOptions | null
+  namingTemplate?: string | null
+  stripSdh?: SdhOptions | null
+  /** Price per model name, for the cost estimate in job reports. */
+  modelPricing?: Record<string, ModelPricing>
+}
+
+/** Expected size of one video's translation, before the job runs. */
+export interface FileEstimate {
+  videoPath: string
+  lines: number
+  batches: number
+  inputTokens: number
+  outputTokens: number
+  /** Why the subtitles could not be read; the file is left out of the totals. */
+  error: string | null
+}
+
+export interface JobEstimate {
+  model: string
+  lines: number
+  batches: number
+  inputTokens: number
+  outputTokens: number
+  /** USD; null when the model has no price in `modelPricing`. */
+  estimatedCost: number | null
+  files: FileEstimate[]
+}
+
+/** USD per million tokens. */
+export interface ModelPricing {
+  inputPerMillion: number
+  outputPerMillion: number
+}
+
+/** Named provider + prompt + language setup; API keys are never stored. */
+export interface Preset {
+  name: string
+  config: LlmConfig
+  sourceLang: string
+  targetLang: string
+  style?: string | null
+  rateLimit?: RateLimitOptions | null
+  maxParallelFiles?: number | null
+  stripSdh?: SdhOptions | null
+  honorificSecondPass?: boolean
+}
+
+export type ToolDownloadStage = 'downloading' | 'verifying' | 'extracting' | 'done'
+
+export interface ToolDownloadProgress {
+  tool: 'ffmpeg' | 'mkvtoolnix'
+  fileName: string
+  stage: ToolDownloadStage
+  downloadedBytes: number
+  totalBytes?: number | null
+}
+
+export interface LogSettings {
+  /** Level or per-module directives, e.g. `warn,animesubs_lib::providers=debug`. */
+  level: string
+  logDir?: string | null
+}
+
+export interface LogEntry {
+  timestamp: string
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE'
+  target: string
+  message: string
+}
+
+export type AnimeSubsErrorCode =
+  | 'FFMPEG_NOT_FOUND'
+  | 'TRACK_NOT_FOUND'
+  | 'PROVIDER_AUTH'
+  | 'RATE_LIMITED'
+  | 'TIMEOUT'
+  | 'PROVIDER'
+  | 'PARSE_ERROR'
+  | 'IO'
+  | 'INSUFFICIENT_SPACE'
+  | 'CANCELLED'
+  | 'OTHER'
+
+/** Rejection value of every backend command. */
+export interface AnimeSubsError {
+  code: AnimeSubsErrorCode
+  message: string
+  line?: number | null
+  retryAfterSecs?: number | null
+  /** Set with `INSUFFICIENT_SPACE`. */
+  path?: string
+  neededBytes?: number
+  availableBytes?: number
 }